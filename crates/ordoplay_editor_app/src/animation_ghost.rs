@@ -0,0 +1,113 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//! Ghost overlay for comparing the active sequence against a reference.
+//!
+//! The reference sequence is sampled at the sequencer playhead and its
+//! transform tracks are resolved against the scene. The resulting ghosts
+//! are drawn translucently in the viewport next to the real entities.
+
+use crate::state::{EntityId, SceneData, Transform};
+use ordoplay_editor_sequencer::{BindingSample, KeyframeValue, TransformChannel};
+
+/// Reference pose of one scene entity at the current playhead
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnimationGhost {
+    /// Entity the ghost belongs to
+    pub entity_id: EntityId,
+    /// Entity transform with the reference tracks applied
+    pub transform: Transform,
+}
+
+/// Resolve sampled reference tracks into ghosts for entities in `scene`.
+///
+/// Bindings whose entity is not in the scene are skipped, as are samples
+/// that do not target a transform channel.
+pub fn resolve_ghosts(samples: &[BindingSample], scene: &SceneData) -> Vec<AnimationGhost> {
+    let mut ghosts: Vec<AnimationGhost> = Vec::new();
+
+    for sample in samples {
+        let entity_id = EntityId(sample.binding.entity_id.0);
+        let Some(entity) = scene.get(&entity_id) else {
+            continue;
+        };
+        let (KeyframeValue::Vec3(value), Some(channel)) = (&sample.value, sample.binding.transform_channel()) else {
+            continue;
+        };
+
+        let index = match ghosts.iter().position(|ghost| ghost.entity_id == entity_id) {
+            Some(index) => index,
+            None => {
+                ghosts.push(AnimationGhost {
+                    entity_id,
+                    transform: entity.transform.clone(),
+                });
+                ghosts.len() - 1
+            }
+        };
+        *field(&mut ghosts[index].transform, channel) = *value;
+    }
+
+    ghosts
+}
+
+/// Transform field a channel writes to
+fn field(transform: &mut Transform, channel: TransformChannel) -> &mut [f32; 3] {
+    match channel {
+        TransformChannel::Position => &mut transform.position,
+        TransformChannel::Rotation => &mut transform.rotation,
+        TransformChannel::Scale => &mut transform.scale,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::EntityData;
+    use ordoplay_editor_sequencer::{EntityBinding, TrackId};
+    use uuid::Uuid;
+
+    fn sample(entity: Uuid, path: &str, value: [f32; 3]) -> BindingSample {
+        BindingSample {
+            track_id: TrackId::new(),
+            binding: EntityBinding::property(
+                ordoplay_editor_sequencer::EntityId(entity),
+                "Transform",
+                path,
+            ),
+            value: KeyframeValue::Vec3(value),
+        }
+    }
+
+    #[test]
+    fn test_unresolved_bindings_are_skipped() {
+        let mut scene = SceneData::new();
+        let id = scene.add_entity(EntityData::new("Hero"));
+
+        let samples = vec![
+            sample(id.0, "position", [1.0, 2.0, 3.0]),
+            sample(Uuid::new_v4(), "position", [9.0, 9.0, 9.0]),
+        ];
+
+        let ghosts = resolve_ghosts(&samples, &scene);
+        assert_eq!(ghosts.len(), 1);
+        assert_eq!(ghosts[0].entity_id, id);
+        assert_eq!(ghosts[0].transform.position, [1.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    fn test_channels_merge_per_entity() {
+        let mut scene = SceneData::new();
+        let id = scene.add_entity(EntityData::new("Hero"));
+
+        let samples = vec![
+            sample(id.0, "position", [1.0, 0.0, 0.0]),
+            sample(id.0, "scale", [2.0, 2.0, 2.0]),
+            sample(id.0, "material.tint", [0.5, 0.5, 0.5]),
+        ];
+
+        let ghosts = resolve_ghosts(&samples, &scene);
+        assert_eq!(ghosts.len(), 1);
+        assert_eq!(ghosts[0].transform.position, [1.0, 0.0, 0.0]);
+        assert_eq!(ghosts[0].transform.scale, [2.0, 2.0, 2.0]);
+        assert_eq!(ghosts[0].transform.rotation, [0.0, 0.0, 0.0]);
+    }
+}
@@ -1,19 +1,28 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 //! Main editor application setup and event loop.
 
-use crate::panel_types::PanelType;
+use crate::audio::MixerGroup;
+use crate::commands::{AlignAxis, AlignMode};
+use crate::panel_types::{PanelType, TabTitle};
+use crate::panels::console::LogLevel;
+use crate::panels::profiler;
 use crate::panels::{
-    AssetBrowserPanel, ConsolePanel, HierarchyPanel, InspectorPanel, ProfilerPanel, ViewportPanel,
+    AssetBrowserPanel, ConsolePanel, HierarchyPanel, HistoryPanel, InspectorPanel, ProfilerPanel, ViewportPanel,
 };
 use crate::state::EditorState;
+use crate::tools::ViewPreset;
 use crate::viewport_renderer::ViewportRenderer;
 use egui_dock::{DockArea, DockState, NodeIndex, Style, TabViewer};
 use egui_wgpu::wgpu;
 use ordoplay_editor_graph::graph::Graph;
-use ordoplay_editor_graph::graphs::{gameplay::create_gameplay_registry, material::create_material_registry};
+use ordoplay_editor_graph::graphs::{
+    gameplay::{create_gameplay_registry, GameplayPreview},
+    material::{create_material_registry, MaterialPreview},
+};
 use ordoplay_editor_graph::node::NodeRegistry;
+use ordoplay_editor_graph::port::PortValue;
 use ordoplay_editor_graph::ui::GraphEditorState;
-use ordoplay_editor_sequencer::SequencerPanel;
+use ordoplay_editor_sequencer::{SequencerPanel, TransformChannel};
 use std::sync::Arc;
 use thiserror::Error;
 use winit::application::ApplicationHandler;
@@ -54,12 +63,17 @@ pub struct EditorTabViewer<'a> {
     asset_browser: &'a mut AssetBrowserPanel,
     console: &'a mut ConsolePanel,
     profiler: &'a mut ProfilerPanel,
+    history: &'a mut HistoryPanel,
     material_graph: &'a mut Graph,
     material_graph_state: &'a mut GraphEditorState,
+    /// Material graph revision with no unsaved changes
+    material_graph_clean_revision: &'a mut u64,
     material_registry: &'a NodeRegistry,
+    material_preview: &'a mut MaterialPreview,
     gameplay_graph: &'a mut Graph,
     gameplay_graph_state: &'a mut GraphEditorState,
     gameplay_registry: &'a NodeRegistry,
+    gameplay_preview: &'a mut GameplayPreview,
     sequencer_panel: &'a mut SequencerPanel,
     /// Viewport renderer (optional, for 3D rendering)
     viewport_renderer: Option<&'a mut ViewportRenderer>,
@@ -75,7 +89,18 @@ impl<'a> TabViewer for EditorTabViewer<'a> {
     type Tab = PanelType;
 
     fn title(&mut self, tab: &mut Self::Tab) -> egui::WidgetText {
-        format!("{} {}", tab.icon(), tab.name()).into()
+        self.tab_title(*tab).text.into()
+    }
+
+    fn id(&mut self, tab: &mut Self::Tab) -> egui::Id {
+        // Titles change with state, so they can't identify the tab
+        egui::Id::new(*tab)
+    }
+
+    fn on_tab_button(&mut self, tab: &mut Self::Tab, response: &egui::Response) {
+        if let Some(full) = self.tab_title(*tab).full {
+            response.clone().on_hover_text(full);
+        }
     }
 
     fn ui(&mut self, ui: &mut egui::Ui, tab: &mut Self::Tab) {
@@ -118,12 +143,12 @@ impl<'a> TabViewer for EditorTabViewer<'a> {
             }
             PanelType::Console => self.console.ui(ui, self.state),
             PanelType::Profiler => self.profiler.ui(ui, self.state),
+            PanelType::History => self.history.ui(ui, self.state),
             PanelType::MaterialGraph => {
                 self.material_graph_ui(ui);
             }
             PanelType::GameplayGraph => {
-                self.gameplay_graph_state
-                    .ui_with_registry(ui, self.gameplay_graph, Some(self.gameplay_registry));
+                self.gameplay_graph_ui(ui);
             }
             PanelType::Sequencer => {
                 self.sequencer_panel.ui(ui);
@@ -141,6 +166,55 @@ impl<'a> TabViewer for EditorTabViewer<'a> {
 }
 
 impl<'a> EditorTabViewer<'a> {
+    fn tab_title(&self, tab: PanelType) -> TabTitle {
+        match tab {
+            PanelType::Viewport => {
+                TabTitle::new(tab, Some(&self.state.scene_name()), self.state.has_unsaved_changes())
+            }
+            PanelType::Sequencer => TabTitle::new(
+                tab,
+                Some(&self.sequencer_panel.sequence.name),
+                self.sequencer_panel.dirty,
+            ),
+            PanelType::MaterialGraph => TabTitle::new(
+                tab,
+                Some(&self.material_graph.name),
+                self.material_graph.revision() != *self.material_graph_clean_revision,
+            ),
+            _ => TabTitle::new(tab, None, false),
+        }
+    }
+
+    fn gameplay_graph_ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            let preview = &mut self.gameplay_graph_state.show_port_values;
+            if ui
+                .toggle_value(preview, "Preview")
+                .on_hover_text("Run the graph from Begin Play and show the value on each port")
+                .changed()
+            {
+                self.gameplay_preview.reset();
+                self.gameplay_graph_state.port_values.clear();
+            }
+            if let Some(error) = &self.gameplay_preview.error {
+                ui.colored_label(egui::Color32::from_rgb(230, 80, 80), error);
+            }
+        });
+
+        if self.gameplay_graph_state.show_port_values {
+            let input = self.state.action_input();
+            let printed = self
+                .gameplay_preview
+                .update(self.gameplay_graph, &input, Some(&*self.state), &mut self.gameplay_graph_state.port_values);
+            for line in printed.into_iter().flatten() {
+                self.console.log(LogLevel::Info, format!("[Print String] {line}"));
+            }
+        }
+
+        self.gameplay_graph_state
+            .ui_with_registry(ui, self.gameplay_graph, Some(self.gameplay_registry));
+    }
+
     fn material_graph_ui(&mut self, ui: &mut egui::Ui) {
         egui::SidePanel::right("material_preview_panel")
             .resizable(false)
@@ -154,8 +228,53 @@ impl<'a> EditorTabViewer<'a> {
             .ui_with_registry(ui, self.material_graph, Some(self.material_registry));
     }
 
+    /// File the material graph is saved to: `<graph name>.matgraph` in the asset root
+    fn material_graph_path(&self) -> std::path::PathBuf {
+        crate::asset_paths::asset_root(self.state.project_manager.project_dir.as_deref())
+            .join(format!("{}.matgraph", self.material_graph.name))
+    }
+
+    fn save_material_graph(&mut self) {
+        let path = self.material_graph_path();
+        let result = ron::ser::to_string_pretty(&*self.material_graph, ron::ser::PrettyConfig::default())
+            .map_err(|e| e.to_string())
+            .and_then(|text| std::fs::write(&path, text).map_err(|e| e.to_string()));
+        match result {
+            Ok(()) => {
+                *self.material_graph_clean_revision = self.material_graph.revision();
+                self.console.log(LogLevel::Info, format!("Saved material graph to {}", path.display()));
+            }
+            Err(e) => self.console.log(LogLevel::Error, format!("Failed to save material graph: {}", e)),
+        }
+    }
+
+    fn load_material_graph(&mut self) {
+        let path = self.material_graph_path();
+        let result = std::fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|text| ron::from_str::<Graph>(&text).map_err(|e| e.to_string()));
+        match result {
+            Ok(graph) => {
+                *self.material_graph = graph;
+                self.material_graph_state.clear_selection();
+                self.material_preview.reset();
+                *self.material_graph_clean_revision = self.material_graph.revision();
+                self.console.log(LogLevel::Info, format!("Loaded material graph from {}", path.display()));
+            }
+            Err(e) => self.console.log(LogLevel::Error, format!("Failed to load material graph: {}", e)),
+        }
+    }
+
     fn material_preview_panel(&mut self, ui: &mut egui::Ui) {
         ui.heading("Material Preview");
+        ui.horizontal(|ui| {
+            if ui.button("Save").on_hover_text("Save the graph to the asset folder").clicked() {
+                self.save_material_graph();
+            }
+            if ui.button("Revert").on_hover_text("Reload the last saved graph").clicked() {
+                self.load_material_graph();
+            }
+        });
         ui.add_space(4.0);
 
         let preview_color = self.material_preview_color();
@@ -186,6 +305,14 @@ impl<'a> EditorTabViewer<'a> {
             ui.label("Output: None");
         }
 
+        if let Some(node) = self
+            .material_graph_state
+            .preview_node()
+            .and_then(|id| self.material_graph.node(id))
+        {
+            ui.label(format!("Previewing: {}", node.name));
+        }
+
         ui.separator();
         ui.label("Graph Settings");
         ui.checkbox(&mut self.material_graph_state.show_grid, "Show Grid");
@@ -226,30 +353,51 @@ impl<'a> EditorTabViewer<'a> {
             .map(|node| node.name.clone())
     }
 
-    fn material_preview_color(&self) -> egui::Color32 {
-        let output_node = self
-            .material_graph
-            .nodes()
-            .find(|node| node.node_type == "material_output" || node.node_type == "unlit_output");
+    /// Preview color of the last-selected node's primary output, or of the
+    /// material output's color input when no node is selected
+    fn material_preview_color(&mut self) -> egui::Color32 {
+        let outputs = self.material_preview.update(self.material_graph);
 
-        if let Some(node) = output_node {
-            let port_name = if node.node_type == "material_output" {
-                "Base Color"
-            } else {
-                "Color"
-            };
+        let preview_node = self
+            .material_graph_state
+            .preview_node()
+            .and_then(|id| self.material_graph.node(id))
+            .filter(|node| !node.outputs.is_empty());
+
+        let value = if let Some(node) = preview_node {
+            outputs
+                .get(&node.id)
+                .and_then(|output| output.get(&node.outputs[0].id))
+                .cloned()
+        } else {
+            self.material_graph
+                .nodes()
+                .find(|node| node.node_type == "material_output" || node.node_type == "unlit_output")
+                .and_then(|node| {
+                    let port_name = if node.node_type == "material_output" {
+                        "Base Color"
+                    } else {
+                        "Color"
+                    };
+                    let port = node.inputs.iter().find(|p| p.name == port_name)?;
+                    match self.material_graph.connections_to(port.id).next() {
+                        Some(connection) => outputs
+                            .get(&connection.from_node)
+                            .and_then(|output| output.get(&connection.from_port))
+                            .cloned(),
+                        None => port.default_value.clone(),
+                    }
+                })
+        };
 
-            if let Some(port) = node.inputs.iter().find(|p| p.name == port_name) {
-                if let Some(ordoplay_editor_graph::port::PortValue::Color(color)) = &port.default_value {
-                    let to_u8 = |v: f32| (v.clamp(0.0, 1.0) * 255.0) as u8;
-                    return egui::Color32::from_rgba_unmultiplied(
-                        to_u8(color[0]),
-                        to_u8(color[1]),
-                        to_u8(color[2]),
-                        to_u8(color[3]),
-                    );
-                }
-            }
+        if let Some(color) = value.as_ref().and_then(PortValue::preview_color) {
+            let to_u8 = |v: f32| (v.clamp(0.0, 1.0) * 255.0) as u8;
+            return egui::Color32::from_rgba_unmultiplied(
+                to_u8(color[0]),
+                to_u8(color[1]),
+                to_u8(color[2]),
+                to_u8(color[3]),
+            );
         }
 
         egui::Color32::from_rgb(70, 70, 80)
@@ -442,6 +590,8 @@ enum FileDialogMode {
     None,
     Open,
     SaveAs,
+    LoadReference,
+    CaptureViewport,
 }
 
 /// Inner editor state and panels
@@ -454,17 +604,32 @@ struct EditorInner {
     asset_browser: AssetBrowserPanel,
     console: ConsolePanel,
     profiler: ProfilerPanel,
+    history: HistoryPanel,
     material_graph: Graph,
     material_graph_state: GraphEditorState,
+    /// Entity subtrees dragged out of other editor instances
+    drag_inbox: crate::entity_transfer::DragInbox,
+    /// Material graph revision with no unsaved changes
+    material_graph_clean_revision: u64,
     material_registry: NodeRegistry,
+    /// Cached preview evaluation of the material graph
+    material_preview: MaterialPreview,
     gameplay_graph: Graph,
     gameplay_graph_state: GraphEditorState,
     gameplay_registry: NodeRegistry,
+    /// Live value preview of the gameplay graph
+    gameplay_preview: GameplayPreview,
     sequencer_panel: SequencerPanel,
     /// Command palette
     command_palette: crate::menus::CommandPalette,
     /// Keyboard shortcut registry
     shortcuts: crate::menus::ShortcutRegistry,
+    /// Shortcut help overlay (toggled with `?`)
+    shortcut_help: crate::menus::ShortcutHelpOverlay,
+    /// Keyboard shortcut settings window
+    shortcut_settings: crate::menus::ShortcutSettingsWindow,
+    /// Panel the shortcut help describes (last focused panel)
+    help_panel: PanelType,
     /// Editor theme
     theme: crate::theme::EditorTheme,
     /// Show theme settings window
@@ -473,6 +638,12 @@ struct EditorInner {
     file_dialog_mode: FileDialogMode,
     /// File dialog path input
     file_dialog_path: String,
+    /// Resolution of viewport captures, in pixels
+    capture_size: [u32; 2],
+    /// PNG path of a viewport capture to take this frame
+    pending_capture: Option<std::path::PathBuf>,
+    /// Recompile the viewport shader this frame
+    pending_shader_reload: bool,
     /// Show unsaved changes warning
     show_unsaved_warning: bool,
     /// Pending action after unsaved warning
@@ -481,8 +652,16 @@ struct EditorInner {
     project_settings: crate::panels::ProjectSettingsPanel,
     /// Entity clipboard for Cut/Copy/Paste operations
     clipboard: Vec<(crate::state::EntityId, crate::state::EntityData)>,
+    /// Name typed for a new selection group
+    selection_group_name: String,
     /// Whether the app should exit (set by unsaved changes dialog)
     request_exit: bool,
+    /// Debounced file changes waiting to be hot-reloaded
+    reload_queue: crate::hot_reload::ReloadQueue,
+    /// Scenes/prefabs changed on disk, awaiting a reload prompt
+    pending_disk_reloads: Vec<std::path::PathBuf>,
+    /// Triangle counts of mesh assets, for scene budgets
+    mesh_stats: crate::mesh_stats::MeshStatsCache,
 }
 
 impl EditorInner {
@@ -490,6 +669,9 @@ impl EditorInner {
         let material_registry = create_material_registry();
         let gameplay_registry = create_gameplay_registry();
 
+        let material_graph = Self::create_material_graph(&material_registry);
+        let material_graph_clean_revision = material_graph.revision();
+
         Self {
             state: EditorState::new(),
             dock_state: Self::create_default_layout(),
@@ -499,24 +681,39 @@ impl EditorInner {
             asset_browser: AssetBrowserPanel::new(),
             console: ConsolePanel::with_tracing_receiver(tracing_rx),
             profiler: ProfilerPanel::new(),
-            material_graph: Self::create_material_graph(&material_registry),
+            history: HistoryPanel::new(),
+            material_graph,
             material_graph_state: GraphEditorState::new(),
+            drag_inbox: crate::entity_transfer::DragInbox::new(crate::entity_transfer::drag_dir()),
+            material_graph_clean_revision,
             material_registry,
+            material_preview: MaterialPreview::new(),
             gameplay_graph: Self::create_gameplay_graph(&gameplay_registry),
             gameplay_graph_state: GraphEditorState::new(),
             gameplay_registry,
+            gameplay_preview: GameplayPreview::new(),
             sequencer_panel: SequencerPanel::new("Main Sequencer"),
             command_palette: crate::menus::CommandPalette::new(),
             shortcuts: crate::menus::ShortcutRegistry::new(),
+            shortcut_help: crate::menus::ShortcutHelpOverlay::default(),
+            shortcut_settings: crate::menus::ShortcutSettingsWindow::default(),
+            help_panel: PanelType::Viewport,
             theme: crate::theme::EditorTheme::default(),
             show_theme_settings: false,
             file_dialog_mode: FileDialogMode::None,
             file_dialog_path: String::new(),
+            capture_size: [1920, 1080],
+            pending_capture: None,
+            pending_shader_reload: false,
             show_unsaved_warning: false,
             pending_action: None,
             project_settings: crate::panels::ProjectSettingsPanel::new(),
             clipboard: Vec::new(),
+            selection_group_name: String::new(),
             request_exit: false,
+            reload_queue: crate::hot_reload::ReloadQueue::default(),
+            pending_disk_reloads: Vec::new(),
+            mesh_stats: crate::mesh_stats::MeshStatsCache::default(),
         }
     }
 
@@ -570,6 +767,52 @@ impl EditorInner {
         dock_state
     }
 
+    /// Key last frame's transform edits in the sequencer while it records.
+    ///
+    /// Edits made in play mode are simulation state and are never keyed.
+    fn record_transform_commits(&mut self) {
+        let commits = self.state.take_transform_commits();
+        if self.state.play_mode.current_state().is_active() {
+            return;
+        }
+
+        for commit in commits {
+            let changed: Vec<_> = TransformChannel::ALL
+                .into_iter()
+                .filter_map(|channel| {
+                    let (before, after) = match channel {
+                        TransformChannel::Position => (commit.before.position, commit.after.position),
+                        TransformChannel::Rotation => (commit.before.rotation, commit.after.rotation),
+                        TransformChannel::Scale => (commit.before.scale, commit.after.scale),
+                    };
+                    (before != after).then_some((channel, after))
+                })
+                .collect();
+            let name = self.state.scene.get(&commit.entity).map(|e| e.name.clone()).unwrap_or_default();
+            self.sequencer_panel
+                .record_transform(ordoplay_editor_sequencer::EntityId(commit.entity.0), &name, &changed);
+        }
+    }
+
+    /// Drain this frame's trigger events, logging enters and exits when
+    /// the physics debug menu asks for it
+    fn log_trigger_events(&mut self) {
+        let events = self.state.physics_world.drain_trigger_events();
+        if !self.state.physics_debug.log_trigger_events {
+            return;
+        }
+
+        let name = |id| self.state.scene.get(&id).map_or("<removed>", |e| e.name.as_str());
+        for event in events {
+            let verb = match event.phase {
+                crate::physics::TriggerPhase::Enter => "entered",
+                crate::physics::TriggerPhase::Exit => "left",
+                crate::physics::TriggerPhase::Stay => continue,
+            };
+            tracing::info!("{} {} trigger {}", name(event.other), verb, name(event.entity));
+        }
+    }
+
     fn update(
         &mut self,
         ctx: &egui::Context,
@@ -579,25 +822,87 @@ impl EditorInner {
         egui_renderer: &mut egui_wgpu::Renderer,
     ) {
         let delta_time = ctx.input(|i| i.stable_dt);
-        self.sequencer_panel.update(delta_time);
+        self.profiler.record_frame(delta_time * 1000.0, profiler::take_sections());
+        // Sections are only timed while someone can look at them
+        profiler::set_sections_enabled(self.dock_state.find_tab(&PanelType::Profiler).is_some());
+
+        {
+            let _scope = profiler::scope("Sequencer");
+            self.sequencer_panel.update(delta_time);
+            // Audio clips resolve against the open project for their waveforms
+            self.sequencer_panel
+                .set_asset_root(crate::asset_paths::asset_root(self.state.project_manager.project_dir.as_deref()));
+            self.record_transform_commits();
+            self.state.animation_ghosts = crate::animation_ghost::resolve_ghosts(
+                &self.sequencer_panel.reference_samples(),
+                &self.state.scene,
+            );
+        }
 
         // Update physics simulation if in play mode
         if self.state.play_mode.current_state() == crate::play_mode::PlayState::Playing {
+            self.state.play_mode.sample_input(ctx);
+
             let fixed_timestep = self.state.project_manager.settings.physics.fixed_timestep;
-            let steps = self.state.play_mode.update(delta_time as f64, fixed_timestep as f64);
+            let steps = self.state.play_mode.update_recording(
+                delta_time as f64,
+                fixed_timestep as f64,
+                &self.state.scene,
+                &self.state.physics_world,
+            );
 
             // Run fixed timestep physics updates
+            let physics_scope = profiler::scope("Physics");
             for _ in 0..steps {
                 self.state.physics_world.step(fixed_timestep);
             }
+            self.log_trigger_events();
 
             // Sync physics results back to scene
             self.state.physics_world.sync_to_scene(&mut self.state.scene);
+            drop(physics_scope);
 
             // Update audio system
+            let _scope = profiler::scope("Audio");
             self.state.audio_engine.update(&self.state.scene);
         }
 
+        // Hot reload: coalesce bursts of file changes before reloading
+        let assets_scope = profiler::scope("Assets");
+        self.asset_browser.poll_file_watcher();
+        let now = std::time::Instant::now();
+        self.reload_queue.extend(self.asset_browser.take_modified_paths(), now);
+        let ready = self.reload_queue.drain_ready(now);
+        if !ready.is_empty() {
+            self.apply_hot_reload(&ready, egui_renderer);
+        }
+        if std::mem::take(&mut self.pending_shader_reload) {
+            Self::reload_shaders(viewport_renderer, device, self.state.project_manager.project_dir.as_deref());
+        }
+        if !self.reload_queue.is_empty() || self.asset_browser.has_pending_file_events() {
+            ctx.request_repaint_after(std::time::Duration::from_millis(100));
+        }
+        drop(assets_scope);
+
+        // Background builds report progress and results through the manager
+        self.state.build_manager.poll();
+        if self.state.build_manager.is_building() {
+            ctx.request_repaint_after(std::time::Duration::from_millis(100));
+        }
+
+        // Background scene validation (debounced, paused during play mode)
+        let validating = self.state.validator.update(
+            &self.state.scene,
+            self.state.history.revision(),
+            self.state.play_mode.current_state().is_active(),
+        );
+        if validating {
+            ctx.request_repaint_after(std::time::Duration::from_millis(100));
+        }
+
+        // Everything from here on builds the egui UI
+        let _ui_scope = profiler::scope("UI");
+
         // Top menu bar
         egui::TopBottomPanel::top("menu_bar").show(ctx, |ui| {
             egui::menu::bar(ui, |ui| {
@@ -614,6 +919,48 @@ impl EditorInner {
             });
         });
 
+        // Status bar
+        egui::TopBottomPanel::bottom("status_bar").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(self.state.gizmo_mode.name());
+                if let Some(axis) = self.state.axis_constraint.name() {
+                    ui.label(format!("({} only)", axis));
+                }
+                ui.separator();
+                ui.label(if self.state.use_world_space { "World" } else { "Local" });
+
+                if let Some(pending) = self.shortcuts.pending_sequence_display() {
+                    ui.separator();
+                    ui.label(
+                        egui::RichText::new(format!("{}, \u{2026}", pending))
+                            .color(egui::Color32::from_rgb(255, 200, 80))
+                            .monospace(),
+                    )
+                    .on_hover_text("Waiting for the next key of a shortcut sequence");
+                    ctx.request_repaint_after(std::time::Duration::from_secs_f64(
+                        self.shortcuts.sequence_timeout(),
+                    ));
+                }
+
+                let warnings = self.budget_warnings();
+                if let Some(first) = warnings.first() {
+                    ui.separator();
+                    let text = if warnings.len() > 1 {
+                        format!("\u{26a0} {} (+{} more)", first, warnings.len() - 1)
+                    } else {
+                        format!("\u{26a0} {}", first)
+                    };
+                    ui.label(egui::RichText::new(text).color(egui::Color32::from_rgb(255, 170, 60)))
+                        .on_hover_ui(|ui| {
+                            ui.label("Scene exceeds project budgets:");
+                            for warning in &warnings {
+                                ui.label(warning.to_string());
+                            }
+                        });
+                }
+            });
+        });
+
         // Prefab editing indicator bar
         if self.state.is_editing_prefab() {
             egui::TopBottomPanel::top("prefab_edit_bar").show(ctx, |ui| {
@@ -662,12 +1009,16 @@ impl EditorInner {
             asset_browser: &mut self.asset_browser,
             console: &mut self.console,
             profiler: &mut self.profiler,
+            history: &mut self.history,
             material_graph: &mut self.material_graph,
             material_graph_state: &mut self.material_graph_state,
+            material_graph_clean_revision: &mut self.material_graph_clean_revision,
             material_registry: &self.material_registry,
+            material_preview: &mut self.material_preview,
             gameplay_graph: &mut self.gameplay_graph,
             gameplay_graph_state: &mut self.gameplay_graph_state,
             gameplay_registry: &self.gameplay_registry,
+            gameplay_preview: &mut self.gameplay_preview,
             sequencer_panel: &mut self.sequencer_panel,
             viewport_renderer: Some(viewport_renderer),
             device: Some(device),
@@ -681,8 +1032,15 @@ impl EditorInner {
 
         // Show dialogs
         self.show_file_dialog(ctx);
+        if let Some(path) = self.pending_capture.take() {
+            self.capture_viewport(&path, viewport_renderer, device, queue);
+        }
         self.show_unsaved_warning_dialog(ctx);
+        self.show_disk_reload_dialog(ctx);
         self.show_theme_settings(ctx);
+        if self.shortcut_settings.ui(ctx, &self.command_palette.registry, &mut self.shortcuts) {
+            self.save_shortcuts();
+        }
         self.project_settings.show(ctx, &mut self.state);
 
         // Show command palette
@@ -690,9 +1048,26 @@ impl EditorInner {
 
         // Handle pending commands from command palette
         if let Some(command_id) = self.command_palette.take_pending_command() {
+            let path = crate::menus::user_config_dir().join(crate::menus::RECENT_COMMANDS_FILE);
+            if let Err(e) = self.command_palette.save_recent(&path) {
+                tracing::error!("Failed to save recent commands to {}: {}", path.display(), e);
+            }
             self.execute_command(command_id);
         }
 
+        // Show shortcut help for the focused panel
+        if let Some((_, tab)) = self.dock_state.find_active_focused() {
+            self.help_panel = *tab;
+        }
+        if self.shortcut_help.is_visible() {
+            let entries = crate::menus::shortcuts_for_panel(
+                &self.command_palette.registry,
+                &self.shortcuts,
+                self.help_panel,
+            );
+            self.shortcut_help.ui(ctx, self.help_panel, &entries);
+        }
+
         // Handle keyboard shortcuts
         self.handle_shortcuts(ctx);
 
@@ -702,6 +1077,108 @@ impl EditorInner {
         }
     }
 
+    /// Reload assets modified on disk and queue prompts for changed scenes/prefabs
+    fn apply_hot_reload(
+        &mut self,
+        paths: &[std::path::PathBuf],
+        egui_renderer: &mut egui_wgpu::Renderer,
+    ) {
+        let request = crate::hot_reload::ReloadRequest::new(paths, &self.state.scene);
+        if request.is_empty() {
+            return;
+        }
+
+        let project_dir = self.state.project_manager.project_dir.as_deref();
+        if request.shaders.iter().any(|path| ViewportRenderer::uses_shader(path, project_dir)) {
+            self.pending_shader_reload = true;
+        }
+
+        if !request.assets.is_empty() {
+            self.asset_browser
+                .thumbnail_manager
+                .hot_reload_paths(&request.assets, egui_renderer);
+            tracing::info!(
+                "Hot reloaded {} asset(s) used by {} entities",
+                request.assets.len(),
+                request.affected_entities.len()
+            );
+        }
+
+        // Only prompt for the open scene when someone else wrote it
+        for path in &request.scenes {
+            let is_open_scene = self.state.scene_path.as_ref().is_some_and(|p| path.ends_with(p));
+            if is_open_scene && self.state.scene_changed_on_disk() && !self.pending_disk_reloads.contains(path) {
+                self.pending_disk_reloads.push(path.clone());
+            }
+        }
+
+        // Prefabs only matter if instanced in the current scene
+        for path in &request.prefabs {
+            let in_use = self.state.prefab_manager
+                .all_instances()
+                .any(|instance| path.ends_with(&instance.prefab_path));
+            if in_use && !self.pending_disk_reloads.contains(path) {
+                self.pending_disk_reloads.push(path.clone());
+            }
+        }
+    }
+
+    /// Recompile the viewport shader, keeping the current pipelines if it
+    /// fails to compile
+    fn reload_shaders(
+        viewport_renderer: &mut ViewportRenderer,
+        device: &wgpu::Device,
+        project_dir: Option<&std::path::Path>,
+    ) {
+        match viewport_renderer.reload_shaders(device, project_dir) {
+            Ok(()) => tracing::info!("Reloaded viewport shader"),
+            Err(err) => tracing::error!("Shader reload failed, keeping the previous shader: {}", err),
+        }
+    }
+
+    fn show_disk_reload_dialog(&mut self, ctx: &egui::Context) {
+        let Some(path) = self.pending_disk_reloads.first().cloned() else {
+            return;
+        };
+
+        let is_prefab = crate::hot_reload::HotReloadAssetType::from_path(&path)
+            == crate::hot_reload::HotReloadAssetType::Prefab;
+        let mut should_close = false;
+
+        egui::Window::new("File Changed on Disk")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label(format!("'{}' was modified outside the editor.", path.display()));
+                if !is_prefab && self.state.has_unsaved_changes() {
+                    ui.colored_label(egui::Color32::YELLOW, "Reloading will discard your unsaved changes.");
+                }
+                ui.add_space(10.0);
+
+                ui.horizontal(|ui| {
+                    if ui.button("Ignore").clicked() {
+                        should_close = true;
+                    }
+                    if ui.button("Reload").clicked() {
+                        let result = if is_prefab {
+                            self.state.prefab_manager.reload_prefab(&path).map_err(|e| e.to_string())
+                        } else {
+                            self.state.load_scene(&path)
+                        };
+                        if let Err(e) = result {
+                            tracing::error!("Failed to reload {:?}: {}", path, e);
+                        }
+                        should_close = true;
+                    }
+                });
+            });
+
+        if should_close {
+            self.pending_disk_reloads.remove(0);
+        }
+    }
+
     fn show_theme_settings(&mut self, ctx: &egui::Context) {
         if !self.show_theme_settings {
             return;
@@ -724,6 +1201,31 @@ impl EditorInner {
         }
     }
 
+    /// Apply the user's shortcut overrides and the palette's recent
+    /// commands, if any were saved
+    fn load_user_config(&mut self) {
+        let path = crate::menus::user_config_dir().join(crate::menus::SHORTCUTS_FILE);
+        if path.exists() {
+            if let Err(e) = self.shortcuts.load(&path, &self.command_palette.registry) {
+                tracing::error!("Failed to load shortcuts from {}: {}", path.display(), e);
+            }
+        }
+
+        let path = crate::menus::user_config_dir().join(crate::menus::RECENT_COMMANDS_FILE);
+        if path.exists() {
+            if let Err(e) = self.command_palette.load_recent(&path) {
+                tracing::error!("Failed to load recent commands from {}: {}", path.display(), e);
+            }
+        }
+    }
+
+    fn save_shortcuts(&self) {
+        let path = crate::menus::user_config_dir().join(crate::menus::SHORTCUTS_FILE);
+        if let Err(e) = self.shortcuts.save(&path) {
+            tracing::error!("Failed to save shortcuts to {}: {}", path.display(), e);
+        }
+    }
+
     fn show_file_dialog(&mut self, ctx: &egui::Context) {
         if self.file_dialog_mode == FileDialogMode::None {
             return;
@@ -732,6 +1234,8 @@ impl EditorInner {
         let title = match self.file_dialog_mode {
             FileDialogMode::Open => "Open Scene",
             FileDialogMode::SaveAs => "Save Scene As",
+            FileDialogMode::LoadReference => "Load Reference Sequence",
+            FileDialogMode::CaptureViewport => "Capture Viewport",
             FileDialogMode::None => return,
         };
 
@@ -747,6 +1251,20 @@ impl EditorInner {
                     ui.add(egui::TextEdit::singleline(&mut self.file_dialog_path).desired_width(300.0));
                 });
 
+                if self.file_dialog_mode == FileDialogMode::CaptureViewport {
+                    ui.horizontal(|ui| {
+                        ui.label("Size:");
+                        ui.add(egui::DragValue::new(&mut self.capture_size[0]).range(1..=16384).suffix(" px"));
+                        ui.label("\u{d7}");
+                        ui.add(egui::DragValue::new(&mut self.capture_size[1]).range(1..=16384).suffix(" px"));
+                        for (name, size) in [("1080p", [1920, 1080]), ("1440p", [2560, 1440]), ("4K", [3840, 2160])] {
+                            if ui.selectable_label(self.capture_size == size, name).clicked() {
+                                self.capture_size = size;
+                            }
+                        }
+                    });
+                }
+
                 ui.add_space(10.0);
 
                 ui.horizontal(|ui| {
@@ -757,6 +1275,8 @@ impl EditorInner {
                     let action_text = match self.file_dialog_mode {
                         FileDialogMode::Open => "Open",
                         FileDialogMode::SaveAs => "Save",
+                        FileDialogMode::LoadReference => "Load",
+                        FileDialogMode::CaptureViewport => "Capture",
                         FileDialogMode::None => "OK",
                     };
 
@@ -773,6 +1293,17 @@ impl EditorInner {
                                     tracing::error!("Failed to save scene: {}", e);
                                 }
                             }
+                            FileDialogMode::LoadReference => {
+                                match std::fs::read_to_string(&path)
+                                    .map_err(|e| e.to_string())
+                                    .and_then(|text| ron::from_str(&text).map_err(|e| e.to_string()))
+                                {
+                                    Ok(reference) => self.sequencer_panel.set_reference(reference),
+                                    Err(e) => tracing::error!("Failed to load reference sequence: {}", e),
+                                }
+                            }
+                            // Needs the renderer, so it happens after the dialogs
+                            FileDialogMode::CaptureViewport => self.pending_capture = Some(path),
                             FileDialogMode::None => {}
                         }
                         should_close = true;
@@ -785,6 +1316,32 @@ impl EditorInner {
         }
     }
 
+    /// Render the viewport at the capture size and save it as a PNG
+    fn capture_viewport(
+        &mut self,
+        path: &std::path::Path,
+        viewport_renderer: &mut ViewportRenderer,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+    ) {
+        let image = match viewport_renderer.capture(device, queue, self.capture_size) {
+            Ok(image) => image,
+            Err(e) => {
+                tracing::error!("Failed to read back viewport capture: {}", e);
+                return;
+            }
+        };
+        match image.save_with_format(path, image::ImageFormat::Png) {
+            Ok(()) => tracing::info!(
+                "Saved {}x{} viewport capture to {}",
+                image.width(),
+                image.height(),
+                path.display()
+            ),
+            Err(e) => tracing::error!("Failed to save viewport capture to {}: {}", path.display(), e),
+        }
+    }
+
     fn show_unsaved_warning_dialog(&mut self, ctx: &egui::Context) {
         if !self.show_unsaved_warning {
             return;
@@ -981,12 +1538,73 @@ impl EditorInner {
                 self.state.duplicate_selected();
                 ui.close_menu();
             }
+            if ui.button("Group Selected (Ctrl+G)").clicked() {
+                self.state.group_selected();
+                ui.close_menu();
+            }
+            if ui.button("Ungroup (Ctrl+Shift+G)").clicked() {
+                self.state.ungroup_selected();
+                ui.close_menu();
+            }
+            ui.menu_button("Selection Groups", |ui| {
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.selection_group_name)
+                            .desired_width(120.0)
+                            .hint_text("group name"),
+                    );
+                    let can_store = !self.selection_group_name.trim().is_empty() && !self.state.selection.is_empty();
+                    if ui.add_enabled(can_store, egui::Button::new("Store")).clicked() {
+                        self.state.store_selection_group(&self.selection_group_name);
+                        self.selection_group_name.clear();
+                    }
+                });
+
+                let names: Vec<String> = self.state.scene.selection_groups.keys().cloned().collect();
+                if !names.is_empty() {
+                    ui.separator();
+                }
+                for name in names {
+                    ui.horizontal(|ui| {
+                        if ui.button(&name).on_hover_text("Select this group").clicked() {
+                            self.state.recall_selection_group(&name);
+                            ui.close_menu();
+                        }
+                        if ui.small_button("✕").on_hover_text("Delete group").clicked() {
+                            self.state.delete_selection_group(&name);
+                        }
+                    });
+                }
+            });
+            ui.add_enabled_ui(!self.state.selection.is_empty(), |ui| {
+                ui.menu_button("Select Similar", |ui| {
+                    for criterion in crate::state::SimilarityCriterion::all() {
+                        if ui.button(criterion.name()).clicked() {
+                            self.state.select_similar(*criterion);
+                            ui.close_menu();
+                        }
+                    }
+                });
+                if ui.button("Drop to Ground (End)").clicked() {
+                    self.state.drop_selected_to_ground();
+                    ui.close_menu();
+                }
+                if ui.button("Combine Static Meshes").clicked() {
+                    let ids = self.state.selection.entities.clone();
+                    self.state.combine_static_meshes(&ids);
+                    ui.close_menu();
+                }
+            });
 
             ui.separator();
             if ui.button("Project Settings...").clicked() {
                 self.project_settings.open = true;
                 ui.close_menu();
             }
+            if ui.button("Keyboard Shortcuts...").clicked() {
+                self.shortcut_settings.open();
+                ui.close_menu();
+            }
         });
     }
 
@@ -1000,6 +1618,7 @@ impl EditorInner {
                     PanelType::AssetBrowser,
                     PanelType::Console,
                     PanelType::Profiler,
+                    PanelType::History,
                     PanelType::MaterialGraph,
                     PanelType::GameplayGraph,
                     PanelType::Sequencer,
@@ -1011,6 +1630,11 @@ impl EditorInner {
                 }
             });
 
+            ui.menu_button("Camera", |ui| {
+                self.viewport.camera_view_menu(ui);
+            });
+            ui.checkbox(&mut self.viewport.show_grid, "Show Grid");
+
             ui.menu_button("Theme", |ui| {
                 // Theme presets
                 for preset in crate::theme::ThemePreset::all() {
@@ -1030,8 +1654,26 @@ impl EditorInner {
                 ui.checkbox(&mut self.state.physics_debug.show_colliders, "Show Colliders").changed();
                 ui.checkbox(&mut self.state.physics_debug.show_velocities, "Show Velocities").changed();
                 ui.checkbox(&mut self.state.physics_debug.show_contacts, "Show Contacts").changed();
-                if ui.checkbox(&mut self.state.physics_debug.show_layers, "Show Layers").changed() {
-                }
+                ui.checkbox(&mut self.state.physics_debug.show_layers, "Show Layers");
+                ui.checkbox(&mut self.state.physics_debug.log_trigger_events, "Log Trigger Events");
+            });
+
+            ui.menu_button("Audio Mixer", |ui| {
+                let engine = &mut self.state.audio_engine;
+                let groups: Vec<(String, MixerGroup)> =
+                    engine.mixer.groups.iter().map(|(name, group)| (name.clone(), *group)).collect();
+                egui::Grid::new("audio_mixer_grid").num_columns(3).show(ui, |ui| {
+                    for (name, mut group) in groups {
+                        ui.label(&name);
+                        if ui.add(egui::Slider::new(&mut group.volume, 0.0..=1.0)).changed() {
+                            engine.set_group_volume(&name, group.volume);
+                        }
+                        if ui.checkbox(&mut group.muted, "Mute").changed() {
+                            engine.mute_group(&name, group.muted);
+                        }
+                        ui.end_row();
+                    }
+                });
             });
 
             ui.separator();
@@ -1056,6 +1698,99 @@ impl EditorInner {
                 self.open_panel(PanelType::Sequencer);
                 ui.close_menu();
             }
+            if ui.button("Load Animation Reference...").clicked() {
+                self.file_dialog_mode = FileDialogMode::LoadReference;
+                self.file_dialog_path = "reference.ron".to_string();
+                ui.close_menu();
+            }
+            if ui
+                .add_enabled(self.sequencer_panel.reference().is_some(), egui::Button::new("Clear Animation Reference"))
+                .clicked()
+            {
+                self.sequencer_panel.clear_reference();
+                ui.close_menu();
+            }
+            if ui.button("Capture Viewport...").clicked() {
+                self.file_dialog_mode = FileDialogMode::CaptureViewport;
+                self.file_dialog_path = "viewport.png".to_string();
+                ui.close_menu();
+            }
+            if ui
+                .button("Reload Shaders")
+                .on_hover_text("Recompile the viewport shader from disk")
+                .clicked()
+            {
+                self.pending_shader_reload = true;
+                ui.close_menu();
+            }
+            ui.separator();
+            if ui
+                .button("Collapse Duplicate Asset Paths")
+                .on_hover_text("Unify asset references that differ only by separators or casing")
+                .clicked()
+            {
+                let unified = self.state.collapse_duplicate_asset_paths();
+                tracing::info!("Unified {} asset path reference(s)", unified);
+                ui.close_menu();
+            }
+            let selected = self.state.selection.entities.len();
+            if ui
+                .add_enabled(selected >= 1, egui::Button::new("Snap to Ground (Shift+End)"))
+                .on_hover_text("Move the selection onto the colliders below it")
+                .clicked()
+            {
+                self.state.snap_selected_to_ground(false);
+                ui.close_menu();
+            }
+            if ui
+                .add_enabled(selected >= 1, egui::Button::new("Snap to Ground (Align to Surface)"))
+                .on_hover_text("Also tilt each entity so its up axis follows the surface")
+                .clicked()
+            {
+                self.state.snap_selected_to_ground(true);
+                ui.close_menu();
+            }
+            ui.add_enabled_ui(selected >= 2, |ui| {
+                ui.menu_button("Align", |ui| {
+                    for axis in AlignAxis::ALL {
+                        for mode in AlignMode::ALL {
+                            if ui.button(format!("{} {}", axis.name(), mode.name())).clicked() {
+                                self.state.align_selected(axis, mode);
+                                ui.close_menu();
+                            }
+                        }
+                    }
+                });
+            });
+            ui.add_enabled_ui(selected >= 3, |ui| {
+                ui.menu_button("Distribute", |ui| {
+                    for axis in AlignAxis::ALL {
+                        if ui.button(format!("Along {}", axis.name())).clicked() {
+                            self.state.distribute_selected(axis);
+                            ui.close_menu();
+                        }
+                    }
+                });
+            });
+            // Select the entity to copy, then the path entity
+            let pair = match self.state.selection.entities.as_slice() {
+                [entity, path] => Some((*entity, *path)),
+                _ => None,
+            };
+            ui.add_enabled_ui(pair.is_some(), |ui| {
+                ui.menu_button("Distribute Along Path", |ui| {
+                    for count in [3, 5, 10] {
+                        for (label, align) in [("", false), (" (Aligned)", true)] {
+                            if ui.button(format!("{count} Copies{label}")).clicked() {
+                                if let Some((entity, path)) = pair {
+                                    self.state.distribute_along_path(entity, path, count, align);
+                                }
+                                ui.close_menu();
+                            }
+                        }
+                    }
+                });
+            });
             ui.separator();
             if ui.button("Profiler").clicked() {
                 self.open_panel(PanelType::Profiler);
@@ -1075,8 +1810,24 @@ impl EditorInner {
         });
     }
 
+    /// Project budget violations of the current scene
+    fn budget_warnings(&mut self) -> Vec<crate::project::BudgetWarning> {
+        let budgets = self.state.project_manager.settings.budgets;
+        if budgets == crate::project::PerformanceBudgets::default() {
+            return Vec::new();
+        }
+
+        let root = crate::asset_paths::asset_root(self.state.project_manager.project_dir.as_deref());
+        let mesh_stats = &mut self.mesh_stats;
+        let stats = self
+            .state
+            .scene
+            .statistics(|mesh| mesh_stats.triangle_count(&root, mesh));
+        budgets.check(&stats)
+    }
+
     fn play_mode_controls(&mut self, ui: &mut egui::Ui) {
-        use crate::play_mode::PlayState;
+        use crate::play_mode::{GameViewAspect, PlayState};
 
         let play_state = self.state.play_mode.current_state();
         let is_playing = play_state == PlayState::Playing;
@@ -1104,15 +1855,53 @@ impl EditorInner {
             }
         }
 
-        // Step frame button (only when paused)
-        if is_paused
-            && ui.small_button("\u{23ED}")  // Next frame symbol
+        // Game view aspect, applied to the viewport while playing
+        let aspect = &mut self.state.play_mode.game_view_aspect;
+        egui::ComboBox::from_id_salt("game_view_aspect")
+            .selected_text(aspect.label())
+            .show_ui(ui, |ui| {
+                let custom = match *aspect {
+                    GameViewAspect::Resolution { .. } => *aspect,
+                    _ => GameViewAspect::Resolution { width: 1920, height: 1080 },
+                };
+                for option in [GameViewAspect::Free, GameViewAspect::Wide, GameViewAspect::Standard] {
+                    ui.selectable_value(aspect, option, option.label());
+                }
+                ui.selectable_value(aspect, custom, "Custom Resolution");
+            })
+            .response
+            .on_hover_text("Game view aspect in play mode");
+        if let GameViewAspect::Resolution { width, height } = aspect {
+            ui.add(egui::DragValue::new(width).range(1..=7680).suffix(" w"));
+            ui.add(egui::DragValue::new(height).range(1..=4320).suffix(" h"));
+        }
+
+        // Step frame buttons (only when paused)
+        if is_paused {
+            let can_step_back = self.state.play_mode.snapshot_count() > 0;
+            if ui.add_enabled(can_step_back, egui::Button::new("\u{23EE}").small())  // Previous frame symbol
+                .on_hover_text("Step Back")
+                .on_disabled_hover_text("No earlier frame recorded")
+                .clicked()
+            {
+                if let Some((scene, physics)) = self.state.play_mode.step_frame_back() {
+                    self.state.scene = scene;
+                    self.state.physics_world = physics;
+                }
+            }
+
+            if ui.small_button("\u{23ED}")  // Next frame symbol
                 .on_hover_text("Step Frame")
                 .clicked()
             {
-                let timestep = self.state.project_manager.settings.physics.fixed_timestep as f64;
-                self.state.play_mode.step_frame(timestep);
+                let timestep = self.state.project_manager.settings.physics.fixed_timestep;
+                self.state.play_mode.record_snapshot(&self.state.scene, &self.state.physics_world);
+                self.state.play_mode.step_frame(f64::from(timestep));
+                self.state.physics_world.step(timestep);
+                self.log_trigger_events();
+                self.state.physics_world.sync_to_scene(&mut self.state.scene);
             }
+        }
 
         // Stop button
         if ui.add_enabled(!is_stopped, egui::Button::new("\u{25A0}"))  // Stop symbol
@@ -1161,16 +1950,26 @@ impl EditorInner {
 
     fn handle_shortcuts(&mut self, ctx: &egui::Context) {
         // Check play mode shortcuts (extract key states first to avoid borrow issues)
-        let (escape_pressed, f5_pressed, f6_pressed) = ctx.input(|input| {
+        let (escape_pressed, f5_pressed, f6_pressed, help_pressed) = ctx.input(|input| {
             (
                 input.key_pressed(egui::Key::Escape),
                 input.key_pressed(egui::Key::F5),
                 input.key_pressed(egui::Key::F6),
+                input.events.iter().any(|event| matches!(event, egui::Event::Text(text) if text == "?")),
             )
         });
 
-        // Escape to stop play mode
-        if escape_pressed && self.state.play_mode.current_state().is_active() {
+        // `?` typed outside a text field toggles the shortcut help
+        if help_pressed && ctx.memory(|m| m.focused().is_none()) {
+            self.shortcut_help.toggle();
+        }
+
+        // Escape cancels an eyedropper pick before anything else
+        if escape_pressed && self.state.entity_picker.is_active() {
+            self.state.entity_picker.cancel();
+        } else if escape_pressed && self.shortcut_help.is_visible() {
+            self.shortcut_help.hide();
+        } else if escape_pressed && self.state.play_mode.current_state().is_active() {
             if let Some((scene, selection)) = self.state.play_mode.stop() {
                 self.state.scene = scene;
                 self.state.selection = selection;
@@ -1208,14 +2007,48 @@ impl EditorInner {
             self.state.audio_engine.pause_all();
         }
 
-        // Use the shortcut registry to check for triggered commands
-        if let Some(command_id) = self.shortcuts.check_input(ctx) {
-            // Handle special UI commands that aren't in execute_command
-            if command_id == "ui.command_palette" {
-                self.command_palette.toggle();
-                return;
+        // Entity subtrees pasted from another editor are imported while the
+        // hierarchy or viewport has focus and no text field takes the paste
+        let entity_panel_focused = matches!(
+            self.dock_state.find_active_focused(),
+            Some((_, PanelType::Hierarchy | PanelType::Viewport))
+        );
+        let pasted_subtree = (entity_panel_focused && !ctx.wants_keyboard_input())
+            .then(|| {
+                ctx.input(|input| {
+                    input.events.iter().find_map(|event| match event {
+                        egui::Event::Paste(text) => crate::entity_transfer::EntitySubtree::from_ron(text).ok(),
+                        _ => None,
+                    })
+                })
+            })
+            .flatten();
+        if let Some(subtree) = &pasted_subtree {
+            let parent = self.hierarchy.hovered_entity;
+            if self.state.import_subtree(subtree, parent).is_some() {
+                tracing::info!("Imported {} entities", subtree.entities.len());
+            }
+        }
+
+        // Subtrees dragged out of another editor drop under the hierarchy
+        // row or viewport entity the pointer moves over
+        if self.drag_inbox.poll() {
+            let target = if self.hierarchy.hovered {
+                Some(self.hierarchy.hovered_entity)
+            } else {
+                self.viewport.drop_target(&self.state)
+            };
+            if let Some(parent) = target {
+                if let Some(subtree) = self.drag_inbox.take() {
+                    if self.state.import_subtree(&subtree, parent).is_some() {
+                        tracing::info!("Imported {} dragged entities", subtree.entities.len());
+                    }
+                }
             }
+        }
 
+        // Use the shortcut registry to check for triggered commands
+        if let Some(command_id) = self.shortcuts.check_input(ctx) {
             // Execute the command
             self.execute_command(command_id);
         }
@@ -1283,6 +2116,12 @@ impl EditorInner {
             "edit.duplicate" => {
                 self.state.duplicate_selected();
             }
+            "edit.group" => {
+                self.state.group_selected();
+            }
+            "edit.ungroup" => {
+                self.state.ungroup_selected();
+            }
             "edit.select_all" => {
                 // Select all entities
                 let ids: Vec<_> = self.state.scene.entities.keys().copied().collect();
@@ -1299,36 +2138,89 @@ impl EditorInner {
             "view.focus_selection" => {
                 self.viewport.focus_on_selection(&self.state);
             }
+            "view.toggle_projection" => {
+                self.viewport.camera.toggle_projection();
+            }
+            "view.camera_front" | "view.camera_back" | "view.camera_right" |
+            "view.camera_left" | "view.camera_top" | "view.camera_bottom" => {
+                let preset = match command_id {
+                    "view.camera_front" => ViewPreset::Front,
+                    "view.camera_back" => ViewPreset::Back,
+                    "view.camera_right" => ViewPreset::Right,
+                    "view.camera_left" => ViewPreset::Left,
+                    "view.camera_top" => ViewPreset::Top,
+                    _ => ViewPreset::Bottom,
+                };
+                self.viewport.camera.set_view_preset(preset);
+            }
 
             // Transform commands
             "transform.translate" => {
                 self.state.gizmo_mode = crate::tools::GizmoMode::Translate;
+                self.state.axis_constraint = crate::tools::AxisConstraint::None;
             }
             "transform.rotate" => {
                 self.state.gizmo_mode = crate::tools::GizmoMode::Rotate;
+                self.state.axis_constraint = crate::tools::AxisConstraint::None;
             }
             "transform.scale" => {
                 self.state.gizmo_mode = crate::tools::GizmoMode::Scale;
+                self.state.axis_constraint = crate::tools::AxisConstraint::None;
+            }
+            "transform.grab_x" | "transform.grab_y" | "transform.grab_z" => {
+                use crate::tools::AxisConstraint;
+                self.state.gizmo_mode = crate::tools::GizmoMode::Translate;
+                self.state.axis_constraint = match command_id {
+                    "transform.grab_x" => AxisConstraint::X,
+                    "transform.grab_y" => AxisConstraint::Y,
+                    _ => AxisConstraint::Z,
+                };
             }
             "transform.toggle_space" => {
                 self.state.use_world_space = !self.state.use_world_space;
             }
             "transform.toggle_snap" => {
-                self.state.snap_enabled = !self.state.snap_enabled;
+                self.state.snap_mode = match self.state.snap_mode {
+                    crate::tools::SnapMode::None => crate::tools::SnapMode::Grid,
+                    _ => crate::tools::SnapMode::None,
+                };
             }
 
             // Entity commands
             "entity.create" => {
                 let _ = self.state.spawn_entity_with_command("New Entity", None, true);
             }
+            "entity.drop_to_ground" => {
+                self.state.drop_selected_to_ground();
+            }
             "entity.rename" => {
                 // Focus would be handled by hierarchy panel
                 tracing::info!("Rename entity (F2)");
             }
 
+            // Tools commands
+            "tools.snap_to_ground" => {
+                self.state.snap_selected_to_ground(false);
+            }
+            "tools.snap_to_ground_aligned" => {
+                self.state.snap_selected_to_ground(true);
+            }
+            "tools.reload_shaders" => {
+                self.pending_shader_reload = true;
+            }
+
+            // UI commands
+            "ui.command_palette" => {
+                self.command_palette.toggle();
+            }
+            "ui.keyboard_shortcuts" => {
+                self.shortcut_settings.open();
+            }
+
             // Panel commands - these would ideally show/focus the panels
             "panel.viewport" | "panel.hierarchy" | "panel.inspector" |
-            "panel.asset_browser" | "panel.console" | "panel.profiler" => {
+            "panel.asset_browser" | "panel.console" | "panel.profiler" | "panel.history" |
+            "panel.material_graph" | "panel.gameplay_graph" => {
                 let panel = match command_id {
                     "panel.viewport" => PanelType::Viewport,
                     "panel.hierarchy" => PanelType::Hierarchy,
@@ -1336,6 +2228,9 @@ impl EditorInner {
                     "panel.asset_browser" => PanelType::AssetBrowser,
                     "panel.console" => PanelType::Console,
                     "panel.profiler" => PanelType::Profiler,
+                    "panel.history" => PanelType::History,
+                    "panel.material_graph" => PanelType::MaterialGraph,
+                    "panel.gameplay_graph" => PanelType::GameplayGraph,
                     _ => return,
                 };
                 self.open_panel(panel);
@@ -1478,10 +2373,11 @@ impl ApplicationHandler for EditorApp {
         let egui_ctx = egui::Context::default();
 
         // Create editor inner state
-        let editor = EditorInner::new(self.tracing_rx.take());
+        let mut editor = EditorInner::new(self.tracing_rx.take());
 
         // Apply editor theme to egui context
         editor.theme.apply(&egui_ctx);
+        editor.load_user_config();
 
         // Create egui-winit state
         let egui_state = egui_winit::State::new(
@@ -1495,10 +2391,20 @@ impl ApplicationHandler for EditorApp {
 
         // Create viewport renderer
         let initial_size = window.inner_size();
-        let viewport_renderer = ViewportRenderer::new(
+        let mut viewport_renderer = ViewportRenderer::new(
             &graphics.device,
             [initial_size.width.max(1), initial_size.height.max(1)],
         );
+        // Use a shader file found on disk over the built-in one, and send
+        // edits to it through hot reload with the assets
+        let project_dir = editor.state.project_manager.project_dir.clone();
+        if let Some(path) = ViewportRenderer::shader_source_path(project_dir.as_deref()) {
+            tracing::info!("Viewport shader: {}", path.display());
+            EditorInner::reload_shaders(&mut viewport_renderer, &graphics.device, project_dir.as_deref());
+            if let Some(dir) = path.parent() {
+                editor.asset_browser.watch_directory(dir);
+            }
+        }
 
         tracing::info!("Editor initialized successfully!");
         tracing::info!("Window size: {:?}", window.inner_size());
@@ -0,0 +1,242 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//! Asset path normalization for component references.
+//!
+//! Imported scenes often refer to the same file with different separators
+//! or casing (`Assets\Foo.glb`, `assets/foo.glb`). These helpers rewrite
+//! such references to one canonical spelling: forward slashes, and the
+//! casing actually used on disk when the file can be found under the
+//! project root.
+
+use crate::components::Component;
+use crate::state::{EntityId, SceneData};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Normalize separators: backslashes become `/`, and empty or `.`
+/// segments are dropped. Casing is left untouched.
+///
+/// Absolute paths keep their root: a leading `/`, the `//` of a UNC share,
+/// or a drive letter (`C:\Foo` -> `C:/Foo`).
+pub fn normalize_separators(path: &str) -> String {
+    let path = path.trim();
+    let is_separator = |c: char| c == '/' || c == '\\';
+    let mut chars = path.chars();
+    let root = match (chars.next(), chars.next()) {
+        (Some(a), Some(b)) if is_separator(a) && is_separator(b) => "//",
+        (Some(a), _) if is_separator(a) => "/",
+        _ => "",
+    };
+
+    let relative = path
+        .split(is_separator)
+        .filter(|segment| !segment.is_empty() && *segment != ".")
+        .collect::<Vec<_>>()
+        .join("/");
+    format!("{}{}", root, relative)
+}
+
+/// Resolve a normalized relative path to its on-disk spelling under `root`.
+///
+/// Each segment prefers an exact name match, so distinct files that only
+/// differ by case on a case-sensitive filesystem are kept apart. Returns
+/// `None` if a segment is missing or matches several entries ambiguously.
+pub fn on_disk_path(root: &Path, normalized: &str) -> Option<String> {
+    let mut dir = root.to_path_buf();
+    let mut segments = Vec::new();
+
+    for segment in normalized.split('/') {
+        let name = on_disk_name(&dir, segment)?;
+        dir.push(&name);
+        segments.push(name);
+    }
+
+    (!segments.is_empty()).then(|| segments.join("/"))
+}
+
+fn on_disk_name(dir: &Path, segment: &str) -> Option<String> {
+    let names: Vec<String> = std::fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+        .collect();
+
+    if names.iter().any(|name| name == segment) {
+        return Some(segment.to_string());
+    }
+
+    let lower = segment.to_lowercase();
+    let mut matches = names.into_iter().filter(|name| name.to_lowercase() == lower);
+    let first = matches.next()?;
+    matches.next().is_none().then_some(first)
+}
+
+/// Canonical spelling of an asset reference
+pub fn canonical_asset_path(root: &Path, path: &str) -> String {
+    let normalized = normalize_separators(path);
+    on_disk_path(root, &normalized).unwrap_or(normalized)
+}
+
+/// A component whose asset paths were rewritten
+#[derive(Debug, Clone)]
+pub struct ComponentRewrite {
+    /// Entity owning the component
+    pub entity: EntityId,
+    /// Index of the component on the entity
+    pub component_index: usize,
+    /// Component before the rewrite
+    pub previous: Component,
+    /// Component with canonical asset paths
+    pub rewritten: Component,
+}
+
+/// Result of planning an asset path deduplication pass
+#[derive(Debug, Clone, Default)]
+pub struct AssetPathDedup {
+    /// Components that need rewriting
+    pub rewrites: Vec<ComponentRewrite>,
+    /// Number of individual path references changed
+    pub unified: usize,
+}
+
+/// Plan canonical asset paths for every component in the scene.
+///
+/// `root` is the directory asset paths are relative to (the project root).
+pub fn plan_asset_path_dedup(scene: &SceneData, root: &Path) -> AssetPathDedup {
+    let mut cache: HashMap<String, String> = HashMap::new();
+    let mut plan = AssetPathDedup::default();
+
+    for (entity_id, entity) in &scene.entities {
+        for (index, component) in entity.components.iter().enumerate() {
+            let mut rewritten = component.clone();
+            let mut changed = 0;
+
+            for path in rewritten.asset_paths_mut() {
+                if path.is_empty() {
+                    continue;
+                }
+                let canonical = cache
+                    .entry(path.clone())
+                    .or_insert_with(|| canonical_asset_path(root, path));
+                if *canonical != *path {
+                    path.clone_from(canonical);
+                    changed += 1;
+                }
+            }
+
+            if changed > 0 {
+                plan.unified += changed;
+                plan.rewrites.push(ComponentRewrite {
+                    entity: *entity_id,
+                    component_index: index,
+                    previous: component.clone(),
+                    rewritten,
+                });
+            }
+        }
+    }
+
+    plan
+}
+
+/// Directory asset references are resolved against
+pub fn asset_root(project_dir: Option<&Path>) -> PathBuf {
+    project_dir
+        .map(Path::to_path_buf)
+        .or_else(|| std::env::current_dir().ok())
+        .unwrap_or_default()
+}
+
+/// Reference to the file at `path` as stored in components: relative to
+/// `root` when it lies inside it, with forward slashes either way
+pub fn asset_reference(root: &Path, path: &Path) -> String {
+    match path.strip_prefix(root) {
+        Ok(relative) => normalize_separators(&relative.to_string_lossy()),
+        Err(_) => path.to_string_lossy().replace('\\', "/"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::MeshRendererComponent;
+    use crate::state::EntityData;
+    use uuid::Uuid;
+
+    fn temp_project() -> PathBuf {
+        let root = std::env::temp_dir().join(format!("asset_paths_{}", Uuid::new_v4()));
+        std::fs::create_dir_all(root.join("Assets")).unwrap();
+        std::fs::write(root.join("Assets").join("Foo.glb"), b"mesh").unwrap();
+        root
+    }
+
+    fn mesh_entity(mesh: &str) -> EntityData {
+        EntityData {
+            components: vec![Component::MeshRenderer(MeshRendererComponent {
+                mesh: mesh.to_string(),
+                ..Default::default()
+            })],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_normalize_separators() {
+        assert_eq!(normalize_separators("Assets\\Models\\\\Foo.glb"), "Assets/Models/Foo.glb");
+        assert_eq!(normalize_separators("./assets//foo.glb "), "assets/foo.glb");
+    }
+
+    #[test]
+    fn test_asset_reference_is_project_relative() {
+        let root = Path::new("/projects/game");
+        assert_eq!(asset_reference(root, &root.join("Assets").join("Foo.glb")), "Assets/Foo.glb");
+        assert_eq!(asset_reference(root, Path::new("/elsewhere/bar.mat")), "/elsewhere/bar.mat");
+    }
+
+    #[test]
+    fn test_normalize_separators_keeps_root() {
+        assert_eq!(normalize_separators("/home/user//Assets/Foo.glb"), "/home/user/Assets/Foo.glb");
+        assert_eq!(normalize_separators("C:\\Projects\\Game\\Foo.glb"), "C:/Projects/Game/Foo.glb");
+        assert_eq!(normalize_separators("\\\\server\\share\\Foo.glb"), "//server/share/Foo.glb");
+    }
+
+    #[test]
+    fn test_variants_unify_to_on_disk_path() {
+        let root = temp_project();
+
+        assert_eq!(canonical_asset_path(&root, "Assets\\Foo.glb"), "Assets/Foo.glb");
+        assert_eq!(canonical_asset_path(&root, "assets/foo.glb"), "Assets/Foo.glb");
+        // Missing files only get separators normalized
+        assert_eq!(canonical_asset_path(&root, "assets\\Bar.glb"), "assets/Bar.glb");
+
+        let mut scene = SceneData::new();
+        scene.add_entity(mesh_entity("Assets\\Foo.glb"));
+        scene.add_entity(mesh_entity("assets/foo.glb"));
+        scene.add_entity(mesh_entity("Assets/Foo.glb"));
+
+        let plan = plan_asset_path_dedup(&scene, &root);
+        assert_eq!(plan.unified, 2);
+        assert_eq!(plan.rewrites.len(), 2);
+        for rewrite in &plan.rewrites {
+            assert_eq!(rewrite.rewritten.asset_paths()[0], "Assets/Foo.glb");
+        }
+
+        std::fs::remove_dir_all(root).ok();
+    }
+
+    #[test]
+    fn test_exact_case_match_wins() {
+        let root = temp_project();
+        let lower = root.join("Assets").join("foo.glb");
+        std::fs::write(&lower, b"other").unwrap();
+
+        // On case-insensitive filesystems the write above hits Foo.glb
+        let names: Vec<_> = std::fs::read_dir(root.join("Assets")).unwrap().collect();
+        if names.len() == 2 {
+            assert_eq!(canonical_asset_path(&root, "Assets/foo.glb"), "Assets/foo.glb");
+            assert_eq!(canonical_asset_path(&root, "Assets/Foo.glb"), "Assets/Foo.glb");
+            // Ambiguous without an exact match
+            assert_eq!(canonical_asset_path(&root, "Assets/FOO.glb"), "Assets/FOO.glb");
+        }
+
+        std::fs::remove_dir_all(root).ok();
+    }
+}
@@ -0,0 +1,383 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//! Index of the asset references held by scenes and prefabs.
+//!
+//! Components refer to assets by path, so renaming or deleting an asset
+//! file silently breaks every scene and prefab using it. The index scans
+//! the project's scene and prefab files, plus the open scene with its
+//! unsaved changes, so the asset browser can warn before such an edit and
+//! retarget the references on rename.
+//!
+//! Paths are compared after separator normalization and without regard to
+//! case, and a folder matches every reference below it.
+
+use crate::asset_paths::normalize_separators;
+use crate::components::Component;
+use crate::prefab::{Prefab, PrefabEntity};
+use crate::state::{EntityId, SceneData, SceneFormat};
+use std::path::{Path, PathBuf};
+
+/// File extensions that may hold a scene
+const SCENE_EXTENSIONS: [&str; 3] = ["scene", "ron", "json"];
+
+/// File extension of prefabs
+const PREFAB_EXTENSION: &str = "prefab";
+
+/// Where an asset reference lives
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ReferenceSource {
+    /// The scene open in the editor
+    OpenScene,
+    /// A scene file
+    Scene(PathBuf),
+    /// A prefab file
+    Prefab(PathBuf),
+}
+
+impl ReferenceSource {
+    /// Short description for warnings
+    pub fn label(&self) -> String {
+        match self {
+            Self::OpenScene => "Open scene".to_string(),
+            Self::Scene(path) => format!("Scene {}", path.display()),
+            Self::Prefab(path) => format!("Prefab {}", path.display()),
+        }
+    }
+}
+
+/// Entity holding an asset reference
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReferencingEntity {
+    /// Entity of a scene
+    Scene(EntityId),
+    /// Entity of a prefab, by its local ID
+    Prefab(u32),
+}
+
+/// One component field referring to an asset
+#[derive(Debug, Clone, PartialEq)]
+#[allow(dead_code)] // Intentionally kept for API completeness
+pub struct AssetReference {
+    /// Scene or prefab holding the reference
+    pub source: ReferenceSource,
+    /// Entity holding the reference
+    pub entity: ReferencingEntity,
+    /// Name of that entity
+    pub entity_name: String,
+    /// Asset path field, such as `mesh` or `clip`
+    pub field: &'static str,
+    /// The path as written in the field
+    pub path: String,
+}
+
+/// Scene or prefab file that couldn't be read while indexing, so its
+/// references are unknown
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnreadableFile {
+    /// Path of the file
+    pub path: PathBuf,
+    /// Why reading it failed
+    pub error: String,
+}
+
+/// Asset references of a set of scenes and prefabs
+#[derive(Debug, Clone, Default)]
+pub struct AssetDependencyIndex {
+    references: Vec<AssetReference>,
+    unreadable: Vec<UnreadableFile>,
+}
+
+impl AssetDependencyIndex {
+    /// Index every scene and prefab file under `root`, skipping hidden
+    /// directories, `target` and the directories in `exclude` (such as
+    /// build output). The file at `open_scene_path` is indexed from
+    /// `open_scene` instead, so unsaved changes count. Scenes and prefabs
+    /// that fail to parse are recorded in [`Self::unreadable`].
+    pub fn scan(root: &Path, exclude: &[PathBuf], open_scene: &SceneData, open_scene_path: Option<&Path>) -> Self {
+        let mut index = Self::default();
+        index.add_scene(ReferenceSource::OpenScene, open_scene);
+
+        let walker = walkdir::WalkDir::new(root).into_iter().filter_entry(|entry| {
+            let name = entry.file_name().to_string_lossy();
+            entry.depth() == 0
+                || !(entry.file_type().is_dir()
+                    && (name.starts_with('.') || name == "target" || exclude.iter().any(|dir| dir == entry.path())))
+        });
+
+        for entry in walker.filter_map(Result::ok).filter(|entry| entry.file_type().is_file()) {
+            let path = entry.path();
+            if open_scene_path == Some(path) {
+                continue;
+            }
+
+            let extension = path
+                .extension()
+                .and_then(|e| e.to_str())
+                .map(str::to_lowercase)
+                .unwrap_or_default();
+            if extension == PREFAB_EXTENSION {
+                match Prefab::load(path) {
+                    Ok(prefab) => index.add_prefab(path, &prefab),
+                    Err(e) => index.add_unreadable(path, e.to_string()),
+                }
+            } else if SCENE_EXTENSIONS.contains(&extension.as_str()) {
+                match load_scene(path) {
+                    Ok(Some(scene)) => index.add_scene(ReferenceSource::Scene(path.to_path_buf()), &scene),
+                    Ok(None) => {}
+                    Err(e) => index.add_unreadable(path, e),
+                }
+            }
+        }
+
+        index
+    }
+
+    /// Index the references of a scene
+    pub fn add_scene(&mut self, source: ReferenceSource, scene: &SceneData) {
+        for (id, entity) in &scene.entities {
+            self.add_components(&source, ReferencingEntity::Scene(*id), &entity.name, &entity.components);
+        }
+    }
+
+    /// Index the references of a prefab file
+    pub fn add_prefab(&mut self, path: &Path, prefab: &Prefab) {
+        let source = ReferenceSource::Prefab(path.to_path_buf());
+        let mut stack = vec![&prefab.root];
+        while let Some(entity) = stack.pop() {
+            self.add_components(
+                &source,
+                ReferencingEntity::Prefab(entity.local_id),
+                &entity.name,
+                &entity.components,
+            );
+            stack.extend(&entity.children);
+        }
+    }
+
+    fn add_unreadable(&mut self, path: &Path, error: String) {
+        tracing::warn!("Couldn't check {} for asset references: {}", path.display(), error);
+        self.unreadable.push(UnreadableFile {
+            path: path.to_path_buf(),
+            error,
+        });
+    }
+
+    fn add_components(
+        &mut self,
+        source: &ReferenceSource,
+        entity: ReferencingEntity,
+        entity_name: &str,
+        components: &[Component],
+    ) {
+        for component in components {
+            for (field, path) in component.asset_path_fields() {
+                if path.trim().is_empty() {
+                    continue;
+                }
+                self.references.push(AssetReference {
+                    source: source.clone(),
+                    entity,
+                    entity_name: entity_name.to_string(),
+                    field,
+                    path: path.to_string(),
+                });
+            }
+        }
+    }
+
+    /// References to the asset at `asset`, or to anything inside it when
+    /// it's a folder
+    pub fn find_references(&self, asset: &str) -> Vec<&AssetReference> {
+        let asset = normalize_separators(asset);
+        self.references
+            .iter()
+            .filter(|reference| retarget_path(&reference.path, &asset, &asset).is_some())
+            .collect()
+    }
+
+    /// Every indexed reference
+    #[allow(dead_code)] // Intentionally kept for API completeness
+    pub fn references(&self) -> &[AssetReference] {
+        &self.references
+    }
+
+    /// Scene and prefab files whose references couldn't be indexed
+    pub fn unreadable(&self) -> &[UnreadableFile] {
+        &self.unreadable
+    }
+}
+
+/// `reference` with `old` swapped for `new`, if it points at `old` or
+/// inside it. The part below a renamed folder is kept as written.
+pub fn retarget_path(reference: &str, old: &str, new: &str) -> Option<String> {
+    let reference = normalize_separators(reference);
+    let old = normalize_separators(old);
+    let prefix = reference.get(..old.len())?;
+    if old.is_empty() || !prefix.eq_ignore_ascii_case(&old) {
+        return None;
+    }
+
+    let rest = &reference[old.len()..];
+    if rest.is_empty() {
+        Some(normalize_separators(new))
+    } else if rest.starts_with('/') {
+        Some(format!("{}{}", normalize_separators(new), rest))
+    } else {
+        None
+    }
+}
+
+/// Point the asset path fields of `components` at `new` instead of `old`.
+/// Returns the number of fields changed.
+pub fn retarget_components<'a>(components: impl IntoIterator<Item = &'a mut Component>, old: &str, new: &str) -> usize {
+    let mut changed = 0;
+    for component in components {
+        for (_, path) in component.asset_path_fields_mut() {
+            if let Some(retargeted) = retarget_path(path, old, new) {
+                *path = retargeted;
+                changed += 1;
+            }
+        }
+    }
+    changed
+}
+
+/// Retarget the references in a scene or prefab file and save it.
+/// Returns the number of fields changed.
+pub fn retarget_file(source: &ReferenceSource, old: &str, new: &str) -> Result<usize, String> {
+    match source {
+        ReferenceSource::OpenScene => Err("The open scene is retargeted in the editor".to_string()),
+        ReferenceSource::Scene(path) => {
+            let content = std::fs::read_to_string(path).map_err(|e| format!("File read error: {}", e))?;
+            let mut file = SceneFormat::detect(path, &content).parse(&content)?;
+            let changed = retarget_components(
+                file.scene.entities.values_mut().flat_map(|entity| entity.components.iter_mut()),
+                old,
+                new,
+            );
+            if changed > 0 {
+                let text = SceneFormat::from_path(path).serialize(&file)?;
+                std::fs::write(path, text).map_err(|e| format!("File write error: {}", e))?;
+            }
+            Ok(changed)
+        }
+        ReferenceSource::Prefab(path) => {
+            let mut prefab = Prefab::load(path).map_err(|e| e.to_string())?;
+            let mut changed = 0;
+            let mut stack: Vec<&mut PrefabEntity> = vec![&mut prefab.root];
+            while let Some(entity) = stack.pop() {
+                changed += retarget_components(entity.components.iter_mut(), old, new);
+                stack.extend(entity.children.iter_mut());
+            }
+            if changed > 0 {
+                prefab.save(path).map_err(|e| e.to_string())?;
+            }
+            Ok(changed)
+        }
+    }
+}
+
+/// Scene data of a file with a scene extension, or `None` if it holds
+/// other RON or JSON data. Only files laid out as scenes report errors.
+fn load_scene(path: &Path) -> Result<Option<SceneData>, String> {
+    let content = std::fs::read_to_string(path).map_err(|e| format!("File read error: {}", e))?;
+    let format = SceneFormat::detect(path, &content);
+    match format.parse(&content) {
+        Ok(file) => Ok(Some(file.scene)),
+        Err(e) if has_scene_layout(path, format, &content) => Err(e),
+        Err(_) => Ok(None),
+    }
+}
+
+/// Whether a file is a scene, going by a `.scene` extension or a top-level
+/// `scene` (versioned file) or `entities` (legacy bare scene) field
+fn has_scene_layout(path: &Path, format: SceneFormat, content: &str) -> bool {
+    if path.extension().is_some_and(|e| e.eq_ignore_ascii_case("scene")) {
+        return true;
+    }
+    match format {
+        SceneFormat::Ron => match ron::from_str::<ron::Value>(content) {
+            Ok(ron::Value::Map(fields)) => ["scene", "entities"]
+                .into_iter()
+                .any(|field| fields.get(&ron::Value::from(field)).is_some()),
+            _ => false,
+        },
+        SceneFormat::Json => serde_json::from_str::<serde_json::Value>(content)
+            .is_ok_and(|value| value.get("scene").is_some()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::MeshRendererComponent;
+    use crate::state::{EntityData, SceneFile};
+    use uuid::Uuid;
+
+    fn mesh_entity(name: &str, mesh: &str, material: &str) -> EntityData {
+        EntityData {
+            name: name.to_string(),
+            components: vec![Component::MeshRenderer(MeshRendererComponent {
+                mesh: mesh.to_string(),
+                material: material.to_string(),
+                ..Default::default()
+            })],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_scene_file_reference_is_found() {
+        let root = std::env::temp_dir().join(format!("asset_references_{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&root).unwrap();
+        let mut scene = SceneData::new();
+        let wall = scene.add_entity(mesh_entity("Wall", "", "Assets/Textures/brick.png"));
+        scene.add_entity(mesh_entity("Floor", "Assets/floor.glb", ""));
+        let scene_path = root.join("level.ron");
+        let text = SceneFormat::Ron.serialize(&SceneFile::from_scene("level", scene)).unwrap();
+        std::fs::write(&scene_path, text).unwrap();
+
+        let index = AssetDependencyIndex::scan(&root, &[], &SceneData::new(), None);
+        let references = index.find_references("assets\\textures\\Brick.png");
+
+        assert_eq!(references.len(), 1);
+        assert_eq!(references[0].source, ReferenceSource::Scene(scene_path.clone()));
+        assert_eq!(references[0].entity, ReferencingEntity::Scene(wall));
+        assert_eq!(references[0].field, "material");
+        // A folder finds everything inside it
+        assert_eq!(index.find_references("Assets").len(), 2);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_unreadable_files_are_reported() {
+        let root = std::env::temp_dir().join(format!("asset_references_{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&root).unwrap();
+        let broken_scene = root.join("broken.ron");
+        std::fs::write(&broken_scene, "SceneFile(version: 1, name: \"Broken\", scene: (entities: {(\"x\"): (name: 5)}))")
+            .unwrap();
+        let broken_prefab = root.join("broken.prefab");
+        std::fs::write(&broken_prefab, "(name: ").unwrap();
+        // RON that isn't a scene is skipped without complaint
+        std::fs::write(root.join("settings.ron"), "(theme: \"dark\")").unwrap();
+
+        let index = AssetDependencyIndex::scan(&root, &[], &SceneData::new(), None);
+        let mut unreadable: Vec<&Path> = index.unreadable().iter().map(|file| file.path.as_path()).collect();
+        unreadable.sort();
+        assert_eq!(unreadable, vec![broken_prefab.as_path(), broken_scene.as_path()]);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_retarget_path() {
+        assert_eq!(retarget_path("Assets/a.png", "Assets/a.png", "Assets/b.png").as_deref(), Some("Assets/b.png"));
+        assert_eq!(
+            retarget_path("Assets/Old/a.png", "assets/old", "Assets/New").as_deref(),
+            Some("Assets/New/a.png")
+        );
+        // A sibling sharing a prefix is left alone
+        assert_eq!(retarget_path("Assets/a.png.bak", "Assets/a.png", "Assets/b.png"), None);
+        assert_eq!(retarget_path("Assets/Older/a.png", "Assets/Old", "Assets/New"), None);
+    }
+}
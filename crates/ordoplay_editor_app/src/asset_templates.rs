@@ -0,0 +1,187 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//! Empty assets created from the asset browser.
+//!
+//! Each template writes a minimal file that the editor can open right
+//! away. New files never overwrite existing ones: a colliding name gets a
+//! numeric suffix (`New Material 2.mat`).
+
+use crate::prefab::Prefab;
+use crate::state::{SceneFile, SceneFormat};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Base name of new folders
+const NEW_FOLDER_NAME: &str = "New Folder";
+
+/// Kind of asset that can be created empty
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AssetTemplate {
+    /// Material with default surface values
+    Material,
+    /// Scene without entities
+    Scene,
+    /// Prefab with a single empty root entity
+    Prefab,
+}
+
+impl AssetTemplate {
+    /// Every template, in menu order
+    pub const ALL: [Self; 3] = [Self::Material, Self::Scene, Self::Prefab];
+
+    /// Display name for menus
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Material => "Material",
+            Self::Scene => "Scene",
+            Self::Prefab => "Prefab",
+        }
+    }
+
+    /// File name of a new asset, before collision suffixes
+    pub fn base_name(self) -> &'static str {
+        match self {
+            Self::Material => "New Material",
+            Self::Scene => "New Scene",
+            Self::Prefab => "New Prefab",
+        }
+    }
+
+    /// File extension, without the dot
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Material => "mat",
+            Self::Scene => "ron",
+            Self::Prefab => "prefab",
+        }
+    }
+
+    /// File contents of a new asset called `name`
+    pub fn contents(self, name: &str) -> Result<String, String> {
+        match self {
+            Self::Material => MaterialFile::new(name).to_ron(),
+            Self::Scene => SceneFormat::Ron.serialize(&SceneFile::new(name)),
+            Self::Prefab => Prefab::new(name).to_ron().map_err(|e| e.to_string()),
+        }
+    }
+}
+
+/// Surface settings stored in a `.mat` file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MaterialFile {
+    /// Material name
+    pub name: String,
+    /// Shader the material renders with
+    pub shader: String,
+    /// Base color (RGBA, linear)
+    pub base_color: [f32; 4],
+    /// Base color texture path, empty for none
+    #[serde(default)]
+    pub base_color_texture: String,
+    /// Metallic factor (0-1)
+    pub metallic: f32,
+    /// Roughness factor (0-1)
+    pub roughness: f32,
+    /// Emissive color (RGB, linear)
+    pub emissive: [f32; 3],
+}
+
+impl MaterialFile {
+    /// Default opaque white material
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            shader: "standard".to_string(),
+            base_color: [1.0, 1.0, 1.0, 1.0],
+            base_color_texture: String::new(),
+            metallic: 0.0,
+            roughness: 0.5,
+            emissive: [0.0, 0.0, 0.0],
+        }
+    }
+
+    /// Serialize to RON format
+    pub fn to_ron(&self) -> Result<String, String> {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default()).map_err(|e| e.to_string())
+    }
+
+    /// Deserialize from RON format
+    #[allow(dead_code)] // Intentionally kept for API completeness
+    pub fn from_ron(s: &str) -> Result<Self, String> {
+        ron::from_str(s).map_err(|e| e.to_string())
+    }
+}
+
+/// First free path in `dir` named `base_name`, then `base_name 2`,
+/// `base_name 3`, and so on
+pub fn unique_path(dir: &Path, base_name: &str, extension: Option<&str>) -> PathBuf {
+    let file_name = |suffix: usize| {
+        let stem = if suffix < 2 {
+            base_name.to_string()
+        } else {
+            format!("{} {}", base_name, suffix)
+        };
+        match extension {
+            Some(extension) => format!("{}.{}", stem, extension),
+            None => stem,
+        }
+    };
+
+    (1..)
+        .map(|suffix| dir.join(file_name(suffix)))
+        .find(|path| !path.exists())
+        .expect("unbounded suffix range")
+}
+
+/// Create an empty folder in `dir` and return its path
+pub fn create_folder(dir: &Path) -> Result<PathBuf, String> {
+    let path = unique_path(dir, NEW_FOLDER_NAME, None);
+    std::fs::create_dir(&path).map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+    Ok(path)
+}
+
+/// Write a new asset from `template` into `dir` and return its path
+pub fn create_asset(dir: &Path, template: AssetTemplate) -> Result<PathBuf, String> {
+    let path = unique_path(dir, template.base_name(), Some(template.extension()));
+    let name = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let contents = template.contents(&name)?;
+    std::fs::write(&path, contents).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_name_collisions_get_suffix() {
+        let dir = std::env::temp_dir().join(format!("asset_templates_{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let first = create_asset(&dir, AssetTemplate::Material).unwrap();
+        let second = create_asset(&dir, AssetTemplate::Material).unwrap();
+        let folder = create_folder(&dir).unwrap();
+
+        assert_eq!(first, dir.join("New Material.mat"));
+        assert_eq!(second, dir.join("New Material 2.mat"));
+        assert_eq!(folder, dir.join("New Folder"));
+        let material = MaterialFile::from_ron(&std::fs::read_to_string(&second).unwrap()).unwrap();
+        assert_eq!(material.name, "New Material 2");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_templates_load_back() {
+        let scene = AssetTemplate::Scene.contents("Level").unwrap();
+        let file = SceneFormat::Ron.parse(&scene).unwrap();
+        assert_eq!(file.name, "Level");
+        assert!(file.scene.entities.is_empty());
+
+        let prefab = Prefab::from_ron(&AssetTemplate::Prefab.contents("Crate").unwrap()).unwrap();
+        assert_eq!(prefab.name, "Crate");
+    }
+}
@@ -4,18 +4,19 @@
 //! This module provides:
 //! - Audio engine using rodio (when "audio" feature is enabled)
 //! - Audio source playback management
-//! - 3D spatial audio
-//! - Audio mixer with volume controls
+//! - 3D spatial audio: spatial sources fade with distance from the main
+//!   camera and pan between the left and right channels
+//! - Audio mixer with named groups (Music, SFX, UI, ...) that sources route
+//!   into, each with its own volume and mute
 //!
 //! When the "audio" feature is not enabled, a stub implementation is provided
 //! that logs warnings but does not play audio.
 
 
-#[cfg(feature = "audio")]
 use crate::components::Component;
-use crate::components::AudioSourceComponent;
+use crate::components::{AudioRolloff, AudioSourceComponent};
 use crate::state::{EntityId, SceneData, Transform};
-use std::collections::HashMap;
+use indexmap::IndexMap;
 use std::path::{Path, PathBuf};
 
 /// Audio listener position (typically attached to camera)
@@ -54,68 +55,163 @@ impl AudioListener {
             up: [0.0, 1.0, 0.0],
         }
     }
+
+    /// Listener at the scene's main camera, if it has an active one
+    pub fn from_scene(scene: &SceneData) -> Option<Self> {
+        let (id, _) = scene.entities.iter().find(|(_, entity)| {
+            entity.active
+                && entity
+                    .components
+                    .iter()
+                    .any(|c| matches!(c, Component::Camera(camera) if camera.is_main))
+        })?;
+        let transform = scene.world_transform(*id)?;
+
+        Some(Self {
+            position: transform.position,
+            forward: transform.rotate([0.0, 0.0, -1.0]),
+            up: transform.rotate([0.0, 1.0, 0.0]),
+        })
+    }
+
+    /// Unit vector pointing out of the listener's right ear
+    pub fn right(&self) -> [f32; 3] {
+        let [fx, fy, fz] = self.forward;
+        let [ux, uy, uz] = self.up;
+        normalize([fy * uz - fz * uy, fz * ux - fx * uz, fx * uy - fy * ux])
+    }
 }
 
-/// Mixer channel for volume control
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub enum AudioChannel {
-    Master,
-    Music,
-    Sfx,
-    Voice,
-    Ambient,
+fn normalize(v: [f32; 3]) -> [f32; 3] {
+    let length = (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt();
+    if length > f32::EPSILON {
+        v.map(|c| c / length)
+    } else {
+        [0.0; 3]
+    }
 }
 
-/// Audio mixer settings
-#[allow(dead_code)] // Intentionally kept for API completeness
+/// Volume of a spatial source at `distance` from the listener.
+///
+/// Full volume within `min_distance`, silent from `max_distance` on, and
+/// shaped by `rolloff` in between.
+pub fn distance_attenuation(rolloff: AudioRolloff, distance: f32, min_distance: f32, max_distance: f32) -> f32 {
+    if distance <= min_distance {
+        1.0
+    } else if distance >= max_distance {
+        0.0
+    } else {
+        match rolloff {
+            AudioRolloff::Linear => 1.0 - (distance - min_distance) / (max_distance - min_distance),
+            AudioRolloff::Logarithmic => min_distance.max(f32::EPSILON) / distance,
+        }
+    }
+}
+
+/// Left and right channel gains of a source heard by `listener`.
+///
+/// Non-spatial sources play at full volume on both channels. Spatial ones
+/// are attenuated by distance and panned by where they sit relative to the
+/// listener's right ear; a source straight ahead keeps both channels at the
+/// attenuated volume.
+#[cfg_attr(not(feature = "audio"), allow(dead_code))] // Only the rodio engine plays sound
+pub fn spatial_gains(listener: &AudioListener, position: [f32; 3], audio: &AudioSourceComponent) -> [f32; 2] {
+    if !audio.spatial {
+        return [1.0, 1.0];
+    }
+
+    let offset = [
+        position[0] - listener.position[0],
+        position[1] - listener.position[1],
+        position[2] - listener.position[2],
+    ];
+    let distance = (offset[0] * offset[0] + offset[1] * offset[1] + offset[2] * offset[2]).sqrt();
+    let volume = distance_attenuation(audio.rolloff, distance, audio.min_distance, audio.max_distance);
+
+    let direction = normalize(offset);
+    let right = listener.right();
+    let pan = direction[0] * right[0] + direction[1] * right[1] + direction[2] * right[2];
+    [volume * (1.0 - pan).min(1.0), volume * (1.0 + pan).min(1.0)]
+}
+
+/// Mixer group every other group is scaled by
+pub const MASTER_GROUP: &str = "Master";
+
+/// Groups a new mixer starts with, after [`MASTER_GROUP`]
+pub const DEFAULT_MIXER_GROUPS: [&str; 5] = ["Music", "SFX", "UI", "Voice", "Ambient"];
+
+/// Volume and mute state of one mixer group
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MixerGroup {
+    /// Volume (0.0 to 1.0)
+    pub volume: f32,
+    /// Whether the group is silenced
+    pub muted: bool,
+}
+
+impl Default for MixerGroup {
+    fn default() -> Self {
+        Self { volume: 1.0, muted: false }
+    }
+}
+
+impl MixerGroup {
+    /// Volume after muting
+    pub fn gain(&self) -> f32 {
+        if self.muted {
+            0.0
+        } else {
+            self.volume
+        }
+    }
+}
+
+/// Audio mixer settings.
+///
+/// Sources name the group they route into. A group that isn't listed
+/// plays at full volume, scaled only by the master group.
 #[derive(Debug, Clone)]
 pub struct AudioMixer {
-    /// Channel volumes (0.0 to 1.0)
-    pub volumes: HashMap<AudioChannel, f32>,
-    /// Muted channels
-    pub muted: HashMap<AudioChannel, bool>,
+    /// Groups by name, in display order
+    pub groups: IndexMap<String, MixerGroup>,
 }
 
 impl Default for AudioMixer {
     fn default() -> Self {
-        let mut volumes = HashMap::new();
-        volumes.insert(AudioChannel::Master, 1.0);
-        volumes.insert(AudioChannel::Music, 1.0);
-        volumes.insert(AudioChannel::Sfx, 1.0);
-        volumes.insert(AudioChannel::Voice, 1.0);
-        volumes.insert(AudioChannel::Ambient, 1.0);
+        let groups = std::iter::once(MASTER_GROUP)
+            .chain(DEFAULT_MIXER_GROUPS)
+            .map(|name| (name.to_string(), MixerGroup::default()))
+            .collect();
 
-        let muted = HashMap::new();
-
-        Self { volumes, muted }
+        Self { groups }
     }
 }
 
 #[allow(dead_code)] // Intentionally kept for API completeness
 impl AudioMixer {
-    pub fn get_volume(&self, channel: AudioChannel) -> f32 {
-        let master = *self.volumes.get(&AudioChannel::Master).unwrap_or(&1.0);
-        let channel_vol = *self.volumes.get(&channel).unwrap_or(&1.0);
-        let is_muted = *self.muted.get(&channel).unwrap_or(&false);
-
-        if is_muted || *self.muted.get(&AudioChannel::Master).unwrap_or(&false) {
-            0.0
+    /// Final gain of `group`, including the master group
+    pub fn get_volume(&self, group: &str) -> f32 {
+        let gain = |name: &str| self.groups.get(name).map_or(1.0, MixerGroup::gain);
+        if group == MASTER_GROUP {
+            gain(MASTER_GROUP)
         } else {
-            master * channel_vol
+            gain(MASTER_GROUP) * gain(group)
         }
     }
 
-    pub fn set_volume(&mut self, channel: AudioChannel, volume: f32) {
-        self.volumes.insert(channel, volume.clamp(0.0, 1.0));
+    /// Set a group's volume, adding the group if it's new
+    pub fn set_volume(&mut self, group: &str, volume: f32) {
+        self.groups.entry(group.to_string()).or_default().volume = volume.clamp(0.0, 1.0);
     }
 
-    pub fn set_muted(&mut self, channel: AudioChannel, muted: bool) {
-        self.muted.insert(channel, muted);
+    /// Mute or unmute a group, adding the group if it's new
+    pub fn set_muted(&mut self, group: &str, muted: bool) {
+        self.groups.entry(group.to_string()).or_default().muted = muted;
     }
 
-    pub fn toggle_mute(&mut self, channel: AudioChannel) {
-        let current = *self.muted.get(&channel).unwrap_or(&false);
-        self.muted.insert(channel, !current);
+    pub fn toggle_mute(&mut self, group: &str) {
+        let entry = self.groups.entry(group.to_string()).or_default();
+        entry.muted = !entry.muted;
     }
 }
 
@@ -140,21 +236,136 @@ impl Default for AudioListenerComponent {
 #[cfg(feature = "audio")]
 mod engine {
     use super::*;
-    use rodio::{Decoder, OutputStream, OutputStreamHandle, Sink};
+    use rodio::{Decoder, OutputStream, OutputStreamHandle, Sample, Sink, Source};
+    use std::collections::HashMap;
     use std::fs::File;
     use std::io::BufReader;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    /// Left and right gains shared between the engine and a playing source
+    #[derive(Debug)]
+    struct StereoGains([AtomicU32; 2]);
+
+    impl StereoGains {
+        fn new(gains: [f32; 2]) -> Self {
+            Self(gains.map(|gain| AtomicU32::new(gain.to_bits())))
+        }
+
+        fn set(&self, gains: [f32; 2]) {
+            for (slot, gain) in self.0.iter().zip(gains) {
+                slot.store(gain.to_bits(), Ordering::Relaxed);
+            }
+        }
+
+        fn get(&self, channel: usize) -> f32 {
+            f32::from_bits(self.0[channel].load(Ordering::Relaxed))
+        }
+    }
+
+    /// Source that scales the left and right channels of its input by the
+    /// current [`StereoGains`]. Mono input is spread to stereo so it can be
+    /// panned; channels past the second get the average gain.
+    struct PannedSource<S: Source>
+    where
+        S::Item: Sample,
+    {
+        input: S,
+        gains: Arc<StereoGains>,
+        /// Right-channel copy of the last mono sample
+        pending: Option<S::Item>,
+        /// Channel of the next interleaved input sample
+        channel: u16,
+    }
+
+    impl<S: Source> PannedSource<S>
+    where
+        S::Item: Sample,
+    {
+        fn new(input: S, gains: Arc<StereoGains>) -> Self {
+            Self {
+                input,
+                gains,
+                pending: None,
+                channel: 0,
+            }
+        }
+    }
+
+    impl<S: Source> Iterator for PannedSource<S>
+    where
+        S::Item: Sample,
+    {
+        type Item = S::Item;
+
+        fn next(&mut self) -> Option<S::Item> {
+            if let Some(sample) = self.pending.take() {
+                return Some(sample.amplify(self.gains.get(1)));
+            }
+
+            let channels = self.input.channels();
+            let sample = self.input.next()?;
+            if channels == 1 {
+                self.pending = Some(sample);
+                return Some(sample.amplify(self.gains.get(0)));
+            }
+
+            let channel = self.channel;
+            self.channel = (channel + 1) % channels.max(1);
+            let gain = match channel {
+                0 | 1 => self.gains.get(usize::from(channel)),
+                _ => (self.gains.get(0) + self.gains.get(1)) * 0.5,
+            };
+            Some(sample.amplify(gain))
+        }
+    }
+
+    impl<S: Source> Source for PannedSource<S>
+    where
+        S::Item: Sample,
+    {
+        fn current_frame_len(&self) -> Option<usize> {
+            let len = self.input.current_frame_len()?;
+            Some(if self.input.channels() == 1 { len * 2 } else { len })
+        }
+
+        fn channels(&self) -> u16 {
+            self.input.channels().max(2)
+        }
+
+        fn sample_rate(&self) -> u32 {
+            self.input.sample_rate()
+        }
+
+        fn total_duration(&self) -> Option<Duration> {
+            self.input.total_duration()
+        }
+    }
 
     /// Playing audio source instance
     struct PlayingSource {
         entity_id: EntityId,
         sink: Sink,
-        channel: AudioChannel,
+        /// Mixer group the source routes into
+        group: String,
         base_volume: f32,
-        is_spatial: bool,
+        /// Panning gains, for spatial sources
+        gains: Option<Arc<StereoGains>>,
         loop_audio: bool,
         clip_path: PathBuf,
     }
 
+    impl PlayingSource {
+        /// Queue `source` on the sink, panned if this source is spatial
+        fn append(&self, source: Decoder<BufReader<File>>) {
+            match &self.gains {
+                Some(gains) => self.sink.append(PannedSource::new(source, Arc::clone(gains))),
+                None => self.sink.append(source),
+            }
+        }
+    }
+
     /// Audio engine managing all audio playback
     pub struct AudioEngine {
         /// Output stream (must be kept alive)
@@ -251,12 +462,17 @@ mod engine {
                 return;
             }
 
+            if let Some(listener) = AudioListener::from_scene(scene) {
+                self.listener = listener;
+            }
+
             // Find and start all audio sources with play_on_awake
             for (entity_id, entity_data) in &scene.entities {
                 for component in &entity_data.components {
                     if let Component::AudioSource(audio) = component {
                         if audio.play_on_awake && !audio.clip.is_empty() {
-                            self.play_source(*entity_id, audio, &entity_data.transform);
+                            let position = scene.world_position(*entity_id).unwrap_or(entity_data.transform.position);
+                            self.play_source(*entity_id, audio, position);
                         }
                     }
                 }
@@ -265,8 +481,8 @@ mod engine {
             tracing::info!("Audio initialized: {} sources playing", self.playing_sources.len());
         }
 
-        /// Play an audio source
-        pub fn play_source(&mut self, entity_id: EntityId, audio: &AudioSourceComponent, transform: &Transform) {
+        /// Play an audio source whose entity is at the world `position`
+        pub fn play_source(&mut self, entity_id: EntityId, audio: &AudioSourceComponent, position: [f32; 3]) {
             if !self.initialized {
                 return;
             }
@@ -314,33 +530,27 @@ mod engine {
                 }
             };
 
-            // Calculate volume with spatial attenuation if needed
+            // Spatial attenuation and panning go through the source's gains,
+            // the rest through the sink volume
             let base_volume = audio.volume * audio.pitch.abs();
-            let channel = AudioChannel::Sfx; // Default to SFX channel
-            let spatial_volume = if audio.spatial {
-                self.calculate_spatial_volume(transform, audio.min_distance, audio.max_distance)
-            } else {
-                1.0
-            };
-
-            let final_volume = base_volume * spatial_volume * self.mixer.get_volume(channel);
+            let gains = audio.spatial.then(|| {
+                Arc::new(StereoGains::new(spatial_gains(&self.listener, position, audio)))
+            });
 
-            sink.set_volume(final_volume);
+            sink.set_volume(base_volume * self.mixer.get_volume(&audio.group));
             sink.set_speed(audio.pitch.abs());
 
-            // Append source
-            sink.append(source);
-
-            // Store the playing source
-            self.playing_sources.insert(entity_id, PlayingSource {
+            let playing = PlayingSource {
                 entity_id,
                 sink,
-                channel,
+                group: audio.group.clone(),
                 base_volume,
-                is_spatial: audio.spatial,
+                gains,
                 loop_audio: audio.loop_audio,
                 clip_path,
-            });
+            };
+            playing.append(source);
+            self.playing_sources.insert(entity_id, playing);
 
             tracing::debug!("Started playing audio for entity {:?}", entity_id);
         }
@@ -400,7 +610,7 @@ mod engine {
                         // Reload and play again for looping
                         if let Ok(file) = File::open(&source.clip_path) {
                             if let Ok(new_source) = Decoder::new(BufReader::new(file)) {
-                                source.sink.append(new_source);
+                                source.append(new_source);
                                 return true;
                             }
                         }
@@ -411,48 +621,46 @@ mod engine {
                 }
             });
 
-            // Update spatial audio volumes
-            for (entity_id, source) in &mut self.playing_sources {
-                if source.is_spatial {
-                    if let Some(entity) = scene.get(entity_id) {
-                        // Find the audio source component to get min/max distance
-                        let (min_dist, max_dist) = entity.components.iter()
-                            .find_map(|c| {
-                                if let Component::AudioSource(audio) = c {
-                                    Some((audio.min_distance, audio.max_distance))
-                                } else {
-                                    None
-                                }
-                            })
-                            .unwrap_or((1.0, 50.0));
-
-                        let spatial_volume = self.calculate_spatial_volume(
-                            &entity.transform,
-                            min_dist,
-                            max_dist,
-                        );
-
-                        let final_volume = source.base_volume * spatial_volume * self.mixer.get_volume(source.channel);
-                        source.sink.set_volume(final_volume);
-                    }
-                }
+            // Refresh mixer gains, follow the main camera, then re-pan
+            // spatial sources around it
+            if let Some(listener) = AudioListener::from_scene(scene) {
+                self.listener = listener;
+            }
+            self.apply_mixer();
+            for (entity_id, source) in &self.playing_sources {
+                let Some(gains) = &source.gains else {
+                    continue;
+                };
+                let Some(entity) = scene.get(entity_id) else {
+                    continue;
+                };
+                let Some(audio) = entity.components.iter().find_map(|c| match c {
+                    Component::AudioSource(audio) => Some(audio),
+                    _ => None,
+                }) else {
+                    continue;
+                };
+                let position = scene.world_position(*entity_id).unwrap_or(entity.transform.position);
+                gains.set(spatial_gains(&self.listener, position, audio));
             }
         }
 
-        /// Calculate spatial audio volume based on distance
-        fn calculate_spatial_volume(&self, source_transform: &Transform, min_distance: f32, max_distance: f32) -> f32 {
-            let dx = source_transform.position[0] - self.listener.position[0];
-            let dy = source_transform.position[1] - self.listener.position[1];
-            let dz = source_transform.position[2] - self.listener.position[2];
-            let distance = (dx * dx + dy * dy + dz * dz).sqrt();
-
-            if distance <= min_distance {
-                1.0
-            } else if distance >= max_distance {
-                0.0
-            } else {
-                // Linear falloff between min and max distance
-                1.0 - (distance - min_distance) / (max_distance - min_distance)
+        /// Set a mixer group's volume, applied to its playing sources at once
+        pub fn set_group_volume(&mut self, group: &str, volume: f32) {
+            self.mixer.set_volume(group, volume);
+            self.apply_mixer();
+        }
+
+        /// Mute or unmute a mixer group, applied to its playing sources at once
+        pub fn mute_group(&mut self, group: &str, muted: bool) {
+            self.mixer.set_muted(group, muted);
+            self.apply_mixer();
+        }
+
+        /// Push the mixer's gains to every playing source
+        pub fn apply_mixer(&mut self) {
+            for source in self.playing_sources.values() {
+                source.sink.set_volume(source.base_volume * self.mixer.get_volume(&source.group));
             }
         }
 
@@ -511,7 +719,7 @@ mod engine {
                 Err(_) => return false,
             };
 
-            sink.set_volume(self.mixer.get_volume(AudioChannel::Master));
+            sink.set_volume(self.mixer.get_volume(MASTER_GROUP));
             sink.append(source);
             sink.detach(); // Let it play independently
 
@@ -581,8 +789,8 @@ mod engine {
             self.warn_once();
         }
 
-        /// Play an audio source
-        pub fn play_source(&mut self, _entity_id: EntityId, _audio: &AudioSourceComponent, _transform: &Transform) {
+        /// Play an audio source whose entity is at the world `position`
+        pub fn play_source(&mut self, _entity_id: EntityId, _audio: &AudioSourceComponent, _position: [f32; 3]) {
             self.warn_once();
         }
 
@@ -607,6 +815,19 @@ mod engine {
         /// Update audio system (call each frame)
         pub fn update(&mut self, _scene: &SceneData) {}
 
+        /// Set a mixer group's volume
+        pub fn set_group_volume(&mut self, group: &str, volume: f32) {
+            self.mixer.set_volume(group, volume);
+        }
+
+        /// Mute or unmute a mixer group
+        pub fn mute_group(&mut self, group: &str, muted: bool) {
+            self.mixer.set_muted(group, muted);
+        }
+
+        /// Push the mixer's gains to every playing source
+        pub fn apply_mixer(&mut self) {}
+
         /// Set the audio listener position
         pub fn set_listener(&mut self, listener: AudioListener) {
             self.listener = listener;
@@ -643,3 +864,64 @@ mod engine {
 // Re-export AudioEngine from the appropriate module
 #[allow(unused_imports)]
 pub use engine::AudioEngine;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::CameraComponent;
+    use crate::state::EntityData;
+
+    #[test]
+    fn test_source_to_the_right_pans_right() {
+        let mut scene = SceneData::new();
+        scene.add_entity(EntityData {
+            transform: Transform {
+                position: [0.0, 0.0, 10.0],
+                ..Default::default()
+            },
+            components: vec![Component::Camera(CameraComponent {
+                is_main: true,
+                ..Default::default()
+            })],
+            ..Default::default()
+        });
+        let listener = AudioListener::from_scene(&scene).unwrap();
+        let audio = AudioSourceComponent::default();
+
+        let [left, right] = spatial_gains(&listener, [5.0, 0.0, 10.0], &audio);
+        assert!(right > left);
+        assert!(right > 0.0 && right < 1.0);
+
+        // Straight ahead stays centered, non-spatial stays at full volume
+        let [left, right] = spatial_gains(&listener, [0.0, 0.0, 5.0], &audio);
+        assert!((left - right).abs() < 1e-5);
+        let flat = AudioSourceComponent { spatial: false, ..Default::default() };
+        assert_eq!(spatial_gains(&listener, [5.0, 0.0, 10.0], &flat), [1.0, 1.0]);
+    }
+
+    #[test]
+    fn test_mixer_group_gain() {
+        let mut mixer = AudioMixer::default();
+        mixer.set_volume("Music", 0.5);
+        mixer.set_volume(MASTER_GROUP, 0.5);
+        assert!((mixer.get_volume("Music") - 0.25).abs() < 1e-5);
+        assert!((mixer.get_volume("SFX") - 0.5).abs() < 1e-5);
+        // Unlisted groups only follow the master
+        assert!((mixer.get_volume("Footsteps") - 0.5).abs() < 1e-5);
+
+        mixer.set_muted("Music", true);
+        assert_eq!(mixer.get_volume("Music"), 0.0);
+        mixer.set_muted(MASTER_GROUP, true);
+        assert_eq!(mixer.get_volume("SFX"), 0.0);
+    }
+
+    #[test]
+    fn test_distance_attenuation_rolloff() {
+        for rolloff in [AudioRolloff::Linear, AudioRolloff::Logarithmic] {
+            assert_eq!(distance_attenuation(rolloff, 0.5, 1.0, 50.0), 1.0);
+            assert_eq!(distance_attenuation(rolloff, 50.0, 1.0, 50.0), 0.0);
+        }
+        assert!((distance_attenuation(AudioRolloff::Linear, 25.5, 1.0, 50.0) - 0.5).abs() < 1e-5);
+        assert!((distance_attenuation(AudioRolloff::Logarithmic, 2.0, 1.0, 50.0) - 0.5).abs() < 1e-5);
+    }
+}
@@ -4,28 +4,35 @@
 //! This module handles:
 //! - Exporting scenes to runtime format
 //! - Asset processing and compression
-//! - Platform-specific build generation
-//! - Build progress reporting
+//! - Platform-specific build generation, one output directory per target
+//!   platform, with several targets built back to back
+//! - Compiling the project's player crate with `cargo build --target` for
+//!   each platform's triple and copying its executables into the output
+//! - Build progress reporting: builds run on a worker thread that sends
+//!   progress over a channel the editor polls each frame, and can be
+//!   cancelled between files
 
 use crate::project::{BuildConfiguration, ProjectSettings, TargetPlatform, TextureCompression};
+use std::io::Read;
 use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
 use std::sync::Arc;
-use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::thread::JoinHandle;
 
 /// Build progress reporting
 #[allow(dead_code)] // Intentionally kept for API completeness
 #[derive(Debug, Clone)]
 pub struct BuildProgress {
+    /// Target being built
+    pub target: Option<TargetPlatform>,
     /// Current step description
     pub step: String,
-    /// Current progress (0-100)
-    pub progress: u32,
-    /// Total steps
-    pub total_steps: u32,
-    /// Current step number
-    pub current_step: u32,
-    /// Whether build is complete
-    pub complete: bool,
+    /// Detail within the step, such as the file being processed
+    pub message: String,
+    /// Progress of the current target (0.0 to 1.0)
+    pub fraction: f32,
     /// Error message if any
     pub error: Option<String>,
 }
@@ -33,46 +40,73 @@ pub struct BuildProgress {
 impl Default for BuildProgress {
     fn default() -> Self {
         Self {
+            target: None,
             step: "Preparing...".to_string(),
-            progress: 0,
-            total_steps: 1,
-            current_step: 0,
-            complete: false,
+            message: String::new(),
+            fraction: 0.0,
             error: None,
         }
     }
 }
 
-/// Shared build state for async builds
+/// Message from a build worker to the [`BuildManager`]
+#[derive(Debug)]
+pub enum BuildMessage {
+    /// Progress changed
+    Progress(BuildProgress),
+    /// Every queued target finished, failed or was cancelled
+    Finished(Vec<(TargetPlatform, BuildResult)>),
+}
+
+/// Build state shared between the editor and a build worker
 pub struct BuildState {
-    pub progress: AtomicU32,
-    pub cancelled: AtomicBool,
-    pub step: parking_lot::Mutex<String>,
-    pub error: parking_lot::Mutex<Option<String>>,
-    pub complete: AtomicBool,
+    cancelled: AtomicBool,
+    progress: parking_lot::Mutex<BuildProgress>,
+    sender: Sender<BuildMessage>,
 }
 
+#[allow(dead_code)] // Intentionally kept for API completeness
 impl BuildState {
-    pub fn new() -> Self {
+    /// Build state reporting to `sender`
+    pub fn new(sender: Sender<BuildMessage>) -> Self {
         Self {
-            progress: AtomicU32::new(0),
             cancelled: AtomicBool::new(false),
-            step: parking_lot::Mutex::new("Initializing...".to_string()),
-            error: parking_lot::Mutex::new(None),
-            complete: AtomicBool::new(false),
+            progress: parking_lot::Mutex::new(BuildProgress::default()),
+            sender,
         }
     }
 
+    /// Update the progress and send it. The editor may have stopped
+    /// listening, so a closed channel is ignored.
+    fn update(&self, change: impl FnOnce(&mut BuildProgress)) {
+        let mut progress = self.progress.lock();
+        change(&mut progress);
+        let _ = self.sender.send(BuildMessage::Progress(progress.clone()));
+    }
+
+    /// Start reporting for `target`
+    pub fn set_target(&self, target: TargetPlatform) {
+        self.update(|progress| *progress = BuildProgress { target: Some(target), ..BuildProgress::default() });
+    }
+
     pub fn set_step(&self, step: impl Into<String>) {
-        *self.step.lock() = step.into();
+        self.update(|progress| {
+            progress.step = step.into();
+            progress.message.clear();
+        });
+    }
+
+    pub fn set_message(&self, message: impl Into<String>) {
+        self.update(|progress| progress.message = message.into());
     }
 
+    /// Set progress as a percentage (0-100)
     pub fn set_progress(&self, progress: u32) {
-        self.progress.store(progress.min(100), Ordering::Relaxed);
+        self.update(|p| p.fraction = progress.min(100) as f32 / 100.0);
     }
 
     pub fn set_error(&self, error: impl Into<String>) {
-        *self.error.lock() = Some(error.into());
+        self.update(|progress| progress.error = Some(error.into()));
     }
 
     pub fn is_cancelled(&self) -> bool {
@@ -84,30 +118,16 @@ impl BuildState {
     }
 
     pub fn mark_complete(&self) {
-        self.complete.store(true, Ordering::Relaxed);
-        self.progress.store(100, Ordering::Relaxed);
+        self.update(|progress| progress.fraction = 1.0);
     }
 
     pub fn get_progress(&self) -> BuildProgress {
-        BuildProgress {
-            step: self.step.lock().clone(),
-            progress: self.progress.load(Ordering::Relaxed),
-            total_steps: 0,
-            current_step: 0,
-            complete: self.complete.load(Ordering::Relaxed),
-            error: self.error.lock().clone(),
-        }
-    }
-}
-
-impl Default for BuildState {
-    fn default() -> Self {
-        Self::new()
+        self.progress.lock().clone()
     }
 }
 
 /// Build result
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum BuildResult {
     Success {
         output_dir: PathBuf,
@@ -139,10 +159,15 @@ pub struct BuildOptions {
     pub include_debug_symbols: bool,
 }
 
+#[allow(dead_code)] // Intentionally kept for API completeness
 impl BuildOptions {
     /// Create build options from project settings
     pub fn from_settings(settings: &ProjectSettings, project_dir: &Path) -> Self {
-        let platform = settings.target_platform;
+        Self::for_target(settings, project_dir, settings.target_platform)
+    }
+
+    /// Create build options for `platform`, with that platform's settings
+    pub fn for_target(settings: &ProjectSettings, project_dir: &Path, platform: TargetPlatform) -> Self {
         let platform_settings = settings.get_platform_settings(platform);
 
         Self {
@@ -157,20 +182,43 @@ impl BuildOptions {
     }
 }
 
+/// Executables reported by `cargo build --message-format=json`
+fn built_executables(messages: &str) -> Vec<PathBuf> {
+    messages
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .filter(|message| message["reason"] == "compiler-artifact")
+        .filter_map(|message| message["executable"].as_str().map(PathBuf::from))
+        .collect()
+}
+
+/// The last `count` lines of `text`
+fn last_lines(text: &str, count: usize) -> String {
+    let lines: Vec<&str> = text.lines().collect();
+    lines[lines.len().saturating_sub(count)..].join("\n")
+}
+
 /// Build system
 pub struct BuildSystem;
 
 impl BuildSystem {
-    /// Start a build (synchronous, for simplicity)
+    /// Start a build for `target` (synchronous, for simplicity)
     pub fn build(
         settings: &ProjectSettings,
         project_dir: &Path,
+        target: TargetPlatform,
         state: &BuildState,
     ) -> BuildResult {
         let start_time = std::time::Instant::now();
-        let options = BuildOptions::from_settings(settings, project_dir);
+        let options = BuildOptions::for_target(settings, project_dir, target);
 
-        tracing::info!("Starting build for {:?} {:?}", options.platform, options.configuration);
+        tracing::info!(
+            "Starting {:?} build for {} ({}) into {}",
+            options.configuration,
+            target.display_name(),
+            target.target_triple(),
+            options.output_dir.display()
+        );
         state.set_step("Preparing build...");
         state.set_progress(0);
 
@@ -189,7 +237,7 @@ impl BuildSystem {
         }
 
         if state.is_cancelled() {
-            return BuildResult::Cancelled;
+            return Self::cancelled(&options);
         }
 
         // Process scenes
@@ -198,7 +246,7 @@ impl BuildSystem {
 
         let scenes_processed = Self::process_scenes(settings, project_dir, &options, state);
         if state.is_cancelled() {
-            return BuildResult::Cancelled;
+            return Self::cancelled(&options);
         }
 
         // Process assets
@@ -207,7 +255,7 @@ impl BuildSystem {
 
         let assets_processed = Self::process_assets(project_dir, &options, state);
         if state.is_cancelled() {
-            return BuildResult::Cancelled;
+            return Self::cancelled(&options);
         }
 
         // Generate runtime config
@@ -219,19 +267,34 @@ impl BuildSystem {
             return BuildResult::Failed(format!("Failed to generate runtime config: {}", e));
         }
 
+        // Compile the player
+        if let Some(player_crate) = &settings.player_crate {
+            state.set_step("Compiling player...");
+            state.set_progress(85);
+
+            if let Err(e) = Self::compile_player(&project_dir.join(player_crate), &options, state) {
+                if state.is_cancelled() {
+                    return Self::cancelled(&options);
+                }
+                state.set_error(e.clone());
+                return BuildResult::Failed(e);
+            }
+        }
+
         // Finalize
         state.set_step("Finalizing build...");
         state.set_progress(95);
 
         if state.is_cancelled() {
-            return BuildResult::Cancelled;
+            return Self::cancelled(&options);
         }
 
         state.mark_complete();
         let build_time = start_time.elapsed().as_secs_f64();
 
         tracing::info!(
-            "Build completed in {:.2}s: {} scenes, {} assets",
+            "{} build completed in {:.2}s: {} scenes, {} assets",
+            target.display_name(),
             build_time,
             scenes_processed,
             assets_processed
@@ -245,6 +308,38 @@ impl BuildSystem {
         }
     }
 
+    /// Remove the partial output of a cancelled build
+    fn cancelled(options: &BuildOptions) -> BuildResult {
+        tracing::info!("Build cancelled, removing {}", options.output_dir.display());
+        if let Err(e) = std::fs::remove_dir_all(&options.output_dir) {
+            tracing::warn!("Failed to remove partial build output: {}", e);
+        }
+        BuildResult::Cancelled
+    }
+
+    /// Build `targets` one after another, stopping at a cancel
+    pub fn build_targets(
+        settings: &ProjectSettings,
+        project_dir: &Path,
+        targets: &[TargetPlatform],
+        state: &BuildState,
+    ) -> Vec<(TargetPlatform, BuildResult)> {
+        let mut results = Vec::new();
+        for &target in targets {
+            state.set_target(target);
+            let result = Self::build(settings, project_dir, target, state);
+            let cancelled = matches!(result, BuildResult::Cancelled);
+            if let BuildResult::Failed(error) = &result {
+                tracing::error!("{} build failed: {}", target.display_name(), error);
+            }
+            results.push((target, result));
+            if cancelled {
+                break;
+            }
+        }
+        results
+    }
+
     fn process_scenes(
         settings: &ProjectSettings,
         project_dir: &Path,
@@ -268,7 +363,7 @@ impl BuildSystem {
 
             let progress = 10 + ((i as u32 * 30) / total.max(1) as u32);
             state.set_progress(progress);
-            state.set_step(format!("Processing scene: {}", scene_entry.path.display()));
+            state.set_message(scene_entry.path.display().to_string());
 
             let source_path = project_dir.join(&scene_entry.path);
             if !source_path.exists() {
@@ -325,6 +420,7 @@ impl BuildSystem {
 
             let entry_path = entry.path();
             let rel_path = entry_path.strip_prefix(&assets_source).unwrap_or(entry_path);
+            state.set_message(rel_path.display().to_string());
             let dest_path = assets_dest.join(rel_path);
 
             // Create parent directories
@@ -358,6 +454,96 @@ impl BuildSystem {
         processed
     }
 
+    /// `cargo build` of the player crate at `manifest` for the options'
+    /// target triple. Artifacts go to a `target` directory next to the
+    /// platform output directories, so cleaning an output keeps them.
+    fn cargo_command(manifest: &Path, options: &BuildOptions) -> Command {
+        let cargo = std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+        let target_dir = options.output_dir.parent().unwrap_or(&options.output_dir).join("target");
+
+        let mut command = Command::new(cargo);
+        command
+            .arg("build")
+            .arg("--manifest-path")
+            .arg(manifest)
+            .arg("--target")
+            .arg(options.platform.target_triple())
+            .arg("--target-dir")
+            .arg(target_dir)
+            .arg("--message-format=json-render-diagnostics");
+        if options.configuration == BuildConfiguration::Release {
+            command.arg("--release");
+        }
+        command
+    }
+
+    /// Compile the player crate in `crate_dir` and copy its executables
+    /// into the output directory. Errors carry the end of cargo's output.
+    fn compile_player(crate_dir: &Path, options: &BuildOptions, state: &BuildState) -> Result<(), String> {
+        let manifest = crate_dir.join("Cargo.toml");
+        if !manifest.is_file() {
+            return Err(format!("Player crate not found: {} does not exist", manifest.display()));
+        }
+
+        let triple = options.platform.target_triple();
+        let mut command = Self::cargo_command(&manifest, options);
+        tracing::info!("Compiling player for {}: {:?}", triple, command);
+        state.set_message(format!("cargo build --target {}", triple));
+
+        let mut child = command
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("Failed to run cargo: {}", e))?;
+
+        // Drain both pipes on their own threads so cargo never blocks on a full pipe
+        let read = |pipe: Option<Box<dyn Read + Send>>| {
+            std::thread::spawn(move || {
+                let mut text = String::new();
+                if let Some(mut pipe) = pipe {
+                    let _ = pipe.read_to_string(&mut text);
+                }
+                text
+            })
+        };
+        let stdout = read(child.stdout.take().map(|p| Box::new(p) as Box<dyn Read + Send>));
+        let stderr = read(child.stderr.take().map(|p| Box::new(p) as Box<dyn Read + Send>));
+
+        let status = loop {
+            if state.is_cancelled() {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err("Build cancelled".to_string());
+            }
+            match child.try_wait() {
+                Ok(Some(status)) => break status,
+                Ok(None) => std::thread::sleep(std::time::Duration::from_millis(50)),
+                Err(e) => return Err(format!("Failed to wait for cargo: {}", e)),
+            }
+        };
+        let stdout = stdout.join().unwrap_or_default();
+        let stderr = stderr.join().unwrap_or_default();
+
+        if !status.success() {
+            return Err(format!("cargo build for {} failed ({}):\n{}", triple, status, last_lines(&stderr, 20)));
+        }
+
+        let executables = built_executables(&stdout);
+        if executables.is_empty() {
+            return Err(format!("cargo build for {} produced no executable", triple));
+        }
+        for executable in executables {
+            let Some(name) = executable.file_name() else {
+                continue;
+            };
+            let dest = options.output_dir.join(name);
+            std::fs::copy(&executable, &dest)
+                .map_err(|e| format!("Failed to copy {}: {}", executable.display(), e))?;
+            tracing::info!("Player executable for {}: {}", triple, dest.display());
+        }
+        Ok(())
+    }
+
     fn process_texture(
         source: &Path,
         dest: &Path,
@@ -380,6 +566,7 @@ impl BuildSystem {
         let config = RuntimeConfig {
             project_name: settings.metadata.name.clone(),
             version: settings.metadata.version.clone(),
+            target_triple: options.platform.target_triple().to_string(),
             startup_scene: settings.scenes.startup_scene.clone()
                 .or_else(|| settings.scenes.build_scenes.first().map(|s| s.path.clone())),
             graphics: RuntimeGraphicsConfig {
@@ -410,6 +597,7 @@ impl BuildSystem {
 struct RuntimeConfig {
     project_name: String,
     version: String,
+    target_triple: String,
     startup_scene: Option<PathBuf>,
     graphics: RuntimeGraphicsConfig,
     audio: RuntimeAudioConfig,
@@ -432,51 +620,151 @@ struct RuntimeAudioConfig {
     sfx_volume: f32,
 }
 
-/// Build manager for the editor
+/// Build manager for the editor.
+///
+/// Builds run on a worker thread; call [`BuildManager::poll`] each frame to
+/// pick up their progress and results.
 pub struct BuildManager {
-    /// Current build state (if building)
-    pub current_build: Option<Arc<BuildState>>,
+    /// State shared with the running build
+    current_build: Option<Arc<BuildState>>,
+    /// Thread running the build
+    worker: Option<JoinHandle<()>>,
+    /// Messages from the running build
+    receiver: Option<Receiver<BuildMessage>>,
+    /// Latest progress of the running build
+    progress: Option<BuildProgress>,
     /// Last build result
     pub last_result: Option<BuildResult>,
+    /// Result of each target in the last queued build, in build order
+    pub target_results: Vec<(TargetPlatform, BuildResult)>,
 }
 
+#[allow(dead_code)] // Intentionally kept for API completeness
 impl BuildManager {
     pub fn new() -> Self {
         Self {
             current_build: None,
+            worker: None,
+            receiver: None,
+            progress: None,
             last_result: None,
+            target_results: Vec::new(),
         }
     }
 
     /// Check if a build is in progress
     pub fn is_building(&self) -> bool {
-        self.current_build.as_ref()
-            .map(|s| !s.complete.load(Ordering::Relaxed))
-            .unwrap_or(false)
+        self.current_build.is_some()
     }
 
-    /// Get current build progress
-    pub fn get_progress(&self) -> Option<BuildProgress> {
-        self.current_build.as_ref().map(|s| s.get_progress())
+    /// Latest progress of the running build
+    pub fn progress(&self) -> Option<&BuildProgress> {
+        self.progress.as_ref()
     }
 
-    /// Cancel current build
+    /// Cancel the running build. It stops at the next file and removes
+    /// its partial output.
     pub fn cancel(&self) {
         if let Some(state) = &self.current_build {
             state.cancel();
         }
     }
 
-    /// Start a new build (blocking for simplicity)
-    pub fn start_build(&mut self, settings: &ProjectSettings, project_dir: &Path) -> &Option<BuildResult> {
-        let state = Arc::new(BuildState::new());
-        self.current_build = Some(Arc::clone(&state));
+    /// Start a build for the project's target platform
+    pub fn start_build(&mut self, settings: &ProjectSettings, project_dir: &Path) {
+        self.build_targets(settings, project_dir, &[settings.target_platform]);
+    }
+
+    /// Start a build of one target into its own output directory
+    pub fn build(&mut self, settings: &ProjectSettings, project_dir: &Path, target: TargetPlatform) {
+        self.build_targets(settings, project_dir, &[target]);
+    }
+
+    /// Start building several targets one after another in the
+    /// background. Each target's result lands in `target_results`; a
+    /// failure moves on to the next target, a cancel stops the queue.
+    /// Ignored while a build is running.
+    pub fn build_targets(&mut self, settings: &ProjectSettings, project_dir: &Path, targets: &[TargetPlatform]) {
+        if self.is_building() {
+            tracing::warn!("A build is already running");
+            return;
+        }
+
+        let (sender, receiver) = mpsc::channel();
+        let state = Arc::new(BuildState::new(sender.clone()));
+        let worker_state = Arc::clone(&state);
+        let settings = settings.clone();
+        let project_dir = project_dir.to_path_buf();
+        let targets = targets.to_vec();
+        let worker = std::thread::Builder::new().name("build".to_string()).spawn(move || {
+            let results = BuildSystem::build_targets(&settings, &project_dir, &targets, &worker_state);
+            let _ = sender.send(BuildMessage::Finished(results));
+        });
+
+        match worker {
+            Ok(worker) => {
+                self.target_results.clear();
+                self.progress = Some(BuildProgress::default());
+                self.current_build = Some(state);
+                self.worker = Some(worker);
+                self.receiver = Some(receiver);
+            }
+            Err(e) => {
+                self.last_result = Some(BuildResult::Failed(format!("Failed to start build thread: {}", e)));
+            }
+        }
+    }
+
+    /// Pick up progress and results from the running build
+    pub fn poll(&mut self) {
+        let Some(receiver) = self.receiver.take() else {
+            return;
+        };
+
+        loop {
+            // Checked before receiving, so everything a finished worker sent
+            // is already queued
+            let worker_done = self.worker.as_ref().map_or(true, JoinHandle::is_finished);
+            match receiver.try_recv() {
+                Ok(BuildMessage::Progress(progress)) => self.progress = Some(progress),
+                Ok(BuildMessage::Finished(results)) => {
+                    self.finish(results);
+                    return;
+                }
+                Err(_) if worker_done => {
+                    // The worker stopped without reporting, most likely a panic
+                    self.finish(Vec::new());
+                    self.last_result = Some(BuildResult::Failed("Build thread stopped unexpectedly".to_string()));
+                    return;
+                }
+                Err(_) => {
+                    self.receiver = Some(receiver);
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Block until the running build finishes
+    pub fn wait(&mut self) {
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+        self.poll();
+    }
 
-        let result = BuildSystem::build(settings, project_dir, &state);
-        self.last_result = Some(result);
+    /// Return to idle with the results of the finished build
+    fn finish(&mut self, results: Vec<(TargetPlatform, BuildResult)>) {
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
         self.current_build = None;
+        self.receiver = None;
+        self.progress = None;
 
-        &self.last_result
+        // The last result mirrors the last target built
+        self.last_result = results.last().map(|(_, result)| result.clone());
+        self.target_results = results;
     }
 }
 
@@ -485,3 +773,142 @@ impl Default for BuildManager {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_build_targets_into_separate_directories() {
+        let project_dir = std::env::temp_dir().join(format!("build_targets_{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&project_dir).unwrap();
+        let settings = ProjectSettings::default();
+
+        let mut manager = BuildManager::new();
+        manager.build_targets(&settings, &project_dir, &[TargetPlatform::Linux, TargetPlatform::WebGL]);
+        assert!(manager.is_building());
+        manager.wait();
+        assert!(!manager.is_building());
+
+        assert_eq!(manager.target_results.len(), 2);
+        let mut output_dirs = Vec::new();
+        for (target, result) in &manager.target_results {
+            let BuildResult::Success { output_dir, .. } = result else {
+                panic!("{} build failed: {:?}", target.display_name(), result);
+            };
+            let config = std::fs::read_to_string(output_dir.join("config.ron")).unwrap();
+            assert!(config.contains(target.target_triple()));
+            output_dirs.push(output_dir.clone());
+        }
+        assert_ne!(output_dirs[0], output_dirs[1]);
+        assert!(matches!(manager.last_result, Some(BuildResult::Success { .. })));
+
+        std::fs::remove_dir_all(&project_dir).unwrap();
+    }
+
+    #[test]
+    fn test_cancelled_build_removes_partial_output() {
+        let project_dir = std::env::temp_dir().join(format!("build_cancel_{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&project_dir).unwrap();
+        let settings = ProjectSettings::default();
+        let target = TargetPlatform::Linux;
+        let output_dir = BuildOptions::for_target(&settings, &project_dir, target).output_dir;
+
+        let (sender, receiver) = mpsc::channel();
+        let state = BuildState::new(sender);
+        state.cancel();
+        let results = BuildSystem::build_targets(&settings, &project_dir, &[target, TargetPlatform::WebGL], &state);
+
+        // The queue stops at the cancelled target
+        assert_eq!(results.len(), 1);
+        assert!(matches!(results[0].1, BuildResult::Cancelled));
+        assert!(!output_dir.exists());
+        assert!(receiver.try_iter().any(|message| matches!(message, BuildMessage::Progress(_))));
+
+        std::fs::remove_dir_all(&project_dir).unwrap();
+    }
+
+    #[test]
+    fn test_missing_player_crate_fails_the_target() {
+        let project_dir = std::env::temp_dir().join(format!("build_player_{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&project_dir).unwrap();
+        let settings = ProjectSettings {
+            player_crate: Some(PathBuf::from("Player")),
+            ..Default::default()
+        };
+
+        let (sender, _receiver) = mpsc::channel();
+        let state = BuildState::new(sender);
+        let results = BuildSystem::build_targets(&settings, &project_dir, &[TargetPlatform::Linux], &state);
+        let BuildResult::Failed(error) = &results[0].1 else {
+            panic!("expected a failed build, got {:?}", results[0].1);
+        };
+        assert!(error.contains("Player crate not found"), "{}", error);
+        assert!(error.contains("Cargo.toml"), "{}", error);
+
+        std::fs::remove_dir_all(&project_dir).unwrap();
+    }
+
+    // Runs a real `cargo build`, so only where the Linux triple is the host
+    #[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+    #[test]
+    fn test_player_crate_is_compiled_into_the_output() {
+        let project_dir = std::env::temp_dir().join(format!("build_player_{}", Uuid::new_v4()));
+        let crate_dir = project_dir.join("Player");
+        std::fs::create_dir_all(crate_dir.join("src")).unwrap();
+        // Its own [workspace] keeps cargo from looking for a parent workspace
+        std::fs::write(
+            crate_dir.join("Cargo.toml"),
+            "[package]\nname = \"player\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[workspace]\n",
+        )
+        .unwrap();
+        std::fs::write(crate_dir.join("src/main.rs"), "fn main() {}\n").unwrap();
+        let settings = ProjectSettings {
+            player_crate: Some(PathBuf::from("Player")),
+            ..Default::default()
+        };
+
+        let (sender, _receiver) = mpsc::channel();
+        let state = BuildState::new(sender);
+        let results = BuildSystem::build_targets(&settings, &project_dir, &[TargetPlatform::Linux], &state);
+        let BuildResult::Success { output_dir, .. } = &results[0].1 else {
+            panic!("expected a successful build, got {:?}", results[0].1);
+        };
+        assert!(output_dir.join("player").is_file());
+        assert!(output_dir.join("config.ron").is_file());
+
+        std::fs::remove_dir_all(&project_dir).unwrap();
+    }
+
+    #[test]
+    fn test_cargo_command_targets_the_platform_triple() {
+        let settings = ProjectSettings {
+            build_configuration: BuildConfiguration::Release,
+            ..Default::default()
+        };
+        let options = BuildOptions::for_target(&settings, Path::new("/project"), TargetPlatform::WebGL);
+        let command = BuildSystem::cargo_command(Path::new("/project/Player/Cargo.toml"), &options);
+
+        let args: Vec<_> = command.get_args().map(|arg| arg.to_string_lossy().into_owned()).collect();
+        let after = |flag: &str| args.iter().position(|arg| arg == flag).map(|i| args[i + 1].clone());
+        assert_eq!(args[0], "build");
+        assert_eq!(after("--target").as_deref(), Some("wasm32-unknown-unknown"));
+        assert_eq!(after("--manifest-path").as_deref(), Some("/project/Player/Cargo.toml"));
+        assert_eq!(after("--target-dir").map(PathBuf::from), Some(PathBuf::from("/project/Build/target")));
+        assert!(args.iter().any(|arg| arg == "--release"));
+    }
+
+    #[test]
+    fn test_built_executables_from_cargo_messages() {
+        let messages = [
+            r#"{"reason":"compiler-artifact","target":{"name":"serde"},"executable":null}"#,
+            "not json",
+            r#"{"reason":"compiler-artifact","target":{"name":"player"},"executable":"/t/release/player"}"#,
+            r#"{"reason":"build-finished","success":true}"#,
+        ]
+        .join("\n");
+        assert_eq!(built_executables(&messages), vec![PathBuf::from("/t/release/player")]);
+        assert_eq!(last_lines("a\nb\nc", 2), "b\nc");
+    }
+}
@@ -6,7 +6,8 @@
 
 
 use crate::history::{HistoryError, Operation, OperationID, StateSnapshot};
-use crate::state::{EditorState, EntityData, EntityId, Transform};
+use crate::state::{ColorLabel, EditorState, EntityData, EntityId, SceneData, Transform};
+use ordoplay_editor_sequencer::math::{euler_to_quaternion, quaternion_to_euler};
 use serde::{Deserialize, Serialize};
 
 /// Trait for editor commands that can be undone/redone
@@ -23,6 +24,34 @@ pub trait EditorCommand: Send + Sync {
 
     /// Create an operation for the undo system
     fn to_operation(&self, id: OperationID) -> Result<Operation, CommandError>;
+
+    /// Key under which rapid repeats of this command merge into one undo
+    /// step, or `None` to always record a separate step
+    fn coalesce_key(&self) -> Option<String> {
+        None
+    }
+}
+
+impl<C: EditorCommand + ?Sized> EditorCommand for Box<C> {
+    fn description(&self) -> &str {
+        (**self).description()
+    }
+
+    fn execute(&self, state: &mut EditorState) -> Result<(), CommandError> {
+        (**self).execute(state)
+    }
+
+    fn snapshots(&self, state: &EditorState) -> Result<(StateSnapshot, StateSnapshot), CommandError> {
+        (**self).snapshots(state)
+    }
+
+    fn to_operation(&self, id: OperationID) -> Result<Operation, CommandError> {
+        (**self).to_operation(id)
+    }
+
+    fn coalesce_key(&self) -> Option<String> {
+        (**self).coalesce_key()
+    }
 }
 
 /// Error type for command execution
@@ -76,56 +105,37 @@ impl From<&Transform> for TransformData {
     }
 }
 
-/// Convert euler angles (degrees) to quaternion [x, y, z, w]
-fn euler_to_quaternion(euler_deg: [f32; 3]) -> [f32; 4] {
-    let half_x = (euler_deg[0] * std::f32::consts::PI / 180.0) * 0.5;
-    let half_y = (euler_deg[1] * std::f32::consts::PI / 180.0) * 0.5;
-    let half_z = (euler_deg[2] * std::f32::consts::PI / 180.0) * 0.5;
-
-    let (sx, cx) = half_x.sin_cos();
-    let (sy, cy) = half_y.sin_cos();
-    let (sz, cz) = half_z.sin_cos();
-
-    [
-        sx * cy * cz - cx * sy * sz, // x
-        cx * sy * cz + sx * cy * sz, // y
-        cx * cy * sz - sx * sy * cz, // z
-        cx * cy * cz + sx * sy * sz, // w
-    ]
+impl From<Transform> for TransformData {
+    fn from(transform: Transform) -> Self {
+        Self::from(&transform)
+    }
 }
 
-/// Convert quaternion [x, y, z, w] to euler angles (degrees)
-fn quaternion_to_euler(q: [f32; 4]) -> [f32; 3] {
-    let (x, y, z, w) = (q[0], q[1], q[2], q[3]);
-
-    // Roll (X)
-    let sinr_cosp = 2.0 * (w * x + y * z);
-    let cosr_cosp = 1.0 - 2.0 * (x * x + y * y);
-    let roll = sinr_cosp.atan2(cosr_cosp);
-
-    // Pitch (Y)
-    let sinp = 2.0 * (w * y - z * x);
-    let pitch = if sinp.abs() >= 1.0 {
-        std::f32::consts::FRAC_PI_2.copysign(sinp)
-    } else {
-        sinp.asin()
-    };
+/// Snapshot of entity transforms. Any bytes read as floats, so the marker
+/// keeps other snapshots from being mistaken for transforms.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransformSnapshot {
+    /// Layout marker (always [`TransformSnapshot::MARKER`])
+    pub marker: [u8; 4],
+    /// Entities with their transforms
+    pub transforms: Vec<(EntityId, TransformData)>,
+}
 
-    // Yaw (Z)
-    let siny_cosp = 2.0 * (w * z + x * y);
-    let cosy_cosp = 1.0 - 2.0 * (y * y + z * z);
-    let yaw = siny_cosp.atan2(cosy_cosp);
+impl TransformSnapshot {
+    /// Marker identifying a transform snapshot
+    pub const MARKER: [u8; 4] = *b"XFRM";
 
-    [
-        roll * 180.0 / std::f32::consts::PI,
-        pitch * 180.0 / std::f32::consts::PI,
-        yaw * 180.0 / std::f32::consts::PI,
-    ]
-}
+    /// Create a snapshot of entity transforms
+    pub fn new(transforms: Vec<(EntityId, TransformData)>) -> Self {
+        Self {
+            marker: Self::MARKER,
+            transforms,
+        }
+    }
 
-impl From<Transform> for TransformData {
-    fn from(transform: Transform) -> Self {
-        Self::from(&transform)
+    /// Check if the marker matches
+    pub fn is_valid(&self) -> bool {
+        self.marker == Self::MARKER
     }
 }
 
@@ -189,8 +199,8 @@ impl EditorCommand for TransformCommand {
             ));
         }
 
-        let before: Vec<_> = self.entities.iter().copied().zip(self.before.iter().cloned()).collect();
-        let after: Vec<_> = self.entities.iter().copied().zip(self.after.iter().cloned()).collect();
+        let before = TransformSnapshot::new(self.entities.iter().copied().zip(self.before.iter().cloned()).collect());
+        let after = TransformSnapshot::new(self.entities.iter().copied().zip(self.after.iter().cloned()).collect());
 
         Ok((StateSnapshot::from_value(&before)?, StateSnapshot::from_value(&after)?))
     }
@@ -200,6 +210,326 @@ impl EditorCommand for TransformCommand {
         let after = StateSnapshot::from_value(&self.after)?;
         Ok(Operation::new(id, self.description.clone(), before, after))
     }
+
+    fn coalesce_key(&self) -> Option<String> {
+        let mut ids: Vec<String> = self.entities.iter().map(|id| id.0.to_string()).collect();
+        ids.sort();
+        Some(format!("{}:{}", self.description, ids.join(",")))
+    }
+}
+
+/// World axis used by [`AlignCommand`] and [`DistributeCommand`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AlignAxis {
+    /// X axis
+    X,
+    /// Y axis
+    Y,
+    /// Z axis
+    Z,
+}
+
+impl AlignAxis {
+    /// All axes, in menu order
+    pub const ALL: [Self; 3] = [Self::X, Self::Y, Self::Z];
+
+    /// Component index in a position
+    pub fn index(self) -> usize {
+        match self {
+            Self::X => 0,
+            Self::Y => 1,
+            Self::Z => 2,
+        }
+    }
+
+    /// Display name
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::X => "X",
+            Self::Y => "Y",
+            Self::Z => "Z",
+        }
+    }
+}
+
+/// Which edge of the selection entities align to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AlignMode {
+    /// Lowest coordinate
+    Min,
+    /// Midpoint between the lowest and highest coordinate
+    Center,
+    /// Highest coordinate
+    Max,
+}
+
+impl AlignMode {
+    /// All modes, in menu order
+    pub const ALL: [Self; 3] = [Self::Min, Self::Center, Self::Max];
+
+    /// Display name
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Min => "Min",
+            Self::Center => "Center",
+            Self::Max => "Max",
+        }
+    }
+}
+
+/// Local transforms before and after a layout change, for
+/// [`EditorState::set_transforms_bulk_with_before`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TransformBatch {
+    /// Entities that move
+    pub entities: Vec<EntityId>,
+    /// Their current local transforms
+    pub before: Vec<Transform>,
+    /// Their new local transforms
+    pub after: Vec<Transform>,
+}
+
+impl TransformBatch {
+    /// Move each entity so its world position along `axis` becomes the
+    /// given coordinate, keeping the other axes. Targets should come from
+    /// [`top_level`] entities, since children move with their parents.
+    fn along_axis(state: &EditorState, axis: AlignAxis, targets: &[(EntityId, f32)]) -> Self {
+        let mut batch = Self::default();
+        for (id, coordinate) in targets {
+            let (Some(entity), Some(mut world)) = (state.scene.get(id), state.scene.world_position(*id)) else {
+                continue;
+            };
+            world[axis.index()] = *coordinate;
+
+            let mut after = entity.transform.clone();
+            after.position = state.scene.local_point(entity.parent, world);
+            batch.entities.push(*id);
+            batch.before.push(entity.transform.clone());
+            batch.after.push(after);
+        }
+        batch
+    }
+}
+
+/// `entities` without duplicates and without those that have an ancestor
+/// in the list, which move along with it
+pub(crate) fn top_level(state: &EditorState, entities: &[EntityId]) -> Vec<EntityId> {
+    let mut seen = std::collections::HashSet::new();
+    entities
+        .iter()
+        .copied()
+        .filter(|id| seen.insert(*id))
+        .filter(|id| !state.scene.ancestors(*id).iter().any(|a| entities.contains(a)))
+        .collect()
+}
+
+/// World positions of the [`top_level`] `entities` along `axis`, skipping
+/// missing ones
+fn axis_coordinates(state: &EditorState, entities: &[EntityId], axis: AlignAxis) -> Vec<(EntityId, f32)> {
+    top_level(state, entities)
+        .into_iter()
+        .filter_map(|id| Some((id, state.scene.world_position(id)?[axis.index()])))
+        .collect()
+}
+
+/// Align entities' world positions along one axis.
+///
+/// Resolves to a [`TransformBatch`] that is committed as one bulk transform.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlignCommand {
+    /// Entities to align
+    pub entities: Vec<EntityId>,
+    /// Axis to align along
+    pub axis: AlignAxis,
+    /// Edge to align to
+    pub mode: AlignMode,
+}
+
+impl AlignCommand {
+    /// Create a new align command
+    pub fn new(entities: Vec<EntityId>, axis: AlignAxis, mode: AlignMode) -> Self {
+        Self { entities, axis, mode }
+    }
+
+    /// Undo description
+    pub fn description(&self) -> String {
+        format!("Align {} {}", self.axis.name(), self.mode.name())
+    }
+
+    /// The transforms that align the entities
+    pub fn resolve(&self, state: &EditorState) -> TransformBatch {
+        let coordinates = axis_coordinates(state, &self.entities, self.axis);
+        if coordinates.len() < 2 {
+            return TransformBatch::default();
+        }
+
+        let min = coordinates.iter().map(|(_, c)| *c).fold(f32::INFINITY, f32::min);
+        let max = coordinates.iter().map(|(_, c)| *c).fold(f32::NEG_INFINITY, f32::max);
+        let target = match self.mode {
+            AlignMode::Min => min,
+            AlignMode::Center => (min + max) * 0.5,
+            AlignMode::Max => max,
+        };
+        let targets: Vec<_> = coordinates.iter().map(|(id, _)| (*id, target)).collect();
+        TransformBatch::along_axis(state, self.axis, &targets)
+    }
+}
+
+/// Space entities evenly along one axis between the outermost two, which
+/// stay in place.
+///
+/// Resolves to a [`TransformBatch`] that is committed as one bulk transform.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DistributeCommand {
+    /// Entities to distribute
+    pub entities: Vec<EntityId>,
+    /// Axis to distribute along
+    pub axis: AlignAxis,
+}
+
+impl DistributeCommand {
+    /// Create a new distribute command
+    pub fn new(entities: Vec<EntityId>, axis: AlignAxis) -> Self {
+        Self { entities, axis }
+    }
+
+    /// Undo description
+    pub fn description(&self) -> String {
+        format!("Distribute {}", self.axis.name())
+    }
+
+    /// The transforms that distribute the entities
+    pub fn resolve(&self, state: &EditorState) -> TransformBatch {
+        let mut coordinates = axis_coordinates(state, &self.entities, self.axis);
+        if coordinates.len() < 3 {
+            return TransformBatch::default();
+        }
+
+        coordinates.sort_by(|a, b| a.1.total_cmp(&b.1));
+        let first = coordinates[0].1;
+        let step = (coordinates[coordinates.len() - 1].1 - first) / (coordinates.len() - 1) as f32;
+        let targets: Vec<_> = coordinates
+            .iter()
+            .enumerate()
+            .map(|(index, (id, _))| (*id, first + step * index as f32))
+            .collect();
+        TransformBatch::along_axis(state, self.axis, &targets)
+    }
+}
+
+/// Move entities down the world up axis onto the collider below them,
+/// optionally tilting them to the surface. Entities with nothing below
+/// them stay where they are, or land on the ground plane if asked to.
+/// Children of a snapped entity move along with it.
+///
+/// Resolves to a [`TransformBatch`] that is committed as one bulk transform.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapToGroundCommand {
+    /// Entities to snap
+    pub entities: Vec<EntityId>,
+    /// Turn each entity's up axis to the surface normal
+    pub align_to_normal: bool,
+    /// Land entities with no collider below them on the ground plane
+    pub ground_plane: bool,
+}
+
+impl SnapToGroundCommand {
+    /// Create a new snap-to-ground command
+    pub fn new(entities: Vec<EntityId>, align_to_normal: bool) -> Self {
+        Self {
+            entities,
+            align_to_normal,
+            ground_plane: false,
+        }
+    }
+
+    /// Fall back to the ground plane when there is no collider below
+    pub fn with_ground_plane(mut self) -> Self {
+        self.ground_plane = true;
+        self
+    }
+
+    /// Undo description
+    pub fn description(&self) -> String {
+        match (self.align_to_normal, self.ground_plane) {
+            (true, _) => "Snap to Ground (Aligned)".to_string(),
+            (false, true) => "Drop to ground".to_string(),
+            (false, false) => "Snap to Ground".to_string(),
+        }
+    }
+
+    /// The transforms that put the entities on the ground
+    pub fn resolve(&self, state: &EditorState) -> TransformBatch {
+        let up = state.up_axis().index();
+        let mut down = [0.0; 3];
+        down[up] = -1.0;
+
+        // Nothing being moved can be the ground it lands on
+        let entities = top_level(state, &self.entities);
+        let filter = crate::physics::RaycastFilter {
+            ignore: state.collect_with_descendants(&entities).into_iter().collect(),
+            ..Default::default()
+        };
+        let physics = state.query_world();
+
+        let mut batch = TransformBatch::default();
+        for id in &entities {
+            let (Some(entity), Some(mut world)) = (state.scene.get(id), state.scene.world_transform(*id)) else {
+                continue;
+            };
+            let ground = match physics.raycast_filtered(world.position, down, f32::MAX, &filter) {
+                Some(hit) => (hit.point, hit.normal),
+                None if self.ground_plane => {
+                    let mut point = world.position;
+                    point[up] = 0.0;
+                    let mut normal = [0.0; 3];
+                    normal[up] = 1.0;
+                    (point, normal)
+                }
+                None => continue,
+            };
+
+            let mut after = entity.transform.clone();
+            world.position = ground.0;
+            if self.align_to_normal {
+                world.rotation = rotation_with_up(&world, up, ground.1);
+                after.rotation = state.scene.local_transform(entity.parent, &world).rotation;
+            }
+            after.position = state.scene.local_point(entity.parent, world.position);
+            batch.entities.push(*id);
+            batch.before.push(entity.transform.clone());
+            batch.after.push(after);
+        }
+        batch
+    }
+}
+
+/// Euler angles of `transform` turned by the smallest rotation that points
+/// its `up` axis along `normal`, so the heading is kept
+fn rotation_with_up(transform: &Transform, up: usize, normal: [f32; 3]) -> [f32; 3] {
+    use crate::physics::Vec3;
+
+    let mut unit = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+    let basis = unit.map(|axis| Vec3::from_array(transform.rotate(axis)));
+    let from = basis[up];
+    let to = Vec3::from_array(normal).normalize();
+    let cos = from.dot(&to);
+
+    let turn = |v: Vec3| -> Vec3 {
+        if 1.0 + cos < 1e-6 {
+            // Upside down: half turn around another local axis
+            let pivot = basis[(up + 1) % 3];
+            pivot * (2.0 * pivot.dot(&v)) - v
+        } else {
+            // Rodrigues' formula with the unnormalized axis
+            let k = from.cross(&to);
+            v * cos + k.cross(&v) + k * (k.dot(&v) / (1.0 + cos))
+        }
+    };
+    for (axis, image) in unit.iter_mut().zip(basis) {
+        *axis = turn(image).to_array();
+    }
+    Transform::euler_from_basis(unit)
 }
 
 /// Command to spawn an entity
@@ -393,6 +723,10 @@ pub struct DuplicateCommand {
     pub new_entities: Vec<EntityId>,
     /// Whether to select the duplicates
     pub select: bool,
+    /// Local transforms for the top-level duplicates, by index.
+    /// Duplicates without an entry keep the source transform.
+    #[serde(default)]
+    pub transforms: Vec<Transform>,
 }
 
 impl DuplicateCommand {
@@ -403,7 +737,33 @@ impl DuplicateCommand {
             source_entities,
             new_entities,
             select: true,
+            transforms: Vec::new(),
+        }
+    }
+
+    /// Place the top-level duplicates at the given local transforms
+    pub fn with_transforms(mut self, transforms: Vec<Transform>) -> Self {
+        self.transforms = transforms;
+        self
+    }
+
+    /// Duplicate one source entity, applying its transform override
+    fn duplicate_at(
+        &self,
+        index: usize,
+        state: &EditorState,
+        id_map: &mut std::collections::HashMap<EntityId, EntityId>,
+    ) -> Result<Vec<(EntityId, EntityData)>, CommandError> {
+        let mut dupes = Self::duplicate_recursive(
+            self.source_entities[index],
+            self.new_entities[index],
+            state,
+            id_map,
+        )?;
+        if let (Some(transform), Some((_, top))) = (self.transforms.get(index), dupes.first_mut()) {
+            top.transform = transform.clone();
         }
+        Ok(dupes)
     }
 
     /// Recursively duplicate an entity and all its children, returning all (`new_id`, `entity_data`) pairs.
@@ -459,8 +819,8 @@ impl EditorCommand for DuplicateCommand {
 
         let mut id_map = std::collections::HashMap::new();
 
-        for (source_id, new_id) in self.source_entities.iter().zip(self.new_entities.iter()) {
-            let all_duplicates = Self::duplicate_recursive(*source_id, *new_id, state, &mut id_map)?;
+        for (index, new_id) in self.new_entities.iter().enumerate() {
+            let all_duplicates = self.duplicate_at(index, state, &mut id_map)?;
 
             for (dup_id, mut dup_data) in all_duplicates {
                 // For top-level duplicates, keep original parent; for children, remap parent
@@ -519,16 +879,96 @@ impl EditorCommand for DuplicateCommand {
     }
 }
 
+/// Command to insert prepared entities, e.g. a subtree imported from
+/// another scene. Parent and children links must already be consistent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportEntitiesCommand {
+    /// Entities to insert, top-level entity first
+    pub entities: Vec<(EntityId, EntityData)>,
+    /// Whether to select the top-level entity
+    pub select: bool,
+}
+
+impl ImportEntitiesCommand {
+    /// Create a new import command
+    pub fn new(entities: Vec<(EntityId, EntityData)>) -> Self {
+        Self {
+            entities,
+            select: true,
+        }
+    }
+}
+
+impl EditorCommand for ImportEntitiesCommand {
+    fn description(&self) -> &str {
+        "Import Entities"
+    }
+
+    fn execute(&self, state: &mut EditorState) -> Result<(), CommandError> {
+        let Some((root_id, root)) = self.entities.first() else {
+            return Ok(());
+        };
+
+        if let Some((id, _)) = self.entities.iter().find(|(id, _)| state.scene.entities.contains_key(id)) {
+            return Err(CommandError::InvalidOperation(format!(
+                "Entity already exists: {:?}",
+                id
+            )));
+        }
+        if let Some(parent_id) = root.parent {
+            if !state.scene.entities.contains_key(&parent_id) {
+                return Err(CommandError::EntityNotFound(parent_id));
+            }
+        }
+
+        for (id, data) in &self.entities {
+            state.scene.insert_entity(*id, data.clone());
+        }
+        if let Some(parent) = root.parent.and_then(|parent_id| state.scene.get_mut(&parent_id)) {
+            if !parent.children.contains(root_id) {
+                parent.children.push(*root_id);
+            }
+        }
+
+        if self.select {
+            state.selection.clear();
+            state.selection.add(*root_id);
+        }
+
+        state.dirty = true;
+        Ok(())
+    }
+
+    fn snapshots(&self, _state: &EditorState) -> Result<(StateSnapshot, StateSnapshot), CommandError> {
+        let ids: Vec<EntityId> = self.entities.iter().map(|(id, _)| *id).collect();
+        let before = StateSnapshot::from_value(&ids)?;
+        let after = StateSnapshot::from_value(&self.entities)?;
+        Ok((before, after))
+    }
+
+    fn to_operation(&self, id: OperationID) -> Result<Operation, CommandError> {
+        let before = StateSnapshot::new(vec![]);
+        let after = StateSnapshot::from_value(self)?;
+        Ok(Operation::new(
+            id,
+            self.description().to_string(),
+            before,
+            after,
+        ))
+    }
+}
+
 /// Command to edit a component property
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PropertyEditCommand {
     /// Entity being edited
     pub entity: EntityId,
-    /// Component type name
+    /// Component type name ("Transform", "Entity"), or a component
+    /// selector ("Light", "components[0]") for component fields
     pub component_type: String,
     /// Field path (e.g., "transform.position.x")
     pub field_path: String,
-    /// Serialized old value
+    /// Serialized old value (JSON for component fields, bincode otherwise)
     pub old_value: Vec<u8>,
     /// Serialized new value
     pub new_value: Vec<u8>,
@@ -593,10 +1033,9 @@ impl EditorCommand for PropertyEditCommand {
             return Ok(());
         }
 
-        Err(CommandError::InvalidOperation(format!(
-            "Unsupported property edit: {}.{}",
-            self.component_type, self.field_path
-        )))
+        apply_component_field_edit(entity, component, field, &self.new_value)?;
+        state.dirty = true;
+        Ok(())
     }
 
     fn snapshots(&self, _state: &EditorState) -> Result<(StateSnapshot, StateSnapshot), CommandError> {
@@ -731,74 +1170,510 @@ impl EditorCommand for ReparentCommand {
     }
 }
 
+/// Snapshot of the order of an entity's siblings
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct PropertyEditSnapshot {
-    pub entity: EntityId,
-    pub component_type: String,
-    pub field_path: String,
-    pub value: Vec<u8>,
+pub struct SiblingOrderSnapshot {
+    /// Layout marker (always [`SiblingOrderSnapshot::MARKER`])
+    pub marker: [u8; 4],
+    /// Parent of the siblings (None for root entities)
+    pub parent: Option<EntityId>,
+    /// Siblings in order
+    pub order: Vec<EntityId>,
 }
 
-impl PropertyEditSnapshot {
-    pub fn new(
-        entity: EntityId,
-        component_type: String,
-        field_path: String,
-        value: Vec<u8>,
-    ) -> Self {
+impl SiblingOrderSnapshot {
+    /// Marker identifying a sibling order snapshot
+    pub const MARKER: [u8; 4] = *b"SORD";
+
+    /// Create a snapshot of the children of `parent` in `order`
+    pub fn new(parent: Option<EntityId>, order: Vec<EntityId>) -> Self {
         Self {
-            entity,
-            component_type,
-            field_path,
-            value,
+            marker: Self::MARKER,
+            parent,
+            order,
         }
     }
+
+    /// Check if the marker matches
+    pub fn is_valid(&self) -> bool {
+        self.marker == Self::MARKER
+    }
+
+    /// Put the siblings back in the recorded order
+    pub fn apply(&self, scene: &mut SceneData) -> bool {
+        scene.set_sibling_order(self.parent, &self.order)
+    }
 }
 
-/// Command to edit multiple properties as a single undoable operation
+/// Command to move an entity to another position among its siblings
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct PropertyEditGroupCommand {
-    pub description: String,
-    pub edits: Vec<PropertyEditCommand>,
+pub struct ReorderSiblingCommand {
+    /// Entity being moved
+    pub entity_id: EntityId,
+    /// Index among its siblings the entity ends up at
+    pub to: usize,
 }
 
-impl PropertyEditGroupCommand {
-    pub fn new(description: impl Into<String>, edits: Vec<PropertyEditCommand>) -> Self {
-        Self {
-            description: description.into(),
-            edits,
+impl ReorderSiblingCommand {
+    /// Create a new reorder sibling command
+    pub fn new(entity_id: EntityId, to: usize) -> Self {
+        Self { entity_id, to }
+    }
+
+    /// Parent and sibling order before and after the move
+    fn orders(&self, scene: &SceneData) -> Result<(Option<EntityId>, Vec<EntityId>, Vec<EntityId>), CommandError> {
+        let Some(entity) = scene.get(&self.entity_id) else {
+            return Err(CommandError::EntityNotFound(self.entity_id));
+        };
+        let before = scene.siblings(self.entity_id);
+        if self.to >= before.len() {
+            return Err(CommandError::InvalidOperation(format!(
+                "Sibling index {} out of bounds",
+                self.to
+            )));
         }
+        let mut after = before.clone();
+        after.retain(|id| *id != self.entity_id);
+        after.insert(self.to, self.entity_id);
+        Ok((entity.parent, before, after))
     }
 }
 
-impl EditorCommand for PropertyEditGroupCommand {
+impl EditorCommand for ReorderSiblingCommand {
     fn description(&self) -> &str {
-        &self.description
+        "Reorder Siblings"
     }
 
     fn execute(&self, state: &mut EditorState) -> Result<(), CommandError> {
-        if self.edits.is_empty() {
-            return Ok(());
-        }
-
-        for edit in &self.edits {
-            edit.execute(state)?;
-        }
-
+        let (parent, _, after) = self.orders(&state.scene)?;
+        state.scene.set_sibling_order(parent, &after);
+        state.dirty = true;
         Ok(())
     }
 
-    fn snapshots(&self, _state: &EditorState) -> Result<(StateSnapshot, StateSnapshot), CommandError> {
-        if self.edits.is_empty() {
-            return Err(CommandError::InvalidOperation(
-                "No property edits provided".to_string(),
-            ));
-        }
-
-        let mut before = Vec::new();
-        let mut after = Vec::new();
+    fn snapshots(&self, state: &EditorState) -> Result<(StateSnapshot, StateSnapshot), CommandError> {
+        let (parent, before, after) = self.orders(&state.scene)?;
+        Ok((
+            StateSnapshot::from_value(&SiblingOrderSnapshot::new(parent, before))?,
+            StateSnapshot::from_value(&SiblingOrderSnapshot::new(parent, after))?,
+        ))
+    }
 
-        for edit in &self.edits {
+    fn to_operation(&self, id: OperationID) -> Result<Operation, CommandError> {
+        let before = StateSnapshot::new(vec![]);
+        let after = StateSnapshot::from_value(self)?;
+        Ok(Operation::new(
+            id,
+            self.description().to_string(),
+            before,
+            after,
+        ))
+    }
+}
+
+/// Snapshot of part of the entity hierarchy. Applying it removes
+/// `removed` and writes `entities` back at their scene positions, so parent
+/// and child order come back exactly.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HierarchySnapshot {
+    /// Layout marker (always [`HierarchySnapshot::MARKER`])
+    pub marker: [u8; 4],
+    /// Entities that don't exist in this state
+    pub removed: Vec<EntityId>,
+    /// Entities in this state with their index in scene order
+    pub entities: Vec<(usize, EntityId, EntityData)>,
+}
+
+impl HierarchySnapshot {
+    /// Marker identifying a hierarchy snapshot
+    pub const MARKER: [u8; 4] = *b"HIER";
+
+    /// Capture the `touched` entities of `scene`; ids it lacks are recorded
+    /// as removed
+    pub fn capture(scene: &SceneData, touched: &[EntityId]) -> Self {
+        let mut removed = Vec::new();
+        let mut entities = Vec::new();
+        for id in touched {
+            match scene.entities.get_full(id) {
+                Some((index, _, data)) => entities.push((index, *id, data.clone())),
+                None => removed.push(*id),
+            }
+        }
+        entities.sort_by_key(|(index, _, _)| *index);
+
+        Self {
+            marker: Self::MARKER,
+            removed,
+            entities,
+        }
+    }
+
+    /// Check if the marker matches
+    pub fn is_valid(&self) -> bool {
+        self.marker == Self::MARKER
+    }
+
+    /// Apply the snapshot to `scene`
+    pub fn apply(&self, scene: &mut SceneData) -> bool {
+        for id in &self.removed {
+            scene.remove(id);
+        }
+        // Ascending indices, so earlier inserts don't shift later ones
+        for (index, id, data) in &self.entities {
+            match scene.get_mut(id) {
+                Some(existing) => *existing = data.clone(),
+                None => {
+                    let index = (*index).min(scene.entities.len());
+                    scene.entities.shift_insert(index, *id, data.clone());
+                }
+            }
+        }
+        true
+    }
+}
+
+/// Insert `child` into `parent`'s children at `index`. Root entities are
+/// ordered by scene order instead, so nothing happens for them.
+fn insert_child(scene: &mut SceneData, parent: Option<EntityId>, index: usize, child: EntityId) {
+    if let Some(parent) = parent.and_then(|p| scene.get_mut(&p)) {
+        let index = index.min(parent.children.len());
+        parent.children.insert(index, child);
+    }
+}
+
+/// Move `child` under `parent` in `scene`, keeping its world transform
+fn lift_to(scene: &mut SceneData, child: EntityId, parent: Option<EntityId>, index: usize) {
+    let Some(world) = scene.world_transform(child) else {
+        return;
+    };
+    let old_parent = scene.get(&child).and_then(|data| data.parent);
+    if let Some(old) = old_parent.and_then(|p| scene.get_mut(&p)) {
+        old.children.retain(|id| *id != child);
+    }
+
+    let transform = scene.local_transform(parent, &world);
+    if let Some(data) = scene.get_mut(&child) {
+        data.parent = parent;
+        data.transform = transform;
+    }
+    insert_child(scene, parent, index, child);
+}
+
+/// Command to group entities under a new empty parent at their centroid
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupCommand {
+    /// Entities to group
+    pub entities: Vec<EntityId>,
+    /// ID of the group entity
+    pub group_id: EntityId,
+    /// Name of the group entity
+    pub name: String,
+}
+
+impl GroupCommand {
+    /// Create a new group command
+    pub fn new(entities: Vec<EntityId>) -> Self {
+        Self {
+            entities,
+            group_id: EntityId::new(),
+            name: "Group".to_string(),
+        }
+    }
+
+    /// The scene after grouping, and the entities the group touches
+    fn grouped(&self, scene: &SceneData) -> Result<(SceneData, Vec<EntityId>), CommandError> {
+        if scene.entities.contains_key(&self.group_id) {
+            return Err(CommandError::InvalidOperation(format!(
+                "Entity already exists: {:?}",
+                self.group_id
+            )));
+        }
+        for id in &self.entities {
+            if !scene.entities.contains_key(id) {
+                return Err(CommandError::EntityNotFound(*id));
+            }
+        }
+
+        // Entities whose ancestor is also grouped move along with it
+        let mut seen = std::collections::HashSet::new();
+        let members: Vec<EntityId> = self
+            .entities
+            .iter()
+            .copied()
+            .filter(|id| seen.insert(*id))
+            .filter(|id| !scene.ancestors(*id).iter().any(|a| self.entities.contains(a)))
+            .collect();
+        let Some(first) = members.first().copied() else {
+            return Err(CommandError::InvalidOperation("Nothing to group".to_string()));
+        };
+
+        // Keep the group where the members were if they share a parent
+        let first_parent = scene.get(&first).and_then(|data| data.parent);
+        let parent = members
+            .iter()
+            .all(|id| scene.get(id).and_then(|data| data.parent) == first_parent)
+            .then_some(first_parent)
+            .flatten();
+
+        let mut centroid = [0.0; 3];
+        for id in &members {
+            let position = scene.world_position(*id).unwrap_or_default();
+            for (sum, value) in centroid.iter_mut().zip(position) {
+                *sum += value / members.len() as f32;
+            }
+        }
+
+        let mut touched = vec![self.group_id];
+        touched.extend(parent);
+        for id in &members {
+            touched.push(*id);
+            touched.extend(scene.get(id).and_then(|data| data.parent));
+        }
+        let mut seen = std::collections::HashSet::new();
+        touched.retain(|id| seen.insert(*id));
+
+        let mut scratch = scene.clone();
+        let group = EntityData {
+            name: self.name.clone(),
+            parent,
+            transform: Transform {
+                position: scene.local_point(parent, centroid),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let map_index = scene.entities.get_index_of(&first).unwrap_or(scene.entities.len());
+        scratch.entities.shift_insert(map_index, self.group_id, group);
+        let child_index = parent
+            .and_then(|p| scene.get(&p))
+            .and_then(|p| p.children.iter().position(|c| *c == first))
+            .unwrap_or(0);
+        insert_child(&mut scratch, parent, child_index, self.group_id);
+
+        for (index, id) in members.iter().enumerate() {
+            lift_to(&mut scratch, *id, Some(self.group_id), index);
+        }
+
+        Ok((scratch, touched))
+    }
+}
+
+impl EditorCommand for GroupCommand {
+    fn description(&self) -> &str {
+        "Group Entities"
+    }
+
+    fn execute(&self, state: &mut EditorState) -> Result<(), CommandError> {
+        let (grouped, touched) = self.grouped(&state.scene)?;
+        HierarchySnapshot::capture(&grouped, &touched).apply(&mut state.scene);
+
+        state.selection.clear();
+        state.selection.add(self.group_id);
+        state.dirty = true;
+        Ok(())
+    }
+
+    fn snapshots(&self, state: &EditorState) -> Result<(StateSnapshot, StateSnapshot), CommandError> {
+        let (grouped, touched) = self.grouped(&state.scene)?;
+        let before = HierarchySnapshot::capture(&state.scene, &touched);
+        let after = HierarchySnapshot::capture(&grouped, &touched);
+        Ok((StateSnapshot::from_value(&before)?, StateSnapshot::from_value(&after)?))
+    }
+
+    fn to_operation(&self, id: OperationID) -> Result<Operation, CommandError> {
+        let before = StateSnapshot::new(vec![]);
+        let after = StateSnapshot::from_value(self)?;
+        Ok(Operation::new(
+            id,
+            self.description().to_string(),
+            before,
+            after,
+        ))
+    }
+}
+
+/// Command to dissolve groups, lifting their children to the group's parent.
+/// Only an empty transform is removed; a parent with components stays in
+/// place with its children lifted out after it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UngroupCommand {
+    /// Group entities to dissolve
+    pub groups: Vec<EntityId>,
+}
+
+impl UngroupCommand {
+    /// Create a new ungroup command
+    pub fn new(groups: Vec<EntityId>) -> Self {
+        Self { groups }
+    }
+
+    /// The scene after ungrouping, the entities it touches, and the lifted
+    /// children still in the scene
+    fn ungrouped(&self, scene: &SceneData) -> Result<(SceneData, Vec<EntityId>, Vec<EntityId>), CommandError> {
+        let mut scratch = scene.clone();
+        let mut touched = Vec::new();
+        let mut lifted = Vec::new();
+
+        for group_id in &self.groups {
+            let Some(group) = scratch.get(group_id).cloned() else {
+                return Err(CommandError::EntityNotFound(*group_id));
+            };
+            if group.children.is_empty() {
+                return Err(CommandError::InvalidOperation(format!(
+                    "Entity has no children to ungroup: {:?}",
+                    group_id
+                )));
+            }
+
+            let keep = !group.components.is_empty();
+            let index = group
+                .parent
+                .and_then(|p| scratch.get(&p))
+                .and_then(|p| p.children.iter().position(|c| c == group_id))
+                .unwrap_or(0)
+                + usize::from(keep);
+            touched.push(*group_id);
+            touched.extend(group.parent);
+            for (offset, child) in group.children.iter().enumerate() {
+                touched.push(*child);
+                lift_to(&mut scratch, *child, group.parent, index + offset);
+                lifted.push(*child);
+            }
+            if keep {
+                continue;
+            }
+            if let Some(parent) = group.parent.and_then(|p| scratch.get_mut(&p)) {
+                parent.children.retain(|c| c != group_id);
+            }
+            scratch.remove(group_id);
+        }
+
+        let mut seen = std::collections::HashSet::new();
+        touched.retain(|id| seen.insert(*id));
+        lifted.retain(|id| scratch.entities.contains_key(id));
+        Ok((scratch, touched, lifted))
+    }
+}
+
+impl EditorCommand for UngroupCommand {
+    fn description(&self) -> &str {
+        "Ungroup Entities"
+    }
+
+    fn execute(&self, state: &mut EditorState) -> Result<(), CommandError> {
+        let (ungrouped, touched, lifted) = self.ungrouped(&state.scene)?;
+        HierarchySnapshot::capture(&ungrouped, &touched).apply(&mut state.scene);
+
+        state.selection.clear();
+        for id in lifted {
+            state.selection.add(id);
+        }
+        state.dirty = true;
+        Ok(())
+    }
+
+    fn snapshots(&self, state: &EditorState) -> Result<(StateSnapshot, StateSnapshot), CommandError> {
+        let (ungrouped, touched, _) = self.ungrouped(&state.scene)?;
+        let before = HierarchySnapshot::capture(&state.scene, &touched);
+        let after = HierarchySnapshot::capture(&ungrouped, &touched);
+        Ok((StateSnapshot::from_value(&before)?, StateSnapshot::from_value(&after)?))
+    }
+
+    fn to_operation(&self, id: OperationID) -> Result<Operation, CommandError> {
+        let before = StateSnapshot::new(vec![]);
+        let after = StateSnapshot::from_value(self)?;
+        Ok(Operation::new(
+            id,
+            self.description().to_string(),
+            before,
+            after,
+        ))
+    }
+}
+
+/// Snapshot of one property value.
+///
+/// The marker keeps this layout from being mistaken for the tuple snapshots
+/// used by other commands, whose leading entity id it shares.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PropertyEditSnapshot {
+    /// Layout marker (always [`PropertyEditSnapshot::MARKER`])
+    pub marker: [u8; 4],
+    pub entity: EntityId,
+    pub component_type: String,
+    pub field_path: String,
+    pub value: Vec<u8>,
+}
+
+impl PropertyEditSnapshot {
+    /// Marker identifying a property edit snapshot
+    pub const MARKER: [u8; 4] = *b"PROP";
+
+    pub fn new(
+        entity: EntityId,
+        component_type: String,
+        field_path: String,
+        value: Vec<u8>,
+    ) -> Self {
+        Self {
+            marker: Self::MARKER,
+            entity,
+            component_type,
+            field_path,
+            value,
+        }
+    }
+
+    /// Check if the marker matches
+    pub fn is_valid(&self) -> bool {
+        self.marker == Self::MARKER
+    }
+}
+
+/// Command to edit multiple properties as a single undoable operation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PropertyEditGroupCommand {
+    pub description: String,
+    pub edits: Vec<PropertyEditCommand>,
+}
+
+impl PropertyEditGroupCommand {
+    pub fn new(description: impl Into<String>, edits: Vec<PropertyEditCommand>) -> Self {
+        Self {
+            description: description.into(),
+            edits,
+        }
+    }
+}
+
+impl EditorCommand for PropertyEditGroupCommand {
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn execute(&self, state: &mut EditorState) -> Result<(), CommandError> {
+        if self.edits.is_empty() {
+            return Ok(());
+        }
+
+        for edit in &self.edits {
+            edit.execute(state)?;
+        }
+
+        Ok(())
+    }
+
+    fn snapshots(&self, _state: &EditorState) -> Result<(StateSnapshot, StateSnapshot), CommandError> {
+        if self.edits.is_empty() {
+            return Err(CommandError::InvalidOperation(
+                "No property edits provided".to_string(),
+            ));
+        }
+
+        let mut before = Vec::new();
+        let mut after = Vec::new();
+
+        for edit in &self.edits {
             before.push(PropertyEditSnapshot::new(
                 edit.entity,
                 edit.component_type.clone(),
@@ -847,7 +1722,8 @@ impl EditorCommand for PropertyEditGroupCommand {
     }
 }
 
-fn to_editor_transform(data: &TransformData) -> Transform {
+/// Convert a quaternion transform back to the editor's Euler angles
+pub(crate) fn to_editor_transform(data: &TransformData) -> Transform {
     Transform {
         position: data.position,
         rotation: quaternion_to_euler(data.rotation),
@@ -927,6 +1803,31 @@ fn apply_transform_edit(entity: &mut EntityData, field: &str, value: &[u8]) -> R
     }
 }
 
+/// Set a component field from its JSON-encoded value. `component` selects
+/// the component as in a prefab property path: "Light" for the first light,
+/// "components[2]" for a slot.
+pub(crate) fn apply_component_field_edit(
+    entity: &mut EntityData,
+    component: &str,
+    field: &str,
+    value: &[u8],
+) -> Result<(), CommandError> {
+    let path = format!("{component}.{field}");
+    let unsupported = || CommandError::InvalidOperation(format!("Unsupported property edit: {path}"));
+    let Some((selector, field)) = crate::prefab::component_field_path(&path) else {
+        return Err(unsupported());
+    };
+    let Some(target) = selector.find_mut(&mut entity.components) else {
+        return Err(unsupported());
+    };
+    let value: serde_json::Value = serde_json::from_slice(value)
+        .map_err(|e| CommandError::InvalidOperation(format!("Invalid value for {path}: {e}")))?;
+    if !target.set_field_value(field, value) {
+        return Err(unsupported());
+    }
+    Ok(())
+}
+
 impl SpawnCommand {
     fn entity_data(&self) -> EntityData {
         let name = self
@@ -958,9 +1859,8 @@ impl DuplicateCommand {
 
         let mut all_duplicates = Vec::new();
         let mut id_map = std::collections::HashMap::new();
-        for (source_id, new_id) in self.source_entities.iter().zip(self.new_entities.iter()) {
-            let dupes = Self::duplicate_recursive(*source_id, *new_id, state, &mut id_map)?;
-            all_duplicates.extend(dupes);
+        for index in 0..self.source_entities.len() {
+            all_duplicates.extend(self.duplicate_at(index, state, &mut id_map)?);
         }
 
         Ok(all_duplicates)
@@ -1016,14 +1916,13 @@ impl EditorCommand for AddComponentCommand {
         Ok(())
     }
 
-    fn snapshots(&self, _state: &EditorState) -> Result<(StateSnapshot, StateSnapshot), CommandError> {
-        // Before: no component, After: has component
-        let before: Option<Component> = None;
-        let after: Option<Component> = Some(self.component.clone());
-        Ok((
-            StateSnapshot::from_value(&(self.entity_id, before))?,
-            StateSnapshot::from_value(&(self.entity_id, after))?,
-        ))
+    fn snapshots(&self, state: &EditorState) -> Result<(StateSnapshot, StateSnapshot), CommandError> {
+        let entity = state.scene.get(&self.entity_id).ok_or(CommandError::EntityNotFound(self.entity_id))?;
+        let mut components = entity.components.clone();
+        let before = ComponentListSnapshot::new(self.entity_id, &components)?;
+        components.push(self.component.clone());
+        let after = ComponentListSnapshot::new(self.entity_id, &components)?;
+        Ok((StateSnapshot::from_value(&before)?, StateSnapshot::from_value(&after)?))
     }
 
     fn to_operation(&self, id: OperationID) -> Result<Operation, CommandError> {
@@ -1082,14 +1981,15 @@ impl EditorCommand for RemoveComponentCommand {
         Ok(())
     }
 
-    fn snapshots(&self, _state: &EditorState) -> Result<(StateSnapshot, StateSnapshot), CommandError> {
-        // Before: has component, After: no component
-        let before: Option<Component> = Some(self.removed_component.clone());
-        let after: Option<Component> = None;
-        Ok((
-            StateSnapshot::from_value(&(self.entity_id, self.component_index, before))?,
-            StateSnapshot::from_value(&(self.entity_id, self.component_index, after))?,
-        ))
+    fn snapshots(&self, state: &EditorState) -> Result<(StateSnapshot, StateSnapshot), CommandError> {
+        let entity = state.scene.get(&self.entity_id).ok_or(CommandError::EntityNotFound(self.entity_id))?;
+        let mut components = entity.components.clone();
+        let before = ComponentListSnapshot::new(self.entity_id, &components)?;
+        if self.component_index < components.len() {
+            components.remove(self.component_index);
+        }
+        let after = ComponentListSnapshot::new(self.entity_id, &components)?;
+        Ok((StateSnapshot::from_value(&before)?, StateSnapshot::from_value(&after)?))
     }
 
     fn to_operation(&self, id: OperationID) -> Result<Operation, CommandError> {
@@ -1104,6 +2004,660 @@ impl EditorCommand for RemoveComponentCommand {
     }
 }
 
+/// Snapshot of one component slot on an entity.
+///
+/// Components are internally tagged, which bincode cannot read back, so the
+/// component is stored as JSON. The marker keeps this layout from being
+/// mistaken for the tuple snapshots used by other commands.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComponentSnapshot {
+    /// Layout marker (always [`ComponentSnapshot::MARKER`])
+    pub marker: [u8; 4],
+    /// Entity owning the component
+    pub entity: EntityId,
+    /// Index of the component on the entity
+    pub component_index: u32,
+    /// Component value as JSON
+    pub component_json: String,
+}
+
+impl ComponentSnapshot {
+    /// Marker identifying a component snapshot
+    pub const MARKER: [u8; 4] = *b"CMPS";
+
+    /// Create a snapshot of a component value
+    pub fn new(entity: EntityId, component_index: usize, component: &Component) -> Result<Self, CommandError> {
+        let component_json = serde_json::to_string(component)
+            .map_err(|e| CommandError::InvalidOperation(e.to_string()))?;
+        Ok(Self {
+            marker: Self::MARKER,
+            entity,
+            component_index: component_index as u32,
+            component_json,
+        })
+    }
+
+    /// Check if the marker matches
+    pub fn is_valid(&self) -> bool {
+        self.marker == Self::MARKER
+    }
+
+    /// Decode the stored component
+    pub fn component(&self) -> Option<Component> {
+        serde_json::from_str(&self.component_json).ok()
+    }
+}
+
+/// Snapshot of an entity's whole component list, used when components are
+/// added or removed. Stored as JSON for the same reason as [`ComponentSnapshot`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComponentListSnapshot {
+    /// Layout marker (always [`ComponentListSnapshot::MARKER`])
+    pub marker: [u8; 4],
+    /// Entity owning the components
+    pub entity: EntityId,
+    /// Component list as JSON
+    pub components_json: String,
+}
+
+impl ComponentListSnapshot {
+    /// Marker identifying a component list snapshot
+    pub const MARKER: [u8; 4] = *b"CLST";
+
+    /// Create a snapshot of a component list
+    pub fn new(entity: EntityId, components: &[Component]) -> Result<Self, CommandError> {
+        let components_json = serde_json::to_string(components)
+            .map_err(|e| CommandError::InvalidOperation(e.to_string()))?;
+        Ok(Self {
+            marker: Self::MARKER,
+            entity,
+            components_json,
+        })
+    }
+
+    /// Check if the marker matches
+    pub fn is_valid(&self) -> bool {
+        self.marker == Self::MARKER
+    }
+
+    /// Decode the stored components
+    pub fn components(&self) -> Option<Vec<Component>> {
+        serde_json::from_str(&self.components_json).ok()
+    }
+}
+
+/// Command to reset a component to its registry default values
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResetComponentCommand {
+    /// Target entity
+    pub entity_id: EntityId,
+    /// Index of the component to reset
+    pub component_index: usize,
+    /// Component values before the reset (for undo)
+    pub previous: Component,
+    /// Default component values
+    pub default: Component,
+}
+
+impl ResetComponentCommand {
+    /// Create a reset command, or `None` if the component type has no registry default
+    pub fn new(entity_id: EntityId, component_index: usize, previous: Component) -> Option<Self> {
+        let default = previous.factory_default()?;
+        Some(Self {
+            entity_id,
+            component_index,
+            previous,
+            default,
+        })
+    }
+}
+
+impl EditorCommand for ResetComponentCommand {
+    fn description(&self) -> &str {
+        "Reset Component"
+    }
+
+    fn execute(&self, state: &mut EditorState) -> Result<(), CommandError> {
+        let Some(entity) = state.scene.get_mut(&self.entity_id) else {
+            return Err(CommandError::EntityNotFound(self.entity_id));
+        };
+
+        let Some(component) = entity.components.get_mut(self.component_index) else {
+            return Err(CommandError::InvalidOperation(format!(
+                "Component index {} out of bounds",
+                self.component_index
+            )));
+        };
+
+        if component.type_id() != self.default.type_id() {
+            return Err(CommandError::InvalidOperation(format!(
+                "Component {} is not a {}",
+                self.component_index,
+                self.default.display_name()
+            )));
+        }
+
+        *component = self.default.clone();
+        state.dirty = true;
+        Ok(())
+    }
+
+    fn snapshots(&self, _state: &EditorState) -> Result<(StateSnapshot, StateSnapshot), CommandError> {
+        let before = ComponentSnapshot::new(self.entity_id, self.component_index, &self.previous)?;
+        let after = ComponentSnapshot::new(self.entity_id, self.component_index, &self.default)?;
+        Ok((
+            StateSnapshot::from_value(&before)?,
+            StateSnapshot::from_value(&after)?,
+        ))
+    }
+
+    fn to_operation(&self, id: OperationID) -> Result<Operation, CommandError> {
+        let before = ComponentSnapshot::new(self.entity_id, self.component_index, &self.previous)?;
+        let after = ComponentSnapshot::new(self.entity_id, self.component_index, &self.default)?;
+        Ok(Operation::new(
+            id,
+            format!("Reset {}", self.default.display_name()),
+            StateSnapshot::from_value(&before)?,
+            StateSnapshot::from_value(&after)?,
+        ))
+    }
+}
+
+/// Command to replace a component with a different one (e.g. collider type conversion)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplaceComponentCommand {
+    /// Target entity
+    pub entity_id: EntityId,
+    /// Index of the component to replace
+    pub component_index: usize,
+    /// Component before replacement (for undo)
+    pub previous: Component,
+    /// Replacement component
+    pub replacement: Component,
+    /// Description for the undo history
+    pub description: String,
+}
+
+impl ReplaceComponentCommand {
+    /// Create a new replace component command
+    pub fn new(
+        entity_id: EntityId,
+        component_index: usize,
+        previous: Component,
+        replacement: Component,
+        description: impl Into<String>,
+    ) -> Self {
+        Self {
+            entity_id,
+            component_index,
+            previous,
+            replacement,
+            description: description.into(),
+        }
+    }
+}
+
+impl EditorCommand for ReplaceComponentCommand {
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn execute(&self, state: &mut EditorState) -> Result<(), CommandError> {
+        let Some(entity) = state.scene.get_mut(&self.entity_id) else {
+            return Err(CommandError::EntityNotFound(self.entity_id));
+        };
+
+        let replacement_type = self.replacement.type_id();
+        let duplicate = entity.components.iter().enumerate().any(|(i, c)| {
+            i != self.component_index && c.type_id() == replacement_type
+        });
+        if duplicate {
+            return Err(CommandError::InvalidOperation(format!(
+                "Entity already has a {} component",
+                self.replacement.display_name()
+            )));
+        }
+
+        let Some(component) = entity.components.get_mut(self.component_index) else {
+            return Err(CommandError::InvalidOperation(format!(
+                "Component index {} out of bounds",
+                self.component_index
+            )));
+        };
+
+        *component = self.replacement.clone();
+        state.dirty = true;
+        Ok(())
+    }
+
+    fn snapshots(&self, _state: &EditorState) -> Result<(StateSnapshot, StateSnapshot), CommandError> {
+        let before = ComponentSnapshot::new(self.entity_id, self.component_index, &self.previous)?;
+        let after = ComponentSnapshot::new(self.entity_id, self.component_index, &self.replacement)?;
+        Ok((
+            StateSnapshot::from_value(&before)?,
+            StateSnapshot::from_value(&after)?,
+        ))
+    }
+
+    fn to_operation(&self, id: OperationID) -> Result<Operation, CommandError> {
+        let (before, after) = (
+            ComponentSnapshot::new(self.entity_id, self.component_index, &self.previous)?,
+            ComponentSnapshot::new(self.entity_id, self.component_index, &self.replacement)?,
+        );
+        Ok(Operation::new(
+            id,
+            self.description.clone(),
+            StateSnapshot::from_value(&before)?,
+            StateSnapshot::from_value(&after)?,
+        ))
+    }
+}
+
+/// Snapshot of the order of an entity's components. Components are unique
+/// per type, so the order is recorded as type ids.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComponentOrderSnapshot {
+    /// Layout marker (always [`ComponentOrderSnapshot::MARKER`])
+    pub marker: [u8; 4],
+    /// Entity owning the components
+    pub entity: EntityId,
+    /// Component type ids in order
+    pub order: Vec<String>,
+}
+
+impl ComponentOrderSnapshot {
+    /// Marker identifying a component order snapshot
+    pub const MARKER: [u8; 4] = *b"CORD";
+
+    /// Create a snapshot of the order of `components`
+    pub fn new(entity: EntityId, components: &[Component]) -> Self {
+        Self {
+            marker: Self::MARKER,
+            entity,
+            order: components.iter().map(|c| c.type_id().to_string()).collect(),
+        }
+    }
+
+    /// Check if the marker matches
+    pub fn is_valid(&self) -> bool {
+        self.marker == Self::MARKER
+    }
+
+    /// Put the entity's components back in the recorded order. Components
+    /// missing from the snapshot keep their relative order at the end.
+    pub fn apply(&self, scene: &mut SceneData) -> bool {
+        let Some(entity) = scene.get_mut(&self.entity) else {
+            return false;
+        };
+        entity.components.sort_by_key(|component| {
+            self.order
+                .iter()
+                .position(|type_id| type_id == component.type_id())
+                .unwrap_or(usize::MAX)
+        });
+        true
+    }
+}
+
+/// Command to move a component to another position on its entity
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReorderComponentCommand {
+    /// Target entity
+    pub entity_id: EntityId,
+    /// Current index of the component
+    pub from: usize,
+    /// Index the component ends up at
+    pub to: usize,
+}
+
+impl ReorderComponentCommand {
+    /// Create a new reorder component command
+    pub fn new(entity_id: EntityId, from: usize, to: usize) -> Self {
+        Self { entity_id, from, to }
+    }
+
+    fn reordered(&self, components: &[Component]) -> Result<Vec<Component>, CommandError> {
+        if self.from >= components.len() || self.to >= components.len() {
+            return Err(CommandError::InvalidOperation(format!(
+                "Component index {} or {} out of bounds",
+                self.from, self.to
+            )));
+        }
+        let mut components = components.to_vec();
+        let component = components.remove(self.from);
+        components.insert(self.to, component);
+        Ok(components)
+    }
+}
+
+impl EditorCommand for ReorderComponentCommand {
+    fn description(&self) -> &str {
+        "Reorder Components"
+    }
+
+    fn execute(&self, state: &mut EditorState) -> Result<(), CommandError> {
+        let Some(entity) = state.scene.get_mut(&self.entity_id) else {
+            return Err(CommandError::EntityNotFound(self.entity_id));
+        };
+
+        entity.components = self.reordered(&entity.components)?;
+        state.dirty = true;
+        Ok(())
+    }
+
+    fn snapshots(&self, state: &EditorState) -> Result<(StateSnapshot, StateSnapshot), CommandError> {
+        let Some(entity) = state.scene.get(&self.entity_id) else {
+            return Err(CommandError::EntityNotFound(self.entity_id));
+        };
+
+        let before = ComponentOrderSnapshot::new(self.entity_id, &entity.components);
+        let after = ComponentOrderSnapshot::new(self.entity_id, &self.reordered(&entity.components)?);
+        Ok((
+            StateSnapshot::from_value(&before)?,
+            StateSnapshot::from_value(&after)?,
+        ))
+    }
+
+    fn to_operation(&self, id: OperationID) -> Result<Operation, CommandError> {
+        let before = StateSnapshot::new(vec![]);
+        let after = StateSnapshot::from_value(self)?;
+        Ok(Operation::new(
+            id,
+            self.description().to_string(),
+            before,
+            after,
+        ))
+    }
+}
+
+/// Snapshot of one metadata entry on an entity (`None` when the key is absent)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetadataSnapshot {
+    /// Layout marker (always [`MetadataSnapshot::MARKER`])
+    pub marker: [u8; 4],
+    /// Entity owning the metadata
+    pub entity: EntityId,
+    /// Metadata key
+    pub key: String,
+    /// Value stored under the key
+    pub value: Option<String>,
+}
+
+impl MetadataSnapshot {
+    /// Marker identifying a metadata snapshot
+    pub const MARKER: [u8; 4] = *b"META";
+
+    /// Create a snapshot of a metadata entry
+    pub fn new(entity: EntityId, key: impl Into<String>, value: Option<String>) -> Self {
+        Self {
+            marker: Self::MARKER,
+            entity,
+            key: key.into(),
+            value,
+        }
+    }
+
+    /// Check if the marker matches
+    pub fn is_valid(&self) -> bool {
+        self.marker == Self::MARKER
+    }
+}
+
+/// Snapshot of a prefab override apply: the prefab file, the applying
+/// instance's overrides and the other instances the values were pushed to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrefabApplySnapshot {
+    /// Layout marker (always [`PrefabApplySnapshot::MARKER`])
+    pub marker: [u8; 4],
+    /// Source prefab file
+    pub prefab_path: PathBuf,
+    /// Contents of the prefab file
+    pub prefab_file: String,
+    /// Root entity of the instance whose overrides were applied
+    pub instance: EntityId,
+    /// The instance's overrides as JSON, since their values can't be
+    /// stored in the binary snapshot directly
+    pub overrides: String,
+    /// Entities of other instances that took the applied values
+    pub entities: Vec<(EntityId, EntityData)>,
+}
+
+impl PrefabApplySnapshot {
+    /// Marker identifying a prefab apply snapshot
+    pub const MARKER: [u8; 4] = *b"PAPL";
+
+    /// Check if the marker matches
+    pub fn is_valid(&self) -> bool {
+        self.marker == Self::MARKER
+    }
+}
+
+/// Command to set or remove an entity metadata entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetMetadataCommand {
+    /// Target entity
+    pub entity_id: EntityId,
+    /// Metadata key
+    pub key: String,
+    /// Value before the edit (for undo)
+    pub previous: Option<String>,
+    /// New value (`None` removes the key)
+    pub value: Option<String>,
+}
+
+impl SetMetadataCommand {
+    /// Create a new set metadata command
+    pub fn new(entity_id: EntityId, key: impl Into<String>, previous: Option<String>, value: Option<String>) -> Self {
+        Self {
+            entity_id,
+            key: key.into(),
+            previous,
+            value,
+        }
+    }
+}
+
+impl EditorCommand for SetMetadataCommand {
+    fn description(&self) -> &str {
+        if self.value.is_some() {
+            "Set Metadata"
+        } else {
+            "Remove Metadata"
+        }
+    }
+
+    fn execute(&self, state: &mut EditorState) -> Result<(), CommandError> {
+        if !state.scene.set_metadata(self.entity_id, &self.key, self.value.clone()) {
+            return Err(CommandError::EntityNotFound(self.entity_id));
+        }
+        state.dirty = true;
+        Ok(())
+    }
+
+    fn snapshots(&self, _state: &EditorState) -> Result<(StateSnapshot, StateSnapshot), CommandError> {
+        let before = MetadataSnapshot::new(self.entity_id, self.key.clone(), self.previous.clone());
+        let after = MetadataSnapshot::new(self.entity_id, self.key.clone(), self.value.clone());
+        Ok((StateSnapshot::from_value(&before)?, StateSnapshot::from_value(&after)?))
+    }
+
+    fn to_operation(&self, id: OperationID) -> Result<Operation, CommandError> {
+        let before = MetadataSnapshot::new(self.entity_id, self.key.clone(), self.previous.clone());
+        let after = MetadataSnapshot::new(self.entity_id, self.key.clone(), self.value.clone());
+        Ok(Operation::new(
+            id,
+            format!("{} '{}'", self.description(), self.key),
+            StateSnapshot::from_value(&before)?,
+            StateSnapshot::from_value(&after)?,
+        ))
+    }
+}
+
+/// Snapshot of an entity's tags
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TagsSnapshot {
+    /// Layout marker (always [`TagsSnapshot::MARKER`])
+    pub marker: [u8; 4],
+    /// Entity owning the tags
+    pub entity: EntityId,
+    /// The tags
+    pub tags: Vec<String>,
+}
+
+impl TagsSnapshot {
+    /// Marker identifying a tags snapshot
+    pub const MARKER: [u8; 4] = *b"TAGS";
+
+    /// Create a snapshot of an entity's tags
+    pub fn new(entity: EntityId, tags: Vec<String>) -> Self {
+        Self {
+            marker: Self::MARKER,
+            entity,
+            tags,
+        }
+    }
+
+    /// Check if the marker matches
+    pub fn is_valid(&self) -> bool {
+        self.marker == Self::MARKER
+    }
+}
+
+/// Command to replace an entity's tags
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetTagsCommand {
+    /// Target entity
+    pub entity_id: EntityId,
+    /// Tags before the edit (for undo)
+    pub previous: Vec<String>,
+    /// New tags
+    pub tags: Vec<String>,
+}
+
+impl SetTagsCommand {
+    /// Create a new set tags command
+    pub fn new(entity_id: EntityId, previous: Vec<String>, tags: Vec<String>) -> Self {
+        Self {
+            entity_id,
+            previous,
+            tags,
+        }
+    }
+}
+
+impl EditorCommand for SetTagsCommand {
+    fn description(&self) -> &str {
+        "Set Tags"
+    }
+
+    fn execute(&self, state: &mut EditorState) -> Result<(), CommandError> {
+        if !state.scene.set_tags(self.entity_id, self.tags.clone()) {
+            return Err(CommandError::EntityNotFound(self.entity_id));
+        }
+        state.dirty = true;
+        Ok(())
+    }
+
+    fn snapshots(&self, _state: &EditorState) -> Result<(StateSnapshot, StateSnapshot), CommandError> {
+        let before = TagsSnapshot::new(self.entity_id, self.previous.clone());
+        let after = TagsSnapshot::new(self.entity_id, self.tags.clone());
+        Ok((StateSnapshot::from_value(&before)?, StateSnapshot::from_value(&after)?))
+    }
+
+    fn to_operation(&self, id: OperationID) -> Result<Operation, CommandError> {
+        let before = TagsSnapshot::new(self.entity_id, self.previous.clone());
+        let after = TagsSnapshot::new(self.entity_id, self.tags.clone());
+        Ok(Operation::new(
+            id,
+            self.description().to_string(),
+            StateSnapshot::from_value(&before)?,
+            StateSnapshot::from_value(&after)?,
+        ))
+    }
+}
+
+/// Snapshot of an entity's color label
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColorLabelSnapshot {
+    /// Layout marker (always [`ColorLabelSnapshot::MARKER`])
+    pub marker: [u8; 4],
+    /// Entity owning the label
+    pub entity: EntityId,
+    /// The label
+    pub label: Option<ColorLabel>,
+}
+
+impl ColorLabelSnapshot {
+    /// Marker identifying a color label snapshot
+    pub const MARKER: [u8; 4] = *b"CLBL";
+
+    /// Create a snapshot of an entity's color label
+    pub fn new(entity: EntityId, label: Option<ColorLabel>) -> Self {
+        Self {
+            marker: Self::MARKER,
+            entity,
+            label,
+        }
+    }
+
+    /// Check if the marker matches
+    pub fn is_valid(&self) -> bool {
+        self.marker == Self::MARKER
+    }
+}
+
+/// Command to set or clear an entity's color label
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetColorLabelCommand {
+    /// Target entity
+    pub entity_id: EntityId,
+    /// Label before the edit (for undo)
+    pub previous: Option<ColorLabel>,
+    /// New label
+    pub label: Option<ColorLabel>,
+}
+
+impl SetColorLabelCommand {
+    /// Create a new set color label command
+    pub fn new(entity_id: EntityId, previous: Option<ColorLabel>, label: Option<ColorLabel>) -> Self {
+        Self {
+            entity_id,
+            previous,
+            label,
+        }
+    }
+}
+
+impl EditorCommand for SetColorLabelCommand {
+    fn description(&self) -> &str {
+        "Set Color Label"
+    }
+
+    fn execute(&self, state: &mut EditorState) -> Result<(), CommandError> {
+        if !state.scene.set_color_label(self.entity_id, self.label) {
+            return Err(CommandError::EntityNotFound(self.entity_id));
+        }
+        state.dirty = true;
+        Ok(())
+    }
+
+    fn snapshots(&self, _state: &EditorState) -> Result<(StateSnapshot, StateSnapshot), CommandError> {
+        let before = ColorLabelSnapshot::new(self.entity_id, self.previous);
+        let after = ColorLabelSnapshot::new(self.entity_id, self.label);
+        Ok((StateSnapshot::from_value(&before)?, StateSnapshot::from_value(&after)?))
+    }
+
+    fn to_operation(&self, id: OperationID) -> Result<Operation, CommandError> {
+        let before = ColorLabelSnapshot::new(self.entity_id, self.previous);
+        let after = ColorLabelSnapshot::new(self.entity_id, self.label);
+        Ok(Operation::new(
+            id,
+            self.description().to_string(),
+            StateSnapshot::from_value(&before)?,
+            StateSnapshot::from_value(&after)?,
+        ))
+    }
+}
+
 // ============================================================================
 // Prefab Commands
 // ============================================================================
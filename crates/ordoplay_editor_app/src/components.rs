@@ -5,11 +5,15 @@
 //! along with their serialization and default values.
 
 
+use crate::state::EntityId;
 use serde::{Deserialize, Serialize};
 
 /// Unique identifier for component types
 pub type ComponentTypeId = &'static str;
 
+/// Icon font glyph for entities without components
+pub const ENTITY_ICON: &str = "\u{f192}"; // dot-circle
+
 /// Registry of all available component types
 #[allow(dead_code)] // Intentionally kept for API completeness
 #[derive(Debug, Clone)]
@@ -28,7 +32,7 @@ pub struct ComponentInfo {
 
 /// All component types that can be attached to entities
 #[allow(dead_code)] // Intentionally kept for API completeness
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, PartialEq)]
 #[serde(tag = "type")]
 pub enum Component {
     /// Mesh renderer for 3D models
@@ -53,6 +57,10 @@ pub enum Component {
     AudioSource(AudioSourceComponent),
     /// Script attachment
     Script(ScriptComponent),
+    /// Orients the entity towards another entity
+    LookAt(LookAtComponent),
+    /// Polyline path in local space
+    Path(PathComponent),
 }
 
 #[allow(dead_code)] // Intentionally kept for API completeness
@@ -71,6 +79,8 @@ impl Component {
             Component::PhysicsMaterial(_) => "PhysicsMaterial",
             Component::AudioSource(_) => "AudioSource",
             Component::Script(_) => "Script",
+            Component::LookAt(_) => "LookAt",
+            Component::Path(_) => "Path",
         }
     }
 
@@ -88,8 +98,238 @@ impl Component {
             Component::PhysicsMaterial(_) => "Physics Material",
             Component::AudioSource(_) => "Audio Source",
             Component::Script(_) => "Script",
+            Component::LookAt(_) => "Look At",
+            Component::Path(_) => "Path",
+        }
+    }
+
+    /// Icon font glyph for this component type
+    pub fn icon(&self) -> &'static str {
+        match self {
+            Component::MeshRenderer(_) => "\u{f1b2}",    // cube
+            Component::Light(_) => "\u{f0eb}",           // lightbulb
+            Component::Camera(_) => "\u{f030}",          // camera
+            Component::Rigidbody(_) => "\u{f24e}",       // balance-scale
+            Component::BoxCollider(_)
+            | Component::SphereCollider(_)
+            | Component::CapsuleCollider(_)
+            | Component::MeshCollider(_) => "\u{f0c8}", // square
+            Component::PhysicsMaterial(_) => "\u{f5fd}", // layer-group
+            Component::AudioSource(_) => "\u{f028}",     // volume-up
+            Component::Script(_) => "\u{f121}",          // code
+            Component::LookAt(_) => "\u{f06e}",          // eye
+            Component::Path(_) => "\u{f55b}",            // bezier-curve
+        }
+    }
+
+    /// How well this component describes its entity in the hierarchy;
+    /// lower wins. Cameras and lights outrank meshes, which outrank the
+    /// physics and behavior components that usually accompany them.
+    pub fn icon_priority(&self) -> u8 {
+        match self {
+            Component::Camera(_) => 0,
+            Component::Light(_) => 1,
+            Component::AudioSource(_) => 2,
+            Component::MeshRenderer(_) => 3,
+            Component::Path(_) => 4,
+            Component::Rigidbody(_) => 5,
+            Component::BoxCollider(_)
+            | Component::SphereCollider(_)
+            | Component::CapsuleCollider(_)
+            | Component::MeshCollider(_) => 6,
+            Component::Script(_) => 7,
+            Component::LookAt(_) => 8,
+            Component::PhysicsMaterial(_) => 9,
+        }
+    }
+
+    /// Asset paths referenced by this component (empty paths included)
+    pub fn asset_paths(&self) -> Vec<&str> {
+        self.asset_path_fields().into_iter().map(|(_, path)| path).collect()
+    }
+
+    /// Mutable access to the asset paths referenced by this component
+    pub fn asset_paths_mut(&mut self) -> Vec<&mut String> {
+        self.asset_path_fields_mut().into_iter().map(|(_, path)| path).collect()
+    }
+
+    /// Asset paths referenced by this component, by field name
+    pub fn asset_path_fields(&self) -> Vec<(&'static str, &str)> {
+        match self {
+            Component::MeshRenderer(mesh) => vec![("mesh", mesh.mesh.as_str()), ("material", mesh.material.as_str())],
+            Component::MeshCollider(collider) => vec![("mesh", collider.mesh.as_str())],
+            Component::AudioSource(audio) => vec![("clip", audio.clip.as_str())],
+            Component::Script(script) => vec![("script", script.script.as_str())],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Mutable access to the asset paths referenced by this component, by
+    /// field name
+    pub fn asset_path_fields_mut(&mut self) -> Vec<(&'static str, &mut String)> {
+        match self {
+            Component::MeshRenderer(mesh) => vec![("mesh", &mut mesh.mesh), ("material", &mut mesh.material)],
+            Component::MeshCollider(collider) => vec![("mesh", &mut collider.mesh)],
+            Component::AudioSource(audio) => vec![("clip", &mut audio.clip)],
+            Component::Script(script) => vec![("script", &mut script.script)],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Entity reference fields on this component, by field name
+    pub fn entity_references(&self) -> Vec<(&'static str, Option<EntityId>)> {
+        match self {
+            Component::LookAt(look_at) => vec![("target", look_at.target)],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Set an entity reference field. Returns false if the field doesn't exist.
+    pub fn set_entity_reference(&mut self, field: &str, value: Option<EntityId>) -> bool {
+        match (self, field) {
+            (Component::LookAt(look_at), "target") => {
+                look_at.target = value;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Value of a field by dotted path (e.g. "intensity", "color.0"), in
+    /// the component's serialized form
+    pub fn field_value(&self, field_path: &str) -> Option<serde_json::Value> {
+        let value = serde_json::to_value(self).ok()?;
+        field_path.split('.').try_fold(value, |value, key| match value {
+            serde_json::Value::Object(mut fields) => fields.remove(key),
+            serde_json::Value::Array(mut items) => {
+                let index: usize = key.parse().ok()?;
+                (index < items.len()).then(|| items.swap_remove(index))
+            }
+            _ => None,
+        })
+    }
+
+    /// Set a field by dotted path. Returns false, leaving the component
+    /// unchanged, if the field doesn't exist or the value doesn't fit it.
+    pub fn set_field_value(&mut self, field_path: &str, new_value: serde_json::Value) -> bool {
+        // The serialized type tag isn't a field
+        if field_path == "type" {
+            return false;
+        }
+        let Ok(mut value) = serde_json::to_value(&*self) else {
+            return false;
+        };
+
+        let mut slot = &mut value;
+        for key in field_path.split('.') {
+            let next = match slot {
+                serde_json::Value::Object(fields) => fields.get_mut(key),
+                serde_json::Value::Array(items) => key.parse::<usize>().ok().and_then(|index| items.get_mut(index)),
+                _ => None,
+            };
+            let Some(next) = next else {
+                return false;
+            };
+            slot = next;
+        }
+        *slot = new_value;
+
+        match serde_json::from_value(value) {
+            Ok(component) => {
+                *self = component;
+                true
+            }
+            Err(_) => false,
         }
     }
+
+    /// Create the registry default for this component's type
+    pub fn factory_default(&self) -> Option<Component> {
+        let type_id = self.type_id();
+        get_component_registry()
+            .into_iter()
+            .find(|info| info.type_id == type_id)
+            .map(|info| (info.create_default)())
+    }
+
+    /// Deserialize the component named by a serialized type tag from the
+    /// remaining fields
+    fn deserialize_tagged<'de, D: serde::Deserializer<'de>>(tag: &str, fields: D) -> Result<Self, D::Error> {
+        match tag {
+            "MeshRenderer" => MeshRendererComponent::deserialize(fields).map(Component::MeshRenderer),
+            "Light" => LightComponent::deserialize(fields).map(Component::Light),
+            "Camera" => CameraComponent::deserialize(fields).map(Component::Camera),
+            "Rigidbody" => RigidbodyComponent::deserialize(fields).map(Component::Rigidbody),
+            "BoxCollider" => BoxColliderComponent::deserialize(fields).map(Component::BoxCollider),
+            "SphereCollider" => SphereColliderComponent::deserialize(fields).map(Component::SphereCollider),
+            "CapsuleCollider" => CapsuleColliderComponent::deserialize(fields).map(Component::CapsuleCollider),
+            "MeshCollider" => MeshColliderComponent::deserialize(fields).map(Component::MeshCollider),
+            "PhysicsMaterial" => PhysicsMaterialComponent::deserialize(fields).map(Component::PhysicsMaterial),
+            "AudioSource" => AudioSourceComponent::deserialize(fields).map(Component::AudioSource),
+            "Script" => ScriptComponent::deserialize(fields).map(Component::Script),
+            "LookAt" => LookAtComponent::deserialize(fields).map(Component::LookAt),
+            "Path" => PathComponent::deserialize(fields).map(Component::Path),
+            _ => Err(serde::de::Error::unknown_variant(tag, COMPONENT_TAGS)),
+        }
+    }
+}
+
+/// Serialized type tags of every component variant
+const COMPONENT_TAGS: &[&str] = &[
+    "MeshRenderer",
+    "Light",
+    "Camera",
+    "Rigidbody",
+    "BoxCollider",
+    "SphereCollider",
+    "CapsuleCollider",
+    "MeshCollider",
+    "PhysicsMaterial",
+    "AudioSource",
+    "Script",
+    "LookAt",
+    "Path",
+];
+
+/// Components serialize with serde's internal `type` tag, but reading one
+/// back through serde's buffered tag lookup breaks in RON, which rejects the
+/// quoted tag value. The tag is read here instead and the remaining fields
+/// stream straight into the component struct; fields that precede the tag
+/// (e.g. from a sorted JSON map) are buffered as JSON first.
+impl<'de> Deserialize<'de> for Component {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ComponentVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for ComponentVisitor {
+            type Value = Component;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a component with a `type` field")
+            }
+
+            fn visit_map<A: serde::de::MapAccess<'de>>(self, mut map: A) -> Result<Component, A::Error> {
+                let mut leading = serde_json::Map::new();
+                while let Some(key) = map.next_key::<String>()? {
+                    if key != "type" {
+                        leading.insert(key, map.next_value()?);
+                        continue;
+                    }
+                    let tag: String = map.next_value()?;
+                    if leading.is_empty() {
+                        return Component::deserialize_tagged(&tag, serde::de::value::MapAccessDeserializer::new(map));
+                    }
+                    while let Some(key) = map.next_key::<String>()? {
+                        leading.insert(key, map.next_value()?);
+                    }
+                    return Component::deserialize_tagged(&tag, serde_json::Value::Object(leading))
+                        .map_err(serde::de::Error::custom);
+                }
+                Err(serde::de::Error::missing_field("type"))
+            }
+        }
+
+        deserializer.deserialize_any(ComponentVisitor)
+    }
 }
 
 // ============================================================================
@@ -447,6 +687,17 @@ impl Default for PhysicsMaterialComponent {
     }
 }
 
+/// How a spatial audio source fades between its min and max distance
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum AudioRolloff {
+    /// Volume falls off evenly, reaching silence at max distance
+    #[default]
+    Linear,
+    /// Volume halves each time the distance doubles, like a real source,
+    /// and cuts to silence at max distance
+    Logarithmic,
+}
+
 /// Audio source component for playing sounds
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct AudioSourceComponent {
@@ -466,6 +717,16 @@ pub struct AudioSourceComponent {
     pub min_distance: f32,
     /// Max distance for 3D audio
     pub max_distance: f32,
+    /// Falloff curve between min and max distance
+    #[serde(default)]
+    pub rolloff: AudioRolloff,
+    /// Mixer group the source plays through
+    #[serde(default = "default_audio_group")]
+    pub group: String,
+}
+
+fn default_audio_group() -> String {
+    "SFX".to_string()
 }
 
 impl Default for AudioSourceComponent {
@@ -479,6 +740,8 @@ impl Default for AudioSourceComponent {
             spatial: true,
             min_distance: 1.0,
             max_distance: 50.0,
+            rolloff: AudioRolloff::Linear,
+            group: default_audio_group(),
         }
     }
 }
@@ -501,6 +764,276 @@ impl Default for ScriptComponent {
     }
 }
 
+/// Polyline path through local-space points
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PathComponent {
+    /// Path points in the entity's local space
+    pub points: Vec<[f32; 3]>,
+    /// Whether the last point connects back to the first
+    #[serde(default)]
+    pub closed: bool,
+}
+
+impl Default for PathComponent {
+    fn default() -> Self {
+        Self {
+            points: vec![[0.0, 0.0, 0.0], [5.0, 0.0, 0.0]],
+            closed: false,
+        }
+    }
+}
+
+impl PathComponent {
+    /// Line segments of the path, including the closing segment
+    fn segments(&self) -> Vec<([f32; 3], [f32; 3])> {
+        let mut segments: Vec<_> = self.points.windows(2).map(|w| (w[0], w[1])).collect();
+        if self.closed && self.points.len() > 2 {
+            segments.push((self.points[self.points.len() - 1], self.points[0]));
+        }
+        segments
+    }
+
+    /// Total arc length
+    pub fn length(&self) -> f32 {
+        self.segments().iter().map(|(a, b)| distance(*a, *b)).sum()
+    }
+
+    /// Sample position and unit tangent at normalized arc length `t` (0..=1).
+    ///
+    /// Returns `None` for degenerate paths with no length.
+    pub fn sample(&self, t: f32) -> Option<([f32; 3], [f32; 3])> {
+        let total = self.length();
+        if total <= f32::EPSILON {
+            return None;
+        }
+
+        let mut remaining = t.clamp(0.0, 1.0) * total;
+        let segments: Vec<_> = self
+            .segments()
+            .into_iter()
+            .filter(|(a, b)| distance(*a, *b) > f32::EPSILON)
+            .collect();
+        let last = segments.len() - 1;
+
+        for (i, (a, b)) in segments.into_iter().enumerate() {
+            let length = distance(a, b);
+            if remaining <= length || i == last {
+                let f = (remaining / length).min(1.0);
+                let position = [
+                    a[0] + (b[0] - a[0]) * f,
+                    a[1] + (b[1] - a[1]) * f,
+                    a[2] + (b[2] - a[2]) * f,
+                ];
+                let tangent = [(b[0] - a[0]) / length, (b[1] - a[1]) / length, (b[2] - a[2]) / length];
+                return Some((position, tangent));
+            }
+            remaining -= length;
+        }
+
+        None
+    }
+
+    /// Normalized arc-length positions for `count` evenly spaced copies.
+    ///
+    /// Open paths include both ends; closed paths skip the end, which
+    /// coincides with the start.
+    pub fn distribution(&self, count: usize) -> Vec<f32> {
+        match count {
+            0 => Vec::new(),
+            1 => vec![0.0],
+            _ => {
+                let divisions = if self.closed { count } else { count - 1 };
+                (0..count).map(|i| i as f32 / divisions as f32).collect()
+            }
+        }
+    }
+}
+
+fn distance(a: [f32; 3], b: [f32; 3]) -> f32 {
+    ((b[0] - a[0]).powi(2) + (b[1] - a[1]).powi(2) + (b[2] - a[2]).powi(2)).sqrt()
+}
+
+/// Look-at constraint that keeps the entity facing a target entity
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct LookAtComponent {
+    /// Entity to face
+    #[serde(default)]
+    pub target: Option<EntityId>,
+}
+
+// ============================================================================
+// Collider Conversion
+// ============================================================================
+
+/// Collider component types that can be converted between
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColliderKind {
+    /// Box collider
+    Box,
+    /// Sphere collider
+    Sphere,
+    /// Capsule collider
+    Capsule,
+    /// Mesh collider
+    Mesh,
+}
+
+impl ColliderKind {
+    /// All collider kinds
+    pub fn all() -> &'static [ColliderKind] {
+        &[Self::Box, Self::Sphere, Self::Capsule, Self::Mesh]
+    }
+
+    /// Get display name
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::Box => "Box Collider",
+            Self::Sphere => "Sphere Collider",
+            Self::Capsule => "Capsule Collider",
+            Self::Mesh => "Mesh Collider",
+        }
+    }
+}
+
+/// Axis index for a capsule direction
+fn capsule_axis(direction: CapsuleDirection) -> usize {
+    match direction {
+        CapsuleDirection::X => 0,
+        CapsuleDirection::Y => 1,
+        CapsuleDirection::Z => 2,
+    }
+}
+
+/// Radius of the sphere enclosing the largest box dimension
+pub fn box_to_sphere_radius(size: [f32; 3]) -> f32 {
+    size.iter().copied().fold(0.0, f32::max) * 0.5
+}
+
+/// Capsule along the box's longest axis, wide enough for the other two
+pub fn box_to_capsule(size: [f32; 3]) -> (f32, f32, CapsuleDirection) {
+    let axis = (0..3).fold(1, |best, i| if size[i] > size[best] { i } else { best });
+    let direction = match axis {
+        0 => CapsuleDirection::X,
+        1 => CapsuleDirection::Y,
+        _ => CapsuleDirection::Z,
+    };
+    let radius = (0..3)
+        .filter(|&i| i != axis)
+        .map(|i| size[i])
+        .fold(0.0, f32::max)
+        * 0.5;
+    let height = size[axis].max(radius * 2.0);
+    (radius, height, direction)
+}
+
+/// Box that tightly fits a sphere
+pub fn sphere_to_box_size(radius: f32) -> [f32; 3] {
+    [radius * 2.0; 3]
+}
+
+/// Box that tightly fits a capsule
+pub fn capsule_to_box_size(radius: f32, height: f32, direction: CapsuleDirection) -> [f32; 3] {
+    let mut size = [radius * 2.0; 3];
+    size[capsule_axis(direction)] = height.max(radius * 2.0);
+    size
+}
+
+#[allow(dead_code)] // Intentionally kept for API completeness
+impl Component {
+    /// Get the collider kind if this is a collider component
+    pub fn collider_kind(&self) -> Option<ColliderKind> {
+        match self {
+            Component::BoxCollider(_) => Some(ColliderKind::Box),
+            Component::SphereCollider(_) => Some(ColliderKind::Sphere),
+            Component::CapsuleCollider(_) => Some(ColliderKind::Capsule),
+            Component::MeshCollider(_) => Some(ColliderKind::Mesh),
+            _ => None,
+        }
+    }
+
+    /// Local-space box (min, max) this component occupies, if it has one.
+    ///
+    /// Mesh geometry isn't loaded in the editor, so meshes count as a unit
+    /// cube like the picking proxies.
+    pub fn local_bounds(&self) -> Option<([f32; 3], [f32; 3])> {
+        let (center, size) = match self {
+            Component::BoxCollider(c) => (c.center, c.size),
+            Component::SphereCollider(c) => (c.center, sphere_to_box_size(c.radius)),
+            Component::CapsuleCollider(c) => (c.center, capsule_to_box_size(c.radius, c.height, c.direction)),
+            Component::MeshRenderer(c) if !c.mesh.is_empty() => ([0.0; 3], [1.0; 3]),
+            Component::MeshCollider(_) => ([0.0; 3], [1.0; 3]),
+            _ => return None,
+        };
+        let half = size.map(|v| v.abs() * 0.5);
+        Some((
+            std::array::from_fn(|i| center[i] - half[i]),
+            std::array::from_fn(|i| center[i] + half[i]),
+        ))
+    }
+
+    /// Convert a collider to another collider type.
+    ///
+    /// Center, trigger and layer settings carry over and the new shape is
+    /// estimated from the old one's bounds. `mesh` is used for mesh colliders
+    /// (typically the entity's `MeshRenderer` mesh).
+    pub fn convert_collider(&self, to: ColliderKind, mesh: Option<&str>) -> Option<Component> {
+        let (size, center, is_trigger, layer) = match self {
+            Component::BoxCollider(c) => (Some(c.size), c.center, c.is_trigger, c.layer),
+            Component::SphereCollider(c) => {
+                (Some(sphere_to_box_size(c.radius)), c.center, c.is_trigger, c.layer)
+            }
+            Component::CapsuleCollider(c) => (
+                Some(capsule_to_box_size(c.radius, c.height, c.direction)),
+                c.center,
+                c.is_trigger,
+                c.layer,
+            ),
+            // Mesh colliders have no analytic bounds
+            Component::MeshCollider(c) => (None, [0.0; 3], c.is_trigger, c.layer),
+            _ => return None,
+        };
+
+        let converted = match to {
+            ColliderKind::Box => Component::BoxCollider(BoxColliderComponent {
+                size: size.unwrap_or(BoxColliderComponent::default().size),
+                center,
+                is_trigger,
+                layer,
+            }),
+            ColliderKind::Sphere => Component::SphereCollider(SphereColliderComponent {
+                radius: size
+                    .map(box_to_sphere_radius)
+                    .unwrap_or(SphereColliderComponent::default().radius),
+                center,
+                is_trigger,
+                layer,
+            }),
+            ColliderKind::Capsule => {
+                let defaults = CapsuleColliderComponent::default();
+                let (radius, height, direction) = size
+                    .map(box_to_capsule)
+                    .unwrap_or((defaults.radius, defaults.height, defaults.direction));
+                Component::CapsuleCollider(CapsuleColliderComponent {
+                    radius,
+                    height,
+                    direction,
+                    center,
+                    is_trigger,
+                    layer,
+                })
+            }
+            ColliderKind::Mesh => Component::MeshCollider(MeshColliderComponent {
+                mesh: mesh.unwrap_or_default().to_string(),
+                is_trigger,
+                layer,
+                ..Default::default()
+            }),
+        };
+
+        Some(converted)
+    }
+}
+
 // ============================================================================
 // Component Registry
 // ============================================================================
@@ -586,6 +1119,20 @@ pub fn get_component_registry() -> Vec<ComponentInfo> {
             description: "Attaches gameplay logic",
             create_default: || Component::Script(ScriptComponent::default()),
         },
+        ComponentInfo {
+            type_id: "LookAt",
+            display_name: "Look At",
+            category: "Constraints",
+            description: "Keeps the entity facing a target entity",
+            create_default: || Component::LookAt(LookAtComponent::default()),
+        },
+        ComponentInfo {
+            type_id: "Path",
+            display_name: "Path",
+            category: "Utility",
+            description: "Polyline for placing and animating along",
+            create_default: || Component::Path(PathComponent::default()),
+        },
     ]
 }
 
@@ -604,7 +1151,7 @@ pub fn get_components_by_category() -> Vec<(&'static str, Vec<ComponentInfo>)> {
     }
 
     // Return in a specific order
-    let order = ["Rendering", "Physics", "Audio", "Scripting"];
+    let order = ["Rendering", "Physics", "Audio", "Scripting", "Constraints", "Utility"];
     let mut result = Vec::new();
 
     for cat in order {
@@ -620,3 +1167,134 @@ pub fn get_components_by_category() -> Vec<(&'static str, Vec<ComponentInfo>)> {
 
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_field_value_by_path() {
+        let mut light = Component::Light(LightComponent::default());
+        assert_eq!(light.field_value("intensity"), Some(serde_json::json!(1.0)));
+        assert_eq!(light.field_value("color.1"), Some(serde_json::json!(1.0)));
+        assert_eq!(light.field_value("missing"), None);
+
+        assert!(light.set_field_value("color.0", serde_json::json!(0.5)));
+        assert!(light.set_field_value("cast_shadows", serde_json::json!(false)));
+        // Wrong types and unknown fields leave the component alone
+        assert!(!light.set_field_value("intensity", serde_json::json!("bright")));
+        assert!(!light.set_field_value("brightness", serde_json::json!(2.0)));
+        assert!(!light.set_field_value("type", serde_json::json!("Camera")));
+
+        let Component::Light(light) = light else {
+            panic!("expected a light");
+        };
+        assert_eq!(light.color, [0.5, 1.0, 1.0]);
+        assert!(!light.cast_shadows);
+        assert_eq!(light.intensity, 1.0);
+    }
+
+    #[test]
+    fn test_serialized_round_trip() {
+        let components: Vec<Component> = get_component_registry().iter().map(|info| (info.create_default)()).collect();
+        let config = ron::ser::PrettyConfig::default().struct_names(true);
+        let ron_text = ron::ser::to_string_pretty(&components, config).unwrap();
+        assert_eq!(ron::from_str::<Vec<Component>>(&ron_text).unwrap(), components);
+        let json_text = serde_json::to_string(&components).unwrap();
+        assert_eq!(serde_json::from_str::<Vec<Component>>(&json_text).unwrap(), components);
+
+        // The tag needn't come first
+        let mut fields = serde_json::to_value(LightComponent::default()).unwrap();
+        fields["type"] = serde_json::json!("Light");
+        let json_text = serde_json::to_string(&fields).unwrap();
+        assert!(!json_text.starts_with(r#"{"type""#));
+        let light: Component = serde_json::from_str(&json_text).unwrap();
+        assert_eq!(light, Component::Light(LightComponent::default()));
+        assert!(serde_json::from_str::<Component>(r#"{"type": "Lamp"}"#).is_err());
+        assert!(serde_json::from_str::<Component>(r#"{"intensity": 3.0}"#).is_err());
+    }
+
+    #[test]
+    fn test_path_sample_uses_arc_length() {
+        let path = PathComponent {
+            points: vec![[0.0, 0.0, 0.0], [2.0, 0.0, 0.0], [10.0, 0.0, 0.0]],
+            closed: false,
+        };
+        assert_eq!(path.length(), 10.0);
+        assert_eq!(path.sample(0.5).unwrap().0, [5.0, 0.0, 0.0]);
+        assert_eq!(path.sample(1.0).unwrap(), ([10.0, 0.0, 0.0], [1.0, 0.0, 0.0]));
+        assert_eq!(path.distribution(3), vec![0.0, 0.5, 1.0]);
+        assert_eq!(path.distribution(1), vec![0.0]);
+
+        let degenerate = PathComponent {
+            points: vec![[1.0, 1.0, 1.0], [1.0, 1.0, 1.0]],
+            closed: false,
+        };
+        assert!(degenerate.sample(0.5).is_none());
+    }
+
+    #[test]
+    fn test_box_to_sphere_dimensions() {
+        assert_eq!(box_to_sphere_radius([1.0, 1.0, 1.0]), 0.5);
+        assert_eq!(box_to_sphere_radius([2.0, 4.0, 1.0]), 2.0);
+    }
+
+    #[test]
+    fn test_box_to_capsule_dimensions() {
+        // Tall box -> standing capsule
+        let (radius, height, direction) = box_to_capsule([1.0, 3.0, 0.5]);
+        assert_eq!(direction, CapsuleDirection::Y);
+        assert_eq!(radius, 0.5);
+        assert_eq!(height, 3.0);
+
+        // Long box along Z
+        let (radius, height, direction) = box_to_capsule([1.0, 2.0, 6.0]);
+        assert_eq!(direction, CapsuleDirection::Z);
+        assert_eq!(radius, 1.0);
+        assert_eq!(height, 6.0);
+
+        // Cube -> capsule never shorter than its diameter
+        let (radius, height, direction) = box_to_capsule([1.0, 1.0, 1.0]);
+        assert_eq!(direction, CapsuleDirection::Y);
+        assert_eq!(radius, 0.5);
+        assert_eq!(height, 1.0);
+    }
+
+    #[test]
+    fn test_capsule_round_trip_bounds() {
+        let size = capsule_to_box_size(0.5, 3.0, CapsuleDirection::Y);
+        assert_eq!(size, [1.0, 3.0, 1.0]);
+        assert_eq!(box_to_capsule(size), (0.5, 3.0, CapsuleDirection::Y));
+    }
+
+    #[test]
+    fn test_convert_collider_keeps_shared_fields() {
+        let collider = Component::BoxCollider(BoxColliderComponent {
+            size: [2.0, 2.0, 2.0],
+            center: [0.0, 1.0, 0.0],
+            is_trigger: true,
+            layer: 3,
+        });
+
+        let Some(Component::SphereCollider(sphere)) = collider.convert_collider(ColliderKind::Sphere, None) else {
+            panic!("expected sphere collider");
+        };
+        assert_eq!(sphere.radius, 1.0);
+        assert_eq!(sphere.center, [0.0, 1.0, 0.0]);
+        assert!(sphere.is_trigger);
+        assert_eq!(sphere.layer, 3);
+
+        let Some(Component::MeshCollider(mesh)) =
+            collider.convert_collider(ColliderKind::Mesh, Some("models/rock.glb"))
+        else {
+            panic!("expected mesh collider");
+        };
+        assert_eq!(mesh.mesh, "models/rock.glb");
+        assert!(mesh.is_trigger);
+        assert_eq!(mesh.layer, 3);
+
+        assert!(Component::Light(LightComponent::default())
+            .convert_collider(ColliderKind::Box, None)
+            .is_none());
+    }
+}
@@ -0,0 +1,154 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//! Entity reference fields and the eyedropper used to fill them.
+//!
+//! Components store references to other entities as `Option<EntityId>`.
+//! The inspector shows each reference with an eyedropper button; while the
+//! eyedropper is active, the next entity clicked in the viewport or the
+//! hierarchy is written into the field instead of changing the selection.
+
+use crate::state::{EntityId, SceneData};
+
+/// Display state of an entity reference field
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EntityRefStatus {
+    /// No entity referenced
+    Empty,
+    /// Reference resolves to an entity with this name
+    Resolved(String),
+    /// Referenced entity no longer exists
+    Missing,
+}
+
+impl EntityRefStatus {
+    /// Resolve a stored reference against the scene
+    pub fn resolve(reference: Option<EntityId>, scene: &SceneData) -> Self {
+        match reference {
+            None => Self::Empty,
+            Some(id) => scene
+                .get(&id)
+                .map_or(Self::Missing, |entity| Self::Resolved(entity.name.clone())),
+        }
+    }
+
+    /// Text shown in the field
+    pub fn label(&self) -> &str {
+        match self {
+            Self::Empty => "(None)",
+            Self::Resolved(name) => name,
+            Self::Missing => "(missing)",
+        }
+    }
+}
+
+/// Component field an eyedropper pick is written to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PickTarget {
+    /// Entity owning the component
+    pub entity: EntityId,
+    /// Index of the component on the entity
+    pub component_index: usize,
+    /// Name of the reference field
+    pub field: &'static str,
+}
+
+/// Eyedropper state for picking an entity into a reference field
+#[derive(Debug, Clone, Default)]
+pub struct EntityPicker {
+    target: Option<PickTarget>,
+}
+
+impl EntityPicker {
+    /// Start picking for `target`, replacing any pick in progress
+    pub fn begin(&mut self, target: PickTarget) {
+        self.target = Some(target);
+    }
+
+    /// Abort the current pick
+    pub fn cancel(&mut self) {
+        self.target = None;
+    }
+
+    /// Whether a pick is in progress
+    pub fn is_active(&self) -> bool {
+        self.target.is_some()
+    }
+
+    /// Whether a pick is in progress for `target`
+    pub fn is_picking(&self, target: &PickTarget) -> bool {
+        self.target.as_ref() == Some(target)
+    }
+
+    /// Finish the pick, returning the field the picked entity goes into.
+    ///
+    /// Picking the entity that owns the field is rejected and leaves the
+    /// eyedropper active.
+    pub fn finish(&mut self, picked: EntityId) -> Option<PickTarget> {
+        if self.target.as_ref()?.entity == picked {
+            return None;
+        }
+        self.target.take()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::EntityData;
+
+    fn target(entity: EntityId) -> PickTarget {
+        PickTarget {
+            entity,
+            component_index: 0,
+            field: "target",
+        }
+    }
+
+    #[test]
+    fn test_reference_status_transitions() {
+        let mut scene = SceneData::new();
+        let camera = scene.add_entity(EntityData::new("Camera"));
+        let player = scene.add_entity(EntityData::new("Player"));
+
+        let mut reference = None;
+        assert_eq!(EntityRefStatus::resolve(reference, &scene), EntityRefStatus::Empty);
+
+        reference = Some(player);
+        assert_eq!(
+            EntityRefStatus::resolve(reference, &scene),
+            EntityRefStatus::Resolved("Player".to_string())
+        );
+
+        scene.entities.shift_remove(&player);
+        let status = EntityRefStatus::resolve(reference, &scene);
+        assert_eq!(status, EntityRefStatus::Missing);
+        assert_eq!(status.label(), "(missing)");
+
+        reference = None;
+        assert_eq!(EntityRefStatus::resolve(reference, &scene), EntityRefStatus::Empty);
+        assert!(scene.get(&camera).is_some());
+    }
+
+    #[test]
+    fn test_picker_finish_and_cancel() {
+        let owner = EntityId::new();
+        let other = EntityId::new();
+        let mut picker = EntityPicker::default();
+
+        assert!(!picker.is_active());
+        assert_eq!(picker.finish(other), None);
+
+        picker.begin(target(owner));
+        assert!(picker.is_picking(&target(owner)));
+
+        // Self-reference keeps the eyedropper armed
+        assert_eq!(picker.finish(owner), None);
+        assert!(picker.is_active());
+
+        assert_eq!(picker.finish(other), Some(target(owner)));
+        assert!(!picker.is_active());
+
+        picker.begin(target(owner));
+        picker.cancel();
+        assert!(!picker.is_active());
+    }
+}
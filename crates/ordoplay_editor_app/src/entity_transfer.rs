@@ -0,0 +1,432 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//! Moving entity subtrees between scenes and editor instances.
+//!
+//! A subtree is serialized to RON with its root in world space.
+//! Importing gives every entity a fresh id, remaps parent, child and
+//! component references inside the subtree, and re-expresses the root
+//! transform relative to the drop target so the subtree keeps its world
+//! placement.
+//!
+//! Between editor instances a hierarchy drag carries the subtree as a
+//! [`DragPayload`] file in the user's [`drag_dir`], one file per editor
+//! process: it is written while the pointer is outside the source window
+//! and marked released when the drag ends there. The [`DragInbox`] of the
+//! editor the pointer then moves over imports it under the hierarchy row or
+//! viewport entity beneath the pointer. "Copy for Another Editor" in the hierarchy context menu puts
+//! the same RON on the OS clipboard for pasting instead.
+
+use crate::state::{EntityData, EntityId, SceneData};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+use thiserror::Error;
+
+/// Current subtree format version
+pub const SUBTREE_FORMAT_VERSION: u32 = 1;
+
+/// Directory under the user config dir that drag payloads are shared through
+const DRAG_DIR: &str = "entity_drags";
+
+/// Extension of drag payload files
+const DRAG_PAYLOAD_EXTENSION: &str = "ron";
+
+/// How long a released drag waits for another editor to take it
+const DRAG_PAYLOAD_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How often an editor looks for a released drag
+const DRAG_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Errors raised while reading a transferred subtree
+#[derive(Debug, Error)]
+pub enum TransferError {
+    /// The text isn't a serialized subtree
+    #[error("Not an entity subtree: {0}")]
+    Parse(String),
+    /// The subtree was written by a newer editor
+    #[error("Entity subtree version {found} is newer than supported version {supported}")]
+    UnsupportedVersion {
+        /// Version found in the payload
+        found: u32,
+        /// Newest version this editor understands
+        supported: u32,
+    },
+    /// The subtree has no entities
+    #[error("Entity subtree is empty")]
+    Empty,
+}
+
+/// A serialized entity and its descendants
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename = "EntitySubtree")]
+pub struct EntitySubtree {
+    /// Format version
+    pub version: u32,
+    /// Root first, then descendants depth-first. The root has no parent
+    /// and its transform is in world space.
+    pub entities: Vec<(EntityId, EntityData)>,
+}
+
+impl EntitySubtree {
+    /// Capture `root` and its descendants from `scene`
+    pub fn capture(scene: &SceneData, root: EntityId) -> Option<Self> {
+        let world = scene.world_transform(root)?;
+        let mut entities = Vec::new();
+        let mut visited = HashSet::new();
+        collect(scene, root, &mut entities, &mut visited);
+
+        let (_, root_data) = entities.first_mut()?;
+        root_data.parent = None;
+        root_data.transform = world;
+
+        Some(Self {
+            version: SUBTREE_FORMAT_VERSION,
+            entities,
+        })
+    }
+
+    /// Serialize to RON
+    pub fn to_ron(&self) -> Result<String, ron::Error> {
+        ron::to_string(self)
+    }
+
+    /// Parse a subtree serialized with [`EntitySubtree::to_ron`]
+    pub fn from_ron(text: &str) -> Result<Self, TransferError> {
+        let subtree: Self = ron::from_str(text).map_err(|e| TransferError::Parse(e.to_string()))?;
+        if subtree.version > SUBTREE_FORMAT_VERSION {
+            return Err(TransferError::UnsupportedVersion {
+                found: subtree.version,
+                supported: SUBTREE_FORMAT_VERSION,
+            });
+        }
+        if subtree.entities.is_empty() {
+            return Err(TransferError::Empty);
+        }
+        Ok(subtree)
+    }
+
+    /// Copies of the subtree's entities with fresh ids, ready to insert
+    /// into `scene` under `parent`. The root comes first.
+    pub fn instantiate(&self, scene: &SceneData, parent: Option<EntityId>) -> Vec<(EntityId, EntityData)> {
+        let id_map: HashMap<EntityId, EntityId> =
+            self.entities.iter().map(|(id, _)| (*id, EntityId::new())).collect();
+
+        self.entities
+            .iter()
+            .enumerate()
+            .map(|(index, (old_id, data))| {
+                let mut data = data.clone();
+                if index == 0 {
+                    data.parent = parent;
+                    data.transform = scene.local_transform(parent, &data.transform);
+                } else {
+                    data.parent = data.parent.and_then(|p| id_map.get(&p).copied());
+                }
+                data.children = data.children.iter().filter_map(|c| id_map.get(c).copied()).collect();
+
+                for component in &mut data.components {
+                    for (field, reference) in component.entity_references() {
+                        if let Some(new_id) = reference.and_then(|r| id_map.get(&r)) {
+                            component.set_entity_reference(field, Some(*new_id));
+                        }
+                    }
+                }
+
+                (id_map[old_id], data)
+            })
+            .collect()
+    }
+}
+
+/// A subtree dragged out of an editor window
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DragPayload {
+    /// Process id of the editor the drag started in
+    pub source: u32,
+    /// The drag ended outside the source window
+    pub released: bool,
+    /// Dragged entities
+    pub subtree: EntitySubtree,
+}
+
+impl DragPayload {
+    /// Payload of a drag in progress in this editor
+    pub fn new(subtree: EntitySubtree) -> Self {
+        Self {
+            source: std::process::id(),
+            released: false,
+            subtree,
+        }
+    }
+
+    /// File in `dir` holding the payload of the editor process `source`
+    fn path(dir: &Path, source: u32) -> PathBuf {
+        dir.join(format!("drag_{source}.{DRAG_PAYLOAD_EXTENSION}"))
+    }
+
+    /// Write the payload to `dir` for other editor instances
+    pub fn publish(&self, dir: &Path) -> std::io::Result<()> {
+        let text = ron::to_string(self)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string()))?;
+        std::fs::create_dir_all(dir)?;
+        std::fs::write(Self::path(dir, self.source), text)
+    }
+
+    /// Remove this editor's payload from `dir`, leaving other editors' alone
+    pub fn withdraw(dir: &Path) {
+        let _ = std::fs::remove_file(Self::path(dir, std::process::id()));
+    }
+
+    fn read(path: &Path) -> Option<Self> {
+        let text = std::fs::read_to_string(path).ok()?;
+        ron::from_str(&text).ok()
+    }
+}
+
+/// Per-user directory drag payloads are shared through, so other users
+/// on the machine can't plant or read them
+pub fn drag_dir() -> PathBuf {
+    crate::menus::user_config_dir().join(DRAG_DIR)
+}
+
+/// Picks up subtrees other editor instances released over this one
+#[derive(Debug)]
+pub struct DragInbox {
+    /// Directory the payload files are shared through
+    dir: PathBuf,
+    /// Last time the payload files were read
+    last_poll: Option<Instant>,
+    /// Released subtree waiting for a drop target, with its payload file
+    pending: Option<(PathBuf, EntitySubtree)>,
+}
+
+impl DragInbox {
+    /// Inbox sharing payloads through `dir`
+    pub fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            last_poll: None,
+            pending: None,
+        }
+    }
+
+    /// Look for a released payload from another editor, reading the
+    /// payload files at most every [`DRAG_POLL_INTERVAL`]. Returns whether
+    /// one is pending.
+    pub fn poll(&mut self) -> bool {
+        if self.last_poll.is_some_and(|last| last.elapsed() < DRAG_POLL_INTERVAL) {
+            return self.pending.is_some();
+        }
+        self.last_poll = Some(Instant::now());
+
+        let own = DragPayload::path(&self.dir, std::process::id());
+        let paths = std::fs::read_dir(&self.dir)
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| *path != own && path.extension().is_some_and(|ext| ext == DRAG_PAYLOAD_EXTENSION));
+
+        self.pending = None;
+        for path in paths {
+            let age = std::fs::metadata(&path)
+                .and_then(|metadata| metadata.modified())
+                .ok()
+                .and_then(|modified| SystemTime::now().duration_since(modified).ok());
+            match (DragPayload::read(&path), age) {
+                (Some(payload), Some(age)) if payload.released && age > DRAG_PAYLOAD_TIMEOUT => {
+                    // Nobody took it; don't let a later hover import it
+                    let _ = std::fs::remove_file(&path);
+                }
+                (Some(payload), Some(_)) if payload.released && self.pending.is_none() => {
+                    self.pending = Some((path, payload.subtree));
+                }
+                _ => {}
+            }
+        }
+        self.pending.is_some()
+    }
+
+    /// Claim the pending subtree. Its file is removed first, so when
+    /// several editors race for it only one imports it.
+    pub fn take(&mut self) -> Option<EntitySubtree> {
+        let (path, subtree) = self.pending.take()?;
+        std::fs::remove_file(path).ok()?;
+        Some(subtree)
+    }
+}
+
+fn collect(
+    scene: &SceneData,
+    id: EntityId,
+    entities: &mut Vec<(EntityId, EntityData)>,
+    visited: &mut HashSet<EntityId>,
+) {
+    if !visited.insert(id) {
+        return;
+    }
+    let Some(data) = scene.get(&id) else {
+        return;
+    };
+
+    entities.push((id, data.clone()));
+    for child in &data.children {
+        collect(scene, *child, entities, visited);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::{Component, LookAtComponent};
+    use crate::state::Transform;
+
+    fn entity(name: &str, parent: Option<EntityId>, position: [f32; 3]) -> EntityData {
+        EntityData {
+            name: name.to_string(),
+            parent,
+            transform: Transform {
+                position,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    fn add_child(scene: &mut SceneData, parent: EntityId, data: EntityData) -> EntityId {
+        let id = scene.add_entity(data);
+        scene.get_mut(&parent).unwrap().children.push(id);
+        id
+    }
+
+    #[test]
+    fn test_subtree_round_trip_with_fresh_ids() {
+        let mut source = SceneData::new();
+        let holder = source.add_entity(entity("Holder", None, [10.0, 0.0, 0.0]));
+        let root = add_child(&mut source, holder, entity("Turret", Some(holder), [1.0, 0.0, 0.0]));
+        let barrel = add_child(&mut source, root, entity("Barrel", Some(root), [0.0, 1.0, 0.0]));
+        let mut sight = entity("Sight", Some(root), [0.0, 0.0, 1.0]);
+        sight.components.push(Component::LookAt(LookAtComponent { target: Some(barrel) }));
+        let sight = add_child(&mut source, root, sight);
+
+        let text = EntitySubtree::capture(&source, root).unwrap().to_ron().unwrap();
+        let subtree = EntitySubtree::from_ron(&text).unwrap();
+        assert_eq!(subtree.entities.len(), 3);
+
+        let mut target = SceneData::new();
+        let imported = subtree.instantiate(&target, None);
+        for (id, data) in &imported {
+            target.insert_entity(*id, data.clone());
+        }
+
+        let ids: Vec<EntityId> = imported.iter().map(|(id, _)| *id).collect();
+        assert!(ids.iter().all(|id| ![holder, root, barrel, sight].contains(id)));
+
+        let (new_root, root_data) = &imported[0];
+        assert_eq!(root_data.parent, None);
+        assert_eq!(root_data.transform.position, [11.0, 0.0, 0.0]);
+        assert_eq!(root_data.children, vec![ids[1], ids[2]]);
+        for (_, child) in &imported[1..] {
+            assert_eq!(child.parent, Some(*new_root));
+        }
+        assert_eq!(imported[2].1.components[0].entity_references(), vec![("target", Some(ids[1]))]);
+        assert_eq!(target.world_position(ids[1]), Some([11.0, 1.0, 0.0]));
+    }
+
+    #[test]
+    fn test_drop_onto_entity_preserves_world_transform() {
+        let mut source = SceneData::new();
+        let root = source.add_entity(EntityData {
+            transform: Transform {
+                position: [4.0, 2.0, 0.0],
+                rotation: [0.0, 90.0, 0.0],
+                scale: [2.0, 2.0, 2.0],
+            },
+            ..Default::default()
+        });
+        let subtree = EntitySubtree::capture(&source, root).unwrap();
+
+        let mut target = SceneData::new();
+        let parent = target.add_entity(EntityData {
+            transform: Transform {
+                position: [1.0, 0.0, 0.0],
+                rotation: [0.0, 0.0, 90.0],
+                scale: [0.5, 0.5, 0.5],
+            },
+            ..Default::default()
+        });
+
+        let imported = subtree.instantiate(&target, Some(parent));
+        let (id, data) = imported[0].clone();
+        assert_eq!(data.parent, Some(parent));
+        target.insert_entity(id, data);
+        target.get_mut(&parent).unwrap().children.push(id);
+
+        let world = target.world_transform(id).unwrap();
+        let close = |a: [f32; 3], b: [f32; 3]| a.iter().zip(b).all(|(x, y)| (x - y).abs() < 1e-3);
+        assert!(close(world.position, [4.0, 2.0, 0.0]), "{:?}", world.position);
+        assert!(close(world.scale, [2.0, 2.0, 2.0]), "{:?}", world.scale);
+        // Compare orientations by where they send the axes
+        let expected = &subtree.entities[0].1.transform;
+        for axis in [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]] {
+            assert!(close(world.rotate(axis), expected.rotate(axis)));
+        }
+    }
+
+    #[test]
+    fn test_released_drag_is_taken_once_by_another_editor() {
+        let dir = std::env::temp_dir().join(format!("entity_drag_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut scene = SceneData::new();
+        let root = scene.add_entity(entity("Crate", None, [1.0, 2.0, 3.0]));
+        let mut payload = DragPayload::new(EntitySubtree::capture(&scene, root).unwrap());
+
+        // Still dragging, or dropped back by this editor: nothing to take
+        payload.publish(&dir).unwrap();
+        assert!(!DragInbox::new(dir.clone()).poll());
+        payload.released = true;
+        payload.publish(&dir).unwrap();
+        assert!(!DragInbox::new(dir.clone()).poll());
+
+        payload.source = std::process::id().wrapping_add(1);
+        payload.publish(&dir).unwrap();
+        let mut inbox = DragInbox::new(dir.clone());
+        assert!(inbox.poll());
+        let taken = inbox.take().unwrap();
+        assert_eq!(taken.entities[0].1.transform.position, [1.0, 2.0, 3.0]);
+        assert!(DragInbox::new(dir.clone()).take().is_none());
+        assert!(!DragPayload::path(&dir, payload.source).exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_concurrent_drags_keep_their_own_payloads() {
+        let dir = std::env::temp_dir().join(format!("entity_drag_{}", uuid::Uuid::new_v4()));
+        let mut scene = SceneData::new();
+        let crate_id = scene.add_entity(entity("Crate", None, [1.0, 0.0, 0.0]));
+        let barrel = scene.add_entity(entity("Barrel", None, [2.0, 0.0, 0.0]));
+
+        for (offset, root) in [(1, crate_id), (2, barrel)] {
+            let mut payload = DragPayload::new(EntitySubtree::capture(&scene, root).unwrap());
+            payload.source = std::process::id().wrapping_add(offset);
+            payload.released = true;
+            payload.publish(&dir).unwrap();
+        }
+
+        let mut inbox = DragInbox::new(dir.clone());
+        let mut names = Vec::new();
+        while inbox.poll() {
+            names.push(inbox.take().unwrap().entities[0].1.name.clone());
+            inbox.last_poll = None;
+        }
+        names.sort();
+        assert_eq!(names, ["Barrel", "Crate"]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_rejects_other_text() {
+        assert!(matches!(EntitySubtree::from_ron("hello"), Err(TransferError::Parse(_))));
+    }
+}
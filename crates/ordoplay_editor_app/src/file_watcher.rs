@@ -3,6 +3,11 @@
 //!
 //! Provides debounced file system events for hot-reloading assets
 //! and keeping the asset browser in sync with the filesystem.
+//!
+//! Some editors save with several writes in a row, which still arrive as
+//! separate events. [`FileWatcherManager`] holds each path's events back
+//! until the path has been quiet for [`COALESCE_WINDOW`] and then emits a
+//! single merged event.
 
 
 use notify_debouncer_full::{
@@ -15,7 +20,10 @@ use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 use std::sync::mpsc::{self, Receiver, TryRecvError};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+
+/// How long a path must be quiet before its merged event is emitted
+pub const COALESCE_WINDOW: Duration = Duration::from_millis(200);
 
 /// Events emitted by the file watcher
 #[allow(dead_code)] // Intentionally kept for API completeness
@@ -245,6 +253,87 @@ impl FileWatcher {
     }
 }
 
+impl FileEvent {
+    /// Path the event is about, or `None` for errors and renames
+    fn coalesce_path(&self) -> Option<&Path> {
+        match self {
+            Self::Created(path) | Self::Modified(path) | Self::Deleted(path) => Some(path),
+            Self::Renamed(..) | Self::Error(_) => None,
+        }
+    }
+
+    /// Fold a later event for the same path into this one
+    fn merge(self, later: Self) -> Self {
+        match (self, later) {
+            // Still new to anyone who hasn't seen it yet
+            (Self::Created(path), Self::Modified(_)) => Self::Created(path),
+            // Replaced in place, as by a write-then-rename save
+            (Self::Deleted(path), Self::Created(_)) => Self::Modified(path),
+            (_, later) => later,
+        }
+    }
+}
+
+/// Holds file events back until their path has been quiet for a window,
+/// merging the events that arrive meanwhile
+#[derive(Debug)]
+pub struct EventCoalescer {
+    /// Quiet time required before an event is released
+    window: Duration,
+    /// Merged event per path with the time of its latest event, in arrival order
+    pending: Vec<(FileEvent, Instant)>,
+    /// Events released on the next drain without merging
+    immediate: Vec<FileEvent>,
+}
+
+impl EventCoalescer {
+    /// Create a coalescer with the given quiet window
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            pending: Vec::new(),
+            immediate: Vec::new(),
+        }
+    }
+
+    /// Queue an event received at `now`
+    pub fn push(&mut self, event: FileEvent, now: Instant) {
+        let Some(path) = event.coalesce_path() else {
+            // Nothing to merge with
+            self.immediate.push(event);
+            return;
+        };
+
+        let existing = self
+            .pending
+            .iter()
+            .position(|(pending, _)| pending.coalesce_path() == Some(path));
+        match existing {
+            Some(index) => {
+                let (pending, _) = self.pending.remove(index);
+                self.pending.push((pending.merge(event), now));
+            }
+            None => self.pending.push((event, now)),
+        }
+    }
+
+    /// Take the events whose path has been quiet for the window
+    pub fn drain_ready(&mut self, now: Instant) -> Vec<FileEvent> {
+        let (ready, waiting): (Vec<_>, Vec<_>) = std::mem::take(&mut self.pending)
+            .into_iter()
+            .partition(|(_, last)| now.duration_since(*last) >= self.window);
+        self.pending = waiting;
+        let mut events = std::mem::take(&mut self.immediate);
+        events.extend(ready.into_iter().map(|(event, _)| event));
+        events
+    }
+
+    /// Check if events are waiting for their window to pass
+    pub fn has_pending(&self) -> bool {
+        !self.pending.is_empty() || !self.immediate.is_empty()
+    }
+}
+
 /// Manages file watchers and aggregates events
 #[allow(dead_code)] // Intentionally kept for API completeness
 pub struct FileWatcherManager {
@@ -256,6 +345,8 @@ pub struct FileWatcherManager {
     modified_paths: HashSet<PathBuf>,
     /// Paths that need thumbnail refresh
     needs_thumbnail_refresh: HashSet<PathBuf>,
+    /// Events waiting for their path to settle
+    coalescer: EventCoalescer,
 }
 
 #[allow(dead_code)] // Intentionally kept for API completeness
@@ -267,6 +358,7 @@ impl FileWatcherManager {
             pending_events: Vec::new(),
             modified_paths: HashSet::new(),
             needs_thumbnail_refresh: HashSet::new(),
+            coalescer: EventCoalescer::new(COALESCE_WINDOW),
         }
     }
 
@@ -283,38 +375,49 @@ impl FileWatcherManager {
         Ok(())
     }
 
-    /// Poll all watchers for events
+    /// Poll all watchers for events. Events are only returned once their
+    /// path has been quiet for [`COALESCE_WINDOW`], merged into one.
     pub fn poll(&mut self) -> &[FileEvent] {
         self.pending_events.clear();
         self.modified_paths.clear();
 
+        let now = Instant::now();
         for watcher in &self.watchers {
-            let events = watcher.poll_events();
-            for event in events {
-                match &event {
-                    FileEvent::Created(path) | FileEvent::Modified(path) => {
-                        self.modified_paths.insert(path.clone());
-                        self.needs_thumbnail_refresh.insert(path.clone());
-                    }
-                    FileEvent::Deleted(path) => {
-                        self.modified_paths.insert(path.clone());
-                        self.needs_thumbnail_refresh.remove(path);
-                    }
-                    FileEvent::Renamed(old, new) => {
-                        self.modified_paths.insert(old.clone());
-                        self.modified_paths.insert(new.clone());
-                        self.needs_thumbnail_refresh.remove(old);
-                        self.needs_thumbnail_refresh.insert(new.clone());
-                    }
-                    FileEvent::Error(_) => {}
+            for event in watcher.poll_events() {
+                self.coalescer.push(event, now);
+            }
+        }
+
+        for event in self.coalescer.drain_ready(now) {
+            match &event {
+                FileEvent::Created(path) | FileEvent::Modified(path) => {
+                    self.modified_paths.insert(path.clone());
+                    self.needs_thumbnail_refresh.insert(path.clone());
                 }
-                self.pending_events.push(event);
+                FileEvent::Deleted(path) => {
+                    self.modified_paths.insert(path.clone());
+                    self.needs_thumbnail_refresh.remove(path);
+                }
+                FileEvent::Renamed(old, new) => {
+                    self.modified_paths.insert(old.clone());
+                    self.modified_paths.insert(new.clone());
+                    self.needs_thumbnail_refresh.remove(old);
+                    self.needs_thumbnail_refresh.insert(new.clone());
+                }
+                FileEvent::Error(_) => {}
             }
+            self.pending_events.push(event);
         }
 
         &self.pending_events
     }
 
+    /// Check if events are held back waiting to settle, so callers keep
+    /// polling
+    pub fn has_pending(&self) -> bool {
+        self.coalescer.has_pending()
+    }
+
     /// Check if any events occurred
     pub fn has_events(&self) -> bool {
         !self.pending_events.is_empty()
@@ -365,4 +468,35 @@ mod tests {
         assert!(config.extensions.contains("glb"));
         assert!(config.extensions.contains("wgsl"));
     }
+
+    #[test]
+    fn test_rapid_modifications_coalesce() {
+        let path = PathBuf::from("/project/assets/crate.png");
+        let start = Instant::now();
+        let mut coalescer = EventCoalescer::new(COALESCE_WINDOW);
+
+        for offset in [0, 50, 100] {
+            coalescer.push(FileEvent::Modified(path.clone()), start + Duration::from_millis(offset));
+        }
+
+        // The window restarts with every event
+        assert!(coalescer.drain_ready(start + Duration::from_millis(250)).is_empty());
+        let events = coalescer.drain_ready(start + Duration::from_millis(300));
+        assert_eq!(events.len(), 1);
+        assert!(matches!(&events[0], FileEvent::Modified(p) if *p == path));
+        assert!(!coalescer.has_pending());
+    }
+
+    #[test]
+    fn test_replaced_file_reports_modified() {
+        let path = PathBuf::from("/project/assets/level.scene");
+        let start = Instant::now();
+        let mut coalescer = EventCoalescer::new(COALESCE_WINDOW);
+
+        coalescer.push(FileEvent::Deleted(path.clone()), start);
+        coalescer.push(FileEvent::Created(path.clone()), start);
+
+        let events = coalescer.drain_ready(start + COALESCE_WINDOW);
+        assert!(matches!(&events[..], [FileEvent::Modified(p)] if *p == path));
+    }
 }
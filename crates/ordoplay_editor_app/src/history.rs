@@ -7,12 +7,18 @@
 
 use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use thiserror::Error;
 
 /// Maximum undo history depth
 const MAX_HISTORY: usize = 100;
 
+/// Default cap on the memory held by undo snapshots
+const MAX_HISTORY_BYTES: usize = 256 * 1024 * 1024;
+
+/// How long after an edit a matching edit still merges into it
+pub const COALESCE_WINDOW: Duration = Duration::from_millis(500);
+
 /// History errors
 #[derive(Debug, Error)]
 pub enum HistoryError {
@@ -183,6 +189,8 @@ pub struct HistoryStats {
     pub memory_used: usize,
     /// Maximum history depth
     pub max_depth: usize,
+    /// Maximum memory held by the undo stack (bytes)
+    pub max_bytes: usize,
 }
 
 /// Undo/redo history manager
@@ -196,8 +204,16 @@ pub struct History {
     next_id: u64,
     /// Maximum history depth
     max_depth: usize,
+    /// Maximum memory held by the undo stack
+    max_bytes: usize,
     /// Total memory used
     memory_used: usize,
+    /// Number of groups evicted by the limits since the last clear
+    evicted: usize,
+    /// Incremented on every commit, undo, redo, and clear
+    revision: u64,
+    /// Coalescing key of the newest undo group and when it last changed
+    coalesce: Option<(String, Instant)>,
 }
 
 impl History {
@@ -213,7 +229,40 @@ impl History {
             redo_stack: VecDeque::new(),
             next_id: 1,
             max_depth,
+            max_bytes: MAX_HISTORY_BYTES,
             memory_used: 0,
+            evicted: 0,
+            revision: 0,
+            coalesce: None,
+        }
+    }
+
+    /// Set the maximum number of undo groups and the memory they may hold.
+    ///
+    /// The oldest groups are evicted immediately if the history is over
+    /// either limit; the newest group is always kept.
+    #[allow(dead_code)] // Intentionally kept for API completeness
+    pub fn set_limits(&mut self, max_operations: usize, max_bytes: usize) {
+        self.max_depth = max_operations.max(1);
+        self.max_bytes = max_bytes;
+        self.enforce_limits();
+    }
+
+    /// Approximate memory held by undo and redo snapshots (bytes)
+    #[allow(dead_code)] // Intentionally kept for API completeness
+    pub fn memory_estimate(&self) -> usize {
+        self.memory_used + self.redo_stack.iter().map(OperationGroup::memory_size).sum::<usize>()
+    }
+
+    /// Evict the oldest undo groups until both limits hold
+    fn enforce_limits(&mut self) {
+        while self.undo_stack.len() > self.max_depth
+            || (self.memory_used > self.max_bytes && self.undo_stack.len() > 1)
+        {
+            if let Some(old_group) = self.undo_stack.pop_front() {
+                self.memory_used = self.memory_used.saturating_sub(old_group.memory_size());
+                self.evicted += 1;
+            }
         }
     }
 
@@ -232,18 +281,59 @@ impl History {
 
         // Clear redo stack
         self.redo_stack.clear();
+        self.revision += 1;
+        self.coalesce = None;
 
         // Add to undo stack
         self.memory_used += group.memory_size();
         self.undo_stack.push_back(group);
 
-        // Enforce history limit
-        while self.undo_stack.len() > self.max_depth {
-            if let Some(old_group) = self.undo_stack.pop_front() {
-                self.memory_used = self.memory_used.saturating_sub(old_group.memory_size());
-            }
+        // Enforce history limits
+        self.enforce_limits();
+
+        Ok(())
+    }
+
+    /// Commit an operation group, merging it into the previous one when
+    /// both share `key` and arrive within [`COALESCE_WINDOW`]
+    pub fn push_coalescing(&mut self, group: OperationGroup, key: &str) -> Result<()> {
+        self.push_coalescing_at(group, key, Instant::now())
+    }
+
+    /// [`History::push_coalescing`] with an explicit arrival time
+    pub fn push_coalescing_at(&mut self, group: OperationGroup, key: &str, now: Instant) -> Result<()> {
+        if group.operations.is_empty() {
+            return Ok(());
         }
 
+        let mergeable = self.redo_stack.is_empty()
+            && self.coalesce.as_ref().is_some_and(|(last_key, at)| {
+                last_key == key && now.saturating_duration_since(*at) <= COALESCE_WINDOW
+            })
+            && self
+                .undo_stack
+                .back()
+                .is_some_and(|last| last.operations.len() == group.operations.len());
+
+        if !mergeable {
+            self.commit(group)?;
+            self.coalesce = Some((key.to_string(), now));
+            return Ok(());
+        }
+
+        if let Some(last) = self.undo_stack.back_mut() {
+            // Keep the older "before" and take the newer "after"
+            self.memory_used = self.memory_used.saturating_sub(last.memory_size());
+            for (existing, newer) in last.operations.iter_mut().zip(group.operations) {
+                existing.after = newer.after;
+                existing.timestamp = newer.timestamp;
+            }
+            last.timestamp = group.timestamp;
+            self.memory_used += last.memory_size();
+        }
+        self.enforce_limits();
+        self.revision += 1;
+        self.coalesce = Some((key.to_string(), now));
         Ok(())
     }
 
@@ -256,6 +346,8 @@ impl History {
 
         self.memory_used = self.memory_used.saturating_sub(group.memory_size());
         self.redo_stack.push_back(group.clone());
+        self.revision += 1;
+        self.coalesce = None;
 
         Ok(group)
     }
@@ -269,6 +361,8 @@ impl History {
 
         self.memory_used += group.memory_size();
         self.undo_stack.push_back(group.clone());
+        self.revision += 1;
+        self.coalesce = None;
 
         Ok(group)
     }
@@ -283,6 +377,24 @@ impl History {
         !self.redo_stack.is_empty()
     }
 
+    /// All recorded groups, oldest first: the undone groups follow the
+    /// applied ones in the order they would be redone
+    pub fn entries(&self) -> impl Iterator<Item = &OperationGroup> {
+        self.undo_stack.iter().chain(self.redo_stack.iter().rev())
+    }
+
+    /// Number of groups currently applied, i.e. the position in
+    /// [`History::entries`] the editor state is at
+    pub fn current_index(&self) -> usize {
+        self.undo_stack.len()
+    }
+
+    /// Number of groups evicted by the history limits, so the oldest entry
+    /// no longer starts from the initial state
+    pub fn evicted_count(&self) -> usize {
+        self.evicted
+    }
+
     /// Get undo stack depth
     #[allow(dead_code)] // Intentionally kept for API completeness
     pub fn undo_depth(&self) -> usize {
@@ -300,6 +412,14 @@ impl History {
         self.undo_stack.clear();
         self.redo_stack.clear();
         self.memory_used = 0;
+        self.evicted = 0;
+        self.revision += 1;
+        self.coalesce = None;
+    }
+
+    /// Revision counter that changes whenever the history changes
+    pub fn revision(&self) -> u64 {
+        self.revision
     }
 
     /// Get history statistics
@@ -310,6 +430,7 @@ impl History {
             redo_count: self.redo_stack.len(),
             memory_used: self.memory_used,
             max_depth: self.max_depth,
+            max_bytes: self.max_bytes,
         }
     }
 
@@ -329,3 +450,101 @@ impl Default for History {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn group(history: &mut History, before: u8, after: u8) -> OperationGroup {
+        let id = history.begin_operation("Nudge");
+        let mut group = OperationGroup::new(id, "Nudge".to_string());
+        group.add_operation(Operation::new(
+            id,
+            "Nudge".to_string(),
+            StateSnapshot::new(vec![before]),
+            StateSnapshot::new(vec![after]),
+        ));
+        group
+    }
+
+    #[test]
+    fn test_rapid_pushes_coalesce() {
+        let mut history = History::new();
+        let start = Instant::now();
+
+        for step in 0..5u8 {
+            let group = group(&mut history, step, step + 1);
+            let at = start + Duration::from_millis(100 * u64::from(step));
+            history.push_coalescing_at(group, "Nudge:a", at).unwrap();
+        }
+
+        assert_eq!(history.undo_depth(), 1);
+        let merged = history.undo().unwrap();
+        assert_eq!(merged.operations[0].before.data, vec![0]);
+        assert_eq!(merged.operations[0].after.data, vec![5]);
+        assert!(!history.can_undo());
+    }
+
+    #[test]
+    fn test_coalescing_respects_key_and_window() {
+        let mut history = History::new();
+        let start = Instant::now();
+
+        let first = group(&mut history, 0, 1);
+        history.push_coalescing_at(first, "Nudge:a", start).unwrap();
+        let other_entity = group(&mut history, 1, 2);
+        history.push_coalescing_at(other_entity, "Nudge:b", start).unwrap();
+        let late = group(&mut history, 2, 3);
+        history
+            .push_coalescing_at(late, "Nudge:b", start + COALESCE_WINDOW * 2)
+            .unwrap();
+        assert_eq!(history.undo_depth(), 3);
+
+        // A plain commit in between stops merging
+        let plain = group(&mut history, 3, 4);
+        history.commit(plain).unwrap();
+        let after_plain = group(&mut history, 4, 5);
+        history
+            .push_coalescing_at(after_plain, "Nudge:b", start + COALESCE_WINDOW * 2)
+            .unwrap();
+        assert_eq!(history.undo_depth(), 5);
+    }
+
+    #[test]
+    fn test_entries_span_undo_and_redo() {
+        let mut history = History::new();
+        for step in 0..3u8 {
+            let group = group(&mut history, step, step + 1);
+            history.commit(group).unwrap();
+        }
+        history.undo().unwrap();
+        history.undo().unwrap();
+
+        let befores: Vec<u8> = history.entries().map(|g| g.operations[0].before.data[0]).collect();
+        assert_eq!(befores, vec![0, 1, 2]);
+        assert_eq!(history.current_index(), 1);
+    }
+
+    #[test]
+    fn test_limits_evict_oldest_groups() {
+        let mut history = History::new();
+        history.set_limits(3, usize::MAX);
+        for step in 0..5u8 {
+            let group = group(&mut history, step, step + 1);
+            history.commit(group).unwrap();
+        }
+        assert_eq!(history.undo_depth(), 3);
+        assert_eq!(history.evicted_count(), 2);
+
+        // Each group holds two one-byte snapshots
+        history.set_limits(10, 4);
+        assert_eq!(history.undo_depth(), 2);
+        assert_eq!(history.evicted_count(), 3);
+        assert_eq!(history.memory_estimate(), 4);
+
+        assert_eq!(history.undo().unwrap().operations[0].before.data, vec![4]);
+        assert_eq!(history.undo().unwrap().operations[0].before.data, vec![3]);
+        assert!(!history.can_undo());
+        assert_eq!(history.memory_estimate(), 4);
+    }
+}
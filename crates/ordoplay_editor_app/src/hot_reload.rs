@@ -3,9 +3,12 @@
 //!
 //! Monitors file changes and automatically reloads modified assets,
 //! including textures, materials, shaders, and scripts.
-
+//!
+//! Bursts of writes to the same file are coalesced by [`ReloadQueue`]
+//! before a reload is requested.
 
 use crate::file_watcher::{FileEvent, FileWatcherManager};
+use crate::state::{EntityId, SceneData};
 use parking_lot::RwLock;
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
@@ -18,6 +21,8 @@ use std::time::{Duration, Instant};
 pub enum HotReloadAssetType {
     /// Texture files (png, jpg, etc.)
     Texture,
+    /// Mesh files (gltf, obj, etc.)
+    Mesh,
     /// Shader files (wgsl, glsl, hlsl)
     Shader,
     /// Material files
@@ -26,6 +31,8 @@ pub enum HotReloadAssetType {
     Script,
     /// Scene files
     Scene,
+    /// Prefab files
+    Prefab,
     /// Animation files
     Animation,
     /// Audio files
@@ -42,6 +49,8 @@ impl HotReloadAssetType {
             // Textures
             "png" | "jpg" | "jpeg" | "gif" | "bmp" | "tga" | "dds" | "ktx2" | "exr" | "hdr"
             | "webp" => Self::Texture,
+            // Meshes
+            "gltf" | "glb" | "obj" | "fbx" | "mesh" => Self::Mesh,
             // Shaders
             "wgsl" | "glsl" | "hlsl" | "spv" | "vert" | "frag" | "comp" => Self::Shader,
             // Materials
@@ -50,6 +59,8 @@ impl HotReloadAssetType {
             "lua" | "wasm" | "rs" => Self::Script,
             // Scenes
             "scene" | "ron" => Self::Scene,
+            // Prefabs
+            "prefab" => Self::Prefab,
             // Animations
             "anim" | "animation" => Self::Animation,
             // Audio
@@ -302,6 +313,149 @@ impl Default for HotReloadManager {
     }
 }
 
+/// Debounced queue of modified paths.
+///
+/// Editors often save in bursts (write temp, rename, touch), so each new
+/// event for a path restarts its timer. A path is only released once it
+/// has been quiet for the debounce duration, producing a single reload.
+#[derive(Debug, Clone)]
+pub struct ReloadQueue {
+    /// Last time each path was reported as modified
+    pending: HashMap<PathBuf, Instant>,
+    /// Quiet period required before a path is reloaded
+    debounce: Duration,
+}
+
+#[allow(dead_code)] // Intentionally kept for API completeness
+impl ReloadQueue {
+    /// Default quiet period before reloading
+    pub const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(250);
+
+    /// Create a queue with the given debounce duration
+    pub fn new(debounce: Duration) -> Self {
+        Self {
+            pending: HashMap::new(),
+            debounce,
+        }
+    }
+
+    /// Record a modification of a path at the given time
+    pub fn push(&mut self, path: PathBuf, at: Instant) {
+        self.pending.insert(path, at);
+    }
+
+    /// Record modifications of several paths at the given time
+    pub fn extend(&mut self, paths: impl IntoIterator<Item = PathBuf>, at: Instant) {
+        for path in paths {
+            self.push(path, at);
+        }
+    }
+
+    /// Take all paths that have been quiet for the debounce duration
+    pub fn drain_ready(&mut self, now: Instant) -> Vec<PathBuf> {
+        let debounce = self.debounce;
+        let mut ready: Vec<PathBuf> = self
+            .pending
+            .iter()
+            .filter(|(_, &at)| now.saturating_duration_since(at) >= debounce)
+            .map(|(path, _)| path.clone())
+            .collect();
+        for path in &ready {
+            self.pending.remove(path);
+        }
+        ready.sort();
+        ready
+    }
+
+    /// Number of paths waiting for their debounce window
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Check if no paths are waiting
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+}
+
+impl Default for ReloadQueue {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_DEBOUNCE)
+    }
+}
+
+/// What to do with a batch of debounced modifications
+#[derive(Debug, Clone, Default)]
+pub struct ReloadRequest {
+    /// Meshes, textures and materials whose cached resources must be reloaded
+    pub assets: Vec<PathBuf>,
+    /// Entities referencing any of the modified assets
+    pub affected_entities: Vec<EntityId>,
+    /// Scene files that changed on disk (prompt before reloading)
+    pub scenes: Vec<PathBuf>,
+    /// Prefab files that changed on disk (prompt before reloading)
+    pub prefabs: Vec<PathBuf>,
+    /// Shader sources whose pipelines must be recompiled
+    pub shaders: Vec<PathBuf>,
+}
+
+#[allow(dead_code)] // Intentionally kept for API completeness
+impl ReloadRequest {
+    /// Categorize modified paths and find the scene entities they affect
+    pub fn new(paths: &[PathBuf], scene: &SceneData) -> Self {
+        let mut request = Self::default();
+
+        for path in paths {
+            // Edited import settings reload the asset they belong to
+            let path = &crate::import_settings::asset_for_meta(path).unwrap_or_else(|| path.clone());
+            match HotReloadAssetType::from_path(path) {
+                HotReloadAssetType::Mesh
+                | HotReloadAssetType::Texture
+                | HotReloadAssetType::Material => {
+                    if !request.assets.contains(path) {
+                        request.assets.push(path.clone());
+                    }
+                }
+                HotReloadAssetType::Scene => request.scenes.push(path.clone()),
+                HotReloadAssetType::Prefab => request.prefabs.push(path.clone()),
+                // Deleted shaders keep their last compiled pipelines
+                HotReloadAssetType::Shader if path.is_file() => request.shaders.push(path.clone()),
+                _ => {}
+            }
+        }
+
+        request.affected_entities = entities_referencing(scene, &request.assets);
+        request
+    }
+
+    /// Check if nothing needs reloading
+    pub fn is_empty(&self) -> bool {
+        self.assets.is_empty() && self.scenes.is_empty() && self.prefabs.is_empty() && self.shaders.is_empty()
+    }
+}
+
+/// Find entities whose components reference any of the given asset paths.
+///
+/// Component paths are usually project-relative, so a modified path matches
+/// when it ends with the referenced path.
+pub fn entities_referencing(scene: &SceneData, paths: &[PathBuf]) -> Vec<EntityId> {
+    let references = |asset: &str| {
+        !asset.is_empty() && paths.iter().any(|p| p.ends_with(Path::new(asset)))
+    };
+
+    scene
+        .entities
+        .iter()
+        .filter(|(_, entity)| {
+            entity
+                .components
+                .iter()
+                .any(|component| component.asset_paths().into_iter().any(references))
+        })
+        .map(|(id, _)| *id)
+        .collect()
+}
+
 /// Integration helper for connecting hot reload to the editor
 #[allow(dead_code)] // Intentionally kept for API completeness
 pub struct HotReloadIntegration {
@@ -380,6 +534,95 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_reload_queue_coalesces_burst() {
+        let mut queue = ReloadQueue::new(Duration::from_millis(100));
+        let path = PathBuf::from("assets/textures/brick.png");
+        let start = Instant::now();
+
+        // Editor saves in a burst of writes 20ms apart
+        for i in 0..5 {
+            queue.push(path.clone(), start + Duration::from_millis(i * 20));
+        }
+        assert_eq!(queue.len(), 1);
+
+        // Still inside the quiet window of the last write
+        assert!(queue.drain_ready(start + Duration::from_millis(150)).is_empty());
+
+        // Single reload once the burst settles
+        let ready = queue.drain_ready(start + Duration::from_millis(180));
+        assert_eq!(ready, vec![path]);
+        assert!(queue.is_empty());
+        assert!(queue.drain_ready(start + Duration::from_secs(1)).is_empty());
+    }
+
+    #[test]
+    fn test_reload_queue_releases_paths_independently() {
+        let mut queue = ReloadQueue::new(Duration::from_millis(100));
+        let start = Instant::now();
+        let a = PathBuf::from("a.png");
+        let b = PathBuf::from("b.png");
+
+        queue.push(a.clone(), start);
+        queue.push(b.clone(), start + Duration::from_millis(80));
+
+        assert_eq!(queue.drain_ready(start + Duration::from_millis(100)), vec![a]);
+        assert_eq!(queue.drain_ready(start + Duration::from_millis(180)), vec![b]);
+    }
+
+    #[test]
+    fn test_reload_request_finds_affected_entities() {
+        use crate::components::{Component, MeshRendererComponent};
+        use crate::state::EntityData;
+
+        let mut scene = SceneData::new();
+        let user = EntityId::new();
+        scene.entities.insert(user, EntityData {
+            components: vec![Component::MeshRenderer(MeshRendererComponent {
+                mesh: "models/crate.glb".to_string(),
+                ..Default::default()
+            })],
+            ..Default::default()
+        });
+        scene.entities.insert(EntityId::new(), EntityData::default());
+
+        let paths = vec![
+            PathBuf::from("/project/assets/models/crate.glb"),
+            PathBuf::from("/project/levels/main.scene"),
+        ];
+        let request = ReloadRequest::new(&paths, &scene);
+
+        assert_eq!(request.assets, vec![paths[0].clone()]);
+        assert_eq!(request.scenes, vec![paths[1].clone()]);
+        assert_eq!(request.affected_entities, vec![user]);
+    }
+
+    #[test]
+    fn test_reload_request_maps_meta_to_asset() {
+        let texture = PathBuf::from("/project/assets/textures/brick.png");
+        let paths = vec![
+            PathBuf::from("/project/assets/textures/brick.png.meta"),
+            texture.clone(),
+        ];
+        let request = ReloadRequest::new(&paths, &SceneData::new());
+        assert_eq!(request.assets, vec![texture]);
+    }
+
+    #[test]
+    fn test_reload_request_collects_existing_shaders() {
+        let dir = std::env::temp_dir().join(format!("hot_reload_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let shader = dir.join("water.wgsl");
+        std::fs::write(&shader, "@fragment fn main() {}").unwrap();
+
+        let paths = vec![shader.clone(), dir.join("deleted.wgsl")];
+        let request = ReloadRequest::new(&paths, &SceneData::new());
+        assert_eq!(request.shaders, vec![shader]);
+        assert!(!request.is_empty());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
     #[test]
     fn test_manager_creation() {
         let manager = HotReloadManager::new();
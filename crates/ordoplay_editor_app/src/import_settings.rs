@@ -0,0 +1,332 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//! Per-asset import settings stored in `.meta` sidecar files.
+//!
+//! An asset such as `textures/brick.png` keeps its settings in
+//! `textures/brick.png.meta`, a RON file written with defaults the first
+//! time the asset browser sees the asset. The sidecar follows the asset
+//! when it is renamed, moved or deleted.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Extension appended to an asset's file name to form its sidecar
+pub const META_EXTENSION: &str = "meta";
+
+/// Color space the texels of a texture are stored in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ColorSpace {
+    /// Color data (albedo, UI); decoded to linear when sampled
+    #[default]
+    Srgb,
+    /// Non-color data (normal, roughness, masks) and HDR images
+    Linear,
+}
+
+impl ColorSpace {
+    /// Display name
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Srgb => "sRGB",
+            Self::Linear => "Linear",
+        }
+    }
+}
+
+/// Texture sampling filter
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum TextureFilter {
+    /// Bilinear filtering
+    #[default]
+    Linear,
+    /// Nearest-neighbour, for pixel art
+    Nearest,
+}
+
+impl TextureFilter {
+    /// Display name
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Linear => "Linear",
+            Self::Nearest => "Nearest",
+        }
+    }
+
+    /// Filter used when scaling the image down
+    pub fn resize_filter(&self) -> image::imageops::FilterType {
+        match self {
+            Self::Linear => image::imageops::FilterType::Lanczos3,
+            Self::Nearest => image::imageops::FilterType::Nearest,
+        }
+    }
+
+    /// Filter used when sampling the uploaded texture
+    pub fn sampler_filter(&self) -> egui_wgpu::wgpu::FilterMode {
+        match self {
+            Self::Linear => egui_wgpu::wgpu::FilterMode::Linear,
+            Self::Nearest => egui_wgpu::wgpu::FilterMode::Nearest,
+        }
+    }
+}
+
+/// Import settings for textures
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TextureImportSettings {
+    /// Color space of the stored texels
+    pub color_space: ColorSpace,
+    /// Generate a full mip chain on import
+    pub generate_mipmaps: bool,
+    /// Sampling filter
+    pub filter: TextureFilter,
+    /// Largest width or height; bigger images are scaled down
+    pub max_size: u32,
+}
+
+impl Default for TextureImportSettings {
+    fn default() -> Self {
+        Self {
+            color_space: ColorSpace::Srgb,
+            generate_mipmaps: true,
+            filter: TextureFilter::Linear,
+            max_size: 4096,
+        }
+    }
+}
+
+impl TextureImportSettings {
+    /// Defaults for a texture, guessed from its name and format: HDR images
+    /// and maps named like `_normal` or `_roughness` hold linear data
+    pub fn for_texture(path: &Path) -> Self {
+        let ext = extension(path);
+        let stem = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        let is_data_map = ["_normal", "_nrm", "_n", "_roughness", "_metallic", "_ao", "_mask", "_height"]
+            .iter()
+            .any(|suffix| stem.ends_with(suffix));
+
+        let color_space = if is_data_map || matches!(ext.as_str(), "hdr" | "exr") {
+            ColorSpace::Linear
+        } else {
+            ColorSpace::Srgb
+        };
+        Self { color_space, ..Self::default() }
+    }
+}
+
+/// Import settings for meshes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ModelImportSettings {
+    /// Uniform scale applied to the imported geometry
+    pub scale: f32,
+    /// Compute normals when the file has none
+    pub generate_normals: bool,
+    /// Create materials for the ones the file defines
+    pub import_materials: bool,
+}
+
+impl Default for ModelImportSettings {
+    fn default() -> Self {
+        Self {
+            scale: 1.0,
+            generate_normals: true,
+            import_materials: true,
+        }
+    }
+}
+
+/// Import settings for audio clips
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioImportSettings {
+    /// Stream from disk instead of decoding up front
+    pub streaming: bool,
+    /// Downmix to a single channel
+    pub force_mono: bool,
+}
+
+/// Import settings of an asset, by asset type
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ImportSettings {
+    Texture(TextureImportSettings),
+    Model(ModelImportSettings),
+    Audio(AudioImportSettings),
+}
+
+impl ImportSettings {
+    /// Default settings for an asset, or `None` for asset types without
+    /// import settings
+    pub fn for_asset(path: &Path) -> Option<Self> {
+        match extension(path).as_str() {
+            "png" | "jpg" | "jpeg" | "bmp" | "tga" | "dds" | "ktx2" | "exr" | "hdr" => {
+                Some(Self::Texture(TextureImportSettings::for_texture(path)))
+            }
+            "glb" | "gltf" | "obj" | "fbx" | "dae" => Some(Self::Model(ModelImportSettings::default())),
+            "wav" | "mp3" | "ogg" | "flac" => Some(Self::Audio(AudioImportSettings::default())),
+            _ => None,
+        }
+    }
+
+    /// Display name of the asset type
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Texture(_) => "Texture",
+            Self::Model(_) => "Model",
+            Self::Audio(_) => "Audio",
+        }
+    }
+
+    /// Read an asset's sidecar, or `None` when it has none
+    pub fn load(asset: &Path) -> std::io::Result<Option<Self>> {
+        let content = match std::fs::read_to_string(meta_path(asset)) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        ron::from_str(&content)
+            .map(Some)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string()))
+    }
+
+    /// Read an asset's sidecar, or its default settings without writing
+    /// one; the sidecar is created when the settings are first saved
+    pub fn load_or_default(asset: &Path) -> std::io::Result<Option<Self>> {
+        Ok(Self::load(asset)?.or_else(|| Self::for_asset(asset)))
+    }
+
+    /// Write the sidecar of an asset
+    pub fn save(&self, asset: &Path) -> std::io::Result<()> {
+        let content = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string()))?;
+        std::fs::write(meta_path(asset), content)
+    }
+}
+
+/// Import settings of a texture: its sidecar if readable, defaults otherwise
+pub fn texture_settings(path: &Path) -> TextureImportSettings {
+    match ImportSettings::load(path) {
+        Ok(Some(ImportSettings::Texture(settings))) => settings,
+        Ok(_) => TextureImportSettings::for_texture(path),
+        Err(e) => {
+            tracing::warn!("Ignoring unreadable import settings for {}: {}", path.display(), e);
+            TextureImportSettings::for_texture(path)
+        }
+    }
+}
+
+/// Sidecar path of an asset: its file name with `.meta` appended
+pub fn meta_path(asset: &Path) -> PathBuf {
+    let mut name = asset.as_os_str().to_os_string();
+    name.push(".");
+    name.push(META_EXTENSION);
+    PathBuf::from(name)
+}
+
+/// Check if a path is a sidecar file
+pub fn is_meta_file(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == META_EXTENSION)
+}
+
+/// Asset a sidecar belongs to, or `None` if the path isn't a sidecar
+pub fn asset_for_meta(path: &Path) -> Option<PathBuf> {
+    is_meta_file(path).then(|| path.with_extension(""))
+}
+
+/// Move an asset's sidecar after the asset moved from `from` to `to`.
+/// Folders carry their sidecars along, so only files are handled.
+pub fn move_meta(from: &Path, to: &Path) -> std::io::Result<()> {
+    let source = meta_path(from);
+    if to.is_dir() || !source.exists() {
+        return Ok(());
+    }
+    std::fs::rename(source, meta_path(to))
+}
+
+/// Delete an asset's sidecar, if it has one
+pub fn remove_meta(asset: &Path) -> std::io::Result<()> {
+    match std::fs::remove_file(meta_path(asset)) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+fn extension(path: &Path) -> String {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_lowercase)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("import_settings_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_meta_path_round_trip() {
+        let asset = Path::new("assets/textures/brick.png");
+        let meta = meta_path(asset);
+        assert_eq!(meta, PathBuf::from("assets/textures/brick.png.meta"));
+        assert!(is_meta_file(&meta));
+        assert!(!is_meta_file(asset));
+        assert_eq!(asset_for_meta(&meta).as_deref(), Some(asset));
+        assert_eq!(asset_for_meta(asset), None);
+    }
+
+    #[test]
+    fn test_defaults_by_asset_type() {
+        let texture = |name: &str| match ImportSettings::for_asset(Path::new(name)) {
+            Some(ImportSettings::Texture(settings)) => settings,
+            other => panic!("expected texture settings for {}, got {:?}", name, other),
+        };
+        assert_eq!(texture("brick.png").color_space, ColorSpace::Srgb);
+        assert_eq!(texture("brick_normal.PNG").color_space, ColorSpace::Linear);
+        assert_eq!(texture("sky.hdr").color_space, ColorSpace::Linear);
+
+        assert!(matches!(ImportSettings::for_asset(Path::new("crate.glb")), Some(ImportSettings::Model(_))));
+        assert!(matches!(ImportSettings::for_asset(Path::new("step.ogg")), Some(ImportSettings::Audio(_))));
+        assert_eq!(ImportSettings::for_asset(Path::new("level.scene")), None);
+    }
+
+    #[test]
+    fn test_load_or_default_does_not_write_a_sidecar() {
+        let dir = temp_dir();
+        let asset = dir.join("step.ogg");
+        std::fs::write(&asset, b"").unwrap();
+
+        let settings = ImportSettings::load_or_default(&asset).unwrap();
+        assert_eq!(settings, ImportSettings::for_asset(&asset));
+        assert!(!meta_path(&asset).exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_move_and_remove_meta() {
+        let dir = temp_dir();
+        let from = dir.join("a.png");
+        let to = dir.join("b.png");
+        ImportSettings::for_asset(&from).unwrap().save(&from).unwrap();
+        std::fs::write(&to, b"").unwrap();
+
+        move_meta(&from, &to).unwrap();
+        assert!(!meta_path(&from).exists());
+        assert!(meta_path(&to).exists());
+
+        // Assets without a sidecar move without error
+        move_meta(&from, &to).unwrap();
+
+        remove_meta(&to).unwrap();
+        assert!(!meta_path(&to).exists());
+        remove_meta(&to).unwrap();
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -16,25 +16,37 @@
 //! the `OrdoPlay` runtime via shared crates. It uses egui for UI with
 //! `egui_dock` for panel docking.
 
+mod animation_ghost;
 mod app;
+mod asset_paths;
+mod asset_references;
+mod asset_templates;
 mod audio;
 mod build;
 mod commands;
 mod components;
+mod entity_ref;
+mod entity_transfer;
 mod file_watcher;
 mod history;
 mod hot_reload;
+mod import_settings;
 mod menus;
+mod mesh_combine;
+mod mesh_stats;
+mod mesh_thumbnail;
 mod panel_types;
 mod panels;
 mod physics;
 mod play_mode;
 mod prefab;
 mod project;
+mod scene_migration;
 mod state;
 mod theme;
 mod thumbnail;
 mod tools;
+mod validation;
 mod viewport_renderer;
 
 use app::EditorApp;
@@ -1,8 +1,10 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 //! Menu definitions, command palette, and keyboard shortcuts.
 
-
-use std::collections::HashMap;
+use crate::panel_types::PanelType;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
 
 /// A command that can be executed from the command palette
 #[derive(Clone)]
@@ -17,6 +19,8 @@ pub struct Command {
     pub shortcut: Option<&'static str>,
     /// Description shown as hint
     pub description: Option<&'static str>,
+    /// Panels the command's shortcut applies in (empty = global)
+    pub contexts: &'static [PanelType],
 }
 
 impl Command {
@@ -28,6 +32,7 @@ impl Command {
             category,
             shortcut: None,
             description: None,
+            contexts: &[],
         }
     }
 
@@ -43,12 +48,39 @@ impl Command {
         self
     }
 
+    /// Tag the panels this command's shortcut applies in
+    pub const fn with_contexts(mut self, contexts: &'static [PanelType]) -> Self {
+        self.contexts = contexts;
+        self
+    }
+
+    /// Whether the command applies in `panel`
+    pub fn applies_to(&self, panel: PanelType) -> bool {
+        self.contexts.contains(&panel)
+    }
+
     /// Get display text for fuzzy matching
     pub fn display_text(&self) -> String {
         format!("{}: {}", self.category, self.name)
     }
 }
 
+/// Panels where scene edits can be undone
+const SCENE_EDITING: &[PanelType] = &[PanelType::Viewport, PanelType::Hierarchy, PanelType::Inspector];
+/// Panels that act on the entity selection
+const SCENE_SELECTION: &[PanelType] = &[PanelType::Viewport, PanelType::Hierarchy];
+/// Panels where Delete removes the selection (entities or graph nodes)
+const DELETABLE: &[PanelType] = &[
+    PanelType::Viewport,
+    PanelType::Hierarchy,
+    PanelType::MaterialGraph,
+    PanelType::GameplayGraph,
+];
+/// Viewport only
+const VIEWPORT: &[PanelType] = &[PanelType::Viewport];
+/// Hierarchy only
+const HIERARCHY: &[PanelType] = &[PanelType::Hierarchy];
+
 /// Registry of all available commands
 pub struct CommandRegistry {
     commands: Vec<Command>,
@@ -81,18 +113,31 @@ impl CommandRegistry {
         // Edit commands
         registry.register(Command::new("edit.undo", "Undo", "Edit")
             .with_shortcut("Ctrl+Z")
+            .with_contexts(SCENE_EDITING)
             .with_description("Undo the last action"));
         registry.register(Command::new("edit.redo", "Redo", "Edit")
             .with_shortcut("Ctrl+Y")
+            .with_contexts(SCENE_EDITING)
             .with_description("Redo the last undone action"));
         registry.register(Command::new("edit.delete", "Delete", "Edit")
             .with_shortcut("Delete")
+            .with_contexts(DELETABLE)
             .with_description("Delete selected entities"));
         registry.register(Command::new("edit.duplicate", "Duplicate", "Edit")
             .with_shortcut("Ctrl+D")
+            .with_contexts(SCENE_SELECTION)
             .with_description("Duplicate selected entities"));
+        registry.register(Command::new("edit.group", "Group Selected", "Edit")
+            .with_shortcut("Ctrl+G")
+            .with_contexts(SCENE_SELECTION)
+            .with_description("Group selected entities under a new empty parent"));
+        registry.register(Command::new("edit.ungroup", "Ungroup", "Edit")
+            .with_shortcut("Ctrl+Shift+G")
+            .with_contexts(SCENE_SELECTION)
+            .with_description("Move the children of selected groups up and remove the groups"));
         registry.register(Command::new("edit.select_all", "Select All", "Edit")
             .with_shortcut("Ctrl+A")
+            .with_contexts(SCENE_SELECTION)
             .with_description("Select all entities"));
 
         // View commands
@@ -100,18 +145,51 @@ impl CommandRegistry {
             .with_description("Reset panel layout to default"));
         registry.register(Command::new("view.focus_selection", "Focus Selection", "View")
             .with_shortcut("F")
+            .with_contexts(SCENE_SELECTION)
             .with_description("Focus camera on selected entities"));
+        registry.register(Command::new("view.toggle_projection", "Toggle Perspective/Orthographic", "View")
+            .with_shortcut("5")
+            .with_contexts(VIEWPORT)
+            .with_description("Switch the viewport camera between perspective and orthographic"));
+        for (id, name, shortcut, description) in [
+            ("view.camera_front", "Front View", "1", "Orthographic view from the front"),
+            ("view.camera_back", "Back View", "Ctrl+1", "Orthographic view from the back"),
+            ("view.camera_right", "Right View", "3", "Orthographic view from the right"),
+            ("view.camera_left", "Left View", "Ctrl+3", "Orthographic view from the left"),
+            ("view.camera_top", "Top View", "7", "Orthographic view from above"),
+            ("view.camera_bottom", "Bottom View", "Ctrl+7", "Orthographic view from below"),
+        ] {
+            registry.register(Command::new(id, name, "View")
+                .with_shortcut(shortcut)
+                .with_contexts(VIEWPORT)
+                .with_description(description));
+        }
 
         // Transform commands
         registry.register(Command::new("transform.translate", "Translate Mode", "Transform")
             .with_shortcut("W")
+            .with_contexts(VIEWPORT)
             .with_description("Switch to translate gizmo"));
         registry.register(Command::new("transform.rotate", "Rotate Mode", "Transform")
             .with_shortcut("E")
+            .with_contexts(VIEWPORT)
             .with_description("Switch to rotate gizmo"));
         registry.register(Command::new("transform.scale", "Scale Mode", "Transform")
             .with_shortcut("R")
+            .with_contexts(VIEWPORT)
             .with_description("Switch to scale gizmo"));
+        registry.register(Command::new("transform.grab_x", "Translate Along X", "Transform")
+            .with_shortcut("G, X")
+            .with_contexts(VIEWPORT)
+            .with_description("Translate constrained to the X axis"));
+        registry.register(Command::new("transform.grab_y", "Translate Along Y", "Transform")
+            .with_shortcut("G, Y")
+            .with_contexts(VIEWPORT)
+            .with_description("Translate constrained to the Y axis"));
+        registry.register(Command::new("transform.grab_z", "Translate Along Z", "Transform")
+            .with_shortcut("G, Z")
+            .with_contexts(VIEWPORT)
+            .with_description("Translate constrained to the Z axis"));
         registry.register(Command::new("transform.toggle_space", "Toggle Local/World Space", "Transform")
             .with_description("Toggle between local and world coordinate space"));
         registry.register(Command::new("transform.toggle_snap", "Toggle Grid Snap", "Transform")
@@ -122,7 +200,29 @@ impl CommandRegistry {
             .with_description("Create a new entity"));
         registry.register(Command::new("entity.rename", "Rename Entity", "Entity")
             .with_shortcut("F2")
+            .with_contexts(HIERARCHY)
             .with_description("Rename the selected entity"));
+        registry.register(Command::new("entity.drop_to_ground", "Drop to Ground", "Entity")
+            .with_shortcut("End")
+            .with_contexts(VIEWPORT)
+            .with_description("Move selected entities onto the ground plane"));
+
+        // Tools commands
+        registry.register(Command::new("tools.reload_shaders", "Reload Shaders", "Tools")
+            .with_description("Recompile the viewport shader from disk"));
+        registry.register(Command::new("tools.snap_to_ground", "Snap to Ground", "Tools")
+            .with_shortcut("Shift+End")
+            .with_contexts(VIEWPORT)
+            .with_description("Move selected entities onto the colliders below them"));
+        registry.register(Command::new("tools.snap_to_ground_aligned", "Snap to Ground (Align to Surface)", "Tools")
+            .with_description("Move selected entities onto the colliders below them, tilted to the surface"));
+
+        // UI commands
+        registry.register(Command::new("ui.command_palette", "Command Palette", "UI")
+            .with_shortcut("Ctrl+P")
+            .with_description("Search and run editor commands"));
+        registry.register(Command::new("ui.keyboard_shortcuts", "Keyboard Shortcuts", "UI")
+            .with_description("View and rebind keyboard shortcuts"));
 
         // Panel commands
         registry.register(Command::new("panel.viewport", "Show Viewport", "Panel")
@@ -137,6 +237,12 @@ impl CommandRegistry {
             .with_description("Show the console panel"));
         registry.register(Command::new("panel.profiler", "Show Profiler", "Panel")
             .with_description("Show the profiler panel"));
+        registry.register(Command::new("panel.history", "Show History", "Panel")
+            .with_description("Show the undo history panel"));
+        registry.register(Command::new("panel.material_graph", "Show Material Graph", "Panel")
+            .with_description("Show the material graph editor"));
+        registry.register(Command::new("panel.gameplay_graph", "Show Gameplay Graph", "Panel")
+            .with_description("Show the gameplay graph editor"));
 
         registry
     }
@@ -161,30 +267,50 @@ impl CommandRegistry {
     }
 
     /// Search commands with fuzzy matching
+    #[allow(dead_code)] // Intentionally kept for API completeness
     pub fn search(&self, query: &str) -> Vec<&Command> {
-        if query.is_empty() {
-            return self.commands.iter().collect();
-        }
+        self.search_with_recent(query, &[])
+    }
 
+    /// Search commands with fuzzy matching, ranking `recent` commands
+    /// (most recent first) above others. With an empty query the recent
+    /// commands come first, then the rest in registration order.
+    pub fn search_with_recent(&self, query: &str, recent: &[&str]) -> Vec<&Command> {
         let query_lower = query.to_lowercase();
         let mut results: Vec<(&Command, i32)> = self.commands
             .iter()
             .filter_map(|cmd| {
-                let score = fuzzy_score(&cmd.display_text().to_lowercase(), &query_lower);
+                let score = fuzzy_score(&cmd.name.to_lowercase(), &query_lower)
+                    .max(fuzzy_score(&cmd.display_text().to_lowercase(), &query_lower));
                 if score > 0 {
-                    Some((cmd, score))
+                    Some((cmd, score + recency_bonus(recent, cmd.id)))
                 } else {
                     None
                 }
             })
             .collect();
 
-        // Sort by score (higher is better)
+        // Sort by score (higher is better); ties keep registration order
         results.sort_by(|a, b| b.1.cmp(&a.1));
         results.into_iter().map(|(cmd, _)| cmd).collect()
     }
 }
 
+/// Maximum number of recently used commands remembered by the palette
+pub const MAX_RECENT_COMMANDS: usize = 10;
+
+/// File the palette's recent commands are stored in, inside [`user_config_dir`]
+pub const RECENT_COMMANDS_FILE: &str = "recent_commands.ron";
+
+/// Score bonus for a command's position in the recent list
+fn recency_bonus(recent: &[&str], command_id: &str) -> i32 {
+    recent
+        .iter()
+        .take(MAX_RECENT_COMMANDS)
+        .position(|id| *id == command_id)
+        .map_or(0, |rank| (MAX_RECENT_COMMANDS - rank) as i32 * 2)
+}
+
 impl Default for CommandRegistry {
     fn default() -> Self {
         Self::new()
@@ -418,17 +544,18 @@ impl Shortcut {
             "left" | "arrowleft" => Some(egui::Key::ArrowLeft),
             "right" | "arrowright" => Some(egui::Key::ArrowRight),
             // Numbers (top row)
-            "0" => Some(egui::Key::Num0),
-            "1" => Some(egui::Key::Num1),
-            "2" => Some(egui::Key::Num2),
-            "3" => Some(egui::Key::Num3),
-            "4" => Some(egui::Key::Num4),
-            "5" => Some(egui::Key::Num5),
-            "6" => Some(egui::Key::Num6),
-            "7" => Some(egui::Key::Num7),
-            "8" => Some(egui::Key::Num8),
-            "9" => Some(egui::Key::Num9),
-            _ => None,
+            "0" | "num0" => Some(egui::Key::Num0),
+            "1" | "num1" => Some(egui::Key::Num1),
+            "2" | "num2" => Some(egui::Key::Num2),
+            "3" | "num3" => Some(egui::Key::Num3),
+            "4" | "num4" => Some(egui::Key::Num4),
+            "5" | "num5" => Some(egui::Key::Num5),
+            "6" | "num6" => Some(egui::Key::Num6),
+            "7" | "num7" => Some(egui::Key::Num7),
+            "8" | "num8" => Some(egui::Key::Num8),
+            "9" | "num9" => Some(egui::Key::Num9),
+            // Any other key by its egui name (e.g. "Minus", "OpenBracket")
+            _ => egui::Key::from_name(s),
         }
     }
 }
@@ -465,15 +592,193 @@ pub enum ShortcutContext {
     NonTextInput,
 }
 
+/// A short sequence of shortcuts pressed one after another (e.g. G then X)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ShortcutSequence {
+    /// Shortcuts in the order they must be pressed
+    pub keys: Vec<Shortcut>,
+}
+
+#[allow(dead_code)] // Intentionally kept for API completeness
+impl ShortcutSequence {
+    /// Create a sequence from its keys
+    pub fn new(keys: impl Into<Vec<Shortcut>>) -> Self {
+        Self { keys: keys.into() }
+    }
+
+    /// Get display string for this sequence (e.g., "G, X")
+    pub fn display(&self) -> String {
+        self.keys.iter().map(Shortcut::display).collect::<Vec<_>>().join(", ")
+    }
+}
+
+/// Outcome of feeding a key press to a [`SequenceMatcher`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SequenceStep {
+    /// The key started or continued a sequence; waiting for more keys
+    Pending,
+    /// The key completed a sequence
+    Matched(&'static str),
+    /// A sequence was pending and the key didn't continue it
+    Cancelled,
+    /// No sequence involved; the key is free for single-chord shortcuts
+    NoMatch,
+}
+
+/// State machine matching key sequences.
+///
+/// Time is passed in by the caller (seconds, e.g. `egui::InputState::time`)
+/// so the matcher stays pure and testable.
+#[derive(Debug, Clone)]
+pub struct SequenceMatcher {
+    /// Registered sequences and their commands
+    sequences: Vec<(ShortcutSequence, &'static str)>,
+    /// Keys pressed so far in the pending sequence
+    pending: Vec<Shortcut>,
+    /// Time of the last key in the pending sequence
+    last_key_time: f64,
+    /// Maximum time between keys (seconds)
+    timeout: f64,
+}
+
+#[allow(dead_code)] // Intentionally kept for API completeness
+impl SequenceMatcher {
+    /// Default maximum time between keys of a sequence (seconds)
+    pub const DEFAULT_TIMEOUT: f64 = 1.0;
+
+    /// Create a matcher with the given timeout between keys
+    pub fn new(timeout: f64) -> Self {
+        Self {
+            sequences: Vec::new(),
+            pending: Vec::new(),
+            last_key_time: 0.0,
+            timeout,
+        }
+    }
+
+    /// Register a sequence for a command
+    pub fn register(&mut self, command_id: &'static str, sequence: ShortcutSequence) {
+        self.sequences.retain(|(existing, _)| *existing != sequence);
+        self.sequences.push((sequence, command_id));
+    }
+
+    /// Feed a key press at the given time
+    pub fn feed(&mut self, shortcut: Shortcut, time: f64) -> SequenceStep {
+        self.expire(time);
+
+        let mut candidate = self.pending.clone();
+        candidate.push(shortcut);
+
+        if let Some((_, command_id)) = self.sequences.iter().find(|(seq, _)| seq.keys == candidate) {
+            self.pending.clear();
+            return SequenceStep::Matched(command_id);
+        }
+
+        let is_prefix = self.sequences.iter().any(|(seq, _)| {
+            seq.keys.len() > candidate.len() && seq.keys.starts_with(&candidate)
+        });
+        if is_prefix {
+            self.pending = candidate;
+            self.last_key_time = time;
+            return SequenceStep::Pending;
+        }
+
+        if self.pending.is_empty() {
+            SequenceStep::NoMatch
+        } else {
+            self.pending.clear();
+            SequenceStep::Cancelled
+        }
+    }
+
+    /// Drop the pending sequence if the timeout has elapsed.
+    /// Returns true if a pending sequence was dropped.
+    pub fn expire(&mut self, time: f64) -> bool {
+        if !self.pending.is_empty() && time - self.last_key_time > self.timeout {
+            self.pending.clear();
+            return true;
+        }
+        false
+    }
+
+    /// Cancel any pending sequence
+    pub fn cancel(&mut self) {
+        self.pending.clear();
+    }
+
+    /// Check if a sequence is in progress
+    pub fn is_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// Keys pressed so far in the pending sequence
+    pub fn pending(&self) -> &[Shortcut] {
+        &self.pending
+    }
+
+    /// Maximum time between keys (seconds)
+    pub fn timeout(&self) -> f64 {
+        self.timeout
+    }
+
+    /// Get the sequences registered for a command
+    pub fn sequences_for<'a>(&'a self, command_id: &'a str) -> impl Iterator<Item = &'a ShortcutSequence> + 'a {
+        self.sequences
+            .iter()
+            .filter(move |(_, id)| *id == command_id)
+            .map(|(seq, _)| seq)
+    }
+}
+
+impl Default for SequenceMatcher {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_TIMEOUT)
+    }
+}
+
+/// Per-user editor configuration directory: `%APPDATA%\OrdoPlay` on
+/// Windows, `~/Library/Application Support/OrdoPlay` on macOS and
+/// `$XDG_CONFIG_HOME/ordoplay` (default `~/.config/ordoplay`) elsewhere.
+/// Falls back to `.ordoplay` in the working directory when the platform's
+/// variables aren't set.
+pub fn user_config_dir() -> PathBuf {
+    let var = |name: &str| std::env::var_os(name).filter(|value| !value.is_empty()).map(PathBuf::from);
+    let dir = if cfg!(windows) {
+        var("APPDATA").map(|dir| dir.join("OrdoPlay"))
+    } else if cfg!(target_os = "macos") {
+        var("HOME").map(|home| home.join("Library/Application Support/OrdoPlay"))
+    } else {
+        var("XDG_CONFIG_HOME")
+            .or_else(|| var("HOME").map(|home| home.join(".config")))
+            .map(|dir| dir.join("ordoplay"))
+    };
+    dir.unwrap_or_else(|| PathBuf::from(".ordoplay"))
+}
+
+/// File the shortcut overrides are stored in, inside [`user_config_dir`]
+pub const SHORTCUTS_FILE: &str = "shortcuts.ron";
+
+/// User shortcut overrides as stored in [`SHORTCUTS_FILE`]
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ShortcutOverrides {
+    /// Command ID -> shortcut display string (None = unbound)
+    bindings: BTreeMap<String, Option<String>>,
+}
+
 /// Registry of keyboard shortcuts
 #[allow(dead_code)] // Intentionally kept for API completeness
 pub struct ShortcutRegistry {
-    /// All bindings, indexed by shortcut
-    bindings: HashMap<Shortcut, ShortcutBinding>,
+    /// All bindings in registration order. A shortcut appears more than
+    /// once while it is in conflict; the first binding wins.
+    bindings: Vec<ShortcutBinding>,
     /// Lookup from command ID to shortcuts
     command_shortcuts: HashMap<&'static str, Vec<Shortcut>>,
+    /// Default bindings, restored by a reset
+    defaults: Vec<ShortcutBinding>,
     /// User customizations (`command_id` -> new shortcut)
     customizations: HashMap<String, Option<Shortcut>>,
+    /// Multi-key sequences (e.g. G then X)
+    sequences: SequenceMatcher,
 }
 
 #[allow(dead_code)] // Intentionally kept for API completeness
@@ -481,13 +786,16 @@ impl ShortcutRegistry {
     /// Create a new registry with default shortcuts
     pub fn new() -> Self {
         let mut registry = Self {
-            bindings: HashMap::new(),
+            bindings: Vec::new(),
             command_shortcuts: HashMap::new(),
+            defaults: Vec::new(),
             customizations: HashMap::new(),
+            sequences: SequenceMatcher::default(),
         };
 
         // Register default shortcuts
         registry.register_defaults();
+        registry.defaults = registry.bindings.clone();
 
         registry
     }
@@ -506,11 +814,26 @@ impl ShortcutRegistry {
         self.register_with_context("edit.redo", Shortcut::ctrl_shift(egui::Key::Z), None);
         self.register("edit.delete", Shortcut::new(egui::Key::Delete));
         self.register("edit.duplicate", Shortcut::ctrl(egui::Key::D));
+        self.register("edit.group", Shortcut::ctrl(egui::Key::G));
+        self.register("edit.ungroup", Shortcut::ctrl_shift(egui::Key::G));
         self.register("edit.select_all", Shortcut::ctrl(egui::Key::A));
 
         // View commands
         self.register("view.focus_selection", Shortcut::new(egui::Key::F));
 
+        // Numpad-style camera views (active in viewport context)
+        for (id, shortcut) in [
+            ("view.toggle_projection", Shortcut::new(egui::Key::Num5)),
+            ("view.camera_front", Shortcut::new(egui::Key::Num1)),
+            ("view.camera_back", Shortcut::ctrl(egui::Key::Num1)),
+            ("view.camera_right", Shortcut::new(egui::Key::Num3)),
+            ("view.camera_left", Shortcut::ctrl(egui::Key::Num3)),
+            ("view.camera_top", Shortcut::new(egui::Key::Num7)),
+            ("view.camera_bottom", Shortcut::ctrl(egui::Key::Num7)),
+        ] {
+            self.register_with_context(id, shortcut, Some(ShortcutContext::NonTextInput));
+        }
+
         // Transform commands (active in viewport context)
         self.register_with_context(
             "transform.translate",
@@ -530,11 +853,47 @@ impl ShortcutRegistry {
 
         // Entity commands
         self.register("entity.rename", Shortcut::new(egui::Key::F2));
+        self.register_with_context(
+            "entity.drop_to_ground",
+            Shortcut::new(egui::Key::End),
+            Some(ShortcutContext::NonTextInput),
+        );
+
+        // Tools commands
+        self.register_with_context(
+            "tools.snap_to_ground",
+            Shortcut::shift(egui::Key::End),
+            Some(ShortcutContext::NonTextInput),
+        );
+
+        // Modal transform sequences (grab, then constrain to an axis)
+        let grab = Shortcut::new(egui::Key::G);
+        self.register_sequence("transform.grab_x", ShortcutSequence::new([grab, Shortcut::new(egui::Key::X)]));
+        self.register_sequence("transform.grab_y", ShortcutSequence::new([grab, Shortcut::new(egui::Key::Y)]));
+        self.register_sequence("transform.grab_z", ShortcutSequence::new([grab, Shortcut::new(egui::Key::Z)]));
 
         // UI commands
         self.register("ui.command_palette", Shortcut::ctrl(egui::Key::P));
     }
 
+    /// Register a multi-key sequence for a command
+    pub fn register_sequence(&mut self, command_id: &'static str, sequence: ShortcutSequence) {
+        self.sequences.register(command_id, sequence);
+    }
+
+    /// Display string for the keys pressed so far in a pending sequence
+    pub fn pending_sequence_display(&self) -> Option<String> {
+        if !self.sequences.is_pending() {
+            return None;
+        }
+        Some(ShortcutSequence::new(self.sequences.pending()).display())
+    }
+
+    /// Maximum time between keys of a sequence (seconds)
+    pub fn sequence_timeout(&self) -> f64 {
+        self.sequences.timeout()
+    }
+
     /// Register a shortcut for a command
     pub fn register(&mut self, command_id: &'static str, shortcut: Shortcut) {
         self.register_with_context(command_id, shortcut, None);
@@ -554,16 +913,25 @@ impl ShortcutRegistry {
             context,
         };
 
-        self.bindings.insert(shortcut, binding);
+        self.add_binding(binding);
+    }
+
+    fn add_binding(&mut self, binding: ShortcutBinding) {
         self.command_shortcuts
-            .entry(command_id)
+            .entry(binding.command_id)
             .or_default()
-            .push(shortcut);
+            .push(binding.shortcut);
+        self.bindings.push(binding);
+    }
+
+    fn remove_bindings(&mut self, command_id: &str) {
+        self.bindings.retain(|binding| binding.command_id != command_id);
+        self.command_shortcuts.remove(command_id);
     }
 
     /// Get the command triggered by a shortcut (if any)
     pub fn get_command(&self, shortcut: &Shortcut) -> Option<&ShortcutBinding> {
-        self.bindings.get(shortcut)
+        self.bindings.iter().find(|binding| binding.shortcut == *shortcut)
     }
 
     /// Get all shortcuts for a command
@@ -587,11 +955,33 @@ impl ShortcutRegistry {
     }
 
     /// Check which command (if any) is triggered by current input
-    pub fn check_input(&self, ctx: &egui::Context) -> Option<&'static str> {
+    pub fn check_input(&mut self, ctx: &egui::Context) -> Option<&'static str> {
         // Check if any text input has focus - if so, skip non-global shortcuts
         let text_has_focus = ctx.memory(|m| m.focused().is_some());
 
-        for (shortcut, binding) in &self.bindings {
+        // Sequences take precedence over single chords while typing is not in progress
+        let (time, pressed) = ctx.input(|i| {
+            let pressed = i.events.iter().find_map(|event| match event {
+                egui::Event::Key { key, pressed: true, repeat: false, modifiers, .. } => {
+                    Some(Shortcut::with_modifiers(*key, Modifiers::from_egui(modifiers)))
+                }
+                _ => None,
+            });
+            (i.time, pressed)
+        });
+
+        self.sequences.expire(time);
+        if text_has_focus {
+            self.sequences.cancel();
+        } else if let Some(shortcut) = pressed {
+            match self.sequences.feed(shortcut, time) {
+                SequenceStep::Matched(command_id) => return Some(command_id),
+                SequenceStep::Pending | SequenceStep::Cancelled => return None,
+                SequenceStep::NoMatch => {}
+            }
+        }
+
+        for binding in &self.bindings {
             // Skip context-specific shortcuts when in text input
             if text_has_focus {
                 if let Some(ShortcutContext::NonTextInput) = binding.context {
@@ -599,55 +989,96 @@ impl ShortcutRegistry {
                 }
             }
 
-            if shortcut.is_pressed(ctx) {
+            if binding.shortcut.is_pressed(ctx) {
                 return Some(binding.command_id);
             }
         }
         None
     }
 
-    /// Customize a shortcut for a command
-    pub fn customize(&mut self, command_id: &str, new_shortcut: Option<Shortcut>) {
-        // Remove old shortcuts for this command
-        if let Some(old_shortcuts) = self.command_shortcuts.get(command_id) {
-            for old in old_shortcuts.clone() {
-                self.bindings.remove(&old);
-            }
+    /// Bind a command to a single shortcut, or unbind it with `None`.
+    ///
+    /// The command keeps the context of its default binding; commands without
+    /// one get [`ShortcutContext::NonTextInput`] for plain keys so typing
+    /// doesn't trigger them. Other commands on the same shortcut keep it,
+    /// which shows up in [`Self::conflicts`].
+    pub fn rebind(&mut self, command_id: &'static str, shortcut: Option<Shortcut>) {
+        let default_context = self
+            .defaults
+            .iter()
+            .find(|binding| binding.command_id == command_id)
+            .map(|binding| binding.context);
+
+        self.remove_bindings(command_id);
+        self.customizations.insert(command_id.to_string(), shortcut);
+
+        if let Some(shortcut) = shortcut {
+            let context = default_context.unwrap_or_else(|| {
+                let plain = !shortcut.modifiers.ctrl && !shortcut.modifiers.alt;
+                plain.then_some(ShortcutContext::NonTextInput)
+            });
+            self.add_binding(ShortcutBinding {
+                command_id,
+                shortcut,
+                is_default: false,
+                context,
+            });
         }
+    }
+
+    /// Check if a shortcut conflicts with existing bindings
+    pub fn check_conflict(&self, shortcut: &Shortcut) -> Option<&'static str> {
+        self.get_command(shortcut).map(|b| b.command_id)
+    }
 
-        // Store customization
-        self.customizations.insert(command_id.to_string(), new_shortcut);
-
-        // Add new shortcut if provided
-        if let Some(shortcut) = new_shortcut {
-            // Find the static command_id reference
-            for &static_id in self.command_shortcuts.keys() {
-                if static_id == command_id {
-                    let binding = ShortcutBinding {
-                        command_id: static_id,
-                        shortcut,
-                        is_default: false,
-                        context: None,
-                    };
-                    self.bindings.insert(shortcut, binding);
-                    self.command_shortcuts.get_mut(static_id).unwrap().clear();
-                    self.command_shortcuts.get_mut(static_id).unwrap().push(shortcut);
-                    break;
+    /// Shortcuts claimed by more than one command, with the commands in
+    /// binding order. A chord that starts a key sequence counts as a
+    /// conflict with the sequence's commands, since the sequence wins.
+    pub fn conflicts(&self) -> Vec<(Shortcut, Vec<&'static str>)> {
+        let mut claims: Vec<(Shortcut, Vec<&'static str>)> = Vec::new();
+        for binding in &self.bindings {
+            match claims.iter_mut().find(|(shortcut, _)| *shortcut == binding.shortcut) {
+                Some((_, commands)) => {
+                    if !commands.contains(&binding.command_id) {
+                        commands.push(binding.command_id);
+                    }
                 }
+                None => claims.push((binding.shortcut, vec![binding.command_id])),
+            }
+        }
+
+        for (sequence, command_id) in &self.sequences.sequences {
+            let Some(first) = sequence.keys.first() else {
+                continue;
+            };
+            if let Some((_, commands)) = claims.iter_mut().find(|(shortcut, _)| shortcut == first) {
+                commands.push(*command_id);
             }
         }
+
+        claims.retain(|(_, commands)| commands.len() > 1);
+        claims
     }
 
-    /// Check if a shortcut conflicts with existing bindings
-    pub fn check_conflict(&self, shortcut: &Shortcut) -> Option<&'static str> {
-        self.bindings.get(shortcut).map(|b| b.command_id)
+    /// Whether the user changed a command's shortcut
+    pub fn is_customized(&self, command_id: &str) -> bool {
+        self.customizations.contains_key(command_id)
     }
 
     /// Reset a command's shortcut to default
     pub fn reset_to_default(&mut self, command_id: &str) {
         self.customizations.remove(command_id);
-        // Re-register defaults would need to track them separately
-        // For now, this just removes customization
+        self.remove_bindings(command_id);
+
+        let defaults: Vec<ShortcutBinding> = self
+            .defaults
+            .iter()
+            .filter(|binding| binding.command_id == command_id)
+            .cloned()
+            .collect();
+        for binding in defaults {
+            self.add_binding(binding);
+        }
     }
 
     /// Reset all shortcuts to defaults
@@ -655,18 +1086,80 @@ impl ShortcutRegistry {
         self.bindings.clear();
         self.command_shortcuts.clear();
         self.customizations.clear();
-        self.register_defaults();
+        for binding in self.defaults.clone() {
+            self.add_binding(binding);
+        }
     }
 
     /// Get all bindings for UI display
     pub fn all_bindings(&self) -> impl Iterator<Item = &ShortcutBinding> {
-        self.bindings.values()
+        self.bindings.iter()
+    }
+
+    /// Save the user's overrides to `path` as RON, creating its directory
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        let overrides = ShortcutOverrides {
+            bindings: self
+                .customizations
+                .iter()
+                .map(|(command_id, shortcut)| (command_id.clone(), shortcut.map(|s| s.display())))
+                .collect(),
+        };
+
+        let content = ron::ser::to_string_pretty(&overrides, ron::ser::PrettyConfig::default())
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string()))?;
+
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, content)
+    }
+
+    /// Apply overrides saved by [`Self::save`] on top of the current bindings.
+    ///
+    /// Entries for unknown commands or unreadable shortcuts are skipped with a
+    /// warning so one bad line doesn't lose the rest.
+    pub fn load(&mut self, path: &Path, commands: &CommandRegistry) -> std::io::Result<()> {
+        let content = std::fs::read_to_string(path)?;
+        let overrides: ShortcutOverrides = ron::from_str(&content)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string()))?;
+
+        for (command_id, keys) in overrides.bindings {
+            let Some(command) = commands.get(&command_id) else {
+                tracing::warn!("Ignoring shortcut for unknown command '{}'", command_id);
+                continue;
+            };
+            let shortcut = match keys {
+                Some(keys) => match Shortcut::parse(&keys) {
+                    Some(shortcut) => Some(shortcut),
+                    None => {
+                        tracing::warn!("Ignoring unreadable shortcut '{}' for '{}'", keys, command_id);
+                        continue;
+                    }
+                },
+                None => None,
+            };
+            self.rebind(command.id, shortcut);
+        }
+        Ok(())
     }
 
     /// Get all command IDs that have shortcuts
     pub fn commands_with_shortcuts(&self) -> impl Iterator<Item = &'static str> + '_ {
         self.command_shortcuts.keys().copied()
     }
+
+    /// Display string for every key binding of a command, chords and
+    /// sequences alike (e.g. "Ctrl+Y / Ctrl+Shift+Z")
+    pub fn bindings_display(&self, command_id: &str) -> Option<String> {
+        let keys: Vec<String> = self
+            .get_shortcuts(command_id)
+            .into_iter()
+            .map(Shortcut::display)
+            .chain(self.sequences.sequences_for(command_id).map(ShortcutSequence::display))
+            .collect();
+        (!keys.is_empty()).then(|| keys.join(" / "))
+    }
 }
 
 impl Default for ShortcutRegistry {
@@ -675,7 +1168,319 @@ impl Default for ShortcutRegistry {
     }
 }
 
-/// Simple fuzzy matching score
+/// One row of the shortcut help overlay
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShortcutHelpEntry {
+    /// Command category (e.g. "Edit")
+    pub category: &'static str,
+    /// Command display name
+    pub name: &'static str,
+    /// Current key bindings
+    pub keys: String,
+}
+
+/// Shortcuts for commands tagged with `panel`, in registration order.
+///
+/// Commands without a current key binding are left out, so the list
+/// follows user customizations.
+pub fn shortcuts_for_panel(
+    commands: &CommandRegistry,
+    shortcuts: &ShortcutRegistry,
+    panel: PanelType,
+) -> Vec<ShortcutHelpEntry> {
+    commands
+        .commands
+        .iter()
+        .filter(|command| command.applies_to(panel))
+        .filter_map(|command| {
+            Some(ShortcutHelpEntry {
+                category: command.category,
+                name: command.name,
+                keys: shortcuts.bindings_display(command.id)?,
+            })
+        })
+        .collect()
+}
+
+/// Mouse and keyboard interactions handled by a panel itself
+pub fn panel_hints(panel: PanelType) -> &'static [(&'static str, &'static str)] {
+    match panel {
+        PanelType::Viewport => &[
+            ("Left-click", "Select entity"),
+            ("Right-drag / Alt+Left-drag", "Orbit camera"),
+            ("Middle-drag", "Pan camera"),
+            ("Scroll", "Zoom camera"),
+        ],
+        PanelType::Hierarchy => &[
+            ("Click", "Select entity"),
+            ("Shift+Click", "Add to selection"),
+            ("Ctrl+Click", "Toggle selection"),
+            ("Drag", "Reparent entity"),
+        ],
+        PanelType::MaterialGraph | PanelType::GameplayGraph => &[
+            ("Drag from pin", "Connect nodes"),
+            ("Drag on empty space", "Box select"),
+            ("Shift+Click", "Add node to selection"),
+            ("Middle-drag", "Pan"),
+            ("Scroll", "Zoom"),
+            ("Backspace", "Delete selected nodes"),
+        ],
+        PanelType::Sequencer => &[
+            ("Ctrl+Scroll", "Zoom timeline"),
+            ("Shift+Scroll / Middle-drag", "Scroll timeline"),
+            ("Right-click track", "Track and keyframe actions"),
+        ],
+        PanelType::Inspector
+        | PanelType::AssetBrowser
+        | PanelType::Console
+        | PanelType::Profiler
+        | PanelType::History => &[],
+    }
+}
+
+/// Overlay listing the shortcuts of the focused panel, toggled with `?`
+#[derive(Debug, Default)]
+pub struct ShortcutHelpOverlay {
+    visible: bool,
+}
+
+impl ShortcutHelpOverlay {
+    /// Toggle visibility
+    pub fn toggle(&mut self) {
+        self.visible = !self.visible;
+    }
+
+    /// Hide the overlay
+    pub fn hide(&mut self) {
+        self.visible = false;
+    }
+
+    /// Whether the overlay is shown
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Render the overlay for `panel`. Draws nothing while hidden.
+    pub fn ui(&mut self, ctx: &egui::Context, panel: PanelType, entries: &[ShortcutHelpEntry]) {
+        if !self.visible {
+            return;
+        }
+
+        let mut open = true;
+        egui::Window::new(format!("{} Shortcuts", panel.name()))
+            .id(egui::Id::new("shortcut_help_overlay"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                if entries.is_empty() {
+                    ui.weak("No panel-specific shortcuts");
+                } else {
+                    egui::Grid::new("shortcut_help_keys").striped(true).show(ui, |ui| {
+                        for entry in entries {
+                            ui.monospace(&entry.keys);
+                            ui.label(entry.name);
+                            ui.weak(entry.category);
+                            ui.end_row();
+                        }
+                    });
+                }
+
+                let hints = panel_hints(panel);
+                if !hints.is_empty() {
+                    ui.separator();
+                    egui::Grid::new("shortcut_help_hints").show(ui, |ui| {
+                        for (input, action) in hints {
+                            ui.monospace(*input);
+                            ui.label(*action);
+                            ui.end_row();
+                        }
+                    });
+                }
+
+                ui.separator();
+                ui.weak("Press ? or Escape to close");
+            });
+
+        if !open {
+            self.hide();
+        }
+    }
+}
+
+/// Window listing every command's shortcut, with rebinding by key capture
+#[derive(Debug, Default)]
+pub struct ShortcutSettingsWindow {
+    open: bool,
+    /// Command waiting for its new chord
+    capturing: Option<&'static str>,
+    /// Text filter over command names and categories
+    filter: String,
+    /// Feedback on the last capture
+    status: Option<String>,
+}
+
+impl ShortcutSettingsWindow {
+    /// Show the window
+    pub fn open(&mut self) {
+        self.open = true;
+    }
+
+    /// Render the window. Returns true if any binding changed.
+    pub fn ui(&mut self, ctx: &egui::Context, commands: &CommandRegistry, shortcuts: &mut ShortcutRegistry) -> bool {
+        if !self.open {
+            return false;
+        }
+
+        let mut changed = false;
+        if let Some(command_id) = self.capturing {
+            if let Some(shortcut) = capture_chord(ctx) {
+                self.capturing = None;
+                if shortcut == Shortcut::new(egui::Key::Escape) {
+                    self.status = None;
+                } else if Shortcut::parse(&shortcut.display()) != Some(shortcut) {
+                    self.status = Some(format!("{} can't be saved as a shortcut", shortcut.display()));
+                } else {
+                    self.status = shortcuts
+                        .check_conflict(&shortcut)
+                        .filter(|other| *other != command_id)
+                        .map(|other| format!("{} is also bound to {}", shortcut.display(), command_name(commands, other)));
+                    shortcuts.rebind(command_id, Some(shortcut));
+                    changed = true;
+                }
+            }
+        }
+
+        let conflicts = shortcuts.conflicts();
+        let filter = self.filter.to_lowercase();
+
+        let mut open = true;
+        egui::Window::new("Keyboard Shortcuts")
+            .id(egui::Id::new("shortcut_settings"))
+            .open(&mut open)
+            .resizable(true)
+            .default_width(480.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Filter:");
+                    ui.text_edit_singleline(&mut self.filter);
+                });
+
+                if !conflicts.is_empty() {
+                    ui.colored_label(
+                        ui.visuals().warn_fg_color,
+                        format!("\u{26A0} {} shortcut(s) bound to more than one command", conflicts.len()),
+                    );
+                }
+                if let Some(status) = &self.status {
+                    ui.colored_label(ui.visuals().warn_fg_color, status);
+                }
+                ui.separator();
+
+                egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                    egui::Grid::new("shortcut_settings_grid").striped(true).num_columns(4).show(ui, |ui| {
+                        for command in commands.all() {
+                            if !filter.is_empty() && !command.display_text().to_lowercase().contains(&filter) {
+                                continue;
+                            }
+
+                            ui.label(command.name).on_hover_text(command.id);
+                            ui.weak(command.category);
+
+                            let others: Vec<&str> = conflicts
+                                .iter()
+                                .filter(|(_, ids)| ids.contains(&command.id))
+                                .flat_map(|(_, ids)| ids.iter().filter(|id| **id != command.id))
+                                .map(|id| command_name(commands, id))
+                                .collect();
+
+                            let capturing = self.capturing == Some(command.id);
+                            let keys = if capturing {
+                                "Press a shortcut...".to_string()
+                            } else {
+                                shortcuts.bindings_display(command.id).unwrap_or_else(|| "-".to_string())
+                            };
+                            let mut text = egui::RichText::new(keys).monospace();
+                            if !others.is_empty() {
+                                text = text.color(ui.visuals().error_fg_color);
+                            }
+
+                            let mut response = ui.selectable_label(capturing, text);
+                            response = if others.is_empty() {
+                                response.on_hover_text("Click, then press the new shortcut")
+                            } else {
+                                response.on_hover_text(format!("Also bound to {}", others.join(", ")))
+                            };
+                            if response.clicked() {
+                                self.capturing = if capturing { None } else { Some(command.id) };
+                                self.status = None;
+                            }
+
+                            ui.horizontal(|ui| {
+                                if ui.small_button("Clear").clicked() {
+                                    shortcuts.rebind(command.id, None);
+                                    changed = true;
+                                }
+                                if ui
+                                    .add_enabled(shortcuts.is_customized(command.id), egui::Button::new("Reset").small())
+                                    .clicked()
+                                {
+                                    shortcuts.reset_to_default(command.id);
+                                    changed = true;
+                                }
+                            });
+                            ui.end_row();
+                        }
+                    });
+                });
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.button("Reset to Defaults").clicked() {
+                        shortcuts.reset_all();
+                        self.capturing = None;
+                        self.status = None;
+                        changed = true;
+                    }
+                    if self.capturing.is_some() {
+                        ui.weak("Escape cancels");
+                    }
+                });
+            });
+
+        if !open {
+            self.open = false;
+            self.capturing = None;
+            self.status = None;
+        }
+        changed
+    }
+}
+
+/// Take the first key press of this frame, so it doesn't also trigger a shortcut
+fn capture_chord(ctx: &egui::Context) -> Option<Shortcut> {
+    ctx.input_mut(|input| {
+        let (key, modifiers) = input.events.iter().find_map(|event| match event {
+            egui::Event::Key { key, pressed: true, repeat: false, modifiers, .. } => Some((*key, *modifiers)),
+            _ => None,
+        })?;
+        input.consume_key(modifiers, key);
+        Some(Shortcut::with_modifiers(key, Modifiers::from_egui(&modifiers)))
+    })
+}
+
+/// Display name of a command, falling back to its ID
+fn command_name<'a>(commands: &'a CommandRegistry, command_id: &'a str) -> &'a str {
+    commands.get(command_id).map_or(command_id, |command| command.name)
+}
+
+/// Fuzzy subsequence score of `query` in `text`, or 0 if the query's
+/// characters don't all appear in order.
+///
+/// Scores the best alignment rather than the first one found, so "mgr"
+/// lines up with the word starts of "material graph". Matches at word
+/// boundaries and runs of consecutive matches score higher.
 fn fuzzy_score(text: &str, query: &str) -> i32 {
     if query.is_empty() {
         return 1;
@@ -684,34 +1489,35 @@ fn fuzzy_score(text: &str, query: &str) -> i32 {
     let text_chars: Vec<char> = text.chars().collect();
     let query_chars: Vec<char> = query.chars().collect();
 
-    let mut score = 0;
-    let mut query_idx = 0;
-    let mut prev_match_idx: Option<usize> = None;
-
-    for (i, &c) in text_chars.iter().enumerate() {
-        if query_idx < query_chars.len() && c == query_chars[query_idx] {
-            // Bonus for consecutive matches
-            if let Some(prev) = prev_match_idx {
-                if i == prev + 1 {
-                    score += 5;
+    // best[i]: best score with the query so far matched and its last char at text[i]
+    let mut best: Vec<Option<i32>> = vec![None; text_chars.len()];
+    for (query_idx, &q) in query_chars.iter().enumerate() {
+        let mut next = vec![None; text_chars.len()];
+        // Best score of the previous query char anywhere before i
+        let mut best_before: Option<i32> = None;
+
+        for (i, &c) in text_chars.iter().enumerate() {
+            if c == q {
+                let mut char_score = 1;
+                // Bonus for matching at word boundaries
+                if i == 0 || !text_chars[i - 1].is_alphanumeric() {
+                    char_score += 3;
                 }
+
+                next[i] = if query_idx == 0 {
+                    Some(char_score)
+                } else {
+                    // Bonus for consecutive matches
+                    let consecutive = i.checked_sub(1).and_then(|prev| best[prev]).map(|score| score + 5);
+                    consecutive.max(best_before).map(|score| score + char_score)
+                };
             }
-            // Bonus for matching at word boundaries
-            if i == 0 || !text_chars[i - 1].is_alphanumeric() {
-                score += 3;
-            }
-            score += 1;
-            prev_match_idx = Some(i);
-            query_idx += 1;
+            best_before = best_before.max(best[i]);
         }
+        best = next;
     }
 
-    // Only return positive score if all query chars were found
-    if query_idx == query_chars.len() {
-        score
-    } else {
-        0
-    }
+    best.into_iter().flatten().max().unwrap_or(0)
 }
 
 /// Command palette UI state
@@ -726,6 +1532,8 @@ pub struct CommandPalette {
     pub registry: CommandRegistry,
     /// Command to execute (set when user selects a command)
     pub pending_command: Option<&'static str>,
+    /// Recently selected commands, most recent first
+    recent: Vec<&'static str>,
 }
 
 impl CommandPalette {
@@ -737,9 +1545,49 @@ impl CommandPalette {
             selected_index: 0,
             registry: CommandRegistry::new(),
             pending_command: None,
+            recent: Vec::new(),
         }
     }
 
+    /// Move a command to the front of the recent list
+    pub fn record_recent(&mut self, command_id: &'static str) {
+        self.recent.retain(|id| *id != command_id);
+        self.recent.insert(0, command_id);
+        self.recent.truncate(MAX_RECENT_COMMANDS);
+    }
+
+    /// Recently selected commands, most recent first
+    #[allow(dead_code)] // Intentionally kept for API completeness
+    pub fn recent(&self) -> &[&'static str] {
+        &self.recent
+    }
+
+    /// Save the recent list to `path` as RON, creating its directory
+    pub fn save_recent(&self, path: &Path) -> std::io::Result<()> {
+        let content = ron::ser::to_string_pretty(&self.recent, ron::ser::PrettyConfig::default())
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string()))?;
+
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, content)
+    }
+
+    /// Load a recent list saved by [`Self::save_recent`], dropping commands
+    /// that no longer exist
+    pub fn load_recent(&mut self, path: &Path) -> std::io::Result<()> {
+        let content = std::fs::read_to_string(path)?;
+        let ids: Vec<String> = ron::from_str(&content)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string()))?;
+
+        self.recent = ids
+            .iter()
+            .filter_map(|id| self.registry.get(id).map(|command| command.id))
+            .take(MAX_RECENT_COMMANDS)
+            .collect();
+        Ok(())
+    }
+
     /// Toggle visibility
     pub fn toggle(&mut self) {
         self.visible = !self.visible;
@@ -795,7 +1643,7 @@ impl CommandPalette {
                 ui.add_space(8.0);
 
                 // Get filtered commands
-                let commands = self.registry.search(&self.query);
+                let commands = self.registry.search_with_recent(&self.query, &self.recent);
                 let max_visible = 12;
 
                 // Handle keyboard navigation
@@ -860,6 +1708,9 @@ impl CommandPalette {
 
         if should_close {
             self.hide();
+            if let Some(command_id) = self.pending_command {
+                self.record_recent(command_id);
+            }
         }
     }
 
@@ -947,3 +1798,224 @@ impl<'a> egui::Widget for CommandItem<'a> {
         response
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn help_names(panel: PanelType) -> Vec<&'static str> {
+        shortcuts_for_panel(&CommandRegistry::new(), &ShortcutRegistry::new(), panel)
+            .into_iter()
+            .map(|entry| entry.name)
+            .collect()
+    }
+
+    #[test]
+    fn test_viewport_shortcuts() {
+        let entries = shortcuts_for_panel(&CommandRegistry::new(), &ShortcutRegistry::new(), PanelType::Viewport);
+        let names: Vec<_> = entries.iter().map(|entry| entry.name).collect();
+
+        assert!(names.contains(&"Translate Mode"));
+        assert!(names.contains(&"Drop to Ground"));
+        assert!(names.contains(&"Undo"));
+        // Global and hierarchy-only commands are left out
+        assert!(!names.contains(&"Save Scene"));
+        assert!(!names.contains(&"Rename Entity"));
+
+        let redo = entries.iter().find(|entry| entry.name == "Redo").unwrap();
+        assert_eq!(redo.keys, "Ctrl+Y / Ctrl+Shift+Z");
+        let grab = entries.iter().find(|entry| entry.name == "Translate Along X").unwrap();
+        assert_eq!(grab.keys, "G, X");
+    }
+
+    #[test]
+    fn test_graph_shortcuts() {
+        for panel in [PanelType::MaterialGraph, PanelType::GameplayGraph] {
+            assert_eq!(help_names(panel), vec!["Delete"]);
+            assert!(!panel_hints(panel).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_unbound_commands_are_hidden() {
+        let mut shortcuts = ShortcutRegistry::new();
+        shortcuts.rebind("transform.translate", None);
+        let entries = shortcuts_for_panel(&CommandRegistry::new(), &shortcuts, PanelType::Viewport);
+        assert!(entries.iter().all(|entry| entry.name != "Translate Mode"));
+    }
+
+    #[test]
+    fn test_fuzzy_score_prefers_word_starts() {
+        assert!(fuzzy_score("material graph", "mat") > fuzzy_score("gameplay graph", "mat"));
+        assert!(fuzzy_score("material graph", "mgr") > 0);
+        // The best alignment wins over the first one found
+        assert!(fuzzy_score("program graph", "gra") > fuzzy_score("program", "gra"));
+        assert_eq!(fuzzy_score("material graph", "xyz"), 0);
+        assert_eq!(fuzzy_score("material", "materials"), 0);
+
+        let registry = CommandRegistry::new();
+        let results = registry.search("mat");
+        assert_eq!(results[0].id, "panel.material_graph");
+        let results = registry.search("mgr");
+        assert_eq!(results[0].id, "panel.material_graph");
+    }
+
+    #[test]
+    fn test_recent_commands_rank_first() {
+        let mut palette = CommandPalette::new();
+        palette.record_recent("panel.profiler");
+        palette.record_recent("file.save");
+        palette.record_recent("panel.profiler");
+        assert_eq!(palette.recent(), &["panel.profiler", "file.save"]);
+
+        let results = palette.registry.search_with_recent("", palette.recent());
+        assert_eq!(results[0].id, "panel.profiler");
+        assert_eq!(results[1].id, "file.save");
+
+        // Among equal matches, the recent one wins
+        let results = palette.registry.search_with_recent("show", palette.recent());
+        assert_eq!(results[0].id, "panel.profiler");
+
+        for id in ["file.new", "file.open", "edit.undo", "edit.redo", "edit.delete", "edit.duplicate",
+                   "edit.group", "edit.ungroup", "edit.select_all", "view.focus_selection"] {
+            palette.record_recent(id);
+        }
+        assert_eq!(palette.recent().len(), MAX_RECENT_COMMANDS);
+        assert_eq!(palette.recent()[0], "view.focus_selection");
+    }
+
+    #[test]
+    fn test_save_and_load_recent_commands() {
+        let path = std::env::temp_dir()
+            .join(format!("recent_commands_{}", uuid::Uuid::new_v4()))
+            .join("recent_commands.ron");
+
+        let mut palette = CommandPalette::new();
+        palette.record_recent("file.save");
+        palette.record_recent("panel.console");
+        palette.save_recent(&path).unwrap();
+
+        let mut loaded = CommandPalette::new();
+        loaded.load_recent(&path).unwrap();
+        assert_eq!(loaded.recent(), &["panel.console", "file.save"]);
+
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_rebind_reports_conflicts_and_resets() {
+        let mut shortcuts = ShortcutRegistry::new();
+        assert!(shortcuts.conflicts().is_empty());
+
+        let ctrl_s = Shortcut::ctrl(egui::Key::S);
+        shortcuts.rebind("file.open", Some(ctrl_s));
+        assert_eq!(shortcuts.get_shortcut_display("file.open").as_deref(), Some("Ctrl+S"));
+        assert_eq!(shortcuts.conflicts(), vec![(ctrl_s, vec!["file.save", "file.open"])]);
+        // The earlier binding keeps the chord
+        assert_eq!(shortcuts.check_conflict(&ctrl_s), Some("file.save"));
+
+        // Rebinding keeps the default context
+        shortcuts.rebind("transform.translate", Some(Shortcut::new(egui::Key::Q)));
+        let binding = shortcuts.get_command(&Shortcut::new(egui::Key::Q)).unwrap();
+        assert_eq!(binding.context, Some(ShortcutContext::NonTextInput));
+
+        // A chord starting a sequence is shadowed by it
+        shortcuts.rebind("entity.create", Some(Shortcut::new(egui::Key::G)));
+        assert_eq!(shortcuts.conflicts().len(), 2);
+
+        shortcuts.reset_to_default("file.open");
+        assert_eq!(shortcuts.get_shortcut_display("file.open").as_deref(), Some("Ctrl+O"));
+        assert!(!shortcuts.is_customized("file.open"));
+
+        shortcuts.reset_all();
+        assert!(shortcuts.conflicts().is_empty());
+        assert!(shortcuts.get_shortcuts("entity.create").is_empty());
+        assert_eq!(shortcuts.get_shortcut_display("transform.translate").as_deref(), Some("W"));
+    }
+
+    #[test]
+    fn test_default_shortcuts_round_trip_through_display() {
+        let shortcuts = ShortcutRegistry::new();
+        for binding in shortcuts.all_bindings() {
+            assert_eq!(Shortcut::parse(&binding.shortcut.display()), Some(binding.shortcut));
+        }
+    }
+
+    #[test]
+    fn test_save_and_load_overrides() {
+        let commands = CommandRegistry::new();
+        let path = std::env::temp_dir()
+            .join(format!("shortcuts_{}", uuid::Uuid::new_v4()))
+            .join("shortcuts.ron");
+
+        let mut shortcuts = ShortcutRegistry::new();
+        shortcuts.rebind("file.save_as", Some(Shortcut::ctrl_shift(egui::Key::S)));
+        shortcuts.rebind("view.camera_front", Some(Shortcut::ctrl(egui::Key::Num1)));
+        shortcuts.rebind("edit.delete", None);
+        shortcuts.save(&path).unwrap();
+
+        let mut loaded = ShortcutRegistry::new();
+        loaded.load(&path, &commands).unwrap();
+        assert_eq!(loaded.get_shortcut_display("file.save_as").as_deref(), Some("Ctrl+Shift+S"));
+        assert_eq!(loaded.get_shortcut_display("view.camera_front").as_deref(), Some("Ctrl+Num1"));
+        assert!(loaded.get_shortcuts("edit.delete").is_empty());
+        assert!(loaded.is_customized("edit.delete"));
+        // Ctrl+1 now belongs to both camera front and camera back
+        assert_eq!(loaded.conflicts().len(), 1);
+
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    fn grab_matcher() -> SequenceMatcher {
+        let mut matcher = SequenceMatcher::new(1.0);
+        let grab = Shortcut::new(egui::Key::G);
+        matcher.register("transform.grab_x", ShortcutSequence::new([grab, Shortcut::new(egui::Key::X)]));
+        matcher.register("transform.grab_y", ShortcutSequence::new([grab, Shortcut::new(egui::Key::Y)]));
+        matcher
+    }
+
+    #[test]
+    fn test_sequence_match() {
+        let mut matcher = grab_matcher();
+
+        assert_eq!(matcher.feed(Shortcut::new(egui::Key::G), 0.0), SequenceStep::Pending);
+        assert!(matcher.is_pending());
+        assert_eq!(
+            matcher.feed(Shortcut::new(egui::Key::X), 0.5),
+            SequenceStep::Matched("transform.grab_x")
+        );
+        assert!(!matcher.is_pending());
+    }
+
+    #[test]
+    fn test_sequence_wrong_second_key_cancels() {
+        let mut matcher = grab_matcher();
+
+        assert_eq!(matcher.feed(Shortcut::new(egui::Key::G), 0.0), SequenceStep::Pending);
+        assert_eq!(matcher.feed(Shortcut::new(egui::Key::Q), 0.2), SequenceStep::Cancelled);
+        assert!(!matcher.is_pending());
+
+        // Next key starts fresh
+        assert_eq!(matcher.feed(Shortcut::new(egui::Key::X), 0.3), SequenceStep::NoMatch);
+    }
+
+    #[test]
+    fn test_sequence_timeout_resets() {
+        let mut matcher = grab_matcher();
+
+        assert_eq!(matcher.feed(Shortcut::new(egui::Key::G), 0.0), SequenceStep::Pending);
+        assert!(!matcher.expire(0.5));
+        assert!(matcher.expire(1.5));
+        assert!(!matcher.is_pending());
+
+        // Second key after the timeout is treated on its own
+        assert_eq!(matcher.feed(Shortcut::new(egui::Key::G), 2.0), SequenceStep::Pending);
+        assert_eq!(matcher.feed(Shortcut::new(egui::Key::X), 3.5), SequenceStep::NoMatch);
+    }
+
+    #[test]
+    fn test_unrelated_key_is_not_consumed() {
+        let mut matcher = grab_matcher();
+        assert_eq!(matcher.feed(Shortcut::ctrl(egui::Key::S), 0.0), SequenceStep::NoMatch);
+    }
+}
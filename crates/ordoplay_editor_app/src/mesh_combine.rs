@@ -0,0 +1,216 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//! Combining static mesh entities into a single entity.
+//!
+//! Combining is non-destructive: the source entities are deactivated rather
+//! than deleted, and a new entity references the combined mesh asset that
+//! will be generated from them. The source list is recorded in the new
+//! entity's metadata so the asset can be baked later, and the whole combine
+//! is one undo step.
+
+use crate::components::{Component, MeshRendererComponent};
+use crate::state::{EntityData, EntityId, SceneData, Transform};
+
+/// Metadata key listing the entities a combined mesh was built from
+pub const COMBINED_FROM_KEY: &str = "combined_from";
+
+/// Folder combined mesh assets are generated into
+pub const COMBINED_MESH_DIR: &str = "meshes/combined";
+
+/// Why an entity can't take part in a combine
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CombineExclusion {
+    /// The entity doesn't exist
+    Missing,
+    /// The entity is inactive, e.g. already part of another combine
+    Inactive,
+    /// The entity isn't marked static
+    NotStatic,
+    /// The entity has no mesh renderer with a mesh
+    NoMeshRenderer,
+    /// The entity has a collider, which would lose its shape
+    HasCollider,
+}
+
+impl CombineExclusion {
+    /// Human-readable reason
+    pub fn reason(&self) -> &'static str {
+        match self {
+            Self::Missing => "entity not found",
+            Self::Inactive => "entity is inactive",
+            Self::NotStatic => "entity is not static",
+            Self::NoMeshRenderer => "entity has no mesh renderer",
+            Self::HasCollider => "entity has a collider",
+        }
+    }
+}
+
+/// A selection split into entities that can be combined and those that can't
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CombineSelection {
+    /// Entities that qualify, in selection order
+    pub qualified: Vec<EntityId>,
+    /// Entities that were left out and why
+    pub excluded: Vec<(EntityId, CombineExclusion)>,
+}
+
+/// Check which of `entities` can be combined
+pub fn partition_combinable(scene: &SceneData, entities: &[EntityId]) -> CombineSelection {
+    let mut selection = CombineSelection::default();
+
+    for id in entities {
+        if selection.qualified.contains(id) {
+            continue;
+        }
+        match exclusion(scene, *id) {
+            Some(reason) => selection.excluded.push((*id, reason)),
+            None => selection.qualified.push(*id),
+        }
+    }
+    selection
+}
+
+fn exclusion(scene: &SceneData, id: EntityId) -> Option<CombineExclusion> {
+    let Some(data) = scene.get(&id) else {
+        return Some(CombineExclusion::Missing);
+    };
+
+    if !data.active {
+        Some(CombineExclusion::Inactive)
+    } else if !data.is_static {
+        Some(CombineExclusion::NotStatic)
+    } else if data.components.iter().any(|c| c.collider_kind().is_some()) {
+        Some(CombineExclusion::HasCollider)
+    } else if mesh_renderer(data).is_none() {
+        Some(CombineExclusion::NoMeshRenderer)
+    } else {
+        None
+    }
+}
+
+fn mesh_renderer(data: &EntityData) -> Option<&MeshRendererComponent> {
+    data.components.iter().find_map(|c| match c {
+        Component::MeshRenderer(renderer) if !renderer.mesh.is_empty() => Some(renderer),
+        _ => None,
+    })
+}
+
+/// Asset path of the combined mesh for the entity `id`
+pub fn combined_mesh_path(id: EntityId) -> String {
+    format!("{}/{}.glb", COMBINED_MESH_DIR, id.0)
+}
+
+/// The entity replacing `sources`: a static root entity at their centroid
+/// with a mesh renderer for the combined mesh. The material is kept when
+/// all sources share one.
+pub fn combined_entity(scene: &SceneData, sources: &[EntityId]) -> (EntityId, EntityData) {
+    let id = EntityId::new();
+
+    let positions: Vec<[f32; 3]> = sources.iter().filter_map(|s| scene.world_position(*s)).collect();
+    let mut centroid = [0.0; 3];
+    for position in &positions {
+        for (sum, value) in centroid.iter_mut().zip(position) {
+            *sum += value / positions.len() as f32;
+        }
+    }
+
+    let materials: Vec<&str> = sources
+        .iter()
+        .filter_map(|s| scene.get(s).and_then(mesh_renderer))
+        .map(|renderer| renderer.material.as_str())
+        .collect();
+    let material = match materials.split_first() {
+        Some((first, rest)) if rest.iter().all(|m| m == first) => first.to_string(),
+        _ => String::new(),
+    };
+
+    let mut data = EntityData {
+        name: "Combined Mesh".to_string(),
+        is_static: true,
+        transform: Transform {
+            position: centroid,
+            ..Default::default()
+        },
+        components: vec![Component::MeshRenderer(MeshRendererComponent {
+            mesh: combined_mesh_path(id),
+            material,
+            ..Default::default()
+        })],
+        ..Default::default()
+    };
+    let source_list: Vec<String> = sources.iter().map(|s| s.0.to_string()).collect();
+    data.metadata.insert(COMBINED_FROM_KEY.to_string(), source_list.join(","));
+
+    (id, data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::BoxColliderComponent;
+
+    fn mesh_entity(scene: &mut SceneData, is_static: bool, material: &str) -> EntityId {
+        scene.add_entity(EntityData {
+            is_static,
+            components: vec![Component::MeshRenderer(MeshRendererComponent {
+                mesh: "meshes/rock.glb".to_string(),
+                material: material.to_string(),
+                ..Default::default()
+            })],
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_partition_combinable() {
+        let mut scene = SceneData::new();
+        let rock = mesh_entity(&mut scene, true, "rock.mat");
+        let moving = mesh_entity(&mut scene, false, "rock.mat");
+        let solid = mesh_entity(&mut scene, true, "rock.mat");
+        scene
+            .get_mut(&solid)
+            .unwrap()
+            .components
+            .push(Component::BoxCollider(BoxColliderComponent::default()));
+        let empty = scene.add_entity(EntityData {
+            is_static: true,
+            ..Default::default()
+        });
+        let hidden = mesh_entity(&mut scene, true, "rock.mat");
+        scene.get_mut(&hidden).unwrap().active = false;
+        let other = mesh_entity(&mut scene, true, "rock.mat");
+        let missing = EntityId::new();
+
+        let selection = partition_combinable(&scene, &[rock, moving, solid, empty, hidden, other, missing, rock]);
+        assert_eq!(selection.qualified, vec![rock, other]);
+        assert_eq!(
+            selection.excluded,
+            vec![
+                (moving, CombineExclusion::NotStatic),
+                (solid, CombineExclusion::HasCollider),
+                (empty, CombineExclusion::NoMeshRenderer),
+                (hidden, CombineExclusion::Inactive),
+                (missing, CombineExclusion::Missing),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_combined_entity_records_sources() {
+        let mut scene = SceneData::new();
+        let a = mesh_entity(&mut scene, true, "rock.mat");
+        let b = mesh_entity(&mut scene, true, "rock.mat");
+        scene.get_mut(&b).unwrap().transform.position = [4.0, 0.0, 2.0];
+
+        let (id, data) = combined_entity(&scene, &[a, b]);
+        assert_eq!(data.transform.position, [2.0, 0.0, 1.0]);
+        assert_eq!(
+            data.components[0],
+            Component::MeshRenderer(MeshRendererComponent {
+                mesh: combined_mesh_path(id),
+                material: "rock.mat".to_string(),
+                ..Default::default()
+            })
+        );
+        assert_eq!(data.metadata[COMBINED_FROM_KEY], format!("{},{}", a.0, b.0));
+    }
+}
@@ -0,0 +1,160 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//! Triangle counts of mesh assets, read from glTF headers.
+//!
+//! Only the JSON part of a `.gltf`/`.glb` file is parsed: every primitive
+//! contributes its index count (or vertex count when unindexed) divided
+//! according to its draw mode. Other formats report no count.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+/// glTF primitive modes
+const MODE_TRIANGLES: u64 = 4;
+const MODE_TRIANGLE_STRIP: u64 = 5;
+const MODE_TRIANGLE_FAN: u64 = 6;
+
+/// GLB chunk type of the JSON chunk (`JSON` in little endian)
+const GLB_JSON_CHUNK: u32 = 0x4E4F_534A;
+
+/// GLB chunk type of the binary buffer chunk (`BIN\0` in little endian)
+const GLB_BIN_CHUNK: u32 = 0x004E_4942;
+
+/// Triangle count of a glTF document
+pub fn gltf_triangle_count(document: &serde_json::Value) -> Option<u64> {
+    let accessors = document.get("accessors")?.as_array()?;
+    let accessor_count = |index: &serde_json::Value| {
+        let index = usize::try_from(index.as_u64()?).ok()?;
+        accessors.get(index)?.get("count")?.as_u64()
+    };
+
+    let mut triangles = 0;
+    for mesh in document.get("meshes")?.as_array()? {
+        for primitive in mesh.get("primitives")?.as_array()? {
+            let count = match primitive.get("indices") {
+                Some(indices) => accessor_count(indices)?,
+                None => accessor_count(primitive.get("attributes")?.get("POSITION")?)?,
+            };
+            let mode = primitive.get("mode").and_then(serde_json::Value::as_u64).unwrap_or(MODE_TRIANGLES);
+            triangles += match mode {
+                MODE_TRIANGLES => count / 3,
+                MODE_TRIANGLE_STRIP | MODE_TRIANGLE_FAN => count.saturating_sub(2),
+                _ => 0,
+            };
+        }
+    }
+    Some(triangles)
+}
+
+/// Extract the JSON chunk of a binary glTF file
+pub fn glb_json(bytes: &[u8]) -> Option<&[u8]> {
+    let word = |offset: usize| {
+        let slice = bytes.get(offset..offset + 4)?;
+        Some(u32::from_le_bytes(slice.try_into().ok()?))
+    };
+
+    if bytes.get(0..4)? != b"glTF" || word(16)? != GLB_JSON_CHUNK {
+        return None;
+    }
+    let length = usize::try_from(word(12)?).ok()?;
+    bytes.get(20..20 + length)
+}
+
+/// Extract the binary buffer chunk of a binary glTF file, which follows
+/// the JSON chunk
+pub fn glb_bin(bytes: &[u8]) -> Option<&[u8]> {
+    let word = |offset: usize| {
+        let slice = bytes.get(offset..offset + 4)?;
+        Some(u32::from_le_bytes(slice.try_into().ok()?))
+    };
+
+    let json_length = usize::try_from(word(12)?).ok()?;
+    let header = 20 + json_length;
+    if glb_json(bytes).is_none() || word(header + 4)? != GLB_BIN_CHUNK {
+        return None;
+    }
+    let length = usize::try_from(word(header)?).ok()?;
+    bytes.get(header + 8..header + 8 + length)
+}
+
+/// Triangle count of the mesh file at `path`
+pub fn mesh_triangle_count(path: &Path) -> Option<u64> {
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+    let bytes = std::fs::read(path).ok()?;
+    let json = match extension.as_str() {
+        "gltf" => bytes.as_slice(),
+        "glb" => glb_json(&bytes)?,
+        _ => return None,
+    };
+    gltf_triangle_count(&serde_json::from_slice(json).ok()?)
+}
+
+/// Cached triangle counts keyed by mesh asset path
+#[derive(Debug, Default)]
+pub struct MeshStatsCache {
+    counts: HashMap<String, Option<u64>>,
+}
+
+impl MeshStatsCache {
+    /// Triangle count of `mesh`, resolved against `root` on first use
+    pub fn triangle_count(&mut self, root: &Path, mesh: &str) -> Option<u64> {
+        *self
+            .counts
+            .entry(mesh.to_string())
+            .or_insert_with(|| mesh_triangle_count(&root.join(mesh)))
+    }
+}
+
+/// Cached vertex positions keyed by mesh asset path
+#[derive(Debug, Default)]
+pub struct MeshVertexCache {
+    positions: HashMap<String, Option<Vec<[f32; 3]>>>,
+}
+
+impl MeshVertexCache {
+    /// Vertex positions of `mesh`, loaded relative to `root` on first use.
+    /// `None` for formats that can't be loaded.
+    pub fn positions(&mut self, root: &Path, mesh: &str) -> Option<&[[f32; 3]]> {
+        self.positions
+            .entry(mesh.to_string())
+            .or_insert_with(|| crate::mesh_thumbnail::load_mesh(&root.join(mesh)).ok().map(|geometry| geometry.positions))
+            .as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gltf_triangle_count() {
+        let document = serde_json::json!({
+            "accessors": [{ "count": 36 }, { "count": 24 }, { "count": 6 }],
+            "meshes": [
+                { "primitives": [{ "attributes": { "POSITION": 1 }, "indices": 0 }] },
+                { "primitives": [{ "attributes": { "POSITION": 2 }, "mode": 5 }] },
+            ],
+        });
+        assert_eq!(gltf_triangle_count(&document), Some(12 + 4));
+    }
+
+    #[test]
+    fn test_glb_json_chunk() {
+        let json = br#"{"asset":{}}"#;
+        let mut bytes = b"glTF".to_vec();
+        bytes.extend_from_slice(&2u32.to_le_bytes());
+        bytes.extend_from_slice(&0u32.to_le_bytes());
+        bytes.extend_from_slice(&(json.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&GLB_JSON_CHUNK.to_le_bytes());
+        bytes.extend_from_slice(json);
+
+        assert_eq!(glb_json(&bytes), Some(&json[..]));
+        assert_eq!(glb_json(b"not a glb file at all"), None);
+        assert_eq!(glb_bin(&bytes), None);
+
+        let bin = [1u8, 2, 3, 4];
+        bytes.extend_from_slice(&(bin.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&GLB_BIN_CHUNK.to_le_bytes());
+        bytes.extend_from_slice(&bin);
+        assert_eq!(glb_bin(&bytes), Some(&bin[..]));
+    }
+}
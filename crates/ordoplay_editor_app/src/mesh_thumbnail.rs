@@ -0,0 +1,348 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//! Rendered previews of mesh assets for the asset browser.
+//!
+//! Thumbnails are generated on the thumbnail worker thread, which has no
+//! GPU device, so meshes are rasterized in software: positions only, flat
+//! shaded with a neutral default material and seen from a 3/4 camera
+//! above and to the front-right. The background stays transparent.
+//!
+//! OBJ files and glTF/GLB files are read. For glTF, every triangle
+//! primitive is drawn in mesh space (node transforms are ignored), and
+//! buffers must be external files or the GLB binary chunk.
+
+use crate::mesh_stats::{glb_bin, glb_json};
+use crate::physics::Vec3;
+use std::path::Path;
+
+/// glTF component types
+const COMPONENT_U8: u64 = 5121;
+const COMPONENT_U16: u64 = 5123;
+const COMPONENT_U32: u64 = 5125;
+const COMPONENT_F32: u64 = 5126;
+
+/// glTF triangle list primitive mode
+const MODE_TRIANGLES: u64 = 4;
+
+/// Base color of the default material
+const BASE_COLOR: [f32; 3] = [205.0, 205.0, 210.0];
+
+/// Share of the thumbnail the mesh's bounding sphere fills
+const FILL: f32 = 0.9;
+
+/// Triangle mesh reduced to what a preview needs
+#[derive(Debug, Clone, Default)]
+pub struct MeshGeometry {
+    /// Vertex positions
+    pub positions: Vec<[f32; 3]>,
+    /// Triangles as indices into `positions`
+    pub triangles: Vec<[u32; 3]>,
+}
+
+/// Is `path` a mesh format that can be previewed
+pub fn is_previewable_mesh(path: &Path) -> bool {
+    let extension = path.extension().and_then(|e| e.to_str()).map(str::to_lowercase);
+    matches!(extension.as_deref(), Some("obj" | "gltf" | "glb"))
+}
+
+/// Load the triangles of an OBJ, glTF or GLB file
+pub fn load_mesh(path: &Path) -> Result<MeshGeometry, String> {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_lowercase)
+        .unwrap_or_default();
+    let bytes = std::fs::read(path).map_err(|e| e.to_string())?;
+    let base_dir = path.parent().unwrap_or(Path::new("."));
+
+    let mesh = match extension.as_str() {
+        "obj" => parse_obj(&String::from_utf8_lossy(&bytes)),
+        "gltf" => parse_gltf(&parse_json(&bytes)?, None, base_dir)?,
+        "glb" => {
+            let json = glb_json(&bytes).ok_or("Not a binary glTF file")?;
+            parse_gltf(&parse_json(json)?, glb_bin(&bytes), base_dir)?
+        }
+        _ => return Err(format!("Unsupported mesh format: {}", extension)),
+    };
+
+    if mesh.triangles.is_empty() {
+        return Err("Mesh has no triangles".to_string());
+    }
+    Ok(mesh)
+}
+
+fn parse_json(bytes: &[u8]) -> Result<serde_json::Value, String> {
+    serde_json::from_slice(bytes).map_err(|e| e.to_string())
+}
+
+/// Vertices and faces of an OBJ file; polygons are split into fans
+fn parse_obj(text: &str) -> MeshGeometry {
+    let mut mesh = MeshGeometry::default();
+    for line in text.lines() {
+        let mut tokens = line.split_whitespace();
+        match tokens.next() {
+            Some("v") => {
+                let coords: Vec<f32> = tokens.take(3).filter_map(|t| t.parse().ok()).collect();
+                if let [x, y, z] = coords[..] {
+                    mesh.positions.push([x, y, z]);
+                }
+            }
+            Some("f") => {
+                let count = mesh.positions.len() as i64;
+                // Indices are 1-based, negative ones count back from the end
+                let corners: Vec<u32> = tokens
+                    .filter_map(|t| t.split('/').next()?.parse::<i64>().ok())
+                    .map(|i| if i < 0 { count + i } else { i - 1 })
+                    .filter(|i| (0..count).contains(i))
+                    .map(|i| i as u32)
+                    .collect();
+                for i in 1..corners.len().saturating_sub(1) {
+                    mesh.triangles.push([corners[0], corners[i], corners[i + 1]]);
+                }
+            }
+            _ => {}
+        }
+    }
+    mesh
+}
+
+/// Triangles of every mesh in a glTF document
+fn parse_gltf(document: &serde_json::Value, bin: Option<&[u8]>, base_dir: &Path) -> Result<MeshGeometry, String> {
+    let buffers: Vec<Vec<u8>> = document
+        .get("buffers")
+        .and_then(serde_json::Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+        .iter()
+        .map(|buffer| match buffer.get("uri").and_then(serde_json::Value::as_str) {
+            Some(uri) if uri.starts_with("data:") => Err("Embedded glTF buffers are not supported".to_string()),
+            Some(uri) => std::fs::read(base_dir.join(uri)).map_err(|e| format!("{}: {}", uri, e)),
+            None => bin.map(<[u8]>::to_vec).ok_or_else(|| "Missing GLB binary chunk".to_string()),
+        })
+        .collect::<Result<_, _>>()?;
+
+    let meshes = document
+        .get("meshes")
+        .and_then(serde_json::Value::as_array)
+        .ok_or("No meshes")?;
+    let mut mesh = MeshGeometry::default();
+    for primitive in meshes
+        .iter()
+        .filter_map(|m| m.get("primitives")?.as_array())
+        .flatten()
+    {
+        let mode = primitive.get("mode").and_then(serde_json::Value::as_u64).unwrap_or(MODE_TRIANGLES);
+        if mode != MODE_TRIANGLES {
+            continue;
+        }
+        let Some(position) = primitive.get("attributes").and_then(|a| a.get("POSITION")) else {
+            continue;
+        };
+
+        let positions = read_accessor(document, &buffers, position)?;
+        let base = mesh.positions.len() as u32;
+        let vertex_count = positions.len() / 3;
+        mesh.positions.extend(positions.chunks_exact(3).map(|p| [p[0], p[1], p[2]]));
+
+        let indices: Vec<u32> = match primitive.get("indices") {
+            Some(indices) => read_accessor(document, &buffers, indices)?
+                .into_iter()
+                .map(|i| i as u32)
+                .collect(),
+            None => (0..vertex_count as u32).collect(),
+        };
+        mesh.triangles.extend(
+            indices
+                .chunks_exact(3)
+                .filter(|t| t.iter().all(|&i| (i as usize) < vertex_count))
+                .map(|t| [base + t[0], base + t[1], base + t[2]]),
+        );
+    }
+    Ok(mesh)
+}
+
+/// Components of a glTF accessor as floats, tightly packed
+fn read_accessor(document: &serde_json::Value, buffers: &[Vec<u8>], index: &serde_json::Value) -> Result<Vec<f32>, String> {
+    let field = |value: &serde_json::Value, name: &str| value.get(name).and_then(serde_json::Value::as_u64);
+    let element = |array: &str, index: u64| {
+        document
+            .get(array)
+            .and_then(|a| a.get(index as usize))
+            .ok_or_else(|| format!("Missing {} {}", array, index))
+    };
+
+    let accessor = element("accessors", index.as_u64().ok_or("Bad accessor index")?)?;
+    let view = element("bufferViews", field(accessor, "bufferView").ok_or("Sparse accessors are not supported")?)?;
+    let buffer = buffers
+        .get(field(view, "buffer").unwrap_or(0) as usize)
+        .ok_or("Missing buffer")?;
+
+    let components = match accessor.get("type").and_then(serde_json::Value::as_str) {
+        Some("SCALAR") => 1,
+        Some("VEC2") => 2,
+        Some("VEC3") => 3,
+        Some("VEC4") => 4,
+        other => return Err(format!("Unsupported accessor type {:?}", other)),
+    };
+    let component_type = field(accessor, "componentType").unwrap_or(0);
+    let component_size = match component_type {
+        COMPONENT_U8 => 1,
+        COMPONENT_U16 => 2,
+        COMPONENT_U32 | COMPONENT_F32 => 4,
+        other => return Err(format!("Unsupported component type {}", other)),
+    };
+
+    let count = field(accessor, "count").unwrap_or(0) as usize;
+    let start = (field(view, "byteOffset").unwrap_or(0) + field(accessor, "byteOffset").unwrap_or(0)) as usize;
+    let stride = field(view, "byteStride").map_or(components * component_size, |s| s as usize);
+
+    let mut values = Vec::with_capacity(count * components);
+    for item in 0..count {
+        for component in 0..components {
+            let offset = start + item * stride + component * component_size;
+            let bytes = buffer
+                .get(offset..offset + component_size)
+                .ok_or("Accessor reads past the end of its buffer")?;
+            values.push(match component_type {
+                COMPONENT_U8 => f32::from(bytes[0]),
+                COMPONENT_U16 => f32::from(u16::from_le_bytes([bytes[0], bytes[1]])),
+                COMPONENT_U32 => u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f32,
+                _ => f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+            });
+        }
+    }
+    Ok(values)
+}
+
+/// Rasterize `mesh` into a `size` x `size` RGBA image
+pub fn render_mesh_thumbnail(mesh: &MeshGeometry, size: u32) -> Vec<u8> {
+    let size = size.max(1);
+    let pixels = (size * size) as usize;
+    let mut rgba = vec![0u8; pixels * 4];
+    let mut depth = vec![f32::NEG_INFINITY; pixels];
+    if mesh.positions.is_empty() {
+        return rgba;
+    }
+
+    // Fit the bounding sphere of the mesh into the image
+    let (min, max) = mesh.positions.iter().fold(
+        (Vec3::new(f32::MAX, f32::MAX, f32::MAX), Vec3::new(f32::MIN, f32::MIN, f32::MIN)),
+        |(min, max), p| {
+            (
+                Vec3::new(min.x.min(p[0]), min.y.min(p[1]), min.z.min(p[2])),
+                Vec3::new(max.x.max(p[0]), max.y.max(p[1]), max.z.max(p[2])),
+            )
+        },
+    );
+    let center = (min + max) * 0.5;
+    let radius = ((max - min).length() * 0.5).max(1e-6);
+    let half = size as f32 * 0.5;
+    let scale = half * FILL / radius;
+
+    // 3/4 view: camera above, in front and to the right
+    let toward_camera = Vec3::new(1.0, 0.8, 1.0).normalize();
+    let right = Vec3::new(0.0, 1.0, 0.0).cross(&toward_camera).normalize();
+    let up = toward_camera.cross(&right);
+    let light = Vec3::new(0.4, 1.0, 0.7).normalize();
+
+    // Screen position with depth toward the camera, and the world position
+    let project = |index: u32| {
+        let world = Vec3::from_array(mesh.positions.get(index as usize).copied().unwrap_or_default());
+        let p = world - center;
+        (
+            Vec3::new(half + p.dot(&right) * scale, half - p.dot(&up) * scale, p.dot(&toward_camera)),
+            world,
+        )
+    };
+
+    for triangle in &mesh.triangles {
+        let [(a, wa), (b, wb), (c, wc)] = triangle.map(&project);
+        let area = edge(a, b, c);
+        if area.abs() < f32::EPSILON {
+            continue;
+        }
+
+        // Two-sided flat shading
+        let normal = (wb - wa).cross(&(wc - wa)).normalize();
+        let shade = 0.3 + 0.7 * normal.dot(&light).abs();
+        let color = BASE_COLOR.map(|channel| (channel * shade).clamp(0.0, 255.0) as u8);
+
+        let min_x = a.x.min(b.x).min(c.x).floor().max(0.0) as u32;
+        let min_y = a.y.min(b.y).min(c.y).floor().max(0.0) as u32;
+        let max_x = (a.x.max(b.x).max(c.x).ceil() as u32).min(size - 1);
+        let max_y = (a.y.max(b.y).max(c.y).ceil() as u32).min(size - 1);
+        for y in min_y..=max_y {
+            for x in min_x..=max_x {
+                let p = Vec3::new(x as f32 + 0.5, y as f32 + 0.5, 0.0);
+                let w0 = edge(b, c, p) / area;
+                let w1 = edge(c, a, p) / area;
+                let w2 = edge(a, b, p) / area;
+                if w0 < 0.0 || w1 < 0.0 || w2 < 0.0 {
+                    continue;
+                }
+
+                let z = w0 * a.z + w1 * b.z + w2 * c.z;
+                let pixel = (y * size + x) as usize;
+                if z > depth[pixel] {
+                    depth[pixel] = z;
+                    rgba[pixel * 4..pixel * 4 + 4].copy_from_slice(&[color[0], color[1], color[2], 255]);
+                }
+            }
+        }
+    }
+    rgba
+}
+
+/// Signed area of the screen-space parallelogram spanned from `a`
+fn edge(a: Vec3, b: Vec3, p: Vec3) -> f32 {
+    (b.x - a.x) * (p.y - a.y) - (b.y - a.y) * (p.x - a.x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CUBE_OBJ: &str = "\
+v -1 -1 -1\nv 1 -1 -1\nv 1 1 -1\nv -1 1 -1\n\
+v -1 -1 1\nv 1 -1 1\nv 1 1 1\nv -1 1 1\n\
+f 1 2 3 4\nf 5 8 7 6\nf 1 5 6 2\nf 2 6 7 3\nf 3 7 8 4\nf 5 1 4 8\n";
+
+    #[test]
+    fn test_parse_obj_triangulates_faces() {
+        let mesh = parse_obj(CUBE_OBJ);
+        assert_eq!(mesh.positions.len(), 8);
+        assert_eq!(mesh.triangles.len(), 12);
+        assert_eq!(mesh.triangles[0], [0, 1, 2]);
+    }
+
+    #[test]
+    fn test_render_fills_center_and_keeps_background() {
+        let size = 32;
+        let rgba = render_mesh_thumbnail(&parse_obj(CUBE_OBJ), size);
+        let alpha = |x: u32, y: u32| rgba[((y * size + x) * 4 + 3) as usize];
+        assert_eq!(alpha(size / 2, size / 2), 255);
+        assert_eq!(alpha(0, 0), 0);
+    }
+
+    #[test]
+    fn test_gltf_external_buffer() {
+        let dir = std::env::temp_dir().join(format!("mesh_thumbnail_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let positions: Vec<u8> = [0.0f32, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0]
+            .iter()
+            .flat_map(|v| v.to_le_bytes())
+            .collect();
+        std::fs::write(dir.join("tri.bin"), &positions).unwrap();
+        let document = serde_json::json!({
+            "buffers": [{ "uri": "tri.bin", "byteLength": 36 }],
+            "bufferViews": [{ "buffer": 0, "byteLength": 36 }],
+            "accessors": [{ "bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3" }],
+            "meshes": [{ "primitives": [{ "attributes": { "POSITION": 0 } }] }],
+        });
+
+        let mesh = parse_gltf(&document, None, &dir).unwrap();
+        assert_eq!(mesh.positions[1], [1.0, 0.0, 0.0]);
+        assert_eq!(mesh.triangles, vec![[0, 1, 2]]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -16,6 +16,8 @@ pub enum PanelType {
     Console,
     /// Performance profiler
     Profiler,
+    /// Undo history
+    History,
     /// Material graph editor
     MaterialGraph,
     /// Gameplay graph (visual scripting)
@@ -34,6 +36,7 @@ impl PanelType {
             Self::AssetBrowser => "Asset Browser",
             Self::Console => "Console",
             Self::Profiler => "Profiler",
+            Self::History => "History",
             Self::MaterialGraph => "Material Graph",
             Self::GameplayGraph => "Gameplay Graph",
             Self::Sequencer => "Sequencer",
@@ -49,9 +52,84 @@ impl PanelType {
             Self::AssetBrowser => "\u{1f4c1}",  // folder
             Self::Console => "\u{1f4bb}",       // terminal
             Self::Profiler => "\u{1f4ca}",      // chart
+            Self::History => "\u{1f552}",       // clock
             Self::MaterialGraph => "\u{1f3a8}", // palette
             Self::GameplayGraph => "\u{1f500}", // branch
             Self::Sequencer => "\u{1f3ac}",     // film
         }
     }
 }
+
+/// Longest context name (in characters) shown on a tab before truncation
+pub const MAX_TAB_CONTEXT_CHARS: usize = 24;
+
+/// Title of a docked panel tab
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TabTitle {
+    /// Text shown on the tab
+    pub text: String,
+    /// Untruncated title, set when the context name had to be shortened
+    pub full: Option<String>,
+}
+
+impl TabTitle {
+    /// Build a tab title from the panel, the name of what it shows (scene,
+    /// sequence, graph) and whether that has unsaved changes
+    pub fn new(panel: PanelType, context: Option<&str>, dirty: bool) -> Self {
+        let marker = if dirty { "*" } else { "" };
+        let Some(context) = context.filter(|c| !c.is_empty()) else {
+            return Self {
+                text: format!("{} {}{}", panel.icon(), panel.name(), marker),
+                full: None,
+            };
+        };
+
+        let title = |context: &str| format!("{} {} - {}{}", panel.icon(), panel.name(), context, marker);
+        if context.chars().count() <= MAX_TAB_CONTEXT_CHARS {
+            return Self {
+                text: title(context),
+                full: None,
+            };
+        }
+
+        let truncated: String = context
+            .chars()
+            .take(MAX_TAB_CONTEXT_CHARS - 1)
+            .chain(std::iter::once('\u{2026}'))
+            .collect();
+        Self {
+            text: title(&truncated),
+            full: Some(title(context)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tab_title_dirty_marker() {
+        let clean = TabTitle::new(PanelType::Viewport, Some("Level1"), false);
+        assert_eq!(clean.text, "\u{1f3a5} Viewport - Level1");
+        assert_eq!(clean.full, None);
+
+        let dirty = TabTitle::new(PanelType::Viewport, Some("Level1"), true);
+        assert_eq!(dirty.text, "\u{1f3a5} Viewport - Level1*");
+
+        let plain = TabTitle::new(PanelType::Console, None, false);
+        assert_eq!(plain.text, "\u{1f4bb} Console");
+    }
+
+    #[test]
+    fn test_tab_title_truncates_long_names() {
+        let name = "A Very Long Cinematic Sequence Name";
+        let title = TabTitle::new(PanelType::Sequencer, Some(name), true);
+        assert_eq!(title.text, "\u{1f3ac} Sequencer - A Very Long Cinematic S\u{2026}*");
+        assert_eq!(title.full.as_deref(), Some("\u{1f3ac} Sequencer - A Very Long Cinematic Sequence Name*"));
+
+        // Exactly at the limit stays untouched
+        let exact = "x".repeat(MAX_TAB_CONTEXT_CHARS);
+        assert_eq!(TabTitle::new(PanelType::MaterialGraph, Some(&exact), false).full, None);
+    }
+}
@@ -2,13 +2,16 @@
 //! Asset browser panel - File/asset navigation.
 
 
+use crate::asset_references::{AssetReference, UnreadableFile};
+use crate::asset_templates::{self, AssetTemplate};
 use crate::file_watcher::{FileEvent, FileWatcherManager};
+use crate::import_settings::{self, ColorSpace, ImportSettings, TextureFilter};
 use crate::panel_types::PanelType;
 use crate::state::EditorState;
 use crate::thumbnail::{ThumbnailManager, ThumbnailState};
 use egui_wgpu::wgpu;
 use std::collections::HashSet;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// View mode for assets
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -190,7 +193,7 @@ pub struct AssetBrowserPanel {
     history: Vec<PathBuf>,
     /// Current history index
     history_index: usize,
-    /// Pending asset to drag (for drag-drop)
+    /// Asset being dragged; the selection moves with it when it's selected
     pub dragging_asset: Option<PathBuf>,
     /// Thumbnail manager for image previews
     pub thumbnail_manager: ThumbnailManager,
@@ -222,6 +225,40 @@ pub struct AssetBrowserPanel {
     rename_focus_request: bool,
     /// Path pending deletion confirmation
     pending_delete: Option<PathBuf>,
+    /// Renames and moves (from, to), waiting for a reference check
+    pending_moves: Vec<(PathBuf, PathBuf)>,
+    /// Delete or rename held back because scenes or prefabs use the asset,
+    /// or some couldn't be checked
+    reference_warning: Option<ReferenceWarning>,
+    /// Import settings of the single selected asset, while it has any
+    import_editor: Option<ImportSettingsEditor>,
+}
+
+/// Import settings being edited, applied to the `.meta` sidecar on demand
+struct ImportSettingsEditor {
+    /// Asset the settings belong to
+    path: PathBuf,
+    /// Settings as stored in the sidecar
+    saved: ImportSettings,
+    /// Settings with unapplied edits
+    edited: ImportSettings,
+}
+
+/// Asset edit that needs confirmation because other assets refer to it or
+/// couldn't be checked
+enum ReferenceWarning {
+    /// Delete of an asset
+    Delete {
+        path: PathBuf,
+        references: Vec<AssetReference>,
+        unreadable: Vec<UnreadableFile>,
+    },
+    /// Rename or move of assets, as (from, to) pairs
+    Move {
+        moves: Vec<(PathBuf, PathBuf)>,
+        references: Vec<AssetReference>,
+        unreadable: Vec<UnreadableFile>,
+    },
 }
 
 impl AssetBrowserPanel {
@@ -264,6 +301,9 @@ impl AssetBrowserPanel {
             rename_buffer: String::new(),
             rename_focus_request: false,
             pending_delete: None,
+            pending_moves: Vec::new(),
+            reference_warning: None,
+            import_editor: None,
         };
 
         panel.expanded_dirs.insert(root);
@@ -290,8 +330,17 @@ impl AssetBrowserPanel {
         }
     }
 
+    /// Also report changes under `dir`, outside the asset root, through
+    /// [`Self::take_modified_paths`]
+    pub fn watch_directory(&mut self, dir: &Path) {
+        let watcher = self.file_watcher.get_or_insert_with(FileWatcherManager::new);
+        match watcher.watch_directory(dir) {
+            Ok(()) => tracing::info!("File watcher started for: {:?}", dir),
+            Err(e) => tracing::warn!("Failed to watch {:?}: {}", dir, e),
+        }
+    }
+
     /// Poll file watcher for changes and trigger refresh if needed
-    #[allow(dead_code)] // Intentionally kept for API completeness
     pub fn poll_file_watcher(&mut self) {
         let Some(ref mut watcher) = self.file_watcher else {
             return;
@@ -353,11 +402,15 @@ impl AssetBrowserPanel {
     }
 
     /// Get paths that were recently modified (for hot reload)
-    #[allow(dead_code)] // Intentionally kept for API completeness
     pub fn take_modified_paths(&mut self) -> HashSet<PathBuf> {
         std::mem::take(&mut self.modified_paths)
     }
 
+    /// Check if file events are waiting to settle before they're reported
+    pub fn has_pending_file_events(&self) -> bool {
+        self.file_watcher.as_ref().is_some_and(FileWatcherManager::has_pending)
+    }
+
     /// Check if file watcher is active
     #[allow(dead_code)] // Intentionally kept for API completeness
     pub fn has_file_watcher(&self) -> bool {
@@ -435,8 +488,8 @@ impl AssetBrowserPanel {
                     .map(|n| n.to_string_lossy().to_string())
                     .unwrap_or_default();
 
-                // Skip hidden files
-                if name.starts_with('.') {
+                // Skip hidden files and import settings sidecars
+                if name.starts_with('.') || import_settings::is_meta_file(&path) {
                     continue;
                 }
 
@@ -708,6 +761,13 @@ impl AssetBrowserPanel {
 
     /// Render the asset browser panel
     pub fn ui(&mut self, ui: &mut egui::Ui, state: &mut EditorState) {
+        // Thumbnails persist under the open project
+        let cache_dir = crate::asset_paths::asset_root(state.project_manager.project_dir.as_deref())
+            .join(crate::thumbnail::CACHE_DIR);
+        if self.thumbnail_manager.cache_dir() != Some(cache_dir.as_path()) {
+            self.thumbnail_manager.set_cache_dir(cache_dir);
+        }
+
         // Handle keyboard shortcuts
         let ctrl_f_pressed = ui.input(|i| i.modifiers.ctrl && i.key_pressed(egui::Key::F));
         let f5_pressed = ui.input(|i| i.key_pressed(egui::Key::F5));
@@ -748,6 +808,8 @@ impl AssetBrowserPanel {
                 self.refresh_filesystem();
             }
 
+            ui.menu_button("Add", |ui| self.create_menu(ui, state));
+
             ui.separator();
 
             // Breadcrumb navigation
@@ -848,6 +910,14 @@ impl AssetBrowserPanel {
                 });
         }
 
+        self.sync_import_editor();
+        if self.import_editor.is_some() {
+            egui::SidePanel::right("asset_import_panel")
+                .resizable(true)
+                .default_width(220.0)
+                .show_inside(ui, |ui| self.import_settings_ui(ui));
+        }
+
         // Content area
         egui::ScrollArea::vertical().show(ui, |ui| {
             match self.view_mode {
@@ -855,6 +925,147 @@ impl AssetBrowserPanel {
                 AssetViewMode::List => self.list_view(ui, state),
             }
         });
+
+        // Handle inline rename: render overlay/check for completion
+        if self.renaming_path.is_some() {
+            self.show_rename_ui(ui);
+        }
+
+        // A drag released anywhere but on a folder is dropped
+        if self.dragging_asset.is_some() {
+            ui.ctx().set_cursor_icon(egui::CursorIcon::Grabbing);
+            if ui.input(|i| i.pointer.any_released()) {
+                self.dragging_asset = None;
+            }
+        }
+
+        self.handle_pending_edits(ui.ctx(), state);
+    }
+
+    /// Load the import settings of the selected asset when a single asset
+    /// with import settings is selected
+    fn sync_import_editor(&mut self) {
+        let path = match self.selected.as_slice() {
+            [path] if ImportSettings::for_asset(path).is_some() && path.is_file() => path,
+            _ => {
+                self.import_editor = None;
+                return;
+            }
+        };
+        if self.import_editor.as_ref().is_some_and(|editor| &editor.path == path) {
+            return;
+        }
+
+        // Sidecars are written on Apply, so browsing never touches disk
+        self.import_editor = match ImportSettings::load_or_default(path) {
+            Ok(Some(settings)) => Some(ImportSettingsEditor {
+                path: path.clone(),
+                saved: settings.clone(),
+                edited: settings,
+            }),
+            Ok(None) => None,
+            Err(e) => {
+                tracing::warn!("Failed to read import settings for {}: {}", path.display(), e);
+                None
+            }
+        };
+    }
+
+    /// Import settings of the selected asset, with Apply/Revert
+    fn import_settings_ui(&mut self, ui: &mut egui::Ui) {
+        let Some(editor) = &mut self.import_editor else {
+            return;
+        };
+
+        ui.heading(format!("{} Import Settings", editor.edited.label()));
+        let name = editor
+            .path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        ui.label(egui::RichText::new(name).weak());
+        ui.separator();
+
+        egui::Grid::new("import_settings_grid")
+            .num_columns(2)
+            .spacing([8.0, 4.0])
+            .show(ui, |ui| match &mut editor.edited {
+                ImportSettings::Texture(texture) => {
+                    ui.label("Color Space");
+                    egui::ComboBox::from_id_salt("import_color_space")
+                        .selected_text(texture.color_space.name())
+                        .show_ui(ui, |ui| {
+                            for color_space in [ColorSpace::Srgb, ColorSpace::Linear] {
+                                ui.selectable_value(&mut texture.color_space, color_space, color_space.name());
+                            }
+                        });
+                    ui.end_row();
+
+                    ui.label("Generate Mipmaps");
+                    ui.checkbox(&mut texture.generate_mipmaps, "");
+                    ui.end_row();
+
+                    ui.label("Filter Mode");
+                    egui::ComboBox::from_id_salt("import_filter")
+                        .selected_text(texture.filter.name())
+                        .show_ui(ui, |ui| {
+                            for filter in [TextureFilter::Linear, TextureFilter::Nearest] {
+                                ui.selectable_value(&mut texture.filter, filter, filter.name());
+                            }
+                        });
+                    ui.end_row();
+
+                    ui.label("Max Size");
+                    egui::ComboBox::from_id_salt("import_max_size")
+                        .selected_text(texture.max_size.to_string())
+                        .show_ui(ui, |ui| {
+                            for size in [256, 512, 1024, 2048, 4096, 8192] {
+                                ui.selectable_value(&mut texture.max_size, size, size.to_string());
+                            }
+                        });
+                    ui.end_row();
+                }
+                ImportSettings::Model(model) => {
+                    ui.label("Scale");
+                    ui.add(egui::DragValue::new(&mut model.scale).speed(0.01).range(0.001..=1000.0));
+                    ui.end_row();
+
+                    ui.label("Generate Normals");
+                    ui.checkbox(&mut model.generate_normals, "");
+                    ui.end_row();
+
+                    ui.label("Import Materials");
+                    ui.checkbox(&mut model.import_materials, "");
+                    ui.end_row();
+                }
+                ImportSettings::Audio(audio) => {
+                    ui.label("Streaming");
+                    ui.checkbox(&mut audio.streaming, "");
+                    ui.end_row();
+
+                    ui.label("Force Mono");
+                    ui.checkbox(&mut audio.force_mono, "");
+                    ui.end_row();
+                }
+            });
+
+        ui.separator();
+        let dirty = editor.edited != editor.saved;
+        ui.horizontal(|ui| {
+            if ui.add_enabled(dirty, egui::Button::new("Apply")).clicked() {
+                // The file watcher picks up the sidecar and regenerates the thumbnail
+                match editor.edited.save(&editor.path) {
+                    Ok(()) => {
+                        editor.saved = editor.edited.clone();
+                        tracing::info!("Applied import settings for {}", editor.path.display());
+                    }
+                    Err(e) => tracing::error!("Failed to save import settings for {}: {}", editor.path.display(), e),
+                }
+            }
+            if ui.add_enabled(dirty, egui::Button::new("Revert")).clicked() {
+                editor.edited = editor.saved.clone();
+            }
+        });
     }
 
     fn render_breadcrumb(&mut self, ui: &mut egui::Ui) {
@@ -933,13 +1144,18 @@ impl AssetBrowserPanel {
 
             let response = ui.add(
                 egui::Label::new(egui::RichText::new(&entry.name).color(name_color))
-                    .sense(egui::Sense::click())
+                    .sense(egui::Sense::click_and_drag())
             );
 
             if response.clicked() {
                 self.navigate_to(entry.path.clone());
             }
 
+            if response.drag_started() && entry.path != self.root_path {
+                self.dragging_asset = Some(entry.path.clone());
+            }
+            self.folder_drop_target(ui, &response, &entry.path);
+
             // Highlight selected
             if is_selected {
                 let rect = response.rect.expand(2.0);
@@ -970,15 +1186,6 @@ impl AssetBrowserPanel {
         let mut new_selection: Option<PathBuf> = None;
         let mut open_path: Option<PathBuf> = None;
 
-        // Request thumbnails for visible texture assets
-        if self.show_thumbnails {
-            for (path, _, asset_type, is_folder, _) in &filtered_data {
-                if !*is_folder && *asset_type == AssetType::Texture {
-                    self.thumbnail_manager.request_thumbnail(path);
-                }
-            }
-        }
-
         egui::Grid::new("asset_grid")
             .num_columns(columns)
             .spacing([8.0, 8.0])
@@ -1002,12 +1209,19 @@ impl AssetBrowserPanel {
                                 );
                             }
 
-                            // Try to render thumbnail for texture assets
-                            let rendered_thumbnail = if self.show_thumbnails && !*is_folder && *asset_type == AssetType::Texture {
-                                self.render_thumbnail(ui, path, icon_rect)
-                            } else {
-                                false
-                            };
+                            // Try to render thumbnail for texture and mesh assets,
+                            // requesting it once the cell scrolls into view
+                            let has_thumbnail = self.show_thumbnails
+                                && !*is_folder
+                                && matches!(asset_type, AssetType::Texture | AssetType::Mesh);
+                            if has_thumbnail && ui.is_rect_visible(icon_rect) {
+                                if *asset_type == AssetType::Mesh {
+                                    self.thumbnail_manager.request_mesh_thumbnail(path);
+                                } else {
+                                    self.thumbnail_manager.request_thumbnail(path);
+                                }
+                            }
+                            let rendered_thumbnail = has_thumbnail && self.render_thumbnail(ui, path, icon_rect);
 
                             // Fall back to icon if no thumbnail
                             if !rendered_thumbnail {
@@ -1036,8 +1250,16 @@ impl AssetBrowserPanel {
 
                             ui.label(egui::RichText::new(display_name).small());
                         });
+                        let response = response.response.interact(egui::Sense::click_and_drag());
 
-                        if response.response.clicked() {
+                        if response.drag_started() {
+                            self.dragging_asset = Some(path.clone());
+                        }
+                        if *is_folder {
+                            self.folder_drop_target(ui, &response, path);
+                        }
+
+                        if response.clicked() {
                             if *is_folder {
                                 new_path = Some(path.clone());
                             } else {
@@ -1045,7 +1267,7 @@ impl AssetBrowserPanel {
                             }
                         }
 
-                        if response.response.double_clicked() {
+                        if response.double_clicked() {
                             if *is_folder {
                                 new_path = Some(path.clone());
                             } else {
@@ -1061,6 +1283,7 @@ impl AssetBrowserPanel {
             self.navigate_to(path);
         }
         if let Some(path) = new_selection {
+            state.selected_asset = Some(path.clone());
             self.selected = vec![path];
         }
         if let Some(path) = open_path {
@@ -1109,7 +1332,7 @@ impl AssetBrowserPanel {
 
                 // Name
                 let name_response = ui.add(
-                    egui::Label::new(&asset.name).sense(egui::Sense::click())
+                    egui::Label::new(&asset.name).sense(egui::Sense::click_and_drag())
                 );
 
                 // Show asset type on the right
@@ -1124,6 +1347,7 @@ impl AssetBrowserPanel {
                 if asset.is_folder {
                     self.navigate_to(asset.path.clone());
                 } else {
+                    state.selected_asset = Some(asset.path.clone());
                     self.selected = vec![asset.path.clone()];
                 }
             }
@@ -1132,6 +1356,13 @@ impl AssetBrowserPanel {
                 self.open_asset(state, &asset.path);
             }
 
+            if response.inner.drag_started() {
+                self.dragging_asset = Some(asset.path.clone());
+            }
+            if asset.is_folder {
+                self.folder_drop_target(ui, &response.inner, &asset.path);
+            }
+
             // Context menu
             response.inner.context_menu(|ui| {
                 if ui.button("Open").clicked() {
@@ -1151,6 +1382,13 @@ impl AssetBrowserPanel {
                     ui.output_mut(|o| o.copied_text = asset.path.display().to_string());
                     ui.close_menu();
                 }
+                if asset.asset_type == AssetType::Prefab && ui.button("Create Variant").clicked() {
+                    match state.create_variant_from(&asset.path) {
+                        Ok(path) => self.select_created(state, &path),
+                        Err(e) => tracing::error!("{}", e),
+                    }
+                    ui.close_menu();
+                }
                 ui.separator();
                 if ui.button("Rename").clicked() {
                     self.begin_rename(&asset.path);
@@ -1160,23 +1398,15 @@ impl AssetBrowserPanel {
                     self.pending_delete = Some(asset.path.clone());
                     ui.close_menu();
                 }
+                ui.separator();
+                self.create_menu(ui, state);
             });
         }
-
-        // Handle pending delete
-        if let Some(path) = self.pending_delete.take() {
-            self.delete_asset(&path);
-        }
-
-        // Handle inline rename: render overlay/check for completion
-        if self.renaming_path.is_some() {
-            self.show_rename_ui(ui);
-        }
     }
 
     /// Render a thumbnail for the given path in the given rect
     /// Returns true if a thumbnail was rendered, false if fallback to icon is needed
-    fn render_thumbnail(&self, ui: &mut egui::Ui, path: &std::path::Path, rect: egui::Rect) -> bool {
+    fn render_thumbnail(&self, ui: &mut egui::Ui, path: &Path, rect: egui::Rect) -> bool {
         match self.thumbnail_manager.get_state(path) {
             ThumbnailState::Ready(texture_id) => {
                 // Draw the thumbnail image
@@ -1257,7 +1487,7 @@ impl AssetBrowserPanel {
     }
 
     /// Open the OS file manager and select/reveal the given path
-    fn show_in_explorer(path: &std::path::Path) {
+    fn show_in_explorer(path: &Path) {
         let canonical = std::fs::canonicalize(path)
             .unwrap_or_else(|_| path.to_path_buf());
 
@@ -1288,8 +1518,45 @@ impl AssetBrowserPanel {
         tracing::info!("Show in explorer: {}", canonical.display());
     }
 
+    /// "New Folder" and the "Create" submenu of empty assets, all created
+    /// in the current directory
+    fn create_menu(&mut self, ui: &mut egui::Ui, state: &mut EditorState) {
+        if ui.button("New Folder").clicked() {
+            match asset_templates::create_folder(&self.current_path) {
+                Ok(path) => {
+                    self.select_created(state, &path);
+                    self.begin_rename(&path);
+                }
+                Err(e) => tracing::error!("{}", e),
+            }
+            ui.close_menu();
+        }
+        ui.menu_button("Create", |ui| {
+            for template in AssetTemplate::ALL {
+                if ui.button(template.label()).clicked() {
+                    match asset_templates::create_asset(&self.current_path, template) {
+                        Ok(path) => self.select_created(state, &path),
+                        Err(e) => tracing::error!("{}", e),
+                    }
+                    ui.close_menu();
+                }
+            }
+        });
+    }
+
+    /// Show a newly created file or folder and select it
+    fn select_created(&mut self, state: &mut EditorState, path: &Path) {
+        tracing::info!("Created {}", path.display());
+        self.needs_refresh = true;
+        self.scan_current_directory();
+        if !path.is_dir() {
+            state.selected_asset = Some(path.to_path_buf());
+        }
+        self.selected = vec![path.to_path_buf()];
+    }
+
     /// Begin inline rename for the given path
-    fn begin_rename(&mut self, path: &std::path::Path) {
+    fn begin_rename(&mut self, path: &Path) {
         let name = path
             .file_name()
             .map(|n| n.to_string_lossy().to_string())
@@ -1299,7 +1566,7 @@ impl AssetBrowserPanel {
         self.rename_focus_request = true;
     }
 
-    /// Finish inline rename — actually rename the file/directory on disk
+    /// Finish inline rename — queue the rename for the reference check
     fn finish_rename(&mut self) {
         if let Some(old_path) = self.renaming_path.take() {
             let new_name = self.rename_buffer.trim();
@@ -1312,24 +1579,235 @@ impl AssetBrowserPanel {
                 if new_path == old_path {
                     return; // no change
                 }
-                match std::fs::rename(&old_path, &new_path) {
-                    Ok(()) => {
-                        tracing::info!("Renamed {} -> {}", old_path.display(), new_path.display());
-                        for sel in &mut self.selected {
-                            if *sel == old_path {
-                                *sel = new_path.clone();
-                            }
-                        }
-                        self.needs_refresh = true;
-                        self.scan_current_directory();
-                    }
-                    Err(e) => {
-                        tracing::error!("Failed to rename {}: {}", old_path.display(), e);
+                self.pending_moves.push((old_path, new_path));
+            }
+        }
+        self.rename_buffer.clear();
+    }
+
+    /// Rename a file or directory on disk. Returns whether it succeeded.
+    fn rename_asset(&mut self, old_path: &Path, new_path: &Path) -> bool {
+        match std::fs::rename(old_path, new_path) {
+            Ok(()) => {
+                tracing::info!("Renamed {} -> {}", old_path.display(), new_path.display());
+                if let Err(e) = import_settings::move_meta(old_path, new_path) {
+                    tracing::warn!("Failed to move import settings of {}: {}", old_path.display(), e);
+                }
+                for sel in &mut self.selected {
+                    if sel.as_path() == old_path {
+                        *sel = new_path.to_path_buf();
                     }
                 }
+                self.needs_refresh = true;
+                if new_path.is_dir() {
+                    self.scan_directory_tree();
+                }
+                self.scan_current_directory();
+                true
+            }
+            Err(e) => {
+                tracing::error!("Failed to rename {}: {}", old_path.display(), e);
+                false
             }
         }
-        self.rename_buffer.clear();
+    }
+
+    /// Assets that move with the dragged one: the whole selection when the
+    /// dragged asset is part of it
+    fn drag_sources(&self) -> Vec<PathBuf> {
+        match &self.dragging_asset {
+            Some(dragging) if self.selected.contains(dragging) => self.selected.clone(),
+            Some(dragging) => vec![dragging.clone()],
+            None => Vec::new(),
+        }
+    }
+
+    /// Highlight `folder` while a drag hovers it and queue the move of the
+    /// dragged assets when released over it
+    fn folder_drop_target(&mut self, ui: &egui::Ui, response: &egui::Response, folder: &Path) {
+        // Hovering the dragged folder itself is not a drop
+        if self.dragging_asset.is_none() || self.dragging_asset.as_deref() == Some(folder) || !response.contains_pointer() {
+            return;
+        }
+
+        let sources = self.drag_sources();
+        let mut moves = Vec::new();
+        let mut rejection = None;
+        for source in &sources {
+            match Self::move_destination(source, folder) {
+                Ok(Some(destination)) => moves.push((source.clone(), destination)),
+                Ok(None) => {}
+                Err(reason) => rejection = Some(reason),
+            }
+        }
+
+        let fill = if rejection.is_some() {
+            egui::Color32::from_rgba_unmultiplied(180, 60, 60, 35)
+        } else {
+            egui::Color32::from_rgba_unmultiplied(60, 180, 90, 35)
+        };
+        ui.painter().rect_filled(response.rect.expand(2.0), 4.0, fill);
+
+        if ui.input(|i| i.pointer.any_released()) {
+            match rejection {
+                Some(reason) => tracing::warn!("Cannot move into {}: {}", folder.display(), reason),
+                None => self.pending_moves.extend(moves),
+            }
+            self.dragging_asset = None;
+        }
+    }
+
+    /// Where `source` ends up when moved into `folder`. `None` when it's
+    /// already there; an error for a folder dropped into itself or one of
+    /// its descendants, or when the name is taken.
+    fn move_destination(source: &Path, folder: &Path) -> Result<Option<PathBuf>, String> {
+        if source.parent() == Some(folder) {
+            return Ok(None);
+        }
+        if folder.starts_with(source) {
+            return Err("Cannot move a folder into itself".to_string());
+        }
+        let Some(name) = source.file_name() else {
+            return Err(format!("{} has no file name", source.display()));
+        };
+        let destination = folder.join(name);
+        if destination.exists() {
+            return Err(format!("{} already exists", destination.display()));
+        }
+        Ok(Some(destination))
+    }
+
+    /// Apply a pending delete, rename or move, first asking for
+    /// confirmation when scenes or prefabs refer to the assets
+    fn handle_pending_edits(&mut self, ctx: &egui::Context, state: &mut EditorState) {
+        if let Some(path) = self.pending_delete.take() {
+            let (references, unreadable) = state.find_asset_references(std::slice::from_ref(&path));
+            if references.is_empty() && unreadable.is_empty() {
+                self.delete_asset(&path);
+            } else {
+                self.reference_warning = Some(ReferenceWarning::Delete {
+                    path,
+                    references,
+                    unreadable,
+                });
+            }
+        }
+
+        if !self.pending_moves.is_empty() {
+            let moves = std::mem::take(&mut self.pending_moves);
+            let sources: Vec<PathBuf> = moves.iter().map(|(from, _)| from.clone()).collect();
+            let (references, unreadable) = state.find_asset_references(&sources);
+            if references.is_empty() && unreadable.is_empty() {
+                self.move_assets(&moves, false, state);
+            } else {
+                self.reference_warning = Some(ReferenceWarning::Move {
+                    moves,
+                    references,
+                    unreadable,
+                });
+            }
+        }
+
+        self.show_reference_warning(ctx, state);
+    }
+
+    /// Confirmation dialog listing the references to assets about to be
+    /// deleted, renamed or moved, and the files that couldn't be checked
+    fn show_reference_warning(&mut self, ctx: &egui::Context, state: &mut EditorState) {
+        let Some(warning) = &self.reference_warning else {
+            return;
+        };
+        let (verb, subject, references, unreadable) = match warning {
+            ReferenceWarning::Delete {
+                path,
+                references,
+                unreadable,
+            } => ("Delete", path.display().to_string(), references, unreadable),
+            ReferenceWarning::Move {
+                moves,
+                references,
+                unreadable,
+            } => {
+                let renaming = moves.iter().all(|(from, to)| from.parent() == to.parent());
+                let subject = match moves.as_slice() {
+                    [(from, _)] => from.display().to_string(),
+                    _ => format!("{} assets", moves.len()),
+                };
+                (if renaming { "Rename" } else { "Move" }, subject, references, unreadable)
+            }
+        };
+
+        let mut should_close = false;
+        let mut proceed = false;
+        let mut update_references = false;
+
+        egui::Window::new(format!("{} Referenced Asset", verb))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label(format!("{} is used in {} place(s):", subject, references.len()));
+                egui::ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
+                    for reference in references {
+                        ui.label(format!(
+                            "{}: {} ({})",
+                            reference.source.label(),
+                            reference.entity_name,
+                            reference.field
+                        ));
+                    }
+                });
+                if !unreadable.is_empty() {
+                    ui.add_space(6.0);
+                    ui.colored_label(
+                        egui::Color32::YELLOW,
+                        format!("{} file(s) couldn't be read and may also use it:", unreadable.len()),
+                    );
+                    egui::ScrollArea::vertical()
+                        .id_salt("unreadable_reference_files")
+                        .max_height(120.0)
+                        .show(ui, |ui| {
+                            for file in unreadable {
+                                ui.label(format!("{}: {}", file.path.display(), file.error));
+                            }
+                        });
+                }
+                ui.add_space(10.0);
+
+                ui.horizontal(|ui| {
+                    if ui.button("Cancel").clicked() {
+                        should_close = true;
+                    }
+                    match warning {
+                        ReferenceWarning::Delete { .. } => {
+                            if ui.button("Delete Anyway").clicked() {
+                                proceed = true;
+                                should_close = true;
+                            }
+                        }
+                        ReferenceWarning::Move { .. } => {
+                            if ui.button(format!("{} Only", verb)).clicked() {
+                                proceed = true;
+                                should_close = true;
+                            }
+                            if ui.button(format!("{} and Update References", verb)).clicked() {
+                                proceed = true;
+                                update_references = true;
+                                should_close = true;
+                            }
+                        }
+                    }
+                });
+            });
+
+        if !should_close {
+            return;
+        }
+        match self.reference_warning.take() {
+            Some(ReferenceWarning::Delete { path, .. }) if proceed => self.delete_asset(&path),
+            Some(ReferenceWarning::Move { moves, .. }) if proceed => self.move_assets(&moves, update_references, state),
+            _ => {}
+        }
     }
 
     /// Cancel inline rename
@@ -1339,14 +1817,14 @@ impl AssetBrowserPanel {
     }
 
     /// Delete a file or directory
-    fn delete_asset(&mut self, path: &std::path::Path) {
+    fn delete_asset(&mut self, path: &Path) {
         let path_str = path.display().to_string();
         let result = if path.is_dir() {
             tracing::info!("Deleting directory: {}", path_str);
             std::fs::remove_dir_all(path)
         } else {
             tracing::info!("Deleting file: {}", path_str);
-            std::fs::remove_file(path)
+            std::fs::remove_file(path).and_then(|()| import_settings::remove_meta(path))
         };
         match result {
             Ok(()) => {
@@ -1361,6 +1839,17 @@ impl AssetBrowserPanel {
         }
     }
 
+    /// Rename or move each (from, to) pair on disk, optionally pointing the
+    /// references at the new paths
+    fn move_assets(&mut self, moves: &[(PathBuf, PathBuf)], update_references: bool, state: &mut EditorState) {
+        for (from, to) in moves {
+            if self.rename_asset(from, to) && update_references {
+                let changed = state.retarget_asset_references(from, to);
+                tracing::info!("Updated {} asset reference(s) to {}", changed, to.display());
+            }
+        }
+    }
+
     /// Show an inline rename text edit UI at the bottom of the panel area
     fn show_rename_ui(&mut self, ui: &mut egui::Ui) {
         ui.separator();
@@ -1398,7 +1887,7 @@ impl AssetBrowserPanel {
         });
     }
 
-    fn open_asset(&mut self, state: &mut EditorState, path: &std::path::Path) {
+    fn open_asset(&mut self, state: &mut EditorState, path: &Path) {
         let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
         let asset_type = AssetType::from_extension(ext);
 
@@ -1445,3 +1934,33 @@ impl Default for AssetBrowserPanel {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_move_destination() {
+        let root = std::env::temp_dir().join(format!("asset_browser_{}", uuid::Uuid::new_v4()));
+        let props = root.join("props");
+        let nested = props.join("nested");
+        std::fs::create_dir_all(&nested).unwrap();
+
+        let crate_mesh = root.join("crate.glb");
+        assert_eq!(
+            AssetBrowserPanel::move_destination(&crate_mesh, &props),
+            Ok(Some(props.join("crate.glb")))
+        );
+        // Same folder is a no-op
+        assert_eq!(AssetBrowserPanel::move_destination(&crate_mesh, &root), Ok(None));
+        // A folder can't go into itself or its descendants
+        assert!(AssetBrowserPanel::move_destination(&props, &props).is_err());
+        assert!(AssetBrowserPanel::move_destination(&props, &nested).is_err());
+        // Existing names are not overwritten
+        assert!(AssetBrowserPanel::move_destination(&nested, &root.join("props")).unwrap().is_none());
+        std::fs::write(props.join("crate.glb"), "").unwrap();
+        assert!(AssetBrowserPanel::move_destination(&crate_mesh, &props).is_err());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
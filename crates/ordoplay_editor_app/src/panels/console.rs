@@ -4,6 +4,7 @@
 
 use crate::state::EditorState;
 use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
 use std::sync::mpsc;
 
 /// A tracing event captured by the [`TracingBridge`] layer.
@@ -191,7 +192,74 @@ pub struct LogEntry {
     pub message: String,
     pub timestamp: String,
     pub source: Option<SourceLocation>,
-    pub count: u32, // For collapsed duplicate messages
+    /// Receive order, counted from the start of the session
+    pub sequence: u64,
+    /// Lowercased message and source location, lowered once on arrival
+    /// so filtering doesn't redo it for the whole backlog every frame
+    search_text: String,
+}
+
+/// File format of a saved console log
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogExportFormat {
+    /// One readable line per entry
+    Text,
+    /// One JSON object per line
+    JsonLines,
+}
+
+impl LogExportFormat {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Text => "Plain text",
+            Self::JsonLines => "JSON lines",
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Text => "log",
+            Self::JsonLines => "jsonl",
+        }
+    }
+}
+
+/// Render `entries` in `format`, one line each
+pub fn export_log<'a>(entries: impl IntoIterator<Item = &'a LogEntry>, format: LogExportFormat) -> String {
+    let mut out = String::new();
+    for entry in entries {
+        let source = entry.source.as_ref().map(SourceLocation::display);
+        let line = match format {
+            LogExportFormat::Text => {
+                let mut line = format!("{} [{}] {} {}", entry.sequence, entry.timestamp, entry.level.name(), entry.message);
+                if let Some(source) = source {
+                    line.push_str(&format!(" @ {}", source));
+                }
+                line
+            }
+            LogExportFormat::JsonLines => serde_json::json!({
+                "sequence": entry.sequence,
+                "time": entry.timestamp,
+                "level": entry.level.name(),
+                "message": entry.message,
+                "source": source,
+            })
+            .to_string(),
+        };
+        out.push_str(&line);
+        out.push('\n');
+    }
+    out
+}
+
+/// A displayed console row: a run of identical consecutive entries when
+/// collapsing duplicates, otherwise a single entry
+#[derive(Debug, Clone, Copy)]
+pub struct LogRow<'a> {
+    /// Latest entry of the run
+    pub entry: &'a LogEntry,
+    /// Number of entries in the run
+    pub count: usize,
 }
 
 /// The console panel
@@ -227,6 +295,14 @@ pub struct ConsolePanel {
     pub show_error: bool,
     /// Pending source to jump to (will be handled externally)
     pub pending_jump: Option<SourceLocation>,
+    /// Destination of "Save Log", relative to the project directory unless absolute
+    pub export_path: String,
+    /// Format of "Save Log"
+    pub export_format: LogExportFormat,
+    /// Save only the entries passing the filters
+    pub export_filtered: bool,
+    /// Sequence number of the next entry
+    next_sequence: u64,
     /// Entry counts by level
     trace_count: usize,
     debug_count: usize,
@@ -247,7 +323,7 @@ impl ConsolePanel {
         let mut panel = Self {
             tracing_rx,
             entries: VecDeque::new(),
-            max_entries: 1000,
+            max_entries: 10_000,
             min_level: LogLevel::Info,
             search: String::new(),
             auto_scroll: true,
@@ -262,6 +338,10 @@ impl ConsolePanel {
             show_warn: true,
             show_error: true,
             pending_jump: None,
+            export_path: "console.log".to_string(),
+            export_format: LogExportFormat::Text,
+            export_filtered: true,
+            next_sequence: 0,
             trace_count: 0,
             debug_count: 0,
             info_count: 0,
@@ -314,35 +394,43 @@ impl ConsolePanel {
         let now = std::time::SystemTime::now();
 
         // Update counts
-        match level {
-            LogLevel::Trace => self.trace_count += 1,
-            LogLevel::Debug => self.debug_count += 1,
-            LogLevel::Info => self.info_count += 1,
-            LogLevel::Warn => self.warn_count += 1,
-            LogLevel::Error => self.error_count += 1,
-        }
+        *self.level_count_mut(level) += 1;
 
-        // Check if we should collapse with the previous entry
-        if self.collapse_duplicates {
-            if let Some(last) = self.entries.back_mut() {
-                if last.level == level && last.message == message {
-                    last.count += 1;
-                    last.timestamp = format_system_time(&now);
-                    return;
-                }
-            }
+        let mut search_text = message.to_lowercase();
+        if let Some(source) = &source {
+            // A newline can't be typed into the search, so no match spans both
+            search_text.push('\n');
+            search_text.push_str(&source.display().to_lowercase());
         }
 
+        // Every event is kept; duplicates are only grouped for display
         self.entries.push_back(LogEntry {
             level,
             message,
             timestamp: format_system_time(&now),
             source,
-            count: 1,
+            sequence: self.next_sequence,
+            search_text,
         });
+        self.next_sequence += 1;
 
+        // Keep the counts in step with the backlog
         while self.entries.len() > self.max_entries {
-            self.entries.pop_front();
+            if let Some(evicted) = self.entries.pop_front() {
+                let count = self.level_count_mut(evicted.level);
+                *count = count.saturating_sub(1);
+            }
+        }
+    }
+
+    /// Number of messages of `level` in the backlog
+    fn level_count_mut(&mut self, level: LogLevel) -> &mut usize {
+        match level {
+            LogLevel::Trace => &mut self.trace_count,
+            LogLevel::Debug => &mut self.debug_count,
+            LogLevel::Info => &mut self.info_count,
+            LogLevel::Warn => &mut self.warn_count,
+            LogLevel::Error => &mut self.error_count,
         }
     }
 
@@ -367,6 +455,59 @@ impl ConsolePanel {
         }
     }
 
+    /// Entries passing the level toggles and the search text, oldest first.
+    ///
+    /// The search is case-insensitive and also matches the source location.
+    /// Filtering never touches the backlog, so re-enabling a level or
+    /// clearing the search brings its entries back.
+    pub fn visible_entries(&self) -> Vec<&LogEntry> {
+        let search = self.search.trim().to_lowercase();
+        self.entries
+            .iter()
+            .filter(|entry| self.is_level_visible(entry.level))
+            .filter(|entry| search.is_empty() || entry.search_text.contains(&search))
+            .collect()
+    }
+
+    /// Rows to display: the visible entries, with runs of consecutive
+    /// entries of the same level and message grouped into one row when
+    /// collapsing duplicates
+    pub fn visible_rows(&self) -> Vec<LogRow<'_>> {
+        let mut rows: Vec<LogRow<'_>> = Vec::new();
+        for entry in self.visible_entries() {
+            match rows.last_mut() {
+                Some(row)
+                    if self.collapse_duplicates
+                        && row.entry.level == entry.level
+                        && row.entry.message == entry.message =>
+                {
+                    row.entry = entry;
+                    row.count += 1;
+                }
+                _ => rows.push(LogRow { entry, count: 1 }),
+            }
+        }
+        rows
+    }
+
+    /// Write the displayed entries, or the whole backlog, to `export_path`,
+    /// resolved against the project directory `root`
+    pub fn save_log(&self, root: &Path) -> Result<PathBuf, String> {
+        let export_path = self.export_path.trim();
+        if export_path.is_empty() {
+            return Err("No file name given".to_string());
+        }
+        let path = root.join(export_path);
+
+        let text = if self.export_filtered {
+            export_log(self.visible_entries(), self.export_format)
+        } else {
+            export_log(&self.entries, self.export_format)
+        };
+        std::fs::write(&path, text).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        Ok(path)
+    }
+
     /// Drain any pending tracing events into the log.
     pub fn poll_tracing_events(&mut self) {
         let Some(rx) = &self.tracing_rx else {
@@ -398,7 +539,7 @@ impl ConsolePanel {
     }
 
     /// Render the console panel
-    pub fn ui(&mut self, ui: &mut egui::Ui, _state: &mut EditorState) {
+    pub fn ui(&mut self, ui: &mut egui::Ui, state: &mut EditorState) {
         self.poll_tracing_events();
         // Toolbar
         ui.horizontal(|ui| {
@@ -443,6 +584,44 @@ impl ConsolePanel {
 
             ui.separator();
 
+            // Filtered subset size
+            let shown = self.visible_entries().len();
+            if shown < self.entries.len() {
+                ui.label(
+                    egui::RichText::new(format!("{} / {}", shown, self.entries.len()))
+                        .small()
+                        .color(egui::Color32::GRAY),
+                )
+                .on_hover_text("Entries shown / entries in the backlog");
+                ui.separator();
+            }
+
+            // Save log
+            ui.menu_button("Save Log\u{2026}", |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("File:");
+                    ui.text_edit_singleline(&mut self.export_path);
+                });
+                ui.horizontal(|ui| {
+                    for format in [LogExportFormat::Text, LogExportFormat::JsonLines] {
+                        if ui.radio(self.export_format == format, format.label()).clicked() {
+                            self.export_format = format;
+                            let path = PathBuf::from(&self.export_path).with_extension(format.extension());
+                            self.export_path = path.display().to_string();
+                        }
+                    }
+                });
+                ui.checkbox(&mut self.export_filtered, "Only displayed entries");
+                if ui.button("Save").clicked() {
+                    let root = crate::asset_paths::asset_root(state.project_manager.project_dir.as_deref());
+                    match self.save_log(&root) {
+                        Ok(path) => tracing::info!("Saved console log to {}", path.display()),
+                        Err(e) => tracing::error!("{}", e),
+                    }
+                    ui.close_menu();
+                }
+            });
+
             // Options menu
             ui.menu_button("Options", |ui| {
                 ui.checkbox(&mut self.auto_scroll, "Auto-scroll");
@@ -458,21 +637,10 @@ impl ConsolePanel {
             .auto_shrink([false, false])
             .stick_to_bottom(self.auto_scroll);
 
-        let entries_to_show: Vec<_> = self.entries.iter().filter(|entry| {
-            // Filter by level toggles
-            if !self.is_level_visible(entry.level) {
-                return false;
-            }
-            // Filter by search
-            if !self.search.is_empty()
-                && !entry.message.to_lowercase().contains(&self.search.to_lowercase()) {
-                    return false;
-                }
-            true
-        }).collect();
+        let rows = self.visible_rows();
 
         log_area.show(ui, |ui| {
-            for entry in entries_to_show {
+            for LogRow { entry, count } in rows {
                 // Draw background for warnings/errors
                 let bg_color = entry.level.bg_color();
 
@@ -505,9 +673,9 @@ impl ConsolePanel {
                     ui.label(level_text);
 
                     // Count badge for collapsed entries
-                    if entry.count > 1 {
+                    if count > 1 {
                         ui.label(
-                            egui::RichText::new(format!("({})", entry.count))
+                            egui::RichText::new(format!("\u{d7}{}", count))
                                 .monospace()
                                 .size(10.0)
                                 .color(egui::Color32::from_rgb(150, 150, 200)),
@@ -713,3 +881,110 @@ impl Default for ConsolePanel {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn panel() -> ConsolePanel {
+        let mut panel = ConsolePanel::new();
+        panel.clear();
+        panel.log(LogLevel::Info, "Scene loaded");
+        panel.log(LogLevel::Warn, "Texture missing mipmaps");
+        panel.log(LogLevel::Error, "Failed to load texture");
+        panel
+    }
+
+    #[test]
+    fn test_level_toggle_restores_entries() {
+        let mut panel = panel();
+        panel.show_info = false;
+        panel.show_warn = false;
+        assert_eq!(panel.visible_entries().len(), 1);
+        assert_eq!(panel.entries.len(), 3);
+
+        panel.show_info = true;
+        panel.show_warn = true;
+        assert_eq!(panel.visible_entries().len(), 3);
+    }
+
+    #[test]
+    fn test_search_is_case_insensitive() {
+        let mut panel = panel();
+        panel.search = "TEXTURE".to_string();
+        let messages: Vec<_> = panel.visible_entries().iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(messages, ["Texture missing mipmaps", "Failed to load texture"]);
+
+        panel.log_with_source(
+            LogLevel::Warn,
+            "Unused variable",
+            SourceLocation {
+                file: "Scripts/Player.rs".to_string(),
+                line: 7,
+                column: None,
+            },
+        );
+        panel.search = "player.RS:7".to_string();
+        let messages: Vec<_> = panel.visible_entries().iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(messages, ["Unused variable"]);
+    }
+
+    #[test]
+    fn test_duplicates_collapse_for_display_only() {
+        let mut panel = panel();
+        for _ in 0..3 {
+            panel.log(LogLevel::Warn, "Frame took too long");
+        }
+        panel.log(LogLevel::Info, "Saved");
+        panel.log(LogLevel::Warn, "Frame took too long");
+
+        let counts: Vec<usize> = panel.visible_rows().iter().map(|row| row.count).collect();
+        assert_eq!(counts, [1, 1, 1, 3, 1, 1]);
+        assert_eq!(panel.entries.len(), 8);
+
+        panel.collapse_duplicates = false;
+        assert_eq!(panel.visible_rows().len(), 8);
+    }
+
+    #[test]
+    fn test_export_formats() {
+        let mut panel = panel();
+        panel.search = "texture".to_string();
+        let entries = panel.visible_entries();
+
+        let text = export_log(entries.iter().copied(), LogExportFormat::Text);
+        assert_eq!(text.lines().count(), 2);
+        assert!(text.lines().next().unwrap().contains("WARN Texture missing mipmaps"));
+
+        let json = export_log(entries.iter().copied(), LogExportFormat::JsonLines);
+        let last: serde_json::Value = serde_json::from_str(json.lines().last().unwrap()).unwrap();
+        assert_eq!(last["level"], "ERROR");
+        assert_eq!(last["message"], "Failed to load texture");
+        assert_eq!(last["sequence"], entries[1].sequence);
+    }
+
+    #[test]
+    fn test_save_log_goes_into_the_project_directory() {
+        let root = std::env::temp_dir().join(format!("console_log_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&root).unwrap();
+
+        let mut panel = panel();
+        panel.export_filtered = false;
+        let path = panel.save_log(&root).unwrap();
+        assert_eq!(path, root.join("console.log"));
+        assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 3);
+
+        panel.export_path = "  ".to_string();
+        assert!(panel.save_log(&root).is_err());
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_counts_follow_evicted_entries() {
+        let mut panel = panel();
+        panel.max_entries = 2;
+        panel.log(LogLevel::Error, "Shader compile failed");
+        assert_eq!(panel.info_count, 0);
+        assert_eq!(panel.error_count, 2);
+    }
+}
@@ -1,9 +1,85 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 //! Hierarchy panel - Entity tree view.
 
-use crate::state::{EditorState, EntityId, SelectMode};
+use crate::entity_transfer::{drag_dir, DragPayload, EntitySubtree};
+use crate::state::{ColorLabel, EditorState, EntityId, SceneData, SelectMode, SimilarityCriterion};
+use crate::validation::ValidationSeverity;
 use std::collections::HashSet;
 
+/// Fraction of a row's height at its top and bottom edges that drops
+/// between siblings instead of onto the entity
+const INSERT_BAND: f32 = 0.25;
+
+/// Where a dragged entity lands relative to the row under the pointer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DropPlacement {
+    /// Above the row, among its siblings
+    Before,
+    /// Onto the row, as a child
+    Onto,
+    /// Below the row, among its siblings
+    After,
+}
+
+impl DropPlacement {
+    /// Placement for a pointer at `y` over a row spanning `rect`
+    fn at(rect: egui::Rect, y: f32) -> Self {
+        let t = (y - rect.top()) / rect.height().max(1.0);
+        if t < INSERT_BAND {
+            Self::Before
+        } else if t > 1.0 - INSERT_BAND {
+            Self::After
+        } else {
+            Self::Onto
+        }
+    }
+}
+
+/// Query prefix that matches entity tags instead of names
+const TAG_PREFIX: &str = "tag:";
+
+/// Entities kept in the tree by a search
+#[derive(Debug, Default)]
+struct SearchMatches {
+    /// Entities matching every search term
+    matched: HashSet<EntityId>,
+    /// Matches plus their ancestors, so matches stay reachable
+    visible: HashSet<EntityId>,
+}
+
+impl SearchMatches {
+    /// Evaluate a search. Terms are separated by whitespace and must all
+    /// match, case-insensitively: `tag:enemy` matches a tag, anything else
+    /// a part of the name.
+    fn find(scene: &SceneData, query: &str) -> Self {
+        let terms: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+        let mut matches = Self::default();
+
+        for (id, entity) in &scene.entities {
+            let name = entity.name.to_lowercase();
+            let is_match = terms.iter().all(|term| match term.strip_prefix(TAG_PREFIX) {
+                Some(tag) => entity.tags.iter().any(|t| t.to_lowercase() == tag),
+                None => name.contains(term.as_str()),
+            });
+            if !is_match {
+                continue;
+            }
+
+            matches.matched.insert(*id);
+            let mut current = Some(*id);
+            while let Some(entity_id) = current {
+                // Stop at ancestors already walked (or a parent cycle)
+                if !matches.visible.insert(entity_id) {
+                    break;
+                }
+                current = scene.entities.get(&entity_id).and_then(|e| e.parent);
+            }
+        }
+
+        matches
+    }
+}
+
 /// The hierarchy panel showing the entity tree
 pub struct HierarchyPanel {
     /// Search filter
@@ -18,6 +94,14 @@ pub struct HierarchyPanel {
     rename_buffer: String,
     /// Currently dragged entity (for reparenting)
     dragging_entity: Option<EntityId>,
+    /// The dragged subtree is published for other editor instances
+    drag_exported: bool,
+    /// Pointer is over the panel
+    pub hovered: bool,
+    /// Entity row under the pointer, the target for imported subtrees
+    pub hovered_entity: Option<EntityId>,
+    /// Result of the search filter this frame, `None` when it's empty
+    search: Option<SearchMatches>,
 }
 
 impl HierarchyPanel {
@@ -30,6 +114,10 @@ impl HierarchyPanel {
             renaming: None,
             rename_buffer: String::new(),
             dragging_entity: None,
+            drag_exported: false,
+            hovered: false,
+            hovered_entity: None,
+            search: None,
         }
     }
 
@@ -42,7 +130,8 @@ impl HierarchyPanel {
                 egui::TextEdit::singleline(&mut self.filter)
                     .hint_text("Search...")
                     .desired_width(ui.available_width() - 60.0),
-            );
+            )
+            .on_hover_text("Filter by name; use tag:name to filter by tag");
 
             // Add entity button
             if ui.button("+").on_hover_text("Create Entity").clicked() {
@@ -64,6 +153,8 @@ impl HierarchyPanel {
         });
 
         ui.separator();
+        self.hovered_entity = None;
+        self.search = (!self.filter.trim().is_empty()).then(|| SearchMatches::find(&state.scene, &self.filter));
 
         // Entity tree
         egui::ScrollArea::vertical().show(ui, |ui| {
@@ -102,6 +193,8 @@ impl HierarchyPanel {
                 ui.centered_and_justified(|ui| {
                     ui.label("No entities in scene");
                 });
+            } else if self.search.as_ref().is_some_and(|search| search.matched.is_empty()) {
+                ui.label(egui::RichText::new("No matching entities").weak());
             } else {
                 for entity_id in roots {
                     self.render_node(ui, entity_id, state, 0);
@@ -109,11 +202,66 @@ impl HierarchyPanel {
             }
         });
 
+        self.hovered = ui.rect_contains_pointer(ui.max_rect());
+        self.update_drag_export(ui, state);
         if ui.input(|i| i.pointer.any_released()) {
             self.dragging_entity = None;
         }
     }
 
+    /// Publish the dragged subtree for other editor instances while the
+    /// pointer is outside the window, and withdraw it if the drag comes
+    /// back or ends inside
+    fn update_drag_export(&mut self, ui: &egui::Ui, state: &EditorState) {
+        let dir = drag_dir();
+        let outside = ui
+            .input(|i| i.pointer.latest_pos())
+            .map_or(true, |pos| !ui.ctx().screen_rect().contains(pos));
+        let Some(dragging) = self.dragging_entity.filter(|_| outside) else {
+            if std::mem::take(&mut self.drag_exported) {
+                DragPayload::withdraw(&dir);
+            }
+            return;
+        };
+
+        let released = ui.input(|i| i.pointer.any_released());
+        if self.drag_exported && !released {
+            return;
+        }
+        let Some(subtree) = EntitySubtree::capture(&state.scene, dragging) else {
+            return;
+        };
+        let count = subtree.entities.len();
+        let mut payload = DragPayload::new(subtree);
+        payload.released = released;
+        match payload.publish(&dir) {
+            // A released payload now belongs to whichever editor takes it
+            Ok(()) => self.drag_exported = !released,
+            Err(err) => tracing::warn!("Failed to export dragged entities: {}", err),
+        }
+        if released {
+            tracing::info!("Dropped {} entities outside the editor for another editor to import", count);
+        }
+    }
+
+    /// Put an entity's subtree on the OS clipboard so another editor
+    /// instance can import it by pasting
+    fn copy_for_transfer(ctx: &egui::Context, state: &EditorState, entity_id: EntityId) {
+        let Some(subtree) = EntitySubtree::capture(&state.scene, entity_id) else {
+            return;
+        };
+        match subtree.to_ron() {
+            Ok(text) => {
+                ctx.copy_text(text);
+                tracing::info!(
+                    "Copied {} entities for transfer; paste into another editor to import",
+                    subtree.entities.len()
+                );
+            }
+            Err(err) => tracing::warn!("Failed to serialize dragged entities: {}", err),
+        }
+    }
+
     fn render_node(&mut self, ui: &mut egui::Ui, entity_id: EntityId, state: &mut EditorState, depth: usize) {
         // Get entity data
         let entity = match state.scene.get(&entity_id) {
@@ -122,18 +270,11 @@ impl HierarchyPanel {
         };
 
         // Filter check
-        if !self.filter.is_empty() {
-            let matches_filter = entity.name.to_lowercase().contains(&self.filter.to_lowercase());
-            let any_child_matches = entity.children.iter().any(|child_id| {
-                state.scene.get(child_id)
-                    .map(|c| c.name.to_lowercase().contains(&self.filter.to_lowercase()))
-                    .unwrap_or(false)
-            });
-
-            if !matches_filter && !any_child_matches {
-                return;
-            }
-        }
+        let is_match = match &self.search {
+            Some(search) if !search.visible.contains(&entity_id) => return,
+            Some(search) => search.matched.contains(&entity_id),
+            None => false,
+        };
 
         // Hidden check (based on active flag)
         if !entity.active && !self.show_hidden {
@@ -141,15 +282,26 @@ impl HierarchyPanel {
         }
 
         let is_selected = state.selection.contains(&entity_id);
+        // Children of inactive entities are inactive too
+        let active_in_hierarchy = state.scene.is_active_in_hierarchy(entity_id);
         let is_expanded = self.expanded.contains(&entity_id);
         let has_children = !entity.children.is_empty();
+        // Searching reveals every path to a match without touching the
+        // expansion state, so clearing the search restores the tree
+        let shows_children = has_children
+            && match &self.search {
+                Some(search) => entity.children.iter().any(|child| search.visible.contains(child)),
+                None => is_expanded,
+            };
 
         ui.horizontal(|ui| {
             // Indentation
             ui.add_space(depth as f32 * 16.0);
 
             // Expand/collapse button
-            if has_children {
+            if has_children && self.search.is_some() {
+                ui.add_enabled(false, egui::Button::new(if shows_children { "v" } else { ">" }).small());
+            } else if has_children {
                 let icon = if is_expanded { "v" } else { ">" };
                 if ui.small_button(icon).clicked() {
                     if is_expanded {
@@ -180,6 +332,30 @@ impl HierarchyPanel {
                     .on_hover_text("Prefab Instance (child)");
             }
 
+            // Validation warnings
+            let issues = state.validator.issues_for(&entity_id);
+            if let Some(severity) = issues.iter().map(|i| i.severity).max() {
+                let color = match severity {
+                    ValidationSeverity::Warning => egui::Color32::from_rgb(230, 180, 60),
+                    ValidationSeverity::Error => egui::Color32::from_rgb(220, 80, 80),
+                };
+                let tooltip = issues
+                    .iter()
+                    .map(|i| i.message.as_str())
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                ui.label(egui::RichText::new("\u{26A0}").color(color))
+                    .on_hover_text(tooltip);
+            }
+
+            // Type icon, tinted with the color label
+            let icon_color = match entity.color_label {
+                Some(label) => label_color(label),
+                None => ui.style().visuals.text_color(),
+            };
+            let icon_color = if active_in_hierarchy { icon_color } else { icon_color.gamma_multiply(0.4) };
+            ui.label(egui::RichText::new(entity.icon()).color(icon_color));
+
             // Entity name (selectable)
             let response = if self.renaming == Some(entity_id) {
                 // Rename mode
@@ -198,13 +374,19 @@ impl HierarchyPanel {
                 response
             } else {
                 // Normal display - dim text if inactive
-                let text_color = if entity.active {
+                let text_color = if is_match {
+                    egui::Color32::from_rgb(255, 210, 90)
+                } else if active_in_hierarchy {
                     ui.style().visuals.text_color()
                 } else {
                     ui.style().visuals.weak_text_color()
                 };
 
-                let label = egui::SelectableLabel::new(is_selected, egui::RichText::new(&entity.name).color(text_color));
+                let mut text = egui::RichText::new(&entity.name).color(text_color);
+                if is_match {
+                    text = text.strong();
+                }
+                let label = egui::SelectableLabel::new(is_selected, text);
                 let response = ui.add(label);
 
                 // Double-click to rename
@@ -220,35 +402,68 @@ impl HierarchyPanel {
                 self.dragging_entity = Some(entity_id);
             }
 
-            if let Some(dragging) = self.dragging_entity {
-                let dropped = response.hovered() && ui.input(|i| i.pointer.any_released());
-                if dropped && dragging != entity_id {
-                    let sources = self.drag_sources(state, dragging);
-                    if !self.is_invalid_drop(state, &sources, entity_id) {
-                        state.reparent_entities_with_command(&sources, Some(entity_id));
-                    }
-                    self.dragging_entity = None;
-                }
+            if response.hovered() {
+                self.hovered_entity = Some(entity_id);
+            }
 
-                if response.hovered() && dragging != entity_id {
-                    let sources = self.drag_sources(state, dragging);
-                    let invalid = self.is_invalid_drop(state, &sources, entity_id);
-                    let fill = if invalid {
-                        egui::Color32::from_rgba_unmultiplied(180, 60, 60, 35)
-                    } else {
-                        egui::Color32::from_rgba_unmultiplied(60, 180, 90, 35)
-                    };
-                    ui.painter().rect_filled(response.rect, 4.0, fill);
-                    response.clone().on_hover_text(if invalid {
-                        "Cannot parent to self or descendant"
+            if let Some(dragging) = self.dragging_entity.filter(|dragging| *dragging != entity_id) {
+                let pointer = ui.input(|i| i.pointer.interact_pos()).filter(|_| response.hovered());
+                if let Some(pointer) = pointer {
+                    let placement = DropPlacement::at(response.rect, pointer.y);
+                    let released = ui.input(|i| i.pointer.any_released());
+                    if placement == DropPlacement::Onto {
+                        let sources = self.drag_sources(state, dragging);
+                        let invalid = self.is_invalid_drop(state, &sources, entity_id);
+                        if released {
+                            if !invalid {
+                                state.reparent_entities_with_command(&sources, Some(entity_id));
+                            }
+                            self.dragging_entity = None;
+                        }
+
+                        let fill = if invalid {
+                            egui::Color32::from_rgba_unmultiplied(180, 60, 60, 35)
+                        } else {
+                            egui::Color32::from_rgba_unmultiplied(60, 180, 90, 35)
+                        };
+                        ui.painter().rect_filled(response.rect, 4.0, fill);
+                        response.clone().on_hover_text(if invalid {
+                            "Cannot parent to self or descendant"
+                        } else {
+                            "Drop to reparent"
+                        });
                     } else {
-                        "Drop to reparent"
-                    });
+                        let target = Self::sibling_insert_index(state, dragging, entity_id, placement);
+                        if released {
+                            if let Some(to) = target {
+                                state.reorder_sibling(dragging, to);
+                            }
+                            self.dragging_entity = None;
+                        }
+
+                        // Insertion indicator line across the row
+                        let y = if placement == DropPlacement::Before {
+                            response.rect.top()
+                        } else {
+                            response.rect.bottom()
+                        };
+                        let color = if target.is_some() {
+                            egui::Color32::from_rgb(90, 160, 255)
+                        } else {
+                            egui::Color32::from_rgb(180, 60, 60)
+                        };
+                        ui.painter().hline(ui.max_rect().x_range(), y, egui::Stroke::new(2.0, color));
+                        response.clone().on_hover_text(if target.is_some() {
+                            "Drop to reorder"
+                        } else {
+                            "Only siblings can be reordered; drop onto an entity to reparent"
+                        });
+                    }
                 }
             }
 
-            // Handle selection
-            if response.clicked() {
+            // Handle selection (or finish an eyedropper pick)
+            if response.clicked() && !state.complete_entity_pick(entity_id) {
                 let modifiers = ui.input(|i| i.modifiers);
                 if modifiers.shift {
                     state.select_mode = SelectMode::Add;
@@ -272,10 +487,51 @@ impl HierarchyPanel {
                     self.duplicate_entity(state, entity_id);
                     ui.close_menu();
                 }
+                if ui
+                    .button("Copy for Another Editor")
+                    .on_hover_text("Copy this entity and its children to the system clipboard")
+                    .clicked()
+                {
+                    Self::copy_for_transfer(ui.ctx(), state, entity_id);
+                    ui.close_menu();
+                }
                 if ui.button("Delete").clicked() {
                     self.delete_entity(state, entity_id);
                     ui.close_menu();
                 }
+                ui.menu_button("Select Similar", |ui| {
+                    for criterion in SimilarityCriterion::all() {
+                        if ui.button(criterion.name()).clicked() {
+                            let similar = state.scene.select_similar(entity_id, *criterion);
+                            state.select_mode = SelectMode::Set;
+                            state.select(&similar);
+                            ui.close_menu();
+                        }
+                    }
+                });
+                ui.menu_button("Color Label", |ui| {
+                    ui.horizontal(|ui| {
+                        for label in ColorLabel::ALL {
+                            let selected = entity.color_label == Some(label);
+                            let swatch = egui::Button::new("")
+                                .fill(label_color(label))
+                                .min_size(egui::vec2(16.0, 16.0))
+                                .stroke(if selected {
+                                    egui::Stroke::new(2.0, ui.style().visuals.strong_text_color())
+                                } else {
+                                    egui::Stroke::NONE
+                                });
+                            if ui.add(swatch).on_hover_text(label.name()).clicked() {
+                                state.set_color_label(entity_id, Some(label));
+                                ui.close_menu();
+                            }
+                        }
+                    });
+                    if ui.add_enabled(entity.color_label.is_some(), egui::Button::new("None")).clicked() {
+                        state.set_color_label(entity_id, None);
+                        ui.close_menu();
+                    }
+                });
                 ui.separator();
                 if ui.button("Create Child").clicked() {
                     self.create_child_entity(state, entity_id);
@@ -300,6 +556,18 @@ impl HierarchyPanel {
                         }
                         ui.close_menu();
                     }
+                    if ui.button("Save as Prefab Variant").clicked() {
+                        let variant_path = state
+                            .prefab_manager
+                            .get_instance(entity_id)
+                            .map(|instance| crate::prefab::free_variant_path(&instance.prefab_path));
+                        if let Some(variant_path) = variant_path {
+                            if let Err(e) = state.save_as_variant(entity_id, &variant_path) {
+                                tracing::error!("Failed to save prefab variant: {}", e);
+                            }
+                        }
+                        ui.close_menu();
+                    }
                     ui.separator();
                     if ui.button("Unpack Prefab").clicked() {
                         state.unpack_prefab(entity_id);
@@ -320,7 +588,7 @@ impl HierarchyPanel {
         });
 
         // Render children if expanded
-        if is_expanded && has_children {
+        if shows_children {
             let children = entity.children.clone();
             for child_id in children {
                 self.render_node(ui, child_id, state, depth + 1);
@@ -328,6 +596,22 @@ impl HierarchyPanel {
         }
     }
 
+    /// Index among its siblings `dragged` moves to when dropped next to
+    /// `target`, or `None` when they aren't siblings
+    fn sibling_insert_index(
+        state: &EditorState,
+        dragged: EntityId,
+        target: EntityId,
+        placement: DropPlacement,
+    ) -> Option<usize> {
+        let siblings = state.scene.siblings(target);
+        let from = siblings.iter().position(|id| *id == dragged)?;
+        let target_index = siblings.iter().position(|id| *id == target)?;
+        let index = if placement == DropPlacement::After { target_index + 1 } else { target_index };
+        // The dragged entity leaves its old slot before it's inserted
+        Some(if from < index { index - 1 } else { index })
+    }
+
     fn drag_sources(&self, state: &EditorState, dragged: EntityId) -> Vec<EntityId> {
         if state.selection.contains(&dragged) {
             state.selection.entities.clone()
@@ -404,8 +688,45 @@ impl HierarchyPanel {
     }
 }
 
+/// Swatch color of a color label
+fn label_color(label: ColorLabel) -> egui::Color32 {
+    let [r, g, b] = label.rgb();
+    egui::Color32::from_rgb(r, g, b)
+}
+
 impl Default for HierarchyPanel {
     fn default() -> Self {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::EntityData;
+
+    #[test]
+    fn test_search_keeps_ancestors_visible() {
+        let mut scene = SceneData::new();
+        let level = scene.add_entity(EntityData::new("Level"));
+        let goblin = scene.add_entity(EntityData {
+            name: "Goblin Scout".to_string(),
+            parent: Some(level),
+            tags: vec!["Enemy".to_string()],
+            ..Default::default()
+        });
+        let crate_id = scene.add_entity(EntityData {
+            name: "Crate".to_string(),
+            parent: Some(level),
+            ..Default::default()
+        });
+
+        let search = SearchMatches::find(&scene, "SCOUT");
+        assert_eq!(search.matched, HashSet::from([goblin]));
+        assert_eq!(search.visible, HashSet::from([goblin, level]));
+        assert!(!search.visible.contains(&crate_id));
+
+        assert_eq!(SearchMatches::find(&scene, "tag:enemy").matched, HashSet::from([goblin]));
+        assert!(SearchMatches::find(&scene, "tag:enemy crate").matched.is_empty());
+    }
+}
@@ -0,0 +1,105 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//! History panel - Undo stack view.
+//!
+//! Lists every recorded operation with the current position marked.
+//! Clicking an entry undoes or redoes until the scene is in the state right
+//! after that operation; the panel keeps no copy of the stack, so closing
+//! and reopening it can't get out of sync with the history.
+
+use crate::state::EditorState;
+
+/// The history panel listing undoable operations
+pub struct HistoryPanel {
+    /// Scroll to the current entry on the next frame
+    scroll_to_current: bool,
+    /// History revision seen last frame
+    last_revision: Option<u64>,
+}
+
+impl HistoryPanel {
+    /// Create a new history panel
+    pub fn new() -> Self {
+        Self {
+            scroll_to_current: true,
+            last_revision: None,
+        }
+    }
+
+    /// Render the history panel
+    pub fn ui(&mut self, ui: &mut egui::Ui, state: &mut EditorState) {
+        let revision = state.history.revision();
+        if self.last_revision != Some(revision) {
+            self.last_revision = Some(revision);
+            self.scroll_to_current = true;
+        }
+
+        let current = state.history.current_index();
+        let descriptions: Vec<String> = state.history.entries().map(|g| g.description.clone()).collect();
+
+        ui.horizontal(|ui| {
+            ui.label(format!("{} operation(s)", descriptions.len()));
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui.add_enabled(state.history.can_redo(), egui::Button::new("Redo")).clicked() {
+                    if let Err(err) = state.redo() {
+                        tracing::warn!("Redo failed: {}", err);
+                    }
+                }
+                if ui.add_enabled(state.history.can_undo(), egui::Button::new("Undo")).clicked() {
+                    if let Err(err) = state.undo() {
+                        tracing::warn!("Undo failed: {}", err);
+                    }
+                }
+            });
+        });
+        ui.separator();
+
+        let mut jump_to = None;
+        egui::ScrollArea::vertical().auto_shrink([false, false]).show(ui, |ui| {
+            // Index 0 is the state before the oldest retained operation,
+            // which is only the initial state if nothing was evicted
+            let evicted = state.history.evicted_count();
+            let first = if evicted == 0 {
+                "Initial State".to_string()
+            } else {
+                format!("Oldest Retained State ({evicted} older discarded)")
+            };
+            let first_kept = descriptions.first().cloned().unwrap_or_default();
+            let rows = std::iter::once(first).chain(descriptions);
+            for (index, description) in rows.enumerate() {
+                let is_current = index == current;
+                let marker = if is_current { "\u{25b6}" } else { "  " };
+                let mut text = egui::RichText::new(format!("{marker} {description}"));
+                if index > current {
+                    // Undone operations that can still be redone
+                    text = text.weak();
+                }
+
+                let mut response = ui.selectable_label(is_current, text);
+                if index == 0 && evicted > 0 {
+                    response = response.on_hover_text(format!(
+                        "The state before \"{first_kept}\"; older operations were discarded by the history limits"
+                    ));
+                }
+                if is_current && self.scroll_to_current {
+                    response.scroll_to_me(Some(egui::Align::Center));
+                    self.scroll_to_current = false;
+                }
+                if response.clicked() && !is_current {
+                    jump_to = Some(index);
+                }
+            }
+        });
+
+        if let Some(index) = jump_to {
+            if let Err(err) = state.jump_to_history(index) {
+                tracing::warn!("History jump failed: {}", err);
+            }
+        }
+    }
+}
+
+impl Default for HistoryPanel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -2,10 +2,19 @@
 //! Inspector panel - Component/property editor.
 
 use crate::components::{
-    get_components_by_category, Component, LightType,
+    get_components_by_category, AudioRolloff, ColliderKind, Component, LightType,
 };
+use crate::entity_ref::{EntityRefStatus, PickTarget};
 use crate::state::{EditorState, EntityId, Transform};
 
+/// Action requested from an entity reference field
+enum EntityRefAction {
+    None,
+    Pick,
+    CancelPick,
+    Clear,
+}
+
 /// The inspector panel for editing entity components
 pub struct InspectorPanel {
     /// Sections that are expanded
@@ -14,6 +23,8 @@ pub struct InspectorPanel {
     editing_transform: Option<(EntityId, Transform)>,
     /// Start of the current transform edit (for undo)
     editing_transform_start: Option<(EntityId, Transform)>,
+    /// Component being edited, as it was before the edit (for undo)
+    editing_component_start: Option<(EntityId, usize, Component)>,
     /// Entity name being edited
     editing_name: Option<(EntityId, String)>,
     /// Multi-edit transform buffer (offset values when relative mode is on)
@@ -32,6 +43,14 @@ pub struct InspectorPanel {
     add_component_search: String,
     /// Property search/filter text
     property_search: String,
+    /// Metadata value being edited (entity, key, text)
+    metadata_edit: Option<(EntityId, String, String)>,
+    /// Key for a new metadata entry
+    new_metadata_key: String,
+    /// Value for a new metadata entry
+    new_metadata_value: String,
+    /// Tag being typed for the selected entity
+    new_tag: String,
 }
 
 impl InspectorPanel {
@@ -45,6 +64,7 @@ impl InspectorPanel {
             expanded_sections: expanded,
             editing_transform: None,
             editing_transform_start: None,
+            editing_component_start: None,
             editing_name: None,
             multi_transform: Transform::default(),
             multi_selection: Vec::new(),
@@ -54,15 +74,64 @@ impl InspectorPanel {
             add_component_popup_open: false,
             add_component_search: String::new(),
             property_search: String::new(),
+            metadata_edit: None,
+            new_metadata_key: String::new(),
+            new_metadata_value: String::new(),
+            new_tag: String::new(),
+        }
+    }
+
+    /// Scene-wide settings shown when nothing is selected
+    fn scene_settings_ui(&mut self, ui: &mut egui::Ui, state: &mut EditorState) {
+        ui.heading("Scene Settings");
+        ui.label(egui::RichText::new("No entity selected").weak());
+        ui.separator();
+
+        let mut environment = state.scene.environment.clone();
+
+        egui::CollapsingHeader::new("Ambient Light")
+            .default_open(true)
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Color");
+                    ui.color_edit_button_rgb(&mut environment.ambient_color);
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Intensity");
+                    ui.add(egui::DragValue::new(&mut environment.ambient_intensity).speed(0.01).range(0.0..=10.0));
+                });
+            });
+
+        egui::CollapsingHeader::new("Fog")
+            .default_open(true)
+            .show(ui, |ui| {
+                ui.checkbox(&mut environment.fog_enabled, "Enabled");
+                ui.add_enabled_ui(environment.fog_enabled, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label("Color");
+                        ui.color_edit_button_rgb(&mut environment.fog_color);
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("Start");
+                        ui.add(egui::DragValue::new(&mut environment.fog_start).speed(0.5).range(0.0..=f32::MAX));
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("End");
+                        ui.add(egui::DragValue::new(&mut environment.fog_end).speed(0.5).range(environment.fog_start..=f32::MAX));
+                    });
+                });
+            });
+
+        if environment != state.scene.environment {
+            state.scene.environment = environment;
+            state.dirty = true;
         }
     }
 
     /// Render the inspector panel
     pub fn ui(&mut self, ui: &mut egui::Ui, state: &mut EditorState) {
         if state.selection.is_empty() {
-            ui.centered_and_justified(|ui| {
-                ui.label("No entity selected");
-            });
+            self.scene_settings_ui(ui, state);
             return;
         }
 
@@ -116,6 +185,24 @@ impl InspectorPanel {
 
                     // Components section
                     self.components_section_filtered(ui, state, entity_id, &search_filter);
+
+                    // Tags (show if matches filter)
+                    if search_filter.is_empty()
+                        || "tags".contains(&search_filter)
+                        || data.tags.iter().any(|tag| tag.to_lowercase().contains(&search_filter))
+                    {
+                        ui.separator();
+                        self.tags_section(ui, state, entity_id, &data.tags);
+                    }
+
+                    // Metadata (show if matches filter)
+                    if search_filter.is_empty()
+                        || "metadata".contains(&search_filter)
+                        || data.metadata.keys().any(|key| key.to_lowercase().contains(&search_filter))
+                    {
+                        ui.separator();
+                        self.metadata_section(ui, state, entity_id, &data.metadata);
+                    }
                 });
             }
         }
@@ -164,6 +251,16 @@ impl InspectorPanel {
                 if ui.small_button("Revert All").on_hover_text("Revert all property changes to prefab values").clicked() {
                     state.revert_all_overrides(entity_id);
                 }
+                let instance_root = state.prefab_manager
+                    .find_instance_containing(entity_id)
+                    .map(|instance| instance.root_entity_id);
+                if let Some(root) = instance_root {
+                    if ui.small_button("Apply All").on_hover_text("Write this instance's overrides into the source prefab. Undo restores other instances but not the prefab file.").clicked() {
+                        if let Err(e) = state.apply_overrides_to_prefab(root) {
+                            tracing::error!("Failed to apply overrides: {}", e);
+                        }
+                    }
+                }
                 if is_prefab_root
                     && ui.small_button("Unpack").on_hover_text("Remove prefab link").clicked() {
                         state.unpack_prefab(entity_id);
@@ -480,6 +577,24 @@ impl InspectorPanel {
 
         // Track component to remove (deferred to avoid borrow issues)
         let mut remove_index: Option<usize> = None;
+        let mut reset_index: Option<usize> = None;
+        let mut copy_index: Option<usize> = None;
+        let mut paste_values_index: Option<usize> = None;
+        let mut paste_as_new = false;
+        let mut move_component: Option<(usize, usize)> = None;
+        let mut convert_to: Option<(usize, ColliderKind)> = None;
+        let mut clear_reference: Option<(usize, &'static str)> = None;
+        let mut edited: Option<(usize, Component)> = None;
+
+        // Asset picked in the asset browser, as a component reference
+        let selected_asset = state.selected_asset.as_deref().map(|path| {
+            let root = crate::asset_paths::asset_root(state.project_manager.project_dir.as_deref());
+            crate::asset_paths::asset_reference(&root, path)
+        });
+
+        // Copied component, if any, and whether this entity can take it as a new component
+        let clipboard_type = state.component_clipboard.as_ref().map(|c| (c.type_id(), c.display_name()));
+        let can_paste_as_new = clipboard_type.is_some_and(|(type_id, _)| !state.has_component(entity_id, type_id));
 
         // Render each component (filtered)
         for (index, component) in components.iter().enumerate() {
@@ -500,25 +615,110 @@ impl InspectorPanel {
                         // Component-specific UI
                         // Clone component for mutable editing
                         let mut component_mut = component.clone();
-                        let changed = self.draw_component_ui(ui, &mut component_mut);
+                        let changed = self.draw_component_ui(
+                            ui,
+                            &mut component_mut,
+                            selected_asset.as_deref(),
+                            (entity_id, index),
+                        );
                         if changed {
+                            // Applied live; recorded for undo when the edit ends
+                            edited = Some((index, component.clone()));
                             if let Some(entity) = state.scene.get_mut(&entity_id) {
                                 if index < entity.components.len() {
                                     entity.components[index] = component_mut;
                                     state.dirty = true;
+                                    state.validator.request_revalidation();
                                 }
                             }
                         }
 
+                        // Entity reference fields with eyedropper
+                        for (field, reference) in component.entity_references() {
+                            let target = PickTarget { entity: entity_id, component_index: index, field };
+                            let status = EntityRefStatus::resolve(reference, &state.scene);
+                            let picking = state.entity_picker.is_picking(&target);
+                            match Self::entity_ref_field(ui, field, &status, picking) {
+                                EntityRefAction::Pick => state.entity_picker.begin(target),
+                                EntityRefAction::CancelPick => state.entity_picker.cancel(),
+                                EntityRefAction::Clear => clear_reference = Some((index, field)),
+                                EntityRefAction::None => {}
+                            }
+                        }
+
                         // Remove button at the bottom
                         ui.separator();
                         ui.horizontal(|ui| {
                             if ui.small_button("Remove Component").clicked() {
                                 remove_index = Some(index);
                             }
+                            if ui
+                                .add_enabled(index > 0, egui::Button::new("\u{23f6}").small())
+                                .on_hover_text("Move Up")
+                                .clicked()
+                            {
+                                move_component = Some((index, index - 1));
+                            }
+                            if ui
+                                .add_enabled(index + 1 < components.len(), egui::Button::new("\u{23f7}").small())
+                                .on_hover_text("Move Down")
+                                .clicked()
+                            {
+                                move_component = Some((index, index + 1));
+                            }
+
+                            if let Some(kind) = component.collider_kind() {
+                                egui::ComboBox::from_id_salt(("convert_collider", index))
+                                    .selected_text("Convert To\u{2026}")
+                                    .show_ui(ui, |ui| {
+                                        for &target in ColliderKind::all() {
+                                            if target != kind && ui.selectable_label(false, target.display_name()).clicked() {
+                                                convert_to = Some((index, target));
+                                            }
+                                        }
+                                    });
+                            }
                         });
                     });
 
+                header.header_response.context_menu(|ui| {
+                    if ui.button("Copy Component").clicked() {
+                        copy_index = Some(index);
+                        ui.close_menu();
+                    }
+                    let matches_clipboard = clipboard_type.is_some_and(|(type_id, _)| type_id == component.type_id());
+                    if ui.add_enabled(matches_clipboard, egui::Button::new("Paste Component Values")).clicked() {
+                        paste_values_index = Some(index);
+                        ui.close_menu();
+                    }
+                    let mut paste_new = ui.add_enabled(can_paste_as_new, egui::Button::new("Paste as New Component"));
+                    if let Some((_, name)) = clipboard_type {
+                        paste_new = paste_new.on_disabled_hover_text(format!("Already has a {name}"));
+                    }
+                    if paste_new.clicked() {
+                        paste_as_new = true;
+                        ui.close_menu();
+                    }
+                    ui.separator();
+                    if ui.add_enabled(index > 0, egui::Button::new("Move Up")).clicked() {
+                        move_component = Some((index, index - 1));
+                        ui.close_menu();
+                    }
+                    if ui.add_enabled(index + 1 < components.len(), egui::Button::new("Move Down")).clicked() {
+                        move_component = Some((index, index + 1));
+                        ui.close_menu();
+                    }
+                    ui.separator();
+                    if ui.button("Reset Component").clicked() {
+                        reset_index = Some(index);
+                        ui.close_menu();
+                    }
+                    if ui.button("Remove Component").clicked() {
+                        remove_index = Some(index);
+                        ui.close_menu();
+                    }
+                });
+
                 if header.header_response.clicked() {
                     if expanded {
                         self.expanded_sections.remove(&header_id);
@@ -529,6 +729,50 @@ impl InspectorPanel {
             });
         }
 
+        // Field edits become one undo step once the drag or click ends
+        if let Some((index, previous)) = edited {
+            let same_edit = self
+                .editing_component_start
+                .as_ref()
+                .is_some_and(|(id, start_index, _)| *id == entity_id && *start_index == index);
+            if !same_edit {
+                self.editing_component_start = Some((entity_id, index, previous));
+            }
+        }
+        if ui.input(|i| i.pointer.any_released() || i.key_pressed(egui::Key::Enter)) {
+            if let Some((id, index, previous)) = self.editing_component_start.take() {
+                state.commit_component_edit(id, index, previous);
+            }
+        }
+
+        // Apply deferred collider conversion
+        if let Some((index, target)) = convert_to {
+            state.convert_collider(entity_id, index, target);
+        }
+
+        // Apply deferred reference clear
+        if let Some((index, field)) = clear_reference {
+            state.set_entity_reference(entity_id, index, field, None);
+        }
+
+        // Apply deferred clipboard actions
+        if let Some(index) = copy_index {
+            state.copy_component(entity_id, index);
+        }
+        if let Some(index) = paste_values_index {
+            state.paste_component_values(entity_id, index);
+        }
+
+        // Apply deferred move
+        if let Some((from, to)) = move_component {
+            state.reorder_component(entity_id, from, to);
+        }
+
+        // Apply deferred reset
+        if let Some(index) = reset_index {
+            state.reset_component(entity_id, index);
+        }
+
         // Apply deferred remove
         if let Some(index) = remove_index {
             state.remove_component(entity_id, index);
@@ -537,7 +781,125 @@ impl InspectorPanel {
         // Add Component button (always show)
         if search_filter.is_empty() || "add component".contains(search_filter) {
             ui.separator();
-            self.add_component_button(ui, state, entity_id);
+            ui.horizontal(|ui| {
+                self.add_component_button(ui, state, entity_id);
+                if let Some((_, name)) = clipboard_type {
+                    if ui.add_enabled(can_paste_as_new, egui::Button::new(format!("Paste {name}"))).clicked() {
+                        paste_as_new = true;
+                    }
+                }
+            });
+        }
+
+        if paste_as_new {
+            state.paste_component_as_new(entity_id);
+        }
+    }
+
+    fn tags_section(&mut self, ui: &mut egui::Ui, state: &mut EditorState, entity_id: EntityId, tags: &[String]) {
+        let mut removed: Option<String> = None;
+        let mut added: Option<String> = None;
+
+        egui::CollapsingHeader::new(format!("Tags ({})", tags.len()))
+            .id_salt(("tags", entity_id.0))
+            .default_open(!tags.is_empty())
+            .show(ui, |ui| {
+                ui.horizontal_wrapped(|ui| {
+                    for tag in tags {
+                        if ui.small_button(format!("{tag} ✕")).on_hover_text("Remove tag").clicked() {
+                            removed = Some(tag.clone());
+                        }
+                    }
+                });
+
+                ui.horizontal(|ui| {
+                    let response = ui.add(
+                        egui::TextEdit::singleline(&mut self.new_tag)
+                            .desired_width(140.0)
+                            .hint_text("tag"),
+                    );
+                    let tag = self.new_tag.trim();
+                    let can_add = !tag.is_empty() && !tags.iter().any(|t| t == tag);
+                    let submitted = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                    if (ui.add_enabled(can_add, egui::Button::new("Add")).clicked() || submitted) && can_add {
+                        added = Some(tag.to_string());
+                        self.new_tag.clear();
+                    }
+                });
+            });
+
+        if let Some(tag) = removed {
+            state.remove_tag(entity_id, &tag);
+        }
+        if let Some(tag) = added {
+            state.add_tag(entity_id, &tag);
+        }
+    }
+
+    fn metadata_section(
+        &mut self,
+        ui: &mut egui::Ui,
+        state: &mut EditorState,
+        entity_id: EntityId,
+        metadata: &std::collections::BTreeMap<String, String>,
+    ) {
+        let mut commit: Option<(String, Option<String>)> = None;
+
+        egui::CollapsingHeader::new(format!("Metadata ({})", metadata.len()))
+            .id_salt(("metadata", entity_id.0))
+            .default_open(!metadata.is_empty())
+            .show(ui, |ui| {
+                egui::Grid::new(("metadata_grid", entity_id.0))
+                    .num_columns(3)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for (key, value) in metadata {
+                            ui.label(key);
+
+                            let mut text = match &self.metadata_edit {
+                                Some((id, edit_key, text)) if *id == entity_id && edit_key == key => text.clone(),
+                                _ => value.clone(),
+                            };
+                            let response = ui.add(egui::TextEdit::singleline(&mut text).desired_width(140.0));
+                            if response.changed() {
+                                self.metadata_edit = Some((entity_id, key.clone(), text.clone()));
+                            }
+                            if response.lost_focus() {
+                                if text != *value {
+                                    commit = Some((key.clone(), Some(text)));
+                                }
+                                self.metadata_edit = None;
+                            }
+
+                            if ui.small_button("✕").on_hover_text("Remove entry").clicked() {
+                                commit = Some((key.clone(), None));
+                            }
+                            ui.end_row();
+                        }
+                    });
+
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.new_metadata_key)
+                            .desired_width(80.0)
+                            .hint_text("key"),
+                    );
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.new_metadata_value)
+                            .desired_width(100.0)
+                            .hint_text("value"),
+                    );
+                    let key = self.new_metadata_key.trim();
+                    let can_add = !key.is_empty() && !metadata.contains_key(key);
+                    if ui.add_enabled(can_add, egui::Button::new("Add")).clicked() {
+                        commit = Some((key.to_string(), Some(std::mem::take(&mut self.new_metadata_value))));
+                        self.new_metadata_key.clear();
+                    }
+                });
+            });
+
+        if let Some((key, value)) = commit {
+            state.set_metadata(entity_id, &key, value);
         }
     }
 
@@ -579,38 +941,53 @@ impl InspectorPanel {
                 "physics material friction bounciness".contains(filter)
             }
             Component::AudioSource(a) => {
-                "audio source clip volume pitch loop spatial".contains(filter)
+                "audio source clip volume pitch loop spatial distance rolloff group mixer".contains(filter)
                     || a.clip.to_lowercase().contains(filter)
             }
             Component::Script(s) => {
                 "script enabled".contains(filter)
                     || s.script.to_lowercase().contains(filter)
             }
+            Component::LookAt(_) => "look at target constraint".contains(filter),
+            Component::Path(_) => "path points closed spline".contains(filter),
         }
     }
 
-    fn draw_component_ui(&self, ui: &mut egui::Ui, component: &mut Component) -> bool {
+    /// Fields of one component, the `index`th of `entity_id`; the pair
+    /// keeps widget ids apart between components
+    fn draw_component_ui(
+        &self,
+        ui: &mut egui::Ui,
+        component: &mut Component,
+        selected_asset: Option<&str>,
+        (entity_id, index): (EntityId, usize),
+    ) -> bool {
         let mut changed = false;
         match component {
             Component::MeshRenderer(mesh) => {
-                ui.horizontal(|ui| {
-                    ui.label("Mesh");
-                    ui.label(if mesh.mesh.is_empty() { "(None)" } else { &mesh.mesh });
-                });
-                ui.horizontal(|ui| {
-                    ui.label("Material");
-                    ui.label(if mesh.material.is_empty() { "(None)" } else { &mesh.material });
-                });
-                ui.label(format!("Cast Shadows: {}", mesh.cast_shadows));
-                ui.label(format!("Receive Shadows: {}", mesh.receive_shadows));
+                changed |= Self::asset_path_field(ui, "Mesh", &mut mesh.mesh, selected_asset);
+                changed |= Self::asset_path_field(ui, "Material", &mut mesh.material, selected_asset);
+                changed |= ui.checkbox(&mut mesh.cast_shadows, "Cast Shadows").changed();
+                changed |= ui.checkbox(&mut mesh.receive_shadows, "Receive Shadows").changed();
             }
             Component::Light(light) => {
-                let type_str = match light.light_type {
+                let type_name = |light_type: LightType| match light_type {
                     LightType::Directional => "Directional",
                     LightType::Point => "Point",
                     LightType::Spot => "Spot",
                 };
-                ui.label(format!("Type: {}", type_str));
+                ui.horizontal(|ui| {
+                    ui.label("Type");
+                    egui::ComboBox::from_id_salt(("light_type", entity_id.0, index))
+                        .selected_text(type_name(light.light_type))
+                        .show_ui(ui, |ui| {
+                            for light_type in [LightType::Directional, LightType::Point, LightType::Spot] {
+                                changed |= ui
+                                    .selectable_value(&mut light.light_type, light_type, type_name(light_type))
+                                    .changed();
+                            }
+                        });
+                });
                 ui.horizontal(|ui| {
                     ui.label("Color");
                     let mut color_bytes = [
@@ -626,17 +1003,51 @@ impl InspectorPanel {
                         changed = true;
                     }
                 });
-                ui.label(format!("Intensity: {:.2}", light.intensity));
-                ui.label(format!("Range: {:.2}", light.range));
+                ui.horizontal(|ui| {
+                    ui.label("Intensity");
+                    changed |= ui
+                        .add(egui::DragValue::new(&mut light.intensity).speed(0.05).range(0.0..=f32::MAX))
+                        .changed();
+                });
+                // Directional lights reach everywhere
+                if !matches!(light.light_type, LightType::Directional) {
+                    ui.horizontal(|ui| {
+                        ui.label("Range");
+                        changed |= ui
+                            .add(egui::DragValue::new(&mut light.range).speed(0.1).range(0.0..=f32::MAX))
+                            .changed();
+                    });
+                }
                 if matches!(light.light_type, LightType::Spot) {
-                    ui.label(format!("Spot Angle: {:.1}°", light.spot_angle));
+                    ui.horizontal(|ui| {
+                        ui.label("Spot Angle");
+                        changed |= ui
+                            .add(egui::DragValue::new(&mut light.spot_angle).speed(0.5).range(1.0..=179.0).suffix("°"))
+                            .changed();
+                    });
                 }
+                changed |= ui.checkbox(&mut light.cast_shadows, "Cast Shadows").changed();
             }
             Component::Camera(camera) => {
-                ui.label(format!("FOV: {:.1}°", camera.fov));
-                ui.label(format!("Near: {:.3}", camera.near));
-                ui.label(format!("Far: {:.1}", camera.far));
-                ui.label(format!("Main Camera: {}", camera.is_main));
+                ui.horizontal(|ui| {
+                    ui.label("FOV");
+                    changed |= ui
+                        .add(egui::DragValue::new(&mut camera.fov).speed(0.5).range(1.0..=179.0).suffix("°"))
+                        .changed();
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Near");
+                    changed |= ui
+                        .add(egui::DragValue::new(&mut camera.near).speed(0.01).range(0.001..=camera.far))
+                        .changed();
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Far");
+                    changed |= ui
+                        .add(egui::DragValue::new(&mut camera.far).speed(1.0).range(camera.near..=f32::MAX))
+                        .changed();
+                });
+                changed |= ui.checkbox(&mut camera.is_main, "Main Camera").changed();
             }
             Component::Rigidbody(rb) => {
                 use crate::components::RigidbodyType;
@@ -709,16 +1120,124 @@ impl InspectorPanel {
                 ui.label(format!("Pitch: {:.2}", audio.pitch));
                 ui.label(format!("Loop: {}", audio.loop_audio));
                 ui.label(format!("Play on Awake: {}", audio.play_on_awake));
+                ui.label(format!("Group: {}", audio.group));
                 ui.label(format!("Spatial: {}", audio.spatial));
+                if audio.spatial {
+                    ui.horizontal(|ui| {
+                        ui.label("Distance");
+                        changed |= ui
+                            .add(
+                                egui::DragValue::new(&mut audio.min_distance)
+                                    .speed(0.1)
+                                    .range(0.0..=audio.max_distance)
+                                    .prefix("Min: "),
+                            )
+                            .changed();
+                        changed |= ui
+                            .add(
+                                egui::DragValue::new(&mut audio.max_distance)
+                                    .speed(0.1)
+                                    .range(audio.min_distance..=f32::MAX)
+                                    .prefix("Max: "),
+                            )
+                            .changed();
+                    });
+                    let rolloff_name = |rolloff: AudioRolloff| match rolloff {
+                        AudioRolloff::Linear => "Linear",
+                        AudioRolloff::Logarithmic => "Logarithmic",
+                    };
+                    ui.horizontal(|ui| {
+                        ui.label("Rolloff");
+                        egui::ComboBox::from_id_salt(("audio_rolloff", entity_id.0, index))
+                            .selected_text(rolloff_name(audio.rolloff))
+                            .show_ui(ui, |ui| {
+                                for rolloff in [AudioRolloff::Linear, AudioRolloff::Logarithmic] {
+                                    changed |= ui
+                                        .selectable_value(&mut audio.rolloff, rolloff, rolloff_name(rolloff))
+                                        .changed();
+                                }
+                            });
+                    });
+                }
             }
             Component::Script(script) => {
                 ui.label(format!("Script: {}", if script.script.is_empty() { "(None)" } else { &script.script }));
                 ui.label(format!("Enabled: {}", script.enabled));
             }
+            // Target is drawn as an entity reference field
+            Component::LookAt(_) => {}
+            Component::Path(path) => {
+                ui.label(format!("Points: {}", path.points.len()));
+                ui.label(format!("Length: {:.2}", path.length()));
+                changed |= ui.checkbox(&mut path.closed, "Closed").changed();
+            }
         }
         changed
     }
 
+    /// Draw an editable asset path with a button that assigns the asset
+    /// selected in the asset browser
+    fn asset_path_field(ui: &mut egui::Ui, label: &str, path: &mut String, selected_asset: Option<&str>) -> bool {
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            ui.label(label);
+            changed |= ui
+                .add(egui::TextEdit::singleline(path).hint_text("(None)").desired_width(160.0))
+                .changed();
+
+            let assignable = selected_asset.filter(|asset| *asset != path.as_str());
+            let response = ui
+                .add_enabled(assignable.is_some(), egui::Button::new("\u{25c0}").small())
+                .on_hover_text(format!("Use {}", assignable.unwrap_or_default()))
+                .on_disabled_hover_text("Select an asset in the Asset Browser to assign it");
+            if let (true, Some(asset)) = (response.clicked(), assignable) {
+                *path = asset.to_string();
+                changed = true;
+            }
+        });
+        changed
+    }
+
+    /// Draw an entity reference with eyedropper and clear buttons
+    fn entity_ref_field(ui: &mut egui::Ui, field: &str, status: &EntityRefStatus, picking: bool) -> EntityRefAction {
+        let mut action = EntityRefAction::None;
+        ui.horizontal(|ui| {
+            let mut chars = field.chars();
+            let label: String = chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect())
+                .unwrap_or_default();
+            ui.label(label);
+
+            let text = egui::RichText::new(status.label());
+            let text = match status {
+                EntityRefStatus::Missing => text.color(egui::Color32::from_rgb(230, 90, 90)),
+                EntityRefStatus::Empty => text.weak(),
+                EntityRefStatus::Resolved(_) => text,
+            };
+            ui.label(text);
+
+            if ui
+                .selectable_label(picking, "🎯")
+                .on_hover_text("Pick an entity in the viewport or hierarchy")
+                .clicked()
+            {
+                action = if picking { EntityRefAction::CancelPick } else { EntityRefAction::Pick };
+            }
+            if ui
+                .add_enabled(*status != EntityRefStatus::Empty, egui::Button::new("✕").small())
+                .on_hover_text("Clear reference")
+                .clicked()
+            {
+                action = EntityRefAction::Clear;
+            }
+        });
+        if picking {
+            ui.label(egui::RichText::new("Click an entity to reference it (Esc to cancel)").small().weak());
+        }
+        action
+    }
+
     fn add_component_button(&mut self, ui: &mut egui::Ui, state: &mut EditorState, entity_id: EntityId) {
         let button_response = ui.button("Add Component");
 
@@ -6,7 +6,8 @@ mod hierarchy;
 mod inspector;
 mod asset_browser;
 pub mod console;
-mod profiler;
+pub mod profiler;
+mod history;
 mod project_settings;
 pub mod property_drawer;
 
@@ -16,4 +17,5 @@ pub use inspector::InspectorPanel;
 pub use asset_browser::AssetBrowserPanel;
 pub use console::ConsolePanel;
 pub use profiler::ProfilerPanel;
+pub use history::HistoryPanel;
 pub use project_settings::ProjectSettingsPanel;
@@ -1,9 +1,68 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 //! Profiler panel - Performance monitoring.
+//!
+//! Sections of the frame are timed with [`scope`], which returns a guard
+//! that records the elapsed time when dropped. Timing is only active while
+//! the panel is open, so instrumented code costs a flag check otherwise.
 
 
 use crate::state::EditorState;
+use std::cell::{Cell, RefCell};
 use std::collections::VecDeque;
+use std::time::Instant;
+
+thread_local! {
+    static SECTIONS_ENABLED: Cell<bool> = const { Cell::new(false) };
+    static FRAME_SECTIONS: RefCell<Vec<SectionTiming>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Time spent in one named section of a frame
+#[derive(Debug, Clone, PartialEq)]
+pub struct SectionTiming {
+    /// Section name
+    pub name: &'static str,
+    /// Total time in the section this frame (ms)
+    pub duration_ms: f32,
+}
+
+/// Guard returned by [`scope`]; records the section when dropped
+#[must_use = "the section is timed until the guard is dropped"]
+pub struct SectionScope {
+    name: &'static str,
+    start: Option<Instant>,
+}
+
+impl Drop for SectionScope {
+    fn drop(&mut self) {
+        let Some(start) = self.start else {
+            return;
+        };
+        let duration_ms = start.elapsed().as_secs_f32() * 1000.0;
+        FRAME_SECTIONS.with_borrow_mut(|sections| {
+            // A section entered several times in a frame is summed
+            match sections.iter_mut().find(|section| section.name == self.name) {
+                Some(section) => section.duration_ms += duration_ms,
+                None => sections.push(SectionTiming { name: self.name, duration_ms }),
+            }
+        });
+    }
+}
+
+/// Time the section `name` until the returned guard is dropped
+pub fn scope(name: &'static str) -> SectionScope {
+    let start = SECTIONS_ENABLED.get().then(Instant::now);
+    SectionScope { name, start }
+}
+
+/// Turn section timing on or off for the following frames
+pub fn set_sections_enabled(enabled: bool) {
+    SECTIONS_ENABLED.set(enabled);
+}
+
+/// Take the sections recorded since the last call
+pub fn take_sections() -> Vec<SectionTiming> {
+    FRAME_SECTIONS.take()
+}
 
 /// Profiler view mode
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -60,6 +119,41 @@ pub struct ProfileFrame {
     pub cpu_scopes: Vec<ProfileScope>,
     /// GPU scopes
     pub gpu_scopes: Vec<ProfileScope>,
+    /// Timed sections of the editor update
+    pub sections: Vec<SectionTiming>,
+}
+
+/// How the frame time history is drawn
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameGraphStyle {
+    /// One bar per frame, colored by budget
+    Bars,
+    /// Connected line through the frame times
+    Line,
+}
+
+/// Summary of the frame times in the history window
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrameTimeStats {
+    /// Shortest frame (ms)
+    pub min: f32,
+    /// Mean frame time (ms)
+    pub avg: f32,
+    /// Longest frame (ms)
+    pub max: f32,
+    /// Most recent frame (ms)
+    pub current: f32,
+}
+
+impl FrameTimeStats {
+    /// Frames per second at the most recent frame time
+    pub fn current_fps(&self) -> f32 {
+        if self.current > 0.0 {
+            1000.0 / self.current
+        } else {
+            0.0
+        }
+    }
 }
 
 /// Capture mode for profiling
@@ -73,6 +167,12 @@ pub enum CaptureMode {
     Paused,
 }
 
+/// Default number of frames in the history window
+const DEFAULT_HISTORY_FRAMES: usize = 300;
+
+/// Range of the configurable history window
+const HISTORY_FRAMES_RANGE: std::ops::RangeInclusive<usize> = 30..=3600;
+
 /// The profiler panel
 #[allow(dead_code)] // Intentionally kept for API completeness
 pub struct ProfilerPanel {
@@ -86,8 +186,10 @@ pub struct ProfilerPanel {
     pub captured_frames: VecDeque<ProfileFrame>,
     /// Currently selected frame for detailed view
     pub selected_frame: Option<usize>,
-    /// Max frames to keep
+    /// Max frames to keep (history window length)
     pub max_frames: usize,
+    /// How the frame time graph is drawn
+    pub graph_style: FrameGraphStyle,
     /// Target frame time (for 60 FPS)
     pub target_frame_time: f32,
     /// Frame counter
@@ -101,41 +203,23 @@ pub struct ProfilerPanel {
 impl ProfilerPanel {
     /// Create a new profiler panel
     pub fn new() -> Self {
-        let mut panel = Self {
+        Self {
             view_mode: ProfilerViewMode::Overview,
             capture_mode: CaptureMode::Continuous,
             frame_times: VecDeque::new(),
             captured_frames: VecDeque::new(),
             selected_frame: None,
-            max_frames: 300,
+            max_frames: DEFAULT_HISTORY_FRAMES,
+            graph_style: FrameGraphStyle::Bars,
             target_frame_time: 16.67, // 60 FPS
             frame_counter: 0,
             show_trace: false,
             show_debug: true,
             show_info: true,
-        };
-
-        // Add mock frame time data
-        panel.add_mock_data();
-        panel
-    }
-
-    fn add_mock_data(&mut self) {
-        for i in 0..300 {
-            // Simulate varying frame times with occasional spikes
-            let base = 16.0;
-            let variation = (i as f32 * 0.1).sin() * 2.0;
-            let spike = if i % 50 == 0 { 8.0 } else { 0.0 };
-            let frame_time = base + variation + spike;
-            self.frame_times.push_back(frame_time);
-
-            // Create detailed frame data
-            self.captured_frames.push_back(self.create_mock_frame(i as u64, frame_time));
         }
-        self.frame_counter = 300;
     }
 
-    fn create_mock_frame(&self, frame_num: u64, total_ms: f32) -> ProfileFrame {
+    fn create_mock_frame(&self, frame_num: u64, total_ms: f32, sections: Vec<SectionTiming>) -> ProfileFrame {
         // Create mock CPU scopes
         let cpu_scopes = vec![
             ProfileScope {
@@ -183,26 +267,21 @@ impl ProfilerPanel {
             total_ms,
             cpu_scopes,
             gpu_scopes,
+            sections,
         }
     }
 
-    /// Record a new frame
-    #[allow(dead_code)] // Intentionally kept for API completeness
-    pub fn record_frame(&mut self, frame_time_ms: f32) {
+    /// Record a new frame with its timed sections; ignored while paused so
+    /// the graph stays frozen
+    pub fn record_frame(&mut self, frame_time_ms: f32, sections: Vec<SectionTiming>) {
         if self.capture_mode == CaptureMode::Paused {
             return;
         }
 
         self.frame_times.push_back(frame_time_ms);
-        while self.frame_times.len() > self.max_frames {
-            self.frame_times.pop_front();
-        }
-
-        let frame = self.create_mock_frame(self.frame_counter, frame_time_ms);
+        let frame = self.create_mock_frame(self.frame_counter, frame_time_ms, sections);
         self.captured_frames.push_back(frame);
-        while self.captured_frames.len() > self.max_frames {
-            self.captured_frames.pop_front();
-        }
+        self.trim_history();
 
         self.frame_counter += 1;
 
@@ -211,6 +290,45 @@ impl ProfilerPanel {
         }
     }
 
+    /// Drop the oldest frames beyond the history window
+    fn trim_history(&mut self) {
+        let excess = self.frame_times.len().saturating_sub(self.max_frames);
+        self.frame_times.drain(..excess);
+        self.selected_frame = self.selected_frame.and_then(|i| i.checked_sub(excess));
+        let excess = self.captured_frames.len().saturating_sub(self.max_frames);
+        self.captured_frames.drain(..excess);
+    }
+
+    /// Min/avg/max and current frame time over the history window
+    pub fn frame_time_stats(&self) -> Option<FrameTimeStats> {
+        let current = *self.frame_times.back()?;
+        let sum: f32 = self.frame_times.iter().sum();
+        Some(FrameTimeStats {
+            min: self.frame_times.iter().copied().fold(f32::MAX, f32::min),
+            avg: sum / self.frame_times.len() as f32,
+            max: self.frame_times.iter().copied().fold(f32::MIN, f32::max),
+            current,
+        })
+    }
+
+    /// Average time per frame of each section over the history window, in
+    /// order of first appearance. Frames that skipped a section count as zero.
+    pub fn section_averages(&self) -> Vec<SectionTiming> {
+        let mut totals: Vec<SectionTiming> = Vec::new();
+        for section in self.captured_frames.iter().flat_map(|frame| &frame.sections) {
+            match totals.iter_mut().find(|total| total.name == section.name) {
+                Some(total) => total.duration_ms += section.duration_ms,
+                None => totals.push(section.clone()),
+            }
+        }
+
+        let frames = self.captured_frames.len().max(1) as f32;
+        for total in &mut totals {
+            total.duration_ms /= frames;
+        }
+        totals
+    }
+
     /// Export to Chrome trace format
     pub fn export_chrome_trace(&self) -> String {
         let mut events = Vec::new();
@@ -327,6 +445,31 @@ impl ProfilerPanel {
     }
 
     fn overview_view(&mut self, ui: &mut egui::Ui) {
+        // Graph controls
+        ui.horizontal(|ui| {
+            let paused = self.capture_mode == CaptureMode::Paused;
+            if ui
+                .selectable_label(paused, "Pause")
+                .on_hover_text("Freeze the graph for inspection")
+                .clicked()
+            {
+                self.capture_mode = if paused { CaptureMode::Continuous } else { CaptureMode::Paused };
+            }
+
+            ui.separator();
+            ui.label("Window:");
+            if ui
+                .add(egui::DragValue::new(&mut self.max_frames).range(HISTORY_FRAMES_RANGE).suffix(" frames"))
+                .changed()
+            {
+                self.trim_history();
+            }
+
+            ui.separator();
+            ui.selectable_value(&mut self.graph_style, FrameGraphStyle::Bars, "Bars");
+            ui.selectable_value(&mut self.graph_style, FrameGraphStyle::Line, "Line");
+        });
+
         // Frame time graph
         let available_size = ui.available_size();
         let graph_height = 150.0;
@@ -339,63 +482,104 @@ impl ProfilerPanel {
         );
         let rect = response.rect;
 
+        // Scale to the slowest frame so spikes stay on screen, keeping
+        // both budget lines in view
+        let stats = self.frame_time_stats();
+        let scale_ms = stats.map_or(0.0, |stats| stats.max * 1.1).max(40.0);
+        let y_for = |ms: f32| rect.bottom() - (ms / scale_ms) * rect.height();
+
         // Draw background
         painter.rect_filled(rect, 0.0, egui::Color32::from_rgb(30, 30, 30));
 
         // Draw target line (60 FPS)
-        let target_y = rect.bottom() - (self.target_frame_time / 50.0) * rect.height();
+        let target_y = y_for(self.target_frame_time);
         painter.line_segment(
             [egui::pos2(rect.left(), target_y), egui::pos2(rect.right(), target_y)],
             egui::Stroke::new(1.0, egui::Color32::from_rgb(80, 200, 80)),
         );
 
         // Draw 30 FPS line
-        let thirty_fps_y = rect.bottom() - (33.33 / 50.0) * rect.height();
+        let thirty_fps_y = y_for(33.33);
         painter.line_segment(
             [egui::pos2(rect.left(), thirty_fps_y), egui::pos2(rect.right(), thirty_fps_y)],
             egui::Stroke::new(1.0, egui::Color32::from_rgb(200, 200, 80)),
         );
 
-        // Draw frame time bars and handle click to select
+        // Draw frame times and handle click to select. The newest frame is at
+        // the right edge and the history scrolls left as frames arrive.
         if !self.frame_times.is_empty() {
-            let bar_width = rect.width() / self.frame_times.len() as f32;
-
-            for (i, &frame_time) in self.frame_times.iter().enumerate() {
-                let x = rect.left() + i as f32 * bar_width;
-                let height = (frame_time / 50.0) * rect.height();
-                let y = rect.bottom() - height;
-
-                let is_selected = self.selected_frame == Some(i);
-                let color = if is_selected {
-                    egui::Color32::from_rgb(100, 150, 255) // Blue for selected
-                } else if frame_time > 33.33 {
+            let slot_width = rect.width() / self.max_frames.max(1) as f32;
+            let first_x = rect.right() - self.frame_times.len() as f32 * slot_width;
+            let frame_color = |frame_time: f32| {
+                if frame_time > 33.33 {
                     egui::Color32::from_rgb(255, 80, 80) // Red for < 30 FPS
                 } else if frame_time > 16.67 {
                     egui::Color32::from_rgb(255, 200, 80) // Yellow for < 60 FPS
                 } else {
                     egui::Color32::from_rgb(80, 200, 80) // Green for >= 60 FPS
-                };
+                }
+            };
 
-                painter.rect_filled(
-                    egui::Rect::from_min_size(egui::pos2(x, y), egui::vec2(bar_width.max(1.0), height)),
-                    0.0,
-                    color,
-                );
+            match self.graph_style {
+                FrameGraphStyle::Bars => {
+                    for (i, &frame_time) in self.frame_times.iter().enumerate() {
+                        let x = first_x + i as f32 * slot_width;
+                        let y = y_for(frame_time);
+                        let color = if self.selected_frame == Some(i) {
+                            egui::Color32::from_rgb(100, 150, 255) // Blue for selected
+                        } else {
+                            frame_color(frame_time)
+                        };
+
+                        painter.rect_filled(
+                            egui::Rect::from_min_max(egui::pos2(x, y), egui::pos2(x + slot_width.max(1.0), rect.bottom())),
+                            0.0,
+                            color,
+                        );
+                    }
+                }
+                FrameGraphStyle::Line => {
+                    let points: Vec<egui::Pos2> = self
+                        .frame_times
+                        .iter()
+                        .enumerate()
+                        .map(|(i, &frame_time)| egui::pos2(first_x + (i as f32 + 0.5) * slot_width, y_for(frame_time)))
+                        .collect();
+                    painter.add(egui::Shape::line(
+                        points.clone(),
+                        egui::Stroke::new(1.5, egui::Color32::from_rgb(120, 180, 255)),
+                    ));
+                    // Mark frames over budget so spikes stand out
+                    for (point, &frame_time) in points.iter().zip(&self.frame_times) {
+                        if frame_time > 16.67 {
+                            painter.circle_filled(*point, 2.0, frame_color(frame_time));
+                        }
+                    }
+                    if let Some(point) = self.selected_frame.and_then(|i| points.get(i)) {
+                        painter.circle_stroke(*point, 4.0, egui::Stroke::new(1.5, egui::Color32::WHITE));
+                    }
+                }
             }
 
             // Handle click to select frame
             if response.clicked() {
                 if let Some(pos) = response.interact_pointer_pos() {
-                    let x_ratio = (pos.x - rect.left()) / rect.width();
-                    let frame_idx = (x_ratio * self.frame_times.len() as f32) as usize;
-                    if frame_idx < self.frame_times.len() {
-                        self.selected_frame = Some(frame_idx);
+                    let frame_idx = ((pos.x - first_x) / slot_width).floor();
+                    if frame_idx >= 0.0 && (frame_idx as usize) < self.frame_times.len() {
+                        self.selected_frame = Some(frame_idx as usize);
                     }
                 }
             }
         }
 
         // Labels
+        painter.text(
+            egui::pos2(rect.left() + 4.0, rect.top() + 2.0),
+            egui::Align2::LEFT_TOP,
+            format!("{:.1} ms", scale_ms),
+            egui::FontId::proportional(10.0),
+            egui::Color32::GRAY,
+        );
         painter.text(
             egui::pos2(rect.right() - 50.0, target_y - 5.0),
             egui::Align2::RIGHT_BOTTOM,
@@ -414,23 +598,67 @@ impl ProfilerPanel {
         ui.add_space(10.0);
 
         // Statistics
-        if !self.frame_times.is_empty() {
-            let avg: f32 = self.frame_times.iter().sum::<f32>() / self.frame_times.len() as f32;
-            let min = self.frame_times.iter().cloned().fold(f32::MAX, f32::min);
-            let max = self.frame_times.iter().cloned().fold(f32::MIN, f32::max);
-
+        if let Some(stats) = stats {
             ui.horizontal(|ui| {
-                ui.label(format!("Avg: {:.2} ms ({:.0} FPS)", avg, 1000.0 / avg));
+                ui.label(format!("Current: {:.2} ms ({:.0} FPS)", stats.current, stats.current_fps()));
+                ui.separator();
+                ui.label(format!("Avg: {:.2} ms ({:.0} FPS)", stats.avg, 1000.0 / stats.avg));
                 ui.separator();
-                ui.label(format!("Min: {:.2} ms", min));
+                ui.label(format!("Min: {:.2} ms", stats.min));
                 ui.separator();
-                ui.label(format!("Max: {:.2} ms", max));
+                ui.label(format!("Max: {:.2} ms", stats.max));
                 if let Some(idx) = self.selected_frame {
                     ui.separator();
                     ui.label(format!("Selected: Frame {} ({:.2} ms)", idx, self.frame_times[idx]));
                 }
             });
         }
+
+        ui.add_space(10.0);
+        self.section_breakdown(ui);
+    }
+
+    /// Per-section times of the selected (or latest) frame next to their
+    /// rolling average
+    fn section_breakdown(&self, ui: &mut egui::Ui) {
+        ui.label("Update Sections");
+
+        let frame = match self.selected_frame {
+            Some(idx) => self.captured_frames.get(idx),
+            None => self.captured_frames.back(),
+        };
+        let averages = self.section_averages();
+        if averages.is_empty() {
+            ui.label(egui::RichText::new("No sections recorded").weak());
+            return;
+        }
+
+        let frame_ms = frame.map_or(0.0, |frame| frame.total_ms).max(f32::EPSILON);
+        egui::Grid::new("profiler_sections")
+            .num_columns(4)
+            .striped(true)
+            .show(ui, |ui| {
+                ui.strong("Section");
+                ui.strong("Frame");
+                ui.strong("Average");
+                ui.strong("");
+                ui.end_row();
+
+                for average in &averages {
+                    let last_ms = frame
+                        .and_then(|frame| frame.sections.iter().find(|section| section.name == average.name))
+                        .map_or(0.0, |section| section.duration_ms);
+                    ui.label(average.name);
+                    ui.monospace(format!("{:>7.3} ms", last_ms));
+                    ui.monospace(format!("{:>7.3} ms", average.duration_ms));
+                    ui.add(
+                        egui::ProgressBar::new((last_ms / frame_ms).clamp(0.0, 1.0))
+                            .desired_width(120.0)
+                            .show_percentage(),
+                    );
+                    ui.end_row();
+                }
+            });
     }
 
     fn cpu_scope_view(&self, ui: &mut egui::Ui) {
@@ -768,3 +996,53 @@ impl Default for ProfilerPanel {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_history_window_and_stats() {
+        let mut profiler = ProfilerPanel::new();
+        profiler.max_frames = 3;
+        for frame_time in [10.0, 40.0, 20.0, 30.0] {
+            profiler.record_frame(frame_time, Vec::new());
+        }
+
+        assert_eq!(profiler.frame_times, [40.0, 20.0, 30.0]);
+        let stats = profiler.frame_time_stats().unwrap();
+        assert_eq!((stats.min, stats.avg, stats.max, stats.current), (20.0, 30.0, 40.0, 30.0));
+
+        // Paused history stays frozen
+        profiler.capture_mode = CaptureMode::Paused;
+        profiler.record_frame(100.0, Vec::new());
+        assert_eq!(profiler.frame_time_stats().unwrap().current, 30.0);
+    }
+
+    #[test]
+    fn test_section_averages() {
+        let section = |name, duration_ms| SectionTiming { name, duration_ms };
+        let mut profiler = ProfilerPanel::new();
+        profiler.record_frame(16.0, vec![section("UI", 2.0), section("Physics", 4.0)]);
+        profiler.record_frame(16.0, vec![section("UI", 4.0)]);
+
+        let averages = profiler.section_averages();
+        assert_eq!(averages, vec![section("UI", 3.0), section("Physics", 2.0)]);
+    }
+
+    #[test]
+    fn test_scope_records_only_when_enabled() {
+        drop(scope("Disabled"));
+        assert!(take_sections().is_empty());
+
+        set_sections_enabled(true);
+        drop(scope("Audio"));
+        drop(scope("Audio"));
+        set_sections_enabled(false);
+
+        let sections = take_sections();
+        assert_eq!(sections.len(), 1);
+        assert_eq!(sections[0].name, "Audio");
+        assert!(take_sections().is_empty());
+    }
+}
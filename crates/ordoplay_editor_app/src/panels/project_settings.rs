@@ -2,7 +2,8 @@
 //! Project Settings panel - Configure project-wide settings.
 
 use crate::project::{
-    BuildConfiguration, QualityLevel, TargetPlatform, TextureCompression, InputType,
+    BuildConfiguration, CollisionLayerSettings, QualityLevel, TargetPlatform, TextureCompression, InputBinding,
+    InputType, UpAxis,
 };
 use crate::state::EditorState;
 
@@ -53,6 +54,10 @@ pub struct ProjectSettingsPanel {
     pub current_category: SettingsCategory,
     /// New axis name buffer
     pub new_axis_name: String,
+    /// New input action name buffer
+    pub new_action_name: String,
+    /// Up axis awaiting confirmation (re-orient vs. change conventions only)
+    pub pending_up_axis: Option<UpAxis>,
 }
 
 impl ProjectSettingsPanel {
@@ -61,6 +66,8 @@ impl ProjectSettingsPanel {
             open: false,
             current_category: SettingsCategory::default(),
             new_axis_name: String::new(),
+            new_action_name: String::new(),
+            pending_up_axis: None,
         }
     }
 
@@ -79,6 +86,49 @@ impl ProjectSettingsPanel {
                 self.ui(ui, state);
             });
         self.open = open;
+
+        self.up_axis_prompt(ctx, state);
+    }
+
+    /// Ask how to apply a pending up axis change to the open scene
+    fn up_axis_prompt(&mut self, ctx: &egui::Context, state: &mut EditorState) {
+        let Some(up_axis) = self.pending_up_axis else {
+            return;
+        };
+
+        let mut should_close = false;
+
+        egui::Window::new("Change World Up Axis")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "Switch from {} to {}?",
+                    state.up_axis().display_name(),
+                    up_axis.display_name()
+                ));
+                ui.label("Re-orienting rotates the open scene so existing content stays upright.");
+                ui.add_space(10.0);
+
+                ui.horizontal(|ui| {
+                    if ui.button("Cancel").clicked() {
+                        should_close = true;
+                    }
+                    if ui.button("Change Conventions Only").clicked() {
+                        state.set_up_axis(up_axis, false);
+                        should_close = true;
+                    }
+                    if ui.button("Re-orient Scene").clicked() {
+                        state.set_up_axis(up_axis, true);
+                        should_close = true;
+                    }
+                });
+            });
+
+        if should_close {
+            self.pending_up_axis = None;
+        }
     }
 
     fn ui(&mut self, ui: &mut egui::Ui, state: &mut EditorState) {
@@ -150,6 +200,21 @@ impl ProjectSettingsPanel {
             state.project_manager.mark_dirty();
         }
 
+        ui.separator();
+        ui.heading("World");
+
+        let current_up = state.up_axis();
+        ui.horizontal(|ui| {
+            ui.label("Up Axis:");
+            for up_axis in UpAxis::all() {
+                if ui.selectable_label(current_up == *up_axis, up_axis.display_name()).clicked()
+                    && current_up != *up_axis
+                {
+                    self.pending_up_axis = Some(*up_axis);
+                }
+            }
+        });
+
         if let Some(dir) = &state.project_manager.project_dir {
             ui.separator();
             ui.label(format!("Location: {}", dir.display()));
@@ -175,6 +240,21 @@ impl ProjectSettingsPanel {
             }
         });
 
+        // Player crate; empty exports content only
+        ui.horizontal(|ui| {
+            ui.label("Player Crate:");
+            let settings = &mut state.project_manager.settings;
+            let mut path = settings.player_crate.as_ref().map(|p| p.display().to_string()).unwrap_or_default();
+            if ui
+                .text_edit_singleline(&mut path)
+                .on_hover_text("Cargo crate compiled for each target, relative to the project. Leave empty to export content only.")
+                .changed()
+            {
+                settings.player_crate = (!path.trim().is_empty()).then(|| std::path::PathBuf::from(path.trim()));
+                dirty = true;
+            }
+        });
+
         // Platform
         let current_platform = state.project_manager.settings.target_platform;
         ui.horizontal(|ui| {
@@ -226,6 +306,17 @@ impl ProjectSettingsPanel {
             }
         });
 
+        // Targets queued by "Build Enabled Targets"
+        ui.separator();
+        ui.heading("Build Targets");
+        for target in TargetPlatform::all() {
+            let settings = state.project_manager.settings.get_platform_settings_mut(*target);
+            dirty |= ui
+                .checkbox(&mut settings.enabled, target.display_name())
+                .on_hover_text(format!("{} \u{2192} {}", target.target_triple(), settings.output_dir.display()))
+                .changed();
+        }
+
         if dirty {
             state.project_manager.mark_dirty();
         }
@@ -234,44 +325,70 @@ impl ProjectSettingsPanel {
 
         // Show build progress if building
         if state.build_manager.is_building() {
-            if let Some(progress) = state.build_manager.get_progress() {
+            if let Some(progress) = state.build_manager.progress() {
+                let target = progress.target.map_or("", |target| target.display_name());
+                let mut cancel = false;
                 ui.horizontal(|ui| {
-                    ui.label(format!("Building: {}", progress.step));
-                    if ui.small_button("Cancel").clicked() {
-                        state.build_manager.cancel();
-                    }
+                    ui.label(format!("Building {}: {}", target, progress.step));
+                    cancel = ui.small_button("Cancel").clicked();
                 });
-                ui.add(egui::ProgressBar::new(progress.progress as f32 / 100.0));
+                ui.add(egui::ProgressBar::new(progress.fraction).show_percentage());
+                if !progress.message.is_empty() {
+                    ui.label(egui::RichText::new(&progress.message).small().weak());
+                }
+                if cancel {
+                    state.build_manager.cancel();
+                }
             }
         } else {
-            // Show build button and last result
-            if ui.button("Build Project").clicked() {
+            // Show build buttons and the result of each target
+            let enabled_targets: Vec<TargetPlatform> = TargetPlatform::all()
+                .iter()
+                .copied()
+                .filter(|target| state.project_manager.settings.get_platform_settings(*target).enabled)
+                .collect();
+            let mut queued = Vec::new();
+            ui.horizontal(|ui| {
+                if ui.button("Build Project").clicked() {
+                    queued.push(platform);
+                }
+                if ui
+                    .add_enabled(!enabled_targets.is_empty(), egui::Button::new("Build Enabled Targets"))
+                    .on_hover_text(format!("Build {} targets one after another", enabled_targets.len()))
+                    .clicked()
+                {
+                    queued.clone_from(&enabled_targets);
+                }
+            });
+
+            if !queued.is_empty() {
                 if let Some(project_dir) = state.project_manager.project_dir.clone() {
-                    tracing::info!("Starting build for {:?}", platform);
-                    state.build_manager.start_build(
+                    tracing::info!("Starting build for {:?}", queued);
+                    state.build_manager.build_targets(
                         &state.project_manager.settings,
                         &project_dir,
+                        &queued,
                     );
                 } else {
                     tracing::warn!("No project directory set");
                 }
             }
 
-            // Show last build result
-            if let Some(ref result) = state.build_manager.last_result {
+            for (target, result) in &state.build_manager.target_results {
+                let name = target.display_name();
                 match result {
                     crate::build::BuildResult::Success { output_dir, build_time_secs, assets_processed, scenes_processed } => {
                         ui.label(egui::RichText::new(format!(
-                            "Build successful: {} scenes, {} assets in {:.1}s",
-                            scenes_processed, assets_processed, build_time_secs
+                            "{} build successful: {} scenes, {} assets in {:.1}s",
+                            name, scenes_processed, assets_processed, build_time_secs
                         )).color(egui::Color32::GREEN));
-                        ui.label(format!("Output: {}", output_dir.display()));
+                        ui.label(format!("Output: {} ({})", output_dir.display(), target.target_triple()));
                     }
                     crate::build::BuildResult::Cancelled => {
-                        ui.label(egui::RichText::new("Build cancelled").color(egui::Color32::YELLOW));
+                        ui.label(egui::RichText::new(format!("{} build cancelled", name)).color(egui::Color32::YELLOW));
                     }
                     crate::build::BuildResult::Failed(err) => {
-                        ui.label(egui::RichText::new(format!("Build failed: {}", err)).color(egui::Color32::RED));
+                        ui.label(egui::RichText::new(format!("{} build failed: {}", name, err)).color(egui::Color32::RED));
                     }
                 }
             }
@@ -403,11 +520,52 @@ impl ProjectSettingsPanel {
             ui.checkbox(&mut physics.continuous_collision, "").changed()
         }).inner;
 
+        ui.separator();
+        ui.collapsing("Collision Layers", |ui| {
+            dirty |= Self::collision_matrix_ui(ui, &mut physics.collision_layers);
+        });
+
         if dirty {
             state.project_manager.mark_dirty();
         }
     }
 
+    /// Layer names and a grid of which layer pairs collide. Collision is
+    /// symmetric, so only the upper triangle of the matrix is shown.
+    fn collision_matrix_ui(ui: &mut egui::Ui, layers: &mut CollisionLayerSettings) -> bool {
+        let mut dirty = false;
+        let count = layers.layer_names.len().min(layers.layer_matrix.len());
+
+        egui::Grid::new("collision_layer_matrix").striped(true).show(ui, |ui| {
+            ui.label("");
+            for j in 0..count {
+                ui.label(j.to_string()).on_hover_text(layers.layer_names[j].as_str());
+            }
+            ui.end_row();
+
+            for i in 0..count {
+                let name = egui::TextEdit::singleline(&mut layers.layer_names[i]).desired_width(110.0);
+                dirty |= ui.add(name).changed();
+                for j in 0..count {
+                    if j < i {
+                        ui.label("");
+                        continue;
+                    }
+                    let (a, b) = (i as u32, j as u32);
+                    let mut collides = layers.should_collide(a, b);
+                    let pair = format!("{} \u{2194} {}", layers.layer_names[i], layers.layer_names[j]);
+                    if ui.checkbox(&mut collides, "").on_hover_text(pair).changed() {
+                        layers.set_collision(a, b, collides);
+                        dirty = true;
+                    }
+                }
+                ui.end_row();
+            }
+        });
+
+        dirty
+    }
+
     fn graphics_ui(&mut self, ui: &mut egui::Ui, state: &mut EditorState) {
         ui.heading("Graphics Settings");
 
@@ -478,6 +636,26 @@ impl ProjectSettingsPanel {
             ui.add(egui::DragValue::new(&mut graphics.shadow_distance).range(10.0..=1000.0)).changed()
         }).inner;
 
+        ui.add_space(8.0);
+        ui.heading("Performance Budgets");
+        ui.label("Warn when the open scene exceeds these limits (0 = unlimited).");
+
+        let budgets = &mut state.project_manager.settings.budgets;
+        dirty |= ui.horizontal(|ui| {
+            ui.label("Max Entities:");
+            ui.add(egui::DragValue::new(&mut budgets.max_entities)).changed()
+        }).inner;
+
+        dirty |= ui.horizontal(|ui| {
+            ui.label("Max Lights:");
+            ui.add(egui::DragValue::new(&mut budgets.max_lights)).changed()
+        }).inner;
+
+        dirty |= ui.horizontal(|ui| {
+            ui.label("Max Triangles:");
+            ui.add(egui::DragValue::new(&mut budgets.max_triangles).speed(100.0)).changed()
+        }).inner;
+
         if dirty {
             state.project_manager.mark_dirty();
         }
@@ -616,6 +794,123 @@ impl ProjectSettingsPanel {
         if dirty {
             state.project_manager.mark_dirty();
         }
+
+        ui.add_space(12.0);
+        self.input_actions_ui(ui, state);
+    }
+
+    fn input_actions_ui(&mut self, ui: &mut egui::Ui, state: &mut EditorState) {
+        ui.heading("Input Actions");
+
+        // Add new action
+        ui.horizontal(|ui| {
+            ui.label("New:");
+            ui.text_edit_singleline(&mut self.new_action_name);
+            let name = self.new_action_name.trim().to_string();
+            let exists = state.project_manager.settings.input.actions.actions.contains_key(&name);
+            if ui.add_enabled(!name.is_empty() && !exists, egui::Button::new("Add")).clicked() {
+                state.project_manager.settings.input.actions.add_action(name);
+                self.new_action_name.clear();
+                state.project_manager.mark_dirty();
+            }
+        });
+
+        ui.separator();
+
+        // Duplicates are allowed but flagged
+        let duplicates = state.project_manager.settings.input.actions.duplicate_bindings();
+        let action_names: Vec<String> = state.project_manager.settings.input.actions.actions
+            .keys()
+            .cloned()
+            .collect();
+
+        let mut remove_action: Option<String> = None;
+        let mut dirty = false;
+
+        for name in &action_names {
+            let id = egui::Id::new(format!("input_action_{}", name));
+            egui::CollapsingHeader::new(name)
+                .id_salt(id)
+                .show(ui, |ui| {
+                    let Some(bindings) = state.project_manager.settings.input.actions.actions.get_mut(name) else {
+                        return;
+                    };
+
+                    let mut remove_binding: Option<usize> = None;
+                    for (i, binding) in bindings.iter_mut().enumerate() {
+                        ui.horizontal(|ui| {
+                            let device = binding.device_name();
+                            egui::ComboBox::from_id_salt(format!("binding_device_{}_{}", name, i))
+                                .selected_text(device)
+                                .width(80.0)
+                                .show_ui(ui, |ui| {
+                                    let button = binding.button().to_string();
+                                    let options = [
+                                        InputBinding::Key(button.clone()),
+                                        InputBinding::MouseButton(button.clone()),
+                                        InputBinding::GamepadButton(button),
+                                    ];
+                                    for option in options {
+                                        let label = option.device_name();
+                                        if ui.selectable_label(device == label, label).clicked() {
+                                            *binding = option;
+                                            dirty = true;
+                                        }
+                                    }
+                                });
+
+                            dirty |= ui.add(
+                                egui::TextEdit::singleline(binding.button_mut()).desired_width(100.0),
+                            ).changed();
+
+                            if let Some(others) = duplicates.get(&*binding) {
+                                let others: Vec<&str> = others.iter()
+                                    .map(String::as_str)
+                                    .filter(|other| *other != name.as_str())
+                                    .collect();
+                                ui.colored_label(egui::Color32::YELLOW, "\u{26A0}")
+                                    .on_hover_text(format!("Also bound to: {}", others.join(", ")));
+                            }
+
+                            if ui.small_button("\u{2716}").on_hover_text("Remove binding").clicked() {
+                                remove_binding = Some(i);
+                            }
+                        });
+                    }
+
+                    if let Some(i) = remove_binding {
+                        bindings.remove(i);
+                        dirty = true;
+                    }
+
+                    ui.horizontal(|ui| {
+                        if ui.small_button("+ Binding").clicked() {
+                            bindings.push(InputBinding::Key(String::new()));
+                            dirty = true;
+                        }
+                        if ui.small_button("Remove Action").clicked() {
+                            remove_action = Some(name.clone());
+                        }
+                    });
+                });
+        }
+
+        if let Some(name) = remove_action {
+            state.project_manager.settings.input.actions.remove_action(&name);
+            dirty = true;
+        }
+
+        if !duplicates.is_empty() {
+            ui.add_space(4.0);
+            ui.colored_label(
+                egui::Color32::YELLOW,
+                format!("\u{26A0} {} binding(s) shared by multiple actions", duplicates.len()),
+            );
+        }
+
+        if dirty {
+            state.project_manager.mark_dirty();
+        }
     }
 }
 
@@ -8,6 +8,7 @@
 use egui::Ui;
 use std::any::{Any, TypeId};
 use std::collections::HashMap;
+use std::ops::RangeInclusive;
 
 /// Result of drawing a property - indicates if the value was changed
 #[allow(dead_code)] // Intentionally kept for API completeness
@@ -270,43 +271,128 @@ pub fn draw_string(ui: &mut Ui, value: &mut String, meta: &PropertyMeta) -> Draw
     result
 }
 
-/// Draw a Vec3 (as [f32; 3])
-#[allow(dead_code)] // Intentionally kept for API completeness
-pub fn draw_vec3(ui: &mut Ui, value: &mut [f32; 3], meta: &PropertyMeta) -> DrawResult {
+/// Drag range from the meta's min/max, `default` where unset
+fn meta_range(meta: &PropertyMeta, default: RangeInclusive<f32>) -> RangeInclusive<f32> {
+    let min = meta.min.map_or(*default.start(), |min| min as f32);
+    let max = meta.max.map_or(*default.end(), |max| max as f32);
+    min..=max.max(min)
+}
+
+/// Labeled drag values for each component of a vector.
+///
+/// Components set from code outside the range are clamped into it, which
+/// counts as a change.
+fn draw_components<const N: usize>(
+    ui: &mut Ui,
+    value: &mut [f32; N],
+    labels: [&str; N],
+    range: RangeInclusive<f32>,
+    meta: &PropertyMeta,
+) -> DrawResult {
     let mut result = DrawResult::Unchanged;
     let speed = meta.step.unwrap_or(0.1) as f32;
 
-    ui.horizontal(|ui| {
-        ui.label(&meta.name);
+    for component in value.iter_mut().filter(|c| !c.is_nan()) {
+        let clamped = component.clamp(*range.start(), *range.end());
+        if clamped != *component {
+            *component = clamped;
+            result = DrawResult::Changed;
+        }
+    }
 
-        for (i, label) in ["X", "Y", "Z"].iter().enumerate() {
-            let mut drag = egui::DragValue::new(&mut value[i])
-                .speed(speed)
-                .prefix(format!("{}: ", label));
+    for (component, label) in value.iter_mut().zip(labels) {
+        let mut drag = egui::DragValue::new(component)
+            .speed(speed)
+            .range(range.clone())
+            .prefix(format!("{}: ", label));
 
-            if let Some(suffix) = &meta.suffix {
-                drag = drag.suffix(suffix.as_str());
-            }
+        if let Some(suffix) = &meta.suffix {
+            drag = drag.suffix(suffix.as_str());
+        }
 
-            let response = ui.add_enabled(!meta.read_only, drag);
+        let response = ui.add_enabled(!meta.read_only, drag);
 
-            if response.changed() {
-                result = DrawResult::Changed;
-            }
-            if response.drag_started() {
-                result = DrawResult::EditStarted;
-            }
-            if response.drag_stopped() {
-                result = DrawResult::EditEnded;
-            }
+        if response.changed() {
+            result = DrawResult::Changed;
+        }
+        if response.drag_started() {
+            result = DrawResult::EditStarted;
         }
+        if response.drag_stopped() {
+            result = DrawResult::EditEnded;
+        }
+    }
+
+    result
+}
+
+/// Draw a labeled vector with one drag value per component
+fn draw_vector<const N: usize>(ui: &mut Ui, value: &mut [f32; N], labels: [&str; N], meta: &PropertyMeta) -> DrawResult {
+    let range = meta_range(meta, f32::MIN..=f32::MAX);
+    ui.horizontal(|ui| {
+        ui.label(&meta.name);
+        let result = draw_components(ui, value, labels, range, meta);
 
         if let Some(tooltip) = &meta.tooltip {
             ui.label("").on_hover_text(tooltip);
         }
-    });
+        result
+    })
+    .inner
+}
 
-    result
+/// Draw a Vec2 (as [f32; 2])
+#[allow(dead_code)] // Intentionally kept for API completeness
+pub fn draw_vec2(ui: &mut Ui, value: &mut [f32; 2], meta: &PropertyMeta) -> DrawResult {
+    draw_vector(ui, value, ["X", "Y"], meta)
+}
+
+/// Draw a Vec3 (as [f32; 3])
+#[allow(dead_code)] // Intentionally kept for API completeness
+pub fn draw_vec3(ui: &mut Ui, value: &mut [f32; 3], meta: &PropertyMeta) -> DrawResult {
+    draw_vector(ui, value, ["X", "Y", "Z"], meta)
+}
+
+/// Draw a Vec4 (as [f32; 4])
+#[allow(dead_code)] // Intentionally kept for API completeness
+pub fn draw_vec4(ui: &mut Ui, value: &mut [f32; 4], meta: &PropertyMeta) -> DrawResult {
+    draw_vector(ui, value, ["X", "Y", "Z", "W"], meta)
+}
+
+/// Draw an unmultiplied RGBA color ([f32; 4]) as a picker plus a drag value
+/// per channel. Channels stay within 0-1 unless the meta sets a range,
+/// e.g. for HDR tints.
+#[allow(dead_code)] // Intentionally kept for API completeness
+pub fn draw_color_rgba(ui: &mut Ui, value: &mut [f32; 4], meta: &PropertyMeta) -> DrawResult {
+    let range = meta_range(meta, 0.0..=1.0);
+    let meta = PropertyMeta {
+        step: meta.step.or(Some(0.01)),
+        ..meta.clone()
+    };
+
+    ui.horizontal(|ui| {
+        ui.label(&meta.name);
+
+        // Edit a copy: the picker round-trips through premultiplied alpha,
+        // which would lose the color of fully transparent values
+        let mut picked = *value;
+        let response = ui.add_enabled_ui(!meta.read_only, |ui| ui.color_edit_button_rgba_unmultiplied(&mut picked)).inner;
+        let mut result = DrawResult::Unchanged;
+        if response.changed() {
+            *value = picked;
+            result = DrawResult::Changed;
+        }
+        if let Some(tooltip) = &meta.tooltip {
+            response.on_hover_text(tooltip);
+        }
+
+        ui.add_space(8.0);
+        match draw_components(ui, value, ["R", "G", "B", "A"], range, &meta) {
+            DrawResult::Unchanged => result,
+            channels => channels,
+        }
+    })
+    .inner
 }
 
 /// Draw a color picker (RGB as [f32; 3])
@@ -722,4 +808,35 @@ mod tests {
         assert_eq!(meta.step, Some(0.5));
         assert!(meta.is_angle);
     }
+
+    fn run_drawer(mut draw: impl FnMut(&mut Ui) -> DrawResult) -> bool {
+        let ctx = egui::Context::default();
+        let mut changed = false;
+        let _ = ctx.run(egui::RawInput::default(), |ctx| {
+            egui::CentralPanel::default().show(ctx, |ui| changed |= draw(ui).is_changed());
+        });
+        changed
+    }
+
+    #[test]
+    fn test_vector_drawers_detect_changes() {
+        let meta = PropertyMeta::new("Tint").with_range(0.0, 1.0).with_step(0.01);
+        let mut tint = [0.5, 0.25, 1.0, 1.0];
+        assert!(!run_drawer(|ui| draw_vec4(ui, &mut tint, &meta)));
+
+        // A value written from code outside the range is clamped and reported
+        tint[2] = 2.0;
+        assert!(run_drawer(|ui| draw_vec4(ui, &mut tint, &meta)));
+        assert_eq!(tint, [0.5, 0.25, 1.0, 1.0]);
+
+        let mut size = [-3.0, 200.0];
+        assert!(!run_drawer(|ui| draw_vec2(ui, &mut size, &PropertyMeta::new("Size"))));
+
+        let mut color = [0.2, 0.4, 0.6, 0.0];
+        assert!(!run_drawer(|ui| draw_color_rgba(ui, &mut color, &PropertyMeta::new("Color"))));
+        assert_eq!(color, [0.2, 0.4, 0.6, 0.0]);
+        color[3] = 1.5;
+        assert!(run_drawer(|ui| draw_color_rgba(ui, &mut color, &PropertyMeta::new("Color"))));
+        assert_eq!(color[3], 1.0);
+    }
 }
@@ -3,8 +3,11 @@
 
 
 use crate::state::{EditorState, EntityId, SelectMode};
-use crate::tools::{EditorCamera, GizmoMode, GizmoOperation};
-use crate::viewport_renderer::ViewportRenderer;
+use crate::tools::{
+    AlignmentGuide, CameraProjection, EditorCamera, GizmoMode, GizmoOperation, PivotMode, RelationshipKind,
+    SnapMode, VertexSnapTarget, ViewPreset,
+};
+use crate::viewport_renderer::{RenderMode, ViewportRenderer};
 use egui_wgpu::wgpu;
 
 /// Gizmo axis being dragged
@@ -13,6 +16,34 @@ pub enum GizmoAxis {
     X,
     Y,
     Z,
+    /// Center handle of the scale gizmo, scaling all axes at once
+    Uniform,
+}
+
+impl GizmoAxis {
+    /// The three single-axis handles
+    const AXES: [Self; 3] = [Self::X, Self::Y, Self::Z];
+
+    /// Component index of the axis, `None` for the uniform handle
+    fn index(self) -> Option<usize> {
+        match self {
+            Self::X => Some(0),
+            Self::Y => Some(1),
+            Self::Z => Some(2),
+            Self::Uniform => None,
+        }
+    }
+}
+
+/// Screen-space shape of the gizmo for the current mode
+#[derive(Debug, Clone)]
+struct GizmoGeometry {
+    /// Gizmo center (the selection pivot)
+    center: egui::Pos2,
+    /// Handle end of each axis (scale gizmo)
+    axis_ends: [egui::Pos2; 3],
+    /// Ring outline around each axis (rotate gizmo)
+    rings: [Vec<egui::Pos2>; 3],
 }
 
 /// Active gizmo drag state
@@ -27,8 +58,63 @@ struct GizmoDragState {
     start_mouse: egui::Pos2,
     /// Primary entity being manipulated (for gizmo positioning)
     primary_entity_id: EntityId,
+    /// Entities rotated or scaled, with their parent and start world
+    /// transform. Children of other selected entities follow their parent.
+    start_world: Vec<(EntityId, Option<EntityId>, crate::state::Transform)>,
+    /// Shared selection pivot; with individual origins each entity turns
+    /// and scales around its own origin instead
+    pivot: [f32; 3],
+    /// World direction of the dragged axis
+    axis_dir: [f32; 3],
+    /// Gizmo center on screen at drag start
+    screen_center: egui::Pos2,
+    /// Screen offset of the dragged scale handle from the center
+    screen_axis: egui::Vec2,
+    /// Angle swept around the center so far, in radians (screen orientation)
+    swept_angle: f32,
+    /// Mouse position when the swept angle was last updated
+    last_mouse: egui::Pos2,
+    /// World positions of the entities that stay put during the drag,
+    /// which dragged entities can line up with
+    alignment_candidates: Vec<(EntityId, [f32; 3])>,
+    /// World mesh vertices of the entities that stay put, gathered the
+    /// first time vertex snapping is used during the drag
+    vertex_candidates: Option<Vec<(EntityId, [f32; 3])>>,
+}
+
+impl GizmoDragState {
+    /// Add the angle swept around the gizmo center since the last update
+    fn sweep(&mut self, mouse: egui::Pos2) {
+        let angle = |pos: egui::Pos2| (pos.y - self.screen_center.y).atan2(pos.x - self.screen_center.x);
+        let mut step = angle(mouse) - angle(self.last_mouse);
+        if step > std::f32::consts::PI {
+            step -= std::f32::consts::TAU;
+        } else if step < -std::f32::consts::PI {
+            step += std::f32::consts::TAU;
+        }
+        self.swept_angle += step;
+        self.last_mouse = mouse;
+    }
 }
 
+/// Screen size of the gizmo handles in points
+const GIZMO_SIZE: f32 = 60.0;
+
+/// Segments used to draw and hit-test a rotation ring
+const RING_SEGMENTS: usize = 48;
+
+/// Alignment threshold as a fraction of the camera distance
+const ALIGNMENT_THRESHOLD: f32 = 0.015;
+
+/// How close to the cursor, in points, a vertex must be to snap to it
+const VERTEX_SNAP_RADIUS: f32 = 24.0;
+
+/// Vertical field of view of the viewport camera (45 degrees)
+const VIEWPORT_FOV: f32 = std::f32::consts::FRAC_PI_4;
+
+/// Far clip distance of the viewport camera
+const VIEWPORT_FAR: f32 = 1000.0;
+
 /// The main 3D viewport panel
 pub struct ViewportPanel {
     /// Editor camera
@@ -44,14 +130,32 @@ pub struct ViewportPanel {
     has_focus: bool,
     /// Grid visibility
     pub show_grid: bool,
+    /// How entity geometry is drawn
+    pub render_mode: RenderMode,
     /// Gizmo visibility
     pub show_gizmos: bool,
     /// Stats overlay visibility
     pub show_stats: bool,
+    /// Parent and reference line overlay visibility
+    pub show_relationships: bool,
+    /// Only draw relationship lines touching the selection
+    pub relationships_selection_only: bool,
     /// Active gizmo drag state
     gizmo_drag: Option<GizmoDragState>,
     /// Currently hovered gizmo axis (for highlighting)
     hovered_axis: Option<GizmoAxis>,
+    /// Alignment guides for the current translate drag
+    alignment_guides: Vec<AlignmentGuide>,
+    /// Vertex the current translate drag snaps onto
+    vertex_snap_target: Option<VertexSnapTarget>,
+    /// Mesh vertices for vertex snapping, loaded on first use
+    mesh_vertices: crate::mesh_stats::MeshVertexCache,
+    /// Marquee selection rectangle being dragged (start, current)
+    marquee: Option<(egui::Pos2, egui::Pos2)>,
+    /// Entity the renderer's ID pass found under this frame's click position
+    gpu_pick: Option<(egui::Pos2, Option<EntityId>)>,
+    /// Pointer position over the viewport and the viewport rect
+    hover: Option<(egui::Pos2, egui::Rect)>,
 }
 
 impl ViewportPanel {
@@ -64,15 +168,26 @@ impl ViewportPanel {
             viewport_size: [800.0, 600.0],
             has_focus: false,
             show_grid: true,
+            render_mode: RenderMode::default(),
             show_gizmos: true,
             show_stats: true,
+            show_relationships: false,
+            relationships_selection_only: true,
             gizmo_drag: None,
             hovered_axis: None,
+            alignment_guides: Vec::new(),
+            vertex_snap_target: None,
+            mesh_vertices: crate::mesh_stats::MeshVertexCache::default(),
+            marquee: None,
+            gpu_pick: None,
+            hover: None,
         }
     }
 
     /// Render the viewport panel
     pub fn ui(&mut self, ui: &mut egui::Ui, state: &mut EditorState) {
+        self.camera.set_up_axis(state.up_axis());
+
         // Toolbar
         ui.horizontal(|ui| {
             self.toolbar(ui, state);
@@ -102,7 +217,13 @@ impl ViewportPanel {
         self.draw_overlay(ui, &painter, response.rect, state);
 
         // Handle input
-        self.handle_input(&response, state);
+        self.handle_input(&response, response.rect, state);
+
+        // Draw reference animation ghosts
+        self.draw_animation_ghosts(&painter, response.rect, state);
+        self.draw_relationship_lines(&painter, response.rect, state);
+        self.draw_alignment_guides(&painter, response.rect, state);
+        self.draw_marquee(&painter);
 
         // Draw gizmos if selection exists
         if !state.selection.is_empty() && self.show_gizmos {
@@ -120,6 +241,18 @@ impl ViewportPanel {
         queue: &wgpu::Queue,
         egui_renderer: &mut egui_wgpu::Renderer,
     ) {
+        let up_axis = state.up_axis();
+        self.camera.set_up_axis(up_axis);
+        renderer.update_grid(
+            device,
+            up_axis,
+            state.snap_size,
+            self.camera.target,
+            self.camera.distance,
+            VIEWPORT_FAR,
+        );
+        renderer.set_environment(&state.scene.environment);
+
         // Toolbar
         ui.horizontal(|ui| {
             self.toolbar(ui, state);
@@ -127,33 +260,42 @@ impl ViewportPanel {
 
         ui.separator();
 
-        // Main viewport area
+        // Main viewport area, letterboxed to the game view aspect in play mode
         let available_size = ui.available_size();
-        self.viewport_size = [available_size.x, available_size.y];
+        let view_size = state.play_mode.game_view_size(available_size);
+        self.viewport_size = [view_size.x, view_size.y];
 
         // Convert to pixels (accounting for scale factor)
         let pixels_per_point = ui.ctx().pixels_per_point();
-        let width = (available_size.x * pixels_per_point) as u32;
-        let height = (available_size.y * pixels_per_point) as u32;
+        let width = (view_size.x * pixels_per_point) as u32;
+        let height = (view_size.y * pixels_per_point) as u32;
 
         // Resize renderer if needed
         renderer.resize(device, [width.max(1), height.max(1)]);
 
         // Update camera
-        let aspect = available_size.x / available_size.y.max(1.0);
+        let aspect = view_size.x / view_size.y.max(1.0);
         renderer.update_camera(
             queue,
             self.camera.position,
             self.camera.target,
-            [0.0, 1.0, 0.0], // up vector
+            self.camera.up,
             aspect,
-            std::f32::consts::FRAC_PI_4, // 45 degree FOV
+            VIEWPORT_FOV,
             0.1,
-            1000.0,
+            VIEWPORT_FAR,
+            self.camera.projection,
         );
 
         // Render the 3D scene
-        renderer.render(device, queue, self.show_grid);
+        renderer.render(
+            device,
+            queue,
+            &state.scene,
+            self.show_grid,
+            self.render_mode,
+            &state.physics_debug_lines(),
+        );
 
         // Get or create egui texture ID
         let texture_id = renderer.get_egui_texture_id(egui_renderer, device);
@@ -161,26 +303,60 @@ impl ViewportPanel {
 
         // Allocate space and display the rendered texture
         let (response, painter) = ui.allocate_painter(available_size, egui::Sense::click_and_drag());
+        let rect = egui::Rect::from_center_size(response.rect.center(), view_size);
 
         // Track focus
         self.has_focus = response.has_focus() || response.hovered();
 
-        // Draw the rendered texture
+        // Draw the rendered texture, with bars around a letterboxed game view
+        if rect != response.rect {
+            painter.rect_filled(response.rect, 0.0, egui::Color32::BLACK);
+        }
         let uv = egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0));
-        painter.image(texture_id, response.rect, uv, egui::Color32::WHITE);
+        painter.image(texture_id, rect, uv, egui::Color32::WHITE);
 
         // Draw viewport overlay info
-        self.draw_overlay(ui, &painter, response.rect, state);
+        self.draw_overlay(ui, &painter, rect, state);
+        if state.play_mode.current_state().is_active() {
+            Self::draw_game_view_label(&painter, rect, state, [width, height]);
+        }
+
+        // Resolve this frame's click against the entity ID buffer
+        self.gpu_pick = Self::click_position(&response).filter(|pos| rect.contains(*pos)).map(|pos| {
+            let offset = (pos - rect.min) * pixels_per_point;
+            let picked = renderer.pick(device, queue, &state.scene, offset.x as u32, offset.y as u32);
+            (pos, picked)
+        });
 
         // Handle input
-        self.handle_input(&response, state);
+        self.handle_input(&response, rect, state);
+        self.gpu_pick = None;
+
+        // Draw reference animation ghosts
+        self.draw_animation_ghosts(&painter, rect, state);
+        self.draw_relationship_lines(&painter, rect, state);
+        self.draw_alignment_guides(&painter, rect, state);
+        self.draw_marquee(&painter);
 
         // Draw gizmos if selection exists
         if !state.selection.is_empty() && self.show_gizmos {
-            self.draw_gizmo_overlay(&painter, response.rect, state);
+            self.draw_gizmo_overlay(&painter, rect, state);
         }
     }
 
+    /// Label the game view with its aspect and rendered resolution
+    fn draw_game_view_label(painter: &egui::Painter, rect: egui::Rect, state: &EditorState, size: [u32; 2]) {
+        let text = format!("{}  {}x{}", state.play_mode.game_view_aspect.label(), size[0], size[1]);
+        let galley = painter.layout_no_wrap(text, egui::FontId::monospace(11.0), egui::Color32::from_rgb(200, 200, 200));
+        let pos = rect.right_bottom() - egui::vec2(galley.size().x + 8.0, galley.size().y + 8.0);
+        painter.rect_filled(
+            egui::Rect::from_min_size(pos, galley.size()).expand(3.0),
+            3.0,
+            egui::Color32::from_black_alpha(160),
+        );
+        painter.galley(pos, galley, egui::Color32::WHITE);
+    }
+
     fn toolbar(&mut self, ui: &mut egui::Ui, state: &mut EditorState) {
         // Gizmo mode buttons
         let modes = [
@@ -208,22 +384,111 @@ impl ViewportPanel {
             state.use_world_space = !state.use_world_space;
         }
 
+        // Pivot mode
+        egui::ComboBox::from_id_salt("pivot_mode")
+            .selected_text(state.pivot_mode.name())
+            .show_ui(ui, |ui| {
+                for mode in PivotMode::ALL {
+                    ui.selectable_value(&mut state.pivot_mode, mode, mode.name());
+                }
+            })
+            .response
+            .on_hover_text("Pivot for rotating and scaling a multi-selection");
+
         // Snap toggle
-        let snap_text = if state.snap_enabled {
-            format!("Snap: {}", state.snap_size)
-        } else {
-            "Snap: Off".to_string()
+        let snap_text = match state.snap_mode {
+            SnapMode::None => "Snap: Off".to_string(),
+            SnapMode::Grid => format!("Snap: {}", state.snap_size),
+            SnapMode::Vertex => "Snap: Vertex".to_string(),
         };
-        if ui.button(&snap_text).on_hover_text("Toggle grid snapping").clicked() {
-            state.snap_enabled = !state.snap_enabled;
+        let snap_button = ui
+            .button(&snap_text)
+            .on_hover_text("Toggle grid snapping (right-click for snap settings, hold Ctrl while moving to toggle vertex snapping)");
+        if snap_button.clicked() {
+            state.snap_mode = match state.snap_mode {
+                SnapMode::None => SnapMode::Grid,
+                _ => SnapMode::None,
+            };
         }
+        snap_button.context_menu(|ui| {
+            ui.horizontal(|ui| {
+                ui.label("Mode");
+                for mode in SnapMode::ALL {
+                    ui.selectable_value(&mut state.snap_mode, mode, mode.name());
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.label("Size");
+                ui.add(egui::DragValue::new(&mut state.snap_size).speed(0.05).range(0.01..=100.0));
+            });
+            ui.horizontal(|ui| {
+                ui.label("Origin");
+                for value in &mut state.snap_origin {
+                    ui.add(egui::DragValue::new(value).speed(0.05));
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.label("Rotation");
+                ui.add(egui::DragValue::new(&mut state.snap_rotation).speed(1.0).suffix("°").range(-180.0..=180.0));
+            });
+            if ui.button("Reset Grid").clicked() {
+                state.snap_origin = [0.0; 3];
+                state.snap_rotation = 0.0;
+                ui.close_menu();
+            }
+        });
+        ui.checkbox(&mut state.alignment_snap, "Align")
+            .on_hover_text("Snap to alignment guides while moving (hold Shift to bypass)");
+
+        ui.separator();
+
+        // Camera projection and axis-aligned views
+        let view_text = match self.camera.view_preset {
+            Some(preset) => preset.name(),
+            None => self.camera.projection.name(),
+        };
+        egui::ComboBox::from_id_salt("viewport_camera_view")
+            .selected_text(view_text)
+            .show_ui(ui, |ui| self.camera_view_menu(ui));
+
+        egui::ComboBox::from_id_salt("viewport_render_mode")
+            .selected_text(self.render_mode.name())
+            .show_ui(ui, |ui| {
+                for mode in RenderMode::ALL {
+                    ui.selectable_value(&mut self.render_mode, mode, mode.name());
+                }
+            });
 
         ui.separator();
 
         // View options
-        ui.checkbox(&mut self.show_grid, "Grid");
+        ui.checkbox(&mut self.show_grid, "Grid")
+            .on_hover_text("Show the ground grid, spaced by the snap size");
         ui.checkbox(&mut self.show_gizmos, "Gizmos");
         ui.checkbox(&mut self.show_stats, "Stats");
+        ui.checkbox(&mut self.show_relationships, "Links")
+            .on_hover_text("Draw lines to parents and referenced entities (right-click for scope)")
+            .context_menu(|ui| {
+                ui.checkbox(&mut self.relationships_selection_only, "Selection only");
+            });
+    }
+
+    /// Projection and view preset choices, shared by the toolbar and the View menu
+    pub fn camera_view_menu(&mut self, ui: &mut egui::Ui) {
+        for projection in [CameraProjection::Perspective, CameraProjection::Orthographic] {
+            let selected = self.camera.projection == projection && self.camera.view_preset.is_none();
+            if ui.selectable_label(selected, projection.name()).clicked() {
+                self.camera.set_projection(projection);
+                ui.close_menu();
+            }
+        }
+        ui.separator();
+        for preset in ViewPreset::ALL {
+            if ui.selectable_label(self.camera.view_preset == Some(preset), preset.name()).clicked() {
+                self.camera.set_view_preset(preset);
+                ui.close_menu();
+            }
+        }
     }
 
     fn draw_placeholder_grid(&self, painter: &egui::Painter, rect: egui::Rect) {
@@ -336,20 +601,182 @@ impl ViewportPanel {
         );
     }
 
-    fn draw_gizmo_overlay(&self, painter: &egui::Painter, rect: egui::Rect, state: &EditorState) {
-        // Get selected entity position for gizmo placement
-        let gizmo_center = if let Some(entity_id) = state.selection.primary() {
-            if let Some(entity) = state.scene.get(entity_id) {
-                // Project the entity's world position to screen space
-                self.project_to_screen(entity.transform.position, rect)
-            } else {
-                rect.center()
+    fn draw_animation_ghosts(&self, painter: &egui::Painter, rect: egui::Rect, state: &EditorState) {
+        let ghost_color = egui::Color32::from_rgba_unmultiplied(120, 180, 255, 90);
+        let link_color = egui::Color32::from_rgba_unmultiplied(120, 180, 255, 50);
+
+        for ghost in &state.animation_ghosts {
+            let Some(entity) = state.scene.get(&ghost.entity_id) else {
+                continue;
+            };
+
+            // Samples are relative to the parent, like the entity's transform
+            let (world_position, parent_scale) = match entity.parent {
+                Some(parent) => (
+                    state.scene.world_point(parent, ghost.transform.position),
+                    state.scene.world_transform(parent).map_or([1.0; 3], |world| world.scale),
+                ),
+                None => (Some(ghost.transform.position), [1.0; 3]),
+            };
+            let Some(world_position) = world_position else {
+                continue;
+            };
+            let ghost_pos = self.project_to_screen(world_position, rect);
+            if !rect.contains(ghost_pos) {
+                continue;
             }
-        } else {
-            rect.center()
+
+            // Connect the live entity to its reference pose
+            if let Some(entity_world) = state.scene.world_position(ghost.entity_id) {
+                let entity_pos = self.project_to_screen(entity_world, rect);
+                painter.line_segment([entity_pos, ghost_pos], egui::Stroke::new(1.0, link_color));
+            }
+
+            let scale = std::array::from_fn::<f32, 3, _>(|i| ghost.transform.scale[i] * parent_scale[i]);
+            let radius = 8.0 * scale.iter().copied().fold(0.0, f32::max).clamp(0.25, 4.0);
+            painter.circle(ghost_pos, radius, link_color, egui::Stroke::new(1.5, ghost_color));
+            painter.text(
+                ghost_pos + egui::vec2(radius + 4.0, 0.0),
+                egui::Align2::LEFT_CENTER,
+                &entity.name,
+                egui::FontId::proportional(11.0),
+                ghost_color,
+            );
+        }
+    }
+
+    fn draw_relationship_lines(&self, painter: &egui::Painter, rect: egui::Rect, state: &EditorState) {
+        if !self.show_relationships {
+            return;
+        }
+        let focus = self.relationships_selection_only.then_some(state.selection.entities.as_slice());
+
+        for line in crate::tools::collect_relationship_lines(&state.scene, focus) {
+            let from = self.project_to_screen(line.start, rect);
+            let to = self.project_to_screen(line.end, rect);
+            if !rect.contains(from) && !rect.contains(to) {
+                continue;
+            }
+            match line.kind {
+                RelationshipKind::Parent => {
+                    let stroke = egui::Stroke::new(1.0, egui::Color32::from_rgba_unmultiplied(200, 200, 200, 140));
+                    painter.line_segment([from, to], stroke);
+                    painter.circle_filled(to, 3.0, stroke.color);
+                }
+                RelationshipKind::Reference(_) => {
+                    let stroke = egui::Stroke::new(1.5, egui::Color32::from_rgb(255, 170, 60));
+                    painter.extend(egui::Shape::dashed_line(&[from, to], stroke, 5.0, 3.0));
+                    painter.circle_stroke(to, 4.0, stroke);
+                }
+            }
+        }
+    }
+
+    fn draw_alignment_guides(&self, painter: &egui::Painter, rect: egui::Rect, state: &EditorState) {
+        let Some(drag) = &self.gizmo_drag else {
+            return;
+        };
+        let Some(dragged) = state.scene.world_position(drag.primary_entity_id) else {
+            return;
+        };
+
+        let axis_colors = [
+            egui::Color32::from_rgb(255, 120, 200),
+            egui::Color32::from_rgb(120, 255, 200),
+            egui::Color32::from_rgb(120, 200, 255),
+        ];
+        let from = self.project_to_screen(dragged, rect);
+        for guide in &self.alignment_guides {
+            let to = self.project_to_screen(guide.target_position, rect);
+            let stroke = egui::Stroke::new(1.0, axis_colors[guide.axis]);
+            painter.extend(egui::Shape::dashed_line(&[from, to], stroke, 6.0, 4.0));
+            painter.circle_stroke(to, 4.0, stroke);
+        }
+
+        if let Some(target) = &self.vertex_snap_target {
+            let at = self.project_to_screen(target.position, rect);
+            painter.circle_stroke(at, 6.0, egui::Stroke::new(2.0, egui::Color32::from_rgb(255, 220, 80)));
+        }
+    }
+
+    /// Nearest vertex to `cursor` among the drag's vertex candidates
+    fn find_vertex_snap(&self, cursor: egui::Pos2, rect: egui::Rect) -> Option<VertexSnapTarget> {
+        let candidates = self.gizmo_drag.as_ref()?.vertex_candidates.as_ref()?;
+        crate::tools::nearest_vertex(
+            [cursor.x, cursor.y],
+            candidates.iter().copied(),
+            |position| self.project_point(position, rect).map(|p| [p.x, p.y]),
+            VERTEX_SNAP_RADIUS,
+        )
+    }
+
+    /// World vertices of the meshes of entities that don't move with the
+    /// selection. Entities whose mesh can't be loaded offer the corners of
+    /// their placeholder cube.
+    fn vertex_snap_candidates(&mut self, state: &EditorState) -> Vec<(EntityId, [f32; 3])> {
+        let root = crate::asset_paths::asset_root(state.project_manager.project_dir.as_deref());
+        let placeholder = crate::viewport_renderer::placeholder_corners();
+
+        let mut vertices = Vec::new();
+        for (id, data) in &state.scene.entities {
+            let moves_with_selection = state.selection.contains(id)
+                || state.scene.ancestors(*id).iter().any(|ancestor| state.selection.contains(ancestor));
+            if moves_with_selection || !state.scene.is_active_in_hierarchy(*id) {
+                continue;
+            }
+            let Some(mesh) = data.components.iter().find_map(|component| match component {
+                crate::components::Component::MeshRenderer(renderer) if !renderer.mesh.is_empty() => {
+                    Some(renderer.mesh.as_str())
+                }
+                _ => None,
+            }) else {
+                continue;
+            };
+
+            let local = self.mesh_vertices.positions(&root, mesh).unwrap_or(&placeholder);
+            vertices.extend(state.scene.world_points(*id, local).into_iter().map(|vertex| (*id, vertex)));
+        }
+        vertices
+    }
+
+    /// Find alignment guides for the dragged entity at `local_position`
+    /// and the world-space offset that snaps onto them
+    fn alignment_for_drag(
+        &self,
+        state: &EditorState,
+        drag: &GizmoDragState,
+        local_position: [f32; 3],
+    ) -> (Vec<AlignmentGuide>, [f32; 3]) {
+        let parent = state.scene.get(&drag.primary_entity_id).and_then(|e| e.parent);
+        let world = match parent {
+            Some(parent) => state.scene.world_point(parent, local_position).unwrap_or(local_position),
+            None => local_position,
         };
 
-        let size = 60.0;
+        let mut axes = [false; 3];
+        if let Some(index) = drag.axis.index() {
+            axes[index] = true;
+        }
+
+        // Only entities near the drag are worth testing
+        let guides = crate::tools::find_alignments(
+            world,
+            drag.alignment_candidates.iter().copied(),
+            axes,
+            ALIGNMENT_THRESHOLD * self.camera.distance,
+            self.camera.distance,
+        );
+
+        let offset = crate::tools::alignment_snap_offset(&guides);
+        (guides, offset)
+    }
+
+    fn draw_gizmo_overlay(&self, painter: &egui::Painter, rect: egui::Rect, state: &EditorState) {
+        let Some(geometry) = self.gizmo_geometry(rect, state) else {
+            return;
+        };
+        let gizmo_center = geometry.center;
+        let size = GIZMO_SIZE;
 
         // Base colors per axis
         let base_colors = [
@@ -365,37 +792,22 @@ impl ViewportPanel {
             egui::Color32::from_rgb(100, 200, 255), // Z highlighted
         ];
 
-        // Determine which axis to highlight
-        let get_color = |axis: GizmoAxis| {
+        let is_active = |axis: GizmoAxis| {
             let is_dragging = self.gizmo_drag.as_ref().map(|d| d.axis == axis).unwrap_or(false);
-            let is_hovered = self.hovered_axis == Some(axis);
-
-            if is_dragging || is_hovered {
-                match axis {
-                    GizmoAxis::X => highlight_colors[0],
-                    GizmoAxis::Y => highlight_colors[1],
-                    GizmoAxis::Z => highlight_colors[2],
-                }
-            } else {
-                match axis {
-                    GizmoAxis::X => base_colors[0],
-                    GizmoAxis::Y => base_colors[1],
-                    GizmoAxis::Z => base_colors[2],
-                }
-            }
+            is_dragging || self.hovered_axis == Some(axis)
         };
 
-        let get_stroke_width = |axis: GizmoAxis| {
-            let is_dragging = self.gizmo_drag.as_ref().map(|d| d.axis == axis).unwrap_or(false);
-            let is_hovered = self.hovered_axis == Some(axis);
-
-            if is_dragging || is_hovered {
-                4.0
-            } else {
-                2.0
-            }
+        // Determine which axis to highlight
+        let get_color = |axis: GizmoAxis| match (axis.index(), is_active(axis)) {
+            (Some(index), true) => highlight_colors[index],
+            (Some(index), false) => base_colors[index],
+            (None, true) => egui::Color32::from_rgb(255, 255, 140),
+            (None, false) => egui::Color32::from_gray(220),
         };
 
+        let get_stroke_width = |axis: GizmoAxis| if is_active(axis) { 4.0 } else { 2.0 };
+        let stroke = |axis: GizmoAxis| egui::Stroke::new(get_stroke_width(axis), get_color(axis));
+
         // Draw mode indicator
         let mode_label = match state.gizmo_mode {
             GizmoMode::Translate => "Move",
@@ -410,6 +822,47 @@ impl ViewportPanel {
             egui::Color32::WHITE,
         );
 
+        match state.gizmo_mode {
+            GizmoMode::Translate => self.draw_translate_handles(painter, gizmo_center, get_color, get_stroke_width),
+            GizmoMode::Rotate => {
+                for (axis, ring) in GizmoAxis::AXES.into_iter().zip(&geometry.rings) {
+                    painter.add(egui::Shape::closed_line(ring.clone(), stroke(axis)));
+                }
+                // Show the swept angle while dragging
+                if let Some(drag) = &self.gizmo_drag {
+                    painter.text(
+                        egui::pos2(gizmo_center.x, gizmo_center.y + size + 8.0),
+                        egui::Align2::CENTER_TOP,
+                        format!("{:.1}\u{b0}", self.rotation_degrees(drag, state)),
+                        egui::FontId::proportional(11.0),
+                        egui::Color32::WHITE,
+                    );
+                }
+            }
+            GizmoMode::Scale => {
+                let handle = egui::vec2(8.0, 8.0);
+                for (axis, end) in GizmoAxis::AXES.into_iter().zip(geometry.axis_ends) {
+                    painter.line_segment([gizmo_center, end], stroke(axis));
+                    painter.rect_filled(egui::Rect::from_center_size(end, handle), 0.0, get_color(axis));
+                }
+                painter.rect_filled(
+                    egui::Rect::from_center_size(gizmo_center, handle * 1.5),
+                    0.0,
+                    get_color(GizmoAxis::Uniform),
+                );
+            }
+        }
+    }
+
+    fn draw_translate_handles(
+        &self,
+        painter: &egui::Painter,
+        gizmo_center: egui::Pos2,
+        get_color: impl Fn(GizmoAxis) -> egui::Color32,
+        get_stroke_width: impl Fn(GizmoAxis) -> f32,
+    ) {
+        let size = GIZMO_SIZE;
+
         // X axis (right)
         painter.arrow(
             gizmo_center,
@@ -455,8 +908,15 @@ impl ViewportPanel {
         );
     }
 
-    /// Project a 3D world position to 2D screen position
+    /// Project a 3D world position to 2D screen position, or to the
+    /// viewport center if it is behind the camera
     fn project_to_screen(&self, world_pos: [f32; 3], rect: egui::Rect) -> egui::Pos2 {
+        self.project_point(world_pos, rect).unwrap_or(rect.center())
+    }
+
+    /// Project a 3D world position to 2D screen position, or `None` if it
+    /// is behind the camera
+    fn project_point(&self, world_pos: [f32; 3], rect: egui::Rect) -> Option<egui::Pos2> {
         // Simple projection using camera matrices
         let cam_pos = self.camera.position;
         let cam_forward = self.camera.get_forward();
@@ -473,60 +933,206 @@ impl ViewportPanel {
         // Project onto camera plane
         let depth = to_point[0] * cam_forward[0] + to_point[1] * cam_forward[1] + to_point[2] * cam_forward[2];
 
-        if depth <= 0.1 {
-            // Behind camera
-            return rect.center();
-        }
-
         let x = to_point[0] * cam_right[0] + to_point[1] * cam_right[1] + to_point[2] * cam_right[2];
         let y = to_point[0] * cam_up[0] + to_point[1] * cam_up[1] + to_point[2] * cam_up[2];
 
-        // Apply perspective
-        let fov_factor = (std::f32::consts::FRAC_PI_4 * 0.5).tan();
+        let fov_factor = (VIEWPORT_FOV * 0.5).tan();
         let aspect = rect.width() / rect.height().max(1.0);
 
+        // Orthographic views have no perspective divide and don't clip
+        // what lies behind the camera position
+        let depth = match self.camera.projection {
+            CameraProjection::Perspective if depth <= 0.1 => return None, // Behind camera
+            CameraProjection::Perspective => depth,
+            CameraProjection::Orthographic => self.camera.ortho_half_height(VIEWPORT_FOV) / fov_factor,
+        };
+
         let screen_x = (x / (depth * fov_factor * aspect)) * 0.5 + 0.5;
         let screen_y = (-y / (depth * fov_factor)) * 0.5 + 0.5;
 
-        egui::pos2(
+        Some(egui::pos2(
             rect.left() + screen_x * rect.width(),
             rect.top() + screen_y * rect.height(),
-        )
+        ))
     }
 
-    /// Get the gizmo center in screen space
-    fn get_gizmo_screen_center(&self, rect: egui::Rect, state: &EditorState) -> Option<egui::Pos2> {
-        state.selection.primary().and_then(|entity_id| {
-            state.scene.get(entity_id).map(|entity| {
-                self.project_to_screen(entity.transform.position, rect)
+    /// Entities whose projected world positions fall inside `marquee`.
+    /// Entities behind the camera are excluded.
+    fn entities_in_marquee(&self, state: &EditorState, rect: egui::Rect, marquee: egui::Rect) -> Vec<EntityId> {
+        state
+            .scene
+            .entities
+            .keys()
+            .filter(|id| {
+                state
+                    .scene
+                    .world_position(**id)
+                    .and_then(|position| self.project_point(position, rect))
+                    .is_some_and(|point| marquee.contains(point))
             })
-        })
+            .copied()
+            .collect()
     }
 
-    /// Check if a screen position is over a gizmo axis
-    fn hit_test_gizmo(&self, pos: egui::Pos2, gizmo_center: egui::Pos2) -> Option<GizmoAxis> {
-        let size = 60.0;
-        let hit_radius = 12.0;
+    fn draw_marquee(&self, painter: &egui::Painter) {
+        let Some((start, end)) = self.marquee else {
+            return;
+        };
+        let color = egui::Color32::from_rgb(100, 160, 255);
+        let marquee = egui::Rect::from_two_pos(start, end);
+        painter.rect_filled(marquee, 0.0, color.gamma_multiply(0.15));
+        painter.rect_stroke(marquee, 0.0, egui::Stroke::new(1.0, color));
+    }
 
-        // X axis (right)
-        let x_end = egui::pos2(gizmo_center.x + size, gizmo_center.y);
-        if Self::point_near_line(pos, gizmo_center, x_end, hit_radius) {
-            return Some(GizmoAxis::X);
+    /// Shared pivot of the selection for the current pivot mode
+    fn gizmo_pivot(state: &EditorState) -> Option<[f32; 3]> {
+        let selected = &state.selection.entities;
+        let origins: Vec<[f32; 3]> = selected.iter().filter_map(|id| state.scene.world_position(*id)).collect();
+        let bounds: Vec<([f32; 3], [f32; 3])> =
+            selected.iter().filter_map(|id| state.scene.world_bounds(*id)).collect();
+        let active = state.selection.primary().and_then(|id| state.scene.world_position(*id));
+        crate::tools::selection_pivot(state.pivot_mode, &origins, &bounds, active)
+    }
+
+    /// Gizmo axis under a screen position that the active axis constraint
+    /// lets the user grab
+    fn grabbable_axis(&self, pos: egui::Pos2, rect: egui::Rect, state: &EditorState) -> Option<GizmoAxis> {
+        self.hit_test_gizmo(pos, rect, state)
+            .filter(|axis| axis.index().map_or(true, |index| state.axis_constraint.allows(index)))
+    }
+
+    /// World directions of the gizmo axes: the world axes, or the primary
+    /// entity's local axes when editing in local space
+    fn gizmo_axes(state: &EditorState) -> [[f32; 3]; 3] {
+        let world_axes = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+        if state.use_world_space {
+            return world_axes;
         }
+        let Some(world) = state.selection.primary().and_then(|id| state.scene.world_transform(*id)) else {
+            return world_axes;
+        };
+        world_axes.map(|axis| world.rotate(axis))
+    }
 
-        // Y axis (up)
-        let y_end = egui::pos2(gizmo_center.x, gizmo_center.y - size);
-        if Self::point_near_line(pos, gizmo_center, y_end, hit_radius) {
-            return Some(GizmoAxis::Y);
+    /// World units covered by one screen point at `point`
+    fn world_per_point(&self, point: [f32; 3], rect: egui::Rect) -> f32 {
+        let depth = match self.camera.projection {
+            CameraProjection::Perspective => {
+                let forward = self.camera.get_forward();
+                let offset = [
+                    point[0] - self.camera.position[0],
+                    point[1] - self.camera.position[1],
+                    point[2] - self.camera.position[2],
+                ];
+                (offset[0] * forward[0] + offset[1] * forward[1] + offset[2] * forward[2]).max(0.1)
+            }
+            CameraProjection::Orthographic => self.camera.distance,
+        };
+        depth * 2.0 * (VIEWPORT_FOV * 0.5).tan() / rect.height().max(1.0)
+    }
+
+    /// Screen-space gizmo shape around the selection pivot
+    fn gizmo_geometry(&self, rect: egui::Rect, state: &EditorState) -> Option<GizmoGeometry> {
+        let pivot = Self::gizmo_pivot(state)?;
+        let center = self.project_point(pivot, rect)?;
+        let axes = Self::gizmo_axes(state);
+        let radius = GIZMO_SIZE * self.world_per_point(pivot, rect);
+        let at = |offset: [f32; 3]| {
+            let point = [pivot[0] + offset[0], pivot[1] + offset[1], pivot[2] + offset[2]];
+            self.project_point(point, rect).unwrap_or(center)
+        };
+
+        let axis_ends = axes.map(|axis| at(axis.map(|v| v * radius)));
+        let rings = [0, 1, 2].map(|index| {
+            let (u, v) = (axes[(index + 1) % 3], axes[(index + 2) % 3]);
+            (0..RING_SEGMENTS)
+                .map(|segment| {
+                    let (sin, cos) = (segment as f32 / RING_SEGMENTS as f32 * std::f32::consts::TAU).sin_cos();
+                    at(std::array::from_fn(|i| (u[i] * cos + v[i] * sin) * radius))
+                })
+                .collect()
+        });
+
+        Some(GizmoGeometry { center, axis_ends, rings })
+    }
+
+    /// Check if a screen position is over a gizmo handle for the current mode
+    fn hit_test_gizmo(&self, pos: egui::Pos2, rect: egui::Rect, state: &EditorState) -> Option<GizmoAxis> {
+        let geometry = self.gizmo_geometry(rect, state)?;
+        let gizmo_center = geometry.center;
+        let size = GIZMO_SIZE;
+        let hit_radius = 12.0;
+
+        match state.gizmo_mode {
+            GizmoMode::Translate => {
+                // X axis (right)
+                let x_end = egui::pos2(gizmo_center.x + size, gizmo_center.y);
+                if Self::point_near_line(pos, gizmo_center, x_end, hit_radius) {
+                    return Some(GizmoAxis::X);
+                }
+
+                // Y axis (up)
+                let y_end = egui::pos2(gizmo_center.x, gizmo_center.y - size);
+                if Self::point_near_line(pos, gizmo_center, y_end, hit_radius) {
+                    return Some(GizmoAxis::Y);
+                }
+
+                // Z axis (diagonal)
+                let z_end = egui::pos2(gizmo_center.x - size * 0.5, gizmo_center.y + size * 0.5);
+                if Self::point_near_line(pos, gizmo_center, z_end, hit_radius) {
+                    return Some(GizmoAxis::Z);
+                }
+
+                None
+            }
+            GizmoMode::Rotate => GizmoAxis::AXES.into_iter().zip(&geometry.rings).find_map(|(axis, ring)| {
+                let hit = (0..ring.len())
+                    .any(|i| Self::point_near_line(pos, ring[i], ring[(i + 1) % ring.len()], hit_radius * 0.75));
+                hit.then_some(axis)
+            }),
+            GizmoMode::Scale => {
+                if (pos - gizmo_center).length() < hit_radius {
+                    return Some(GizmoAxis::Uniform);
+                }
+                GizmoAxis::AXES
+                    .into_iter()
+                    .zip(geometry.axis_ends)
+                    .find(|(_, end)| Self::point_near_line(pos, gizmo_center, *end, hit_radius))
+                    .map(|(axis, _)| axis)
+            }
         }
+    }
 
-        // Z axis (diagonal)
-        let z_end = egui::pos2(gizmo_center.x - size * 0.5, gizmo_center.y + size * 0.5);
-        if Self::point_near_line(pos, gizmo_center, z_end, hit_radius) {
-            return Some(GizmoAxis::Z);
+    /// Rotation in degrees for a rotate drag: the angle swept around the
+    /// gizmo, signed by which way the axis faces the camera, and snapped
+    fn rotation_degrees(&self, drag: &GizmoDragState, state: &EditorState) -> f32 {
+        let forward = self.camera.get_forward();
+        let facing = drag.axis_dir[0] * forward[0] + drag.axis_dir[1] * forward[1] + drag.axis_dir[2] * forward[2];
+        // Screen angles grow clockwise; an axis pointing at the viewer
+        // rotates counter-clockwise for positive angles
+        let mut degrees = drag.swept_angle.to_degrees();
+        if facing < 0.0 {
+            degrees = -degrees;
         }
+        if state.snap_mode == SnapMode::Grid && state.rotation_snap > 0.0 {
+            degrees = (degrees / state.rotation_snap).round() * state.rotation_snap;
+        }
+        degrees
+    }
 
-        None
+    /// Scale factor for a scale drag, snapped to `scale_snap` steps
+    fn scale_factor(drag: &GizmoDragState, delta: egui::Vec2, state: &EditorState) -> f32 {
+        let handle_length_sq = drag.screen_axis.length_sq();
+        let mut factor = if drag.axis != GizmoAxis::Uniform && handle_length_sq > 1.0 {
+            // Dragging the handle end by its own length doubles the scale
+            1.0 + delta.dot(drag.screen_axis) / handle_length_sq
+        } else {
+            1.0 + (delta.x - delta.y) * 0.01
+        };
+        if state.snap_mode == SnapMode::Grid && state.scale_snap > 0.0 {
+            factor = (factor / state.scale_snap).round() * state.scale_snap;
+        }
+        factor.max(0.01)
     }
 
     /// Check if a point is near a line segment
@@ -546,112 +1152,191 @@ impl ViewportPanel {
         dist < threshold
     }
 
-    fn handle_input(&mut self, response: &egui::Response, state: &mut EditorState) {
-        // Only handle input if viewport is focused
-        if !self.has_focus {
+    /// Handle input over `rect`, the part of the response showing the scene
+    fn handle_input(&mut self, response: &egui::Response, rect: egui::Rect, state: &mut EditorState) {
+        self.hover = response.hover_pos().map(|pos| (pos, rect));
+
+        // Only handle input if viewport is focused (a marquee may be
+        // released outside it)
+        if !self.has_focus && self.marquee.is_none() {
             return;
         }
 
         let modifiers = response.ctx.input(|i| i.modifiers);
-        let rect = response.rect;
 
         // Update hovered gizmo axis
         if let Some(hover_pos) = response.hover_pos() {
-            if let Some(gizmo_center) = self.get_gizmo_screen_center(rect, state) {
-                self.hovered_axis = self.hit_test_gizmo(hover_pos, gizmo_center);
-            } else {
-                self.hovered_axis = None;
-            }
+            self.hovered_axis = self.grabbable_axis(hover_pos, rect, state);
         } else {
             self.hovered_axis = None;
         }
 
+        // Track the angle swept around the gizmo for rotation
+        if let (Some(drag_state), Some(current_pos)) = (self.gizmo_drag.as_mut(), response.hover_pos()) {
+            if state.gizmo_mode == GizmoMode::Rotate && response.dragged_by(egui::PointerButton::Primary) {
+                drag_state.sweep(current_pos);
+            }
+        }
+
+        // Ctrl (Cmd on macOS) toggles vertex snapping for the current drag
+        let snap_mode = state.snap_mode.toggled(modifiers.ctrl || modifiers.command);
+        self.vertex_snap_target = None;
+        if self.gizmo_drag.is_some()
+            && state.gizmo_mode == GizmoMode::Translate
+            && snap_mode == SnapMode::Vertex
+            && response.dragged_by(egui::PointerButton::Primary)
+        {
+            if let Some(cursor) = response.hover_pos() {
+                if self.gizmo_drag.as_ref().is_some_and(|drag| drag.vertex_candidates.is_none()) {
+                    let candidates = self.vertex_snap_candidates(state);
+                    if let Some(drag) = &mut self.gizmo_drag {
+                        drag.vertex_candidates = Some(candidates);
+                    }
+                }
+                self.vertex_snap_target = self.find_vertex_snap(cursor, rect);
+            }
+        }
+        let vertex_target = self.vertex_snap_target.map(|target| target.position);
+
         // Handle gizmo drag
         if let Some(drag_state) = &self.gizmo_drag {
             if response.dragged_by(egui::PointerButton::Primary) {
                 // Continue dragging
                 if let Some(current_pos) = response.hover_pos() {
                     let delta = current_pos - drag_state.start_mouse;
-                    let sensitivity = 0.02 * self.camera.distance;
 
-                    // Calculate transform delta based on gizmo mode
-                    let (pos_delta, rot_delta, scale_delta) = match state.gizmo_mode {
+                    match state.gizmo_mode {
                         GizmoMode::Translate => {
-                            let mut d = [0.0, 0.0, 0.0];
+                            let sensitivity = 0.02 * self.camera.distance;
+                            let mut pos_delta = [0.0, 0.0, 0.0];
                             match drag_state.axis {
-                                GizmoAxis::X => d[0] = delta.x * sensitivity,
-                                GizmoAxis::Y => d[1] = -delta.y * sensitivity,
-                                GizmoAxis::Z => d[2] = (-delta.x + delta.y) * sensitivity * 0.5,
+                                GizmoAxis::X => pos_delta[0] = delta.x * sensitivity,
+                                GizmoAxis::Y => pos_delta[1] = -delta.y * sensitivity,
+                                GizmoAxis::Z => pos_delta[2] = (-delta.x + delta.y) * sensitivity * 0.5,
+                                GizmoAxis::Uniform => {}
+                            }
+                            // Apply grid snapping if enabled: moves snap in grid steps on
+                            // the world grid, and onto grid points on an offset or rotated one
+                            if snap_mode == SnapMode::Grid {
+                                let grid = state.snap_grid();
+                                if grid.is_world_aligned() {
+                                    let snap = state.snap_size;
+                                    pos_delta[0] = (pos_delta[0] / snap).round() * snap;
+                                    pos_delta[1] = (pos_delta[1] / snap).round() * snap;
+                                    pos_delta[2] = (pos_delta[2] / snap).round() * snap;
+                                } else {
+                                    pos_delta = grid_snap_delta(state, drag_state, &grid, pos_delta);
+                                }
+                            }
+
+                            // Line up with nearby entities, snapping unless Shift is held
+                            self.alignment_guides.clear();
+                            let mut snap_offset = [0.0; 3];
+                            let primary_start = drag_state
+                                .start_transforms
+                                .iter()
+                                .find(|(id, _)| *id == drag_state.primary_entity_id);
+                            if let Some((_, start)) = primary_start {
+                                if let Some(target) = vertex_target {
+                                    // Put the pivot exactly on the vertex, on every axis
+                                    let parent = state.scene.get(&drag_state.primary_entity_id).and_then(|e| e.parent);
+                                    let local = state.scene.local_point(parent, target);
+                                    pos_delta = std::array::from_fn(|i| local[i] - start.position[i]);
+                                } else {
+                                    let position = [
+                                        start.position[0] + pos_delta[0],
+                                        start.position[1] + pos_delta[1],
+                                        start.position[2] + pos_delta[2],
+                                    ];
+                                    let (guides, offset) = self.alignment_for_drag(state, drag_state, position);
+                                    if state.alignment_snap && !modifiers.shift {
+                                        snap_offset = offset;
+                                    }
+                                    self.alignment_guides = guides;
+                                }
                             }
-                            // Apply grid snapping if enabled
-                            if state.snap_enabled {
-                                let snap = state.snap_size;
-                                d[0] = (d[0] / snap).round() * snap;
-                                d[1] = (d[1] / snap).round() * snap;
-                                d[2] = (d[2] / snap).round() * snap;
+
+                            // Vertex and alignment snaps stay within the axis constraint
+                            pos_delta = state.axis_constraint.apply(pos_delta);
+                            snap_offset = state.axis_constraint.apply(snap_offset);
+
+                            // Apply the move to ALL selected entities
+                            for (entity_id, start_transform) in &drag_state.start_transforms {
+                                let mut position: [f32; 3] =
+                                    std::array::from_fn(|i| start_transform.position[i] + pos_delta[i]);
+                                if snap_offset != [0.0; 3] {
+                                    // The snap is a world offset; each entity takes it
+                                    // through its own parent's rotation and scale
+                                    let parent = state.scene.get(entity_id).and_then(|e| e.parent);
+                                    let world = parent
+                                        .and_then(|parent| state.scene.world_point(parent, position))
+                                        .unwrap_or(position);
+                                    let target = std::array::from_fn(|i| world[i] + snap_offset[i]);
+                                    position = state.scene.local_point(parent, target);
+                                }
+                                if let Some(entity_data) = state.scene.get_mut(entity_id) {
+                                    entity_data.transform.position = position;
+                                }
                             }
-                            (d, [0.0, 0.0, 0.0], [0.0, 0.0, 0.0])
                         }
                         GizmoMode::Rotate => {
-                            let rotation_sensitivity = 0.5;
-                            let mut d = [0.0, 0.0, 0.0];
-                            match drag_state.axis {
-                                GizmoAxis::X => d[0] = delta.y * rotation_sensitivity,
-                                GizmoAxis::Y => d[1] = delta.x * rotation_sensitivity,
-                                GizmoAxis::Z => d[2] = (delta.x - delta.y) * rotation_sensitivity * 0.5,
-                            }
-                            // Apply rotation snapping if enabled (15 degree increments)
-                            if state.snap_enabled {
-                                let snap = state.rotation_snap;
-                                d[0] = (d[0] / snap).round() * snap;
-                                d[1] = (d[1] / snap).round() * snap;
-                                d[2] = (d[2] / snap).round() * snap;
+                            let degrees = self.rotation_degrees(drag_state, state);
+                            for (entity_id, parent, start_world) in &drag_state.start_world {
+                                let world = crate::tools::rotate_transform_about(
+                                    start_world,
+                                    state.pivot_mode.pivot_for(drag_state.pivot, start_world.position),
+                                    drag_state.axis_dir,
+                                    degrees,
+                                );
+                                let local = state.scene.local_transform(*parent, &world);
+                                if let Some(entity_data) = state.scene.get_mut(entity_id) {
+                                    entity_data.transform.position = local.position;
+                                    entity_data.transform.rotation = local.rotation;
+                                }
                             }
-                            ([0.0, 0.0, 0.0], d, [0.0, 0.0, 0.0])
                         }
                         GizmoMode::Scale => {
-                            let scale_delta_val = (delta.x - delta.y) * 0.01;
-                            let mut d = [0.0, 0.0, 0.0];
-                            match drag_state.axis {
-                                GizmoAxis::X => d[0] = scale_delta_val,
-                                GizmoAxis::Y => d[1] = scale_delta_val,
-                                GizmoAxis::Z => d[2] = scale_delta_val,
-                            }
-                            // Apply scale snapping if enabled
-                            if state.snap_enabled {
-                                let snap = state.scale_snap;
-                                d[0] = (d[0] / snap).round() * snap;
-                                d[1] = (d[1] / snap).round() * snap;
-                                d[2] = (d[2] / snap).round() * snap;
+                            let factor = Self::scale_factor(drag_state, delta, state);
+                            let index = drag_state.axis.index();
+                            let direction = index.map(|_| drag_state.axis_dir);
+                            for (entity_id, parent, start_world) in &drag_state.start_world {
+                                let Some((_, start_local)) =
+                                    drag_state.start_transforms.iter().find(|(id, _)| id == entity_id)
+                                else {
+                                    continue;
+                                };
+                                let scaled = crate::tools::scale_offset_about(
+                                    start_world.position,
+                                    state.pivot_mode.pivot_for(drag_state.pivot, start_world.position),
+                                    direction,
+                                    factor,
+                                );
+                                // Uniform scaling only spreads along the unconstrained axes
+                                let offset = state
+                                    .axis_constraint
+                                    .apply(std::array::from_fn(|i| scaled[i] - start_world.position[i]));
+                                let world_position = std::array::from_fn(|i| start_world.position[i] + offset[i]);
+                                let position = state.scene.local_point(*parent, world_position);
+                                // Scale is applied to the matching local axis, clamped to min 0.01
+                                let mut scale = start_local.scale;
+                                for (i, value) in scale.iter_mut().enumerate() {
+                                    if index.map_or(true, |index| index == i) && state.axis_constraint.allows(i) {
+                                        *value = (*value * factor).max(0.01);
+                                    }
+                                }
+                                if let Some(entity_data) = state.scene.get_mut(entity_id) {
+                                    entity_data.transform.position = position;
+                                    entity_data.transform.scale = scale;
+                                }
                             }
-                            ([0.0, 0.0, 0.0], [0.0, 0.0, 0.0], d)
-                        }
-                    };
-
-                    // Apply transform delta to ALL selected entities
-                    for (entity_id, start_transform) in &drag_state.start_transforms {
-                        if let Some(entity_data) = state.scene.get_mut(entity_id) {
-                            let mut new_transform = start_transform.clone();
-                            // Apply position delta
-                            new_transform.position[0] += pos_delta[0];
-                            new_transform.position[1] += pos_delta[1];
-                            new_transform.position[2] += pos_delta[2];
-                            // Apply rotation delta
-                            new_transform.rotation[0] += rot_delta[0];
-                            new_transform.rotation[1] += rot_delta[1];
-                            new_transform.rotation[2] += rot_delta[2];
-                            // Apply scale delta (additive, clamped to min 0.01)
-                            new_transform.scale[0] = (start_transform.scale[0] + scale_delta[0]).max(0.01);
-                            new_transform.scale[1] = (start_transform.scale[1] + scale_delta[1]).max(0.01);
-                            new_transform.scale[2] = (start_transform.scale[2] + scale_delta[2]).max(0.01);
-                            entity_data.transform = new_transform;
                         }
                     }
                 }
             } else {
-                // End drag - commit to undo history for all entities
+                // End drag - commit to undo history as a single step
                 let start_transforms = drag_state.start_transforms.clone();
                 self.gizmo_drag = None;
+                self.alignment_guides.clear();
 
                 let description = match state.gizmo_mode {
                     GizmoMode::Translate => if start_transforms.len() > 1 { "Move entities" } else { "Move entity" },
@@ -659,15 +1344,19 @@ impl ViewportPanel {
                     GizmoMode::Scale => if start_transforms.len() > 1 { "Scale entities" } else { "Scale entity" },
                 };
 
-                // Commit each entity's transform change to undo history
+                let mut entities = Vec::new();
+                let mut before = Vec::new();
+                let mut after = Vec::new();
                 for (entity_id, start_transform) in start_transforms {
                     if let Some(entity_data) = state.scene.get(&entity_id) {
-                        let new_transform = entity_data.transform.clone();
-                        if new_transform != start_transform {
-                            state.set_transform_with_before(entity_id, start_transform, new_transform, description);
+                        if entity_data.transform != start_transform {
+                            entities.push(entity_id);
+                            after.push(entity_data.transform.clone());
+                            before.push(start_transform);
                         }
                     }
                 }
+                state.set_transforms_bulk_with_before(&entities, &before, &after, description);
             }
             return; // Don't process other input while dragging gizmo
         }
@@ -675,31 +1364,103 @@ impl ViewportPanel {
         // Start gizmo drag
         if response.drag_started_by(egui::PointerButton::Primary) && !modifiers.alt {
             if let Some(start_pos) = response.hover_pos() {
-                if let Some(gizmo_center) = self.get_gizmo_screen_center(rect, state) {
-                    if let Some(axis) = self.hit_test_gizmo(start_pos, gizmo_center) {
-                        if let Some(primary_id) = state.selection.primary().copied() {
-                            // Collect starting transforms for ALL selected entities
-                            let start_transforms: Vec<_> = state.selection.entities.iter()
-                                .filter_map(|id| {
-                                    state.scene.get(id).map(|e| (*id, e.transform.clone()))
-                                })
+                if let Some(axis) = self.grabbable_axis(start_pos, rect, state) {
+                    if let Some(primary_id) = state.selection.primary().copied() {
+                        // Collect starting transforms for ALL selected entities
+                        let start_transforms: Vec<_> = state.selection.entities.iter()
+                            .filter_map(|id| {
+                                state.scene.get(id).map(|e| (*id, e.transform.clone()))
+                            })
+                            .collect();
+
+                        // Rotation and scale move children with their selected parent
+                        let start_world = start_transforms
+                            .iter()
+                            .filter(|(id, _)| !has_selected_ancestor(state, *id))
+                            .filter_map(|(id, _)| {
+                                let parent = state.scene.get(id)?.parent;
+                                Some((*id, parent, state.scene.world_transform(*id)?))
+                            })
+                            .collect();
+
+                        let pivot = Self::gizmo_pivot(state).unwrap_or_default();
+                        let axes = Self::gizmo_axes(state);
+                        let geometry = self.gizmo_geometry(rect, state);
+                        let screen_center = geometry.as_ref().map_or(start_pos, |g| g.center);
+                        let screen_axis = match (&geometry, axis.index()) {
+                            (Some(geometry), Some(index)) => geometry.axis_ends[index] - screen_center,
+                            _ => egui::Vec2::ZERO,
+                        };
+
+                        if !start_transforms.is_empty() {
+                            // Positions of everything that doesn't move with the selection
+                            let alignment_candidates = state
+                                .scene
+                                .entities
+                                .keys()
+                                .filter(|id| !state.selection.contains(id) && !has_selected_ancestor(state, **id))
+                                .filter_map(|id| Some((*id, state.scene.world_position(*id)?)))
                                 .collect();
-
-                            if !start_transforms.is_empty() {
-                                self.gizmo_drag = Some(GizmoDragState {
-                                    axis,
-                                    start_transforms,
-                                    start_mouse: start_pos,
-                                    primary_entity_id: primary_id,
-                                });
-                                tracing::debug!("Started gizmo drag on {:?} axis ({} entities)", axis, state.selection.len());
-                                return;
-                            }
+                            self.gizmo_drag = Some(GizmoDragState {
+                                axis,
+                                start_transforms,
+                                start_mouse: start_pos,
+                                primary_entity_id: primary_id,
+                                start_world,
+                                pivot,
+                                axis_dir: axis.index().map_or([0.0; 3], |index| axes[index]),
+                                screen_center,
+                                screen_axis,
+                                swept_angle: 0.0,
+                                last_mouse: start_pos,
+                                alignment_candidates,
+                                vertex_candidates: None,
+                            });
+                            tracing::debug!("Started gizmo drag on {:?} axis ({} entities)", axis, state.selection.len());
+                            return;
                         }
                     }
                 }
             }
         }
+        // Left-drag from empty space: marquee selection
+        if let Some((start, _)) = self.marquee {
+            if response.drag_stopped() {
+                self.marquee = None;
+                let end = response.interact_pointer_pos().or_else(|| response.hover_pos()).unwrap_or(start);
+                let hits = self.entities_in_marquee(state, rect, egui::Rect::from_two_pos(start, end));
+
+                state.select_mode = if modifiers.shift {
+                    SelectMode::Add
+                } else if modifiers.ctrl || modifiers.command {
+                    SelectMode::Toggle
+                } else {
+                    SelectMode::Set
+                };
+                state.select(&hits);
+                state.select_mode = SelectMode::Set;
+                tracing::debug!("Marquee selected {} entities", hits.len());
+            } else if response.dragged_by(egui::PointerButton::Primary) {
+                if let Some(current) = response.interact_pointer_pos() {
+                    self.marquee = Some((start, current));
+                }
+            } else {
+                self.marquee = None;
+            }
+            return;
+        }
+        if response.drag_started_by(egui::PointerButton::Primary)
+            && !modifiers.alt
+            && !state.entity_picker.is_active()
+        {
+            let origin = response.ctx.input(|i| i.pointer.press_origin());
+            if let Some(origin) = origin {
+                if self.pick_at(origin, rect, state).is_none() {
+                    self.marquee = Some((origin, origin));
+                    return;
+                }
+            }
+        }
 
         // Right-click drag: Orbit camera
         if response.dragged_by(egui::PointerButton::Secondary) {
@@ -733,19 +1494,23 @@ impl ViewportPanel {
         if response.clicked() && !modifiers.alt {
             if let Some(click_pos) = response.hover_pos() {
                 // Check if clicked on gizmo first
-                if let Some(gizmo_center) = self.get_gizmo_screen_center(rect, state) {
-                    if self.hit_test_gizmo(click_pos, gizmo_center).is_some() {
-                        // Clicked on gizmo, don't change selection
-                        return;
-                    }
+                if self.hit_test_gizmo(click_pos, rect, state).is_some() {
+                    // Clicked on gizmo, don't change selection
+                    return;
                 }
 
-                // Convert click position to viewport-relative coordinates
-                let normalized_x = (click_pos.x - rect.left()) / rect.width();
-                let normalized_y = (click_pos.y - rect.top()) / rect.height();
+                // Find the clicked entity
+                let picked = self.pick_at(click_pos, rect, state);
 
-                // Raycast to find clicked entity
-                if let Some(entity_id) = self.raycast_pick(normalized_x, normalized_y, state) {
+                // Eyedropper picks take the click instead of selection
+                if state.entity_picker.is_active() {
+                    if let Some(entity_id) = picked {
+                        state.complete_entity_pick(entity_id);
+                    }
+                    return;
+                }
+
+                if let Some(entity_id) = picked {
                     // Determine select mode based on modifiers
                     if modifiers.shift {
                         state.select_mode = SelectMode::Add;
@@ -769,6 +1534,39 @@ impl ViewportPanel {
         }
     }
 
+    /// Position of a click or the start of a primary drag this frame, the
+    /// points [`ViewportPanel::handle_input`] picks at
+    fn click_position(response: &egui::Response) -> Option<egui::Pos2> {
+        if response.clicked() {
+            response.hover_pos()
+        } else if response.drag_started_by(egui::PointerButton::Primary) {
+            response.ctx.input(|i| i.pointer.press_origin())
+        } else {
+            None
+        }
+    }
+
+    /// Where a subtree imported now would go: `None` when the pointer isn't
+    /// over the viewport, otherwise the entity under it to parent to
+    pub fn drop_target(&self, state: &EditorState) -> Option<Option<EntityId>> {
+        let (pos, rect) = self.hover?;
+        Some(self.pick_at(pos, rect, state))
+    }
+
+    /// Entity under `pos`. Uses the renderer's ID pass result when one was
+    /// resolved for `pos` this frame, otherwise falls back to raycasting.
+    fn pick_at(&self, pos: egui::Pos2, rect: egui::Rect, state: &EditorState) -> Option<EntityId> {
+        if let Some((picked_pos, picked)) = self.gpu_pick {
+            if picked_pos == pos {
+                return picked;
+            }
+        }
+
+        let normalized_x = (pos.x - rect.left()) / rect.width();
+        let normalized_y = (pos.y - rect.top()) / rect.height();
+        self.raycast_pick(normalized_x, normalized_y, state)
+    }
+
     /// Simple raycast picking - returns the entity closest to the camera that was clicked
     fn raycast_pick(&self, normalized_x: f32, normalized_y: f32, state: &EditorState) -> Option<EntityId> {
         // Convert normalized screen coordinates to clip space (-1 to 1)
@@ -782,24 +1580,36 @@ impl ViewportPanel {
         let up = self.camera.get_up();
 
         // Approximate FOV and aspect ratio
-        let fov_rad = std::f32::consts::FRAC_PI_4; // 45 degrees
         let aspect = self.viewport_size[0] / self.viewport_size[1].max(1.0);
 
-        let half_height = (fov_rad * 0.5).tan();
+        let half_height = (VIEWPORT_FOV * 0.5).tan();
         let half_width = half_height * aspect;
 
-        // Ray direction in world space
-        let ray_dir = [
-            forward[0] + right[0] * clip_x * half_width + up[0] * clip_y * half_height,
-            forward[1] + right[1] * clip_x * half_width + up[1] * clip_y * half_height,
-            forward[2] + right[2] * clip_x * half_width + up[2] * clip_y * half_height,
-        ];
-
-        // Normalize ray direction
-        let ray_len = (ray_dir[0] * ray_dir[0] + ray_dir[1] * ray_dir[1] + ray_dir[2] * ray_dir[2]).sqrt();
-        let ray_dir = [ray_dir[0] / ray_len, ray_dir[1] / ray_len, ray_dir[2] / ray_len];
-
-        let ray_origin = self.camera.position;
+        let (ray_origin, ray_dir) = match self.camera.projection {
+            CameraProjection::Perspective => {
+                // Ray direction in world space
+                let ray_dir = [
+                    forward[0] + right[0] * clip_x * half_width + up[0] * clip_y * half_height,
+                    forward[1] + right[1] * clip_x * half_width + up[1] * clip_y * half_height,
+                    forward[2] + right[2] * clip_x * half_width + up[2] * clip_y * half_height,
+                ];
+
+                // Normalize ray direction
+                let ray_len = (ray_dir[0] * ray_dir[0] + ray_dir[1] * ray_dir[1] + ray_dir[2] * ray_dir[2]).sqrt();
+                let ray_dir = [ray_dir[0] / ray_len, ray_dir[1] / ray_len, ray_dir[2] / ray_len];
+                (self.camera.position, ray_dir)
+            }
+            CameraProjection::Orthographic => {
+                // Parallel rays, starting as far behind the camera as the
+                // renderer draws
+                let scale = self.camera.distance;
+                let origin = std::array::from_fn(|i| {
+                    self.camera.position[i] + (right[i] * clip_x * half_width + up[i] * clip_y * half_height) * scale
+                        - forward[i] * VIEWPORT_FAR
+                });
+                (origin, forward)
+            }
+        };
 
         // Find closest entity hit by the ray (using sphere intersection)
         let mut closest_entity: Option<EntityId> = None;
@@ -809,6 +1619,9 @@ impl ViewportPanel {
         let pick_radius = 1.0_f32;
 
         for (entity_id, entity_data) in state.scene.entities.iter() {
+            if !state.scene.is_active_in_hierarchy(*entity_id) {
+                continue;
+            }
             let sphere_center = entity_data.transform.position;
 
             // Ray-sphere intersection
@@ -836,53 +1649,34 @@ impl ViewportPanel {
         closest_entity
     }
 
-    /// Focus the camera on the current selection
+    /// Frame the selection's bounds, or the whole scene when nothing is
+    /// selected
     pub fn focus_on_selection(&mut self, state: &EditorState) {
-        if state.selection.is_empty() {
-            // Focus on origin if nothing selected
-            self.camera.focus([0.0, 0.0, 0.0], Some(10.0));
-            return;
-        }
-
-        // Calculate bounding box center of selected entities
-        let mut min = [f32::MAX, f32::MAX, f32::MAX];
-        let mut max = [f32::MIN, f32::MIN, f32::MIN];
-        let mut count = 0;
-
-        for entity_id in state.selection.entities.iter() {
-            if let Some(entity) = state.scene.get(entity_id) {
-                let pos = entity.transform.position;
-                for i in 0..3 {
-                    min[i] = min[i].min(pos[i]);
-                    max[i] = max[i].max(pos[i]);
-                }
-                count += 1;
-            }
-        }
+        let ids: Vec<EntityId> = if state.selection.is_empty() {
+            state.scene.entities.keys().filter(|id| state.scene.is_active_in_hierarchy(**id)).copied().collect()
+        } else {
+            state.selection.entities.clone()
+        };
 
-        if count == 0 {
+        let bounds = ids.iter().filter_map(|id| state.scene.world_bounds(*id)).reduce(|a, b| {
+            (std::array::from_fn(|i| a.0[i].min(b.0[i])), std::array::from_fn(|i| a.1[i].max(b.1[i])))
+        });
+        let Some((min, max)) = bounds else {
+            // Nothing to frame
             self.camera.focus([0.0, 0.0, 0.0], Some(10.0));
             return;
-        }
-
-        // Calculate center and appropriate distance
-        let center = [
-            (min[0] + max[0]) * 0.5,
-            (min[1] + max[1]) * 0.5,
-            (min[2] + max[2]) * 0.5,
-        ];
-
-        // Calculate distance based on bounding box size
-        let size = [
-            (max[0] - min[0]).abs(),
-            (max[1] - min[1]).abs(),
-            (max[2] - min[2]).abs(),
-        ];
-        let max_size = size[0].max(size[1]).max(size[2]);
-        let distance = (max_size * 2.0).max(5.0); // Ensure minimum distance
+        };
 
-        self.camera.focus(center, Some(distance));
-        tracing::debug!("Focused on selection: center={:?}, distance={}", center, distance);
+        let [width, height] = self.viewport_size;
+        let aspect = if width > 0.0 && height > 0.0 { width / height } else { 1.0 };
+        self.camera.frame_bounds(min, max, VIEWPORT_FOV, aspect);
+        tracing::debug!(
+            "Framed bounds {:?}..{:?}: target={:?}, distance={}",
+            min,
+            max,
+            self.camera.target,
+            self.camera.distance
+        );
     }
 }
 
@@ -891,3 +1685,52 @@ impl Default for ViewportPanel {
         Self::new()
     }
 }
+
+/// Whether any ancestor of `id` is selected too
+fn has_selected_ancestor(state: &EditorState, id: EntityId) -> bool {
+    let mut current = state.scene.get(&id).and_then(|e| e.parent);
+    // Depth guard against malformed parent cycles
+    for _ in 0..256 {
+        let Some(parent) = current else {
+            return false;
+        };
+        if state.selection.contains(&parent) {
+            return true;
+        }
+        current = state.scene.get(&parent).and_then(|e| e.parent);
+    }
+    false
+}
+
+/// Local-space translation that moves the primary dragged entity from its
+/// start by about `delta`, along the same axis, onto a line of `grid`
+fn grid_snap_delta(
+    state: &EditorState,
+    drag: &GizmoDragState,
+    grid: &crate::tools::SnapGrid,
+    delta: [f32; 3],
+) -> [f32; 3] {
+    let Some((_, start)) = drag.start_transforms.iter().find(|(id, _)| *id == drag.primary_entity_id) else {
+        return delta;
+    };
+    let local = [
+        start.position[0] + delta[0],
+        start.position[1] + delta[1],
+        start.position[2] + delta[2],
+    ];
+
+    let parent = state.scene.get(&drag.primary_entity_id).and_then(|e| e.parent);
+    let to_world = |point: [f32; 3]| match parent {
+        Some(parent) => state.scene.world_point(parent, point).unwrap_or(point),
+        None => point,
+    };
+    let world_start = to_world(start.position);
+    let world_end = to_world(local);
+    let offset = std::array::from_fn(|i| world_end[i] - world_start[i]);
+    let snapped = state.scene.local_point(parent, grid.snap_along(world_start, offset));
+    [
+        snapped[0] - start.position[0],
+        snapped[1] - start.position[1],
+        snapped[2] - start.position[2],
+    ]
+}
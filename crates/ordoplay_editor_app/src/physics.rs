@@ -6,14 +6,17 @@
 //! - Collision detection (sphere-sphere, box-box, sphere-box)
 //! - Collision response with friction and bounciness
 //! - Constraint solving (position and rotation locks)
+//! - Raycasts against colliders for editor tools and gameplay graphs;
+//!   edit-time queries also hit a box proxy for each mesh collider
+//! - Trigger enter/stay/exit events
 
 
 use crate::components::{
-    BoxColliderComponent, CapsuleColliderComponent, Component,
+    BoxColliderComponent, CapsuleColliderComponent, Component, MeshColliderComponent,
     PhysicsMaterialComponent, RigidbodyComponent, RigidbodyType, SphereColliderComponent,
 };
-use crate::state::{EntityId, SceneData, Transform};
-use std::collections::HashMap;
+use crate::state::{EntityId, PhysicsDebugSettings, SceneData, Transform};
+use std::collections::{HashMap, HashSet};
 
 /// 3D Vector operations
 #[derive(Debug, Clone, Copy, Default)]
@@ -304,6 +307,33 @@ impl PhysicsCollider {
             bounciness,
         }
     }
+
+    /// Mesh assets aren't loaded here, so a mesh collider is the box of
+    /// its component's [`Component::local_bounds`]. The proxy is only added
+    /// to edit-time worlds (see [`PhysicsWorld::add_mesh_proxies`]) so it
+    /// never takes part in the play-mode simulation.
+    pub fn from_mesh(
+        entity_id: EntityId,
+        mc: &MeshColliderComponent,
+        (min, max): ([f32; 3], [f32; 3]),
+        material: Option<&PhysicsMaterialComponent>,
+    ) -> Self {
+        let (friction, bounciness) = material
+            .map(|m| (m.dynamic_friction, m.bounciness))
+            .unwrap_or((0.6, 0.0));
+
+        Self {
+            entity_id,
+            shape: ColliderShape::Box {
+                size: Vec3::from_array(std::array::from_fn(|i| max[i] - min[i])),
+                center: Vec3::from_array(std::array::from_fn(|i| (min[i] + max[i]) * 0.5)),
+            },
+            is_trigger: mc.is_trigger,
+            layer: mc.layer,
+            friction,
+            bounciness,
+        }
+    }
 }
 
 /// Contact point from collision detection
@@ -326,6 +356,29 @@ pub struct Contact {
     pub bounciness: f32,
 }
 
+/// Stage of an overlap between a trigger and another collider
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriggerPhase {
+    /// The overlap started this step
+    Enter,
+    /// The overlap continued from the previous step
+    Stay,
+    /// The overlap ended this step
+    Exit,
+}
+
+/// Overlap event between a trigger and another collider
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TriggerEvent {
+    /// Entity owning the trigger collider. When both colliders are
+    /// triggers, the pair is reported once.
+    pub entity: EntityId,
+    /// Entity overlapping the trigger
+    pub other: EntityId,
+    /// Stage of the overlap
+    pub phase: TriggerPhase,
+}
+
 /// Collision layer mask configuration
 #[allow(dead_code)] // Intentionally kept for API completeness
 #[derive(Debug, Clone)]
@@ -369,6 +422,7 @@ impl CollisionLayers {
 
 /// Physics world managing the simulation
 #[allow(dead_code)] // Intentionally kept for API completeness
+#[derive(Clone)]
 pub struct PhysicsWorld {
     /// Gravity vector
     pub gravity: Vec3,
@@ -382,12 +436,12 @@ pub struct PhysicsWorld {
     pub collision_layers: CollisionLayers,
     /// Current contacts
     pub contacts: Vec<Contact>,
-    /// Trigger enter events this frame
-    pub trigger_enters: Vec<(EntityId, EntityId)>,
-    /// Trigger exit events this frame
-    pub trigger_exits: Vec<(EntityId, EntityId)>,
-    /// Currently overlapping triggers
-    active_triggers: std::collections::HashSet<(EntityId, EntityId)>,
+    /// Trigger events since the last drain
+    trigger_events: Vec<TriggerEvent>,
+    /// Trigger overlaps found by the last step, as (trigger, other)
+    active_triggers: HashSet<(EntityId, EntityId)>,
+    /// World positions of colliders without a rigidbody
+    static_positions: HashMap<EntityId, Vec3>,
 }
 
 impl Default for PhysicsWorld {
@@ -406,9 +460,9 @@ impl PhysicsWorld {
             colliders: HashMap::new(),
             collision_layers: CollisionLayers::default(),
             contacts: Vec::new(),
-            trigger_enters: Vec::new(),
-            trigger_exits: Vec::new(),
-            active_triggers: std::collections::HashSet::new(),
+            trigger_events: Vec::new(),
+            active_triggers: HashSet::new(),
+            static_positions: HashMap::new(),
         }
     }
 
@@ -417,6 +471,52 @@ impl PhysicsWorld {
         self.clear();
         self.gravity = Vec3::from_array(gravity);
         self.load_entities(scene);
+        self.log_initialized();
+    }
+
+    /// World holding the scene's colliders and bodies as they are now, for
+    /// edit-time queries and debug drawing. Unlike the `initialize_*`
+    /// methods this is quiet, so it can be rebuilt every frame.
+    pub fn snapshot(scene: &SceneData) -> Self {
+        let mut world = Self::new();
+        world.load_entities(scene);
+        world.add_mesh_proxies(scene);
+        world
+    }
+
+    /// Add box proxies for the scene's mesh colliders so edit-time raycasts
+    /// and debug drawing can see them. The proxy is a rough stand-in for the
+    /// mesh, so it is left out of worlds that simulate play mode.
+    pub fn add_mesh_proxies(&mut self, scene: &SceneData) {
+        for (entity_id, entity_data) in scene.entities.iter() {
+            let physics_material = entity_data.components.iter().find_map(|c| {
+                if let Component::PhysicsMaterial(pm) = c {
+                    Some(pm)
+                } else {
+                    None
+                }
+            });
+
+            let proxies: Vec<_> = entity_data
+                .components
+                .iter()
+                .filter_map(|component| match component {
+                    Component::MeshCollider(mc) => component
+                        .local_bounds()
+                        .map(|bounds| PhysicsCollider::from_mesh(*entity_id, mc, bounds, physics_material)),
+                    _ => None,
+                })
+                .collect();
+            if proxies.is_empty() {
+                continue;
+            }
+
+            if !self.bodies.contains_key(entity_id) {
+                let position = scene.world_position(*entity_id).unwrap_or(entity_data.transform.position);
+                self.static_positions.insert(*entity_id, Vec3::from_array(position));
+            }
+            self.colliders.entry(*entity_id).or_default().extend(proxies);
+        }
     }
 
     /// Initialize the physics world with collision layer settings
@@ -436,6 +536,15 @@ impl PhysicsWorld {
         };
 
         self.load_entities(scene);
+        self.log_initialized();
+    }
+
+    fn log_initialized(&self) {
+        tracing::info!(
+            "Physics world initialized: {} bodies, {} colliders",
+            self.bodies.len(),
+            self.colliders.len()
+        );
     }
 
     /// Load physics entities from scene data
@@ -484,15 +593,13 @@ impl PhysicsWorld {
             }
 
             if !entity_colliders.is_empty() {
+                if rigidbody.is_none() {
+                    let position = scene.world_position(*entity_id).unwrap_or(entity_data.transform.position);
+                    self.static_positions.insert(*entity_id, Vec3::from_array(position));
+                }
                 self.colliders.insert(*entity_id, entity_colliders);
             }
         }
-
-        tracing::info!(
-            "Physics world initialized: {} bodies, {} colliders",
-            self.bodies.len(),
-            self.colliders.len()
-        );
     }
 
     /// Clear all physics state
@@ -500,17 +607,24 @@ impl PhysicsWorld {
         self.bodies.clear();
         self.colliders.clear();
         self.contacts.clear();
-        self.trigger_enters.clear();
-        self.trigger_exits.clear();
+        self.trigger_events.clear();
         self.active_triggers.clear();
+        self.static_positions.clear();
+    }
+
+    /// Current position of an entity's colliders
+    fn collider_position(&self, entity_id: &EntityId) -> Vec3 {
+        self.bodies
+            .get(entity_id)
+            .map(|b| b.position)
+            .or_else(|| self.static_positions.get(entity_id).copied())
+            .unwrap_or_default()
     }
 
     /// Step the physics simulation
     pub fn step(&mut self, dt: f32) {
         // Clear per-frame data
         self.contacts.clear();
-        self.trigger_enters.clear();
-        self.trigger_exits.clear();
 
         // Apply gravity and integrate forces
         self.integrate_forces(dt);
@@ -607,6 +721,7 @@ impl PhysicsWorld {
 
     fn detect_collisions(&mut self) {
         let entity_ids: Vec<EntityId> = self.colliders.keys().copied().collect();
+        let mut overlapping = HashSet::new();
 
         for i in 0..entity_ids.len() {
             for j in (i + 1)..entity_ids.len() {
@@ -616,8 +731,8 @@ impl PhysicsWorld {
                 let colliders_a = self.colliders.get(&id_a).unwrap();
                 let colliders_b = self.colliders.get(&id_b).unwrap();
 
-                let pos_a = self.bodies.get(&id_a).map(|b| b.position).unwrap_or_default();
-                let pos_b = self.bodies.get(&id_b).map(|b| b.position).unwrap_or_default();
+                let pos_a = self.collider_position(&id_a);
+                let pos_b = self.collider_position(&id_b);
 
                 for col_a in colliders_a {
                     for col_b in colliders_b {
@@ -630,11 +745,10 @@ impl PhysicsWorld {
                         if let Some(contact) = self.test_collision(col_a, pos_a, col_b, pos_b) {
                             // Handle triggers
                             if col_a.is_trigger || col_b.is_trigger {
-                                let pair = if id_a.0 < id_b.0 { (id_a, id_b) } else { (id_b, id_a) };
-                                if !self.active_triggers.contains(&pair) {
-                                    self.trigger_enters.push(pair);
-                                    self.active_triggers.insert(pair);
-                                }
+                                // Report from the trigger's side; the lower ID
+                                // when both are triggers
+                                let a_reports = col_a.is_trigger && (!col_b.is_trigger || id_a.0 < id_b.0);
+                                overlapping.insert(if a_reports { (id_a, id_b) } else { (id_b, id_a) });
                             } else {
                                 self.contacts.push(contact);
                             }
@@ -643,6 +757,30 @@ impl PhysicsWorld {
                 }
             }
         }
+
+        self.update_trigger_events(overlapping);
+    }
+
+    /// Compare this step's trigger overlaps with the previous step's
+    fn update_trigger_events(&mut self, overlapping: HashSet<(EntityId, EntityId)>) {
+        let event = |(entity, other): (EntityId, EntityId), phase| TriggerEvent { entity, other, phase };
+        for &pair in &overlapping {
+            let phase = if self.active_triggers.contains(&pair) {
+                TriggerPhase::Stay
+            } else {
+                TriggerPhase::Enter
+            };
+            self.trigger_events.push(event(pair, phase));
+        }
+        for &pair in self.active_triggers.difference(&overlapping) {
+            self.trigger_events.push(event(pair, TriggerPhase::Exit));
+        }
+        self.active_triggers = overlapping;
+    }
+
+    /// Take the trigger events queued by every step since the last call
+    pub fn drain_trigger_events(&mut self) -> Vec<TriggerEvent> {
+        std::mem::take(&mut self.trigger_events)
     }
 
     fn test_collision(
@@ -908,19 +1046,123 @@ impl PhysicsWorld {
         }
     }
 
+    /// Cast a ray and return the closest non-trigger collider it hits
+    /// within `max_distance`
+    pub fn raycast(&self, origin: [f32; 3], direction: [f32; 3], max_distance: f32) -> Option<RaycastHit> {
+        self.raycast_filtered(origin, direction, max_distance, &RaycastFilter::default())
+    }
+
+    /// Cast a ray and return the closest collider accepted by `filter`
+    pub fn raycast_filtered(
+        &self,
+        origin: [f32; 3],
+        direction: [f32; 3],
+        max_distance: f32,
+        filter: &RaycastFilter,
+    ) -> Option<RaycastHit> {
+        let mut closest: Option<RaycastHit> = None;
+        self.for_each_ray_hit(origin, direction, max_distance, filter, |hit| {
+            if !closest.is_some_and(|c| c.distance <= hit.distance) {
+                closest = Some(hit);
+            }
+        });
+        closest
+    }
+
+    /// Cast a ray and return every non-trigger collider it hits within
+    /// `max_distance`, nearest first
+    pub fn raycast_all(&self, origin: [f32; 3], direction: [f32; 3], max_distance: f32) -> Vec<RaycastHit> {
+        self.raycast_all_filtered(origin, direction, max_distance, &RaycastFilter::default())
+    }
+
+    /// Cast a ray and return every collider accepted by `filter`, nearest
+    /// first. An entity with several colliders can appear more than once.
+    pub fn raycast_all_filtered(
+        &self,
+        origin: [f32; 3],
+        direction: [f32; 3],
+        max_distance: f32,
+        filter: &RaycastFilter,
+    ) -> Vec<RaycastHit> {
+        let mut hits = Vec::new();
+        self.for_each_ray_hit(origin, direction, max_distance, filter, |hit| hits.push(hit));
+        hits.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+        hits
+    }
+
+    fn for_each_ray_hit(
+        &self,
+        origin: [f32; 3],
+        direction: [f32; 3],
+        max_distance: f32,
+        filter: &RaycastFilter,
+        mut on_hit: impl FnMut(RaycastHit),
+    ) {
+        let direction = Vec3::from_array(direction);
+        if direction.length_squared() < 1e-8 || max_distance <= 0.0 {
+            return;
+        }
+        let ray = Ray {
+            origin: Vec3::from_array(origin),
+            direction: direction.normalize(),
+        };
+
+        for (entity_id, colliders) in &self.colliders {
+            if filter.ignore.contains(entity_id) {
+                continue;
+            }
+            let pos = self.collider_position(entity_id);
+
+            for collider in colliders {
+                if collider.is_trigger && !filter.include_triggers {
+                    continue;
+                }
+                if let Some(layer) = filter.layer {
+                    if !self.collision_layers.should_collide(layer, collider.layer) {
+                        continue;
+                    }
+                }
+
+                let Some((distance, normal)) = ray.intersect(&collider.shape, pos) else {
+                    continue;
+                };
+                if distance > max_distance {
+                    continue;
+                }
+                on_hit(RaycastHit {
+                    entity: *entity_id,
+                    point: ray.at(distance).to_array(),
+                    normal: normal.to_array(),
+                    distance,
+                });
+            }
+        }
+    }
+
+    /// Debug lines for everything `settings` turns on, in its colors
+    pub fn debug_lines(&self, settings: &PhysicsDebugSettings) -> Vec<DebugLine> {
+        let mut lines = Vec::new();
+        if settings.show_colliders {
+            lines.extend(self.generate_collider_debug_lines(settings.collider_color, settings.trigger_color));
+        }
+        if settings.show_velocities {
+            lines.extend(self.generate_velocity_debug_lines(settings.velocity_color));
+        }
+        if settings.show_contacts {
+            lines.extend(self.generate_contact_debug_lines(settings.contact_color));
+        }
+        lines
+    }
+
     /// Generate debug lines for colliders
-    pub fn generate_collider_debug_lines(&self) -> Vec<DebugLine> {
+    pub fn generate_collider_debug_lines(&self, collider_color: [f32; 4], trigger_color: [f32; 4]) -> Vec<DebugLine> {
         let mut lines = Vec::new();
 
         for (entity_id, colliders) in &self.colliders {
-            let pos = self.bodies.get(entity_id).map(|b| b.position).unwrap_or_default();
+            let pos = self.collider_position(entity_id);
 
             for collider in colliders {
-                let color = if collider.is_trigger {
-                    [1.0, 1.0, 0.0, 0.5] // Yellow for triggers
-                } else {
-                    [0.0, 1.0, 0.0, 0.8] // Green for colliders
-                };
+                let color = if collider.is_trigger { trigger_color } else { collider_color };
 
                 match &collider.shape {
                     ColliderShape::Sphere { radius, center } => {
@@ -1058,35 +1300,60 @@ impl PhysicsWorld {
         lines
     }
 
-    /// Generate debug lines for velocities
-    pub fn generate_velocity_debug_lines(&self) -> Vec<DebugLine> {
+    /// Generate an arrow along each dynamic body's velocity, up to 5 units long
+    pub fn generate_velocity_debug_lines(&self, color: [f32; 4]) -> Vec<DebugLine> {
         let mut lines = Vec::new();
 
-        for body in self.bodies.values() {
+        for body in self.bodies.values().filter(|body| body.is_dynamic()) {
             let vel_length = body.velocity.length();
             if vel_length > 0.01 {
-                let end = body.position + body.velocity.normalize() * vel_length.min(5.0);
-                lines.push(DebugLine {
-                    start: body.position.to_array(),
+                let direction = body.velocity.normalize();
+                let end = body.position + direction * vel_length.min(5.0);
+                let line = |start: Vec3, end: Vec3| DebugLine {
+                    start: start.to_array(),
                     end: end.to_array(),
-                    color: [0.0, 0.5, 1.0, 1.0], // Blue
-                });
+                    color,
+                };
+                lines.push(line(body.position, end));
+
+                // Arrowhead: two barbs in a plane containing the shaft
+                let side = if direction.y.abs() < 0.9 {
+                    direction.cross(&Vec3::new(0.0, 1.0, 0.0))
+                } else {
+                    direction.cross(&Vec3::new(1.0, 0.0, 0.0))
+                }
+                .normalize();
+                let head = 0.25_f32.min(vel_length * 0.5);
+                let back = end - direction * head;
+                lines.push(line(end, back + side * (head * 0.5)));
+                lines.push(line(end, back - side * (head * 0.5)));
             }
         }
 
         lines
     }
 
-    /// Generate debug lines for contact points
-    pub fn generate_contact_debug_lines(&self) -> Vec<DebugLine> {
+    /// Generate a small cross at each contact of the last step, plus its normal
+    pub fn generate_contact_debug_lines(&self, color: [f32; 4]) -> Vec<DebugLine> {
+        const MARKER_SIZE: f32 = 0.05;
         let mut lines = Vec::new();
 
         for contact in &self.contacts {
-            let end = contact.point + contact.normal * 0.5;
+            let point = contact.point;
+            let axes = [Vec3::new(1.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0), Vec3::new(0.0, 0.0, 1.0)];
+            for axis in axes {
+                lines.push(DebugLine {
+                    start: (point - axis * MARKER_SIZE).to_array(),
+                    end: (point + axis * MARKER_SIZE).to_array(),
+                    color,
+                });
+            }
+
+            let end = point + contact.normal * 0.5;
             lines.push(DebugLine {
-                start: contact.point.to_array(),
+                start: point.to_array(),
                 end: end.to_array(),
-                color: [1.0, 0.0, 0.0, 1.0], // Red
+                color,
             });
         }
 
@@ -1094,11 +1361,461 @@ impl PhysicsWorld {
     }
 }
 
+/// Result of a successful raycast
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RaycastHit {
+    /// Entity owning the collider that was hit
+    pub entity: EntityId,
+    /// World-space hit point
+    pub point: [f32; 3],
+    /// Surface normal at the hit point
+    pub normal: [f32; 3],
+    /// Distance from the ray origin to the hit point
+    pub distance: f32,
+}
+
+/// Which colliders a raycast may hit
+#[derive(Debug, Clone, Default)]
+pub struct RaycastFilter {
+    /// Also hit trigger colliders
+    pub include_triggers: bool,
+    /// Layer the ray is cast on. Colliders on layers that don't collide
+    /// with it are skipped; `None` hits every layer.
+    pub layer: Option<u32>,
+    /// Entities whose colliders are ignored, usually the caster
+    pub ignore: HashSet<EntityId>,
+}
+
+/// Ray with a normalized direction
+#[derive(Debug, Clone, Copy)]
+struct Ray {
+    origin: Vec3,
+    direction: Vec3,
+}
+
+impl Ray {
+    fn at(&self, distance: f32) -> Vec3 {
+        self.origin + self.direction * distance
+    }
+
+    /// Distance and surface normal of the first intersection with a
+    /// collider shape positioned at `position`. Shapes are axis-aligned,
+    /// matching collision detection. A ray starting inside a shape hits
+    /// it at distance zero, facing back along the ray.
+    fn intersect(&self, shape: &ColliderShape, position: Vec3) -> Option<(f32, Vec3)> {
+        match shape {
+            ColliderShape::Sphere { radius, center } => self.intersect_sphere(position + *center, *radius),
+            ColliderShape::Box { size, center } => self.intersect_box(position + *center, *size * 0.5),
+            ColliderShape::Capsule {
+                radius,
+                height,
+                center,
+                direction,
+            } => {
+                let mut axis = [0.0; 3];
+                axis[usize::from((*direction).min(2))] = 1.0;
+                let half = ((height - 2.0 * radius) * 0.5).max(0.0);
+                let axis = Vec3::from_array(axis) * half;
+                let center = position + *center;
+                self.intersect_capsule(center - axis, center + axis, *radius)
+            }
+        }
+    }
+
+    fn intersect_sphere(&self, center: Vec3, radius: f32) -> Option<(f32, Vec3)> {
+        let offset = self.origin - center;
+        let c = offset.length_squared() - radius * radius;
+        if c <= 0.0 {
+            return Some((0.0, -self.direction));
+        }
+        let b = offset.dot(&self.direction);
+        let discriminant = b * b - c;
+        if b > 0.0 || discriminant < 0.0 {
+            return None;
+        }
+        let distance = -b - discriminant.sqrt();
+        Some((distance, (self.at(distance) - center).normalize()))
+    }
+
+    fn intersect_box(&self, center: Vec3, half_extents: Vec3) -> Option<(f32, Vec3)> {
+        let origin = (self.origin - center).to_array();
+        let direction = self.direction.to_array();
+        let half = half_extents.to_array();
+
+        let mut near = f32::NEG_INFINITY;
+        let mut far = f32::INFINITY;
+        let mut normal = [0.0; 3];
+        for axis in 0..3 {
+            if direction[axis].abs() < 1e-8 {
+                if origin[axis].abs() > half[axis] {
+                    return None;
+                }
+                continue;
+            }
+            let t1 = (-half[axis] - origin[axis]) / direction[axis];
+            let t2 = (half[axis] - origin[axis]) / direction[axis];
+            let (entry, exit) = if t1 < t2 { (t1, t2) } else { (t2, t1) };
+            if entry > near {
+                near = entry;
+                normal = [0.0; 3];
+                normal[axis] = -direction[axis].signum();
+            }
+            far = far.min(exit);
+        }
+
+        if near > far || far < 0.0 {
+            return None;
+        }
+        if near < 0.0 {
+            return Some((0.0, -self.direction));
+        }
+        Some((near, Vec3::from_array(normal)))
+    }
+
+    fn intersect_capsule(&self, a: Vec3, b: Vec3, radius: f32) -> Option<(f32, Vec3)> {
+        let mut closest = [self.intersect_sphere(a, radius), self.intersect_sphere(b, radius)]
+            .into_iter()
+            .flatten()
+            .min_by(|x, y| x.0.total_cmp(&y.0));
+
+        // Cylinder between the two cap centers
+        let segment = b - a;
+        let length_sq = segment.length_squared();
+        if length_sq > 1e-8 {
+            let axis = segment * (1.0 / length_sq.sqrt());
+            let offset = self.origin - a;
+            let radial_origin = offset - axis * offset.dot(&axis);
+            let radial_direction = self.direction - axis * self.direction.dot(&axis);
+
+            let qa = radial_direction.length_squared();
+            let qb = radial_origin.dot(&radial_direction);
+            let qc = radial_origin.length_squared() - radius * radius;
+            let origin_along = offset.dot(&axis);
+            if qc <= 0.0 && origin_along >= 0.0 && origin_along * origin_along <= length_sq {
+                return Some((0.0, -self.direction));
+            }
+            let discriminant = qb * qb - qa * qc;
+            if qa > 1e-8 && discriminant >= 0.0 {
+                let distance = (-qb - discriminant.sqrt()) / qa;
+                let along = (self.at(distance) - a).dot(&axis);
+                if distance >= 0.0
+                    && along >= 0.0
+                    && along * along <= length_sq
+                    && closest.map_or(true, |(best, _)| distance < best)
+                {
+                    let normal = (radial_origin + radial_direction * distance).normalize();
+                    closest = Some((distance, normal));
+                }
+            }
+        }
+        closest
+    }
+}
+
 /// Debug line for visualization
-#[allow(dead_code)] // Intentionally kept for API completeness
 #[derive(Debug, Clone, Copy)]
 pub struct DebugLine {
     pub start: [f32; 3],
     pub end: [f32; 3],
     pub color: [f32; 4],
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::EntityData;
+
+    fn box_entity(scene: &mut SceneData, position: [f32; 3], collider: BoxColliderComponent) -> EntityId {
+        scene.add_entity(EntityData {
+            transform: Transform {
+                position,
+                ..Default::default()
+            },
+            components: vec![Component::BoxCollider(collider)],
+            ..Default::default()
+        })
+    }
+
+    fn world(scene: &SceneData) -> PhysicsWorld {
+        let mut world = PhysicsWorld::new();
+        world.initialize_from_scene(scene, [0.0, -9.81, 0.0]);
+        world
+    }
+
+    #[test]
+    fn test_downward_ray_hits_box_top() {
+        let mut scene = SceneData::new();
+        let ground = box_entity(
+            &mut scene,
+            [2.0, 1.0, 3.0],
+            BoxColliderComponent {
+                size: [4.0, 2.0, 4.0],
+                ..Default::default()
+            },
+        );
+        let world = world(&scene);
+
+        let hit = world.raycast([2.5, 10.0, 3.0], [0.0, -1.0, 0.0], 100.0).unwrap();
+        assert_eq!(hit.entity, ground);
+        assert!((hit.distance - 8.0).abs() < 1e-4, "{}", hit.distance);
+        assert!((hit.point[1] - 2.0).abs() < 1e-4);
+        assert_eq!([hit.point[0], hit.point[2]], [2.5, 3.0]);
+        assert_eq!(hit.normal, [0.0, 1.0, 0.0]);
+
+        assert!(world.raycast([2.5, 10.0, 3.0], [0.0, -1.0, 0.0], 7.5).is_none());
+        assert!(world.raycast([10.0, 10.0, 3.0], [0.0, -1.0, 0.0], 100.0).is_none());
+    }
+
+    #[test]
+    fn test_raycast_all_sorts_hits_by_distance() {
+        let mut scene = SceneData::new();
+        let floor = box_entity(&mut scene, [0.0, 0.0, 0.0], BoxColliderComponent::default());
+        let upper = box_entity(&mut scene, [0.0, 5.0, 0.0], BoxColliderComponent::default());
+        let world = world(&scene);
+
+        let hits = world.raycast_all([0.0, 10.0, 0.0], [0.0, -2.0, 0.0], 100.0);
+        assert_eq!(hits.iter().map(|hit| hit.entity).collect::<Vec<_>>(), vec![upper, floor]);
+        assert!((hits[0].distance - 4.5).abs() < 1e-4);
+        assert!((hits[1].distance - 9.5).abs() < 1e-4);
+
+        // The range cuts off the farther box, and a ray beside both misses
+        assert_eq!(world.raycast_all([0.0, 10.0, 0.0], [0.0, -1.0, 0.0], 5.0).len(), 1);
+        assert!(world.raycast_all([3.0, 10.0, 0.0], [0.0, -1.0, 0.0], 100.0).is_empty());
+    }
+
+    #[test]
+    fn test_edit_time_raycast_hits_mesh_collider_proxy() {
+        let mut scene = SceneData::new();
+        let rock = scene.add_entity(EntityData {
+            transform: Transform {
+                position: [1.0, 2.0, 0.0],
+                ..Default::default()
+            },
+            components: vec![Component::MeshCollider(MeshColliderComponent {
+                mesh: "meshes/rock.glb".to_string(),
+                ..Default::default()
+            })],
+            ..Default::default()
+        });
+        // The play-mode simulation doesn't collide with the proxy
+        assert!(world(&scene).raycast([1.0, 10.0, 0.0], [0.0, -1.0, 0.0], 100.0).is_none());
+
+        // The unit cube around the entity stands in for the mesh
+        let editor = PhysicsWorld::snapshot(&scene);
+        let hit = editor.raycast([1.0, 10.0, 0.0], [0.0, -1.0, 0.0], 100.0).unwrap();
+        assert_eq!(hit.entity, rock);
+        assert!((hit.distance - 7.5).abs() < 1e-4);
+        assert!(editor.raycast([2.0, 10.0, 0.0], [0.0, -1.0, 0.0], 100.0).is_none());
+    }
+
+    #[test]
+    fn test_raycast_skips_triggers_unless_requested() {
+        let mut scene = SceneData::new();
+        let trigger = box_entity(
+            &mut scene,
+            [0.0, 5.0, 0.0],
+            BoxColliderComponent {
+                is_trigger: true,
+                ..Default::default()
+            },
+        );
+        let floor = box_entity(&mut scene, [0.0, 0.0, 0.0], BoxColliderComponent::default());
+        let world = world(&scene);
+
+        let hit = world.raycast([0.0, 10.0, 0.0], [0.0, -1.0, 0.0], 100.0).unwrap();
+        assert_eq!(hit.entity, floor);
+
+        let filter = RaycastFilter {
+            include_triggers: true,
+            ..Default::default()
+        };
+        let hit = world.raycast_filtered([0.0, 10.0, 0.0], [0.0, -1.0, 0.0], 100.0, &filter).unwrap();
+        assert_eq!(hit.entity, trigger);
+        assert!((hit.distance - 4.5).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_raycast_respects_collision_layers() {
+        let mut scene = SceneData::new();
+        box_entity(
+            &mut scene,
+            [0.0, 5.0, 0.0],
+            BoxColliderComponent {
+                layer: 1,
+                ..Default::default()
+            },
+        );
+        let floor = box_entity(&mut scene, [0.0, 0.0, 0.0], BoxColliderComponent::default());
+        let mut world = world(&scene);
+        world.collision_layers.matrix[2][1] = false;
+
+        let filter = RaycastFilter {
+            layer: Some(2),
+            ..Default::default()
+        };
+        let hit = world.raycast_filtered([0.0, 10.0, 0.0], [0.0, -1.0, 0.0], 100.0, &filter).unwrap();
+        assert_eq!(hit.entity, floor);
+    }
+
+    #[test]
+    fn test_disabled_layer_pair_never_collides() {
+        let mut scene = SceneData::new();
+        for (x, layer) in [(0.0, 1), (0.5, 2)] {
+            box_entity(
+                &mut scene,
+                [x, 0.0, 0.0],
+                BoxColliderComponent {
+                    layer,
+                    ..Default::default()
+                },
+            );
+        }
+
+        let mut settings = crate::project::CollisionLayerSettings::default();
+        let mut world = PhysicsWorld::new();
+        world.initialize_with_settings(&scene, [0.0, -9.81, 0.0], &settings);
+        world.step(1.0 / 60.0);
+        assert_eq!(world.contacts.len(), 1);
+
+        settings.set_collision(2, 1, false);
+        world.initialize_with_settings(&scene, [0.0, -9.81, 0.0], &settings);
+        for _ in 0..10 {
+            world.step(1.0 / 60.0);
+            assert!(world.contacts.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_body_passing_through_trigger() {
+        let mut scene = SceneData::new();
+        let trigger = box_entity(
+            &mut scene,
+            [0.0, 0.0, 0.0],
+            BoxColliderComponent {
+                is_trigger: true,
+                ..Default::default()
+            },
+        );
+        let ball = scene.add_entity(EntityData {
+            transform: Transform {
+                position: [-3.0, 0.0, 0.0],
+                ..Default::default()
+            },
+            components: vec![
+                Component::SphereCollider(SphereColliderComponent::default()),
+                Component::Rigidbody(RigidbodyComponent {
+                    use_gravity: false,
+                    initial_velocity: [6.0, 0.0, 0.0],
+                    ..Default::default()
+                }),
+            ],
+            ..Default::default()
+        });
+        let mut world = world(&scene);
+
+        let mut events = Vec::new();
+        for _ in 0..60 {
+            world.step(1.0 / 60.0);
+            events.extend(world.drain_trigger_events());
+        }
+
+        assert!(events.iter().all(|event| event.entity == trigger && event.other == ball));
+        let phases: Vec<TriggerPhase> = events
+            .iter()
+            .map(|event| event.phase)
+            .filter(|phase| *phase != TriggerPhase::Stay)
+            .collect();
+        assert_eq!(phases, vec![TriggerPhase::Enter, TriggerPhase::Exit]);
+        assert!(events.iter().any(|event| event.phase == TriggerPhase::Stay));
+        // The trigger doesn't push the ball back
+        assert!(world.contacts.is_empty());
+        assert!(world.drain_trigger_events().is_empty());
+    }
+
+    #[test]
+    fn test_debug_lines_follow_settings() {
+        let mut scene = SceneData::new();
+        scene.add_entity(EntityData {
+            components: vec![
+                Component::SphereCollider(SphereColliderComponent::default()),
+                Component::Rigidbody(RigidbodyComponent {
+                    use_gravity: false,
+                    initial_velocity: [2.0, 0.0, 0.0],
+                    ..Default::default()
+                }),
+            ],
+            ..Default::default()
+        });
+        let world = PhysicsWorld::snapshot(&scene);
+
+        let mut settings = PhysicsDebugSettings::default();
+        assert!(world.debug_lines(&settings).is_empty());
+
+        settings.show_velocities = true;
+        let lines = world.debug_lines(&settings);
+        assert!(!lines.is_empty());
+        assert!(lines.iter().all(|line| line.color == settings.velocity_color));
+        // The shaft runs along the velocity from the body's center
+        assert_eq!(lines[0].start, [0.0, 0.0, 0.0]);
+        assert!(lines[0].end[0] > 0.0);
+    }
+
+    #[test]
+    fn test_ray_sphere_and_capsule() {
+        let ray = Ray {
+            origin: Vec3::new(-5.0, 0.0, 0.0),
+            direction: Vec3::new(1.0, 0.0, 0.0),
+        };
+        let sphere = ColliderShape::Sphere {
+            radius: 1.0,
+            center: Vec3::zero(),
+        };
+        let (distance, normal) = ray.intersect(&sphere, Vec3::zero()).unwrap();
+        assert!((distance - 4.0).abs() < 1e-4);
+        assert!((normal.x + 1.0).abs() < 1e-4);
+
+        let capsule = ColliderShape::Capsule {
+            radius: 0.5,
+            height: 4.0,
+            center: Vec3::zero(),
+            direction: 1,
+        };
+        let (distance, _) = ray.intersect(&capsule, Vec3::new(0.0, 1.0, 0.0)).unwrap();
+        assert!((distance - 4.5).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_static_colliders_use_their_world_position() {
+        let mut scene = SceneData::new();
+        let trigger = box_entity(
+            &mut scene,
+            [10.0, 0.0, 0.0],
+            BoxColliderComponent {
+                is_trigger: true,
+                ..Default::default()
+            },
+        );
+        let body = box_entity(&mut scene, [10.0, 0.0, 0.0], BoxColliderComponent::default());
+        let idle = box_entity(&mut scene, [0.0, 0.0, 0.0], BoxColliderComponent::default());
+        for id in [body, idle] {
+            scene.get_mut(&id).unwrap().components.push(Component::Rigidbody(RigidbodyComponent {
+                use_gravity: false,
+                ..Default::default()
+            }));
+        }
+        let mut world = PhysicsWorld::new();
+        world.initialize_from_scene(&scene, [0.0, 0.0, 0.0]);
+        world.step(1.0 / 60.0);
+
+        // The trigger overlaps the body at x = 10, not the one at the origin
+        let events = world.drain_trigger_events();
+        assert!(events.contains(&TriggerEvent {
+            entity: trigger,
+            other: body,
+            phase: TriggerPhase::Enter,
+        }));
+        assert!(!events
+            .iter()
+            .any(|e| e.phase == TriggerPhase::Enter && (e.entity == idle || e.other == idle)));
+    }
+}
@@ -5,9 +5,18 @@
 //! - Entering and exiting play mode
 //! - Pausing and resuming gameplay
 //! - Scene state backup and restoration
+//! - Stepping backward through recent frames while paused
+//! - Game view aspect ratio, letterboxed in the viewport while playing
 //! - Play mode UI indicators
+//! - Sampling input for named input actions
 
+use crate::physics::PhysicsWorld;
+use crate::project::{InputBinding, InputMap};
 use crate::state::{SceneData, Selection};
+use std::collections::{HashSet, VecDeque};
+
+/// Default number of frames kept for stepping backward
+pub const DEFAULT_MAX_SNAPSHOTS: usize = 120;
 
 /// Play mode state
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -44,6 +53,201 @@ impl PlayState {
     }
 }
 
+/// Aspect ratio of the game view while playing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GameViewAspect {
+    /// Fill the viewport
+    #[default]
+    Free,
+    /// 16:9 widescreen
+    Wide,
+    /// 4:3 standard
+    Standard,
+    /// Fixed resolution, scaled down to fit when the viewport is smaller
+    Resolution {
+        /// Width in pixels
+        width: u32,
+        /// Height in pixels
+        height: u32,
+    },
+}
+
+impl GameViewAspect {
+    /// Name shown in the aspect picker
+    pub fn label(&self) -> String {
+        match self {
+            Self::Free => "Free Aspect".to_string(),
+            Self::Wide => "16:9".to_string(),
+            Self::Standard => "4:3".to_string(),
+            Self::Resolution { width, height } => format!("{width}x{height}"),
+        }
+    }
+
+    /// Width over height, or `None` when free
+    pub fn ratio(&self) -> Option<f32> {
+        match *self {
+            Self::Free => None,
+            Self::Wide => Some(16.0 / 9.0),
+            Self::Standard => Some(4.0 / 3.0),
+            Self::Resolution { width, height } => Some(width.max(1) as f32 / height.max(1) as f32),
+        }
+    }
+
+    /// Largest size with this aspect that fits in `available`. A fixed
+    /// resolution is never scaled up past its own size.
+    pub fn fit(&self, available: egui::Vec2) -> egui::Vec2 {
+        let Some(ratio) = self.ratio() else {
+            return available;
+        };
+
+        let fitted = if available.x / available.y.max(1.0) > ratio {
+            egui::vec2(available.y * ratio, available.y)
+        } else {
+            egui::vec2(available.x, available.x / ratio)
+        };
+        match *self {
+            Self::Resolution { width, height } => fitted.min(egui::vec2(width as f32, height as f32)),
+            _ => fitted,
+        }
+    }
+}
+
+/// Input state sampled each frame while playing
+#[derive(Debug, Clone, Default)]
+pub struct PlayInput {
+    /// Bindings held down this frame
+    pressed: HashSet<InputBinding>,
+    /// Bindings held down last frame
+    previous: HashSet<InputBinding>,
+}
+
+#[allow(dead_code)] // Intentionally kept for API completeness
+impl PlayInput {
+    /// Advance to a new frame with the given set of held bindings
+    pub fn update(&mut self, pressed: HashSet<InputBinding>) {
+        self.previous = std::mem::replace(&mut self.pressed, pressed);
+    }
+
+    /// Sample keyboard and mouse state from egui, plus the held gamepad buttons
+    pub fn sample(&mut self, input: &egui::InputState, gamepad_buttons: impl IntoIterator<Item = String>) {
+        let mut pressed: HashSet<InputBinding> = input
+            .keys_down
+            .iter()
+            .map(|key| InputBinding::Key(key.name().to_string()))
+            .collect();
+        pressed.extend(gamepad_buttons.into_iter().map(InputBinding::GamepadButton));
+
+        let buttons = [
+            (egui::PointerButton::Primary, "Left"),
+            (egui::PointerButton::Secondary, "Right"),
+            (egui::PointerButton::Middle, "Middle"),
+        ];
+        for (button, name) in buttons {
+            if input.pointer.button_down(button) {
+                pressed.insert(InputBinding::MouseButton(name.to_string()));
+            }
+        }
+
+        self.update(pressed);
+    }
+
+    /// Clear all held input
+    pub fn clear(&mut self) {
+        self.pressed.clear();
+        self.previous.clear();
+    }
+
+    /// Bindings held down this frame
+    pub fn pressed(&self) -> &HashSet<InputBinding> {
+        &self.pressed
+    }
+
+    /// Check if an action is held down
+    pub fn is_action_pressed(&self, map: &InputMap, action: &str) -> bool {
+        map.is_action_pressed(action, &self.pressed)
+    }
+
+    /// Check if an action went down this frame
+    pub fn is_action_just_pressed(&self, map: &InputMap, action: &str) -> bool {
+        map.is_action_pressed(action, &self.pressed) && !map.is_action_pressed(action, &self.previous)
+    }
+
+    /// Check if an action was released this frame
+    pub fn is_action_just_released(&self, map: &InputMap, action: &str) -> bool {
+        !map.is_action_pressed(action, &self.pressed) && map.is_action_pressed(action, &self.previous)
+    }
+}
+
+/// Connected gamepads, read through gilrs with the `gamepad` feature
+#[derive(Debug, Default)]
+pub struct Gamepads {
+    /// Gamepad backend, opened on the first poll
+    #[cfg(feature = "gamepad")]
+    gilrs: Option<gilrs::Gilrs>,
+    /// Whether opening the backend was attempted
+    #[cfg(feature = "gamepad")]
+    opened: bool,
+}
+
+impl Gamepads {
+    /// Names of the buttons held on any connected gamepad ("A", "Start", ...)
+    #[cfg(feature = "gamepad")]
+    pub fn poll(&mut self) -> Vec<String> {
+        use gilrs::Button;
+
+        if !self.opened {
+            self.opened = true;
+            self.gilrs = gilrs::Gilrs::new()
+                .map_err(|e| tracing::warn!("Gamepad input unavailable: {}", e))
+                .ok();
+        }
+        let Some(gilrs) = self.gilrs.as_mut() else {
+            return Vec::new();
+        };
+        // Button state only updates as events are drained
+        while gilrs.next_event().is_some() {}
+
+        let buttons = [
+            (Button::South, "A"),
+            (Button::East, "B"),
+            (Button::West, "X"),
+            (Button::North, "Y"),
+            (Button::LeftTrigger, "LeftBumper"),
+            (Button::RightTrigger, "RightBumper"),
+            (Button::LeftTrigger2, "LeftTrigger"),
+            (Button::RightTrigger2, "RightTrigger"),
+            (Button::Select, "Select"),
+            (Button::Start, "Start"),
+            (Button::LeftThumb, "LeftStick"),
+            (Button::RightThumb, "RightStick"),
+            (Button::DPadUp, "DPadUp"),
+            (Button::DPadDown, "DPadDown"),
+            (Button::DPadLeft, "DPadLeft"),
+            (Button::DPadRight, "DPadRight"),
+        ];
+        buttons
+            .iter()
+            .filter(|(button, _)| gilrs.gamepads().any(|(_, pad)| pad.is_pressed(*button)))
+            .map(|(_, name)| (*name).to_string())
+            .collect()
+    }
+
+    /// Without the `gamepad` feature no gamepad is ever connected
+    #[cfg(not(feature = "gamepad"))]
+    pub fn poll(&mut self) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+/// Scene and physics state at the start of a simulated frame
+#[derive(Clone)]
+struct PlaySnapshot {
+    scene: SceneData,
+    physics: PhysicsWorld,
+    frame_count: u64,
+    elapsed_time: f64,
+}
+
 /// Play mode manager - handles entering/exiting play mode
 pub struct PlayModeManager {
     /// Current play state
@@ -60,6 +264,16 @@ pub struct PlayModeManager {
     pub frame_count: u64,
     /// Elapsed time since play started
     pub elapsed_time: f64,
+    /// Input sampled while playing
+    pub input: PlayInput,
+    /// Gamepads sampled into `input`
+    gamepads: Gamepads,
+    /// Recent frames, oldest first, for stepping backward
+    snapshots: VecDeque<PlaySnapshot>,
+    /// Most frames kept in `snapshots`
+    pub max_snapshots: usize,
+    /// Aspect the viewport is letterboxed to while playing
+    pub game_view_aspect: GameViewAspect,
 }
 
 #[allow(dead_code)] // Intentionally kept for API completeness
@@ -73,9 +287,20 @@ impl PlayModeManager {
             accumulated_time: 0.0,
             frame_count: 0,
             elapsed_time: 0.0,
+            input: PlayInput::default(),
+            gamepads: Gamepads::default(),
+            snapshots: VecDeque::new(),
+            max_snapshots: DEFAULT_MAX_SNAPSHOTS,
+            game_view_aspect: GameViewAspect::Free,
         }
     }
 
+    /// Sample this frame's keyboard, mouse and gamepad input
+    pub fn sample_input(&mut self, ctx: &egui::Context) {
+        let gamepad_buttons = self.gamepads.poll();
+        ctx.input(|i| self.input.sample(i, gamepad_buttons));
+    }
+
     /// Enter play mode
     /// Returns true if successfully entered play mode
     pub fn play(&mut self, scene: &SceneData, selection: &Selection) -> bool {
@@ -88,6 +313,7 @@ impl PlayModeManager {
                 self.frame_count = 0;
                 self.elapsed_time = 0.0;
                 self.accumulated_time = 0.0;
+                self.snapshots.clear();
                 tracing::info!("Entered play mode");
                 true
             }
@@ -139,6 +365,8 @@ impl PlayModeManager {
         self.frame_count = 0;
         self.elapsed_time = 0.0;
         self.accumulated_time = 0.0;
+        self.input.clear();
+        self.snapshots.clear();
 
         let scene = self.scene_backup.take();
         let selection = self.selection_backup.take();
@@ -179,6 +407,24 @@ impl PlayModeManager {
         steps
     }
 
+    /// [`Self::update`], recording the frame's starting state first when it
+    /// runs any timesteps. `scene` and `physics` must not have been stepped
+    /// for this frame yet.
+    pub fn update_recording(
+        &mut self,
+        delta_time: f64,
+        fixed_timestep: f64,
+        scene: &SceneData,
+        physics: &PhysicsWorld,
+    ) -> u32 {
+        let (frame_count, elapsed_time) = (self.frame_count, self.elapsed_time);
+        let steps = self.update(delta_time, fixed_timestep);
+        if steps > 0 {
+            self.push_snapshot(scene, physics, frame_count, elapsed_time);
+        }
+        steps
+    }
+
     /// Step forward one frame (while paused)
     pub fn step_frame(&mut self, fixed_timestep: f64) -> bool {
         if self.state == PlayState::Paused {
@@ -190,6 +436,60 @@ impl PlayModeManager {
         }
     }
 
+    /// Record the state a frame is about to simulate from, so
+    /// [`Self::step_frame_back`] can return to it. The oldest frame is
+    /// dropped once `max_snapshots` are kept.
+    pub fn record_snapshot(&mut self, scene: &SceneData, physics: &PhysicsWorld) {
+        self.push_snapshot(scene, physics, self.frame_count, self.elapsed_time);
+    }
+
+    fn push_snapshot(&mut self, scene: &SceneData, physics: &PhysicsWorld, frame_count: u64, elapsed_time: f64) {
+        if !self.state.is_active() || self.max_snapshots == 0 {
+            return;
+        }
+
+        while self.snapshots.len() >= self.max_snapshots {
+            self.snapshots.pop_front();
+        }
+        self.snapshots.push_back(PlaySnapshot {
+            scene: scene.clone(),
+            physics: physics.clone(),
+            frame_count,
+            elapsed_time,
+        });
+    }
+
+    /// Step back one recorded frame (while paused).
+    ///
+    /// Returns the scene and physics world to restore, or `None` when not
+    /// paused or no earlier frame is recorded.
+    pub fn step_frame_back(&mut self) -> Option<(SceneData, PhysicsWorld)> {
+        if self.state != PlayState::Paused {
+            return None;
+        }
+
+        let snapshot = self.snapshots.pop_back()?;
+        self.frame_count = snapshot.frame_count;
+        self.elapsed_time = snapshot.elapsed_time;
+        self.accumulated_time = 0.0;
+        Some((snapshot.scene, snapshot.physics))
+    }
+
+    /// Number of frames that can be stepped back through
+    pub fn snapshot_count(&self) -> usize {
+        self.snapshots.len()
+    }
+
+    /// Size of the game view inside a viewport of `available` size. The
+    /// aspect only applies in play mode; editing uses the free viewport.
+    pub fn game_view_size(&self, available: egui::Vec2) -> egui::Vec2 {
+        if self.state.is_active() {
+            self.game_view_aspect.fit(available)
+        } else {
+            available
+        }
+    }
+
     /// Check if editing should be disabled
     pub fn is_editing_disabled(&self) -> bool {
         self.state.is_active()
@@ -225,3 +525,74 @@ impl Default for PlayModeManager {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::EntityData;
+
+    #[test]
+    fn test_sampled_gamepad_buttons_trigger_actions() {
+        let mut map = InputMap::new();
+        map.bind("Jump", InputBinding::GamepadButton("A".to_string()));
+        let mut input = PlayInput::default();
+
+        input.sample(&egui::InputState::default(), ["A".to_string()]);
+        assert!(input.is_action_just_pressed(&map, "Jump"));
+
+        input.sample(&egui::InputState::default(), Vec::new());
+        assert!(input.is_action_just_released(&map, "Jump"));
+    }
+
+    #[test]
+    fn test_game_view_letterboxes_only_while_playing() {
+        let available = egui::vec2(1000.0, 1000.0);
+        let mut play_mode = PlayModeManager::new();
+        play_mode.game_view_aspect = GameViewAspect::Wide;
+        assert_eq!(play_mode.game_view_size(available), available);
+
+        play_mode.play(&SceneData::new(), &Selection::default());
+        assert_eq!(play_mode.game_view_size(available), egui::vec2(1000.0, 562.5));
+        // Pillarboxed in a wide viewport
+        assert_eq!(play_mode.game_view_size(egui::vec2(2000.0, 900.0)), egui::vec2(1600.0, 900.0));
+
+        play_mode.game_view_aspect = GameViewAspect::Resolution { width: 320, height: 240 };
+        assert_eq!(play_mode.game_view_size(available), egui::vec2(320.0, 240.0));
+
+        play_mode.stop();
+        assert_eq!(play_mode.game_view_size(available), available);
+    }
+
+    #[test]
+    fn test_step_frame_back_restores_recorded_frames() {
+        let mut scene = SceneData::new();
+        let id = scene.add_entity(EntityData::default());
+        let physics = PhysicsWorld::new();
+        let mut play_mode = PlayModeManager::new();
+        play_mode.max_snapshots = 2;
+        play_mode.play(&scene, &Selection::default());
+
+        // Recorded in the same order as the app's frame loop
+        for x in [1.0, 2.0, 3.0] {
+            assert_eq!(play_mode.update_recording(1.0 / 60.0, 1.0 / 60.0, &scene, &physics), 1);
+            scene.entities[&id].transform.position[0] = x;
+        }
+        assert_eq!(play_mode.snapshot_count(), 2);
+        // A frame too short to step records nothing
+        assert_eq!(play_mode.update_recording(1.0 / 240.0, 1.0 / 60.0, &scene, &physics), 0);
+        assert_eq!(play_mode.snapshot_count(), 2);
+
+        // Only paused play mode steps back
+        assert!(play_mode.step_frame_back().is_none());
+        play_mode.pause();
+
+        let (restored, _) = play_mode.step_frame_back().unwrap();
+        assert_eq!(restored.entities[&id].transform.position[0], 2.0);
+        assert_eq!(play_mode.frame_count, 2);
+        assert!((play_mode.elapsed_time - 2.0 / 60.0).abs() < 1e-9);
+        let (restored, _) = play_mode.step_frame_back().unwrap();
+        assert_eq!(restored.entities[&id].transform.position[0], 1.0);
+        // The first frame fell out of the bounded buffer
+        assert!(play_mode.step_frame_back().is_none());
+    }
+}
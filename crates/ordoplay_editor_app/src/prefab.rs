@@ -5,13 +5,16 @@
 //! They support:
 //! - Property overrides (instances can differ from the source prefab)
 //! - Nested prefabs (prefabs containing other prefab instances)
+//! - Variants (prefabs inheriting from a base prefab plus a set of overrides,
+//!   resolved against the base whenever they are loaded)
 //! - Live updates (changes to prefab propagate to instances)
 
 
-use crate::state::{EntityData, EntityId, Transform};
+use crate::components::Component;
+use crate::state::{ColorLabel, EntityData, EntityId, Transform};
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
-use std::path::PathBuf;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use uuid::Uuid;
 
 /// Unique identifier for prefabs
@@ -31,6 +34,23 @@ pub struct Prefab {
     pub path: Option<PathBuf>,
     /// Version for format compatibility
     pub version: u32,
+    /// Base prefab this prefab is a variant of
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base: Option<PrefabBase>,
+}
+
+/// Maximum chain of variants-of-variants resolved on load
+pub const MAX_VARIANT_DEPTH: usize = 16;
+
+/// Reference from a prefab variant to the prefab it inherits from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrefabBase {
+    /// Path to the base prefab asset
+    pub prefab_path: PathBuf,
+    /// ID of the base prefab at the time the variant was created
+    pub prefab_id: PrefabId,
+    /// Overrides applied on top of the base (the variant's delta)
+    pub overrides: Vec<PropertyOverride>,
 }
 
 /// An entity within a prefab
@@ -43,7 +63,16 @@ pub struct PrefabEntity {
     /// Transform relative to parent
     pub transform: Transform,
     /// Components attached to this entity
-    pub components: Vec<crate::components::Component>,
+    pub components: Vec<Component>,
+    /// User-defined tags
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Color label shown in the hierarchy
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color_label: Option<ColorLabel>,
+    /// Free-form key/value pipeline data
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub metadata: BTreeMap<String, String>,
     /// Child entities
     pub children: Vec<PrefabEntity>,
     /// If this is a nested prefab instance
@@ -64,7 +93,7 @@ pub struct NestedPrefabRef {
 pub struct PropertyOverride {
     /// Path to the entity within the prefab (e.g., "0/2/1" for nested children)
     pub entity_path: String,
-    /// Property path (e.g., "transform.position.x" or "components[0].intensity")
+    /// Property path (e.g., "transform.position.x", "Light.intensity" or "components[0].intensity")
     pub property_path: String,
     /// Serialized override value
     pub value: serde_json::Value,
@@ -100,11 +129,15 @@ impl Prefab {
                 name: "Root".to_string(),
                 transform: Transform::default(),
                 components: Vec::new(),
+                tags: Vec::new(),
+                color_label: None,
+                metadata: BTreeMap::new(),
                 children: Vec::new(),
                 nested_prefab: None,
             },
             path: None,
             version: Self::FORMAT_VERSION,
+            base: None,
         }
     }
 
@@ -127,9 +160,42 @@ impl Prefab {
             root,
             path: None,
             version: Self::FORMAT_VERSION,
+            base: None,
+        }
+    }
+
+    /// Create an empty variant of `base`, stored at `base_path`.
+    ///
+    /// The variant starts with the base's entity tree and no overrides.
+    pub fn variant_of(name: impl Into<String>, base: &Prefab, base_path: PathBuf) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            name: name.into(),
+            root: base.root.clone(),
+            path: None,
+            version: Self::FORMAT_VERSION,
+            base: Some(PrefabBase {
+                prefab_path: base_path,
+                prefab_id: base.id,
+                overrides: Vec::new(),
+            }),
         }
     }
 
+    /// Turn this prefab into a variant of a base prefab.
+    ///
+    /// The entity tree stays the resolved content; the base reference and
+    /// overrides record what the variant changes relative to its base.
+    pub fn with_base(mut self, base: PrefabBase) -> Self {
+        self.base = Some(base);
+        self
+    }
+
+    /// Check if this prefab is a variant of another prefab
+    pub fn is_variant(&self) -> bool {
+        self.base.is_some()
+    }
+
     /// Convert an `EntityData` to a `PrefabEntity` recursively
     fn entity_to_prefab_entity(
         entity: &EntityData,
@@ -151,6 +217,9 @@ impl Prefab {
             name: entity.name.clone(),
             transform: entity.transform.clone(),
             components: entity.components.clone(),
+            tags: entity.tags.clone(),
+            color_label: entity.color_label,
+            metadata: entity.metadata.clone(),
             children,
             nested_prefab: None, // TODO: detect nested prefabs
         }
@@ -195,10 +264,16 @@ impl Prefab {
             parent: parent_id,
             children: child_ids,
             components: prefab_entity.components.clone(),
+            tags: prefab_entity.tags.clone(),
+            color_label: prefab_entity.color_label,
+            metadata: prefab_entity.metadata.clone(),
         }
     }
 
-    /// Get all entities from instantiation as a flat list
+    /// Get all entities from instantiation as a flat list.
+    ///
+    /// A variant instantiates its resolved tree: [`Prefab::load`] resolves
+    /// the base and applies the variant's overrides.
     pub fn instantiate_flat(&self) -> (Vec<EntityData>, HashMap<u32, EntityId>) {
         let mut id_mapping = HashMap::new();
         let mut entities = Vec::new();
@@ -232,6 +307,9 @@ impl Prefab {
             parent: parent_id,
             children: child_ids.clone(),
             components: prefab_entity.components.clone(),
+            tags: prefab_entity.tags.clone(),
+            color_label: prefab_entity.color_label,
+            metadata: prefab_entity.metadata.clone(),
         };
         entities.push(entity);
 
@@ -267,6 +345,9 @@ impl Prefab {
             parent: Some(parent_id),
             children: child_ids.clone(),
             components: prefab_entity.components.clone(),
+            tags: prefab_entity.tags.clone(),
+            color_label: prefab_entity.color_label,
+            metadata: prefab_entity.metadata.clone(),
         };
         entities.push(entity);
 
@@ -298,22 +379,279 @@ impl Prefab {
     }
 
     /// Save prefab to file
-    pub fn save(&self, path: &PathBuf) -> std::io::Result<()> {
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
         let ron_str = self.to_ron().map_err(|e| {
             std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string())
         })?;
         std::fs::write(path, ron_str)
     }
 
-    /// Load prefab from file
-    pub fn load(path: &PathBuf) -> std::io::Result<Self> {
+    /// Load prefab from file.
+    ///
+    /// A variant is resolved against its base: the base is loaded (itself
+    /// resolved) and the variant's overrides are applied on top. If the
+    /// base can't be loaded, the tree stored with the variant is kept.
+    pub fn load(path: &Path) -> std::io::Result<Self> {
+        Self::load_resolved(path, 0)
+    }
+
+    fn load_resolved(path: &Path, depth: usize) -> std::io::Result<Self> {
+        if depth > MAX_VARIANT_DEPTH {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("Prefab variant chain deeper than {} (cycle?) at {}", MAX_VARIANT_DEPTH, path.display()),
+            ));
+        }
+
         let contents = std::fs::read_to_string(path)?;
         let mut prefab = Self::from_ron(&contents).map_err(|e| {
             std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string())
         })?;
-        prefab.path = Some(path.clone());
+        prefab.path = Some(path.to_path_buf());
+
+        if let Some(base) = &prefab.base {
+            match Self::load_resolved(&base.prefab_path, depth + 1) {
+                Ok(resolved_base) => {
+                    prefab.root = resolved_base.root;
+                    prefab.apply_variant_overrides();
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::InvalidData => return Err(e),
+                Err(e) => tracing::warn!(
+                    "Base prefab {} of variant {} unavailable ({}), using stored entities",
+                    base.prefab_path.display(),
+                    path.display(),
+                    e
+                ),
+            }
+        }
         Ok(prefab)
     }
+
+    /// Apply the variant's overrides to its entity tree
+    pub fn apply_variant_overrides(&mut self) {
+        let Some(base) = &self.base else {
+            return;
+        };
+        for override_ in &base.overrides {
+            let entity = override_
+                .entity_path
+                .parse()
+                .ok()
+                .and_then(|local_id| self.root.find_mut(local_id));
+            let applied = entity.is_some_and(|entity| entity.apply_override(&override_.property_path, &override_.value));
+            if !applied {
+                tracing::warn!(
+                    "Variant override {}:{} could not be applied",
+                    override_.entity_path,
+                    override_.property_path
+                );
+            }
+        }
+    }
+}
+
+impl PrefabEntity {
+    /// Find an entity in this subtree by its local ID
+    pub fn find_mut(&mut self, local_id: u32) -> Option<&mut PrefabEntity> {
+        if self.local_id == local_id {
+            return Some(self);
+        }
+        self.children.iter_mut().find_map(|child| child.find_mut(local_id))
+    }
+
+    /// Set a property from an override value.
+    /// Returns false if the path or value doesn't fit a prefab entity.
+    pub fn apply_override(&mut self, property_path: &str, value: &serde_json::Value) -> bool {
+        if property_path == "name" {
+            let Some(name) = value.as_str() else {
+                return false;
+            };
+            self.name = name.to_string();
+            return true;
+        }
+
+        if let Some((selector, field)) = component_field_path(property_path) {
+            return selector
+                .find_mut(&mut self.components)
+                .is_some_and(|component| component.set_field_value(field, value.clone()));
+        }
+
+        if let Some(index) = component_index(property_path) {
+            let Ok(component) = serde_json::from_value(value.clone()) else {
+                return false;
+            };
+            let len = self.components.len();
+            match self.components.get_mut(index) {
+                Some(slot) => *slot = component,
+                None if index == len => self.components.push(component),
+                None => return false,
+            }
+            return true;
+        }
+
+        let Some((vector, axis)) = self.transform_vector_mut(property_path) else {
+            return false;
+        };
+        match axis {
+            None => match serde_json::from_value(value.clone()) {
+                Ok(components) => *vector = components,
+                Err(_) => return false,
+            },
+            Some(axis) => match value.as_f64() {
+                Some(component) => vector[axis] = component as f32,
+                None => return false,
+            },
+        }
+        true
+    }
+
+    /// Current value of a property, in the form [`Self::apply_override`] takes
+    pub fn property_value(&self, property_path: &str) -> Option<serde_json::Value> {
+        if property_path == "name" {
+            return Some(serde_json::Value::from(self.name.clone()));
+        }
+        if let Some((selector, field)) = component_field_path(property_path) {
+            return selector.find(&self.components)?.field_value(field);
+        }
+        if let Some(index) = component_index(property_path) {
+            return serde_json::to_value(self.components.get(index)?).ok();
+        }
+
+        let (field, axis) = split_axis(property_path.strip_prefix("transform.")?);
+        let vector = match field {
+            "position" => self.transform.position,
+            "rotation" => self.transform.rotation,
+            "scale" => self.transform.scale,
+            _ => return None,
+        };
+        match axis {
+            None => serde_json::to_value(vector).ok(),
+            Some(axis) => Some(serde_json::Value::from(vector[axis])),
+        }
+    }
+
+    /// The transform vector a "transform.<field>[.<axis>]" path refers to
+    fn transform_vector_mut(&mut self, property_path: &str) -> Option<(&mut [f32; 3], Option<usize>)> {
+        let (field, axis) = split_axis(property_path.strip_prefix("transform.")?);
+        let vector = match field {
+            "position" => &mut self.transform.position,
+            "rotation" => &mut self.transform.rotation,
+            "scale" => &mut self.transform.scale,
+            _ => return None,
+        };
+        Some((vector, axis))
+    }
+}
+
+/// First free variant path next to a prefab
+/// ("Crate.prefab" -> "Crate Variant.prefab", then "Crate Variant 2.prefab", ...)
+pub fn free_variant_path(prefab_path: &Path) -> PathBuf {
+    let stem = prefab_path.file_stem().and_then(|s| s.to_str()).unwrap_or("Prefab");
+    (1..)
+        .map(|n| match n {
+            1 => prefab_path.with_file_name(format!("{} Variant.prefab", stem)),
+            n => prefab_path.with_file_name(format!("{} Variant {}.prefab", stem, n)),
+        })
+        .find(|path| !path.exists())
+        .expect("unbounded range always yields a free name")
+}
+
+/// Split a trailing ".x"/".y"/".z" off a property path
+fn split_axis(path: &str) -> (&str, Option<usize>) {
+    match path.rsplit_once('.') {
+        Some((field, "x")) => (field, Some(0)),
+        Some((field, "y")) => (field, Some(1)),
+        Some((field, "z")) => (field, Some(2)),
+        _ => (path, None),
+    }
+}
+
+/// Field a property path edits; per-axis paths ("transform.scale.x")
+/// name their vector field ("transform.scale")
+pub fn property_field(property_path: &str) -> &str {
+    split_axis(property_path).0
+}
+
+/// Component index of a "components[N]" property path
+pub fn component_index(property_path: &str) -> Option<usize> {
+    property_path
+        .strip_prefix("components[")?
+        .strip_suffix(']')?
+        .parse()
+        .ok()
+}
+
+/// The component a component-field property path refers to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ComponentSelector<'a> {
+    /// By slot, as in "components[0].intensity"
+    Index(usize),
+    /// The first component of a type, as in "Light.intensity"
+    Type(&'a str),
+}
+
+impl ComponentSelector<'_> {
+    /// Find the selected component
+    pub fn find<'c>(&self, components: &'c [Component]) -> Option<&'c Component> {
+        match *self {
+            Self::Index(index) => components.get(index),
+            Self::Type(type_id) => components.iter().find(|component| component.type_id() == type_id),
+        }
+    }
+
+    /// Find the selected component, mutably
+    pub fn find_mut<'c>(&self, components: &'c mut [Component]) -> Option<&'c mut Component> {
+        match *self {
+            Self::Index(index) => components.get_mut(index),
+            Self::Type(type_id) => components.iter_mut().find(|component| component.type_id() == type_id),
+        }
+    }
+}
+
+/// Split a component field path ("Light.intensity", "components[0].color.1")
+/// into the component and the field path within it.
+///
+/// Entity transform paths ("transform.position") are not component fields.
+pub fn component_field_path(property_path: &str) -> Option<(ComponentSelector<'_>, &str)> {
+    let (component, field) = property_path.split_once('.')?;
+    if field.is_empty() || component == "transform" {
+        return None;
+    }
+    let selector = match component_index(component) {
+        Some(index) => ComponentSelector::Index(index),
+        None => ComponentSelector::Type(component),
+    };
+    Some((selector, field))
+}
+
+/// Copy one component field between entities' component lists.
+///
+/// Returns false if either side lacks the component, the slots hold
+/// different component types, or the field doesn't exist.
+pub fn copy_component_field(from: &[Component], to: &mut [Component], property_path: &str) -> bool {
+    let Some((selector, field)) = component_field_path(property_path) else {
+        return false;
+    };
+    let (Some(source), Some(target)) = (selector.find(from), selector.find_mut(to)) else {
+        return false;
+    };
+    if source.type_id() != target.type_id() {
+        return false;
+    }
+    source
+        .field_value(field)
+        .is_some_and(|value| target.set_field_value(field, value))
+}
+
+#[allow(dead_code)] // Intentionally kept for API completeness
+impl PrefabBase {
+    /// Add or update a variant override
+    pub fn set_override(&mut self, override_: PropertyOverride) {
+        self.overrides.retain(|o| {
+            !(o.entity_path == override_.entity_path && o.property_path == override_.property_path)
+        });
+        self.overrides.push(override_);
+    }
 }
 
 #[allow(dead_code)] // Intentionally kept for API completeness
@@ -341,6 +679,15 @@ impl PrefabInstance {
         })
     }
 
+    /// Check if any override touches the same field as a property, so
+    /// "transform.scale" and "transform.scale.x" count as one
+    pub fn overrides_field(&self, entity_path: &str, property_path: &str) -> bool {
+        let field = property_field(property_path);
+        self.overrides.iter().any(|o| {
+            o.entity_path == entity_path && property_field(&o.property_path) == field
+        })
+    }
+
     /// Add or update an override
     pub fn set_override(&mut self, override_: PropertyOverride) {
         // Remove existing override for same property
@@ -454,9 +801,9 @@ impl PrefabManager {
     }
 
     /// Reload a prefab and update all instances
-    pub fn reload_prefab(&mut self, path: &PathBuf) -> std::io::Result<()> {
+    pub fn reload_prefab(&mut self, path: &Path) -> std::io::Result<()> {
         let prefab = Prefab::load(path)?;
-        self.loaded_prefabs.insert(path.clone(), prefab);
+        self.loaded_prefabs.insert(path.to_path_buf(), prefab);
         // Note: Updating instances would require access to EditorState
         // This would be done at a higher level
         Ok(())
@@ -495,6 +842,9 @@ mod tests {
             name: "Child".to_string(),
             transform: Transform::default(),
             components: Vec::new(),
+            tags: Vec::new(),
+            color_label: None,
+            metadata: BTreeMap::new(),
             children: Vec::new(),
             nested_prefab: None,
         });
@@ -504,12 +854,138 @@ mod tests {
         assert_eq!(mapping.len(), 2);
     }
 
+    #[test]
+    fn test_entity_annotations_survive_prefab_round_trip() {
+        let mut child = EntityData::new("Lamp");
+        child.tags = vec!["light".to_string()];
+        child.metadata.insert("bake".to_string(), "static".to_string());
+        let child_id = EntityId::new();
+        let mut root = EntityData::new("Post");
+        root.children.push(child_id);
+        root.color_label = Some(ColorLabel::Green);
+        root.metadata.insert("lod".to_string(), "2".to_string());
+        let entities = HashMap::from([(child_id, child.clone())]);
+
+        let prefab = Prefab::from_entities("Post", &root, &entities);
+        let loaded = Prefab::from_ron(&prefab.to_ron().unwrap()).unwrap();
+
+        let (instance, _) = loaded.instantiate();
+        assert_eq!(instance.color_label, root.color_label);
+        assert_eq!(instance.metadata, root.metadata);
+        let (flat, _) = loaded.instantiate_flat();
+        assert_eq!(flat[0].metadata, root.metadata);
+        assert_eq!(flat[1].tags, child.tags);
+        assert_eq!(flat[1].metadata, child.metadata);
+    }
+
     #[test]
     fn test_prefab_serialization() {
         let prefab = Prefab::new("Serialization Test");
         let ron = prefab.to_ron().unwrap();
         let loaded = Prefab::from_ron(&ron).unwrap();
         assert_eq!(loaded.name, prefab.name);
+        assert!(!loaded.is_variant());
+    }
+
+    #[test]
+    fn test_variant_serialization() {
+        let base = Prefab::new("Base");
+        let override_ = PropertyOverride {
+            entity_path: "0".to_string(),
+            property_path: "name".to_string(),
+            value: serde_json::json!("Renamed"),
+        };
+        let variant = Prefab::new("Variant").with_base(PrefabBase {
+            prefab_path: PathBuf::from("base.prefab"),
+            prefab_id: base.id,
+            overrides: vec![override_.clone()],
+        });
+
+        let loaded = Prefab::from_ron(&variant.to_ron().unwrap()).unwrap();
+        let loaded_base = loaded.base.expect("variant should keep its base");
+        assert_eq!(loaded_base.prefab_path, PathBuf::from("base.prefab"));
+        assert_eq!(loaded_base.prefab_id, base.id);
+        assert_eq!(loaded_base.overrides, vec![override_]);
+    }
+
+    #[test]
+    fn test_free_variant_path_skips_taken_names() {
+        let dir = std::env::temp_dir().join(format!("prefab_variant_name_{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let base_path = dir.join("Crate.prefab");
+        assert_eq!(free_variant_path(&base_path), dir.join("Crate Variant.prefab"));
+
+        std::fs::write(dir.join("Crate Variant.prefab"), "").unwrap();
+        let next = free_variant_path(&base_path);
+        let _ = std::fs::remove_dir_all(&dir);
+        assert_eq!(next, dir.join("Crate Variant 2.prefab"));
+    }
+
+    #[test]
+    fn test_variant_round_trip_resolves_base() {
+        let dir = std::env::temp_dir().join(format!("prefab_variant_{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let base_path = dir.join("Crate.prefab");
+        let variant_path = dir.join("Crate Variant.prefab");
+
+        let mut base = Prefab::new("Crate");
+        base.root.name = "Crate".to_string();
+        base.save(&base_path).unwrap();
+
+        let mut variant = Prefab::variant_of("Crate Variant", &base, base_path.clone());
+        variant.base.as_mut().unwrap().set_override(PropertyOverride {
+            entity_path: "0".to_string(),
+            property_path: "transform.scale.y".to_string(),
+            value: serde_json::json!(3.0),
+        });
+        variant.save(&variant_path).unwrap();
+
+        // Later edits to the base reach the variant; its own delta still wins
+        base.root.name = "Wooden Crate".to_string();
+        base.root.transform.scale = [2.0, 2.0, 2.0];
+        base.save(&base_path).unwrap();
+
+        let loaded = Prefab::load(&variant_path).unwrap();
+        assert!(loaded.is_variant());
+        assert_eq!(loaded.root.name, "Wooden Crate");
+        assert_eq!(loaded.root.transform.scale, [2.0, 3.0, 2.0]);
+        assert_eq!(loaded.base.as_ref().unwrap().overrides.len(), 1);
+
+        let (entities, _) = loaded.instantiate_flat();
+        assert_eq!(entities[0].transform.scale, [2.0, 3.0, 2.0]);
+
+        // Without its base, the variant falls back to its stored tree
+        std::fs::remove_file(&base_path).unwrap();
+        let orphan = Prefab::load(&variant_path).unwrap();
+        assert_eq!(orphan.root.name, "Crate");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_variant_cycle_is_an_error() {
+        let path = std::env::temp_dir().join(format!("prefab_cycle_{}.prefab", Uuid::new_v4()));
+        let prefab = Prefab::new("Loop");
+        let looped = Prefab::variant_of("Loop", &prefab, path.clone());
+        looped.save(&path).unwrap();
+
+        let error = Prefab::load(&path).unwrap_err();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_entity_override_values() {
+        let mut entity = Prefab::new("Test").root;
+        assert!(entity.apply_override("transform.position", &serde_json::json!([1.0, 2.0, 3.0])));
+        assert!(entity.apply_override("transform.position.z", &serde_json::json!(5.0)));
+        assert!(entity.apply_override("name", &serde_json::json!("Renamed")));
+        assert!(!entity.apply_override("active", &serde_json::json!(false)));
+        assert!(!entity.apply_override("name", &serde_json::json!(4)));
+
+        assert_eq!(entity.transform.position, [1.0, 2.0, 5.0]);
+        assert_eq!(entity.property_value("transform.position.y"), Some(serde_json::json!(2.0)));
+        assert_eq!(entity.property_value("name"), Some(serde_json::json!("Renamed")));
     }
 
     #[test]
@@ -5,13 +5,16 @@
 //! - Project metadata (name, version, company)
 //! - Build configuration (platforms, quality settings)
 //! - Scene management (startup scene, build scene list)
-//! - Input settings
+//! - World conventions (up axis)
+//! - Input settings (legacy axes and named input actions)
 //! - Physics settings
 //! - Audio settings
 
 
+use crate::state::SceneStatistics;
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 
 /// Current project settings format version
@@ -46,6 +49,18 @@ impl TargetPlatform {
         }
     }
 
+    /// Rust target triple the platform's player is compiled for
+    pub fn target_triple(&self) -> &'static str {
+        match self {
+            TargetPlatform::Windows => "x86_64-pc-windows-msvc",
+            TargetPlatform::Linux => "x86_64-unknown-linux-gnu",
+            TargetPlatform::MacOS => "aarch64-apple-darwin",
+            TargetPlatform::WebGL => "wasm32-unknown-unknown",
+            TargetPlatform::Android => "aarch64-linux-android",
+            TargetPlatform::IOS => "aarch64-apple-ios",
+        }
+    }
+
     /// Get all available platforms
     pub fn all() -> &'static [TargetPlatform] {
         &[
@@ -130,6 +145,88 @@ pub enum TextureCompression {
     ETC2,    // Android/WebGL
 }
 
+/// World up axis convention
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum UpAxis {
+    /// +Y is up, ground plane is XZ
+    #[default]
+    Y,
+    /// +Z is up, ground plane is XY
+    Z,
+}
+
+impl UpAxis {
+    /// Standard gravity magnitude in m/s²
+    pub const STANDARD_GRAVITY: f32 = 9.81;
+
+    /// Get display name for this axis
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            UpAxis::Y => "Y-Up",
+            UpAxis::Z => "Z-Up",
+        }
+    }
+
+    /// Get all available up axes
+    pub fn all() -> &'static [UpAxis] {
+        &[UpAxis::Y, UpAxis::Z]
+    }
+
+    /// Index of the vertical component in a position vector
+    pub fn index(&self) -> usize {
+        match self {
+            UpAxis::Y => 1,
+            UpAxis::Z => 2,
+        }
+    }
+
+    /// Unit vector pointing up
+    pub fn up(&self) -> [f32; 3] {
+        let mut up = [0.0; 3];
+        up[self.index()] = 1.0;
+        up
+    }
+
+    /// Default gravity vector (standard gravity pointing down)
+    pub fn gravity(&self) -> [f32; 3] {
+        self.up().map(|v| -v * Self::STANDARD_GRAVITY)
+    }
+
+    /// Normal of the ground grid plane
+    #[allow(dead_code)] // Intentionally kept for API completeness
+    pub fn grid_normal(&self) -> [f32; 3] {
+        self.up()
+    }
+
+    /// Indices of the two horizontal axes spanning the ground plane
+    pub fn ground_axes(&self) -> [usize; 2] {
+        match self {
+            UpAxis::Y => [0, 2],
+            UpAxis::Z => [0, 1],
+        }
+    }
+
+    /// Express a vector from this convention in another one.
+    ///
+    /// Rotates 90 degrees about X so that "up" stays up and X is unchanged.
+    pub fn convert(&self, to: UpAxis, v: [f32; 3]) -> [f32; 3] {
+        match (self, to) {
+            (UpAxis::Y, UpAxis::Z) => [v[0], -v[2], v[1]],
+            (UpAxis::Z, UpAxis::Y) => [v[0], v[2], -v[1]],
+            _ => v,
+        }
+    }
+
+    /// Rotation about X (in degrees) applied by [`UpAxis::convert`]
+    pub fn conversion_angle(&self, to: UpAxis) -> f32 {
+        match (self, to) {
+            (UpAxis::Y, UpAxis::Z) => 90.0,
+            (UpAxis::Z, UpAxis::Y) => -90.0,
+            _ => 0.0,
+        }
+    }
+}
+
 /// Project metadata
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProjectMetadata {
@@ -291,7 +388,7 @@ pub struct PhysicsSettings {
 impl Default for PhysicsSettings {
     fn default() -> Self {
         Self {
-            gravity: [0.0, -9.81, 0.0],
+            gravity: UpAxis::default().gravity(),
             default_friction: 0.5,
             default_bounciness: 0.0,
             fixed_timestep: 1.0 / 60.0,
@@ -435,11 +532,129 @@ pub enum InputType {
     Joystick,
 }
 
+/// A physical input that can trigger an action
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum InputBinding {
+    /// Keyboard key by name (e.g. `Space`, `W`, `ArrowUp`)
+    Key(String),
+    /// Mouse button ("Left", "Right", "Middle")
+    MouseButton(String),
+    /// Gamepad button (e.g. "A", "B", "Start")
+    GamepadButton(String),
+}
+
+impl InputBinding {
+    /// Get the device name for this binding
+    pub fn device_name(&self) -> &'static str {
+        match self {
+            InputBinding::Key(_) => "Key",
+            InputBinding::MouseButton(_) => "Mouse",
+            InputBinding::GamepadButton(_) => "Gamepad",
+        }
+    }
+
+    /// Get the button/key name for this binding
+    pub fn button(&self) -> &str {
+        match self {
+            InputBinding::Key(name)
+            | InputBinding::MouseButton(name)
+            | InputBinding::GamepadButton(name) => name,
+        }
+    }
+
+    /// Get the button/key name for editing
+    pub fn button_mut(&mut self) -> &mut String {
+        match self {
+            InputBinding::Key(name)
+            | InputBinding::MouseButton(name)
+            | InputBinding::GamepadButton(name) => name,
+        }
+    }
+
+    /// Get display text (e.g. "Key Space", "Gamepad A")
+    #[allow(dead_code)] // Intentionally kept for API completeness
+    pub fn display_name(&self) -> String {
+        format!("{} {}", self.device_name(), self.button())
+    }
+}
+
+/// Named input actions mapped to the bindings that trigger them
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct InputMap {
+    /// Action name -> bindings (in definition order)
+    pub actions: IndexMap<String, Vec<InputBinding>>,
+}
+
+#[allow(dead_code)] // Intentionally kept for API completeness
+impl InputMap {
+    /// Create an empty input map
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an action (no-op if it already exists)
+    pub fn add_action(&mut self, name: impl Into<String>) {
+        self.actions.entry(name.into()).or_default();
+    }
+
+    /// Remove an action and all its bindings
+    pub fn remove_action(&mut self, name: &str) {
+        self.actions.shift_remove(name);
+    }
+
+    /// Bind an input to an action, creating the action if needed
+    pub fn bind(&mut self, action: impl Into<String>, binding: InputBinding) {
+        let bindings = self.actions.entry(action.into()).or_default();
+        if !bindings.contains(&binding) {
+            bindings.push(binding);
+        }
+    }
+
+    /// Get the bindings for an action
+    pub fn bindings(&self, action: &str) -> &[InputBinding] {
+        self.actions.get(action).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// Check if any binding of an action is in the pressed set
+    pub fn is_action_pressed(&self, action: &str, pressed: &HashSet<InputBinding>) -> bool {
+        self.bindings(action).iter().any(|b| pressed.contains(b))
+    }
+
+    /// Names of all actions with at least one binding in the pressed set
+    pub fn pressed_actions<'a>(&'a self, pressed: &'a HashSet<InputBinding>) -> impl Iterator<Item = &'a str> {
+        self.actions
+            .iter()
+            .filter(|(_, bindings)| bindings.iter().any(|b| pressed.contains(b)))
+            .map(|(name, _)| name.as_str())
+    }
+
+    /// Actions that share a binding with another action.
+    ///
+    /// Duplicate bindings are allowed but worth flagging in the UI.
+    pub fn duplicate_bindings(&self) -> HashMap<InputBinding, Vec<String>> {
+        let mut by_binding: HashMap<InputBinding, Vec<String>> = HashMap::new();
+        for (action, bindings) in &self.actions {
+            for binding in bindings {
+                let actions = by_binding.entry(binding.clone()).or_default();
+                if !actions.contains(action) {
+                    actions.push(action.clone());
+                }
+            }
+        }
+        by_binding.retain(|_, actions| actions.len() > 1);
+        by_binding
+    }
+}
+
 /// Input settings
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InputSettings {
     /// Input axes
     pub axes: Vec<InputAxis>,
+    /// Named input actions
+    #[serde(default)]
+    pub actions: InputMap,
 }
 
 impl Default for InputSettings {
@@ -495,10 +710,90 @@ impl Default for InputSettings {
                     ..Default::default()
                 },
             ],
+            actions: Self::default_actions(),
         }
     }
 }
 
+impl InputSettings {
+    /// Default input actions for new projects
+    fn default_actions() -> InputMap {
+        let mut map = InputMap::new();
+        map.bind("Jump", InputBinding::Key("Space".to_string()));
+        map.bind("Jump", InputBinding::GamepadButton("A".to_string()));
+        map.bind("Fire", InputBinding::MouseButton("Left".to_string()));
+        map.bind("Fire", InputBinding::GamepadButton("RightTrigger".to_string()));
+        map.bind("Interact", InputBinding::Key("E".to_string()));
+        map.bind("Interact", InputBinding::GamepadButton("X".to_string()));
+        map
+    }
+}
+
+/// Per-scene performance budgets. A limit of 0 means unlimited.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PerformanceBudgets {
+    /// Maximum number of entities
+    pub max_entities: u32,
+    /// Maximum number of lights
+    pub max_lights: u32,
+    /// Maximum number of triangles
+    pub max_triangles: u64,
+}
+
+/// Scene metric covered by a budget
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BudgetMetric {
+    /// Entity count
+    Entities,
+    /// Light count
+    Lights,
+    /// Triangle count
+    Triangles,
+}
+
+impl BudgetMetric {
+    /// Display name
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Entities => "Entities",
+            Self::Lights => "Lights",
+            Self::Triangles => "Triangles",
+        }
+    }
+}
+
+/// A scene metric over its budget
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BudgetWarning {
+    /// Metric that is over budget
+    pub metric: BudgetMetric,
+    /// Current value in the scene
+    pub value: u64,
+    /// Configured limit
+    pub limit: u64,
+}
+
+impl std::fmt::Display for BudgetWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {} exceeds budget of {}", self.metric.name(), self.value, self.limit)
+    }
+}
+
+impl PerformanceBudgets {
+    /// Warnings for every metric of `stats` over its (non-zero) budget
+    pub fn check(&self, stats: &SceneStatistics) -> Vec<BudgetWarning> {
+        [
+            (BudgetMetric::Entities, stats.entities as u64, u64::from(self.max_entities)),
+            (BudgetMetric::Lights, stats.lights as u64, u64::from(self.max_lights)),
+            (BudgetMetric::Triangles, stats.triangles, self.max_triangles),
+        ]
+        .into_iter()
+        .filter(|&(_, value, limit)| limit > 0 && value > limit)
+        .map(|(metric, value, limit)| BudgetWarning { metric, value, limit })
+        .collect()
+    }
+}
+
 /// Complete project settings
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProjectSettings {
@@ -516,6 +811,12 @@ pub struct ProjectSettings {
     pub graphics: GraphicsSettings,
     /// Input settings
     pub input: InputSettings,
+    /// Scene performance budgets
+    #[serde(default)]
+    pub budgets: PerformanceBudgets,
+    /// World up axis convention
+    #[serde(default)]
+    pub up_axis: UpAxis,
     /// Platform-specific build settings
     #[serde(default)]
     pub platform_settings: HashMap<TargetPlatform, PlatformBuildSettings>,
@@ -525,6 +826,10 @@ pub struct ProjectSettings {
     /// Current target platform
     #[serde(default)]
     pub target_platform: TargetPlatform,
+    /// Player crate compiled for each target (relative to project root);
+    /// `None` exports content only
+    #[serde(default)]
+    pub player_crate: Option<PathBuf>,
 }
 
 impl Default for ProjectSettings {
@@ -542,9 +847,12 @@ impl Default for ProjectSettings {
             audio: AudioSettings::default(),
             graphics: GraphicsSettings::default(),
             input: InputSettings::default(),
+            budgets: PerformanceBudgets::default(),
+            up_axis: UpAxis::default(),
             platform_settings,
             build_configuration: BuildConfiguration::default(),
             target_platform: TargetPlatform::default(),
+            player_crate: None,
         }
     }
 }
@@ -667,6 +975,17 @@ impl ProjectSettings {
     pub fn set_startup_scene(&mut self, path: Option<PathBuf>) {
         self.scenes.startup_scene = path;
     }
+
+    /// Switch the world up axis convention.
+    ///
+    /// Gravity is re-expressed in the new convention so it keeps pointing down.
+    pub fn set_up_axis(&mut self, up_axis: UpAxis) {
+        if self.up_axis == up_axis {
+            return;
+        }
+        self.physics.gravity = self.up_axis.convert(up_axis, self.physics.gravity);
+        self.up_axis = up_axis;
+    }
 }
 
 /// Project manager for handling project lifecycle
@@ -818,6 +1137,37 @@ mod tests {
         assert_eq!(loaded.metadata.name, "Test Project");
     }
 
+    #[test]
+    fn test_light_budget_warning() {
+        let budgets = PerformanceBudgets {
+            max_entities: 100,
+            max_lights: 4,
+            max_triangles: 0,
+        };
+        let stats = SceneStatistics {
+            entities: 20,
+            lights: 6,
+            meshes: 10,
+            triangles: 5_000_000,
+        };
+
+        let warnings = budgets.check(&stats);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].metric, BudgetMetric::Lights);
+        assert_eq!(warnings[0].to_string(), "Lights: 6 exceeds budget of 4");
+    }
+
+    #[test]
+    fn test_zero_budgets_are_unlimited() {
+        let stats = SceneStatistics {
+            entities: 10_000,
+            lights: 500,
+            meshes: 10_000,
+            triangles: u64::MAX,
+        };
+        assert!(PerformanceBudgets::default().check(&stats).is_empty());
+    }
+
     #[test]
     fn test_scene_management() {
         let mut settings = ProjectSettings::default();
@@ -834,4 +1184,93 @@ mod tests {
         settings.remove_build_scene(Path::new("Scenes/Level1.scene"));
         assert_eq!(settings.scenes.build_scenes.len(), 1);
     }
+
+    #[test]
+    fn test_input_map_serialization() {
+        let mut settings = ProjectSettings::new("Input Test");
+        settings.input.actions.bind("Dash", InputBinding::Key("LeftShift".to_string()));
+        settings.input.actions.bind("Dash", InputBinding::GamepadButton("B".to_string()));
+
+        let ron_str = ron::ser::to_string_pretty(&settings, ron::ser::PrettyConfig::default()).unwrap();
+        let loaded: ProjectSettings = ron::from_str(&ron_str).unwrap();
+        assert_eq!(loaded.input.actions, settings.input.actions);
+        assert_eq!(loaded.input.actions.bindings("Dash").len(), 2);
+
+        // Settings written before input actions existed still load
+        let legacy: InputSettings = ron::from_str("(axes: [])").unwrap();
+        assert!(legacy.actions.actions.is_empty());
+    }
+
+    #[test]
+    fn test_input_action_lookup() {
+        let mut map = InputMap::new();
+        map.bind("Jump", InputBinding::Key("Space".to_string()));
+        map.bind("Jump", InputBinding::GamepadButton("A".to_string()));
+        map.bind("Fire", InputBinding::MouseButton("Left".to_string()));
+
+        let pressed: HashSet<InputBinding> = [InputBinding::GamepadButton("A".to_string())].into();
+        assert!(map.is_action_pressed("Jump", &pressed));
+        assert!(!map.is_action_pressed("Fire", &pressed));
+        assert!(!map.is_action_pressed("Missing", &pressed));
+        assert_eq!(map.pressed_actions(&pressed).collect::<Vec<_>>(), vec!["Jump"]);
+
+        assert!(!map.is_action_pressed("Jump", &HashSet::new()));
+    }
+
+    #[test]
+    fn test_input_map_duplicate_bindings() {
+        let mut map = InputMap::new();
+        let space = InputBinding::Key("Space".to_string());
+        map.bind("Jump", space.clone());
+        map.bind("Confirm", space.clone());
+        map.bind("Fire", InputBinding::MouseButton("Left".to_string()));
+
+        // Duplicates are allowed and both actions fire
+        let pressed: HashSet<InputBinding> = [space.clone()].into();
+        assert!(map.is_action_pressed("Jump", &pressed));
+        assert!(map.is_action_pressed("Confirm", &pressed));
+
+        let duplicates = map.duplicate_bindings();
+        assert_eq!(duplicates.len(), 1);
+        assert_eq!(duplicates[&space], vec!["Jump".to_string(), "Confirm".to_string()]);
+    }
+
+    #[test]
+    fn test_up_axis_vectors() {
+        assert_eq!(UpAxis::Y.gravity(), [0.0, -9.81, 0.0]);
+        assert_eq!(UpAxis::Z.gravity(), [0.0, 0.0, -9.81]);
+        assert_eq!(UpAxis::Y.grid_normal(), [0.0, 1.0, 0.0]);
+        assert_eq!(UpAxis::Z.grid_normal(), [0.0, 0.0, 1.0]);
+
+        for axis in UpAxis::all() {
+            let [a, b] = axis.ground_axes();
+            assert_eq!(axis.grid_normal()[a], 0.0);
+            assert_eq!(axis.grid_normal()[b], 0.0);
+        }
+    }
+
+    #[test]
+    fn test_up_axis_convert() {
+        assert_eq!(UpAxis::Y.convert(UpAxis::Z, UpAxis::Y.up()), UpAxis::Z.up());
+        assert_eq!(UpAxis::Z.convert(UpAxis::Y, UpAxis::Z.up()), UpAxis::Y.up());
+
+        let v = [1.0, 2.0, 3.0];
+        assert_eq!(UpAxis::Z.convert(UpAxis::Y, UpAxis::Y.convert(UpAxis::Z, v)), v);
+        assert_eq!(UpAxis::Y.convert(UpAxis::Y, v), v);
+    }
+
+    #[test]
+    fn test_set_up_axis_updates_gravity() {
+        let mut settings = ProjectSettings::default();
+        assert_eq!(settings.physics.gravity, UpAxis::Y.gravity());
+
+        settings.set_up_axis(UpAxis::Z);
+        assert_eq!(settings.up_axis, UpAxis::Z);
+        assert_eq!(settings.physics.gravity, UpAxis::Z.gravity());
+
+        // Custom gravity keeps its magnitude and keeps pointing down
+        settings.physics.gravity = [0.0, 0.0, -20.0];
+        settings.set_up_axis(UpAxis::Y);
+        assert_eq!(settings.physics.gravity, [0.0, -20.0, 0.0]);
+    }
 }
@@ -0,0 +1,449 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//! Scene file schema migrations.
+//!
+//! A scene file is parsed in the shape of the version it was written with
+//! and upgraded one version at a time until it matches
+//! [`SCENE_FORMAT_VERSION`]. Each step is a plain function from one
+//! document shape to the next, registered in [`MIGRATIONS`], so renaming
+//! or restructuring a component only needs a legacy mirror of the changed
+//! parts plus one new entry.
+//!
+//! Migrations run before any editor state is touched: if a step fails the
+//! whole load is aborted.
+
+use crate::components::{Component, MeshRendererComponent};
+use crate::state::{EntityData, EntityId, SceneData, SceneFile, Transform, SCENE_FORMAT_VERSION};
+use indexmap::IndexMap;
+use serde::Deserialize;
+use thiserror::Error;
+
+/// Errors raised while loading or upgrading a scene file
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum MigrationError {
+    /// The file was written by a newer editor
+    #[error("Scene file version {found} is newer than supported version {supported}. Please update the editor.")]
+    UnsupportedVersion {
+        /// Version found in the file
+        found: u32,
+        /// Newest version this editor understands
+        supported: u32,
+    },
+    /// The file doesn't match the shape of its version
+    #[error("Deserialization error (scene v{version}): {message}")]
+    Parse {
+        /// Version the file was parsed as
+        version: u32,
+        /// Parser message
+        message: String,
+    },
+    /// No registered step upgrades this version
+    #[error("No migration step upgrades scene v{version}")]
+    MissingStep {
+        /// Version left without a step
+        version: u32,
+    },
+    /// A migration step rejected an entity
+    #[error("Migrating scene v{version} failed for entity '{entity}': {message}")]
+    Entity {
+        /// Version the failing step migrates from
+        version: u32,
+        /// Name of the entity that couldn't be migrated
+        entity: String,
+        /// What went wrong
+        message: String,
+    },
+}
+
+/// One schema upgrade: turns a document of version `n` into version `n + 1`
+type Migration = fn(SceneDocument) -> Result<SceneDocument, MigrationError>;
+
+/// Registered steps; the step at index `n` upgrades version `n`
+const MIGRATIONS: &[Migration] = &[v0_to_v1];
+
+/// Parse a scene file of any supported version and upgrade it to the
+/// current schema
+pub fn load_scene_file(content: &str) -> Result<SceneFile, MigrationError> {
+    let document = SceneDocument::parse(content)?;
+    let original = document.version();
+    let file = upgrade(document, MIGRATIONS)?;
+
+    if original < SCENE_FORMAT_VERSION {
+        tracing::info!("Upgraded scene from v{} to v{}", original, SCENE_FORMAT_VERSION);
+    }
+    Ok(file)
+}
+
+/// Run the steps in `steps` from the document's version on
+fn upgrade(mut document: SceneDocument, steps: &[Migration]) -> Result<SceneFile, MigrationError> {
+    let original = document.version();
+    let pending = steps
+        .get(original as usize..)
+        .ok_or(MigrationError::UnsupportedVersion {
+            found: original,
+            supported: SCENE_FORMAT_VERSION,
+        })?;
+    for step in pending {
+        document = step(document)?;
+    }
+
+    match document {
+        SceneDocument::Current(file) => Ok(file),
+        legacy => Err(MigrationError::MissingStep {
+            version: legacy.version(),
+        }),
+    }
+}
+
+/// Parse a JSON scene file and upgrade it to the current schema.
+///
+/// JSON export postdates the [`SceneFile`] wrapper, so there is no legacy
+/// layout to migrate from.
+pub fn load_scene_file_json(content: &str) -> Result<SceneFile, MigrationError> {
+    #[derive(Deserialize)]
+    struct Header {
+        version: u32,
+    }
+
+    let parse_error = |version: u32, e: serde_json::Error| MigrationError::Parse {
+        version,
+        message: e.to_string(),
+    };
+    let version = serde_json::from_str::<Header>(content)
+        .map_err(|e| parse_error(0, e))?
+        .version;
+    if version > SCENE_FORMAT_VERSION {
+        return Err(MigrationError::UnsupportedVersion {
+            found: version,
+            supported: SCENE_FORMAT_VERSION,
+        });
+    }
+
+    let mut file: SceneFile = serde_json::from_str(content).map_err(|e| parse_error(version, e))?;
+    file.version = SCENE_FORMAT_VERSION;
+    Ok(file)
+}
+
+/// A parsed scene file in the shape of its version
+enum SceneDocument {
+    /// Pre-versioning raw scene data
+    V0(v0::SceneData),
+    /// Current [`SceneFile`] layout
+    Current(SceneFile),
+}
+
+impl SceneDocument {
+    fn parse(content: &str) -> Result<Self, MigrationError> {
+        let Some(version) = header_version(content) else {
+            return ron::from_str(content).map(Self::V0).map_err(|e| MigrationError::Parse {
+                version: 0,
+                message: e.to_string(),
+            });
+        };
+
+        if version > SCENE_FORMAT_VERSION {
+            return Err(MigrationError::UnsupportedVersion {
+                found: version,
+                supported: SCENE_FORMAT_VERSION,
+            });
+        }
+
+        // v1 introduced the SceneFile wrapper, which is still current
+        ron::from_str(content)
+            .map(Self::Current)
+            .map_err(|e| MigrationError::Parse {
+                version,
+                message: e.to_string(),
+            })
+    }
+
+    fn version(&self) -> u32 {
+        match self {
+            Self::V0(_) => 0,
+            Self::Current(file) => file.version,
+        }
+    }
+}
+
+/// v0 -> v1: wrap the bare scene data in a [`SceneFile`]
+fn v0_to_v1(document: SceneDocument) -> Result<SceneDocument, MigrationError> {
+    match document {
+        SceneDocument::V0(scene) => v0::migrate(scene).map(SceneDocument::Current),
+        // Wrapped files already have the v1 shape
+        SceneDocument::Current(mut file) => {
+            file.version = 1;
+            Ok(SceneDocument::Current(file))
+        }
+    }
+}
+
+/// Version stored in a [`SceneFile`] header, or `None` for raw legacy data
+fn header_version(content: &str) -> Option<u32> {
+    #[derive(Deserialize)]
+    #[serde(rename = "SceneFile")]
+    struct Header {
+        version: u32,
+    }
+
+    ron::from_str::<Header>(content).ok().map(|header| header.version)
+}
+
+/// Pre-v1 scenes: bare `SceneData`, entities that may leave out their
+/// `active`/`is_static` flags (defaulting to active and non-static), and
+/// mesh renderers stored as `Mesh` components whose material is an index
+/// into a scene-wide material list.
+mod v0 {
+    use super::*;
+
+    #[derive(Deserialize)]
+    #[serde(rename = "SceneData")]
+    pub struct SceneData {
+        pub entities: IndexMap<EntityId, EntityData>,
+        #[serde(default)]
+        pub materials: Vec<String>,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename = "EntityData")]
+    pub struct EntityData {
+        pub name: String,
+        #[serde(default = "default_active")]
+        pub active: bool,
+        #[serde(default)]
+        pub is_static: bool,
+        #[serde(default)]
+        pub transform: Transform,
+        #[serde(default)]
+        pub parent: Option<EntityId>,
+        #[serde(default)]
+        pub children: Vec<EntityId>,
+        #[serde(default)]
+        pub components: Vec<ComponentData>,
+    }
+
+    fn default_active() -> bool {
+        true
+    }
+
+    /// Component in either its legacy or its unchanged current shape
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    pub enum ComponentData {
+        Current(Component),
+        Legacy(LegacyComponent),
+    }
+
+    #[derive(Deserialize)]
+    #[serde(tag = "type")]
+    pub enum LegacyComponent {
+        Mesh {
+            mesh: String,
+            #[serde(default)]
+            material: Option<usize>,
+        },
+    }
+
+    pub fn migrate(scene: SceneData) -> Result<SceneFile, MigrationError> {
+        let mut entities = IndexMap::with_capacity(scene.entities.len());
+
+        for (id, entity) in scene.entities {
+            let components = entity
+                .components
+                .into_iter()
+                .map(|component| migrate_component(component, &scene.materials))
+                .collect::<Result<Vec<_>, String>>()
+                .map_err(|message| MigrationError::Entity {
+                    version: 0,
+                    entity: entity.name.clone(),
+                    message,
+                })?;
+
+            entities.insert(
+                id,
+                super::EntityData {
+                    name: entity.name,
+                    active: entity.active,
+                    is_static: entity.is_static,
+                    transform: entity.transform,
+                    parent: entity.parent,
+                    children: entity.children,
+                    components,
+                    ..Default::default()
+                },
+            );
+        }
+
+        Ok(SceneFile::from_scene(
+            "Untitled Scene",
+            super::SceneData {
+                entities,
+                ..Default::default()
+            },
+        ))
+    }
+
+    fn migrate_component(component: ComponentData, materials: &[String]) -> Result<Component, String> {
+        match component {
+            ComponentData::Current(component) => Ok(component),
+            ComponentData::Legacy(LegacyComponent::Mesh { mesh, material }) => {
+                let material = match material {
+                    None => String::new(),
+                    Some(index) => materials
+                        .get(index)
+                        .cloned()
+                        .ok_or_else(|| format!("material index {index} is out of range"))?,
+                };
+                Ok(Component::MeshRenderer(MeshRendererComponent {
+                    mesh,
+                    material,
+                    ..Default::default()
+                }))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CRATE_ID: &str = "5b2f3c1e-8d7a-4e39-9a51-0c6e2b7d4f10";
+    const LAMP_ID: &str = "a4c1d9e2-3f60-4b8e-8e27-71d5f0c3b6a9";
+
+    fn v0_scene(material_index: usize) -> String {
+        format!(
+            r#"(
+                entities: {{
+                    ("{CRATE_ID}"): (
+                        name: "Crate",
+                        transform: (position: (1.0, 2.0, 3.0), rotation: (0.0, 0.0, 0.0), scale: (1.0, 1.0, 1.0)),
+                        children: [("{LAMP_ID}")],
+                        components: [
+                            (type: "Mesh", mesh: "meshes/crate.glb", material: Some({material_index})),
+                            (type: "Script", script: "scripts/crate.lua", enabled: true),
+                        ],
+                    ),
+                    ("{LAMP_ID}"): (
+                        name: "Lamp",
+                        parent: Some(("{CRATE_ID}")),
+                    ),
+                }},
+                materials: ["materials/wood.mat"],
+            )"#
+        )
+    }
+
+    #[test]
+    fn test_v0_scene_migrates_to_current() {
+        let file = load_scene_file(&v0_scene(0)).unwrap();
+        assert_eq!(file.version, SCENE_FORMAT_VERSION);
+
+        let crate_id = EntityId(CRATE_ID.parse().unwrap());
+        let lamp_id = EntityId(LAMP_ID.parse().unwrap());
+        let entity = file.scene.get(&crate_id).unwrap();
+        assert!(entity.active);
+        assert!(!entity.is_static);
+        assert_eq!(entity.transform.position, [1.0, 2.0, 3.0]);
+        assert_eq!(entity.children, vec![lamp_id]);
+        assert_eq!(
+            entity.components[0],
+            Component::MeshRenderer(MeshRendererComponent {
+                mesh: "meshes/crate.glb".to_string(),
+                material: "materials/wood.mat".to_string(),
+                ..Default::default()
+            })
+        );
+        assert_eq!(entity.components[1].type_id(), "Script");
+        assert_eq!(file.scene.get(&lamp_id).unwrap().parent, Some(crate_id));
+
+        // The migrated scene round-trips through the current format
+        let text = ron::to_string(&file).unwrap();
+        let reloaded = load_scene_file(&text).unwrap();
+        assert_eq!(ron::to_string(&reloaded.scene).unwrap(), ron::to_string(&file.scene).unwrap());
+    }
+
+    #[test]
+    fn test_saved_scene_with_components_reloads() {
+        use crate::components::{get_component_registry, LightComponent, LightType};
+        use crate::state::SceneFormat;
+
+        let mut scene = SceneData::new();
+        let mut lamp = EntityData::new("Lamp");
+        lamp.components = get_component_registry().iter().map(|info| (info.create_default)()).collect();
+        lamp.components.push(Component::Light(LightComponent {
+            light_type: LightType::Spot,
+            intensity: 4.0,
+            ..Default::default()
+        }));
+        scene.add_entity(lamp);
+        let file = SceneFile::from_scene("Lights", scene);
+
+        let text = SceneFormat::Ron.serialize(&file).unwrap();
+        let reloaded = SceneFormat::Ron.parse(&text).unwrap();
+        assert_eq!(ron::to_string(&reloaded.scene).unwrap(), ron::to_string(&file.scene).unwrap());
+        let lamp = reloaded.scene.entities.values().next().unwrap();
+        assert_eq!(lamp.components.len(), get_component_registry().len() + 1);
+    }
+
+    #[test]
+    fn test_v0_entity_flags_are_kept() {
+        let text = format!(
+            r#"(
+                entities: {{
+                    ("{CRATE_ID}"): (name: "Wall", active: false, is_static: true),
+                }},
+            )"#
+        );
+        let file = load_scene_file(&text).unwrap();
+
+        let entity = file.scene.get(&EntityId(CRATE_ID.parse().unwrap())).unwrap();
+        assert!(!entity.active);
+        assert!(entity.is_static);
+    }
+
+    #[test]
+    fn test_failed_migration_names_version_and_entity() {
+        let err = load_scene_file(&v0_scene(4)).err().unwrap();
+        assert_eq!(
+            err,
+            MigrationError::Entity {
+                version: 0,
+                entity: "Crate".to_string(),
+                message: "material index 4 is out of range".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_json_scene_round_trip() {
+        let file = load_scene_file(&v0_scene(0)).unwrap();
+        let json = serde_json::to_string_pretty(&file).unwrap();
+        let reloaded = load_scene_file_json(&json).unwrap();
+        assert_eq!(ron::to_string(&reloaded.scene).unwrap(), ron::to_string(&file.scene).unwrap());
+
+        let future = format!(r#"{{"version": {}, "name": "Future", "scene": {{"entities": {{}}}}}}"#, SCENE_FORMAT_VERSION + 1);
+        assert!(matches!(
+            load_scene_file_json(&future),
+            Err(MigrationError::UnsupportedVersion { .. })
+        ));
+    }
+
+    #[test]
+    fn test_every_older_version_has_a_step() {
+        assert_eq!(MIGRATIONS.len(), SCENE_FORMAT_VERSION as usize);
+    }
+
+    #[test]
+    fn test_gap_in_the_step_table_is_an_error() {
+        let legacy = SceneDocument::parse("(entities: {})").unwrap();
+        assert_eq!(upgrade(legacy, &[]).unwrap_err(), MigrationError::MissingStep { version: 0 });
+    }
+
+    #[test]
+    fn test_newer_version_is_rejected() {
+        let text = format!("(version: {}, name: \"Future\", scene: (entities: {{}}))", SCENE_FORMAT_VERSION + 1);
+        assert!(matches!(
+            load_scene_file(&text),
+            Err(MigrationError::UnsupportedVersion { .. })
+        ));
+    }
+}
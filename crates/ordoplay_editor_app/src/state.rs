@@ -6,16 +6,19 @@
 
 
 use crate::commands::{
-    DeleteCommand, DuplicateCommand, EditorCommand, PropertyEditCommand, PropertyEditGroupCommand,
-    PropertyEditSnapshot, ReparentCommand, SpawnCommand, TransformCommand, TransformData,
+    AlignAxis, AlignCommand, AlignMode, DeleteCommand, DistributeCommand, DuplicateCommand, EditorCommand, GroupCommand, PropertyEditCommand,
+    PropertyEditGroupCommand, PropertyEditSnapshot, ReparentCommand, SnapToGroundCommand, SpawnCommand,
+    TransformCommand, TransformData, UngroupCommand,
 };
 use crate::history::{History, HistoryError, Operation, OperationGroup, StateSnapshot};
 use crate::panel_types::PanelType;
-use crate::tools::GizmoMode;
+use crate::tools::{AxisConstraint, GizmoMode, PivotMode, SnapMode};
 use indexmap::IndexMap;
+use ordoplay_editor_graph::graphs::gameplay::{ActionInput, GameplayWorld, RayHit};
 use serde::{Deserialize, Serialize};
-use std::collections::{HashSet, VecDeque};
-use std::path::PathBuf;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
 use uuid::Uuid;
 
 /// Maximum number of recent scenes to track
@@ -170,8 +173,153 @@ impl Default for Transform {
     }
 }
 
-/// Entity data stored in the editor
+impl Transform {
+    /// Rotate a direction by this transform's euler angles.
+    ///
+    /// Rotations are applied X first, then Y, then Z.
+    pub fn rotate(&self, v: [f32; 3]) -> [f32; 3] {
+        let [x, y, z] = self.rotation.map(f32::to_radians);
+        let v = rotate_axis(v, 0, x);
+        let v = rotate_axis(v, 1, y);
+        rotate_axis(v, 2, z)
+    }
+
+    /// Inverse of [`Transform::rotate`]
+    pub fn inverse_rotate(&self, v: [f32; 3]) -> [f32; 3] {
+        let [x, y, z] = self.rotation.map(f32::to_radians);
+        let v = rotate_axis(v, 2, -z);
+        let v = rotate_axis(v, 1, -y);
+        rotate_axis(v, 0, -x)
+    }
+
+    /// Map a point from this transform's local space to its parent space
+    pub fn transform_point(&self, p: [f32; 3]) -> [f32; 3] {
+        let scaled = [p[0] * self.scale[0], p[1] * self.scale[1], p[2] * self.scale[2]];
+        let r = self.rotate(scaled);
+        [r[0] + self.position[0], r[1] + self.position[1], r[2] + self.position[2]]
+    }
+
+    /// Map a point from parent space into this transform's local space
+    pub fn inverse_transform_point(&self, p: [f32; 3]) -> [f32; 3] {
+        let offset = [p[0] - self.position[0], p[1] - self.position[1], p[2] - self.position[2]];
+        let r = self.inverse_rotate(offset);
+        let safe = |s: f32| if s.abs() < f32::EPSILON { 1.0 } else { s };
+        [r[0] / safe(self.scale[0]), r[1] / safe(self.scale[1]), r[2] / safe(self.scale[2])]
+    }
+
+    /// Euler angles (degrees) that turn local +Z towards `direction`
+    pub fn euler_facing(direction: [f32; 3]) -> [f32; 3] {
+        let length = (direction[0] * direction[0] + direction[1] * direction[1] + direction[2] * direction[2]).sqrt();
+        if length < f32::EPSILON {
+            return [0.0; 3];
+        }
+        let d = direction.map(|c| c / length);
+        let pitch = (-d[1]).clamp(-1.0, 1.0).asin();
+        let yaw = d[0].atan2(d[2]);
+        [pitch.to_degrees(), yaw.to_degrees(), 0.0]
+    }
+
+    /// Euler angles (degrees) of the rotation mapping the unit axes to
+    /// `basis` (the images of X, Y and Z, in order)
+    pub fn euler_from_basis(basis: [[f32; 3]; 3]) -> [f32; 3] {
+        let [x_axis, y_axis, z_axis] = basis;
+        let y = (-x_axis[2]).clamp(-1.0, 1.0).asin();
+        let (x, z) = if y.cos().abs() > 1e-4 {
+            (y_axis[2].atan2(z_axis[2]), x_axis[1].atan2(x_axis[0]))
+        } else {
+            // Gimbal lock: only X + Z is determined, keep it all in X
+            ((-z_axis[1]).atan2(y_axis[1]), 0.0)
+        };
+        [x.to_degrees(), y.to_degrees(), z.to_degrees()]
+    }
+}
+
+/// Rotate `v` around a single axis (0 = X, 1 = Y, 2 = Z)
+pub(crate) fn rotate_axis(v: [f32; 3], axis: usize, angle: f32) -> [f32; 3] {
+    let (sin, cos) = angle.sin_cos();
+    match axis {
+        0 => [v[0], v[1] * cos - v[2] * sin, v[1] * sin + v[2] * cos],
+        1 => [v[0] * cos + v[2] * sin, v[1], -v[0] * sin + v[2] * cos],
+        _ => [v[0] * cos - v[1] * sin, v[0] * sin + v[1] * cos, v[2]],
+    }
+}
+
+/// A transform edit committed to the undo history
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransformCommit {
+    /// Entity that was transformed
+    pub entity: EntityId,
+    /// Transform before the edit
+    pub before: Transform,
+    /// Transform after the edit
+    pub after: Transform,
+}
+
+/// Color label users assign to entities to group them visually
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ColorLabel {
+    /// Red
+    Red,
+    /// Orange
+    Orange,
+    /// Yellow
+    Yellow,
+    /// Green
+    Green,
+    /// Blue
+    Blue,
+    /// Purple
+    Purple,
+    /// Gray
+    Gray,
+}
+
+impl ColorLabel {
+    /// Every label, in picker order
+    pub const ALL: [Self; 7] = [
+        Self::Red,
+        Self::Orange,
+        Self::Yellow,
+        Self::Green,
+        Self::Blue,
+        Self::Purple,
+        Self::Gray,
+    ];
+
+    /// Display name
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Red => "Red",
+            Self::Orange => "Orange",
+            Self::Yellow => "Yellow",
+            Self::Green => "Green",
+            Self::Blue => "Blue",
+            Self::Purple => "Purple",
+            Self::Gray => "Gray",
+        }
+    }
+
+    /// Swatch color (sRGB)
+    pub fn rgb(self) -> [u8; 3] {
+        match self {
+            Self::Red => [230, 85, 80],
+            Self::Orange => [240, 150, 60],
+            Self::Yellow => [235, 210, 80],
+            Self::Green => [100, 200, 110],
+            Self::Blue => [90, 150, 240],
+            Self::Purple => [170, 110, 230],
+            Self::Gray => [150, 150, 150],
+        }
+    }
+}
+
+/// Entity data stored in the editor.
+///
+/// Components are internally tagged and the optional fields are skipped when
+/// empty, neither of which bincode can read back, so binary formats (undo
+/// snapshots) carry the entity as JSON instead.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(remote = "Self")]
 pub struct EntityData {
     /// Entity name
     pub name: String,
@@ -188,6 +336,35 @@ pub struct EntityData {
     /// Components attached to this entity
     #[serde(default)]
     pub components: Vec<crate::components::Component>,
+    /// User-defined tags
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Color label shown in the hierarchy
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color_label: Option<ColorLabel>,
+    /// Free-form key/value pipeline data (sorted for stable diffs)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub metadata: BTreeMap<String, String>,
+}
+
+impl Serialize for EntityData {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            return Self::serialize(self, serializer);
+        }
+        let json = serde_json::to_string(self).map_err(serde::ser::Error::custom)?;
+        serializer.serialize_str(&json)
+    }
+}
+
+impl<'de> Deserialize<'de> for EntityData {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            return Self::deserialize(deserializer);
+        }
+        let json = String::deserialize(deserializer)?;
+        serde_json::from_str(&json).map_err(serde::de::Error::custom)
+    }
 }
 
 impl Default for EntityData {
@@ -200,6 +377,9 @@ impl Default for EntityData {
             parent: None,
             children: Vec::new(),
             components: Vec::new(),
+            tags: Vec::new(),
+            color_label: None,
+            metadata: BTreeMap::new(),
         }
     }
 }
@@ -212,11 +392,76 @@ impl EntityData {
             ..Default::default()
         }
     }
+
+    /// Check if the entity carries a tag
+    #[allow(dead_code)] // Intentionally kept for API completeness
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+
+    /// Icon of the component that best describes the entity, or a plain
+    /// entity icon when it has none
+    pub fn icon(&self) -> &'static str {
+        self.components
+            .iter()
+            .min_by_key(|component| component.icon_priority())
+            .map_or(crate::components::ENTITY_ICON, crate::components::Component::icon)
+    }
 }
 
 /// Current scene file format version
 pub const SCENE_FORMAT_VERSION: u32 = 1;
 
+/// Text encoding of a scene file on disk
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SceneFormat {
+    /// RON, the editor's native format
+    Ron,
+    /// JSON, for external tooling
+    Json,
+}
+
+impl SceneFormat {
+    /// Format implied by a file's extension; anything but `.json` is RON
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("json") => Self::Json,
+            _ => Self::Ron,
+        }
+    }
+
+    /// Format of a file's contents, trusting the extension first. A scene
+    /// file is never a bare RON map, so a leading `{` means JSON.
+    pub fn detect(path: &Path, content: &str) -> Self {
+        match Self::from_path(path) {
+            Self::Ron if content.trim_start().starts_with('{') => Self::Json,
+            format => format,
+        }
+    }
+
+    /// Serialize a scene file in this format
+    pub fn serialize(&self, file: &SceneFile) -> Result<String, String> {
+        match self {
+            Self::Ron => {
+                let config = ron::ser::PrettyConfig::default()
+                    .struct_names(true)
+                    .enumerate_arrays(false);
+                ron::ser::to_string_pretty(file, config).map_err(|e| format!("Serialization error: {}", e))
+            }
+            Self::Json => serde_json::to_string_pretty(file).map_err(|e| format!("Serialization error: {}", e)),
+        }
+    }
+
+    /// Parse a scene file in this format, upgrading it to the current schema
+    pub fn parse(&self, content: &str) -> Result<SceneFile, String> {
+        let result = match self {
+            Self::Ron => crate::scene_migration::load_scene_file(content),
+            Self::Json => crate::scene_migration::load_scene_file_json(content),
+        };
+        result.map_err(|e| e.to_string())
+    }
+}
+
 /// Scene file format with versioning and metadata
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SceneFile {
@@ -275,34 +520,179 @@ impl SceneFile {
         let duration = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .unwrap_or_default();
-        // Simple ISO 8601 timestamp (without chrono dependency)
-        let secs = duration.as_secs();
-        let days = secs / 86400;
-        let time = secs % 86400;
-        let hours = time / 3600;
-        let mins = (time % 3600) / 60;
-        let secs = time % 60;
-        // Approximate date calculation (not accounting for leap years precisely)
-        let years = 1970 + days / 365;
-        let remaining_days = days % 365;
-        let months = remaining_days / 30 + 1;
-        let day = remaining_days % 30 + 1;
-        format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
-            years, months.min(12), day.min(31), hours, mins, secs)
+        format_timestamp(duration.as_secs())
     }
 }
 
+/// Format seconds since the UNIX epoch as an ISO 8601 UTC timestamp
+fn format_timestamp(unix_secs: u64) -> String {
+    let days = unix_secs / 86400;
+    let time = unix_secs % 86400;
+    let (year, month, day) = civil_from_days(days);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        time / 3600,
+        (time % 3600) / 60,
+        time % 60
+    )
+}
+
+/// Gregorian (year, month, day) of a day count since 1970-01-01.
+///
+/// Howard Hinnant's `civil_from_days`: shift the epoch to 0000-03-01 so
+/// leap days fall at the end of each year, then split into 400-year eras.
+fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let day_of_era = z % 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153; // March = 0
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+    (year, month, day)
+}
+
 impl Default for SceneFile {
     fn default() -> Self {
         Self::new("Untitled Scene")
     }
 }
 
+/// Trait shared between entities for "Select Similar"
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SimilarityCriterion {
+    /// Same name once numeric/copy suffixes are stripped (`Tree_01` ~ `Tree (2)`)
+    NamePrefix,
+    /// Same set of component types, regardless of order or field values
+    ComponentSet,
+    /// Mesh renderer referencing the same mesh asset
+    SameMesh,
+    /// Mesh renderer referencing the same material asset
+    SameMaterial,
+    /// At least one tag in common
+    SameTag,
+}
+
+impl SimilarityCriterion {
+    /// All criteria, in menu order
+    pub fn all() -> &'static [SimilarityCriterion] {
+        &[
+            Self::NamePrefix,
+            Self::ComponentSet,
+            Self::SameMesh,
+            Self::SameMaterial,
+            Self::SameTag,
+        ]
+    }
+
+    /// Display name for menus
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::NamePrefix => "Same Name Prefix",
+            Self::ComponentSet => "Same Components",
+            Self::SameMesh => "Same Mesh",
+            Self::SameMaterial => "Same Material",
+            Self::SameTag => "Same Tag",
+        }
+    }
+}
+
+/// Last modification time of a file, if it exists
+fn file_mtime(path: &Path) -> Option<std::time::SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Strip numbering and copy suffixes from an entity name (`Crate_02`, `Crate (Copy)` -> `Crate`)
+fn name_prefix(name: &str) -> &str {
+    let mut trimmed = name.trim_end();
+    loop {
+        let next = trimmed
+            .strip_suffix("(Copy)")
+            .unwrap_or(trimmed)
+            .trim_end_matches(|c: char| {
+                c.is_ascii_digit() || c.is_whitespace() || matches!(c, '_' | '-' | '.' | '(' | ')')
+            });
+        if next.len() == trimmed.len() {
+            break;
+        }
+        trimmed = next;
+    }
+
+    if trimmed.is_empty() {
+        name
+    } else {
+        trimmed
+    }
+}
+
+/// Scene-wide ambient lighting and fog
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EnvironmentSettings {
+    /// Ambient light color (linear RGB)
+    pub ambient_color: [f32; 3],
+    /// Ambient light intensity
+    pub ambient_intensity: f32,
+    /// Whether distance fog is enabled
+    pub fog_enabled: bool,
+    /// Fog color (linear RGB)
+    pub fog_color: [f32; 3],
+    /// Distance at which fog starts
+    pub fog_start: f32,
+    /// Distance at which fog is fully opaque
+    pub fog_end: f32,
+}
+
+impl Default for EnvironmentSettings {
+    fn default() -> Self {
+        Self {
+            ambient_color: [0.4, 0.4, 0.45],
+            ambient_intensity: 0.3,
+            fog_enabled: false,
+            fog_color: [0.5, 0.55, 0.6],
+            fog_start: 20.0,
+            fog_end: 100.0,
+        }
+    }
+}
+
+impl EnvironmentSettings {
+    /// Ambient color scaled by intensity
+    pub fn ambient(&self) -> [f32; 3] {
+        self.ambient_color.map(|c| c * self.ambient_intensity)
+    }
+}
+
+/// Linear fog amount at `distance`: 0 before `start`, 1 at or past `end`.
+///
+/// A degenerate range (`end <= start`) switches fog fully on at `start`.
+#[allow(dead_code)] // Intentionally kept for API completeness
+pub fn fog_factor(distance: f32, start: f32, end: f32) -> f32 {
+    if distance <= start {
+        return 0.0;
+    }
+    if end <= start || distance >= end {
+        return 1.0;
+    }
+    (distance - start) / (end - start)
+}
+
 /// Scene data containing all entities
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SceneData {
     /// All entities in the scene
     pub entities: IndexMap<EntityId, EntityData>,
+    /// Ambient lighting and fog
+    #[serde(default)]
+    pub environment: EnvironmentSettings,
+    /// Named entity sets for quick re-selection
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub selection_groups: IndexMap<String, Vec<EntityId>>,
 }
 
 impl SceneData {
@@ -323,6 +713,242 @@ impl SceneData {
         self.entities.insert(id, data).is_none()
     }
 
+    /// Map many points in an entity's local space to world space, walking
+    /// the parent chain once; empty if the entity doesn't exist
+    pub fn world_points(&self, id: EntityId, locals: &[[f32; 3]]) -> Vec<[f32; 3]> {
+        // The local-to-world map is affine, so the images of the origin
+        // and the unit axes determine it
+        let Some(origin) = self.world_point(id, [0.0; 3]) else {
+            return Vec::new();
+        };
+        let axes = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]].map(|axis| {
+            let image = self.world_point(id, axis).unwrap_or(origin);
+            [image[0] - origin[0], image[1] - origin[1], image[2] - origin[2]]
+        });
+        locals
+            .iter()
+            .map(|p| std::array::from_fn(|i| origin[i] + p[0] * axes[0][i] + p[1] * axes[1][i] + p[2] * axes[2][i]))
+            .collect()
+    }
+
+    /// Map a point in an entity's local space to world space
+    pub fn world_point(&self, id: EntityId, local: [f32; 3]) -> Option<[f32; 3]> {
+        let mut point = local;
+        let mut current = Some(id);
+        let mut depth = 0;
+        while let Some(entity_id) = current {
+            let entity = self.entities.get(&entity_id)?;
+            point = entity.transform.transform_point(point);
+            current = entity.parent;
+            depth += 1;
+            if depth > self.entities.len() {
+                break; // Parent cycle
+            }
+        }
+        Some(point)
+    }
+
+    /// Check that an entity and all of its ancestors are active
+    pub fn is_active_in_hierarchy(&self, id: EntityId) -> bool {
+        let mut current = Some(id);
+        let mut depth = 0;
+        while let Some(entity_id) = current {
+            let Some(entity) = self.entities.get(&entity_id) else {
+                return false;
+            };
+            if !entity.active {
+                return false;
+            }
+            current = entity.parent;
+            depth += 1;
+            if depth > self.entities.len() {
+                break; // Parent cycle
+            }
+        }
+        true
+    }
+
+    /// Get an entity's world-space position
+    pub fn world_position(&self, id: EntityId) -> Option<[f32; 3]> {
+        self.world_point(id, [0.0; 3])
+    }
+
+    /// World-space axis-aligned bounds (min, max) of an entity's components,
+    /// or just its position when no component has an extent
+    pub fn world_bounds(&self, id: EntityId) -> Option<([f32; 3], [f32; 3])> {
+        let entity = self.entities.get(&id)?;
+        let corners: Vec<[f32; 3]> = entity
+            .components
+            .iter()
+            .filter_map(crate::components::Component::local_bounds)
+            .flat_map(|(min, max)| {
+                (0..8).map(move |corner: usize| {
+                    std::array::from_fn(|axis| if corner & (1 << axis) == 0 { min[axis] } else { max[axis] })
+                })
+            })
+            .filter_map(|local| self.world_point(id, local))
+            .collect();
+        if corners.is_empty() {
+            let position = self.world_position(id)?;
+            return Some((position, position));
+        }
+
+        let mut min = [f32::MAX; 3];
+        let mut max = [f32::MIN; 3];
+        for corner in &corners {
+            for axis in 0..3 {
+                min[axis] = min[axis].min(corner[axis]);
+                max[axis] = max[axis].max(corner[axis]);
+            }
+        }
+        Some((min, max))
+    }
+
+    /// Map a world-space point into the space of children of `parent`
+    pub fn local_point(&self, parent: Option<EntityId>, world: [f32; 3]) -> [f32; 3] {
+        self.parent_chain(parent)
+            .iter()
+            .rev()
+            .fold(world, |point, transform| transform.inverse_transform_point(point))
+    }
+
+    /// Map a world-space direction into the space of children of `parent`
+    pub fn local_direction(&self, parent: Option<EntityId>, world: [f32; 3]) -> [f32; 3] {
+        self.parent_chain(parent)
+            .iter()
+            .rev()
+            .fold(world, |dir, transform| transform.inverse_rotate(dir))
+    }
+
+    /// Get an entity's world transform.
+    ///
+    /// Scale is accumulated per axis, so shear from non-uniform scale under
+    /// a rotated parent is not represented.
+    pub fn world_transform(&self, id: EntityId) -> Option<Transform> {
+        let chain = self.parent_chain(Some(id));
+        if chain.is_empty() {
+            return None;
+        }
+
+        let basis = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]]
+            .map(|axis| chain.iter().fold(axis, |v, transform| transform.rotate(v)));
+        let scale = chain.iter().fold([1.0; 3], |scale, transform| {
+            [scale[0] * transform.scale[0], scale[1] * transform.scale[1], scale[2] * transform.scale[2]]
+        });
+
+        Some(Transform {
+            position: self.world_position(id)?,
+            rotation: Transform::euler_from_basis(basis),
+            scale,
+        })
+    }
+
+    /// Local transform under `parent` that places a child at `world`
+    pub fn local_transform(&self, parent: Option<EntityId>, world: &Transform) -> Transform {
+        let basis = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]]
+            .map(|axis| self.local_direction(parent, world.rotate(axis)));
+        let parent_scale = self.parent_chain(parent).iter().fold([1.0; 3], |scale, transform| {
+            [scale[0] * transform.scale[0], scale[1] * transform.scale[1], scale[2] * transform.scale[2]]
+        });
+        let safe = |s: f32| if s.abs() < f32::EPSILON { 1.0 } else { s };
+
+        Transform {
+            position: self.local_point(parent, world.position),
+            rotation: Transform::euler_from_basis(basis),
+            scale: [
+                world.scale[0] / safe(parent_scale[0]),
+                world.scale[1] / safe(parent_scale[1]),
+                world.scale[2] / safe(parent_scale[2]),
+            ],
+        }
+    }
+
+    /// Ancestors of `id` from its parent up to the root.
+    ///
+    /// Stops early at a missing entity or a parent cycle.
+    pub fn ancestors(&self, id: EntityId) -> Vec<EntityId> {
+        let mut ancestors = Vec::new();
+        let mut current = self.entities.get(&id).and_then(|entity| entity.parent);
+        while let Some(ancestor) = current {
+            if ancestor == id || ancestors.contains(&ancestor) {
+                break; // Parent cycle
+            }
+            ancestors.push(ancestor);
+            current = self.entities.get(&ancestor).and_then(|entity| entity.parent);
+        }
+        ancestors
+    }
+
+    /// Transforms from `start` up to the root, innermost first
+    fn parent_chain(&self, start: Option<EntityId>) -> Vec<&Transform> {
+        let mut chain = Vec::new();
+        let mut current = start;
+        while let Some(entity_id) = current {
+            let Some(entity) = self.entities.get(&entity_id) else {
+                break;
+            };
+            chain.push(&entity.transform);
+            current = entity.parent;
+            if chain.len() > self.entities.len() {
+                break; // Parent cycle
+            }
+        }
+        chain
+    }
+
+    /// Entities carrying `tag`, in scene order
+    #[allow(dead_code)] // Intentionally kept for API completeness
+    pub fn entities_with_tag(&self, tag: &str) -> Vec<EntityId> {
+        self.entities
+            .iter()
+            .filter(|(_, data)| data.has_tag(tag))
+            .map(|(id, _)| *id)
+            .collect()
+    }
+
+    /// Replace an entity's tags. Returns false if the entity doesn't exist.
+    pub fn set_tags(&mut self, id: EntityId, tags: Vec<String>) -> bool {
+        let Some(entity) = self.entities.get_mut(&id) else {
+            return false;
+        };
+        entity.tags = tags;
+        true
+    }
+
+    /// Set or clear an entity's color label. Returns false if the entity
+    /// doesn't exist.
+    pub fn set_color_label(&mut self, id: EntityId, label: Option<ColorLabel>) -> bool {
+        let Some(entity) = self.entities.get_mut(&id) else {
+            return false;
+        };
+        entity.color_label = label;
+        true
+    }
+
+    /// Get a metadata value on an entity
+    pub fn get_metadata(&self, id: EntityId, key: &str) -> Option<&str> {
+        self.entities.get(&id)?.metadata.get(key).map(String::as_str)
+    }
+
+    /// Set (or with `None`, remove) a metadata value on an entity.
+    ///
+    /// Returns false if the entity doesn't exist. Use
+    /// [`EditorState::set_metadata`] for an undoable edit.
+    pub fn set_metadata(&mut self, id: EntityId, key: &str, value: Option<String>) -> bool {
+        let Some(entity) = self.entities.get_mut(&id) else {
+            return false;
+        };
+        match value {
+            Some(value) => {
+                entity.metadata.insert(key.to_string(), value);
+            }
+            None => {
+                entity.metadata.remove(key);
+            }
+        }
+        true
+    }
+
     /// Get an entity by ID
     pub fn get(&self, id: &EntityId) -> Option<&EntityData> {
         self.entities.get(id)
@@ -346,6 +972,140 @@ impl SceneData {
             .map(|(id, _)| *id)
             .collect()
     }
+
+    /// The entity and its siblings in hierarchy order: its parent's
+    /// children, or the root entities in scene order
+    pub fn siblings(&self, id: EntityId) -> Vec<EntityId> {
+        match self.entities.get(&id).and_then(|entity| entity.parent) {
+            Some(parent) => self.entities.get(&parent).map(|p| p.children.clone()).unwrap_or_default(),
+            None => self.root_entities(),
+        }
+    }
+
+    /// Reorder the children of `parent` (or the root entities) to follow
+    /// `order`. Siblings missing from `order` keep their relative order at
+    /// the end. Root entities are moved within the scene's entity order.
+    pub fn set_sibling_order(&mut self, parent: Option<EntityId>, order: &[EntityId]) -> bool {
+        let rank = |id: &EntityId| order.iter().position(|o| o == id).unwrap_or(usize::MAX);
+        match parent {
+            Some(parent) => {
+                let Some(parent) = self.entities.get_mut(&parent) else {
+                    return false;
+                };
+                parent.children.sort_by_key(rank);
+            }
+            None => {
+                // Sort the roots into the slots roots already occupy, so
+                // child entities keep their positions
+                let slots: Vec<usize> = self
+                    .entities
+                    .values()
+                    .enumerate()
+                    .filter(|(_, entity)| entity.parent.is_none())
+                    .map(|(index, _)| index)
+                    .collect();
+                let mut roots = self.root_entities();
+                roots.sort_by_key(rank);
+                for (slot, id) in slots.into_iter().zip(roots) {
+                    if let Some(index) = self.entities.get_index_of(&id) {
+                        self.entities.swap_indices(index, slot);
+                    }
+                }
+            }
+        }
+        true
+    }
+
+    /// Find all entities sharing a trait with the reference entity.
+    ///
+    /// The reference itself is always included when it exists. Results are in scene order.
+    pub fn select_similar(&self, reference: EntityId, criterion: SimilarityCriterion) -> Vec<EntityId> {
+        let Some(reference_data) = self.get(&reference) else {
+            return Vec::new();
+        };
+
+        let mesh_renderer = |data: &EntityData| {
+            data.components.iter().find_map(|c| match c {
+                crate::components::Component::MeshRenderer(renderer) => Some(renderer.clone()),
+                _ => None,
+            })
+        };
+
+        let matches: Box<dyn Fn(&EntityData) -> bool> = match criterion {
+            SimilarityCriterion::NamePrefix => {
+                let prefix = name_prefix(&reference_data.name).to_lowercase();
+                Box::new(move |data| name_prefix(&data.name).to_lowercase() == prefix)
+            }
+            SimilarityCriterion::ComponentSet => {
+                let types: std::collections::BTreeSet<_> =
+                    reference_data.components.iter().map(crate::components::Component::type_id).collect();
+                Box::new(move |data| {
+                    data.components.iter().map(crate::components::Component::type_id).collect::<std::collections::BTreeSet<_>>() == types
+                })
+            }
+            SimilarityCriterion::SameMesh => {
+                let Some(mesh) = mesh_renderer(reference_data).map(|r| r.mesh).filter(|m| !m.is_empty()) else {
+                    return vec![reference];
+                };
+                Box::new(move |data| mesh_renderer(data).is_some_and(|r| r.mesh == mesh))
+            }
+            SimilarityCriterion::SameMaterial => {
+                let Some(material) = mesh_renderer(reference_data).map(|r| r.material).filter(|m| !m.is_empty()) else {
+                    return vec![reference];
+                };
+                Box::new(move |data| mesh_renderer(data).is_some_and(|r| r.material == material))
+            }
+            SimilarityCriterion::SameTag => {
+                if reference_data.tags.is_empty() {
+                    return vec![reference];
+                }
+                let tags = reference_data.tags.clone();
+                Box::new(move |data| data.tags.iter().any(|t| tags.contains(t)))
+            }
+        };
+
+        self.entities
+            .iter()
+            .filter(|(id, data)| **id == reference || matches(data))
+            .map(|(id, _)| *id)
+            .collect()
+    }
+
+    /// Count entities, lights, meshes and triangles in the scene.
+    ///
+    /// `mesh_triangles` reports the triangle count of a mesh asset path;
+    /// meshes it can't measure contribute no triangles.
+    pub fn statistics(&self, mut mesh_triangles: impl FnMut(&str) -> Option<u64>) -> SceneStatistics {
+        let mut stats = SceneStatistics {
+            entities: self.entities.len(),
+            ..Default::default()
+        };
+
+        for component in self.entities.values().flat_map(|entity| &entity.components) {
+            match component {
+                crate::components::Component::Light(_) => stats.lights += 1,
+                crate::components::Component::MeshRenderer(renderer) if !renderer.mesh.is_empty() => {
+                    stats.meshes += 1;
+                    stats.triangles += mesh_triangles(&renderer.mesh).unwrap_or(0);
+                }
+                _ => {}
+            }
+        }
+        stats
+    }
+}
+
+/// Scene-wide counts used by performance budgets
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SceneStatistics {
+    /// Number of entities
+    pub entities: usize,
+    /// Number of light components
+    pub lights: usize,
+    /// Number of mesh renderers with a mesh assigned
+    pub meshes: usize,
+    /// Total triangles across measurable meshes
+    pub triangles: u64,
 }
 
 /// Main editor state
@@ -362,20 +1122,35 @@ pub struct EditorState {
     /// Current gizmo mode
     pub gizmo_mode: GizmoMode,
 
+    /// Axis constraint for gizmo dragging (set by modal sequences like G then X)
+    pub axis_constraint: AxisConstraint,
+
+    /// Reference animation poses drawn as ghosts in the viewport
+    pub animation_ghosts: Vec<crate::animation_ghost::AnimationGhost>,
+
+    /// Eyedropper for filling entity reference fields
+    pub entity_picker: crate::entity_ref::EntityPicker,
+
     /// Current scene file path
     pub scene_path: Option<PathBuf>,
 
     /// Whether the scene has unsaved changes
     pub dirty: bool,
 
+    /// Modification time of the scene file when it was last saved or loaded
+    scene_file_mtime: Option<std::time::SystemTime>,
+
     /// Current select mode (for multi-select)
     pub select_mode: SelectMode,
 
     /// Coordinate space (local vs world)
     pub use_world_space: bool,
 
-    /// Snap to grid enabled
-    pub snap_enabled: bool,
+    /// What multi-selections rotate and scale around
+    pub pivot_mode: PivotMode,
+
+    /// How translate drags snap; rotation and scale snap in grid mode
+    pub snap_mode: SnapMode,
 
     /// Grid snap size
     pub snap_size: f32,
@@ -386,18 +1161,33 @@ pub struct EditorState {
     /// Scale snap increment
     pub scale_snap: f32,
 
+    /// World position of a translation snap grid point
+    pub snap_origin: [f32; 3],
+
+    /// Rotation of the translation snap grid about the up axis in degrees
+    pub snap_rotation: f32,
+
+    /// Snap to alignment guides while translating
+    pub alignment_snap: bool,
+
     /// Recent scenes list
     pub recent_scenes: VecDeque<PathBuf>,
 
     /// Panels requested to open
     pending_panels: Vec<PanelType>,
 
+    /// Transform edits committed to history since last taken
+    transform_commits: Vec<TransformCommit>,
+
     /// Prefab manager for prefab instances
     pub prefab_manager: crate::prefab::PrefabManager,
 
     /// Currently selected asset path in asset browser
     pub selected_asset: Option<PathBuf>,
 
+    /// Component copied in the inspector, for pasting onto other entities
+    pub component_clipboard: Option<crate::components::Component>,
+
     /// Entity to create a prefab from (shows dialog when Some)
     pub show_create_prefab_dialog: Option<EntityId>,
 
@@ -421,6 +1211,9 @@ pub struct EditorState {
 
     /// Audio engine for playback
     pub audio_engine: crate::audio::AudioEngine,
+
+    /// Background scene validator
+    pub validator: crate::validation::SceneValidator,
 }
 
 /// Physics debug visualization settings
@@ -435,6 +1228,8 @@ pub struct PhysicsDebugSettings {
     pub show_contacts: bool,
     /// Show collision layer info
     pub show_layers: bool,
+    /// Log trigger enter/exit events during play
+    pub log_trigger_events: bool,
     /// Collider wireframe color
     pub collider_color: [f32; 4],
     /// Trigger volume color
@@ -452,6 +1247,7 @@ impl Default for PhysicsDebugSettings {
             show_velocities: false,
             show_contacts: false,
             show_layers: false,
+            log_trigger_events: false,
             collider_color: [0.0, 1.0, 0.0, 0.8], // Green
             trigger_color: [1.0, 1.0, 0.0, 0.5],  // Yellow
             velocity_color: [0.0, 0.0, 1.0, 1.0], // Blue
@@ -506,18 +1302,28 @@ impl EditorState {
             scene,
             history: History::new(),
             gizmo_mode: GizmoMode::Translate,
+            axis_constraint: AxisConstraint::None,
+            animation_ghosts: Vec::new(),
+            entity_picker: crate::entity_ref::EntityPicker::default(),
             scene_path: None,
             dirty: false,
+            scene_file_mtime: None,
             select_mode: SelectMode::Set,
             use_world_space: true,
-            snap_enabled: false,
+            pivot_mode: PivotMode::MedianPoint,
+            snap_mode: SnapMode::None,
             snap_size: 1.0,
             rotation_snap: 15.0,
             scale_snap: 0.1,
+            snap_origin: [0.0; 3],
+            snap_rotation: 0.0,
+            alignment_snap: true,
             recent_scenes: VecDeque::new(),
             pending_panels: Vec::new(),
+            transform_commits: Vec::new(),
             prefab_manager: crate::prefab::PrefabManager::new(),
             selected_asset: None,
+            component_clipboard: None,
             show_create_prefab_dialog: None,
             editing_prefab: None,
             project_manager: crate::project::ProjectManager::new(),
@@ -526,6 +1332,7 @@ impl EditorState {
             physics_world: crate::physics::PhysicsWorld::new(),
             physics_debug: PhysicsDebugSettings::default(),
             audio_engine: crate::audio::AudioEngine::new(),
+            validator: crate::validation::SceneValidator::new(),
         }
     }
 
@@ -535,8 +1342,10 @@ impl EditorState {
         self.selection.clear();
         self.scene = SceneData::new();
         self.history.clear();
+        self.validator.clear();
         self.scene_path = None;
         self.dirty = false;
+        self.scene_file_mtime = None;
         tracing::info!("Created new scene");
     }
 
@@ -550,7 +1359,7 @@ impl EditorState {
     }
 
     /// Save the current scene to a specific path
-    pub fn save_scene_to_path(&mut self, path: &std::path::Path) -> Result<(), String> {
+    pub fn save_scene_to_path(&mut self, path: &Path) -> Result<(), String> {
         // Extract scene name from path or use existing
         let name = path.file_stem()
             .and_then(|s| s.to_str())
@@ -561,21 +1370,16 @@ impl EditorState {
         let mut scene_file = SceneFile::from_scene(name, self.scene.clone());
         scene_file.touch(); // Update modified timestamp
 
-        // Configure RON pretty printing
-        let config = ron::ser::PrettyConfig::default()
-            .struct_names(true)
-            .enumerate_arrays(false);
-
-        // Serialize scene file to RON format
-        let ron_str = ron::ser::to_string_pretty(&scene_file, config)
-            .map_err(|e| format!("Serialization error: {}", e))?;
+        // Serialize in the format implied by the extension (RON unless .json)
+        let text = SceneFormat::from_path(path).serialize(&scene_file)?;
 
         // Write to file
-        std::fs::write(path, ron_str)
+        std::fs::write(path, text)
             .map_err(|e| format!("File write error: {}", e))?;
 
         self.scene_path = Some(path.to_path_buf());
         self.dirty = false;
+        self.scene_file_mtime = file_mtime(path);
 
         // Add to recent scenes
         self.add_to_recent(path.to_path_buf());
@@ -585,41 +1389,24 @@ impl EditorState {
     }
 
     /// Load a scene from a file
-    pub fn load_scene(&mut self, path: &std::path::Path) -> Result<(), String> {
+    pub fn load_scene(&mut self, path: &Path) -> Result<(), String> {
         // Read file contents
         let content = std::fs::read_to_string(path)
             .map_err(|e| format!("File read error: {}", e))?;
 
-        // Try to load as new versioned format first
-        let scene = if let Ok(scene_file) = ron::from_str::<SceneFile>(&content) {
-            // Check version compatibility
-            if scene_file.version > SCENE_FORMAT_VERSION {
-                return Err(format!(
-                    "Scene file version {} is newer than supported version {}. Please update the editor.",
-                    scene_file.version, SCENE_FORMAT_VERSION
-                ));
-            }
-            if scene_file.version < SCENE_FORMAT_VERSION {
-                tracing::info!(
-                    "Upgrading scene from v{} to v{}",
-                    scene_file.version, SCENE_FORMAT_VERSION
-                );
-            }
-            tracing::info!("Loaded scene '{}' v{}", scene_file.name, scene_file.version);
-            scene_file.scene
-        } else {
-            // Fall back to legacy format (raw SceneData)
-            tracing::info!("Loading legacy scene format (pre-v1)");
-            ron::from_str::<SceneData>(&content)
-                .map_err(|e| format!("Deserialization error: {}", e))?
-        };
+        // Parse and upgrade to the current schema before touching any state
+        let scene_file = SceneFormat::detect(path, &content).parse(&content)?;
+        tracing::info!("Loaded scene '{}' v{}", scene_file.name, scene_file.version);
+        let scene = scene_file.scene;
 
         // Update state
         self.scene = scene;
         self.selection.clear();
         self.history.clear();
+        self.validator.clear();
         self.scene_path = Some(path.to_path_buf());
         self.dirty = false;
+        self.scene_file_mtime = file_mtime(path);
 
         // Add to recent scenes
         self.add_to_recent(path.to_path_buf());
@@ -628,6 +1415,14 @@ impl EditorState {
         Ok(())
     }
 
+    /// Check if the open scene file was modified outside the editor
+    pub fn scene_changed_on_disk(&self) -> bool {
+        match &self.scene_path {
+            Some(path) => file_mtime(path) != self.scene_file_mtime,
+            None => false,
+        }
+    }
+
     /// Check if scene has unsaved changes
     pub fn has_unsaved_changes(&self) -> bool {
         self.dirty
@@ -693,18 +1488,104 @@ impl EditorState {
         }
     }
 
-    /// Delete selected entities
-    pub fn delete_selected(&mut self) {
-        if self.selection.is_empty() {
+    /// Replace the selection with entities similar to the primary selection
+    pub fn select_similar(&mut self, criterion: SimilarityCriterion) {
+        let Some(reference) = self.selection.primary().copied() else {
             return;
-        }
+        };
 
-        let ids = self.selection.entities.clone();
-        self.delete_entities_with_command(&ids);
+        let similar = self.scene.select_similar(reference, criterion);
+        let previous_mode = std::mem::replace(&mut self.select_mode, SelectMode::Set);
+        self.select(&similar);
+        self.select_mode = previous_mode;
+        tracing::info!("Selected {} entities ({})", similar.len(), criterion.name());
     }
 
-    /// Duplicate selected entities
-    pub fn duplicate_selected(&mut self) {
+    /// Store the current selection under `name`, replacing any group with
+    /// that name. Groups are saved with the scene.
+    pub fn store_selection_group(&mut self, name: &str) -> bool {
+        let name = name.trim();
+        if name.is_empty() {
+            return false;
+        }
+        self.scene
+            .selection_groups
+            .insert(name.to_string(), self.selection.entities.clone());
+        self.dirty = true;
+        true
+    }
+
+    /// Select the entities of a stored group using the current select mode.
+    ///
+    /// Entities that no longer exist are skipped. Returns false if there is
+    /// no such group.
+    pub fn recall_selection_group(&mut self, name: &str) -> bool {
+        let Some(group) = self.scene.selection_groups.get(name) else {
+            return false;
+        };
+        let alive: Vec<EntityId> = group
+            .iter()
+            .filter(|id| self.scene.entities.contains_key(*id))
+            .copied()
+            .collect();
+        self.select(&alive);
+        true
+    }
+
+    /// Delete a stored selection group
+    pub fn delete_selection_group(&mut self, name: &str) -> bool {
+        if self.scene.selection_groups.shift_remove(name).is_none() {
+            return false;
+        }
+        self.dirty = true;
+        true
+    }
+
+    /// Delete selected entities
+    pub fn delete_selected(&mut self) {
+        if self.selection.is_empty() {
+            return;
+        }
+
+        let ids = self.selection.entities.clone();
+        self.delete_entities_with_command(&ids);
+    }
+
+    /// Group the selected entities under a new empty entity at their centroid
+    pub fn group_selected(&mut self) -> Option<EntityId> {
+        if self.selection.is_empty() {
+            return None;
+        }
+
+        let command = GroupCommand::new(self.selection.entities.clone());
+        if let Err(err) = self.execute_command(&command) {
+            tracing::warn!("Group command failed: {}", err);
+            return None;
+        }
+        Some(command.group_id)
+    }
+
+    /// Dissolve the selected groups, keeping their children in place
+    pub fn ungroup_selected(&mut self) {
+        let groups: Vec<EntityId> = self
+            .selection
+            .entities
+            .iter()
+            .copied()
+            .filter(|id| self.scene.get(id).is_some_and(|data| !data.children.is_empty()))
+            .collect();
+        if groups.is_empty() {
+            return;
+        }
+
+        let command = UngroupCommand::new(groups);
+        if let Err(err) = self.execute_command(&command) {
+            tracing::warn!("Ungroup command failed: {}", err);
+        }
+    }
+
+    /// Duplicate selected entities
+    pub fn duplicate_selected(&mut self) {
         if self.selection.is_empty() {
             return;
         }
@@ -713,6 +1594,142 @@ impl EditorState {
         let _ = self.duplicate_entities(&ids);
     }
 
+    /// Check if a named input action is held down (play mode only)
+    pub fn is_action_pressed(&self, action: &str) -> bool {
+        self.play_mode.current_state().is_playing()
+            && self
+                .play_mode
+                .input
+                .is_action_pressed(&self.project_manager.settings.input.actions, action)
+    }
+
+    /// Input actions held, pressed and released this frame, for gameplay
+    /// graph previews (none unless playing)
+    pub fn action_input(&self) -> ActionInput {
+        let mut input = ActionInput::default();
+        if !self.play_mode.current_state().is_playing() {
+            return input;
+        }
+        let map = &self.project_manager.settings.input.actions;
+        let play_input = &self.play_mode.input;
+        for action in map.actions.keys() {
+            if self.is_action_pressed(action) {
+                input.held.insert(action.clone());
+            }
+            if play_input.is_action_just_pressed(map, action) {
+                input.pressed.insert(action.clone());
+            }
+            if play_input.is_action_just_released(map, action) {
+                input.released.insert(action.clone());
+            }
+        }
+        input
+    }
+
+    /// Translation snap grid from the current snap settings
+    pub fn snap_grid(&self) -> crate::tools::SnapGrid {
+        crate::tools::SnapGrid {
+            size: self.snap_size,
+            origin: self.snap_origin,
+            rotation: self.snap_rotation,
+            up_axis: self.up_axis(),
+        }
+    }
+
+    /// World up axis from the project settings
+    pub fn up_axis(&self) -> crate::project::UpAxis {
+        self.project_manager.settings.up_axis
+    }
+
+    /// Switch the world up axis.
+    ///
+    /// With `reorient_scene` the root entities are rotated into the new convention
+    /// as a single undoable step; otherwise only conventions (gravity, grid, camera) change.
+    pub fn set_up_axis(&mut self, up_axis: crate::project::UpAxis, reorient_scene: bool) {
+        let current = self.up_axis();
+        if current == up_axis {
+            return;
+        }
+
+        if reorient_scene {
+            let angle = current.conversion_angle(up_axis).to_radians();
+            let roots = self.scene.root_entities();
+            let transforms: Vec<Transform> = roots
+                .iter()
+                .filter_map(|id| self.scene.get(id))
+                .map(|data| {
+                    let mut transform = data.transform.clone();
+                    transform.position = current.convert(up_axis, transform.position);
+                    // Apply the conversion after the entity's own rotation
+                    let basis = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]]
+                        .map(|axis| rotate_axis(transform.rotate(axis), 0, angle));
+                    transform.rotation = Transform::euler_from_basis(basis);
+                    transform
+                })
+                .collect();
+            let description = format!("Re-orient scene to {}", up_axis.display_name());
+            self.set_transforms_bulk(&roots, &transforms, &description);
+        }
+
+        self.project_manager.settings.set_up_axis(up_axis);
+        self.project_manager.mark_dirty();
+        tracing::info!("World up axis set to {}", up_axis.display_name());
+    }
+
+    /// Move the selected entities down the world up axis onto the first
+    /// collider below them, or onto the ground plane if there is none
+    pub fn drop_selected_to_ground(&mut self) {
+        let command = SnapToGroundCommand::new(self.selection.entities.clone(), false).with_ground_plane();
+        let batch = command.resolve(self);
+        self.set_transforms_bulk_with_before(&batch.entities, &batch.before, &batch.after, &command.description());
+    }
+
+    /// Cast a ray against the scene's colliders.
+    ///
+    /// During play this queries the running simulation; while editing it
+    /// uses a snapshot of the scene with the project's collision layers.
+    pub fn raycast_scene(
+        &self,
+        origin: [f32; 3],
+        direction: [f32; 3],
+        max_distance: f32,
+        filter: &crate::physics::RaycastFilter,
+    ) -> Option<crate::physics::RaycastHit> {
+        self.query_world().raycast_filtered(origin, direction, max_distance, filter)
+    }
+
+    /// World scene queries run against: the running simulation during play,
+    /// otherwise a snapshot of the scene with the project's collision layers.
+    /// Callers casting several rays should hold on to it.
+    pub fn query_world(&self) -> Cow<'_, crate::physics::PhysicsWorld> {
+        if self.play_mode.current_state().is_playing() {
+            return Cow::Borrowed(&self.physics_world);
+        }
+
+        let physics = &self.project_manager.settings.physics;
+        let mut world = crate::physics::PhysicsWorld::new();
+        world.initialize_with_settings(&self.scene, physics.gravity, &physics.collision_layers);
+        world.add_mesh_proxies(&self.scene);
+        Cow::Owned(world)
+    }
+
+    /// Physics debug lines for the viewport, per the debug settings.
+    ///
+    /// While playing or paused these show the running simulation, with its
+    /// contacts; while editing they show a snapshot of the scene.
+    pub fn physics_debug_lines(&self) -> Vec<crate::physics::DebugLine> {
+        let settings = &self.physics_debug;
+        if !(settings.show_colliders || settings.show_velocities || settings.show_contacts) {
+            return Vec::new();
+        }
+
+        if self.play_mode.current_state().is_active() {
+            self.physics_world.debug_lines(settings)
+        } else {
+            crate::physics::PhysicsWorld::snapshot(&self.scene).debug_lines(settings)
+        }
+    }
+
     /// Mark the scene as modified
     #[allow(dead_code)] // Intentionally kept for API completeness
     pub fn mark_dirty(&mut self) {
@@ -765,14 +1782,7 @@ impl EditorState {
             return;
         }
 
-        let command = TransformCommand::new(
-            vec![entity_id],
-            vec![TransformData::from(before)],
-            vec![TransformData::from(after)],
-            description,
-        );
-
-        if let Err(err) = self.execute_command(&command) {
+        if let Err(err) = self.commit_transforms(vec![entity_id], vec![before], vec![after], description) {
             tracing::warn!("Transform command failed: {}", err);
         }
     }
@@ -931,6 +1941,48 @@ impl EditorState {
         }
     }
 
+    /// Combine static mesh entities into one entity referencing a combined
+    /// mesh asset, as a single undo step.
+    ///
+    /// The sources are deactivated rather than deleted. Entities that aren't
+    /// static, lack a mesh renderer or carry a collider are skipped with a
+    /// warning. Returns the combined entity, or `None` if fewer than two
+    /// entities qualify.
+    pub fn combine_static_meshes(&mut self, entities: &[EntityId]) -> Option<EntityId> {
+        use crate::commands::ImportEntitiesCommand;
+        use crate::mesh_combine::{combined_entity, partition_combinable};
+
+        let selection = partition_combinable(&self.scene, entities);
+        for (id, reason) in &selection.excluded {
+            tracing::warn!("Not combining {:?}: {}", id, reason.reason());
+        }
+        if selection.qualified.len() < 2 {
+            tracing::warn!("Combining meshes needs at least two static mesh entities");
+            return None;
+        }
+
+        let (combined_id, combined) = combined_entity(&self.scene, &selection.qualified);
+        let (Ok(old_value), Ok(new_value)) = (bincode::serialize(&true), bincode::serialize(&false)) else {
+            tracing::warn!("Failed to serialize entity active state");
+            return None;
+        };
+        let deactivate = selection
+            .qualified
+            .iter()
+            .map(|id| PropertyEditCommand::new(*id, "Entity", "active", old_value.clone(), new_value.clone()))
+            .collect();
+
+        let commands: Vec<Box<dyn EditorCommand>> = vec![
+            Box::new(ImportEntitiesCommand::new(vec![(combined_id, combined)])),
+            Box::new(PropertyEditGroupCommand::new("Deactivate Combined Sources", deactivate)),
+        ];
+        if let Err(err) = self.execute_command_group("Combine Static Meshes", &commands) {
+            tracing::warn!("Combine static meshes failed: {}", err);
+            return None;
+        }
+        Some(combined_id)
+    }
+
     /// Add a component to an entity with undo support
     pub fn add_component(&mut self, entity_id: EntityId, component: crate::components::Component) {
         use crate::commands::AddComponentCommand;
@@ -964,346 +2016,722 @@ impl EditorState {
         }
     }
 
-    /// Check if entity has a component of the given type
-    pub fn has_component(&self, entity_id: EntityId, type_id: &str) -> bool {
-        self.scene
+    /// Reset a component to its registry default with undo support.
+    ///
+    /// On prefab instances the reset is recorded as an override when the
+    /// default differs from the prefab's value, and clears it otherwise.
+    pub fn reset_component(&mut self, entity_id: EntityId, component_index: usize) -> bool {
+        use crate::commands::ResetComponentCommand;
+
+        let Some(previous) = self
+            .scene
             .get(&entity_id)
-            .map(|e| e.components.iter().any(|c| c.type_id() == type_id))
-            .unwrap_or(false)
-    }
+            .and_then(|entity| entity.components.get(component_index))
+            .cloned()
+        else {
+            tracing::warn!("Component {} not found on {:?}", component_index, entity_id);
+            return false;
+        };
 
-    /// Request a panel to be opened by the UI
-    pub fn request_panel_open(&mut self, panel: PanelType) {
-        self.pending_panels.push(panel);
-    }
+        let Some(command) = ResetComponentCommand::new(entity_id, component_index, previous) else {
+            tracing::warn!("No default registered for component {}", component_index);
+            return false;
+        };
 
-    /// Take pending panel open requests
-    pub fn take_pending_panels(&mut self) -> Vec<PanelType> {
-        std::mem::take(&mut self.pending_panels)
-    }
+        if let Err(err) = self.execute_command(&command) {
+            tracing::warn!("Reset component failed: {}", err);
+            return false;
+        }
 
-    /// Undo the last operation and apply it to the scene
-    pub fn undo(&mut self) -> Result<(), HistoryError> {
-        let group = self.history.undo()?;
-        self.apply_operation_group(&group, HistoryDirection::Undo);
-        self.dirty = true;
-        Ok(())
-    }
+        if self.prefab_manager.is_prefab_entity(entity_id) {
+            self.track_component_reset_override(entity_id, component_index, &command.default);
+        }
 
-    /// Redo the last operation and apply it to the scene
-    pub fn redo(&mut self) -> Result<(), HistoryError> {
-        let group = self.history.redo()?;
-        self.apply_operation_group(&group, HistoryDirection::Redo);
-        self.dirty = true;
-        Ok(())
+        self.validator.request_revalidation();
+        true
     }
 
-    /// Delete a set of entities (including their descendants)
-    pub fn delete_entities(&mut self, ids: &[EntityId]) {
-        let to_remove = self.collect_with_descendants(ids);
-        if to_remove.is_empty() {
-            return;
-        }
+    /// Copy a component to the component clipboard
+    pub fn copy_component(&mut self, entity_id: EntityId, component_index: usize) -> bool {
+        let Some(component) = self
+            .scene
+            .get(&entity_id)
+            .and_then(|entity| entity.components.get(component_index))
+        else {
+            return false;
+        };
 
-        self.remove_entities_by_id(&to_remove);
-        self.selection.clear();
-        self.dirty = true;
+        self.component_clipboard = Some(component.clone());
+        true
     }
 
-    /// Delete a set of entities via commands (undo/redo)
-    pub fn delete_entities_with_command(&mut self, ids: &[EntityId]) {
-        if ids.is_empty() {
-            return;
+    /// Record an inspector edit of a component, already applied to the
+    /// scene, as one undo step back to `previous`
+    pub fn commit_component_edit(
+        &mut self,
+        entity_id: EntityId,
+        component_index: usize,
+        previous: crate::components::Component,
+    ) -> bool {
+        use crate::commands::ReplaceComponentCommand;
+
+        let Some(current) = self
+            .scene
+            .get(&entity_id)
+            .and_then(|entity| entity.components.get(component_index))
+            .cloned()
+        else {
+            return false;
+        };
+        if current.type_id() != previous.type_id() || current == previous {
+            return false;
         }
 
-        let command = DeleteCommand::new(ids.to_vec());
+        let description = format!("Edit {}", current.display_name());
+        let command = ReplaceComponentCommand::new(entity_id, component_index, previous, current, description);
         if let Err(err) = self.execute_command(&command) {
-            tracing::warn!("Delete command failed: {}", err);
+            tracing::warn!("Component edit failed: {}", err);
+            return false;
         }
+        true
     }
 
-    /// Duplicate a set of entities with undo support
-    pub fn duplicate_entities(&mut self, ids: &[EntityId]) -> Vec<EntityId> {
-        if ids.is_empty() {
-            return Vec::new();
+    /// Overwrite a component with the clipboard's values as one undoable
+    /// group of field edits. The clipboard must hold a component of the
+    /// same type.
+    pub fn paste_component_values(&mut self, entity_id: EntityId, component_index: usize) -> bool {
+        let Some(replacement) = self.component_clipboard.clone() else {
+            return false;
+        };
+        let Some(previous) = self
+            .scene
+            .get(&entity_id)
+            .and_then(|entity| entity.components.get(component_index))
+            .cloned()
+        else {
+            tracing::warn!("Component {} not found on {:?}", component_index, entity_id);
+            return false;
+        };
+        if previous.type_id() != replacement.type_id() {
+            tracing::warn!(
+                "Cannot paste {} values onto {}",
+                replacement.display_name(),
+                previous.display_name()
+            );
+            return false;
         }
+        let (Ok(serde_json::Value::Object(old_fields)), Ok(serde_json::Value::Object(new_fields))) =
+            (serde_json::to_value(&previous), serde_json::to_value(&replacement))
+        else {
+            return false;
+        };
 
-        let command = DuplicateCommand::new(ids.to_vec());
-        let new_ids = command.new_entities.clone();
+        // One edit per top-level field that differs; the type tag matches
+        let slot = format!("components[{}]", component_index);
+        let edits: Vec<PropertyEditCommand> = new_fields
+            .iter()
+            .filter(|(field, value)| field.as_str() != "type" && old_fields.get(*field) != Some(value))
+            .filter_map(|(field, value)| {
+                let old_value = serde_json::to_vec(old_fields.get(field)?).ok()?;
+                let new_value = serde_json::to_vec(value).ok()?;
+                Some(PropertyEditCommand::new(entity_id, slot.clone(), field.clone(), old_value, new_value))
+            })
+            .collect();
+        if edits.is_empty() {
+            return false;
+        }
+
+        let command = PropertyEditGroupCommand::new(format!("Paste {} Values", replacement.display_name()), edits);
         if let Err(err) = self.execute_command(&command) {
-            tracing::warn!("Duplicate command failed: {}", err);
-            return Vec::new();
+            tracing::warn!("Paste component values failed: {}", err);
+            return false;
         }
 
-        new_ids
+        self.validator.request_revalidation();
+        true
     }
 
-    /// Reparent entities via commands (undo/redo)
-    pub fn reparent_entities_with_command(
-        &mut self,
-        entities: &[EntityId],
-        new_parent: Option<EntityId>,
-    ) {
-        if entities.is_empty() {
-            return;
+    /// Add the clipboard's component to an entity, with undo support.
+    /// Refused when the entity already has a component of that type.
+    pub fn paste_component_as_new(&mut self, entity_id: EntityId) -> bool {
+        let Some(component) = self.component_clipboard.clone() else {
+            return false;
+        };
+        if self.scene.get(&entity_id).is_none() {
+            return false;
         }
-
-        if let Some(parent_id) = new_parent {
-            if !self.scene.entities.contains_key(&parent_id) {
-                tracing::warn!("Cannot reparent to missing parent {:?}", parent_id);
-                return;
-            }
+        if self.has_component(entity_id, component.type_id()) {
+            tracing::warn!("{} already has a {}", entity_id.0, component.display_name());
+            return false;
         }
 
-        let mut ids = Vec::new();
-        let mut old_parents = Vec::new();
+        self.add_component(entity_id, component);
+        self.validator.request_revalidation();
+        true
+    }
 
-        for entity_id in entities {
-            let Some(entity) = self.scene.get(entity_id) else {
-                continue;
-            };
-            if Some(*entity_id) == new_parent || entity.parent == new_parent {
-                continue;
-            }
-            ids.push(*entity_id);
-            old_parents.push(entity.parent);
-        }
+    /// Move a component to another position on its entity with undo support
+    pub fn reorder_component(&mut self, entity_id: EntityId, from: usize, to: usize) -> bool {
+        use crate::commands::ReorderComponentCommand;
 
-        if ids.is_empty() {
-            return;
+        if from == to {
+            return false;
         }
 
-        let command = ReparentCommand::new(ids, old_parents, new_parent);
+        let command = ReorderComponentCommand::new(entity_id, from, to);
         if let Err(err) = self.execute_command(&command) {
-            tracing::warn!("Reparent command failed: {}", err);
+            tracing::warn!("Reorder component failed: {}", err);
+            return false;
         }
+        true
     }
 
-    fn apply_operation_group(&mut self, group: &OperationGroup, direction: HistoryDirection) {
-        let ops: Box<dyn Iterator<Item = &Operation>> = match direction {
-            HistoryDirection::Undo => Box::new(group.operations.iter().rev()),
-            HistoryDirection::Redo => Box::new(group.operations.iter()),
-        };
+    /// Move an entity to index `to` among its siblings with undo support
+    pub fn reorder_sibling(&mut self, entity_id: EntityId, to: usize) -> bool {
+        use crate::commands::ReorderSiblingCommand;
 
-        for operation in ops {
-            let snapshot = match direction {
-                HistoryDirection::Undo => &operation.before,
-                HistoryDirection::Redo => &operation.after,
-            };
-            let _ = self.apply_snapshot(snapshot);
+        if self.scene.siblings(entity_id).iter().position(|id| *id == entity_id) == Some(to) {
+            return false;
         }
-    }
 
-    fn apply_snapshot(&mut self, snapshot: &StateSnapshot) -> bool {
-        if snapshot.data.is_empty() {
+        let command = ReorderSiblingCommand::new(entity_id, to);
+        if let Err(err) = self.execute_command(&command) {
+            tracing::warn!("Reorder sibling failed: {}", err);
             return false;
         }
-
-        if let Ok((entity_id, transform)) = snapshot.to_value::<(EntityId, Transform)>() {
-            if let Some(entity) = self.scene.get_mut(&entity_id) {
-                entity.transform = transform;
-                return true;
-            }
+        true
+    }
+
+    /// Convert a collider component to another collider type with undo support
+    pub fn convert_collider(
+        &mut self,
+        entity_id: EntityId,
+        component_index: usize,
+        to: crate::components::ColliderKind,
+    ) -> bool {
+        use crate::commands::ReplaceComponentCommand;
+        use crate::components::Component;
+
+        let Some(entity) = self.scene.get(&entity_id) else {
+            return false;
+        };
+        let Some(previous) = entity.components.get(component_index).cloned() else {
+            return false;
+        };
+
+        // Mesh colliders pick up the entity's rendered mesh
+        let mesh = entity.components.iter().find_map(|c| match c {
+            Component::MeshRenderer(renderer) if !renderer.mesh.is_empty() => Some(renderer.mesh.clone()),
+            _ => None,
+        });
+
+        let Some(replacement) = previous.convert_collider(to, mesh.as_deref()) else {
+            tracing::warn!("Component {} is not a collider", component_index);
+            return false;
+        };
+
+        let description = format!("Convert to {}", to.display_name());
+        let command = ReplaceComponentCommand::new(entity_id, component_index, previous, replacement, description);
+        if let Err(err) = self.execute_command(&command) {
+            tracing::warn!("Convert collider failed: {}", err);
+            return false;
         }
 
-        if let Ok(pairs) = snapshot.to_value::<Vec<(EntityId, Transform)>>() {
-            if !pairs.is_empty() {
-                self.apply_transform_pairs(pairs);
-                return true;
-            }
+        self.validator.request_revalidation();
+        true
+    }
+
+    /// Set (or clear) an entity reference field on a component with undo support
+    pub fn set_entity_reference(
+        &mut self,
+        entity_id: EntityId,
+        component_index: usize,
+        field: &str,
+        value: Option<EntityId>,
+    ) -> bool {
+        use crate::commands::ReplaceComponentCommand;
+
+        let Some(previous) = self
+            .scene
+            .get(&entity_id)
+            .and_then(|entity| entity.components.get(component_index))
+            .cloned()
+        else {
+            return false;
+        };
+
+        let mut replacement = previous.clone();
+        if !replacement.set_entity_reference(field, value) {
+            tracing::warn!("Component {} has no reference field '{}'", component_index, field);
+            return false;
+        }
+        if replacement == previous {
+            return false;
         }
 
-        if let Ok(pairs) = snapshot.to_value::<Vec<(EntityId, TransformData)>>() {
-            if !pairs.is_empty() {
-                self.apply_transform_data_pairs(pairs);
-                return true;
-            }
+        let description = if value.is_some() {
+            format!("Set {} {}", previous.display_name(), field)
+        } else {
+            format!("Clear {} {}", previous.display_name(), field)
+        };
+        let command = ReplaceComponentCommand::new(entity_id, component_index, previous, replacement, description);
+        if let Err(err) = self.execute_command(&command) {
+            tracing::warn!("Set entity reference failed: {}", err);
+            return false;
         }
+        true
+    }
 
-        if let Ok((entity_id, name)) = snapshot.to_value::<(EntityId, String)>() {
-            if let Some(entity) = self.scene.get_mut(&entity_id) {
-                entity.name = name;
-                return true;
-            }
+    /// Set (or with `None`, remove) entity metadata with undo support
+    pub fn set_metadata(&mut self, entity_id: EntityId, key: &str, value: Option<String>) -> bool {
+        use crate::commands::SetMetadataCommand;
+
+        let key = key.trim();
+        if key.is_empty() || !self.scene.entities.contains_key(&entity_id) {
+            return false;
         }
 
-        if let Ok(edit) = snapshot.to_value::<PropertyEditSnapshot>() {
-            if self.apply_property_snapshot(edit) {
-                return true;
-            }
+        let previous = self.scene.get_metadata(entity_id, key).map(str::to_string);
+        if previous == value {
+            return false;
         }
 
-        if let Ok(edits) = snapshot.to_value::<Vec<PropertyEditSnapshot>>() {
-            if !edits.is_empty() {
-                let mut applied = false;
-                for edit in edits {
-                    applied |= self.apply_property_snapshot(edit);
-                }
-                return applied;
-            }
+        let command = SetMetadataCommand::new(entity_id, key, previous, value);
+        if let Err(err) = self.execute_command(&command) {
+            tracing::warn!("Set metadata failed: {}", err);
+            return false;
         }
+        true
+    }
 
-        if let Ok(pairs) = snapshot.to_value::<Vec<(EntityId, Option<EntityId>)>>() {
-            if !pairs.is_empty() {
-                for (entity_id, parent) in pairs {
-                    self.set_entity_parent(entity_id, parent);
-                }
-                return true;
+    /// Replace an entity's tags with undo support.
+    ///
+    /// Tags are trimmed, empty ones dropped and duplicates removed. Returns
+    /// true if the tags changed.
+    pub fn set_tags(&mut self, entity_id: EntityId, tags: Vec<String>) -> bool {
+        use crate::commands::SetTagsCommand;
+
+        let Some(data) = self.scene.get(&entity_id) else {
+            return false;
+        };
+        let mut cleaned: Vec<String> = Vec::with_capacity(tags.len());
+        for tag in tags {
+            let tag = tag.trim();
+            if !tag.is_empty() && !cleaned.iter().any(|t| t == tag) {
+                cleaned.push(tag.to_string());
             }
         }
+        if cleaned == data.tags {
+            return false;
+        }
 
-        if let Ok(entities) = snapshot.to_value::<Vec<(EntityId, EntityData)>>() {
-            if !entities.is_empty() {
-                self.restore_entities(entities);
-                return true;
-            }
+        let command = SetTagsCommand::new(entity_id, data.tags.clone(), cleaned);
+        if let Err(err) = self.execute_command(&command) {
+            tracing::warn!("Set tags failed: {}", err);
+            return false;
         }
+        true
+    }
 
-        if let Ok(ids) = snapshot.to_value::<Vec<EntityId>>() {
-            if !ids.is_empty() {
-                self.remove_entities_by_id(&ids);
-                return true;
-            }
+    /// Set or clear an entity's color label with undo support
+    pub fn set_color_label(&mut self, entity_id: EntityId, label: Option<ColorLabel>) -> bool {
+        use crate::commands::SetColorLabelCommand;
+
+        let Some(data) = self.scene.get(&entity_id) else {
+            return false;
+        };
+        if data.color_label == label {
+            return false;
         }
 
-        false
+        let command = SetColorLabelCommand::new(entity_id, data.color_label, label);
+        if let Err(err) = self.execute_command(&command) {
+            tracing::warn!("Set color label failed: {}", err);
+            return false;
+        }
+        true
     }
 
-    pub(crate) fn collect_with_descendants(&self, ids: &[EntityId]) -> Vec<EntityId> {
-        let mut visited = HashSet::new();
-        let mut collected = Vec::new();
+    /// Add a tag to an entity with undo support
+    pub fn add_tag(&mut self, entity_id: EntityId, tag: &str) -> bool {
+        let Some(data) = self.scene.get(&entity_id) else {
+            return false;
+        };
+        let mut tags = data.tags.clone();
+        tags.push(tag.to_string());
+        self.set_tags(entity_id, tags)
+    }
 
-        for id in ids {
-            self.collect_descendants(*id, &mut collected, &mut visited);
-        }
+    /// Remove a tag from an entity with undo support
+    pub fn remove_tag(&mut self, entity_id: EntityId, tag: &str) -> bool {
+        let Some(data) = self.scene.get(&entity_id) else {
+            return false;
+        };
+        let tags = data.tags.iter().filter(|t| *t != tag).cloned().collect();
+        self.set_tags(entity_id, tags)
+    }
 
-        collected
+    /// Route a viewport/hierarchy click to the active eyedropper.
+    ///
+    /// Returns true if the click was consumed by a pick.
+    pub fn complete_entity_pick(&mut self, picked: EntityId) -> bool {
+        if !self.entity_picker.is_active() {
+            return false;
+        }
+        if let Some(target) = self.entity_picker.finish(picked) {
+            self.set_entity_reference(target.entity, target.component_index, target.field, Some(picked));
+        }
+        true
     }
 
-    fn collect_descendants(
-        &self,
-        id: EntityId,
-        collected: &mut Vec<EntityId>,
-        visited: &mut HashSet<EntityId>,
+    /// Record (or clear) the prefab override for a reset component
+    fn track_component_reset_override(
+        &mut self,
+        entity_id: EntityId,
+        component_index: usize,
+        default: &crate::components::Component,
     ) {
-        if !visited.insert(id) {
+        let property_path = format!("components[{}]", component_index);
+
+        let Some((instance_root, local_id, prefab_path)) = self
+            .prefab_manager
+            .find_instance_containing(entity_id)
+            .and_then(|instance| {
+                let local_id = instance.get_local_id(entity_id)?;
+                Some((instance.root_entity_id, local_id, instance.prefab_path.clone()))
+            })
+        else {
             return;
-        }
+        };
 
-        collected.push(id);
+        // Compare against the prefab's value for this component slot
+        let prefab_value = crate::prefab::Prefab::load(&prefab_path).ok().and_then(|prefab| {
+            self.find_prefab_entity_by_local_id(&prefab.root, local_id)
+                .and_then(|entity| entity.components.get(component_index).cloned())
+        });
 
-        if let Some(entity) = self.scene.get(&id) {
-            for child_id in &entity.children {
-                self.collect_descendants(*child_id, collected, visited);
+        if prefab_value.as_ref() == Some(default) {
+            if let Some(instance) = self.prefab_manager.get_instance_mut(instance_root) {
+                instance.remove_override(&local_id.to_string(), &property_path);
             }
+        } else if let Ok(value) = serde_json::to_value(default) {
+            self.track_prefab_override(entity_id, &property_path, value);
         }
     }
 
-    fn remove_entities_by_id(&mut self, ids: &[EntityId]) {
-        for id in ids {
-            if let Some(entity) = self.scene.remove(id) {
-                if let Some(parent_id) = entity.parent {
-                    if let Some(parent) = self.scene.get_mut(&parent_id) {
-                        parent.children.retain(|child| child != id);
-                    }
-                }
-            }
-            self.selection.remove(id);
-        }
+    /// Check if entity has a component of the given type
+    pub fn has_component(&self, entity_id: EntityId, type_id: &str) -> bool {
+        self.scene
+            .get(&entity_id)
+            .map(|e| e.components.iter().any(|c| c.type_id() == type_id))
+            .unwrap_or(false)
     }
 
-    fn restore_entities(&mut self, entities: Vec<(EntityId, EntityData)>) {
-        for (id, data) in entities.iter() {
-            self.scene.entities.insert(*id, data.clone());
-        }
-
-        for (id, data) in entities {
-            self.attach_to_parent(id, data.parent);
-        }
+    /// Request a panel to be opened by the UI
+    pub fn request_panel_open(&mut self, panel: PanelType) {
+        self.pending_panels.push(panel);
     }
 
-    fn apply_transform_pairs(&mut self, pairs: Vec<(EntityId, Transform)>) {
-        for (entity_id, transform) in pairs {
-            if let Some(entity) = self.scene.get_mut(&entity_id) {
-                entity.transform = transform;
-            }
-        }
+    /// Take pending panel open requests
+    pub fn take_pending_panels(&mut self) -> Vec<PanelType> {
+        std::mem::take(&mut self.pending_panels)
     }
 
-    fn apply_transform_data_pairs(&mut self, pairs: Vec<(EntityId, TransformData)>) {
-        for (entity_id, transform) in pairs {
-            if let Some(entity) = self.scene.get_mut(&entity_id) {
-                entity.transform = Transform {
-                    position: transform.position,
-                    rotation: [transform.rotation[0], transform.rotation[1], transform.rotation[2]],
-                    scale: transform.scale,
-                };
-            }
-        }
+    /// Take the transform edits committed since the last call
+    ///
+    /// Undo and redo don't produce commits, only new edits do.
+    pub fn take_transform_commits(&mut self) -> Vec<TransformCommit> {
+        std::mem::take(&mut self.transform_commits)
     }
 
-    fn apply_property_snapshot(&mut self, snapshot: PropertyEditSnapshot) -> bool {
-        let Some(entity) = self.scene.get_mut(&snapshot.entity) else {
-            return false;
-        };
+    /// Record a transform edit in history and queue its commits on success
+    fn commit_transforms(
+        &mut self,
+        entities: Vec<EntityId>,
+        before: Vec<Transform>,
+        after: Vec<Transform>,
+        description: &str,
+    ) -> Result<(), crate::commands::CommandError> {
+        let command = TransformCommand::new(
+            entities.clone(),
+            before.iter().map(TransformData::from).collect(),
+            after.iter().map(TransformData::from).collect(),
+            description,
+        );
+        self.execute_command(&command)?;
 
-        let component = snapshot.component_type.as_str();
-        let field = snapshot.field_path.as_str();
+        self.transform_commits.extend(
+            entities
+                .into_iter()
+                .zip(before.into_iter().zip(after))
+                .map(|(entity, (before, after))| TransformCommit { entity, before, after }),
+        );
+        Ok(())
+    }
 
-        if component.eq_ignore_ascii_case("Transform") || component.eq_ignore_ascii_case("transform") {
-            if let Ok(value) = bincode::deserialize::<[f32; 3]>(&snapshot.value) {
-                match field {
-                    "position" => entity.transform.position = value,
-                    "rotation" => entity.transform.rotation = value,
-                    "scale" => entity.transform.scale = value,
-                    "position.x" => entity.transform.position[0] = value[0],
-                    "position.y" => entity.transform.position[1] = value[1],
-                    "position.z" => entity.transform.position[2] = value[2],
-                    "rotation.x" => entity.transform.rotation[0] = value[0],
-                    "rotation.y" => entity.transform.rotation[1] = value[1],
-                    "rotation.z" => entity.transform.rotation[2] = value[2],
-                    "scale.x" => entity.transform.scale[0] = value[0],
-                    "scale.y" => entity.transform.scale[1] = value[1],
-                    "scale.z" => entity.transform.scale[2] = value[2],
-                    _ => return false,
-                }
-                return true;
-            }
+    /// Undo the last operation and apply it to the scene
+    pub fn undo(&mut self) -> Result<(), HistoryError> {
+        let group = self.history.undo()?;
+        self.apply_operation_group(&group, HistoryDirection::Undo);
+        self.dirty = true;
+        Ok(())
+    }
 
-            if let Ok(value) = bincode::deserialize::<f32>(&snapshot.value) {
-                match field {
-                    "position.x" => entity.transform.position[0] = value,
-                    "position.y" => entity.transform.position[1] = value,
-                    "position.z" => entity.transform.position[2] = value,
-                    "rotation.x" => entity.transform.rotation[0] = value,
-                    "rotation.y" => entity.transform.rotation[1] = value,
-                    "rotation.z" => entity.transform.rotation[2] = value,
-                    "scale.x" => entity.transform.scale[0] = value,
-                    "scale.y" => entity.transform.scale[1] = value,
-                    "scale.z" => entity.transform.scale[2] = value,
-                    _ => return false,
-                }
-                return true;
-            }
-        }
+    /// Redo the last operation and apply it to the scene
+    pub fn redo(&mut self) -> Result<(), HistoryError> {
+        let group = self.history.redo()?;
+        self.apply_operation_group(&group, HistoryDirection::Redo);
+        self.dirty = true;
+        Ok(())
+    }
 
-        if component.eq_ignore_ascii_case("Entity") && field.eq_ignore_ascii_case("name") {
-            if let Ok(name) = bincode::deserialize::<String>(&snapshot.value) {
-                entity.name = name;
-                return true;
-            }
+    /// Undo or redo until `index` groups of [`History::entries`] are applied
+    pub fn jump_to_history(&mut self, index: usize) -> Result<(), HistoryError> {
+        let index = index.min(self.history.entries().count());
+        while self.history.current_index() > index {
+            self.undo()?;
         }
-
-        if component.eq_ignore_ascii_case("Entity") && field.eq_ignore_ascii_case("active") {
-            if let Ok(active) = bincode::deserialize::<bool>(&snapshot.value) {
-                entity.active = active;
-                return true;
-            }
+        while self.history.current_index() < index {
+            self.redo()?;
         }
+        Ok(())
+    }
 
-        if component.eq_ignore_ascii_case("Entity") && field.eq_ignore_ascii_case("is_static") {
-            if let Ok(is_static) = bincode::deserialize::<bool>(&snapshot.value) {
-                entity.is_static = is_static;
+    /// Delete a set of entities (including their descendants)
+    pub fn delete_entities(&mut self, ids: &[EntityId]) {
+        let to_remove = self.collect_with_descendants(ids);
+        if to_remove.is_empty() {
+            return;
+        }
+
+        self.remove_entities_by_id(&to_remove);
+        self.selection.clear();
+        self.dirty = true;
+    }
+
+    /// Delete a set of entities via commands (undo/redo)
+    pub fn delete_entities_with_command(&mut self, ids: &[EntityId]) {
+        if ids.is_empty() {
+            return;
+        }
+
+        let command = DeleteCommand::new(ids.to_vec());
+        if let Err(err) = self.execute_command(&command) {
+            tracing::warn!("Delete command failed: {}", err);
+        }
+    }
+
+    /// Duplicate a set of entities with undo support
+    pub fn duplicate_entities(&mut self, ids: &[EntityId]) -> Vec<EntityId> {
+        if ids.is_empty() {
+            return Vec::new();
+        }
+
+        let command = DuplicateCommand::new(ids.to_vec());
+        let new_ids = command.new_entities.clone();
+        if let Err(err) = self.execute_command(&command) {
+            tracing::warn!("Duplicate command failed: {}", err);
+            return Vec::new();
+        }
+
+        new_ids
+    }
+
+    /// Import a transferred entity subtree under `parent` as one undo step.
+    ///
+    /// Returns the id of the imported root, or `None` if the import failed.
+    pub fn import_subtree(
+        &mut self,
+        subtree: &crate::entity_transfer::EntitySubtree,
+        parent: Option<EntityId>,
+    ) -> Option<EntityId> {
+        let entities = subtree.instantiate(&self.scene, parent);
+        let root = entities.first()?.0;
+        let command = crate::commands::ImportEntitiesCommand::new(entities);
+        if let Err(err) = self.execute_command(&command) {
+            tracing::warn!("Import command failed: {}", err);
+            return None;
+        }
+        Some(root)
+    }
+
+    /// Reparent entities via commands (undo/redo)
+    pub fn reparent_entities_with_command(
+        &mut self,
+        entities: &[EntityId],
+        new_parent: Option<EntityId>,
+    ) {
+        if entities.is_empty() {
+            return;
+        }
+
+        if let Some(parent_id) = new_parent {
+            if !self.scene.entities.contains_key(&parent_id) {
+                tracing::warn!("Cannot reparent to missing parent {:?}", parent_id);
+                return;
+            }
+            // Parenting an entity under its own descendant would create a cycle
+            if let Some(moved) = self.scene.ancestors(parent_id).into_iter().find(|id| entities.contains(id)) {
+                tracing::warn!(
+                    "Cannot reparent {:?} under its own descendant {:?}",
+                    moved,
+                    parent_id
+                );
+                return;
+            }
+        }
+
+        let mut ids = Vec::new();
+        let mut old_parents = Vec::new();
+
+        for entity_id in entities {
+            let Some(entity) = self.scene.get(entity_id) else {
+                continue;
+            };
+            if Some(*entity_id) == new_parent || entity.parent == new_parent {
+                continue;
+            }
+            ids.push(*entity_id);
+            old_parents.push(entity.parent);
+        }
+
+        if ids.is_empty() {
+            return;
+        }
+
+        let command = ReparentCommand::new(ids, old_parents, new_parent);
+        if let Err(err) = self.execute_command(&command) {
+            tracing::warn!("Reparent command failed: {}", err);
+        }
+    }
+
+    fn apply_operation_group(&mut self, group: &OperationGroup, direction: HistoryDirection) {
+        let ops: Box<dyn Iterator<Item = &Operation>> = match direction {
+            HistoryDirection::Undo => Box::new(group.operations.iter().rev()),
+            HistoryDirection::Redo => Box::new(group.operations.iter()),
+        };
+
+        for operation in ops {
+            let snapshot = match direction {
+                HistoryDirection::Undo => &operation.before,
+                HistoryDirection::Redo => &operation.after,
+            };
+            let _ = self.apply_snapshot(snapshot);
+        }
+    }
+
+    fn apply_snapshot(&mut self, snapshot: &StateSnapshot) -> bool {
+        if snapshot.data.is_empty() {
+            return false;
+        }
+
+        // Marked layouts, so it is safe to check before the tuple layouts below
+        if let Ok(component) = snapshot.to_value::<crate::commands::ComponentSnapshot>() {
+            if component.is_valid() {
+                return self.apply_component_snapshot(component);
+            }
+        }
+
+        if let Ok(list) = snapshot.to_value::<crate::commands::ComponentListSnapshot>() {
+            if list.is_valid() {
+                return self.apply_component_list_snapshot(list);
+            }
+        }
+
+        if let Ok(metadata) = snapshot.to_value::<crate::commands::MetadataSnapshot>() {
+            if metadata.is_valid() {
+                return self.scene.set_metadata(metadata.entity, &metadata.key, metadata.value);
+            }
+        }
+
+        if let Ok(apply) = snapshot.to_value::<crate::commands::PrefabApplySnapshot>() {
+            if apply.is_valid() {
+                return self.apply_prefab_apply_snapshot(apply);
+            }
+        }
+
+        if let Ok(tags) = snapshot.to_value::<crate::commands::TagsSnapshot>() {
+            if tags.is_valid() {
+                return self.scene.set_tags(tags.entity, tags.tags);
+            }
+        }
+
+        if let Ok(label) = snapshot.to_value::<crate::commands::ColorLabelSnapshot>() {
+            if label.is_valid() {
+                return self.scene.set_color_label(label.entity, label.label);
+            }
+        }
+
+        if let Ok(hierarchy) = snapshot.to_value::<crate::commands::HierarchySnapshot>() {
+            if hierarchy.is_valid() {
+                for id in &hierarchy.removed {
+                    self.selection.remove(id);
+                }
+                return hierarchy.apply(&mut self.scene);
+            }
+        }
+
+        if let Ok(order) = snapshot.to_value::<crate::commands::ComponentOrderSnapshot>() {
+            if order.is_valid() {
+                return order.apply(&mut self.scene);
+            }
+        }
+
+        if let Ok(order) = snapshot.to_value::<crate::commands::SiblingOrderSnapshot>() {
+            if order.is_valid() {
+                return order.apply(&mut self.scene);
+            }
+        }
+
+        if let Ok(transforms) = snapshot.to_value::<crate::commands::TransformSnapshot>() {
+            if transforms.is_valid() {
+                self.apply_transform_data_pairs(transforms.transforms);
+                return true;
+            }
+        }
+
+        if let Ok(edit) = snapshot.to_value::<PropertyEditSnapshot>() {
+            if edit.is_valid() {
+                return self.apply_property_snapshot(edit);
+            }
+        }
+
+        if let Ok(edits) = snapshot.to_value::<Vec<PropertyEditSnapshot>>() {
+            if !edits.is_empty() && edits.iter().all(PropertyEditSnapshot::is_valid) {
+                let mut applied = false;
+                for edit in edits {
+                    applied |= self.apply_property_snapshot(edit);
+                }
+                return applied;
+            }
+        }
+
+        if let Ok((entity_id, name)) = snapshot.to_value::<(EntityId, String)>() {
+            if let Some(entity) = self.scene.get_mut(&entity_id) {
+                entity.name = name;
+                return true;
+            }
+        }
+
+        if let Ok(pairs) = snapshot.to_value::<Vec<(EntityId, Option<EntityId>)>>() {
+            if !pairs.is_empty() {
+                for (entity_id, parent) in pairs {
+                    self.set_entity_parent(entity_id, parent);
+                }
+                return true;
+            }
+        }
+
+        if let Ok(entities) = snapshot.to_value::<Vec<(EntityId, EntityData)>>() {
+            if !entities.is_empty() {
+                self.restore_entities(entities);
+                return true;
+            }
+        }
+
+        if let Ok(ids) = snapshot.to_value::<Vec<EntityId>>() {
+            if !ids.is_empty() {
+                self.remove_entities_by_id(&ids);
                 return true;
             }
         }
@@ -1311,544 +2739,2734 @@ impl EditorState {
         false
     }
 
-    fn set_entity_parent(&mut self, entity_id: EntityId, new_parent: Option<EntityId>) {
-        // First pass: check if entity exists and get old parent
-        let old_parent = {
-            let Some(entity) = self.scene.get(&entity_id) else {
-                return;
-            };
-            if entity.parent == new_parent {
-                return;
+    pub(crate) fn collect_with_descendants(&self, ids: &[EntityId]) -> Vec<EntityId> {
+        let mut visited = HashSet::new();
+        let mut collected = Vec::new();
+
+        for id in ids {
+            self.collect_descendants(*id, &mut collected, &mut visited);
+        }
+
+        collected
+    }
+
+    fn collect_descendants(
+        &self,
+        id: EntityId,
+        collected: &mut Vec<EntityId>,
+        visited: &mut HashSet<EntityId>,
+    ) {
+        if !visited.insert(id) {
+            return;
+        }
+
+        collected.push(id);
+
+        if let Some(entity) = self.scene.get(&id) {
+            for child_id in &entity.children {
+                self.collect_descendants(*child_id, collected, visited);
             }
-            entity.parent
+        }
+    }
+
+    fn remove_entities_by_id(&mut self, ids: &[EntityId]) {
+        for id in ids {
+            if let Some(entity) = self.scene.remove(id) {
+                if let Some(parent_id) = entity.parent {
+                    if let Some(parent) = self.scene.get_mut(&parent_id) {
+                        parent.children.retain(|child| child != id);
+                    }
+                }
+            }
+            self.selection.remove(id);
+        }
+    }
+
+    /// Put a prefab file, an instance's overrides and the instances the
+    /// apply pushed to back as they were in `snapshot`
+    fn apply_prefab_apply_snapshot(&mut self, snapshot: crate::commands::PrefabApplySnapshot) -> bool {
+        let path = &snapshot.prefab_path;
+        if let Err(e) = std::fs::write(path, &snapshot.prefab_file) {
+            tracing::error!("Failed to restore prefab {}: {}", path.display(), e);
+            return false;
+        }
+        if let Err(e) = self.prefab_manager.reload_prefab(path) {
+            tracing::warn!("Failed to refresh cached prefab {}: {}", path.display(), e);
+        }
+
+        match serde_json::from_str(&snapshot.overrides) {
+            Ok(overrides) => {
+                if let Some(instance) = self.prefab_manager.get_instance_mut(snapshot.instance) {
+                    instance.overrides = overrides;
+                }
+            }
+            Err(e) => tracing::warn!("Failed to restore prefab overrides: {}", e),
+        }
+
+        if !snapshot.entities.is_empty() {
+            self.restore_entities(snapshot.entities);
+        }
+        true
+    }
+
+    fn restore_entities(&mut self, entities: Vec<(EntityId, EntityData)>) {
+        for (id, data) in entities.iter() {
+            self.scene.entities.insert(*id, data.clone());
+        }
+
+        for (id, data) in entities {
+            self.attach_to_parent(id, data.parent);
+        }
+    }
+
+    fn apply_component_snapshot(&mut self, snapshot: crate::commands::ComponentSnapshot) -> bool {
+        let Some(component) = snapshot.component() else {
+            return false;
+        };
+        let Some(entity) = self.scene.get_mut(&snapshot.entity) else {
+            return false;
+        };
+        match entity.components.get_mut(snapshot.component_index as usize) {
+            Some(slot) => {
+                *slot = component;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn apply_component_list_snapshot(&mut self, snapshot: crate::commands::ComponentListSnapshot) -> bool {
+        let Some(components) = snapshot.components() else {
+            return false;
+        };
+        let Some(entity) = self.scene.get_mut(&snapshot.entity) else {
+            return false;
+        };
+        entity.components = components;
+        true
+    }
+
+    fn apply_transform_data_pairs(&mut self, pairs: Vec<(EntityId, TransformData)>) {
+        for (entity_id, transform) in pairs {
+            if let Some(entity) = self.scene.get_mut(&entity_id) {
+                entity.transform = crate::commands::to_editor_transform(&transform);
+            }
+        }
+    }
+
+    fn apply_property_snapshot(&mut self, snapshot: PropertyEditSnapshot) -> bool {
+        let Some(entity) = self.scene.get_mut(&snapshot.entity) else {
+            return false;
+        };
+
+        let component = snapshot.component_type.as_str();
+        let field = snapshot.field_path.as_str();
+
+        if component.eq_ignore_ascii_case("Transform") || component.eq_ignore_ascii_case("transform") {
+            if let Ok(value) = bincode::deserialize::<[f32; 3]>(&snapshot.value) {
+                match field {
+                    "position" => entity.transform.position = value,
+                    "rotation" => entity.transform.rotation = value,
+                    "scale" => entity.transform.scale = value,
+                    "position.x" => entity.transform.position[0] = value[0],
+                    "position.y" => entity.transform.position[1] = value[1],
+                    "position.z" => entity.transform.position[2] = value[2],
+                    "rotation.x" => entity.transform.rotation[0] = value[0],
+                    "rotation.y" => entity.transform.rotation[1] = value[1],
+                    "rotation.z" => entity.transform.rotation[2] = value[2],
+                    "scale.x" => entity.transform.scale[0] = value[0],
+                    "scale.y" => entity.transform.scale[1] = value[1],
+                    "scale.z" => entity.transform.scale[2] = value[2],
+                    _ => return false,
+                }
+                return true;
+            }
+
+            if let Ok(value) = bincode::deserialize::<f32>(&snapshot.value) {
+                match field {
+                    "position.x" => entity.transform.position[0] = value,
+                    "position.y" => entity.transform.position[1] = value,
+                    "position.z" => entity.transform.position[2] = value,
+                    "rotation.x" => entity.transform.rotation[0] = value,
+                    "rotation.y" => entity.transform.rotation[1] = value,
+                    "rotation.z" => entity.transform.rotation[2] = value,
+                    "scale.x" => entity.transform.scale[0] = value,
+                    "scale.y" => entity.transform.scale[1] = value,
+                    "scale.z" => entity.transform.scale[2] = value,
+                    _ => return false,
+                }
+                return true;
+            }
+        }
+
+        if component.eq_ignore_ascii_case("Entity") && field.eq_ignore_ascii_case("name") {
+            if let Ok(name) = bincode::deserialize::<String>(&snapshot.value) {
+                entity.name = name;
+                return true;
+            }
+        }
+
+        if component.eq_ignore_ascii_case("Entity") && field.eq_ignore_ascii_case("active") {
+            if let Ok(active) = bincode::deserialize::<bool>(&snapshot.value) {
+                entity.active = active;
+                return true;
+            }
+        }
+
+        if component.eq_ignore_ascii_case("Entity") && field.eq_ignore_ascii_case("is_static") {
+            if let Ok(is_static) = bincode::deserialize::<bool>(&snapshot.value) {
+                entity.is_static = is_static;
+                return true;
+            }
+        }
+
+        crate::commands::apply_component_field_edit(entity, component, field, &snapshot.value).is_ok()
+    }
+
+    fn set_entity_parent(&mut self, entity_id: EntityId, new_parent: Option<EntityId>) {
+        // First pass: check if entity exists and get old parent
+        let old_parent = {
+            let Some(entity) = self.scene.get(&entity_id) else {
+                return;
+            };
+            if entity.parent == new_parent {
+                return;
+            }
+            entity.parent
+        };
+
+        // Second pass: remove from old parent's children
+        if let Some(old_parent_id) = old_parent {
+            if let Some(parent) = self.scene.get_mut(&old_parent_id) {
+                parent.children.retain(|id| id != &entity_id);
+            }
+        }
+
+        // Third pass: update entity's parent reference
+        if let Some(entity) = self.scene.get_mut(&entity_id) {
+            entity.parent = new_parent;
+        }
+
+        // Fourth pass: add to new parent's children
+        self.attach_to_parent(entity_id, new_parent);
+    }
+
+    fn attach_to_parent(&mut self, entity_id: EntityId, parent_id: Option<EntityId>) {
+        if let Some(parent_id) = parent_id {
+            if let Some(parent) = self.scene.get_mut(&parent_id) {
+                if !parent.children.contains(&entity_id) {
+                    parent.children.push(entity_id);
+                }
+            }
+        }
+    }
+
+    /// Execute an editor command and commit its undo/redo snapshot.
+    ///
+    /// Repeats of a coalescing command (e.g. nudging the same entities)
+    /// within a short window merge into the previous undo step.
+    pub fn execute_command<C: EditorCommand>(&mut self, command: &C) -> Result<(), crate::commands::CommandError> {
+        let (before, after) = command.snapshots(self)?;
+        let op_id = self.history.begin_operation(command.description());
+        command.execute(self)?;
+
+        let operation = Operation::new(op_id, command.description().to_string(), before, after);
+        let mut group = OperationGroup::new(op_id, command.description().to_string());
+        group.add_operation(operation);
+        match command.coalesce_key() {
+            Some(key) => self.history.push_coalescing(group, &key)?,
+            None => self.history.commit(group)?,
+        }
+        Ok(())
+    }
+
+    /// Execute several commands as a single undo group.
+    ///
+    /// If any command fails, the ones already applied are rolled back.
+    pub fn execute_command_group<C: EditorCommand>(
+        &mut self,
+        description: &str,
+        commands: &[C],
+    ) -> Result<(), crate::commands::CommandError> {
+        let op_id = self.history.begin_operation(description);
+        let mut group = OperationGroup::new(op_id, description.to_string());
+
+        for command in commands {
+            let result = command
+                .snapshots(self)
+                .and_then(|snapshots| command.execute(self).map(|()| snapshots));
+            match result {
+                Ok((before, after)) => {
+                    let id = self.history.begin_operation(command.description());
+                    group.add_operation(Operation::new(id, command.description().to_string(), before, after));
+                }
+                Err(err) => {
+                    self.apply_operation_group(&group, HistoryDirection::Undo);
+                    return Err(err);
+                }
+            }
+        }
+
+        self.history.commit(group)?;
+        Ok(())
+    }
+
+    /// Rewrite asset references to one canonical spelling per file.
+    ///
+    /// Returns the number of references that changed; all rewrites are a
+    /// single undo step.
+    pub fn collapse_duplicate_asset_paths(&mut self) -> usize {
+        use crate::commands::ReplaceComponentCommand;
+
+        let root = crate::asset_paths::asset_root(self.project_manager.project_dir.as_deref());
+        let plan = crate::asset_paths::plan_asset_path_dedup(&self.scene, &root);
+        if plan.rewrites.is_empty() {
+            return 0;
+        }
+
+        let commands: Vec<ReplaceComponentCommand> = plan
+            .rewrites
+            .into_iter()
+            .map(|rewrite| {
+                ReplaceComponentCommand::new(
+                    rewrite.entity,
+                    rewrite.component_index,
+                    rewrite.previous,
+                    rewrite.rewritten,
+                    "Unify Asset Path",
+                )
+            })
+            .collect();
+
+        if let Err(err) = self.execute_command_group("Collapse Duplicate Asset Paths", &commands) {
+            tracing::warn!("Collapse duplicate asset paths failed: {}", err);
+            return 0;
+        }
+
+        self.validator.request_revalidation();
+        plan.unified
+    }
+
+    /// Index of the asset references held by the project's scene and
+    /// prefab files and by the open scene
+    pub fn asset_dependency_index(&self) -> crate::asset_references::AssetDependencyIndex {
+        let root = crate::asset_paths::asset_root(self.project_manager.project_dir.as_deref());
+        let build_dirs: Vec<PathBuf> = self
+            .project_manager
+            .settings
+            .platform_settings
+            .values()
+            .filter(|settings| !settings.output_dir.as_os_str().is_empty())
+            .map(|settings| root.join(&settings.output_dir))
+            .collect();
+        crate::asset_references::AssetDependencyIndex::scan(
+            &root,
+            &build_dirs,
+            &self.scene,
+            self.scene_path.as_deref(),
+        )
+    }
+
+    /// References to the asset files or folders at `paths`, along with the
+    /// scene and prefab files that couldn't be checked
+    pub fn find_asset_references(
+        &self,
+        paths: &[PathBuf],
+    ) -> (Vec<crate::asset_references::AssetReference>, Vec<crate::asset_references::UnreadableFile>) {
+        let root = crate::asset_paths::asset_root(self.project_manager.project_dir.as_deref());
+        let index = self.asset_dependency_index();
+        let mut references: Vec<crate::asset_references::AssetReference> = Vec::new();
+        for path in paths {
+            let asset = crate::asset_paths::asset_reference(&root, path);
+            for reference in index.find_references(&asset) {
+                if !references.contains(reference) {
+                    references.push(reference.clone());
+                }
+            }
+        }
+        (references, index.unreadable().to_vec())
+    }
+
+    /// Point references to the asset at `old` to `new` after it was renamed
+    /// or moved.
+    ///
+    /// The open scene is updated as one undo step; other scene and prefab
+    /// files are rewritten on disk. Returns the number of fields changed.
+    pub fn retarget_asset_references(&mut self, old: &Path, new: &Path) -> usize {
+        use crate::asset_references::{retarget_components, retarget_file, ReferenceSource};
+        use crate::commands::ReplaceComponentCommand;
+
+        let root = crate::asset_paths::asset_root(self.project_manager.project_dir.as_deref());
+        let old = crate::asset_paths::asset_reference(&root, old);
+        let new = crate::asset_paths::asset_reference(&root, new);
+        let mut changed = 0;
+
+        let mut commands = Vec::new();
+        for (id, entity) in &self.scene.entities {
+            for (index, component) in entity.components.iter().enumerate() {
+                let mut rewritten = component.clone();
+                let count = retarget_components([&mut rewritten], &old, &new);
+                if count > 0 {
+                    changed += count;
+                    commands.push(ReplaceComponentCommand::new(
+                        *id,
+                        index,
+                        component.clone(),
+                        rewritten,
+                        "Retarget Asset Path",
+                    ));
+                }
+            }
+        }
+        if !commands.is_empty() {
+            if let Err(err) = self.execute_command_group("Retarget Asset References", &commands) {
+                tracing::warn!("Retargeting asset references in the open scene failed: {}", err);
+                changed = 0;
+            }
+        }
+
+        let mut files: Vec<ReferenceSource> = self
+            .asset_dependency_index()
+            .find_references(&old)
+            .into_iter()
+            .map(|reference| reference.source.clone())
+            .filter(|source| *source != ReferenceSource::OpenScene)
+            .collect();
+        files.dedup();
+        for source in &files {
+            match retarget_file(source, &old, &new) {
+                Ok(count) => changed += count,
+                Err(err) => tracing::warn!("Failed to retarget references in {}: {}", source.label(), err),
+            }
+        }
+
+        self.validator.request_revalidation();
+        changed
+    }
+
+    /// Place `count` copies of `entity` along the `Path` on `path_entity`.
+    ///
+    /// Copies are spaced evenly by arc length; a single copy goes to the
+    /// path start. With `align_to_tangent`, each copy's +Z faces along the
+    /// path. A path with no length stacks every copy at its start. All
+    /// copies are one undo step. Returns the new entity IDs.
+    pub fn distribute_along_path(
+        &mut self,
+        entity: EntityId,
+        path_entity: EntityId,
+        count: usize,
+        align_to_tangent: bool,
+    ) -> Vec<EntityId> {
+        use crate::components::{Component, PathComponent};
+
+        if count == 0 || entity == path_entity {
+            return Vec::new();
+        }
+        let Some(source) = self.scene.get(&entity) else {
+            return Vec::new();
+        };
+        let Some(path) = self.scene.get(&path_entity).and_then(|e| {
+            e.components.iter().find_map(|c| match c {
+                Component::Path(path) => Some(path.clone()),
+                _ => None,
+            })
+        }) else {
+            tracing::warn!("Distribute along path: entity has no Path component");
+            return Vec::new();
+        };
+
+        // Sample in world space so the path entity's transform is honored
+        let world_path = PathComponent {
+            points: path
+                .points
+                .iter()
+                .filter_map(|p| self.scene.world_point(path_entity, *p))
+                .collect(),
+            closed: path.closed,
+        };
+        let start = world_path
+            .points
+            .first()
+            .copied()
+            .or_else(|| self.scene.world_position(path_entity))
+            .unwrap_or_default();
+
+        let parent = source.parent;
+        let base = source.transform.clone();
+        let fractions = world_path.distribution(count);
+        if world_path.sample(0.0).is_none() {
+            tracing::warn!("Distribute along path: path has no length, stacking copies at its start");
+        }
+
+        let transforms: Vec<Transform> = fractions
+            .iter()
+            .map(|t| {
+                let (position, tangent) = world_path.sample(*t).unwrap_or((start, [0.0; 3]));
+                let mut transform = base.clone();
+                transform.position = self.scene.local_point(parent, position);
+                if align_to_tangent && tangent != [0.0; 3] {
+                    transform.rotation = Transform::euler_facing(self.scene.local_direction(parent, tangent));
+                }
+                transform
+            })
+            .collect();
+
+        let command = DuplicateCommand::new(vec![entity; count]).with_transforms(transforms);
+        let new_ids = command.new_entities.clone();
+        if let Err(err) = self.execute_command(&command) {
+            tracing::warn!("Distribute along path failed: {}", err);
+            return Vec::new();
+        }
+
+        new_ids
+    }
+
+    /// Set transforms for multiple entities as a single undo operation
+    pub fn set_transforms_bulk(
+        &mut self,
+        entities: &[EntityId],
+        transforms: &[Transform],
+        description: &str,
+    ) {
+        if entities.is_empty() || entities.len() != transforms.len() {
+            return;
+        }
+
+        let mut ids = Vec::new();
+        let mut before = Vec::new();
+        let mut after = Vec::new();
+
+        for (entity_id, new_transform) in entities.iter().copied().zip(transforms.iter().cloned()) {
+            let Some(entity) = self.scene.get(&entity_id) else {
+                continue;
+            };
+            if entity.transform == new_transform {
+                continue;
+            }
+            ids.push(entity_id);
+            before.push(entity.transform.clone());
+            after.push(new_transform);
+        }
+
+        if ids.is_empty() {
+            return;
+        }
+
+        if let Err(err) = self.commit_transforms(ids, before, after, description) {
+            tracing::warn!("Bulk transform failed: {}", err);
+        }
+    }
+
+    /// Set transforms for multiple entities with pre-captured before values
+    /// Useful when live preview has already been applied and we want to commit to undo history
+    pub fn set_transforms_bulk_with_before(
+        &mut self,
+        entities: &[EntityId],
+        before_transforms: &[Transform],
+        after_transforms: &[Transform],
+        description: &str,
+    ) {
+        if entities.is_empty()
+            || entities.len() != before_transforms.len()
+            || entities.len() != after_transforms.len()
+        {
+            return;
+        }
+
+        let mut ids = Vec::new();
+        let mut before = Vec::new();
+        let mut after = Vec::new();
+
+        for i in 0..entities.len() {
+            let entity_id = entities[i];
+            let before_transform = &before_transforms[i];
+            let after_transform = &after_transforms[i];
+
+            // Skip if no actual change
+            if before_transform == after_transform {
+                continue;
+            }
+
+            // Ensure entity still exists
+            if self.scene.get(&entity_id).is_none() {
+                continue;
+            }
+
+            ids.push(entity_id);
+            before.push(before_transform.clone());
+            after.push(after_transform.clone());
+        }
+
+        if ids.is_empty() {
+            return;
+        }
+
+        if let Err(err) = self.commit_transforms(ids, before, after, description) {
+            tracing::warn!("Bulk transform with before failed: {}", err);
+        }
+    }
+
+    /// Align the selected entities along a world axis as one undo step
+    pub fn align_selected(&mut self, axis: AlignAxis, mode: AlignMode) {
+        let command = AlignCommand::new(self.selection.entities.clone(), axis, mode);
+        let batch = command.resolve(self);
+        self.set_transforms_bulk_with_before(&batch.entities, &batch.before, &batch.after, &command.description());
+    }
+
+    /// Space the selected entities evenly along a world axis as one undo step
+    pub fn distribute_selected(&mut self, axis: AlignAxis) {
+        let command = DistributeCommand::new(self.selection.entities.clone(), axis);
+        let batch = command.resolve(self);
+        self.set_transforms_bulk_with_before(&batch.entities, &batch.before, &batch.after, &command.description());
+    }
+
+    /// Put the selected entities on the colliders below them as one undo
+    /// step, optionally tilted to the surface
+    pub fn snap_selected_to_ground(&mut self, align_to_normal: bool) {
+        let command = SnapToGroundCommand::new(self.selection.entities.clone(), align_to_normal);
+        let batch = command.resolve(self);
+        self.set_transforms_bulk_with_before(&batch.entities, &batch.before, &batch.after, &command.description());
+    }
+
+    /// Unpack a prefab instance (one level only)
+    /// This removes the prefab link but keeps the entities as regular entities
+    pub fn unpack_prefab(&mut self, root_entity_id: EntityId) {
+        if !self.prefab_manager.is_prefab_root(root_entity_id) {
+            tracing::warn!("Entity {:?} is not a prefab root", root_entity_id);
+            return;
+        }
+
+        // Simply unregister the instance - entities remain in the scene
+        self.prefab_manager.unregister_instance(root_entity_id);
+        self.dirty = true;
+        tracing::info!("Unpacked prefab instance {:?}", root_entity_id);
+    }
+
+    /// Unpack a prefab instance completely (including nested prefabs)
+    pub fn unpack_prefab_completely(&mut self, root_entity_id: EntityId) {
+        if !self.prefab_manager.is_prefab_root(root_entity_id) {
+            tracing::warn!("Entity {:?} is not a prefab root", root_entity_id);
+            return;
+        }
+
+        // Get all entity IDs in this instance
+        let entity_ids: Vec<EntityId> = if let Some(instance) = self.prefab_manager.get_instance(root_entity_id) {
+            instance.all_entity_ids().into_iter().collect()
+        } else {
+            return;
+        };
+
+        // Unregister the main instance
+        self.prefab_manager.unregister_instance(root_entity_id);
+
+        // Find and unregister any nested prefab instances
+        let nested_roots: Vec<EntityId> = entity_ids
+            .iter()
+            .filter(|id| **id != root_entity_id && self.prefab_manager.is_prefab_root(**id))
+            .copied()
+            .collect();
+
+        for nested_root in nested_roots {
+            self.prefab_manager.unregister_instance(nested_root);
+        }
+
+        self.dirty = true;
+        tracing::info!("Unpacked prefab instance {:?} completely", root_entity_id);
+    }
+
+    /// Check if we're currently editing a prefab
+    pub fn is_editing_prefab(&self) -> bool {
+        self.editing_prefab.is_some()
+    }
+
+    /// Get the path of the prefab being edited
+    pub fn editing_prefab_path(&self) -> Option<&PathBuf> {
+        self.editing_prefab.as_ref().map(|s| &s.prefab_path)
+    }
+
+    /// Enter prefab editing mode
+    pub fn enter_prefab_edit_mode(&mut self, prefab_path: &PathBuf) -> Result<(), String> {
+        if self.editing_prefab.is_some() {
+            return Err("Already editing a prefab".to_string());
+        }
+
+        // Load the prefab
+        let prefab = crate::prefab::Prefab::load(prefab_path)
+            .map_err(|e| format!("Failed to load prefab: {}", e))?;
+
+        // Backup current state
+        let editing_state = PrefabEditingState {
+            prefab_path: prefab_path.clone(),
+            scene_backup: self.scene.clone(),
+            selection_backup: self.selection.clone(),
+            prefab_dirty: false,
+        };
+
+        // Load prefab entities into the scene
+        let (entities, _id_mapping) = prefab.instantiate_flat();
+
+        // Clear scene and load prefab content
+        self.scene = SceneData::new();
+        self.selection.clear();
+        self.history.clear();
+
+        // Add prefab entities to scene
+        for entity in entities {
+            self.scene.add_entity(entity);
+        }
+
+        self.editing_prefab = Some(editing_state);
+        tracing::info!("Entered prefab edit mode: {:?}", prefab_path);
+        Ok(())
+    }
+
+    /// Exit prefab editing mode without saving
+    pub fn exit_prefab_edit_mode(&mut self, save: bool) -> Result<(), String> {
+        let Some(editing_state) = self.editing_prefab.take() else {
+            return Err("Not in prefab edit mode".to_string());
+        };
+
+        if save && editing_state.prefab_dirty {
+            self.save_prefab_from_scene(&editing_state.prefab_path)?;
+        }
+
+        // Restore the main scene
+        self.scene = editing_state.scene_backup;
+        self.selection = editing_state.selection_backup;
+        self.history.clear();
+
+        tracing::info!("Exited prefab edit mode");
+        Ok(())
+    }
+
+    /// Save current scene content as a prefab
+    pub fn save_prefab_from_scene(&mut self, path: &PathBuf) -> Result<(), String> {
+        // Get all root entities
+        let roots = self.scene.root_entities();
+        if roots.is_empty() {
+            return Err("No entities in scene to save as prefab".to_string());
+        }
+
+        // Use the first root as the prefab root
+        let root_id = roots[0];
+        let root_entity = self.scene.get(&root_id)
+            .ok_or("Root entity not found")?;
+
+        // Build entity map for hierarchy
+        let entity_map: std::collections::HashMap<EntityId, EntityData> = self.scene.entities
+            .iter()
+            .map(|(id, data)| (*id, data.clone()))
+            .collect();
+
+        // Create prefab from entities
+        let name = path.file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("Prefab")
+            .to_string();
+
+        let prefab = crate::prefab::Prefab::from_entities(name, root_entity, &entity_map);
+
+        // Save to disk
+        prefab.save(path)
+            .map_err(|e| format!("Failed to save prefab: {}", e))?;
+
+        // Mark as clean
+        if let Some(ref mut editing_state) = self.editing_prefab {
+            editing_state.prefab_dirty = false;
+        }
+
+        tracing::info!("Saved prefab to {:?}", path);
+        Ok(())
+    }
+
+    /// Save a customized prefab instance as a variant of its source prefab.
+    ///
+    /// The variant references the source prefab and stores the instance's current
+    /// overrides as its delta. Entities that are not prefab instances are saved
+    /// as a plain prefab instead.
+    pub fn save_as_variant(&mut self, instance_root: EntityId, variant_path: &Path) -> Result<(), String> {
+        let root_entity = self.scene.get(&instance_root)
+            .ok_or("Instance root entity not found")?;
+
+        let entity_map: std::collections::HashMap<EntityId, EntityData> = self.scene.entities
+            .iter()
+            .map(|(id, data)| (*id, data.clone()))
+            .collect();
+
+        let name = variant_path.file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("Prefab")
+            .to_string();
+
+        let mut prefab = crate::prefab::Prefab::from_entities(name, root_entity, &entity_map);
+
+        if let Some(instance) = self.prefab_manager.get_instance(instance_root) {
+            prefab = prefab.with_base(crate::prefab::PrefabBase {
+                prefab_path: instance.prefab_path.clone(),
+                prefab_id: instance.prefab_id,
+                overrides: instance.overrides.clone(),
+            });
+        } else {
+            tracing::info!("Entity {:?} is not a prefab instance, saving as plain prefab", instance_root);
+        }
+
+        prefab.save(variant_path)
+            .map_err(|e| format!("Failed to save prefab variant: {}", e))?;
+
+        tracing::info!("Saved prefab variant to {:?}", variant_path);
+        Ok(())
+    }
+
+    /// Create an empty variant of a prefab asset next to it
+    /// ("Crate.prefab" -> "Crate Variant.prefab", numbered if taken).
+    ///
+    /// The variant stores only its base reference and overrides; it
+    /// follows later changes to the base. Returns the variant's path.
+    pub fn create_variant_from(&mut self, prefab_path: &Path) -> Result<PathBuf, String> {
+        let base = crate::prefab::Prefab::load(prefab_path)
+            .map_err(|e| format!("Failed to load prefab {}: {}", prefab_path.display(), e))?;
+
+        let stem = prefab_path.file_stem().and_then(|s| s.to_str()).unwrap_or("Prefab");
+        let name = format!("{} Variant", stem);
+        let variant_path = crate::prefab::free_variant_path(prefab_path);
+
+        let variant = crate::prefab::Prefab::variant_of(name, &base, prefab_path.to_path_buf());
+        variant.save(&variant_path)
+            .map_err(|e| format!("Failed to save prefab variant: {}", e))?;
+
+        tracing::info!("Created prefab variant {:?}", variant_path);
+        Ok(variant_path)
+    }
+
+    /// Mark the prefab as having unsaved changes
+    #[allow(dead_code)] // Intentionally kept for API completeness
+    pub fn mark_prefab_dirty(&mut self) {
+        if let Some(ref mut editing_state) = self.editing_prefab {
+            editing_state.prefab_dirty = true;
+        }
+    }
+
+    /// Check if the editing prefab has unsaved changes
+    pub fn prefab_has_unsaved_changes(&self) -> bool {
+        self.editing_prefab.as_ref().map(|s| s.prefab_dirty).unwrap_or(false)
+    }
+
+    /// Track a property override on a prefab instance
+    /// Returns true if the entity is part of a prefab instance
+    pub fn track_prefab_override(
+        &mut self,
+        entity_id: EntityId,
+        property_path: &str,
+        value: serde_json::Value,
+    ) -> bool {
+        // Find the prefab instance containing this entity
+        let instance_root = {
+            let Some(instance) = self.prefab_manager.find_instance_containing(entity_id) else {
+                return false;
+            };
+            instance.root_entity_id
+        };
+
+        // Get the local ID for this entity within the prefab
+        let local_id = {
+            let Some(instance) = self.prefab_manager.get_instance(instance_root) else {
+                return false;
+            };
+            match instance.get_local_id(entity_id) {
+                Some(id) => id.to_string(),
+                None => return false,
+            }
+        };
+
+        // Create the override
+        let override_ = crate::prefab::PropertyOverride {
+            entity_path: local_id,
+            property_path: property_path.to_string(),
+            value: value.clone(),
+        };
+
+        // Add the override to the instance
+        if let Some(instance) = self.prefab_manager.get_instance_mut(instance_root) {
+            tracing::debug!(
+                "Tracked override for entity {:?}: {} = {:?}",
+                entity_id,
+                property_path,
+                value
+            );
+            instance.set_override(override_);
+        }
+
+        true
+    }
+
+    /// Check if a property is overridden on a prefab instance entity
+    pub fn is_property_overridden(&self, entity_id: EntityId, property_path: &str) -> bool {
+        let Some(instance) = self.prefab_manager.find_instance_containing(entity_id) else {
+            return false;
+        };
+
+        let Some(local_id) = instance.get_local_id(entity_id) else {
+            return false;
+        };
+
+        instance.is_overridden(&local_id.to_string(), property_path)
+    }
+
+    /// Revert a specific property override on a prefab instance entity
+    pub fn revert_property_override(&mut self, entity_id: EntityId, property_path: &str) -> bool {
+        // Find the instance and get needed data
+        let (instance_root, local_id, prefab_path) = {
+            let Some(instance) = self.prefab_manager.find_instance_containing(entity_id) else {
+                return false;
+            };
+            let Some(local_id) = instance.get_local_id(entity_id) else {
+                return false;
+            };
+            (instance.root_entity_id, local_id, instance.prefab_path.clone())
+        };
+
+        // Load the prefab to get the original value
+        let prefab = match crate::prefab::Prefab::load(&prefab_path) {
+            Ok(p) => p,
+            Err(e) => {
+                tracing::error!("Failed to load prefab for revert: {}", e);
+                return false;
+            }
+        };
+
+        // Find the entity in the prefab by local_id
+        let prefab_entity = self.find_prefab_entity_by_local_id(&prefab.root, local_id);
+        let Some(prefab_entity) = prefab_entity else {
+            tracing::warn!("Could not find prefab entity with local_id {}", local_id);
+            return false;
+        };
+
+        // Revert the property value
+        let reverted = self.revert_property_from_prefab(entity_id, property_path, prefab_entity);
+
+        // Remove the override from the instance
+        if reverted {
+            if let Some(instance) = self.prefab_manager.get_instance_mut(instance_root) {
+                instance.remove_override(&local_id.to_string(), property_path);
+            }
+        }
+
+        reverted
+    }
+
+    fn find_prefab_entity_by_local_id<'a>(
+        &self,
+        entity: &'a crate::prefab::PrefabEntity,
+        local_id: u32,
+    ) -> Option<&'a crate::prefab::PrefabEntity> {
+        if entity.local_id == local_id {
+            return Some(entity);
+        }
+        for child in &entity.children {
+            if let Some(found) = self.find_prefab_entity_by_local_id(child, local_id) {
+                return Some(found);
+            }
+        }
+        None
+    }
+
+    fn revert_property_from_prefab(
+        &mut self,
+        entity_id: EntityId,
+        property_path: &str,
+        prefab_entity: &crate::prefab::PrefabEntity,
+    ) -> bool {
+        let Some(entity) = self.scene.get_mut(&entity_id) else {
+            return false;
+        };
+
+        // Handle common property paths
+        match property_path {
+            "name" => {
+                entity.name = prefab_entity.name.clone();
+                true
+            }
+            "transform.position" | "transform.position.x" | "transform.position.y" | "transform.position.z" => {
+                entity.transform.position = prefab_entity.transform.position;
+                true
+            }
+            "transform.rotation" | "transform.rotation.x" | "transform.rotation.y" | "transform.rotation.z" => {
+                entity.transform.rotation = prefab_entity.transform.rotation;
+                true
+            }
+            "transform.scale" | "transform.scale.x" | "transform.scale.y" | "transform.scale.z" => {
+                entity.transform.scale = prefab_entity.transform.scale;
+                true
+            }
+            "active" => {
+                entity.active = true; // Default for prefab entities
+                true
+            }
+            "is_static" => {
+                entity.is_static = false; // Default for prefab entities
+                true
+            }
+            _ => {
+                // Whole component slot ("components[N]")
+                let prefab_component = crate::prefab::component_index(property_path)
+                    .and_then(|index| Some((index, prefab_entity.components.get(index)?)));
+                if let Some((index, component)) = prefab_component {
+                    if index < entity.components.len() {
+                        entity.components[index] = component.clone();
+                        return true;
+                    }
+                }
+
+                // Single component field ("Light.intensity", "components[N].range")
+                if crate::prefab::copy_component_field(&prefab_entity.components, &mut entity.components, property_path) {
+                    return true;
+                }
+
+                tracing::warn!("Cannot revert property '{}': no matching prefab value", property_path);
+                false
+            }
+        }
+    }
+
+    /// Write an instance entity's current value of a property into its
+    /// prefab entity. Returns false for properties prefabs don't store.
+    fn apply_property_to_prefab(
+        entity: &EntityData,
+        property_path: &str,
+        prefab_entity: &mut crate::prefab::PrefabEntity,
+    ) -> bool {
+        match property_path {
+            "name" => {
+                prefab_entity.name = entity.name.clone();
+                true
+            }
+            "transform.position" | "transform.position.x" | "transform.position.y" | "transform.position.z" => {
+                prefab_entity.transform.position = entity.transform.position;
+                true
+            }
+            "transform.rotation" | "transform.rotation.x" | "transform.rotation.y" | "transform.rotation.z" => {
+                prefab_entity.transform.rotation = entity.transform.rotation;
+                true
+            }
+            "transform.scale" | "transform.scale.x" | "transform.scale.y" | "transform.scale.z" => {
+                prefab_entity.transform.scale = entity.transform.scale;
+                true
+            }
+            _ => {
+                if crate::prefab::copy_component_field(&entity.components, &mut prefab_entity.components, property_path) {
+                    return true;
+                }
+                let Some(index) = crate::prefab::component_index(property_path) else {
+                    return false;
+                };
+                let Some(component) = entity.components.get(index) else {
+                    return false;
+                };
+                let len = prefab_entity.components.len();
+                match prefab_entity.components.get_mut(index) {
+                    Some(slot) => *slot = component.clone(),
+                    None if index == len => {
+                        prefab_entity.components.push(component.clone());
+                    }
+                    None => return false,
+                }
+                true
+            }
+        }
+    }
+
+    /// Write a prefab instance's overrides back into its source prefab.
+    ///
+    /// Each override takes the instance's current value, so later edits to
+    /// an overridden property are included. Applied overrides are cleared
+    /// since the instance now matches the prefab; overrides of properties
+    /// prefabs don't store (`active`, `is_static`) stay on the instance.
+    /// Other instances of the prefab take the new values unless they
+    /// override the same field. The whole apply is one undo step, which
+    /// restores the prefab file, the cleared overrides and the other
+    /// instances.
+    ///
+    /// Returns the number of overrides applied.
+    pub fn apply_overrides_to_prefab(&mut self, root_entity_id: EntityId) -> Result<usize, String> {
+        let instance = self.prefab_manager.get_instance(root_entity_id)
+            .ok_or("Entity is not the root of a prefab instance")?;
+        let prefab_path = instance.prefab_path.clone();
+        let overrides = instance.overrides.clone();
+        let id_mapping = instance.id_mapping.clone();
+
+        let mut prefab = match crate::prefab::Prefab::load(&prefab_path) {
+            Ok(prefab) => prefab,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Err(format!(
+                    "Source prefab {} not found; it may have been moved or deleted",
+                    prefab_path.display()
+                ));
+            }
+            Err(e) => return Err(format!("Failed to load prefab {}: {}", prefab_path.display(), e)),
+        };
+
+        let mut applied = Vec::new();
+        for override_ in &overrides {
+            let Ok(local_id) = override_.entity_path.parse::<u32>() else {
+                tracing::warn!("Skipping override with invalid entity path '{}'", override_.entity_path);
+                continue;
+            };
+            let Some(entity) = id_mapping.get(&local_id).and_then(|id| self.scene.get(id)) else {
+                tracing::warn!("Skipping override for missing instance entity {}", local_id);
+                continue;
+            };
+            let Some(prefab_entity) = prefab.root.find_mut(local_id) else {
+                tracing::warn!("Prefab {} has no entity with local_id {}", prefab_path.display(), local_id);
+                continue;
+            };
+            if Self::apply_property_to_prefab(entity, &override_.property_path, prefab_entity) {
+                // A variant's tree is rebuilt from its base on load, so
+                // the change must also become one of its overrides
+                let value = prefab_entity.property_value(&override_.property_path);
+                if let (Some(base), Some(value)) = (prefab.base.as_mut(), value) {
+                    base.set_override(crate::prefab::PropertyOverride {
+                        entity_path: override_.entity_path.clone(),
+                        property_path: override_.property_path.clone(),
+                        value,
+                    });
+                }
+                applied.push((local_id, override_.property_path.clone()));
+            } else {
+                tracing::warn!("Cannot apply '{}' to a prefab; keeping the override", override_.property_path);
+            }
+        }
+
+        if applied.is_empty() {
+            return Ok(0);
+        }
+
+        let prefab_file_before = std::fs::read_to_string(&prefab_path)
+            .map_err(|e| format!("Failed to read prefab {}: {}", prefab_path.display(), e))?;
+        let prefab_file_after = prefab
+            .to_ron()
+            .map_err(|e| e.to_string())
+            .and_then(|content| std::fs::write(&prefab_path, &content).map(|_| content).map_err(|e| e.to_string()))
+            .map_err(|e| format!("Failed to save prefab {}: {}", prefab_path.display(), e))?;
+
+        if let Some(instance) = self.prefab_manager.get_instance_mut(root_entity_id) {
+            for (local_id, property_path) in &applied {
+                instance.remove_override(&local_id.to_string(), property_path);
+            }
+        }
+
+        // Propagate to other instances where the property isn't overridden
+        let applied_ref = &applied;
+        let others: Vec<(EntityId, u32, String)> = self.prefab_manager
+            .all_instances()
+            .filter(|other| other.root_entity_id != root_entity_id && other.prefab_path == prefab_path)
+            .flat_map(|other| {
+                applied_ref.iter().filter_map(move |(local_id, property_path)| {
+                    let entity_id = *other.id_mapping.get(local_id)?;
+                    let overridden = other.overrides_field(&local_id.to_string(), property_path);
+                    (!overridden).then(|| (entity_id, *local_id, property_path.clone()))
+                })
+            })
+            .collect();
+        let mut seen = HashSet::new();
+        let touched: Vec<EntityId> = others
+            .iter()
+            .map(|(id, _, _)| *id)
+            .filter(|id| seen.insert(*id))
+            .collect();
+        let snapshot = |state: &Self| -> Vec<(EntityId, EntityData)> {
+            touched
+                .iter()
+                .filter_map(|id| Some((*id, state.scene.get(id)?.clone())))
+                .collect()
+        };
+        let before = snapshot(self);
+        for (entity_id, local_id, property_path) in &others {
+            if let Some(prefab_entity) = self.find_prefab_entity_by_local_id(&prefab.root, *local_id) {
+                self.revert_property_from_prefab(*entity_id, property_path, prefab_entity);
+            }
+        }
+        let after = snapshot(self);
+
+        let overrides_after = self
+            .prefab_manager
+            .get_instance(root_entity_id)
+            .map(|instance| instance.overrides.clone())
+            .unwrap_or_default();
+        let record = |prefab_file: String, overrides: &[crate::prefab::PropertyOverride], entities| {
+            Ok::<_, String>(crate::commands::PrefabApplySnapshot {
+                marker: crate::commands::PrefabApplySnapshot::MARKER,
+                prefab_path: prefab_path.clone(),
+                prefab_file,
+                instance: root_entity_id,
+                overrides: serde_json::to_string(overrides).map_err(|e| e.to_string())?,
+                entities,
+            })
+        };
+        let recorded = record(prefab_file_before, &overrides, before)
+            .and_then(|before| Ok((before, record(prefab_file_after, &overrides_after, after)?)))
+            .and_then(|(before, after)| {
+                let before = StateSnapshot::from_value(&before).map_err(|e| e.to_string())?;
+                let after = StateSnapshot::from_value(&after).map_err(|e| e.to_string())?;
+                let description = "Apply Prefab Overrides";
+                let op_id = self.history.begin_operation(description);
+                let mut group = OperationGroup::new(op_id, description.to_string());
+                group.add_operation(Operation::new(op_id, description.to_string(), before, after));
+                self.history.commit(group).map_err(|e| e.to_string())
+            });
+        if let Err(e) = recorded {
+            tracing::warn!("Failed to record prefab override apply: {}", e);
+        }
+
+        if let Err(e) = self.prefab_manager.reload_prefab(&prefab_path) {
+            tracing::warn!("Failed to refresh cached prefab {}: {}", prefab_path.display(), e);
+        }
+
+        self.dirty = true;
+        tracing::info!("Applied {} override(s) to {}", applied.len(), prefab_path.display());
+        Ok(applied.len())
+    }
+
+    /// Revert all overrides on a prefab instance entity
+    pub fn revert_all_overrides(&mut self, entity_id: EntityId) -> bool {
+        let Some(instance) = self.prefab_manager.find_instance_containing(entity_id) else {
+            return false;
+        };
+
+        let root_id = instance.root_entity_id;
+        let prefab_path = instance.prefab_path.clone();
+
+        // Load the prefab
+        let prefab = match crate::prefab::Prefab::load(&prefab_path) {
+            Ok(p) => p,
+            Err(e) => {
+                tracing::error!("Failed to load prefab for revert: {}", e);
+                return false;
+            }
+        };
+
+        // Get the local ID for this entity
+        let Some(instance) = self.prefab_manager.get_instance(root_id) else {
+            return false;
+        };
+        let Some(local_id) = instance.get_local_id(entity_id) else {
+            return false;
+        };
+
+        // Find the prefab entity
+        let Some(prefab_entity) = self.find_prefab_entity_by_local_id(&prefab.root, local_id) else {
+            return false;
+        };
+
+        // Revert all properties
+        if let Some(entity) = self.scene.get_mut(&entity_id) {
+            entity.name = prefab_entity.name.clone();
+            entity.transform = prefab_entity.transform.clone();
+            entity.components = prefab_entity.components.clone();
+        }
+
+        // Clear all overrides for this entity
+        if let Some(instance) = self.prefab_manager.get_instance_mut(root_id) {
+            instance.overrides.retain(|o| o.entity_path != local_id.to_string());
+        }
+
+        self.dirty = true;
+        true
+    }
+
+    /// Get all overrides for an entity (if it's part of a prefab instance)
+    pub fn get_entity_overrides(&self, entity_id: EntityId) -> Vec<String> {
+        let Some(instance) = self.prefab_manager.find_instance_containing(entity_id) else {
+            return Vec::new();
+        };
+
+        let Some(local_id) = instance.get_local_id(entity_id) else {
+            return Vec::new();
+        };
+
+        let local_id_str = local_id.to_string();
+        instance.overrides
+            .iter()
+            .filter(|o| o.entity_path == local_id_str)
+            .map(|o| o.property_path.clone())
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum HistoryDirection {
+    Undo,
+    Redo,
+}
+
+impl Default for EditorState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GameplayWorld for EditorState {
+    /// Scene edits and simulation steps both change raycast results; the
+    /// frame count is zero while editing
+    fn revision(&self) -> u64 {
+        self.history.revision() ^ self.play_mode.frame_count.rotate_left(32)
+    }
+
+    fn raycast(&self, origin: [f32; 3], direction: [f32; 3], max_distance: f32, hit_triggers: bool) -> Option<RayHit> {
+        let filter = crate::physics::RaycastFilter {
+            include_triggers: hit_triggers,
+            ..Default::default()
+        };
+        self.raycast_scene(origin, direction, max_distance, &filter).map(|hit| RayHit {
+            entity: hit.entity.0.to_string(),
+            point: hit.point,
+            normal: hit.normal,
+            distance: hit.distance,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::{
+        BoxColliderComponent, Component, MeshRendererComponent, RigidbodyComponent,
+    };
+
+    fn entity(name: &str, components: Vec<Component>) -> EntityData {
+        EntityData {
+            name: name.to_string(),
+            components,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_name_prefix() {
+        assert_eq!(name_prefix("Tree_01"), "Tree");
+        assert_eq!(name_prefix("Tree (2)"), "Tree");
+        assert_eq!(name_prefix("Tree (Copy)"), "Tree");
+        assert_eq!(name_prefix("Tree"), "Tree");
+        assert_eq!(name_prefix("42"), "42");
+    }
+
+    #[test]
+    fn test_entity_data_round_trips_through_bincode() {
+        use crate::components::LightComponent;
+
+        let entity = EntityData {
+            name: "Lamp".to_string(),
+            components: vec![
+                Component::Light(LightComponent {
+                    intensity: 2.5,
+                    ..Default::default()
+                }),
+                Component::BoxCollider(BoxColliderComponent::default()),
+            ],
+            tags: vec!["lights".to_string(), "interior".to_string()],
+            color_label: Some(ColorLabel::Yellow),
+            metadata: BTreeMap::from([("author".to_string(), "level design".to_string())]),
+            ..Default::default()
+        };
+        let id = EntityId::new();
+
+        // Undo snapshots are bincode, which can't read internally tagged
+        // components or skipped fields on their own
+        let snapshot = StateSnapshot::from_value(&vec![(id, entity.clone())]).unwrap();
+        let decoded: Vec<(EntityId, EntityData)> = snapshot.to_value().unwrap();
+        assert_eq!(decoded.len(), 1);
+        let (decoded_id, decoded) = &decoded[0];
+        assert_eq!(*decoded_id, id);
+        assert_eq!(decoded.name, entity.name);
+        assert_eq!(decoded.components, entity.components);
+        assert_eq!(decoded.tags, entity.tags);
+        assert_eq!(decoded.color_label, entity.color_label);
+        assert_eq!(decoded.metadata, entity.metadata);
+    }
+
+    #[test]
+    fn test_select_similar_name_prefix() {
+        let mut scene = SceneData::new();
+        let tree = scene.add_entity(entity("Tree_01", Vec::new()));
+        let tree2 = scene.add_entity(entity("Tree_02", Vec::new()));
+        let tree3 = scene.add_entity(entity("tree (Copy)", Vec::new()));
+        let _treehouse = scene.add_entity(entity("Treehouse", Vec::new()));
+        let _rock = scene.add_entity(entity("Rock_01", Vec::new()));
+
+        let similar = scene.select_similar(tree, SimilarityCriterion::NamePrefix);
+        assert_eq!(similar, vec![tree, tree2, tree3]);
+    }
+
+    #[test]
+    fn test_select_similar_component_set() {
+        let mut scene = SceneData::new();
+        let physics = || {
+            vec![
+                Component::Rigidbody(RigidbodyComponent::default()),
+                Component::BoxCollider(BoxColliderComponent::default()),
+            ]
+        };
+
+        let reference = scene.add_entity(entity("Crate", physics()));
+
+        // Same types in a different order with different field values
+        let mut reordered = physics();
+        reordered.reverse();
+        if let Component::Rigidbody(body) = &mut reordered[1] {
+            body.mass = 50.0;
+        }
+        let reordered = scene.add_entity(entity("Barrel", reordered));
+
+        // Superset and subset should not match
+        let mut superset = physics();
+        superset.push(Component::MeshRenderer(MeshRendererComponent::default()));
+        let _superset = scene.add_entity(entity("Rendered Crate", superset));
+        let _subset = scene.add_entity(entity("Body", vec![Component::Rigidbody(RigidbodyComponent::default())]));
+        let _empty = scene.add_entity(entity("Empty", Vec::new()));
+
+        let similar = scene.select_similar(reference, SimilarityCriterion::ComponentSet);
+        assert_eq!(similar, vec![reference, reordered]);
+    }
+
+    #[test]
+    fn test_drop_to_ground_respects_up_axis() {
+        let mut state = EditorState::new();
+        let id = state.scene.add_entity(EntityData {
+            transform: Transform {
+                position: [1.0, 2.0, 3.0],
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        state.selection.clear();
+        state.selection.add(id);
+
+        state.set_up_axis(crate::project::UpAxis::Z, false);
+        state.drop_selected_to_ground();
+        assert_eq!(state.scene.get(&id).unwrap().transform.position, [1.0, 2.0, 0.0]);
+    }
+
+    #[test]
+    fn test_world_points_match_world_point() {
+        let mut scene = SceneData::new();
+        let parent = scene.add_entity(EntityData {
+            transform: Transform {
+                position: [1.0, 2.0, 3.0],
+                rotation: [0.0, 90.0, 0.0],
+                scale: [2.0, 1.0, 3.0],
+            },
+            ..Default::default()
+        });
+        let child = scene.add_entity(EntityData {
+            parent: Some(parent),
+            transform: Transform {
+                position: [0.5, 0.0, -1.0],
+                rotation: [30.0, 0.0, 45.0],
+                scale: [1.0, 2.0, 1.0],
+            },
+            ..Default::default()
+        });
+        scene.get_mut(&parent).unwrap().children.push(child);
+
+        let locals = [[0.0, 0.0, 0.0], [1.0, -2.0, 0.5], [-3.0, 4.0, 2.0]];
+        let close = |a: [f32; 3], b: [f32; 3]| a.iter().zip(b).all(|(x, y)| (x - y).abs() < 1e-4);
+        for (local, world) in locals.iter().zip(scene.world_points(child, &locals)) {
+            let expected = scene.world_point(child, *local).unwrap();
+            assert!(close(world, expected), "{:?} != {:?}", world, expected);
+        }
+        assert!(scene.world_points(EntityId::new(), &locals).is_empty());
+    }
+
+    #[test]
+    fn test_world_bounds_from_components() {
+        let mut scene = SceneData::new();
+        let parent = scene.add_entity(EntityData {
+            transform: Transform {
+                position: [10.0, 0.0, 0.0],
+                scale: [2.0, 2.0, 2.0],
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        let child = scene.add_entity(EntityData {
+            parent: Some(parent),
+            transform: Transform {
+                rotation: [0.0, 0.0, 90.0],
+                ..Default::default()
+            },
+            components: vec![Component::BoxCollider(BoxColliderComponent {
+                size: [4.0, 2.0, 2.0],
+                center: [1.0, 0.0, 0.0],
+                ..Default::default()
+            })],
+            ..Default::default()
+        });
+        scene.get_mut(&parent).unwrap().children.push(child);
+
+        // The box lies along Y after the rotation, doubled by the parent scale
+        let (min, max) = scene.world_bounds(child).unwrap();
+        let close = |a: [f32; 3], b: [f32; 3]| a.iter().zip(b).all(|(x, y)| (x - y).abs() < 1e-3);
+        assert!(close(min, [8.0, -2.0, -2.0]), "{:?}", min);
+        assert!(close(max, [12.0, 6.0, 2.0]), "{:?}", max);
+
+        // Without components the bounds are the position
+        assert_eq!(scene.world_bounds(parent), Some(([10.0, 0.0, 0.0], [10.0, 0.0, 0.0])));
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_timestamp(1_609_459_200), "2021-01-01T00:00:00Z");
+        // Leap day and the day after
+        assert_eq!(format_timestamp(1_709_164_800), "2024-02-29T00:00:00Z");
+        assert_eq!(format_timestamp(1_709_251_199), "2024-02-29T23:59:59Z");
+        assert_eq!(format_timestamp(1_709_251_200), "2024-03-01T00:00:00Z");
+        // End of a year
+        assert_eq!(format_timestamp(1_704_067_199), "2023-12-31T23:59:59Z");
+        // 2000 is a leap year, 2100 is not
+        assert_eq!(format_timestamp(951_782_400), "2000-02-29T00:00:00Z");
+        assert_eq!(format_timestamp(4_107_542_400), "2100-03-01T00:00:00Z");
+    }
+
+    #[test]
+    fn test_combine_static_meshes_is_one_undo_step() {
+        let mut state = EditorState::new();
+        let mesh = || {
+            vec![Component::MeshRenderer(MeshRendererComponent {
+                mesh: "meshes/rock.glb".to_string(),
+                ..Default::default()
+            })]
+        };
+        let mut sources = Vec::new();
+        for _ in 0..2 {
+            let mut data = entity("Rock", mesh());
+            data.is_static = true;
+            sources.push(state.scene.add_entity(data));
+        }
+        let moving = state.scene.add_entity(entity("Cart", mesh()));
+
+        let combined = state.combine_static_meshes(&[sources[0], moving, sources[1]]).unwrap();
+        assert!(state.scene.get(&combined).is_some());
+        assert!(sources.iter().all(|id| !state.scene.get(id).unwrap().active));
+        assert!(state.scene.get(&moving).unwrap().active);
+
+        state.undo().unwrap();
+        assert!(state.scene.get(&combined).is_none());
+        assert!(sources.iter().all(|id| state.scene.get(id).unwrap().active));
+
+        state.redo().unwrap();
+        assert!(state.scene.get(&combined).is_some());
+        assert!(sources.iter().all(|id| !state.scene.get(id).unwrap().active));
+    }
+
+    #[test]
+    fn test_drop_to_ground_lands_on_collider() {
+        let mut state = EditorState::new();
+        state.scene.add_entity(EntityData {
+            transform: Transform {
+                position: [0.0, 1.0, 0.0],
+                ..Default::default()
+            },
+            components: vec![Component::BoxCollider(BoxColliderComponent {
+                size: [10.0, 2.0, 10.0],
+                ..Default::default()
+            })],
+            ..Default::default()
+        });
+        let id = state.scene.add_entity(EntityData {
+            transform: Transform {
+                position: [1.0, 6.0, 1.0],
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        state.selection.clear();
+        state.selection.add(id);
+
+        state.drop_selected_to_ground();
+        assert_eq!(state.scene.get(&id).unwrap().transform.position, [1.0, 2.0, 1.0]);
+    }
+
+    #[test]
+    fn test_snap_to_ground_is_one_undo_step() {
+        use crate::components::BoxColliderComponent;
+
+        let mut state = EditorState::new();
+        let box_at = |position: [f32; 3], rotation: [f32; 3], size: [f32; 3]| EntityData {
+            transform: Transform {
+                position,
+                rotation,
+                ..Default::default()
+            },
+            components: vec![Component::BoxCollider(BoxColliderComponent {
+                size,
+                ..Default::default()
+            })],
+            ..Default::default()
+        };
+        // Floor plane with its top face at y = 0
+        state.scene.add_entity(box_at([0.0, -0.5, 0.0], [0.0; 3], [20.0, 1.0, 20.0]));
+        let cube = state.scene.add_entity(box_at([2.0, 5.0, 3.0], [10.0, 45.0, 0.0], [1.0, 1.0, 1.0]));
+        let off_floor = state.scene.add_entity(box_at([50.0, 5.0, 0.0], [0.0; 3], [1.0, 1.0, 1.0]));
+
+        // Rotations go through quaternions in the transform command
+        let close = |a: [f32; 3], b: [f32; 3]| a.iter().zip(b).all(|(x, y)| (x - y).abs() < 1e-3);
+
+        state.selection = Selection::with_entities([cube, off_floor]);
+        state.snap_selected_to_ground(false);
+        let snapped = state.scene.get(&cube).unwrap().transform.clone();
+        assert_eq!(snapped.position, [2.0, 0.0, 3.0]);
+        assert!(close(snapped.rotation, [10.0, 45.0, 0.0]), "{:?}", snapped.rotation);
+        // Nothing below: left unchanged
+        assert_eq!(state.scene.get(&off_floor).unwrap().transform.position, [50.0, 5.0, 0.0]);
+        assert_eq!(state.history.undo_description(), Some("Snap to Ground"));
+
+        state.undo().unwrap();
+        assert_eq!(state.scene.get(&cube).unwrap().transform.position, [2.0, 5.0, 3.0]);
+
+        // Aligning to the flat floor removes the tilt but keeps the heading
+        state.snap_selected_to_ground(true);
+        let aligned = state.scene.get(&cube).unwrap().transform.clone();
+        assert_eq!(aligned.position, [2.0, 0.0, 3.0]);
+        assert!(close(aligned.rotation, [0.0, 45.0, 0.0]), "{:?}", aligned.rotation);
+        assert_eq!(state.history.undo_description(), Some("Snap to Ground (Aligned)"));
+    }
+
+    #[test]
+    fn test_snap_to_ground_moves_selected_children_with_their_parent() {
+        use crate::components::BoxColliderComponent;
+
+        let mut state = EditorState::new();
+        let collider = |size: [f32; 3]| {
+            vec![Component::BoxCollider(BoxColliderComponent {
+                size,
+                ..Default::default()
+            })]
+        };
+        // Floor with its top face at y = 0
+        state.scene.add_entity(EntityData {
+            transform: Transform {
+                position: [0.0, -0.5, 0.0],
+                ..Default::default()
+            },
+            components: collider([20.0, 1.0, 20.0]),
+            ..Default::default()
+        });
+        let parent = state.scene.add_entity(EntityData {
+            transform: Transform {
+                position: [0.0, 5.0, 0.0],
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        // Directly below the parent, so it would be the parent's ground
+        let child = state.scene.add_entity(EntityData {
+            parent: Some(parent),
+            transform: Transform {
+                position: [0.0, -2.0, 0.0],
+                ..Default::default()
+            },
+            components: collider([1.0, 1.0, 1.0]),
+            ..Default::default()
+        });
+        state.scene.get_mut(&parent).unwrap().children.push(child);
+
+        state.selection = Selection::with_entities([parent, child]);
+        state.snap_selected_to_ground(false);
+        assert_eq!(state.scene.get(&parent).unwrap().transform.position, [0.0, 0.0, 0.0]);
+        assert_eq!(state.scene.get(&child).unwrap().transform.position, [0.0, -2.0, 0.0]);
+    }
+
+    #[test]
+    fn test_set_up_axis_reorient() {
+        let mut state = EditorState::new();
+        let id = state.scene.add_entity(EntityData {
+            transform: Transform {
+                position: [1.0, 5.0, 0.0],
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        let original = Transform {
+            rotation: [0.0, 30.0, 20.0],
+            ..Default::default()
         };
+        let turned = state.scene.add_entity(EntityData {
+            transform: original.clone(),
+            ..Default::default()
+        });
 
-        // Second pass: remove from old parent's children
-        if let Some(old_parent_id) = old_parent {
-            if let Some(parent) = self.scene.get_mut(&old_parent_id) {
-                parent.children.retain(|id| id != &entity_id);
-            }
+        state.set_up_axis(crate::project::UpAxis::Z, true);
+        let close = |a: [f32; 3], b: [f32; 3]| a.iter().zip(b).all(|(x, y)| (x - y).abs() < 1e-3);
+        let transform = &state.scene.get(&id).unwrap().transform;
+        assert_eq!(transform.position, [1.0, 0.0, 5.0]);
+        assert!(close(transform.rotation, [90.0, 0.0, 0.0]), "{:?}", transform.rotation);
+
+        // Yaw and roll survive: every local axis ends up where the conversion sends it
+        let reoriented = &state.scene.get(&turned).unwrap().transform;
+        for axis in [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]] {
+            let expected = rotate_axis(original.rotate(axis), 0, 90f32.to_radians());
+            assert!(close(reoriented.rotate(axis), expected), "{:?}", reoriented.rotation);
         }
 
-        // Third pass: update entity's parent reference
-        if let Some(entity) = self.scene.get_mut(&entity_id) {
-            entity.parent = new_parent;
-        }
+        // Conventions only: content is left untouched
+        state.set_up_axis(crate::project::UpAxis::Y, false);
+        assert_eq!(state.scene.get(&id).unwrap().transform.position, [1.0, 0.0, 5.0]);
+        assert_eq!(state.project_manager.settings.physics.gravity, [0.0, -9.81, 0.0]);
+    }
 
-        // Fourth pass: add to new parent's children
-        self.attach_to_parent(entity_id, new_parent);
+    fn temp_prefab_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("{}_{}.prefab", name, Uuid::new_v4()))
     }
 
-    fn attach_to_parent(&mut self, entity_id: EntityId, parent_id: Option<EntityId>) {
-        if let Some(parent_id) = parent_id {
-            if let Some(parent) = self.scene.get_mut(&parent_id) {
-                if !parent.children.contains(&entity_id) {
-                    parent.children.push(entity_id);
-                }
-            }
+    #[test]
+    fn test_save_as_variant() {
+        use crate::prefab::{Prefab, PrefabInstance, PropertyOverride};
+
+        let mut state = EditorState::new();
+        let base_path = PathBuf::from("Assets/Crate.prefab");
+        let base = Prefab::new("Crate");
+        let (root_data, id_mapping) = base.instantiate();
+        let root = state.scene.add_entity(root_data);
+
+        let overrides = vec![
+            PropertyOverride {
+                entity_path: "0".to_string(),
+                property_path: "name".to_string(),
+                value: serde_json::json!("Red Crate"),
+            },
+            PropertyOverride {
+                entity_path: "0".to_string(),
+                property_path: "transform.scale".to_string(),
+                value: serde_json::json!([2.0, 2.0, 2.0]),
+            },
+        ];
+        let mut instance = PrefabInstance::new(root, base_path.clone(), base.id, id_mapping);
+        for override_ in &overrides {
+            instance.set_override(override_.clone());
         }
-    }
+        state.prefab_manager.register_instance(instance);
 
-    /// Execute an editor command and commit its undo/redo snapshot
-    pub fn execute_command<C: EditorCommand>(&mut self, command: &C) -> Result<(), crate::commands::CommandError> {
-        let (before, after) = command.snapshots(self)?;
-        let op_id = self.history.begin_operation(command.description());
-        command.execute(self)?;
+        let variant_path = temp_prefab_path("variant");
+        state.save_as_variant(root, &variant_path).unwrap();
+        let variant = Prefab::load(&variant_path).unwrap();
+        let _ = std::fs::remove_file(&variant_path);
 
-        let operation = Operation::new(op_id, command.description().to_string(), before, after);
-        let mut group = OperationGroup::new(op_id, command.description().to_string());
-        group.add_operation(operation);
-        self.history.commit(group)?;
-        Ok(())
+        let variant_base = variant.base.expect("variant should reference its base");
+        assert_eq!(variant_base.prefab_path, base_path);
+        assert_eq!(variant_base.prefab_id, base.id);
+        assert_eq!(variant_base.overrides, overrides);
     }
 
-    /// Set transforms for multiple entities as a single undo operation
-    pub fn set_transforms_bulk(
-        &mut self,
-        entities: &[EntityId],
-        transforms: &[Transform],
-        description: &str,
-    ) {
-        if entities.is_empty() || entities.len() != transforms.len() {
-            return;
+    #[test]
+    fn test_apply_overrides_to_prefab() {
+        use crate::prefab::{Prefab, PrefabInstance, PropertyOverride};
+        use serde_json::json;
+
+        let path = temp_prefab_path("apply");
+        let prefab = Prefab::new("Crate");
+        prefab.save(&path).unwrap();
+
+        let mut state = EditorState::new();
+        let mut roots = Vec::new();
+        for _ in 0..2 {
+            let (root_data, id_mapping) = prefab.instantiate();
+            let root = id_mapping[&0];
+            state.scene.insert_entity(root, root_data);
+            state.prefab_manager.register_instance(PrefabInstance::new(root, path.clone(), prefab.id, id_mapping));
+            roots.push(root);
         }
+        let (edited, other) = (roots[0], roots[1]);
+
+        state.scene.get_mut(&edited).unwrap().name = "Big Crate".to_string();
+        state.scene.get_mut(&edited).unwrap().transform.scale = [2.0, 2.0, 2.0];
+        state.scene.get_mut(&edited).unwrap().active = false;
+        for (property_path, value) in [
+            ("name", json!("Big Crate")),
+            ("transform.scale.x", json!(2.0)),
+            ("active", json!(false)),
+        ] {
+            state.track_prefab_override(edited, property_path, value);
+        }
+        // The other instance keeps its own scale
+        state.scene.get_mut(&other).unwrap().transform.scale = [3.0, 3.0, 3.0];
+        state.prefab_manager.get_instance_mut(other).unwrap().set_override(PropertyOverride {
+            entity_path: "0".to_string(),
+            property_path: "transform.scale".to_string(),
+            value: json!([3.0, 3.0, 3.0]),
+        });
 
-        let mut ids = Vec::new();
-        let mut before = Vec::new();
-        let mut after = Vec::new();
+        assert_eq!(state.apply_overrides_to_prefab(edited), Ok(2));
 
-        for (entity_id, new_transform) in entities.iter().copied().zip(transforms.iter().cloned()) {
-            let Some(entity) = self.scene.get(&entity_id) else {
-                continue;
-            };
-            if entity.transform == new_transform {
-                continue;
-            }
-            ids.push(entity_id);
-            before.push(TransformData::from(entity.transform.clone()));
-            after.push(TransformData::from(new_transform));
-        }
+        let saved = Prefab::load(&path).unwrap();
+        assert_eq!(saved.root.name, "Big Crate");
+        assert_eq!(saved.root.transform.scale, [2.0, 2.0, 2.0]);
 
-        if ids.is_empty() {
-            return;
-        }
+        // Only the override prefabs can't store is left
+        assert_eq!(state.get_entity_overrides(edited), vec!["active".to_string()]);
 
-        let command = TransformCommand::new(ids, before, after, description);
-        if let Err(err) = self.execute_command(&command) {
-            tracing::warn!("Bulk transform failed: {}", err);
-        }
+        let other_data = state.scene.get(&other).unwrap();
+        assert_eq!(other_data.name, "Big Crate");
+        assert_eq!(other_data.transform.scale, [3.0, 3.0, 3.0]);
+
+        // The whole apply is one undo step
+        state.undo().unwrap();
+        assert_eq!(state.scene.get(&other).unwrap().name, "Root");
+        assert_eq!(Prefab::load(&path).unwrap().root.name, "Root");
+        assert_eq!(state.get_entity_overrides(edited).len(), 3);
+
+        state.redo().unwrap();
+        assert_eq!(state.scene.get(&other).unwrap().name, "Big Crate");
+        assert_eq!(Prefab::load(&path).unwrap().root.name, "Big Crate");
+        assert_eq!(state.get_entity_overrides(edited), vec!["active".to_string()]);
+        let _ = std::fs::remove_file(&path);
     }
 
-    /// Set transforms for multiple entities with pre-captured before values
-    /// Useful when live preview has already been applied and we want to commit to undo history
-    pub fn set_transforms_bulk_with_before(
-        &mut self,
-        entities: &[EntityId],
-        before_transforms: &[Transform],
-        after_transforms: &[Transform],
-        description: &str,
-    ) {
-        if entities.is_empty()
-            || entities.len() != before_transforms.len()
-            || entities.len() != after_transforms.len()
-        {
-            return;
-        }
+    #[test]
+    fn test_revert_on_variant_instance_uses_variant_then_base() {
+        use crate::prefab::{Prefab, PrefabInstance, PropertyOverride};
+        use serde_json::json;
+
+        let base_path = temp_prefab_path("variant_base");
+        let mut base = Prefab::new("Lamp");
+        base.root.transform.position = [0.0, 1.0, 0.0];
+        base.save(&base_path).unwrap();
+
+        let mut state = EditorState::new();
+        let variant_path = state.create_variant_from(&base_path).unwrap();
+        let mut variant = Prefab::load(&variant_path).unwrap();
+        variant.base.as_mut().unwrap().set_override(PropertyOverride {
+            entity_path: "0".to_string(),
+            property_path: "name".to_string(),
+            value: json!("Red Lamp"),
+        });
+        variant.save(&variant_path).unwrap();
+        let variant = Prefab::load(&variant_path).unwrap();
+
+        let (root_data, id_mapping) = variant.instantiate();
+        let root = id_mapping[&0];
+        state.scene.insert_entity(root, root_data);
+        state.prefab_manager.register_instance(PrefabInstance::new(root, variant_path.clone(), variant.id, id_mapping));
+
+        state.scene.get_mut(&root).unwrap().name = "Blue Lamp".to_string();
+        state.scene.get_mut(&root).unwrap().transform.position = [4.0, 4.0, 4.0];
+        state.track_prefab_override(root, "name", json!("Blue Lamp"));
+        state.track_prefab_override(root, "transform.position", json!([4.0, 4.0, 4.0]));
+
+        assert!(state.revert_property_override(root, "name"));
+        assert!(state.revert_property_override(root, "transform.position"));
+        let _ = std::fs::remove_file(&variant_path);
+        let _ = std::fs::remove_file(&base_path);
+
+        let entity = state.scene.get(&root).unwrap();
+        assert_eq!(entity.name, "Red Lamp");
+        assert_eq!(entity.transform.position, [0.0, 1.0, 0.0]);
+    }
 
-        let mut ids = Vec::new();
-        let mut before = Vec::new();
-        let mut after = Vec::new();
+    #[test]
+    fn test_apply_overrides_to_missing_prefab() {
+        use crate::prefab::{Prefab, PrefabInstance};
+
+        let prefab = Prefab::new("Crate");
+        let (root_data, id_mapping) = prefab.instantiate();
+        let mut state = EditorState::new();
+        let root = id_mapping[&0];
+        state.scene.insert_entity(root, root_data);
+        let path = temp_prefab_path("moved");
+        state.prefab_manager.register_instance(PrefabInstance::new(root, path, prefab.id, id_mapping));
+        state.track_prefab_override(root, "name", serde_json::json!("Renamed"));
+
+        let error = state.apply_overrides_to_prefab(root).unwrap_err();
+        assert!(error.contains("not found"), "{}", error);
+        assert_eq!(state.get_entity_overrides(root).len(), 1);
+    }
 
-        for i in 0..entities.len() {
-            let entity_id = entities[i];
-            let before_transform = &before_transforms[i];
-            let after_transform = &after_transforms[i];
+    #[test]
+    fn test_save_as_variant_plain_entity() {
+        let mut state = EditorState::new();
+        let entity = state.scene.add_entity(EntityData::new("Loose"));
 
-            // Skip if no actual change
-            if before_transform == after_transform {
-                continue;
-            }
+        let path = temp_prefab_path("plain");
+        state.save_as_variant(entity, &path).unwrap();
+        let prefab = crate::prefab::Prefab::load(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
 
-            // Ensure entity still exists
-            if self.scene.get(&entity_id).is_none() {
-                continue;
-            }
+        assert!(!prefab.is_variant());
+        assert_eq!(prefab.root.name, "Loose");
+    }
 
-            ids.push(entity_id);
-            before.push(TransformData::from(before_transform.clone()));
-            after.push(TransformData::from(after_transform.clone()));
-        }
+    #[test]
+    fn test_revert_light_intensity_override() {
+        use crate::components::{Component, LightComponent};
+        use crate::prefab::{Prefab, PrefabInstance};
+        use serde_json::json;
+
+        let path = temp_prefab_path("light");
+        let mut prefab = Prefab::new("Lamp");
+        prefab.root.components.push(Component::Light(LightComponent {
+            intensity: 2.5,
+            range: 8.0,
+            ..Default::default()
+        }));
+        prefab.save(&path).unwrap();
 
-        if ids.is_empty() {
-            return;
-        }
+        let mut state = EditorState::new();
+        let (root_data, id_mapping) = prefab.instantiate();
+        let root = id_mapping[&0];
+        state.scene.insert_entity(root, root_data);
+        state.prefab_manager.register_instance(PrefabInstance::new(root, path.clone(), prefab.id, id_mapping));
 
-        let command = TransformCommand::new(ids, before, after, description);
-        if let Err(err) = self.execute_command(&command) {
-            tracing::warn!("Bulk transform with before failed: {}", err);
-        }
+        let Component::Light(light) = &mut state.scene.get_mut(&root).unwrap().components[0] else {
+            panic!("expected a light");
+        };
+        light.intensity = 9.0;
+        light.range = 20.0;
+        light.color = [1.0, 0.0, 0.0];
+        state.track_prefab_override(root, "Light.intensity", json!(9.0));
+        state.track_prefab_override(root, "components[0].range", json!(20.0));
+
+        assert!(state.revert_property_override(root, "Light.intensity"));
+        assert!(state.revert_property_override(root, "components[0].range"));
+        // Unknown components and fields fail without touching the entity
+        assert!(!state.revert_property_override(root, "AudioSource.volume"));
+        assert!(!state.revert_property_override(root, "Light.brightness"));
+        let _ = std::fs::remove_file(&path);
+
+        let Component::Light(light) = &state.scene.get(&root).unwrap().components[0] else {
+            panic!("expected a light");
+        };
+        assert_eq!(light.intensity, 2.5);
+        assert_eq!(light.range, 8.0);
+        // Other fields keep their instance values
+        assert_eq!(light.color, [1.0, 0.0, 0.0]);
+        assert!(state.get_entity_overrides(root).is_empty());
     }
 
-    /// Unpack a prefab instance (one level only)
-    /// This removes the prefab link but keeps the entities as regular entities
-    pub fn unpack_prefab(&mut self, root_entity_id: EntityId) {
-        if !self.prefab_manager.is_prefab_root(root_entity_id) {
-            tracing::warn!("Entity {:?} is not a prefab root", root_entity_id);
-            return;
-        }
+    #[test]
+    fn test_reset_component_restores_defaults() {
+        use crate::components::{Component, LightComponent};
 
-        // Simply unregister the instance - entities remain in the scene
-        self.prefab_manager.unregister_instance(root_entity_id);
-        self.dirty = true;
-        tracing::info!("Unpacked prefab instance {:?}", root_entity_id);
+        let mut state = EditorState::new();
+        let id = EntityId::new();
+        state.scene.entities.insert(id, EntityData {
+            components: vec![Component::Light(LightComponent {
+                intensity: 5.0,
+                color: [1.0, 0.0, 0.0],
+                ..Default::default()
+            })],
+            ..Default::default()
+        });
+
+        assert!(state.reset_component(id, 0));
+        let Component::Light(light) = &state.scene.get(&id).unwrap().components[0] else {
+            panic!("expected light component");
+        };
+        assert_eq!(light.intensity, LightComponent::default().intensity);
+        assert_eq!(light.color, LightComponent::default().color);
+
+        state.undo().unwrap();
+        let Component::Light(light) = &state.scene.get(&id).unwrap().components[0] else {
+            panic!("expected light component");
+        };
+        assert_eq!(light.intensity, 5.0);
+        assert_eq!(light.color, [1.0, 0.0, 0.0]);
+
+        state.redo().unwrap();
+        let Component::Light(light) = &state.scene.get(&id).unwrap().components[0] else {
+            panic!("expected light component");
+        };
+        assert_eq!(light.intensity, LightComponent::default().intensity);
     }
 
-    /// Unpack a prefab instance completely (including nested prefabs)
-    pub fn unpack_prefab_completely(&mut self, root_entity_id: EntityId) {
-        if !self.prefab_manager.is_prefab_root(root_entity_id) {
-            tracing::warn!("Entity {:?} is not a prefab root", root_entity_id);
-            return;
-        }
+    #[test]
+    fn test_reset_component_missing_index() {
+        let mut state = EditorState::new();
+        let id = EntityId::new();
+        state.scene.entities.insert(id, EntityData::default());
 
-        // Get all entity IDs in this instance
-        let entity_ids: Vec<EntityId> = if let Some(instance) = self.prefab_manager.get_instance(root_entity_id) {
-            instance.all_entity_ids().into_iter().collect()
-        } else {
-            return;
+        assert!(!state.reset_component(id, 0));
+        assert!(!state.history.can_undo());
+    }
+
+    #[test]
+    fn test_convert_collider_undo() {
+        use crate::components::{
+            BoxColliderComponent, ColliderKind, Component, MeshRendererComponent,
         };
 
-        // Unregister the main instance
-        self.prefab_manager.unregister_instance(root_entity_id);
+        let mut state = EditorState::new();
+        let id = EntityId::new();
+        state.scene.entities.insert(id, EntityData {
+            components: vec![
+                Component::MeshRenderer(MeshRendererComponent {
+                    mesh: "models/crate.glb".to_string(),
+                    ..Default::default()
+                }),
+                Component::BoxCollider(BoxColliderComponent {
+                    size: [1.0, 3.0, 1.0],
+                    layer: 2,
+                    ..Default::default()
+                }),
+            ],
+            ..Default::default()
+        });
 
-        // Find and unregister any nested prefab instances
-        let nested_roots: Vec<EntityId> = entity_ids
-            .iter()
-            .filter(|id| **id != root_entity_id && self.prefab_manager.is_prefab_root(**id))
-            .copied()
-            .collect();
+        assert!(state.convert_collider(id, 1, ColliderKind::Capsule));
+        let Component::CapsuleCollider(capsule) = &state.scene.get(&id).unwrap().components[1] else {
+            panic!("expected capsule collider");
+        };
+        assert_eq!(capsule.height, 3.0);
+        assert_eq!(capsule.layer, 2);
 
-        for nested_root in nested_roots {
-            self.prefab_manager.unregister_instance(nested_root);
-        }
+        state.undo().unwrap();
+        assert!(matches!(state.scene.get(&id).unwrap().components[1], Component::BoxCollider(_)));
 
-        self.dirty = true;
-        tracing::info!("Unpacked prefab instance {:?} completely", root_entity_id);
+        assert!(state.convert_collider(id, 1, ColliderKind::Mesh));
+        let Component::MeshCollider(mesh) = &state.scene.get(&id).unwrap().components[1] else {
+            panic!("expected mesh collider");
+        };
+        assert_eq!(mesh.mesh, "models/crate.glb");
+
+        // Not a collider
+        assert!(!state.convert_collider(id, 0, ColliderKind::Box));
     }
 
-    /// Check if we're currently editing a prefab
-    pub fn is_editing_prefab(&self) -> bool {
-        self.editing_prefab.is_some()
+    #[test]
+    fn test_select_similar_missing_reference() {
+        let scene = SceneData::new();
+        assert!(scene.select_similar(EntityId::new(), SimilarityCriterion::SameTag).is_empty());
     }
 
-    /// Get the path of the prefab being edited
-    pub fn editing_prefab_path(&self) -> Option<&PathBuf> {
-        self.editing_prefab.as_ref().map(|s| &s.prefab_path)
+    #[test]
+    fn test_fog_factor_curve() {
+        assert_eq!(fog_factor(5.0, 10.0, 50.0), 0.0);
+        assert_eq!(fog_factor(10.0, 10.0, 50.0), 0.0);
+        assert!((fog_factor(30.0, 10.0, 50.0) - 0.5).abs() < 1e-6);
+        assert_eq!(fog_factor(50.0, 10.0, 50.0), 1.0);
+        assert_eq!(fog_factor(80.0, 10.0, 50.0), 1.0);
+
+        // Degenerate range acts as a hard cutoff
+        assert_eq!(fog_factor(9.0, 10.0, 10.0), 0.0);
+        assert_eq!(fog_factor(11.0, 10.0, 10.0), 1.0);
     }
 
-    /// Enter prefab editing mode
-    pub fn enter_prefab_edit_mode(&mut self, prefab_path: &PathBuf) -> Result<(), String> {
-        if self.editing_prefab.is_some() {
-            return Err("Already editing a prefab".to_string());
-        }
+    #[test]
+    fn test_environment_defaults_round_trip() {
+        // Scenes saved before environment settings existed
+        let legacy: SceneData = ron::from_str("(entities: {})").unwrap();
+        assert_eq!(legacy.environment, EnvironmentSettings::default());
 
-        // Load the prefab
-        let prefab = crate::prefab::Prefab::load(prefab_path)
-            .map_err(|e| format!("Failed to load prefab: {}", e))?;
+        // Partial blocks fill in the missing fields
+        let partial: SceneData =
+            ron::from_str("(entities: {}, environment: (fog_enabled: true))").unwrap();
+        assert!(partial.environment.fog_enabled);
+        assert_eq!(partial.environment.fog_end, EnvironmentSettings::default().fog_end);
 
-        // Backup current state
-        let editing_state = PrefabEditingState {
-            prefab_path: prefab_path.clone(),
-            scene_backup: self.scene.clone(),
-            selection_backup: self.selection.clone(),
-            prefab_dirty: false,
-        };
+        let mut scene = SceneData::new();
+        scene.environment.ambient_intensity = 0.8;
+        scene.environment.fog_color = [1.0, 0.0, 0.0];
+        let text = ron::to_string(&scene).unwrap();
+        let restored: SceneData = ron::from_str(&text).unwrap();
+        assert_eq!(restored.environment, scene.environment);
+    }
 
-        // Load prefab entities into the scene
-        let (entities, _id_mapping) = prefab.instantiate_flat();
+    #[test]
+    fn test_entity_reference_pick_and_undo() {
+        use crate::components::LookAtComponent;
+        use crate::entity_ref::PickTarget;
 
-        // Clear scene and load prefab content
-        self.scene = SceneData::new();
-        self.selection.clear();
-        self.history.clear();
+        let mut state = EditorState::new();
+        let camera = state.scene.add_entity(entity("Camera", vec![Component::LookAt(LookAtComponent::default())]));
+        let player = state.scene.add_entity(EntityData::new("Player"));
 
-        // Add prefab entities to scene
-        for entity in entities {
-            self.scene.add_entity(entity);
-        }
+        state.entity_picker.begin(PickTarget { entity: camera, component_index: 0, field: "target" });
+        assert!(state.complete_entity_pick(player));
+        assert!(!state.entity_picker.is_active());
+        assert_eq!(state.scene.get(&camera).unwrap().components[0].entity_references(), vec![("target", Some(player))]);
 
-        self.editing_prefab = Some(editing_state);
-        tracing::info!("Entered prefab edit mode: {:?}", prefab_path);
-        Ok(())
+        state.undo().unwrap();
+        assert_eq!(state.scene.get(&camera).unwrap().components[0].entity_references(), vec![("target", None)]);
+
+        // Clicks pass through when no pick is in progress
+        assert!(!state.complete_entity_pick(player));
+        assert!(!state.set_entity_reference(camera, 0, "missing_field", Some(player)));
     }
 
-    /// Exit prefab editing mode without saving
-    pub fn exit_prefab_edit_mode(&mut self, save: bool) -> Result<(), String> {
-        let Some(editing_state) = self.editing_prefab.take() else {
-            return Err("Not in prefab edit mode".to_string());
-        };
+    #[test]
+    fn test_metadata_set_get_undo() {
+        let mut state = EditorState::new();
+        let id = state.scene.add_entity(EntityData::new("Rock"));
 
-        if save && editing_state.prefab_dirty {
-            self.save_prefab_from_scene(&editing_state.prefab_path)?;
-        }
+        assert!(state.set_metadata(id, "lod_group", Some("rocks".to_string())));
+        assert_eq!(state.scene.get_metadata(id, "lod_group"), Some("rocks"));
 
-        // Restore the main scene
-        self.scene = editing_state.scene_backup;
-        self.selection = editing_state.selection_backup;
-        self.history.clear();
+        // Unchanged values and blank keys are ignored
+        assert!(!state.set_metadata(id, "lod_group", Some("rocks".to_string())));
+        assert!(!state.set_metadata(id, "  ", Some("x".to_string())));
 
-        tracing::info!("Exited prefab edit mode");
-        Ok(())
+        assert!(state.set_metadata(id, "lod_group", Some("cliffs".to_string())));
+        state.undo().unwrap();
+        assert_eq!(state.scene.get_metadata(id, "lod_group"), Some("rocks"));
+
+        assert!(state.set_metadata(id, "lod_group", None));
+        assert_eq!(state.scene.get_metadata(id, "lod_group"), None);
+        state.undo().unwrap();
+        assert_eq!(state.scene.get_metadata(id, "lod_group"), Some("rocks"));
+
+        state.undo().unwrap();
+        assert_eq!(state.scene.get_metadata(id, "lod_group"), None);
+        state.redo().unwrap();
+        assert_eq!(state.scene.get_metadata(id, "lod_group"), Some("rocks"));
     }
 
-    /// Save current scene content as a prefab
-    pub fn save_prefab_from_scene(&mut self, path: &PathBuf) -> Result<(), String> {
-        // Get all root entities
-        let roots = self.scene.root_entities();
-        if roots.is_empty() {
-            return Err("No entities in scene to save as prefab".to_string());
-        }
+    #[test]
+    fn test_metadata_serialization_is_sorted_and_skipped_when_empty() {
+        let mut entity = EntityData::new("Rock");
+        let empty = ron::to_string(&entity).unwrap();
+        assert!(!empty.contains("metadata"));
+
+        entity.metadata.insert("zeta".to_string(), "1".to_string());
+        entity.metadata.insert("alpha".to_string(), "2".to_string());
+        entity.metadata.insert("mid".to_string(), "3".to_string());
+        let text = ron::to_string(&entity).unwrap();
+        let alpha = text.find("alpha").unwrap();
+        let mid = text.find("mid").unwrap();
+        let zeta = text.find("zeta").unwrap();
+        assert!(alpha < mid && mid < zeta);
+
+        let restored: EntityData = ron::from_str(&text).unwrap();
+        assert_eq!(restored.metadata, entity.metadata);
+    }
 
-        // Use the first root as the prefab root
-        let root_id = roots[0];
-        let root_entity = self.scene.get(&root_id)
-            .ok_or("Root entity not found")?;
+    fn path_state(points: Vec<[f32; 3]>) -> (EditorState, EntityId, EntityId) {
+        use crate::components::PathComponent;
 
-        // Build entity map for hierarchy
-        let entity_map: std::collections::HashMap<EntityId, EntityData> = self.scene.entities
+        let mut state = EditorState::new();
+        let item = state.scene.add_entity(EntityData::new("Post"));
+        let mut path = entity("Path", vec![Component::Path(PathComponent { points, closed: false })]);
+        path.transform.position = [1.0, 0.0, 0.0];
+        let path = state.scene.add_entity(path);
+        (state, item, path)
+    }
+
+    #[test]
+    fn test_distribute_along_straight_path() {
+        // Uneven point spacing; copies still land at 0 / 0.5 / 1.0 of arc length
+        let (mut state, item, path) = path_state(vec![[0.0, 0.0, 0.0], [2.0, 0.0, 0.0], [10.0, 0.0, 0.0]]);
+
+        let copies = state.distribute_along_path(item, path, 3, true);
+        assert_eq!(copies.len(), 3);
+        let xs: Vec<f32> = copies
             .iter()
-            .map(|(id, data)| (*id, data.clone()))
+            .map(|id| state.scene.get(id).unwrap().transform.position[0])
             .collect();
+        assert_eq!(xs, vec![1.0, 6.0, 11.0]);
+        // +Z turned towards +X
+        let rotation = state.scene.get(&copies[1]).unwrap().transform.rotation;
+        assert!((rotation[1] - 90.0).abs() < 1e-4);
+
+        state.undo().unwrap();
+        assert!(copies.iter().all(|id| state.scene.get(id).is_none()));
+        assert!(state.scene.get(&item).is_some());
+    }
 
-        // Create prefab from entities
-        let name = path.file_stem()
-            .and_then(|s| s.to_str())
-            .unwrap_or("Prefab")
-            .to_string();
+    #[test]
+    fn test_distribute_single_and_degenerate() {
+        let (mut state, item, path) = path_state(vec![[0.0, 0.0, 0.0], [4.0, 0.0, 0.0]]);
+        let copies = state.distribute_along_path(item, path, 1, false);
+        assert_eq!(copies.len(), 1);
+        assert_eq!(state.scene.get(&copies[0]).unwrap().transform.position, [1.0, 0.0, 0.0]);
+
+        let (mut state, item, path) = path_state(vec![[2.0, 0.0, 0.0], [2.0, 0.0, 0.0]]);
+        let copies = state.distribute_along_path(item, path, 3, true);
+        assert_eq!(copies.len(), 3);
+        for id in &copies {
+            let transform = &state.scene.get(id).unwrap().transform;
+            assert_eq!(transform.position, [3.0, 0.0, 0.0]);
+            assert_eq!(transform.rotation, [0.0, 0.0, 0.0]);
+        }
+    }
+
+    #[test]
+    fn test_import_subtree_is_one_undo_step() {
+        use crate::entity_transfer::EntitySubtree;
+
+        let mut source = SceneData::new();
+        let root = source.add_entity(EntityData::new("Crate"));
+        let lid = source.add_entity(EntityData {
+            parent: Some(root),
+            ..EntityData::new("Lid")
+        });
+        source.get_mut(&root).unwrap().children.push(lid);
+        let subtree = EntitySubtree::capture(&source, root).unwrap();
+
+        let mut state = EditorState::new();
+        let shelf = state.scene.add_entity(EntityData::new("Shelf"));
+        let count = state.scene.entities.len();
+        let imported = state.import_subtree(&subtree, Some(shelf)).unwrap();
+        assert_eq!(state.scene.entities.len(), count + 2);
+        assert_eq!(state.scene.get(&shelf).unwrap().children, vec![imported]);
+
+        state.undo().unwrap();
+        assert_eq!(state.scene.entities.len(), count);
+        assert!(state.scene.get(&shelf).unwrap().children.is_empty());
+
+        state.redo().unwrap();
+        assert_eq!(state.scene.entities.len(), count + 2);
+        assert_eq!(state.scene.get(&shelf).unwrap().children, vec![imported]);
+    }
+
+    #[test]
+    fn test_reparent_under_descendant_is_rejected() {
+        let mut state = EditorState::new();
+        let a = state.scene.add_entity(EntityData::new("A"));
+        let b = state.scene.add_entity(EntityData::new("B"));
+        let c = state.scene.add_entity(EntityData::new("C"));
+        state.reparent_entities_with_command(&[b], Some(a));
+        state.reparent_entities_with_command(&[c], Some(b));
+        assert_eq!(state.scene.ancestors(c), vec![b, a]);
+
+        state.reparent_entities_with_command(&[a], Some(b));
+        state.reparent_entities_with_command(&[a], Some(c));
+        assert_eq!(state.scene.get(&a).unwrap().parent, None);
+        assert_eq!(state.scene.get(&a).unwrap().children, vec![b]);
+        assert_eq!(state.scene.get(&b).unwrap().parent, Some(a));
+        assert_eq!(state.scene.get(&b).unwrap().children, vec![c]);
+        assert_eq!(state.history.undo_description(), Some("Reparent Entities"));
+    }
 
-        let prefab = crate::prefab::Prefab::from_entities(name, root_entity, &entity_map);
+    #[test]
+    fn test_recall_selection_group_skips_deleted_entities() {
+        let mut state = EditorState::new();
+        let a = state.scene.add_entity(EntityData::new("A"));
+        let b = state.scene.add_entity(EntityData::new("B"));
+        let c = state.scene.add_entity(EntityData::new("C"));
+        state.select(&[a, b]);
+        assert!(state.store_selection_group("Props"));
+
+        state.delete_entities_with_command(&[a]);
+        state.select(&[c]);
+        assert!(state.recall_selection_group("Props"));
+        assert_eq!(state.selection.entities, vec![b]);
+
+        state.select_mode = SelectMode::Add;
+        state.select(&[c]);
+        state.select_mode = SelectMode::Remove;
+        assert!(state.recall_selection_group("Props"));
+        assert_eq!(state.selection.entities, vec![c]);
+
+        // Groups are saved with the scene
+        let text = ron::to_string(&state.scene).unwrap();
+        let reloaded: SceneData = ron::from_str(&text).unwrap();
+        assert_eq!(reloaded.selection_groups["Props"], vec![a, b]);
+
+        assert!(state.delete_selection_group("Props"));
+        assert!(!state.recall_selection_group("Props"));
+    }
 
-        // Save to disk
-        prefab.save(path)
-            .map_err(|e| format!("Failed to save prefab: {}", e))?;
+    #[test]
+    fn test_tags_query_and_undo() {
+        let mut state = EditorState::new();
+        let goblin = state.scene.add_entity(EntityData::new("Goblin"));
+        let coin = state.scene.add_entity(EntityData::new("Coin"));
+
+        assert!(state.add_tag(goblin, "enemy"));
+        assert!(state.set_tags(coin, vec![" pickup ".to_string(), "pickup".to_string(), String::new()]));
+        assert!(!state.add_tag(goblin, "enemy"));
+        assert_eq!(state.scene.get(&coin).unwrap().tags, vec!["pickup".to_string()]);
+        assert!(state.scene.get(&goblin).unwrap().has_tag("enemy"));
+        assert_eq!(state.scene.entities_with_tag("enemy"), vec![goblin]);
+
+        assert!(state.remove_tag(goblin, "enemy"));
+        assert!(state.scene.entities_with_tag("enemy").is_empty());
+
+        state.undo().unwrap();
+        assert_eq!(state.scene.entities_with_tag("enemy"), vec![goblin]);
+        state.undo().unwrap();
+        assert!(state.scene.get(&coin).unwrap().tags.is_empty());
+        state.redo().unwrap();
+        assert_eq!(state.scene.entities_with_tag("pickup"), vec![coin]);
+    }
 
-        // Mark as clean
-        if let Some(ref mut editing_state) = self.editing_prefab {
-            editing_state.prefab_dirty = false;
-        }
+    #[test]
+    fn test_json_scene_save_and_load() {
+        let path = std::env::temp_dir().join(format!("json_scene_{}.json", Uuid::new_v4()));
+        let mut state = EditorState::new();
+        let parent = state.scene.add_entity(entity("Crate", vec![Component::Rigidbody(RigidbodyComponent::default())]));
+        let mut child = EntityData::new("Lamp");
+        child.parent = Some(parent);
+        child.metadata.insert("lod".to_string(), "2".to_string());
+        let child = state.scene.add_entity(child);
+        state.scene.get_mut(&parent).unwrap().children.push(child);
+
+        state.save_scene_to_path(&path).unwrap();
+        let text = std::fs::read_to_string(&path).unwrap();
+        assert!(text.trim_start().starts_with('{'));
+
+        let mut reloaded = EditorState::new();
+        reloaded.load_scene(&path).unwrap();
+        assert_eq!(
+            ron::to_string(&reloaded.scene).unwrap(),
+            ron::to_string(&state.scene).unwrap()
+        );
 
-        tracing::info!("Saved prefab to {:?}", path);
-        Ok(())
+        std::fs::remove_file(path).ok();
     }
 
-    /// Mark the prefab as having unsaved changes
-    #[allow(dead_code)] // Intentionally kept for API completeness
-    pub fn mark_prefab_dirty(&mut self) {
-        if let Some(ref mut editing_state) = self.editing_prefab {
-            editing_state.prefab_dirty = true;
-        }
+    #[test]
+    fn test_load_scene_migrates_v0_file() {
+        use crate::components::MeshRendererComponent;
+
+        let path = std::env::temp_dir().join(format!("legacy_scene_{}.ron", Uuid::new_v4()));
+        let (crate_id, lamp_id) = (Uuid::new_v4(), Uuid::new_v4());
+        let legacy = format!(
+            r#"(
+                entities: {{
+                    ("{crate_id}"): (
+                        name: "Crate",
+                        children: [("{lamp_id}")],
+                        components: [(type: "Mesh", mesh: "meshes/crate.glb", material: Some(0))],
+                    ),
+                    ("{lamp_id}"): (name: "Lamp", parent: Some(("{crate_id}"))),
+                }},
+                materials: ["materials/wood.mat"],
+            )"#
+        );
+        std::fs::write(&path, legacy).unwrap();
+
+        let mut state = EditorState::new();
+        state.load_scene(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        let (crate_id, lamp_id) = (EntityId(crate_id), EntityId(lamp_id));
+        let entity = state.scene.get(&crate_id).unwrap();
+        assert!(entity.active);
+        assert_eq!(entity.transform, Transform::default());
+        assert_eq!(entity.children, vec![lamp_id]);
+        assert_eq!(
+            entity.components,
+            vec![Component::MeshRenderer(MeshRendererComponent {
+                mesh: "meshes/crate.glb".to_string(),
+                material: "materials/wood.mat".to_string(),
+                ..Default::default()
+            })]
+        );
+        assert_eq!(state.scene.get(&lamp_id).unwrap().parent, Some(crate_id));
+        assert_eq!(state.scene_path.as_deref(), Some(path.as_path()));
     }
 
-    /// Check if the editing prefab has unsaved changes
-    pub fn prefab_has_unsaved_changes(&self) -> bool {
-        self.editing_prefab.as_ref().map(|s| s.prefab_dirty).unwrap_or(false)
+    #[test]
+    fn test_failed_scene_migration_leaves_state_untouched() {
+        let path = std::env::temp_dir().join(format!("legacy_scene_{}.ron", Uuid::new_v4()));
+        let legacy = format!(
+            "(entities: {{ (\"{}\"): (name: \"Crate\", components: [(type: \"Mesh\", mesh: \"a.glb\", material: Some(3))]) }})",
+            Uuid::new_v4()
+        );
+        std::fs::write(&path, legacy).unwrap();
+
+        let mut state = EditorState::new();
+        let existing = state.scene.add_entity(EntityData::new("Existing"));
+        let err = state.load_scene(&path).unwrap_err();
+        assert!(err.contains("v0") && err.contains("Crate"), "{err}");
+        assert!(state.scene.get(&existing).is_some());
+        assert!(state.scene_path.is_none());
+
+        std::fs::remove_file(path).ok();
     }
 
-    /// Track a property override on a prefab instance
-    /// Returns true if the entity is part of a prefab instance
-    pub fn track_prefab_override(
-        &mut self,
-        entity_id: EntityId,
-        property_path: &str,
-        value: serde_json::Value,
-    ) -> bool {
-        // Find the prefab instance containing this entity
-        let instance_root = {
-            let Some(instance) = self.prefab_manager.find_instance_containing(entity_id) else {
-                return false;
-            };
-            instance.root_entity_id
-        };
+    #[test]
+    fn test_collapse_duplicate_asset_paths_is_one_undo_step() {
+        use crate::components::MeshRendererComponent;
 
-        // Get the local ID for this entity within the prefab
-        let local_id = {
-            let Some(instance) = self.prefab_manager.get_instance(instance_root) else {
-                return false;
-            };
-            match instance.get_local_id(entity_id) {
-                Some(id) => id.to_string(),
-                None => return false,
-            }
-        };
+        let root = std::env::temp_dir().join(format!("collapse_assets_{}", Uuid::new_v4()));
+        std::fs::create_dir_all(root.join("Assets")).unwrap();
+        std::fs::write(root.join("Assets").join("Foo.glb"), b"mesh").unwrap();
 
-        // Create the override
-        let override_ = crate::prefab::PropertyOverride {
-            entity_path: local_id,
-            property_path: property_path.to_string(),
-            value: value.clone(),
+        let mut state = EditorState::new();
+        state.project_manager.project_dir = Some(root.clone());
+        let mesh = |path: &str| {
+            entity("Mesh", vec![Component::MeshRenderer(MeshRendererComponent {
+                mesh: path.to_string(),
+                ..Default::default()
+            })])
         };
+        let a = state.scene.add_entity(mesh("Assets\\Foo.glb"));
+        let b = state.scene.add_entity(mesh("assets/foo.glb"));
 
-        // Add the override to the instance
-        if let Some(instance) = self.prefab_manager.get_instance_mut(instance_root) {
-            tracing::debug!(
-                "Tracked override for entity {:?}: {} = {:?}",
-                entity_id,
-                property_path,
-                value
-            );
-            instance.set_override(override_);
+        assert_eq!(state.collapse_duplicate_asset_paths(), 2);
+        for id in [a, b] {
+            assert_eq!(state.scene.get(&id).unwrap().components[0].asset_paths()[0], "Assets/Foo.glb");
         }
+        assert_eq!(state.collapse_duplicate_asset_paths(), 0);
 
-        true
+        state.undo().unwrap();
+        assert_eq!(state.scene.get(&a).unwrap().components[0].asset_paths()[0], "Assets\\Foo.glb");
+        assert_eq!(state.scene.get(&b).unwrap().components[0].asset_paths()[0], "assets/foo.glb");
+
+        std::fs::remove_dir_all(root).ok();
     }
 
-    /// Check if a property is overridden on a prefab instance entity
-    pub fn is_property_overridden(&self, entity_id: EntityId, property_path: &str) -> bool {
-        let Some(instance) = self.prefab_manager.find_instance_containing(entity_id) else {
-            return false;
-        };
+    #[test]
+    fn test_rapid_nudges_are_one_undo_step() {
+        let mut state = EditorState::new();
+        let id = state.scene.add_entity(EntityData::new("Crate"));
+        let other = state.scene.add_entity(EntityData::new("Barrel"));
 
-        let Some(local_id) = instance.get_local_id(entity_id) else {
-            return false;
+        let at = |position: [f32; 3]| Transform {
+            position,
+            ..Default::default()
         };
-
-        instance.is_overridden(&local_id.to_string(), property_path)
+        for step in 1..=5 {
+            state.set_transform(id, at([step as f32, 0.0, 0.0]), "Nudge");
+        }
+        state.set_transform(other, at([0.0, 1.0, 0.0]), "Nudge");
+        assert_eq!(state.history.undo_depth(), 2);
+
+        state.undo().unwrap();
+        assert_eq!(state.scene.get(&id).unwrap().transform.position, [5.0, 0.0, 0.0]);
+        state.undo().unwrap();
+        assert_eq!(state.scene.get(&id).unwrap().transform.position, [0.0, 0.0, 0.0]);
+        assert!(!state.history.can_undo());
     }
 
-    /// Revert a specific property override on a prefab instance entity
-    pub fn revert_property_override(&mut self, entity_id: EntityId, property_path: &str) -> bool {
-        // Find the instance and get needed data
-        let (instance_root, local_id, prefab_path) = {
-            let Some(instance) = self.prefab_manager.find_instance_containing(entity_id) else {
-                return false;
-            };
-            let Some(local_id) = instance.get_local_id(entity_id) else {
-                return false;
-            };
-            (instance.root_entity_id, local_id, instance.prefab_path.clone())
-        };
-
-        // Load the prefab to get the original value
-        let prefab = match crate::prefab::Prefab::load(&prefab_path) {
-            Ok(p) => p,
-            Err(e) => {
-                tracing::error!("Failed to load prefab for revert: {}", e);
-                return false;
-            }
+    #[test]
+    fn test_transform_commits_skip_undo() {
+        let mut state = EditorState::new();
+        let id = state.scene.add_entity(EntityData::new("Crate"));
+        let moved = Transform {
+            position: [1.0, 0.0, 0.0],
+            ..Default::default()
         };
 
-        // Find the entity in the prefab by local_id
-        let prefab_entity = self.find_prefab_entity_by_local_id(&prefab.root, local_id);
-        let Some(prefab_entity) = prefab_entity else {
-            tracing::warn!("Could not find prefab entity with local_id {}", local_id);
-            return false;
-        };
+        state.set_transform(id, moved.clone(), "Move");
+        let commits = state.take_transform_commits();
+        assert_eq!(commits.len(), 1);
+        assert_eq!(commits[0].entity, id);
+        assert_eq!(commits[0].before, Transform::default());
+        assert_eq!(commits[0].after, moved);
 
-        // Revert the property value
-        let reverted = self.revert_property_from_prefab(entity_id, property_path, prefab_entity);
+        state.undo().unwrap();
+        state.redo().unwrap();
+        assert!(state.take_transform_commits().is_empty());
+    }
 
-        // Remove the override from the instance
-        if reverted {
-            if let Some(instance) = self.prefab_manager.get_instance_mut(instance_root) {
-                instance.remove_override(&local_id.to_string(), property_path);
-            }
+    #[test]
+    fn test_jump_to_history() {
+        let mut state = EditorState::new();
+        let id = state.scene.add_entity(EntityData::new("Crate"));
+        for name in ["A", "B", "C"] {
+            state.set_entity_name(id, name.to_string());
         }
+        let name = |state: &EditorState| state.scene.get(&id).unwrap().name.clone();
 
-        reverted
-    }
+        state.jump_to_history(1).unwrap();
+        assert_eq!(name(&state), "A");
+        assert_eq!(state.history.entries().count(), 3);
 
-    fn find_prefab_entity_by_local_id<'a>(
-        &self,
-        entity: &'a crate::prefab::PrefabEntity,
-        local_id: u32,
-    ) -> Option<&'a crate::prefab::PrefabEntity> {
-        if entity.local_id == local_id {
-            return Some(entity);
-        }
-        for child in &entity.children {
-            if let Some(found) = self.find_prefab_entity_by_local_id(child, local_id) {
-                return Some(found);
-            }
-        }
-        None
+        state.jump_to_history(0).unwrap();
+        assert_eq!(name(&state), "Crate");
+        state.jump_to_history(usize::MAX).unwrap();
+        assert_eq!(name(&state), "C");
+        assert_eq!(state.history.current_index(), 3);
     }
 
-    fn revert_property_from_prefab(
-        &mut self,
-        entity_id: EntityId,
-        property_path: &str,
-        prefab_entity: &crate::prefab::PrefabEntity,
-    ) -> bool {
-        let Some(entity) = self.scene.get_mut(&entity_id) else {
-            return false;
+    #[test]
+    fn test_group_and_ungroup_restore_hierarchy() {
+        let mut state = EditorState::new();
+        let at = |name: &str, parent: Option<EntityId>, x: f32| EntityData {
+            name: name.to_string(),
+            parent,
+            transform: Transform {
+                position: [x, 0.0, 0.0],
+                ..Default::default()
+            },
+            ..Default::default()
         };
+        let root = state.scene.add_entity(at("Root", None, 10.0));
+        let a = state.scene.add_entity(at("A", Some(root), 1.0));
+        let other = state.scene.add_entity(at("Other", Some(root), 0.0));
+        let b = state.scene.add_entity(at("B", Some(root), 3.0));
+        state.scene.get_mut(&root).unwrap().children = vec![a, other, b];
+        let original = ron::to_string(&state.scene).unwrap();
+
+        state.selection = Selection::with_entities([a, b]);
+        let group = state.group_selected().unwrap();
+        assert_eq!(state.scene.get(&root).unwrap().children, vec![group, other]);
+        assert_eq!(state.scene.get(&group).unwrap().children, vec![a, b]);
+        assert_eq!(state.scene.world_position(group), Some([12.0, 0.0, 0.0]));
+        assert_eq!(state.scene.world_position(b), Some([13.0, 0.0, 0.0]));
+        let grouped = ron::to_string(&state.scene).unwrap();
+
+        state.undo().unwrap();
+        assert_eq!(ron::to_string(&state.scene).unwrap(), original);
+        state.redo().unwrap();
+        assert_eq!(ron::to_string(&state.scene).unwrap(), grouped);
+
+        state.selection = Selection::with_entities([group]);
+        state.ungroup_selected();
+        assert!(state.scene.get(&group).is_none());
+        assert_eq!(state.scene.get(&root).unwrap().children, vec![a, b, other]);
+        assert_eq!(state.scene.get(&a).unwrap().parent, Some(root));
+        assert_eq!(state.scene.world_position(a), Some([11.0, 0.0, 0.0]));
+
+        state.undo().unwrap();
+        assert_eq!(ron::to_string(&state.scene).unwrap(), grouped);
+
+        // A parent with components is only emptied, not deleted
+        state.scene.get_mut(&group).unwrap().components.push(Component::Rigidbody(RigidbodyComponent::default()));
+        state.selection = Selection::with_entities([group]);
+        state.ungroup_selected();
+        assert_eq!(state.scene.get(&root).unwrap().children, vec![group, a, b, other]);
+        assert!(state.scene.get(&group).unwrap().children.is_empty());
+        assert_eq!(state.scene.world_position(b), Some([13.0, 0.0, 0.0]));
+    }
 
-        // Handle common property paths
-        match property_path {
-            "name" => {
-                entity.name = prefab_entity.name.clone();
-                true
-            }
-            "transform.position" | "transform.position.x" | "transform.position.y" | "transform.position.z" => {
-                entity.transform.position = prefab_entity.transform.position;
-                true
-            }
-            "transform.rotation" | "transform.rotation.x" | "transform.rotation.y" | "transform.rotation.z" => {
-                entity.transform.rotation = prefab_entity.transform.rotation;
-                true
-            }
-            "transform.scale" | "transform.scale.x" | "transform.scale.y" | "transform.scale.z" => {
-                entity.transform.scale = prefab_entity.transform.scale;
-                true
-            }
-            "active" => {
-                entity.active = true; // Default for prefab entities
-                true
-            }
-            "is_static" => {
-                entity.is_static = false; // Default for prefab entities
-                true
-            }
-            _ => {
-                tracing::warn!("Revert not implemented for property: {}", property_path);
-                false
-            }
-        }
+    #[test]
+    fn test_align_center_x_is_one_undo_step() {
+        let mut state = EditorState::new();
+        let at = |position: [f32; 3]| EntityData {
+            transform: Transform {
+                position,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let a = state.scene.add_entity(at([0.0, 1.0, 0.0]));
+        let b = state.scene.add_entity(at([2.0, 2.0, 0.0]));
+        let parent = state.scene.add_entity(at([4.0, 0.0, 0.0]));
+        let c = state.scene.add_entity(EntityData {
+            parent: Some(parent),
+            ..at([6.0, 3.0, 0.0])
+        });
+        state.scene.get_mut(&parent).unwrap().children.push(c);
+
+        state.selection = Selection::with_entities([a, b, c]);
+        state.align_selected(AlignAxis::X, AlignMode::Center);
+        assert_eq!(state.scene.world_position(a), Some([5.0, 1.0, 0.0]));
+        assert_eq!(state.scene.world_position(b), Some([5.0, 2.0, 0.0]));
+        assert_eq!(state.scene.world_position(c), Some([5.0, 3.0, 0.0]));
+        assert_eq!(state.scene.get(&c).unwrap().transform.position, [1.0, 3.0, 0.0]);
+        assert_eq!(state.history.undo_description(), Some("Align X Center"));
+
+        state.undo().unwrap();
+        assert_eq!(state.scene.world_position(a), Some([0.0, 1.0, 0.0]));
+        assert_eq!(state.scene.world_position(c), Some([10.0, 3.0, 0.0]));
+
+        state.distribute_selected(AlignAxis::Y);
+        assert_eq!(state.scene.world_position(b), Some([2.0, 2.0, 0.0]));
+        state.scene.get_mut(&b).unwrap().transform.position[1] = 2.5;
+        state.distribute_selected(AlignAxis::Y);
+        assert_eq!(state.scene.world_position(b), Some([2.0, 2.0, 0.0]));
     }
 
-    /// Revert all overrides on a prefab instance entity
-    pub fn revert_all_overrides(&mut self, entity_id: EntityId) -> bool {
-        let Some(instance) = self.prefab_manager.find_instance_containing(entity_id) else {
-            return false;
+    #[test]
+    fn test_align_moves_selected_children_with_their_parent() {
+        let mut state = EditorState::new();
+        let at = |position: [f32; 3]| EntityData {
+            transform: Transform {
+                position,
+                ..Default::default()
+            },
+            ..Default::default()
         };
+        let a = state.scene.add_entity(at([0.0, 0.0, 0.0]));
+        let parent = state.scene.add_entity(at([4.0, 0.0, 0.0]));
+        let child = state.scene.add_entity(EntityData {
+            parent: Some(parent),
+            ..at([6.0, 1.0, 0.0])
+        });
+        state.scene.get_mut(&parent).unwrap().children.push(child);
+        let b = state.scene.add_entity(at([8.0, 0.0, 0.0]));
+
+        // The child keeps its offset instead of being aligned on its own
+        state.selection = Selection::with_entities([a, parent, child]);
+        state.align_selected(AlignAxis::X, AlignMode::Min);
+        assert_eq!(state.scene.world_position(parent), Some([0.0, 0.0, 0.0]));
+        assert_eq!(state.scene.get(&child).unwrap().transform.position, [6.0, 1.0, 0.0]);
+        assert_eq!(state.scene.world_position(child), Some([6.0, 1.0, 0.0]));
+
+        // Distributes three entities; the child rides along with its parent
+        state.selection = Selection::with_entities([a, parent, child, b]);
+        state.scene.get_mut(&parent).unwrap().transform.position[0] = 2.0;
+        state.distribute_selected(AlignAxis::X);
+        assert_eq!(state.scene.world_position(parent), Some([4.0, 0.0, 0.0]));
+        assert_eq!(state.scene.get(&child).unwrap().transform.position, [6.0, 1.0, 0.0]);
+    }
 
-        let root_id = instance.root_entity_id;
-        let prefab_path = instance.prefab_path.clone();
+    #[test]
+    fn test_paste_component_values_and_as_new() {
+        use crate::components::LightComponent;
 
-        // Load the prefab
-        let prefab = match crate::prefab::Prefab::load(&prefab_path) {
-            Ok(p) => p,
-            Err(e) => {
-                tracing::error!("Failed to load prefab for revert: {}", e);
-                return false;
-            }
-        };
+        let mut state = EditorState::new();
+        let source = state.scene.add_entity(EntityData {
+            components: vec![Component::MeshRenderer(MeshRendererComponent {
+                mesh: "meshes/rock.glb".to_string(),
+                ..Default::default()
+            })],
+            ..Default::default()
+        });
+        let target = state.scene.add_entity(EntityData {
+            components: vec![
+                Component::Light(LightComponent::default()),
+                Component::MeshRenderer(MeshRendererComponent::default()),
+            ],
+            ..Default::default()
+        });
+        let empty = state.scene.add_entity(EntityData::default());
+
+        assert!(state.copy_component(source, 0));
+        let copied = state.component_clipboard.clone().unwrap();
+
+        // Type mismatch and duplicate unique components are refused
+        assert!(!state.paste_component_values(target, 0));
+        assert!(!state.paste_component_as_new(target));
+        assert!(!state.history.can_undo());
+
+        assert!(state.paste_component_values(target, 1));
+        assert_eq!(state.scene.get(&target).unwrap().components[1], copied);
+        assert_eq!(state.history.undo_description(), Some("Paste Mesh Renderer Values"));
+        state.undo().unwrap();
+        assert_eq!(
+            state.scene.get(&target).unwrap().components[1],
+            Component::MeshRenderer(MeshRendererComponent::default())
+        );
+        state.redo().unwrap();
+        assert_eq!(state.scene.get(&target).unwrap().components[1], copied);
+        // Pasting identical values records nothing
+        assert!(!state.paste_component_values(target, 1));
+        state.undo().unwrap();
+
+        assert!(state.paste_component_as_new(empty));
+        assert_eq!(state.scene.get(&empty).unwrap().components, vec![copied]);
+        state.undo().unwrap();
+        assert!(state.scene.get(&empty).unwrap().components.is_empty());
+    }
 
-        // Get the local ID for this entity
-        let Some(instance) = self.prefab_manager.get_instance(root_id) else {
-            return false;
-        };
-        let Some(local_id) = instance.get_local_id(entity_id) else {
-            return false;
-        };
+    #[test]
+    fn test_inspector_component_edit_is_one_undo_step() {
+        use crate::components::{AudioRolloff, AudioSourceComponent};
 
-        // Find the prefab entity
-        let Some(prefab_entity) = self.find_prefab_entity_by_local_id(&prefab.root, local_id) else {
-            return false;
-        };
+        let mut state = EditorState::new();
+        let original = Component::AudioSource(AudioSourceComponent::default());
+        let id = state.scene.add_entity(entity("Speaker", vec![original.clone()]));
 
-        // Revert all properties
-        if let Some(entity) = self.scene.get_mut(&entity_id) {
-            entity.name = prefab_entity.name.clone();
-            entity.transform = prefab_entity.transform.clone();
-            entity.components = prefab_entity.components.clone();
+        // Live edits over a drag, committed once when it ends
+        for max_distance in [60.0, 70.0, 80.0] {
+            if let Some(Component::AudioSource(audio)) = state.scene.get_mut(&id).unwrap().components.get_mut(0) {
+                audio.max_distance = max_distance;
+                audio.rolloff = AudioRolloff::Logarithmic;
+            }
         }
+        let edited = state.scene.get(&id).unwrap().components[0].clone();
+        assert!(state.commit_component_edit(id, 0, original.clone()));
+        assert_eq!(state.history.undo_description(), Some("Edit Audio Source"));
 
-        // Clear all overrides for this entity
-        if let Some(instance) = self.prefab_manager.get_instance_mut(root_id) {
-            instance.overrides.retain(|o| o.entity_path != local_id.to_string());
-        }
+        state.undo().unwrap();
+        assert_eq!(state.scene.get(&id).unwrap().components[0], original);
+        state.redo().unwrap();
+        assert_eq!(state.scene.get(&id).unwrap().components[0], edited);
 
-        self.dirty = true;
-        true
+        // Nothing to record when the value ended where it started
+        assert!(!state.commit_component_edit(id, 0, edited));
     }
 
-    /// Get all overrides for an entity (if it's part of a prefab instance)
-    pub fn get_entity_overrides(&self, entity_id: EntityId) -> Vec<String> {
-        let Some(instance) = self.prefab_manager.find_instance_containing(entity_id) else {
-            return Vec::new();
+    #[test]
+    fn test_reorder_components_and_undo() {
+        let mut state = EditorState::new();
+        let id = state.scene.add_entity(entity(
+            "Crate",
+            vec![
+                Component::MeshRenderer(MeshRendererComponent::default()),
+                Component::Rigidbody(RigidbodyComponent::default()),
+                Component::BoxCollider(BoxColliderComponent::default()),
+            ],
+        ));
+        let order = |state: &EditorState| -> Vec<&'static str> {
+            state.scene.get(&id).unwrap().components.iter().map(Component::type_id).collect()
         };
 
-        let Some(local_id) = instance.get_local_id(entity_id) else {
-            return Vec::new();
+        assert!(state.reorder_component(id, 2, 0));
+        assert_eq!(order(&state), ["BoxCollider", "MeshRenderer", "Rigidbody"]);
+        assert!(state.reorder_component(id, 1, 2));
+        assert_eq!(order(&state), ["BoxCollider", "Rigidbody", "MeshRenderer"]);
+        assert!(!state.reorder_component(id, 0, 3));
+
+        state.undo().unwrap();
+        assert_eq!(order(&state), ["BoxCollider", "MeshRenderer", "Rigidbody"]);
+        state.undo().unwrap();
+        assert_eq!(order(&state), ["MeshRenderer", "Rigidbody", "BoxCollider"]);
+        state.redo().unwrap();
+        assert_eq!(order(&state), ["BoxCollider", "MeshRenderer", "Rigidbody"]);
+    }
+
+    #[test]
+    fn test_reorder_siblings_and_undo() {
+        let mut state = EditorState::new();
+        let parent = state.scene.add_entity(EntityData::new("Parent"));
+        let children: Vec<EntityId> = ["A", "B", "C"]
+            .into_iter()
+            .map(|name| {
+                let id = state.scene.add_entity(EntityData {
+                    name: name.to_string(),
+                    parent: Some(parent),
+                    ..Default::default()
+                });
+                state.scene.get_mut(&parent).unwrap().children.push(id);
+                id
+            })
+            .collect();
+        let order = |state: &EditorState| -> Vec<String> {
+            state.scene.get(&parent).unwrap().children.iter().map(|id| state.scene.get(id).unwrap().name.clone()).collect()
         };
 
-        let local_id_str = local_id.to_string();
-        instance.overrides
-            .iter()
-            .filter(|o| o.entity_path == local_id_str)
-            .map(|o| o.property_path.clone())
-            .collect()
+        assert!(state.reorder_sibling(children[2], 0));
+        assert_eq!(order(&state), ["C", "A", "B"]);
+        assert!(!state.reorder_sibling(children[2], 0));
+        assert!(!state.reorder_sibling(children[0], 3));
+
+        // The order survives a save and load
+        let text = SceneFormat::Ron.serialize(&SceneFile::from_scene("test", state.scene.clone())).unwrap();
+        let loaded = SceneFormat::Ron.parse(&text).unwrap().scene;
+        assert_eq!(loaded.get(&parent).unwrap().children, state.scene.get(&parent).unwrap().children);
+
+        state.undo().unwrap();
+        assert_eq!(order(&state), ["A", "B", "C"]);
+        state.redo().unwrap();
+        assert_eq!(order(&state), ["C", "A", "B"]);
+
+        // Root entities reorder within the scene order, demo entities included
+        let other_root = state.scene.add_entity(EntityData::new("Other"));
+        let roots = state.scene.root_entities();
+        assert!(state.reorder_sibling(other_root, 0));
+        assert_eq!(state.scene.root_entities()[0], other_root);
+        state.undo().unwrap();
+        assert_eq!(state.scene.root_entities(), roots);
     }
-}
-
-#[derive(Debug, Clone, Copy)]
-enum HistoryDirection {
-    Undo,
-    Redo,
-}
 
-impl Default for EditorState {
-    fn default() -> Self {
-        Self::new()
+    #[test]
+    fn test_color_label_undo_and_entity_icon() {
+        let mut state = EditorState::new();
+        let id = state.scene.add_entity(entity(
+            "Spot",
+            vec![
+                Component::Rigidbody(RigidbodyComponent::default()),
+                Component::MeshRenderer(MeshRendererComponent::default()),
+            ],
+        ));
+        let label = |state: &EditorState| state.scene.get(&id).unwrap().color_label;
+
+        // The mesh outranks the rigidbody; an empty entity gets the plain icon
+        let spot = state.scene.get(&id).unwrap();
+        assert_eq!(spot.icon(), spot.components[1].icon());
+        assert_eq!(EntityData::new("Empty").icon(), crate::components::ENTITY_ICON);
+
+        assert!(state.set_color_label(id, Some(ColorLabel::Blue)));
+        assert!(!state.set_color_label(id, Some(ColorLabel::Blue)));
+        assert!(state.set_color_label(id, None));
+        state.undo().unwrap();
+        assert_eq!(label(&state), Some(ColorLabel::Blue));
+        state.undo().unwrap();
+        assert_eq!(label(&state), None);
+        state.redo().unwrap();
+        assert_eq!(label(&state), Some(ColorLabel::Blue));
     }
 }
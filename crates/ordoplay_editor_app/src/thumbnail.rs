@@ -8,10 +8,8 @@
 use egui_wgpu::wgpu;
 use image::{DynamicImage, GenericImageView, ImageBuffer, Rgba};
 use parking_lot::RwLock;
-use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
-use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::SystemTime;
@@ -20,6 +18,9 @@ use tokio::sync::mpsc;
 /// Default thumbnail size in pixels
 pub const DEFAULT_THUMBNAIL_SIZE: u32 = 128;
 
+/// Directory of the on-disk thumbnail cache, relative to the project root
+pub const CACHE_DIR: &str = ".ordoplay/thumbnails";
+
 /// Maximum number of cached thumbnails in memory
 pub const MAX_CACHED_THUMBNAILS: usize = 500;
 
@@ -78,47 +79,98 @@ pub enum ThumbnailError {
     /// Image decoding error
     #[error("Failed to decode image: {0}")]
     DecodeError(String),
+    /// Mesh loading error
+    #[error("Failed to load mesh: {0}")]
+    MeshError(String),
     /// IO error
     #[error("IO error: {0}")]
     IoError(String),
 }
 
-/// Metadata for disk-cached thumbnails
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct DiskCacheMetadata {
-    /// Original file path
-    source_path: PathBuf,
-    /// Modification time of source file when thumbnail was generated
-    source_modified: u64,
-    /// Thumbnail dimensions
-    width: u32,
-    height: u32,
-    /// Thumbnail size setting used
-    thumbnail_size: u32,
-}
-
-/// Disk cache for thumbnails
-#[allow(dead_code)] // Intentionally kept for API completeness
+/// Disk cache for thumbnails.
+///
+/// Thumbnails are stored as PNGs named `<path hash>-<version hash>.png`,
+/// where the version hash covers the modification time of the source (or of
+/// its `.meta` import settings, whichever is newer) and the thumbnail size.
+/// Editing either changes the key, so a stale entry is never loaded, and
+/// saving the new one removes the old file. The current key of each source
+/// is indexed in memory, so saving doesn't list the cache directory.
 pub struct DiskCache {
     /// Cache directory
     cache_dir: PathBuf,
+    /// Key of the cached file for each path prefix
+    keys: parking_lot::Mutex<HashMap<String, String>>,
 }
 
 #[allow(dead_code)] // Intentionally kept for API completeness
 impl DiskCache {
-    /// Create a new disk cache at the given directory
+    /// Create a new disk cache at the given directory, indexing the
+    /// thumbnails already in it. Where a source has several versions, only
+    /// the newest file is kept.
     pub fn new(cache_dir: impl Into<PathBuf>) -> std::io::Result<Self> {
         let cache_dir = cache_dir.into();
         fs::create_dir_all(&cache_dir)?;
-        Ok(Self { cache_dir })
+
+        let mut newest: HashMap<String, (String, SystemTime)> = HashMap::new();
+        for entry in fs::read_dir(&cache_dir)?.flatten() {
+            let path = entry.path();
+            if path.extension().map_or(true, |e| e != "png") {
+                continue;
+            }
+            let Some(key) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            let Some(dash) = key.find('-') else {
+                continue;
+            };
+            let prefix = &key[..=dash];
+            let modified = entry.metadata().and_then(|m| m.modified()).unwrap_or(SystemTime::UNIX_EPOCH);
+
+            match newest.get(prefix) {
+                Some((_, kept)) if *kept >= modified => {
+                    let _ = fs::remove_file(&path);
+                }
+                _ => {
+                    if let Some((old, _)) = newest.insert(prefix.to_string(), (key.to_string(), modified)) {
+                        let _ = fs::remove_file(cache_dir.join(format!("{}.png", old)));
+                    }
+                }
+            }
+        }
+
+        let keys = newest.into_iter().map(|(prefix, (key, _))| (prefix, key)).collect();
+        Ok(Self { cache_dir, keys: parking_lot::Mutex::new(keys) })
     }
 
-    /// Compute cache path for a source file
-    fn cache_path(&self, source_path: &Path, suffix: &str) -> PathBuf {
-        // Use a hash of the absolute path for the cache filename
-        let path_str = source_path.to_string_lossy();
-        let hash = Self::hash_string(&path_str);
-        self.cache_dir.join(format!("{:016x}{}", hash, suffix))
+    /// Directory the thumbnails are stored in
+    pub fn dir(&self) -> &Path {
+        &self.cache_dir
+    }
+
+    /// Cache key of a source file at a thumbnail size, or `None` when its
+    /// modification time can't be read
+    pub fn cache_key(source_path: &Path, thumbnail_size: u32) -> Option<String> {
+        let meta_modified = Self::get_modified_time(&crate::import_settings::meta_path(source_path));
+        let modified = Self::get_modified_time(source_path)?.max(meta_modified.unwrap_or(0));
+        Some(Self::key_for(source_path, modified, thumbnail_size))
+    }
+
+    fn key_for(source_path: &Path, modified: u128, thumbnail_size: u32) -> String {
+        format!(
+            "{}{:016x}",
+            Self::path_prefix(source_path),
+            Self::hash_string(&format!("{}:{}", modified, thumbnail_size))
+        )
+    }
+
+    /// Part of the key shared by every version of a source file
+    fn path_prefix(source_path: &Path) -> String {
+        format!("{:016x}-", Self::hash_string(&source_path.to_string_lossy()))
+    }
+
+    /// Compute cache path for a cache key
+    fn cache_path(&self, key: &str) -> PathBuf {
+        self.cache_dir.join(format!("{}.png", key))
     }
 
     /// Simple string hash (FNV-1a)
@@ -131,104 +183,61 @@ impl DiskCache {
         hash
     }
 
-    /// Get modification time of a file as u64 (seconds since UNIX epoch)
-    fn get_modified_time(path: &Path) -> Option<u64> {
+    /// Get modification time of a file in milliseconds since the UNIX epoch
+    fn get_modified_time(path: &Path) -> Option<u128> {
         fs::metadata(path)
             .ok()
             .and_then(|m| m.modified().ok())
             .and_then(|t| t.duration_since(SystemTime::UNIX_EPOCH).ok())
-            .map(|d| d.as_secs())
+            .map(|d| d.as_millis())
     }
 
     /// Check if cached thumbnail is valid (exists and source hasn't changed)
     pub fn is_valid(&self, source_path: &Path, thumbnail_size: u32) -> bool {
-        let meta_path = self.cache_path(source_path, ".meta");
-        let data_path = self.cache_path(source_path, ".thumb");
-
-        // Both files must exist
-        if !meta_path.exists() || !data_path.exists() {
-            return false;
-        }
-
-        // Read and validate metadata
-        let Ok(meta_data) = fs::read(&meta_path) else {
-            return false;
-        };
-
-        let Ok(metadata): Result<DiskCacheMetadata, _> = serde_json::from_slice(&meta_data) else {
-            return false;
-        };
-
-        // Check thumbnail size matches
-        if metadata.thumbnail_size != thumbnail_size {
-            return false;
-        }
-
-        // Check source modification time
-        let Some(current_mtime) = Self::get_modified_time(source_path) else {
-            return false;
-        };
-
-        metadata.source_modified == current_mtime
+        Self::cache_key(source_path, thumbnail_size).is_some_and(|key| self.cache_path(&key).exists())
     }
 
     /// Load cached thumbnail data
-    pub fn load(&self, source_path: &Path) -> Option<ThumbnailData> {
-        let meta_path = self.cache_path(source_path, ".meta");
-        let data_path = self.cache_path(source_path, ".thumb");
-
-        // Read metadata
-        let meta_data = fs::read(&meta_path).ok()?;
-        let metadata: DiskCacheMetadata = serde_json::from_slice(&meta_data).ok()?;
-
-        // Read pixel data
-        let mut file = fs::File::open(&data_path).ok()?;
-        let mut pixels = Vec::new();
-        file.read_to_end(&mut pixels).ok()?;
+    pub fn load(&self, source_path: &Path, thumbnail_size: u32) -> Option<ThumbnailData> {
+        let key = Self::cache_key(source_path, thumbnail_size)?;
+        let image = image::open(self.cache_path(&key)).ok()?.to_rgba8();
 
         Some(ThumbnailData {
             path: source_path.to_path_buf(),
-            pixels,
-            width: metadata.width,
-            height: metadata.height,
+            width: image.width(),
+            height: image.height(),
+            pixels: image.into_raw(),
         })
     }
 
-    /// Save thumbnail to disk cache
+    /// Save thumbnail to disk cache, replacing older versions of it
     pub fn save(&self, data: &ThumbnailData, thumbnail_size: u32) -> std::io::Result<()> {
-        let meta_path = self.cache_path(&data.path, ".meta");
-        let data_path = self.cache_path(&data.path, ".thumb");
-
-        // Get source modification time
-        let source_modified = Self::get_modified_time(&data.path).unwrap_or(0);
-
-        // Create metadata
-        let metadata = DiskCacheMetadata {
-            source_path: data.path.clone(),
-            source_modified,
-            width: data.width,
-            height: data.height,
-            thumbnail_size,
-        };
+        let key = Self::cache_key(&data.path, thumbnail_size)
+            .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "source file has no modification time"))?;
 
-        // Write metadata
-        let meta_json = serde_json::to_vec(&metadata)
-            .map_err(std::io::Error::other)?;
-        fs::write(&meta_path, &meta_json)?;
-
-        // Write pixel data
-        let mut file = fs::File::create(&data_path)?;
-        file.write_all(&data.pixels)?;
+        image::save_buffer(
+            self.cache_path(&key),
+            &data.pixels,
+            data.width,
+            data.height,
+            image::ExtendedColorType::Rgba8,
+        )
+        .map_err(std::io::Error::other)?;
 
+        let previous = self.keys.lock().insert(Self::path_prefix(&data.path), key.clone());
+        if let Some(previous) = previous.filter(|previous| *previous != key) {
+            let _ = fs::remove_file(self.cache_path(&previous));
+        }
         Ok(())
     }
 
     /// Clear all cached thumbnails
     pub fn clear(&self) -> std::io::Result<()> {
+        self.keys.lock().clear();
         for entry in fs::read_dir(&self.cache_dir)? {
             let entry = entry?;
             let path = entry.path();
-            if path.extension().map(|e| e == "meta" || e == "thumb").unwrap_or(false) {
+            if path.extension().is_some_and(|e| e == "png") {
                 let _ = fs::remove_file(path);
             }
         }
@@ -272,8 +281,8 @@ pub struct ThumbnailManager {
     states: Arc<RwLock<HashMap<PathBuf, ThumbnailState>>>,
     /// Channel for sending thumbnail requests
     request_tx: mpsc::UnboundedSender<ThumbnailRequest>,
-    /// Channel for receiving generated thumbnails
-    result_rx: mpsc::UnboundedReceiver<ThumbnailResult>,
+    /// Channel for receiving generated thumbnails, keyed by asset path
+    result_rx: mpsc::UnboundedReceiver<(PathBuf, ThumbnailResult)>,
     /// Default thumbnail size
     pub thumbnail_size: u32,
     /// Cache directory for disk persistence
@@ -307,10 +316,16 @@ impl ThumbnailManager {
         }
     }
 
-    /// Set the cache directory for disk persistence
-    #[allow(dead_code)] // Intentionally kept for API completeness
-    pub fn with_cache_dir(mut self, path: impl Into<PathBuf>) -> Self {
+    /// Directory thumbnails are persisted to, if any
+    pub fn cache_dir(&self) -> Option<&Path> {
+        self.cache_dir.as_deref()
+    }
+
+    /// Move disk persistence to another directory, such as when another
+    /// project is opened
+    pub fn set_cache_dir(&mut self, path: impl Into<PathBuf>) {
         let cache_dir = path.into();
+        self.disk_cache = None;
         self.cache_dir = Some(cache_dir.clone());
         // Create disk cache
         match DiskCache::new(&cache_dir) {
@@ -322,7 +337,6 @@ impl ThumbnailManager {
                 tracing::warn!("Failed to create thumbnail disk cache: {}", e);
             }
         }
-        self
     }
 
     /// Get disk cache statistics
@@ -342,15 +356,29 @@ impl ThumbnailManager {
 
     /// Request a thumbnail for an asset
     pub fn request_thumbnail(&self, path: &Path) {
+        self.request(path, Self::can_generate_thumbnail(path));
+    }
+
+    /// Request a rendered preview of a glTF, GLB or OBJ mesh
+    pub fn request_mesh_thumbnail(&self, path: &Path) {
+        self.request(path, crate::mesh_thumbnail::is_previewable_mesh(path));
+    }
+
+    /// Queue generation unless the thumbnail is known already; unsupported
+    /// files fall back to their icon
+    fn request(&self, path: &Path, supported: bool) {
         let mut states = self.states.write();
 
-        // Skip if already loaded or loading
-        if let Some(ThumbnailState::Ready(_) | ThumbnailState::Loading | ThumbnailState::UseDefault) = states.get(path) {
+        // Skip if already loaded, loading or known to be unavailable
+        if let Some(
+            ThumbnailState::Ready(_) | ThumbnailState::Loading | ThumbnailState::UseDefault | ThumbnailState::Failed(_),
+        ) = states.get(path)
+        {
             return;
         }
 
         // Check if we can generate a thumbnail for this file type
-        if !Self::can_generate_thumbnail(path) {
+        if !supported {
             states.insert(path.to_path_buf(), ThumbnailState::UseDefault);
             return;
         }
@@ -415,7 +443,7 @@ impl ThumbnailManager {
         egui_renderer: &mut egui_wgpu::Renderer,
     ) {
         // Process all available results
-        while let Ok(result) = self.result_rx.try_recv() {
+        while let Ok((path, result)) = self.result_rx.try_recv() {
             match result {
                 Ok(data) => {
                     // Create egui texture
@@ -443,16 +471,14 @@ impl ThumbnailManager {
                     self.evict_if_needed(egui_renderer);
                 }
                 Err(e) => {
-                    if let ThumbnailError::UnsupportedFormat(_) = &e {
-                        // Mark as using default icon
-                        if let Some(path) = extract_path_from_error(&e) {
-                            self.states
-                                .write()
-                                .insert(path, ThumbnailState::UseDefault);
-                        }
+                    // Either way the asset falls back to its icon
+                    let state = if let ThumbnailError::UnsupportedFormat(_) = &e {
+                        ThumbnailState::UseDefault
                     } else {
-                        tracing::warn!("Thumbnail generation failed: {}", e);
-                    }
+                        tracing::warn!("Thumbnail generation failed for {}: {}", path.display(), e);
+                        ThumbnailState::Failed(e.to_string())
+                    };
+                    self.states.write().insert(path, state);
                 }
             }
         }
@@ -471,9 +497,15 @@ impl ThumbnailManager {
             &data.pixels,
         );
 
-        // Create a wgpu texture and register it with egui
+        // Create a wgpu texture and register it with egui, sampled the way
+        // the texture is imported
+        let filter = if Self::can_generate_thumbnail(&data.path) {
+            crate::import_settings::texture_settings(&data.path).filter.sampler_filter()
+        } else {
+            wgpu::FilterMode::Linear
+        };
         let texture_view = create_texture(device, queue, &image);
-        egui_renderer.register_native_texture(device, &texture_view, wgpu::FilterMode::Linear)
+        egui_renderer.register_native_texture(device, &texture_view, filter)
     }
 
     /// Evict old cache entries if we're over the limit
@@ -546,7 +578,6 @@ impl ThumbnailManager {
     }
 
     /// Invalidate and immediately re-request thumbnails for the given paths
-    #[allow(dead_code)] // Intentionally kept for API completeness
     pub fn hot_reload_paths(&mut self, paths: &[PathBuf], egui_renderer: &mut egui_wgpu::Renderer) {
         // First invalidate
         self.invalidate_paths(paths.iter(), egui_renderer);
@@ -555,6 +586,8 @@ impl ThumbnailManager {
         for path in paths {
             if Self::can_generate_thumbnail(path) {
                 self.request_thumbnail(path);
+            } else if crate::mesh_thumbnail::is_previewable_mesh(path) {
+                self.request_mesh_thumbnail(path);
             }
         }
     }
@@ -576,7 +609,7 @@ impl Default for ThumbnailManager {
 /// Worker thread that processes thumbnail generation requests
 fn thumbnail_worker(
     mut request_rx: mpsc::UnboundedReceiver<ThumbnailRequest>,
-    result_tx: mpsc::UnboundedSender<ThumbnailResult>,
+    result_tx: mpsc::UnboundedSender<(PathBuf, ThumbnailResult)>,
 ) {
     // Use tokio runtime for async file operations
     let rt = tokio::runtime::Builder::new_current_thread()
@@ -585,17 +618,19 @@ fn thumbnail_worker(
         .expect("Failed to create tokio runtime");
 
     rt.block_on(async {
+        // Kept across requests so its key index is built once
+        let mut disk_cache: Option<DiskCache> = None;
         while let Some(request) = request_rx.recv().await {
             // Try loading from disk cache first
-            let disk_cache = request
-                .cache_dir
-                .as_ref()
-                .and_then(|dir| DiskCache::new(dir).ok());
+            let cache_dir = request.cache_dir.as_deref();
+            if disk_cache.as_ref().map(DiskCache::dir) != cache_dir {
+                disk_cache = cache_dir.and_then(|dir| DiskCache::new(dir).ok());
+            }
 
             if let Some(ref dc) = disk_cache {
                 if dc.is_valid(&request.path, request.size) {
-                    if let Some(data) = dc.load(&request.path) {
-                        if result_tx.send(Ok(data)).is_err() {
+                    if let Some(data) = dc.load(&request.path, request.size) {
+                        if result_tx.send((request.path.clone(), Ok(data))).is_err() {
                             break;
                         }
                         continue;
@@ -613,7 +648,7 @@ fn thumbnail_worker(
                 }
             }
 
-            if result_tx.send(result).is_err() {
+            if result_tx.send((request.path, result)).is_err() {
                 break; // Channel closed
             }
         }
@@ -639,6 +674,7 @@ async fn generate_thumbnail(path: &Path, size: u32) -> ThumbnailResult {
         "png" | "jpg" | "jpeg" | "gif" | "bmp" | "ico" | "tga" | "webp" | "ppm" | "pgm" | "pbm"
         | "pam" => generate_image_thumbnail(path, size).await,
         "hdr" | "exr" => generate_hdr_thumbnail(path, size).await,
+        "obj" | "gltf" | "glb" => generate_mesh_thumbnail(path, size).await,
         _ => Err(ThumbnailError::UnsupportedFormat(ext)),
     }
 }
@@ -654,7 +690,8 @@ async fn generate_image_thumbnail(path: &Path, size: u32) -> ThumbnailResult {
         .map_err(|e| ThumbnailError::DecodeError(e.to_string()))?;
 
     // Resize maintaining aspect ratio
-    let thumbnail = resize_image(&img, size);
+    let filter = crate::import_settings::texture_settings(path).filter;
+    let thumbnail = resize_image(&img, size, filter.resize_filter());
 
     Ok(ThumbnailData {
         path: path.to_path_buf(),
@@ -676,7 +713,8 @@ async fn generate_hdr_thumbnail(path: &Path, size: u32) -> ThumbnailResult {
     let tonemapped = tonemap_hdr(&img);
 
     // Resize
-    let thumbnail = resize_image(&tonemapped, size);
+    let filter = crate::import_settings::texture_settings(path).filter;
+    let thumbnail = resize_image(&tonemapped, size, filter.resize_filter());
 
     Ok(ThumbnailData {
         path: path.to_path_buf(),
@@ -686,8 +724,20 @@ async fn generate_hdr_thumbnail(path: &Path, size: u32) -> ThumbnailResult {
     })
 }
 
+/// Generate thumbnail for meshes by rendering them with the default material
+async fn generate_mesh_thumbnail(path: &Path, size: u32) -> ThumbnailResult {
+    let mesh = crate::mesh_thumbnail::load_mesh(path).map_err(ThumbnailError::MeshError)?;
+
+    Ok(ThumbnailData {
+        path: path.to_path_buf(),
+        pixels: crate::mesh_thumbnail::render_mesh_thumbnail(&mesh, size),
+        width: size,
+        height: size,
+    })
+}
+
 /// Resize image maintaining aspect ratio
-fn resize_image(img: &DynamicImage, max_size: u32) -> DynamicImage {
+fn resize_image(img: &DynamicImage, max_size: u32, filter: image::imageops::FilterType) -> DynamicImage {
     let (width, height) = img.dimensions();
 
     if width <= max_size && height <= max_size {
@@ -701,7 +751,7 @@ fn resize_image(img: &DynamicImage, max_size: u32) -> DynamicImage {
         ((max_size as f32 * ratio) as u32, max_size)
     };
 
-    img.resize(new_width, new_height, image::imageops::FilterType::Lanczos3)
+    img.resize(new_width, new_height, filter)
 }
 
 /// Simple Reinhard tonemapping for HDR images
@@ -730,12 +780,6 @@ fn tonemap_hdr(img: &DynamicImage) -> DynamicImage {
     DynamicImage::ImageRgba8(output)
 }
 
-/// Extract path from error (for updating state)
-fn extract_path_from_error(_error: &ThumbnailError) -> Option<PathBuf> {
-    // This is a simplified version - in practice we'd track this differently
-    None
-}
-
 /// Create a wgpu texture from egui `ColorImage`
 fn create_texture(
     device: &wgpu::Device,
@@ -914,3 +958,62 @@ fn get_file_icon(path: &Path) -> &'static str {
         _ => "\u{f15b}",
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_cache_key_changes_with_source() {
+        let path = Path::new("assets/brick.png");
+        let key = DiskCache::key_for(path, 1_000, 128);
+
+        assert_eq!(key, DiskCache::key_for(path, 1_000, 128));
+        assert_ne!(key, DiskCache::key_for(path, 2_000, 128));
+        assert_ne!(key, DiskCache::key_for(path, 1_000, 64));
+        // Every version of a file shares the prefix used to drop stale ones
+        assert!(DiskCache::key_for(path, 2_000, 128).starts_with(&DiskCache::path_prefix(path)));
+        assert!(!DiskCache::key_for(Path::new("assets/tile.png"), 1_000, 128).starts_with(&DiskCache::path_prefix(path)));
+    }
+
+    #[test]
+    fn test_modified_source_invalidates_entry() {
+        let dir = std::env::temp_dir().join(format!("thumbnail_cache_{}", uuid::Uuid::new_v4()));
+        let cache = DiskCache::new(dir.join("cache")).unwrap();
+        let source = dir.join("tile.png");
+        fs::write(&source, b"source").unwrap();
+        let touch = |seconds: u64| {
+            let file = fs::File::options().write(true).open(&source).unwrap();
+            file.set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(seconds)).unwrap();
+        };
+        let data = ThumbnailData {
+            path: source.clone(),
+            pixels: vec![200; 2 * 2 * 4],
+            width: 2,
+            height: 2,
+        };
+
+        touch(1_000);
+        cache.save(&data, 128).unwrap();
+        assert!(cache.is_valid(&source, 128));
+        assert_eq!(cache.load(&source, 128).unwrap().pixels, data.pixels);
+        assert!(!cache.is_valid(&source, 64));
+
+        touch(2_000);
+        assert!(!cache.is_valid(&source, 128));
+        assert!(cache.load(&source, 128).is_none());
+        cache.save(&data, 128).unwrap();
+        assert_eq!(cache.stats().0, 1);
+
+        // A cache reopened over the directory replaces the entry it found
+        drop(cache);
+        let cache = DiskCache::new(dir.join("cache")).unwrap();
+        touch(3_000);
+        cache.save(&data, 128).unwrap();
+        assert_eq!(cache.stats().0, 1);
+        assert!(cache.is_valid(&source, 128));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -2,6 +2,8 @@
 //! Editor tools (gizmos, transform handles, etc.)
 
 
+use crate::project::UpAxis;
+use crate::state::{EntityId, SceneData, Transform};
 use serde::{Deserialize, Serialize};
 
 /// Gizmo mode for transform operations
@@ -67,7 +69,6 @@ pub enum AxisConstraint {
     YZ,
 }
 
-#[allow(dead_code)] // Intentionally kept for API completeness
 impl AxisConstraint {
     /// Get the axis mask as a Vec3 (1.0 = active, 0.0 = constrained)
     pub fn mask(&self) -> [f32; 3] {
@@ -81,6 +82,391 @@ impl AxisConstraint {
             Self::YZ => [0.0, 1.0, 1.0],
         }
     }
+
+    /// `v` with the components outside the constraint zeroed
+    pub fn apply(&self, v: [f32; 3]) -> [f32; 3] {
+        let mask = self.mask();
+        std::array::from_fn(|i| v[i] * mask[i])
+    }
+
+    /// Whether the axis with component `index` is free to move
+    pub fn allows(&self, index: usize) -> bool {
+        self.mask()[index] > 0.0
+    }
+
+    /// Get a display name (None when unconstrained)
+    pub fn name(&self) -> Option<&'static str> {
+        match self {
+            Self::None => None,
+            Self::X => Some("X"),
+            Self::Y => Some("Y"),
+            Self::Z => Some("Z"),
+            Self::XY => Some("XY"),
+            Self::XZ => Some("XZ"),
+            Self::YZ => Some("YZ"),
+        }
+    }
+}
+
+/// A dragged point lining up with another entity on one world axis
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AlignmentGuide {
+    /// Axis index (0 = X, 1 = Y, 2 = Z)
+    pub axis: usize,
+    /// Entity the point lines up with
+    pub target: EntityId,
+    /// World position of that entity
+    pub target_position: [f32; 3],
+    /// Distance to move along `axis` to line up exactly
+    pub offset: f32,
+}
+
+/// Find the closest alignment on each axis in `axes`.
+///
+/// Only candidates within `search_radius` of `position` are considered,
+/// and a coordinate must be within `threshold` to count as aligned.
+pub fn find_alignments(
+    position: [f32; 3],
+    candidates: impl IntoIterator<Item = (EntityId, [f32; 3])>,
+    axes: [bool; 3],
+    threshold: f32,
+    search_radius: f32,
+) -> Vec<AlignmentGuide> {
+    let mut best: [Option<AlignmentGuide>; 3] = [None; 3];
+    let radius_sq = search_radius * search_radius;
+
+    for (target, target_position) in candidates {
+        let distance_sq: f32 = (0..3).map(|i| (target_position[i] - position[i]).powi(2)).sum();
+        if distance_sq > radius_sq {
+            continue;
+        }
+
+        for axis in (0..3).filter(|axis| axes[*axis]) {
+            let offset = target_position[axis] - position[axis];
+            if offset.abs() > threshold {
+                continue;
+            }
+            if best[axis].map_or(true, |guide| offset.abs() < guide.offset.abs()) {
+                best[axis] = Some(AlignmentGuide {
+                    axis,
+                    target,
+                    target_position,
+                    offset,
+                });
+            }
+        }
+    }
+
+    best.into_iter().flatten().collect()
+}
+
+/// Offset that moves a point onto all of its alignment guides
+pub fn alignment_snap_offset(guides: &[AlignmentGuide]) -> [f32; 3] {
+    let mut offset = [0.0; 3];
+    for guide in guides {
+        offset[guide.axis] = guide.offset;
+    }
+    offset
+}
+
+/// Point a multi-selection rotates and scales around
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum PivotMode {
+    /// Mean of the selected entities' origins
+    #[default]
+    MedianPoint,
+    /// Center of the selection's world bounds
+    BoundingBoxCenter,
+    /// Origin of the last selected entity
+    ActiveElement,
+    /// Each entity around its own origin; the gizmo sits at the median
+    IndividualOrigins,
+}
+
+impl PivotMode {
+    /// All modes, in menu order
+    pub const ALL: [Self; 4] = [
+        Self::MedianPoint,
+        Self::BoundingBoxCenter,
+        Self::ActiveElement,
+        Self::IndividualOrigins,
+    ];
+
+    /// Display name
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::MedianPoint => "Median Point",
+            Self::BoundingBoxCenter => "Bounding Box Center",
+            Self::ActiveElement => "Active Element",
+            Self::IndividualOrigins => "Individual Origins",
+        }
+    }
+
+    /// Pivot of one entity at `origin`, given the selection's shared pivot
+    pub fn pivot_for(self, shared: [f32; 3], origin: [f32; 3]) -> [f32; 3] {
+        match self {
+            Self::IndividualOrigins => origin,
+            _ => shared,
+        }
+    }
+}
+
+/// Shared pivot of a selection: where the gizmo sits and, unless each
+/// entity uses its own origin, what everything turns and scales around.
+///
+/// `origins` are the selected entities' world positions, `bounds` their
+/// world bounds and `active` the origin of the last selected entity.
+pub fn selection_pivot(
+    mode: PivotMode,
+    origins: &[[f32; 3]],
+    bounds: &[([f32; 3], [f32; 3])],
+    active: Option<[f32; 3]>,
+) -> Option<[f32; 3]> {
+    let median = || {
+        if origins.is_empty() {
+            return None;
+        }
+        let mut sum = [0.0; 3];
+        for origin in origins {
+            for (total, value) in sum.iter_mut().zip(origin) {
+                *total += value;
+            }
+        }
+        Some(sum.map(|total| total / origins.len() as f32))
+    };
+
+    match mode {
+        PivotMode::MedianPoint | PivotMode::IndividualOrigins => median(),
+        PivotMode::ActiveElement => active.or_else(median),
+        PivotMode::BoundingBoxCenter => {
+            let (first, rest) = bounds.split_first()?;
+            let (min, max) = rest.iter().fold(*first, |(min, max), (lo, hi)| {
+                (std::array::from_fn(|i| min[i].min(lo[i])), std::array::from_fn(|i| max[i].max(hi[i])))
+            });
+            Some(std::array::from_fn(|i| (min[i] + max[i]) * 0.5))
+        }
+    }
+}
+
+/// Rotate `v` by `degrees` around the unit vector `axis` (right-handed)
+pub fn rotate_around_axis(v: [f32; 3], axis: [f32; 3], degrees: f32) -> [f32; 3] {
+    let (sin, cos) = degrees.to_radians().sin_cos();
+    let dot = v[0] * axis[0] + v[1] * axis[1] + v[2] * axis[2];
+    let cross = [
+        axis[1] * v[2] - axis[2] * v[1],
+        axis[2] * v[0] - axis[0] * v[2],
+        axis[0] * v[1] - axis[1] * v[0],
+    ];
+    std::array::from_fn(|i| v[i] * cos + cross[i] * sin + axis[i] * dot * (1.0 - cos))
+}
+
+/// Rotate a world transform by `degrees` around `axis` through `pivot`
+pub fn rotate_transform_about(world: &Transform, pivot: [f32; 3], axis: [f32; 3], degrees: f32) -> Transform {
+    let offset = [world.position[0] - pivot[0], world.position[1] - pivot[1], world.position[2] - pivot[2]];
+    let offset = rotate_around_axis(offset, axis, degrees);
+    let basis = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]]
+        .map(|unit| rotate_around_axis(world.rotate(unit), axis, degrees));
+
+    Transform {
+        position: [pivot[0] + offset[0], pivot[1] + offset[1], pivot[2] + offset[2]],
+        rotation: Transform::euler_from_basis(basis),
+        scale: world.scale,
+    }
+}
+
+/// Scale the offset of `position` from `pivot` by `factor`, along the unit
+/// vector `axis` only, or uniformly when `axis` is `None`
+pub fn scale_offset_about(position: [f32; 3], pivot: [f32; 3], axis: Option<[f32; 3]>, factor: f32) -> [f32; 3] {
+    let offset = [position[0] - pivot[0], position[1] - pivot[1], position[2] - pivot[2]];
+    let scaled = match axis {
+        Some(axis) => {
+            let along = (offset[0] * axis[0] + offset[1] * axis[1] + offset[2] * axis[2]) * (factor - 1.0);
+            [offset[0] + axis[0] * along, offset[1] + axis[1] * along, offset[2] + axis[2] * along]
+        }
+        None => offset.map(|v| v * factor),
+    };
+    [pivot[0] + scaled[0], pivot[1] + scaled[1], pivot[2] + scaled[2]]
+}
+
+/// How two entities in a relationship line are related
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RelationshipKind {
+    /// Child to parent
+    Parent,
+    /// Entity reference field to its target
+    Reference(&'static str),
+}
+
+/// A world-space line between two related entities
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RelationshipLine {
+    /// How the entities are related
+    pub kind: RelationshipKind,
+    /// Child or referencing entity
+    pub from: EntityId,
+    /// Parent or referenced entity
+    pub to: EntityId,
+    /// World position of `from`
+    pub start: [f32; 3],
+    /// World position of `to`
+    pub end: [f32; 3],
+}
+
+/// Collect relationship lines for the whole scene, or with `focus` only
+/// those touching one of the focused entities.
+pub fn collect_relationship_lines(scene: &SceneData, focus: Option<&[EntityId]>) -> Vec<RelationshipLine> {
+    let in_scope = |from: EntityId, to: EntityId| focus.map_or(true, |ids| ids.contains(&from) || ids.contains(&to));
+    let mut lines = Vec::new();
+
+    for (id, entity) in &scene.entities {
+        let targets = entity
+            .parent
+            .map(|parent| (RelationshipKind::Parent, parent))
+            .into_iter()
+            .chain(entity.components.iter().flat_map(|component| {
+                component
+                    .entity_references()
+                    .into_iter()
+                    .filter_map(|(field, target)| Some((RelationshipKind::Reference(field), target?)))
+            }));
+
+        for (kind, target) in targets {
+            if !in_scope(*id, target) {
+                continue;
+            }
+            let (Some(start), Some(end)) = (scene.world_position(*id), scene.world_position(target)) else {
+                continue;
+            };
+            lines.push(RelationshipLine {
+                kind,
+                from: *id,
+                to: target,
+                start,
+                end,
+            });
+        }
+    }
+    lines
+}
+
+/// Translation snap grid. It can be moved off the world origin and turned
+/// about the world up axis to fit offset or rotated layouts.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SnapGrid {
+    /// Cell size
+    pub size: f32,
+    /// World position of a grid point
+    pub origin: [f32; 3],
+    /// Rotation about the up axis in degrees
+    pub rotation: f32,
+    /// World up axis
+    pub up_axis: UpAxis,
+}
+
+impl SnapGrid {
+    /// Whether this is the plain world grid through the origin
+    pub fn is_world_aligned(&self) -> bool {
+        self.origin == [0.0; 3] && self.rotation % 360.0 == 0.0
+    }
+
+    /// Point on the line from `start` along `offset`, moved to the nearest
+    /// grid line of the grid axis closest to the line's direction. Only that
+    /// axis is snapped, so a constrained drag stays on its axis.
+    pub fn snap_along(&self, start: [f32; 3], offset: [f32; 3]) -> [f32; 3] {
+        if self.size <= 0.0 || offset == [0.0; 3] {
+            return std::array::from_fn(|i| start[i] + offset[i]);
+        }
+        let up = self.up_axis.index();
+        let angle = self.rotation.to_radians();
+
+        // Grid-space start and direction
+        let from = crate::state::rotate_axis(std::array::from_fn(|i| start[i] - self.origin[i]), up, -angle);
+        let direction = crate::state::rotate_axis(offset, up, -angle);
+        let axis = (0..3)
+            .max_by(|&a, &b| direction[a].abs().total_cmp(&direction[b].abs()))
+            .unwrap_or(0);
+
+        let target = ((from[axis] + direction[axis]) / self.size).round() * self.size;
+        let t = (target - from[axis]) / direction[axis];
+        std::array::from_fn(|i| start[i] + offset[i] * t)
+    }
+}
+
+/// How translate drags snap
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum SnapMode {
+    /// Free movement
+    #[default]
+    None,
+    /// Snap to the translation grid; rotation and scale snap in steps
+    Grid,
+    /// Snap the pivot onto the nearest mesh vertex under the cursor
+    Vertex,
+}
+
+impl SnapMode {
+    /// All modes, in menu order
+    pub const ALL: [Self; 3] = [Self::None, Self::Grid, Self::Vertex];
+
+    /// Display name
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::None => "Off",
+            Self::Grid => "Grid",
+            Self::Vertex => "Vertex",
+        }
+    }
+
+    /// Mode in effect while the toggle modifier is `held`: vertex snapping
+    /// turns on, or off when it is already the chosen mode
+    pub fn toggled(self, held: bool) -> Self {
+        match (held, self) {
+            (false, mode) => mode,
+            (true, Self::Vertex) => Self::None,
+            (true, _) => Self::Vertex,
+        }
+    }
+}
+
+/// A mesh vertex the dragged pivot snaps onto
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VertexSnapTarget {
+    /// Entity owning the vertex
+    pub entity: EntityId,
+    /// World position of the vertex
+    pub position: [f32; 3],
+    /// Distance from the cursor on screen, in points
+    pub screen_distance: f32,
+}
+
+/// Find the vertex closest to `cursor` on screen, within `max_distance`.
+///
+/// `project` maps a world position to the screen, or `None` when it is not
+/// visible.
+pub fn nearest_vertex(
+    cursor: [f32; 2],
+    vertices: impl IntoIterator<Item = (EntityId, [f32; 3])>,
+    project: impl Fn([f32; 3]) -> Option<[f32; 2]>,
+    max_distance: f32,
+) -> Option<VertexSnapTarget> {
+    let mut best: Option<VertexSnapTarget> = None;
+    for (entity, position) in vertices {
+        let Some(screen) = project(position) else {
+            continue;
+        };
+        let screen_distance = ((screen[0] - cursor[0]).powi(2) + (screen[1] - cursor[1]).powi(2)).sqrt();
+        if screen_distance > max_distance {
+            continue;
+        }
+        if best.map_or(true, |target| screen_distance < target.screen_distance) {
+            best = Some(VertexSnapTarget {
+                entity,
+                position,
+                screen_distance,
+            });
+        }
+    }
+    best
 }
 
 /// State for an active gizmo operation
@@ -140,6 +526,102 @@ impl GizmoOperation {
     }
 }
 
+/// How the viewport camera projects the scene
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CameraProjection {
+    /// Perspective projection with foreshortening
+    #[default]
+    Perspective,
+    /// Parallel projection, sized by the orbit distance
+    Orthographic,
+}
+
+impl CameraProjection {
+    /// Get display name
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Perspective => "Perspective",
+            Self::Orthographic => "Orthographic",
+        }
+    }
+}
+
+/// Axis-aligned orthographic views, as on the numeric keypad
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ViewPreset {
+    /// Looking down onto the ground plane
+    Top,
+    /// Looking up from below the ground plane
+    Bottom,
+    /// Looking from the front (+Z in Y-up)
+    Front,
+    /// Looking from the back
+    Back,
+    /// Looking from the left (-X)
+    Left,
+    /// Looking from the right (+X)
+    Right,
+}
+
+impl ViewPreset {
+    /// All presets in menu order
+    pub const ALL: [Self; 6] = [Self::Top, Self::Bottom, Self::Front, Self::Back, Self::Left, Self::Right];
+
+    /// Get display name
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Top => "Top",
+            Self::Bottom => "Bottom",
+            Self::Front => "Front",
+            Self::Back => "Back",
+            Self::Left => "Left",
+            Self::Right => "Right",
+        }
+    }
+
+    /// Orbit yaw and pitch that look from this side (Y-up orbit convention)
+    fn orbit_angles(&self) -> (f32, f32) {
+        use std::f32::consts::{FRAC_PI_2, PI};
+        match self {
+            Self::Top => (0.0, FRAC_PI_2),
+            Self::Bottom => (0.0, -FRAC_PI_2),
+            Self::Front => (0.0, 0.0),
+            Self::Back => (PI, 0.0),
+            Self::Left => (-FRAC_PI_2, 0.0),
+            Self::Right => (FRAC_PI_2, 0.0),
+        }
+    }
+
+    /// Direction shown as up on screen (Y-up convention). Top and bottom
+    /// look along the up axis, so they use a ground axis instead.
+    fn screen_up(&self) -> [f32; 3] {
+        match self {
+            Self::Top => [0.0, 0.0, -1.0],
+            Self::Bottom => [0.0, 0.0, 1.0],
+            _ => [0.0, 1.0, 0.0],
+        }
+    }
+}
+
+/// Orbit of the free camera, kept while an orthographic view is shown
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CameraPose {
+    /// Look-at point
+    pub target: [f32; 3],
+    /// Orbit distance from target
+    pub distance: f32,
+    /// Orbit yaw angle in radians
+    pub yaw: f32,
+    /// Orbit pitch angle in radians
+    pub pitch: f32,
+}
+
+/// Smallest radius framed, so a single point isn't zoomed into
+const FRAME_MIN_RADIUS: f32 = 1.0;
+
+/// Extra room around framed bounds
+const FRAME_PADDING: f32 = 1.2;
+
 /// Editor camera controls
 #[allow(dead_code)] // Intentionally kept for API completeness
 #[derive(Debug, Clone)]
@@ -150,6 +632,8 @@ pub struct EditorCamera {
     pub target: [f32; 3],
     /// Camera up vector
     pub up: [f32; 3],
+    /// World up axis the orbit is built around
+    pub up_axis: UpAxis,
     /// Field of view in degrees
     pub fov: f32,
     /// Near clip plane
@@ -168,6 +652,12 @@ pub struct EditorCamera {
     pub rotate_speed: f32,
     /// Zoom speed
     pub zoom_speed: f32,
+    /// Perspective or orthographic projection
+    pub projection: CameraProjection,
+    /// Axis-aligned view the camera is snapped to, if any
+    pub view_preset: Option<ViewPreset>,
+    /// Free-camera pose to restore when switching back to perspective
+    saved_pose: Option<CameraPose>,
 }
 
 impl Default for EditorCamera {
@@ -175,7 +665,8 @@ impl Default for EditorCamera {
         Self {
             position: [5.0, 5.0, 5.0],
             target: [0.0, 0.0, 0.0],
-            up: [0.0, 1.0, 0.0],
+            up: UpAxis::default().up(),
+            up_axis: UpAxis::default(),
             fov: 60.0,
             near: 0.1,
             far: 10000.0,
@@ -185,6 +676,9 @@ impl Default for EditorCamera {
             move_speed: 10.0,
             rotate_speed: 0.01,
             zoom_speed: 1.0,
+            projection: CameraProjection::Perspective,
+            view_preset: None,
+            saved_pose: None,
         }
     }
 }
@@ -197,6 +691,11 @@ impl EditorCamera {
 
     /// Orbit the camera around the target
     pub fn orbit(&mut self, delta_x: f32, delta_y: f32) {
+        // Orbiting leaves an axis-aligned view but keeps the projection
+        if self.view_preset.take().is_some() {
+            self.up = self.up_axis.up();
+        }
+
         self.yaw += delta_x * self.rotate_speed;
         self.pitch += delta_y * self.rotate_speed;
 
@@ -239,16 +738,109 @@ impl EditorCamera {
         self.update_position();
     }
 
+    /// Switch the world up axis, keeping the same orbit around the target
+    pub fn set_up_axis(&mut self, up_axis: UpAxis) {
+        if self.up_axis == up_axis {
+            return;
+        }
+        self.up_axis = up_axis;
+        self.up = match self.view_preset {
+            Some(preset) => UpAxis::Y.convert(up_axis, preset.screen_up()),
+            None => up_axis.up(),
+        };
+        self.update_position();
+    }
+
+    /// Current free-camera orbit
+    pub fn pose(&self) -> CameraPose {
+        CameraPose {
+            target: self.target,
+            distance: self.distance,
+            yaw: self.yaw,
+            pitch: self.pitch,
+        }
+    }
+
+    /// Snap to an axis-aligned orthographic view. Coming from perspective,
+    /// the free-camera pose is kept for [`EditorCamera::set_projection`].
+    pub fn set_view_preset(&mut self, preset: ViewPreset) {
+        if self.projection == CameraProjection::Perspective {
+            self.saved_pose = Some(self.pose());
+        }
+        self.projection = CameraProjection::Orthographic;
+        self.view_preset = Some(preset);
+        (self.yaw, self.pitch) = preset.orbit_angles();
+        self.up = UpAxis::Y.convert(self.up_axis, preset.screen_up());
+        self.update_position();
+    }
+
+    /// Switch projection. Returning to perspective restores the pose the
+    /// free camera had before it went orthographic.
+    pub fn set_projection(&mut self, projection: CameraProjection) {
+        if self.projection == projection {
+            return;
+        }
+        match projection {
+            CameraProjection::Perspective => {
+                if let Some(pose) = self.saved_pose.take() {
+                    self.target = pose.target;
+                    self.distance = pose.distance;
+                    self.yaw = pose.yaw;
+                    self.pitch = pose.pitch;
+                }
+                self.view_preset = None;
+                self.up = self.up_axis.up();
+            }
+            CameraProjection::Orthographic => self.saved_pose = Some(self.pose()),
+        }
+        self.projection = projection;
+        self.update_position();
+    }
+
+    /// Toggle between perspective and orthographic projection
+    pub fn toggle_projection(&mut self) {
+        self.set_projection(match self.projection {
+            CameraProjection::Perspective => CameraProjection::Orthographic,
+            CameraProjection::Orthographic => CameraProjection::Perspective,
+        });
+    }
+
+    /// Orbit the box `min`..`max` with the whole box in view, plus some
+    /// padding. The result depends only on the box, not on the current
+    /// distance, so framing again changes nothing.
+    pub fn frame_bounds(&mut self, min: [f32; 3], max: [f32; 3], fov_y: f32, aspect: f32) {
+        let center = std::array::from_fn(|i| (min[i] + max[i]) * 0.5);
+        let half_diagonal = (0..3).map(|i| (max[i] - min[i]) * 0.5).map(|h| h * h).sum::<f32>().sqrt();
+        let radius = half_diagonal.max(FRAME_MIN_RADIUS);
+
+        // Fit the bounding sphere in the narrower of the two fields of view
+        let half_fov = ((fov_y * 0.5).tan() * aspect.clamp(0.1, 1.0)).atan();
+        let distance = match self.projection {
+            CameraProjection::Perspective => radius / half_fov.sin(),
+            CameraProjection::Orthographic => radius / half_fov.tan(),
+        };
+        self.focus(center, Some(distance * FRAME_PADDING));
+    }
+
+    /// Half height of the orthographic view volume: what a perspective
+    /// camera with `fov_y` (radians) shows at the target distance
+    pub fn ortho_half_height(&self, fov_y: f32) -> f32 {
+        self.distance * (fov_y * 0.5).tan()
+    }
+
     /// Update camera position from orbit parameters
     fn update_position(&mut self) {
         let x = self.distance * self.pitch.cos() * self.yaw.sin();
         let y = self.distance * self.pitch.sin();
         let z = self.distance * self.pitch.cos() * self.yaw.cos();
 
+        // Orbit parameters are Y-up; map the offset into the world convention
+        let offset = UpAxis::Y.convert(self.up_axis, [x, y, z]);
+
         self.position = [
-            self.target[0] + x,
-            self.target[1] + y,
-            self.target[2] + z,
+            self.target[0] + offset[0],
+            self.target[1] + offset[1],
+            self.target[2] + offset[2],
         ];
     }
 
@@ -261,26 +853,344 @@ impl EditorCamera {
         [dx / len, dy / len, dz / len]
     }
 
-    /// Get the camera right direction
+    /// Get the camera right direction (unit length, matching the renderer's view matrix)
     pub fn get_right(&self) -> [f32; 3] {
         let forward = self.get_forward();
-        // Cross product: up x forward
-        [
-            self.up[1] * forward[2] - self.up[2] * forward[1],
-            self.up[2] * forward[0] - self.up[0] * forward[2],
-            self.up[0] * forward[1] - self.up[1] * forward[0],
-        ]
+        // Cross product: forward x up
+        let right = [
+            forward[1] * self.up[2] - forward[2] * self.up[1],
+            forward[2] * self.up[0] - forward[0] * self.up[2],
+            forward[0] * self.up[1] - forward[1] * self.up[0],
+        ];
+        let len = (right[0] * right[0] + right[1] * right[1] + right[2] * right[2]).sqrt();
+        if len > 0.0 {
+            right.map(|v| v / len)
+        } else {
+            right
+        }
     }
 
     /// Get the camera up direction (orthogonalized)
     pub fn get_up(&self) -> [f32; 3] {
         let forward = self.get_forward();
         let right = self.get_right();
-        // Cross product: forward x right
+        // Cross product: right x forward
         [
-            forward[1] * right[2] - forward[2] * right[1],
-            forward[2] * right[0] - forward[0] * right[2],
-            forward[0] * right[1] - forward[1] * right[0],
+            right[1] * forward[2] - right[2] * forward[1],
+            right[2] * forward[0] - right[0] * forward[2],
+            right[0] * forward[1] - right[1] * forward[0],
         ]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nearest_vertex_on_screen() {
+        let near = EntityId::new();
+        let far = EntityId::new();
+        // Orthographic view down -Z, one point per world unit
+        let project = |p: [f32; 3]| (p[2] <= 0.0).then_some([p[0], p[1]]);
+        let vertices = [
+            (far, [10.0, 10.0, -1.0]),
+            (near, [2.0, 1.0, -1.0]),
+            (near, [1.0, 1.0, 1.0]), // Not visible
+            (far, [4.0, 1.0, -1.0]),
+        ];
+
+        let target = nearest_vertex([1.0, 1.0], vertices, project, 5.0).unwrap();
+        assert_eq!(target.entity, near);
+        assert_eq!(target.position, [2.0, 1.0, -1.0]);
+        assert_eq!(target.screen_distance, 1.0);
+
+        assert_eq!(nearest_vertex([1.0, 1.0], vertices, project, 0.5), None);
+
+        assert_eq!(SnapMode::Grid.toggled(false), SnapMode::Grid);
+        assert_eq!(SnapMode::Grid.toggled(true), SnapMode::Vertex);
+        assert_eq!(SnapMode::Vertex.toggled(true), SnapMode::None);
+    }
+
+    #[test]
+    fn test_selection_pivot_modes() {
+        let origins = [[0.0, 0.0, 0.0], [4.0, 2.0, 0.0], [2.0, 4.0, 0.0]];
+        let bounds = [
+            ([-1.0, -1.0, -1.0], [1.0, 1.0, 1.0]),
+            ([3.0, 1.0, 0.0], [5.0, 3.0, 0.0]),
+            ([2.0, 4.0, 0.0], [2.0, 4.0, 0.0]),
+        ];
+        let active = Some(origins[2]);
+        let pivot = |mode| selection_pivot(mode, &origins, &bounds, active);
+
+        assert_eq!(pivot(PivotMode::MedianPoint), Some([2.0, 2.0, 0.0]));
+        assert_eq!(pivot(PivotMode::IndividualOrigins), Some([2.0, 2.0, 0.0]));
+        assert_eq!(pivot(PivotMode::BoundingBoxCenter), Some([2.0, 1.5, 0.0]));
+        assert_eq!(pivot(PivotMode::ActiveElement), Some([2.0, 4.0, 0.0]));
+        assert_eq!(selection_pivot(PivotMode::MedianPoint, &[], &[], None), None);
+    }
+
+    #[test]
+    fn test_rotate_two_entities_about_median_vs_individual_origins() {
+        let close = |a: [f32; 3], b: [f32; 3]| a.iter().zip(b).all(|(x, y)| (x - y).abs() < 1e-4);
+        let at = |x: f32| Transform {
+            position: [x, 0.0, 0.0],
+            ..Default::default()
+        };
+        let worlds = [at(1.0), at(3.0)];
+        let origins = worlds.each_ref().map(|world| world.position);
+        let rotate = |mode: PivotMode| -> Vec<Transform> {
+            let shared = selection_pivot(mode, &origins, &[], None).unwrap();
+            worlds
+                .iter()
+                .map(|world| rotate_transform_about(world, mode.pivot_for(shared, world.position), [0.0, 1.0, 0.0], 90.0))
+                .collect()
+        };
+
+        // Around the median both swing about x = 2
+        let median = rotate(PivotMode::MedianPoint);
+        assert!(close(median[0].position, [2.0, 0.0, 1.0]), "{:?}", median[0].position);
+        assert!(close(median[1].position, [2.0, 0.0, -1.0]), "{:?}", median[1].position);
+
+        // Around their own origins they turn in place
+        let individual = rotate(PivotMode::IndividualOrigins);
+        assert!(close(individual[0].position, [1.0, 0.0, 0.0]));
+        assert!(close(individual[1].position, [3.0, 0.0, 0.0]));
+
+        // Either way each entity ends up facing the same way
+        for (a, b) in median.iter().zip(&individual) {
+            assert!(close(a.rotation, [0.0, 90.0, 0.0]), "{:?}", a.rotation);
+            assert!(close(a.rotation, b.rotation));
+        }
+
+        // Scaling about individual origins keeps the entities in place
+        let shared = selection_pivot(PivotMode::IndividualOrigins, &origins, &[], None).unwrap();
+        let pivot = PivotMode::IndividualOrigins.pivot_for(shared, origins[0]);
+        assert_eq!(scale_offset_about(origins[0], pivot, None, 2.0), origins[0]);
+        assert!(close(scale_offset_about(origins[0], shared, None, 2.0), [0.0, 0.0, 0.0]));
+    }
+
+    #[test]
+    fn test_rotate_and_scale_about_pivot() {
+        let close = |a: [f32; 3], b: [f32; 3]| a.iter().zip(b).all(|(x, y)| (x - y).abs() < 1e-4);
+        assert!(close(rotate_around_axis([1.0, 0.0, 0.0], [0.0, 1.0, 0.0], 90.0), [0.0, 0.0, -1.0]));
+
+        let world = Transform {
+            position: [3.0, 1.0, 0.0],
+            rotation: [0.0, 10.0, 0.0],
+            scale: [2.0, 2.0, 2.0],
+        };
+        let rotated = rotate_transform_about(&world, [1.0, 0.0, 0.0], [0.0, 1.0, 0.0], 30.0);
+        let (sin, cos) = 30.0_f32.to_radians().sin_cos();
+        assert!(close(rotated.position, [1.0 + 2.0 * cos, 1.0, -2.0 * sin]));
+        assert!(close(rotated.rotation, [0.0, 40.0, 0.0]));
+        assert_eq!(rotated.scale, world.scale);
+
+        let pivot = [1.0, 1.0, 1.0];
+        assert!(close(scale_offset_about([3.0, 2.0, 1.0], pivot, Some([1.0, 0.0, 0.0]), 2.0), [5.0, 2.0, 1.0]));
+        assert!(close(scale_offset_about([3.0, 2.0, 1.0], pivot, None, 0.5), [2.0, 1.5, 1.0]));
+    }
+
+    #[test]
+    fn test_view_presets_restore_perspective_pose() {
+        let close = |a: [f32; 3], b: [f32; 3]| a.iter().zip(b).all(|(x, y)| (x - y).abs() < 1e-4);
+        let mut camera = EditorCamera::new();
+        camera.orbit(40.0, -10.0);
+        let free = camera.pose();
+        let free_position = camera.position;
+
+        camera.set_view_preset(ViewPreset::Top);
+        assert_eq!(camera.projection, CameraProjection::Orthographic);
+        assert!(close(camera.get_forward(), [0.0, -1.0, 0.0]));
+        assert!(close(camera.get_right(), [1.0, 0.0, 0.0]));
+        assert!(close(camera.get_up(), [0.0, 0.0, -1.0]));
+
+        camera.set_view_preset(ViewPreset::Right);
+        assert!(close(camera.get_forward(), [-1.0, 0.0, 0.0]));
+        assert!(close(camera.get_right(), [0.0, 0.0, -1.0]));
+        camera.zoom(1.0);
+
+        camera.set_projection(CameraProjection::Perspective);
+        assert_eq!(camera.pose(), free);
+        assert_eq!(camera.position, free_position);
+        assert_eq!(camera.view_preset, None);
+        assert_eq!(camera.up, [0.0, 1.0, 0.0]);
+    }
+
+    #[test]
+    fn test_frame_bounds_fits_and_is_stable() {
+        let mut camera = EditorCamera::new();
+        camera.orbit(30.0, -20.0);
+        let (min, max) = ([-4.0, 0.0, 2.0], [6.0, 3.0, 8.0]);
+        let fov = std::f32::consts::FRAC_PI_4;
+
+        camera.frame_bounds(min, max, fov, 16.0 / 9.0);
+        let framed = camera.pose();
+        assert_eq!(camera.target, [1.0, 1.5, 5.0]);
+
+        // Every corner is inside the vertical field of view
+        let forward = camera.get_forward();
+        for corner in 0..8 {
+            let point: [f32; 3] = std::array::from_fn(|i| if corner & (1 << i) == 0 { min[i] } else { max[i] });
+            let offset: [f32; 3] = std::array::from_fn(|i| point[i] - camera.position[i]);
+            let length = offset.iter().map(|v| v * v).sum::<f32>().sqrt();
+            let cos = offset.iter().zip(forward).map(|(a, b)| a * b).sum::<f32>() / length;
+            assert!(cos.acos() < fov * 0.5);
+        }
+
+        camera.frame_bounds(min, max, fov, 16.0 / 9.0);
+        assert_eq!(camera.pose(), framed);
+    }
+
+    #[test]
+    fn test_alignment_within_threshold() {
+        let a = EntityId::new();
+        let b = EntityId::new();
+        let candidates = [(a, [5.05, 3.0, 0.04]), (b, [9.0, 0.02, 4.0])];
+
+        let guides = find_alignments([0.0, 0.0, 0.0], candidates, [true; 3], 0.1, 20.0);
+        assert_eq!(guides.len(), 2);
+        assert_eq!(guides[0].axis, 1);
+        assert_eq!(guides[0].target, b);
+        assert!((guides[0].offset - 0.02).abs() < 1e-6);
+        assert_eq!(guides[1].axis, 2);
+        assert_eq!(guides[1].target, a);
+
+        let offset = alignment_snap_offset(&guides);
+        assert_eq!(offset[0], 0.0);
+        assert!((offset[1] - 0.02).abs() < 1e-6);
+
+        // Outside the threshold nothing aligns
+        assert!(find_alignments([0.0, 0.5, 1.0], candidates, [true; 3], 0.1, 20.0).is_empty());
+    }
+
+    #[test]
+    fn test_relationship_lines() {
+        use crate::components::{Component, LookAtComponent};
+        use crate::state::{EntityData, Transform};
+
+        let at = |position: [f32; 3], parent: Option<EntityId>| EntityData {
+            parent,
+            transform: Transform {
+                position,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut scene = SceneData::new();
+        let root = scene.add_entity(at([1.0, 0.0, 0.0], None));
+        let arm = scene.add_entity(at([0.0, 2.0, 0.0], Some(root)));
+        let hand = scene.add_entity(at([0.0, 1.0, 0.0], Some(arm)));
+        let mut camera = at([0.0, 0.0, 5.0], None);
+        camera.components.push(Component::LookAt(LookAtComponent { target: Some(hand) }));
+        let camera = scene.add_entity(camera);
+        let dangling = EntityId::new();
+        scene.add_entity(at([0.0; 3], Some(dangling)));
+
+        let lines = collect_relationship_lines(&scene, None);
+        assert_eq!(lines.len(), 3);
+        assert_eq!(
+            lines[1],
+            RelationshipLine {
+                kind: RelationshipKind::Parent,
+                from: hand,
+                to: arm,
+                start: [1.0, 3.0, 0.0],
+                end: [1.0, 2.0, 0.0],
+            }
+        );
+        assert_eq!(lines[2].kind, RelationshipKind::Reference("target"));
+        assert_eq!((lines[2].from, lines[2].to), (camera, hand));
+
+        // Focusing on the arm keeps its parent and child links only
+        let focused = collect_relationship_lines(&scene, Some(&[arm]));
+        let pairs: Vec<_> = focused.iter().map(|line| (line.from, line.to)).collect();
+        assert_eq!(pairs, vec![(arm, root), (hand, arm)]);
+    }
+
+    fn close(a: [f32; 3], b: [f32; 3]) -> bool {
+        a.iter().zip(b).all(|(x, y)| (x - y).abs() < 1e-4)
+    }
+
+    fn grid(origin: [f32; 3], rotation: f32) -> SnapGrid {
+        SnapGrid {
+            size: 1.0,
+            origin,
+            rotation,
+            up_axis: UpAxis::Y,
+        }
+    }
+
+    #[test]
+    fn test_world_grid_snap() {
+        let grid = grid([0.0; 3], 0.0);
+        assert!(grid.is_world_aligned());
+        assert!(close(grid.snap_along([0.0; 3], [1.4, 0.0, 0.0]), [1.0, 0.0, 0.0]));
+        assert!(close(grid.snap_along([0.0; 3], [0.0, -0.6, 0.0]), [0.0, -1.0, 0.0]));
+        assert!(close(grid.snap_along([0.2, 0.0, 0.0], [0.0, 0.0, 2.6]), [0.2, 0.0, 3.0]));
+    }
+
+    #[test]
+    fn test_offset_grid_snap() {
+        let grid = grid([0.25, 0.0, 0.5], 0.0);
+        assert!(!grid.is_world_aligned());
+        assert!(close(grid.snap_along([0.0; 3], [1.0, 0.0, 0.0]), [1.25, 0.0, 0.0]));
+        assert!(close(grid.snap_along([0.0; 3], [-1.0, 0.0, 0.0]), [-0.75, 0.0, 0.0]));
+        assert!(close(grid.snap_along([0.0; 3], [0.0, 0.0, 2.1]), [0.0, 0.0, 2.5]));
+    }
+
+    #[test]
+    fn test_rotated_grid_snap() {
+        let grid = grid([0.0; 3], 45.0);
+        let diagonal = std::f32::consts::FRAC_1_SQRT_2;
+        // One cell along the grid's X axis lies on the world diagonal
+        let on_axis = [diagonal, 0.0, -diagonal];
+        assert!(close(grid.snap_along([0.0; 3], on_axis.map(|v| v * 0.8)), on_axis));
+        assert!(close(grid.snap_along([0.0; 3], on_axis), on_axis));
+        // Two cells along the grid's Z axis
+        let point = [2.0 * diagonal, 0.0, 2.0 * diagonal];
+        assert!(close(grid.snap_along([0.0; 3], point.map(|v| v * 0.9)), point));
+    }
+
+    #[test]
+    fn test_axis_constraint_masks_deltas() {
+        assert_eq!(AxisConstraint::None.apply([1.0, 2.0, 3.0]), [1.0, 2.0, 3.0]);
+        assert_eq!(AxisConstraint::Y.apply([1.0, 2.0, 3.0]), [0.0, 2.0, 0.0]);
+        assert_eq!(AxisConstraint::XZ.apply([1.0, 2.0, 3.0]), [1.0, 0.0, 3.0]);
+        assert!(AxisConstraint::X.allows(0));
+        assert!(!AxisConstraint::X.allows(2));
+    }
+
+    #[test]
+    fn test_snap_along_keeps_other_axes() {
+        // Off-grid Y and Z stay put while X lands on a grid line
+        let offset = grid([0.25, 0.0, 0.5], 0.0);
+        assert!(close(offset.snap_along([0.0, 0.3, 0.2], [0.9, 0.0, 0.0]), [1.25, 0.3, 0.2]));
+
+        // On a rotated grid the move stays on world X and stops where it
+        // crosses the grid line one cell along the grid's X axis
+        let rotated = grid([0.0; 3], 30.0);
+        let crossing = 1.0 / 30f32.to_radians().cos();
+        assert!(close(rotated.snap_along([0.0; 3], [1.5, 0.0, 0.0]), [crossing, 0.0, 0.0]));
+    }
+
+    #[test]
+    fn test_alignment_respects_axes_and_radius() {
+        let near = EntityId::new();
+        let closer = EntityId::new();
+        let far = EntityId::new();
+        let candidates = [
+            (near, [1.08, 2.0, 0.0]),
+            (closer, [1.03, -2.0, 0.0]),
+            (far, [1.0, 100.0, 0.0]),
+        ];
+
+        // Closest match on the axis wins; the exact but distant one is ignored
+        let guides = find_alignments([1.0, 0.0, 5.0], candidates, [true, false, false], 0.1, 10.0);
+        assert_eq!(guides.len(), 1);
+        assert_eq!(guides[0].target, closer);
+
+        // Disabled axes are never reported
+        assert!(find_alignments([1.0, 0.0, 0.0], candidates, [false, false, false], 0.1, 10.0).is_empty());
+    }
+}
@@ -0,0 +1,426 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//! Background scene validation.
+//!
+//! This module provides:
+//! - Per-entity validation rules (missing assets, conflicting settings)
+//! - A debounced validator that re-runs after edits instead of every frame
+//! - Incremental passes that spread work across frames to keep the UI responsive
+
+use crate::components::Component;
+use crate::state::{EntityData, EntityId, SceneData};
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+/// Delay after the last edit before a validation pass starts
+const VALIDATION_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Maximum number of entities validated per frame
+const ENTITIES_PER_TICK: usize = 256;
+
+/// Severity of a validation issue
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ValidationSeverity {
+    /// Likely mistake, scene still works
+    Warning,
+    /// Scene will not behave as intended
+    #[allow(dead_code)] // Intentionally kept for API completeness
+    Error,
+}
+
+/// Identifies which rule produced an issue
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ValidationRule {
+    /// Mesh renderer without a mesh asset
+    MissingMesh,
+    /// More than one camera marked as main
+    MultipleMainCameras,
+    /// Rigidbody without any collider on the entity or its children
+    RigidbodyWithoutCollider,
+}
+
+/// A single validation issue on an entity
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationIssue {
+    /// Rule that produced the issue
+    #[allow(dead_code)] // Intentionally kept for API completeness
+    pub rule: ValidationRule,
+    /// Issue severity
+    pub severity: ValidationSeverity,
+    /// Human-readable description
+    pub message: String,
+}
+
+impl ValidationIssue {
+    fn warning(rule: ValidationRule, message: impl Into<String>) -> Self {
+        Self {
+            rule,
+            severity: ValidationSeverity::Warning,
+            message: message.into(),
+        }
+    }
+}
+
+fn is_collider(component: &Component) -> bool {
+    matches!(
+        component,
+        Component::BoxCollider(_)
+            | Component::SphereCollider(_)
+            | Component::CapsuleCollider(_)
+            | Component::MeshCollider(_)
+    )
+}
+
+fn is_main_camera(entity: &EntityData) -> bool {
+    entity
+        .components
+        .iter()
+        .any(|c| matches!(c, Component::Camera(camera) if camera.is_main))
+}
+
+/// Run the entity-local rules for one entity.
+///
+/// Scene-wide rules (such as the main camera check) are handled by [`validate_scene`]
+/// and [`SceneValidator`], which see every entity.
+pub fn validate_entity(entity: &EntityData, scene: &SceneData) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+
+    for component in &entity.components {
+        if let Component::MeshRenderer(renderer) = component {
+            if renderer.mesh.trim().is_empty() {
+                issues.push(ValidationIssue::warning(
+                    ValidationRule::MissingMesh,
+                    "Mesh Renderer has no mesh assigned",
+                ));
+            }
+        }
+    }
+
+    let has_rigidbody = entity.components.iter().any(|c| matches!(c, Component::Rigidbody(_)));
+    if has_rigidbody {
+        // Compound colliders on direct children also count
+        let has_collider = entity.components.iter().any(is_collider)
+            || entity.children.iter().any(|child| {
+                scene
+                    .get(child)
+                    .is_some_and(|c| c.components.iter().any(is_collider))
+            });
+        if !has_collider {
+            issues.push(ValidationIssue::warning(
+                ValidationRule::RigidbodyWithoutCollider,
+                "Rigidbody has no collider and will not collide with anything",
+            ));
+        }
+    }
+
+    issues
+}
+
+fn multiple_main_cameras_issue(count: usize) -> ValidationIssue {
+    ValidationIssue::warning(
+        ValidationRule::MultipleMainCameras,
+        format!("{count} cameras are marked as main; only one will be used"),
+    )
+}
+
+/// Validate the whole scene synchronously, returning issues for entities that have any
+#[allow(dead_code)] // Intentionally kept for API completeness
+pub fn validate_scene(scene: &SceneData) -> HashMap<EntityId, Vec<ValidationIssue>> {
+    let mut pass = ValidationPass::new(scene);
+    pass.advance(scene, usize::MAX);
+    pass.finish()
+}
+
+/// In-progress incremental validation pass
+#[derive(Debug, Default)]
+struct ValidationPass {
+    /// Entities still to be validated
+    queue: VecDeque<EntityId>,
+    /// Issues found so far
+    issues: HashMap<EntityId, Vec<ValidationIssue>>,
+    /// Main cameras found so far
+    main_cameras: Vec<EntityId>,
+}
+
+impl ValidationPass {
+    /// Start a pass over every entity of the scene
+    fn new(scene: &SceneData) -> Self {
+        Self {
+            queue: scene.entities.keys().copied().collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Validate up to `budget` queued entities; returns true once the queue is empty
+    fn advance(&mut self, scene: &SceneData, budget: usize) -> bool {
+        for _ in 0..budget {
+            let Some(id) = self.queue.pop_front() else {
+                break;
+            };
+            // Entities deleted mid-pass are simply skipped
+            let Some(entity) = scene.get(&id) else {
+                continue;
+            };
+            let entity_issues = validate_entity(entity, scene);
+            if !entity_issues.is_empty() {
+                self.issues.insert(id, entity_issues);
+            }
+            if is_main_camera(entity) {
+                self.main_cameras.push(id);
+            }
+        }
+        self.queue.is_empty()
+    }
+
+    /// Apply the scene-wide rules and return the issues of the whole pass
+    fn finish(mut self) -> HashMap<EntityId, Vec<ValidationIssue>> {
+        if self.main_cameras.len() > 1 {
+            let count = self.main_cameras.len();
+            for id in &self.main_cameras {
+                self.issues
+                    .entry(*id)
+                    .or_default()
+                    .push(multiple_main_cameras_issue(count));
+            }
+        }
+        self.issues
+    }
+}
+
+/// Debounced, incremental validator for the open scene.
+///
+/// Call [`SceneValidator::update`] once per frame. Passes only start after the scene
+/// has been quiet for a short time, and each frame validates a bounded number of
+/// entities. Results are published when a pass completes, so the hierarchy never
+/// shows a half-validated scene.
+#[derive(Debug)]
+pub struct SceneValidator {
+    /// Published issues from the last completed pass
+    issues: HashMap<EntityId, Vec<ValidationIssue>>,
+    /// Change signal observed on the previous update
+    last_signal: Option<(u64, usize)>,
+    /// Time of the most recent detected edit
+    last_edit: Option<Instant>,
+    /// Pass currently being processed
+    pass: Option<ValidationPass>,
+    /// Whether validation is enabled
+    pub enabled: bool,
+}
+
+impl SceneValidator {
+    /// Create a new validator; the first update schedules a full pass
+    pub fn new() -> Self {
+        Self {
+            issues: HashMap::new(),
+            last_signal: None,
+            last_edit: None,
+            pass: None,
+            enabled: true,
+        }
+    }
+
+    /// Force a revalidation after the debounce delay (for edits that bypass history)
+    pub fn request_revalidation(&mut self) {
+        self.last_edit = Some(Instant::now());
+    }
+
+    /// Advance validation by one frame.
+    ///
+    /// `revision` should change whenever the scene is edited. Nothing runs while
+    /// `paused` is set (e.g. during play mode); results from before are kept.
+    /// Returns true while work is pending so the caller can request a repaint.
+    pub fn update(&mut self, scene: &SceneData, revision: u64, paused: bool) -> bool {
+        if !self.enabled || paused {
+            return false;
+        }
+
+        let signal = (revision, scene.entities.len());
+        if self.last_signal != Some(signal) {
+            self.last_signal = Some(signal);
+            self.last_edit = Some(Instant::now());
+            // Restart any in-flight pass; its results would be stale
+            self.pass = None;
+        }
+
+        if let Some(edited_at) = self.last_edit {
+            if edited_at.elapsed() < VALIDATION_DEBOUNCE {
+                return true;
+            }
+            self.last_edit = None;
+            self.pass = Some(ValidationPass::new(scene));
+        }
+
+        let Some(pass) = self.pass.as_mut() else {
+            return false;
+        };
+        if !pass.advance(scene, ENTITIES_PER_TICK) {
+            return true;
+        }
+
+        if let Some(pass) = self.pass.take() {
+            self.issues = pass.finish();
+        }
+        false
+    }
+
+    /// Issues for an entity from the last completed pass
+    pub fn issues_for(&self, entity_id: &EntityId) -> &[ValidationIssue] {
+        self.issues.get(entity_id).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Total number of issues in the scene
+    #[allow(dead_code)] // Intentionally kept for API completeness
+    pub fn issue_count(&self) -> usize {
+        self.issues.values().map(Vec::len).sum()
+    }
+
+    /// Clear all results (e.g. when a new scene is loaded)
+    pub fn clear(&mut self) {
+        self.issues.clear();
+        self.pass = None;
+        self.last_signal = None;
+        self.last_edit = None;
+    }
+}
+
+impl Default for SceneValidator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::{
+        BoxColliderComponent, CameraComponent, MeshRendererComponent, RigidbodyComponent,
+    };
+
+    fn camera(is_main: bool) -> Component {
+        Component::Camera(CameraComponent {
+            is_main,
+            ..Default::default()
+        })
+    }
+
+    fn has_rule(issues: &HashMap<EntityId, Vec<ValidationIssue>>, id: EntityId, rule: ValidationRule) -> bool {
+        issues.get(&id).is_some_and(|list| list.iter().any(|i| i.rule == rule))
+    }
+
+    #[test]
+    fn test_multiple_main_cameras() {
+        let mut scene = SceneData::new();
+        let mut main_a = EntityData::new("Main A");
+        main_a.components.push(camera(true));
+        let mut main_b = EntityData::new("Main B");
+        main_b.components.push(camera(true));
+        let mut secondary = EntityData::new("Secondary");
+        secondary.components.push(camera(false));
+
+        let a = scene.add_entity(main_a);
+        let b = scene.add_entity(main_b);
+        let c = scene.add_entity(secondary);
+
+        let issues = validate_scene(&scene);
+        assert!(has_rule(&issues, a, ValidationRule::MultipleMainCameras));
+        assert!(has_rule(&issues, b, ValidationRule::MultipleMainCameras));
+        assert!(!has_rule(&issues, c, ValidationRule::MultipleMainCameras));
+
+        // A single main camera is fine
+        scene.remove(&b);
+        let issues = validate_scene(&scene);
+        assert!(!has_rule(&issues, a, ValidationRule::MultipleMainCameras));
+    }
+
+    #[test]
+    fn test_rigidbody_without_collider() {
+        let mut scene = SceneData::new();
+
+        let mut bare = EntityData::new("Bare Body");
+        bare.components.push(Component::Rigidbody(RigidbodyComponent::default()));
+        let bare = scene.add_entity(bare);
+
+        let mut boxed = EntityData::new("Boxed Body");
+        boxed.components.push(Component::Rigidbody(RigidbodyComponent::default()));
+        boxed.components.push(Component::BoxCollider(BoxColliderComponent::default()));
+        let boxed = scene.add_entity(boxed);
+
+        // Collider on a child counts as a compound collider
+        let mut compound = EntityData::new("Compound Body");
+        compound.components.push(Component::Rigidbody(RigidbodyComponent::default()));
+        let compound = scene.add_entity(compound);
+        let mut child = EntityData::new("Child Collider");
+        child.parent = Some(compound);
+        child.components.push(Component::BoxCollider(BoxColliderComponent::default()));
+        let child = scene.add_entity(child);
+        if let Some(parent) = scene.get_mut(&compound) {
+            parent.children.push(child);
+        }
+
+        let issues = validate_scene(&scene);
+        assert!(has_rule(&issues, bare, ValidationRule::RigidbodyWithoutCollider));
+        assert!(!has_rule(&issues, boxed, ValidationRule::RigidbodyWithoutCollider));
+        assert!(!has_rule(&issues, compound, ValidationRule::RigidbodyWithoutCollider));
+    }
+
+    #[test]
+    fn test_missing_mesh() {
+        let mut scene = SceneData::new();
+        let mut entity = EntityData::new("Renderer");
+        entity.components.push(Component::MeshRenderer(MeshRendererComponent::default()));
+        let id = scene.add_entity(entity);
+
+        let issues = validate_scene(&scene);
+        assert!(has_rule(&issues, id, ValidationRule::MissingMesh));
+    }
+
+    #[test]
+    fn test_validator_paused_does_nothing() {
+        let mut scene = SceneData::new();
+        let mut body = EntityData::new("Body");
+        body.components.push(Component::Rigidbody(RigidbodyComponent::default()));
+        let id = scene.add_entity(body);
+
+        let mut validator = SceneValidator::new();
+        assert!(!validator.update(&scene, 0, true));
+        assert!(validator.issues_for(&id).is_empty());
+
+        // Not paused: the edit is debounced, so nothing is published yet
+        assert!(validator.update(&scene, 0, false));
+        assert!(validator.issues_for(&id).is_empty());
+    }
+
+    #[test]
+    fn test_incremental_pass_matches_full_validation() {
+        let mut scene = SceneData::new();
+        for i in 0..ENTITIES_PER_TICK {
+            scene.add_entity(EntityData::new(format!("Filler {i}")));
+        }
+        let mut main_a = EntityData::new("Main A");
+        main_a.components.push(camera(true));
+        let a = scene.add_entity(main_a);
+        let mut main_b = EntityData::new("Main B");
+        main_b.components.push(camera(true));
+        let b = scene.add_entity(main_b);
+        let mut body = EntityData::new("Body");
+        body.components.push(Component::Rigidbody(RigidbodyComponent::default()));
+        let body = scene.add_entity(body);
+
+        let mut validator = SceneValidator::new();
+        assert!(validator.update(&scene, 1, false));
+        // Skip the debounce delay
+        validator.last_edit = Instant::now().checked_sub(VALIDATION_DEBOUNCE);
+
+        // The first tick only covers the fillers; nothing is published mid-pass
+        assert!(validator.update(&scene, 1, false));
+        assert!(validator.issues_for(&body).is_empty());
+
+        assert!(!validator.update(&scene, 1, false));
+        let expected = validate_scene(&scene);
+        assert_eq!(validator.issue_count(), expected.values().map(Vec::len).sum::<usize>());
+        for id in [a, b, body] {
+            assert_eq!(validator.issues_for(&id), expected[&id].as_slice());
+        }
+        assert!(has_rule(&expected, a, ValidationRule::MultipleMainCameras));
+        assert!(has_rule(&expected, body, ValidationRule::RigidbodyWithoutCollider));
+    }
+}
@@ -1,7 +1,14 @@
-// Viewport shader for grid and axis rendering
+// Viewport shader for grid and axis rendering, plus the entity ID pass
+// used for picking
 
 struct CameraUniform {
     view_proj: mat4x4<f32>,
+    camera_position: vec4<f32>,
+    fog_color: vec4<f32>,
+    // x: start, y: end, z: enabled
+    fog_params: vec4<f32>,
+    // x: grid fade start, y: grid fade end
+    grid_fade: vec4<f32>,
 };
 
 @group(0) @binding(0)
@@ -15,17 +22,53 @@ struct VertexInput {
 struct VertexOutput {
     @builtin(position) clip_position: vec4<f32>,
     @location(0) color: vec3<f32>,
+    @location(1) world_position: vec3<f32>,
 };
 
+// Must match `state::fog_factor`
+fn fog_factor(distance: f32, start: f32, end: f32) -> f32 {
+    if (distance <= start) {
+        return 0.0;
+    }
+    if (end <= start || distance >= end) {
+        return 1.0;
+    }
+    return (distance - start) / (end - start);
+}
+
 @vertex
 fn vs_main(in: VertexInput) -> VertexOutput {
     var out: VertexOutput;
     out.clip_position = camera.view_proj * vec4<f32>(in.position, 1.0);
     out.color = in.color;
+    out.world_position = in.position;
     return out;
 }
 
+fn apply_fog(color: vec3<f32>, distance: f32) -> vec3<f32> {
+    if (camera.fog_params.z > 0.5) {
+        let fog = fog_factor(distance, camera.fog_params.x, camera.fog_params.y);
+        return mix(color, camera.fog_color.rgb, fog);
+    }
+    return color;
+}
+
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let distance = length(in.world_position - camera.camera_position.xyz);
+    return vec4<f32>(apply_fog(in.color, distance), 1.0);
+}
+
+// Ground grid: lines fade out with distance so the far grid doesn't shimmer
+@fragment
+fn fs_grid(in: VertexOutput) -> @location(0) vec4<f32> {
+    let distance = length(in.world_position - camera.camera_position.xyz);
+    let fade = 1.0 - smoothstep(camera.grid_fade.x, camera.grid_fade.y, distance);
+    return vec4<f32>(apply_fog(in.color, distance), fade);
+}
+
+// Entity ID pass: the vertex color carries the encoded entity index
+@fragment
+fn fs_pick(in: VertexOutput) -> @location(0) vec4<f32> {
     return vec4<f32>(in.color, 1.0);
 }
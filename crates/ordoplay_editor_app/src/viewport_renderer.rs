@@ -3,8 +3,39 @@
 //!
 //! This module provides off-screen rendering for the viewport panel,
 //! which can later be replaced with `ordoplay_render` when available.
+//!
+//! The ground grid is spaced by the snap size and rebuilt around the camera
+//! target as the camera moves, so it reads as endless. Its reach scales
+//! with the camera distance, up to the far plane. It is drawn after the
+//! scene without writing depth and fades out with distance, which hides
+//! the grid edge and the shimmer of dense far lines.
+//!
+//! Clicks are resolved with an entity ID pass: every active entity is drawn
+//! as a proxy cube whose color encodes its index, and the pixel under the
+//! cursor is read back. Index 0 is the cleared background.
+//!
+//! Entities with a mesh are drawn as placeholder cubes, the same shape as
+//! their picking proxies, since mesh assets aren't loaded here. The
+//! [`RenderMode`] picks flat-shaded faces, edge lines, or both.
+//!
+//! Physics debug lines (colliders, velocities, contacts) are passed in
+//! per frame and drawn with the entity edges.
+//!
+//! Captures render the same view into a separate target of any size, so a
+//! still can be larger than the window showing the viewport.
+//!
+//! The shader is compiled in. A `Shaders/viewport.wgsl` in the project, or
+//! a `shaders/viewport.wgsl` next to the executable, replaces it and is
+//! watched and recompiled on change. A shader that fails to compile is
+//! reported and the previous pipelines stay.
 
+use crate::components::Component;
+use crate::physics::DebugLine;
+use crate::project::UpAxis;
+use crate::state::{EntityId, EnvironmentSettings, SceneData};
+use crate::tools::CameraProjection;
 use egui_wgpu::wgpu;
+use std::path::{Path, PathBuf};
 
 /// Simple vertex for 3D rendering
 #[repr(C)]
@@ -31,6 +62,366 @@ impl Vertex {
 #[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
 struct CameraUniform {
     view_proj: [[f32; 4]; 4],
+    /// Camera world position (w unused)
+    camera_position: [f32; 4],
+    /// Fog color (w unused)
+    fog_color: [f32; 4],
+    /// Fog start, fog end, enabled flag, unused
+    fog_params: [f32; 4],
+    /// Grid fade start and end distance from the camera (zw unused)
+    grid_fade: [f32; 4],
+}
+
+/// Grid reach in multiples of the camera's distance from its target
+const GRID_REACH_PER_DISTANCE: f32 = 20.0;
+
+/// Most grid lines drawn on either side of the grid center
+const MAX_GRID_HALF_LINES: i64 = 1024;
+
+/// Every this many grid lines is a brighter major line
+const GRID_MAJOR_EVERY: i64 = 10;
+
+/// Smallest grid spacing, so a zero snap size can't collapse the grid
+const MIN_GRID_SPACING: f32 = 0.01;
+
+/// Grid line colors
+const GRID_MINOR_COLOR: [f32; 3] = [0.3, 0.3, 0.3];
+const GRID_MAJOR_COLOR: [f32; 3] = [0.45, 0.45, 0.45];
+
+/// Colors of the X, Y and Z axes
+const AXIS_COLORS: [[f32; 3]; 3] = [[1.0, 0.2, 0.2], [0.2, 1.0, 0.2], [0.2, 0.2, 1.0]];
+
+/// Placement of the ground grid. The grid is rebuilt when this changes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct GridLayout {
+    /// World up axis; the grid lies on the ground plane
+    up_axis: UpAxis,
+    /// Distance between adjacent lines
+    spacing: f32,
+    /// Grid center on the two ground axes, in major cells
+    center: [i64; 2],
+    /// Lines drawn on either side of the center
+    half_lines: i64,
+}
+
+impl GridLayout {
+    /// Grid spaced by `spacing` around the major cell nearest `focus`,
+    /// reaching [`grid_reach`] from it. The line count is rounded up to a
+    /// power of two so zooming only rebuilds the grid now and then.
+    fn new(up_axis: UpAxis, spacing: f32, focus: [f32; 3], camera_distance: f32, far: f32) -> Self {
+        let spacing = spacing.max(MIN_GRID_SPACING);
+        let major = spacing * GRID_MAJOR_EVERY as f32;
+        let center = up_axis.ground_axes().map(|axis| (focus[axis] / major).round() as i64);
+        // At least one major cell beyond the center cell
+        let lines = (grid_reach(camera_distance, far) / spacing).ceil() as i64 + GRID_MAJOR_EVERY;
+        let half_lines = (lines.max(1) as u64).next_power_of_two().min(MAX_GRID_HALF_LINES as u64) as i64;
+        Self { up_axis, spacing, center, half_lines }
+    }
+
+    /// Distance from the grid center to its edge
+    fn extent(&self) -> f32 {
+        self.half_lines as f32 * self.spacing
+    }
+}
+
+/// How far the grid should reach from a camera `camera_distance` from its
+/// target, never past the far plane
+fn grid_reach(camera_distance: f32, far: f32) -> f32 {
+    (camera_distance * GRID_REACH_PER_DISTANCE).min(far)
+}
+
+/// Line list for the grid. The lines through the origin are drawn in the
+/// color of the axis they run along.
+fn grid_vertices(layout: &GridLayout) -> Vec<Vertex> {
+    let axes = layout.up_axis.ground_axes();
+    let extent = layout.extent();
+    let mut vertices = Vec::new();
+
+    // `k` is the ground axis the line crosses; it runs along the other one
+    for k in 0..2 {
+        let along = axes[1 - k];
+        let along_center = (layout.center[1 - k] * GRID_MAJOR_EVERY) as f32 * layout.spacing;
+        for i in -layout.half_lines..=layout.half_lines {
+            let line = layout.center[k] * GRID_MAJOR_EVERY + i;
+            let color = if line == 0 {
+                AXIS_COLORS[along]
+            } else if line % GRID_MAJOR_EVERY == 0 {
+                GRID_MAJOR_COLOR
+            } else {
+                GRID_MINOR_COLOR
+            };
+
+            for end in [-extent, extent] {
+                let mut position = [0.0; 3];
+                position[axes[k]] = line as f32 * layout.spacing;
+                position[along] = along_center + end;
+                vertices.push(Vertex { position, color });
+            }
+        }
+    }
+    vertices
+}
+
+/// Format of the viewport color target and captures
+const COLOR_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8UnormSrgb;
+
+/// Camera inputs from the last [`ViewportRenderer::update_camera`], kept
+/// so captures can re-project the same view at another aspect ratio
+#[derive(Debug, Clone, Copy)]
+struct CameraView {
+    position: [f32; 3],
+    target: [f32; 3],
+    up: [f32; 3],
+    aspect: f32,
+    fov: f32,
+    near: f32,
+    far: f32,
+    projection: CameraProjection,
+}
+
+/// Bytes per texture row in a buffer copy, padded to the copy alignment
+fn padded_bytes_per_row(width: u32) -> u32 {
+    let unpadded = width * 4;
+    let align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+    unpadded.div_ceil(align) * align
+}
+
+/// Largest capture size up to `size` whose sides fit `max_dimension` and
+/// whose padded readback fits in `max_buffer_size` bytes. Oversized
+/// captures are scaled down, keeping their aspect ratio as far as whole
+/// pixels allow.
+fn capture_size(size: [u32; 2], max_dimension: u32, max_buffer_size: u64) -> [u32; 2] {
+    let [mut width, mut height] = size.map(|v| v.clamp(1, max_dimension.max(1)));
+    let bytes = |width: u32, height: u32| u64::from(padded_bytes_per_row(width)) * u64::from(height);
+
+    let needed = bytes(width, height);
+    if needed > max_buffer_size {
+        let scale = (max_buffer_size as f64 / needed as f64).sqrt();
+        width = ((f64::from(width) * scale) as u32).max(1);
+        height = ((f64::from(height) * scale) as u32).max(1);
+        // Row padding can still leave it a few rows over
+        height = height.min((max_buffer_size / u64::from(padded_bytes_per_row(width))).max(1) as u32);
+    }
+    [width, height]
+}
+
+/// Strip the row padding of a texture copy, leaving tightly packed RGBA rows
+fn unpad_rows(data: &[u8], width: u32, height: u32) -> Vec<u8> {
+    let padded = padded_bytes_per_row(width) as usize;
+    let row = width as usize * 4;
+    data.chunks(padded).take(height as usize).flat_map(|chunk| &chunk[..row]).copied().collect()
+}
+
+/// Format of the entity ID texture. Not sRGB, so encoded bytes read back unchanged.
+const PICK_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8Unorm;
+
+/// Half extent of the cube drawn for each entity in the ID pass
+const PICK_PROXY_HALF_EXTENT: f32 = 0.5;
+
+/// Encode an entity index as a vertex color. Index `n` is stored as `n + 1`
+/// over the red, green and blue bytes.
+fn encode_pick_id(index: usize) -> [f32; 3] {
+    let id = index as u32 + 1;
+    [id & 0xff, (id >> 8) & 0xff, (id >> 16) & 0xff].map(|byte| byte as f32 / 255.0)
+}
+
+/// Decode a pixel of the ID texture back to an entity index
+fn decode_pick_id(pixel: [u8; 4]) -> Option<usize> {
+    let [r, g, b, a] = pixel;
+    let id = u32::from(r) | (u32::from(g) << 8) | (u32::from(b) << 16);
+    if a == 0 || id == 0 {
+        return None;
+    }
+    Some(id as usize - 1)
+}
+
+/// Cube faces as corner signs, each split into two triangles
+const CUBE_FACES: [[[f32; 3]; 4]; 6] = [
+    [[1.0, -1.0, -1.0], [1.0, 1.0, -1.0], [1.0, 1.0, 1.0], [1.0, -1.0, 1.0]],
+    [[-1.0, -1.0, 1.0], [-1.0, 1.0, 1.0], [-1.0, 1.0, -1.0], [-1.0, -1.0, -1.0]],
+    [[-1.0, 1.0, -1.0], [-1.0, 1.0, 1.0], [1.0, 1.0, 1.0], [1.0, 1.0, -1.0]],
+    [[-1.0, -1.0, 1.0], [-1.0, -1.0, -1.0], [1.0, -1.0, -1.0], [1.0, -1.0, 1.0]],
+    [[-1.0, -1.0, 1.0], [1.0, -1.0, 1.0], [1.0, 1.0, 1.0], [-1.0, 1.0, 1.0]],
+    [[1.0, -1.0, -1.0], [-1.0, -1.0, -1.0], [-1.0, 1.0, -1.0], [1.0, 1.0, -1.0]],
+];
+
+/// Corner order of the two triangles of a cube face
+const FACE_TRIANGLES: [usize; 6] = [0, 1, 2, 0, 2, 3];
+
+/// How entity geometry is drawn in the viewport
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RenderMode {
+    /// Flat-shaded faces
+    #[default]
+    Shaded,
+    /// Edges only
+    Wireframe,
+    /// Shaded faces with their edges on top
+    ShadedWireframe,
+}
+
+impl RenderMode {
+    /// All render modes, in menu order
+    pub const ALL: [RenderMode; 3] = [Self::Shaded, Self::Wireframe, Self::ShadedWireframe];
+
+    /// Get display name
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Shaded => "Shaded",
+            Self::Wireframe => "Wireframe",
+            Self::ShadedWireframe => "Shaded Wireframe",
+        }
+    }
+
+    fn shows_faces(&self) -> bool {
+        matches!(self, Self::Shaded | Self::ShadedWireframe)
+    }
+
+    fn shows_edges(&self) -> bool {
+        matches!(self, Self::Wireframe | Self::ShadedWireframe)
+    }
+
+    /// Edge color: dark over faces, light on its own
+    fn edge_color(&self) -> [f32; 3] {
+        if self.shows_faces() {
+            [0.08, 0.08, 0.08]
+        } else {
+            [0.85, 0.85, 0.85]
+        }
+    }
+}
+
+/// Base color of shaded placeholder meshes
+const MESH_COLOR: [f32; 3] = [0.7, 0.7, 0.72];
+
+/// Direction towards the viewport's fixed key light
+const LIGHT_DIRECTION: [f32; 3] = [0.32, 0.86, 0.4];
+
+/// Whether the entity draws as a placeholder mesh
+fn has_mesh(scene: &SceneData, id: EntityId) -> bool {
+    scene.get(&id).is_some_and(|data| {
+        data.components.iter().any(|c| matches!(c, Component::MeshRenderer(r) if !r.mesh.is_empty()))
+    })
+}
+
+/// World-space cube corners of an entity, indexed like [`CUBE_FACES`]
+fn cube_face_corners(scene: &SceneData, id: EntityId) -> Option<[[[f32; 3]; 4]; 6]> {
+    let mut faces = [[[0.0; 3]; 4]; 6];
+    for (face, signs) in faces.iter_mut().zip(&CUBE_FACES) {
+        for (corner, sign) in face.iter_mut().zip(signs) {
+            *corner = scene.world_point(id, sign.map(|s| s * PICK_PROXY_HALF_EXTENT))?;
+        }
+    }
+    Some(faces)
+}
+
+/// Corners of the placeholder cube drawn for mesh entities, in entity space
+pub fn placeholder_corners() -> [[f32; 3]; 8] {
+    std::array::from_fn(|corner| {
+        std::array::from_fn(|axis| {
+            if corner & (1 << axis) == 0 {
+                -PICK_PROXY_HALF_EXTENT
+            } else {
+                PICK_PROXY_HALF_EXTENT
+            }
+        })
+    })
+}
+
+/// Placeholder geometry of active mesh entities: shaded triangles and
+/// edge lines, as needed by `mode`
+fn scene_geometry(scene: &SceneData, mode: RenderMode) -> (Vec<Vertex>, Vec<Vertex>) {
+    let mut triangles = Vec::new();
+    let mut lines = Vec::new();
+    let edge_color = mode.edge_color();
+
+    for id in scene.entities.keys() {
+        if !scene.is_active_in_hierarchy(*id) || !has_mesh(scene, *id) {
+            continue;
+        }
+        let Some(faces) = cube_face_corners(scene, *id) else {
+            continue;
+        };
+
+        for face in &faces {
+            if mode.shows_faces() {
+                let color = face_shade(face);
+                triangles.extend(FACE_TRIANGLES.map(|corner| Vertex { position: face[corner], color }));
+            }
+            if mode.shows_edges() {
+                // Each face outlines its own border; shared edges overlap exactly
+                for i in 0..4 {
+                    lines.push(Vertex { position: face[i], color: edge_color });
+                    lines.push(Vertex { position: face[(i + 1) % 4], color: edge_color });
+                }
+            }
+        }
+    }
+    (triangles, lines)
+}
+
+/// Line-list vertices for physics debug lines. The line pipeline is
+/// opaque, so the alpha of each color is dropped.
+fn debug_line_vertices(lines: &[DebugLine]) -> Vec<Vertex> {
+    lines
+        .iter()
+        .flat_map(|line| {
+            let color = [line.color[0], line.color[1], line.color[2]];
+            [Vertex { position: line.start, color }, Vertex { position: line.end, color }]
+        })
+        .collect()
+}
+
+/// Flat shading for a face from its world-space normal
+fn face_shade(face: &[[f32; 3]; 4]) -> [f32; 3] {
+    let a: [f32; 3] = std::array::from_fn(|i| face[1][i] - face[0][i]);
+    let b: [f32; 3] = std::array::from_fn(|i| face[2][i] - face[0][i]);
+    let normal = ViewportRenderer::normalize(ViewportRenderer::cross(a, b));
+    let light = ViewportRenderer::normalize(LIGHT_DIRECTION);
+    let diffuse = ViewportRenderer::dot(normal, light).max(0.0);
+    MESH_COLOR.map(|c| c * (0.35 + 0.65 * diffuse))
+}
+
+/// Proxy cubes for every active entity, with the entity each index refers to
+fn pick_proxies(scene: &SceneData) -> (Vec<Vertex>, Vec<EntityId>) {
+    let mut vertices = Vec::new();
+    let mut entities = Vec::new();
+    for id in scene.entities.keys() {
+        if !scene.is_active_in_hierarchy(*id) {
+            continue;
+        }
+
+        let color = encode_pick_id(entities.len());
+        entities.push(*id);
+        for face in &CUBE_FACES {
+            for corner in FACE_TRIANGLES {
+                let local = face[corner].map(|sign| sign * PICK_PROXY_HALF_EXTENT);
+                if let Some(position) = scene.world_point(*id, local) {
+                    vertices.push(Vertex { position, color });
+                }
+            }
+        }
+    }
+    (vertices, entities)
+}
+
+/// Built-in viewport shader, used when no shader file is found on disk
+const SHADER_SOURCE: &str = include_str!("viewport.wgsl");
+
+/// Viewport shader override, relative to the project's asset root. Only
+/// debug builds look for it, so opening a project in a release editor
+/// never swaps out the viewport shader.
+const PROJECT_SHADER_PATH: &str = "Shaders/viewport.wgsl";
+
+/// Viewport shader shipped next to the editor executable
+const SHIPPED_SHADER_PATH: &str = "shaders/viewport.wgsl";
+
+/// Pipelines built from the viewport shader
+struct ViewportPipelines {
+    lines: wgpu::RenderPipeline,
+    grid: wgpu::RenderPipeline,
+    mesh: wgpu::RenderPipeline,
+    pick: wgpu::RenderPipeline,
 }
 
 /// Viewport renderer that renders a 3D scene to a texture
@@ -45,12 +436,28 @@ pub struct ViewportRenderer {
     depth_view: wgpu::TextureView,
     /// Current size
     size: [u32; 2],
+    /// Layout shared by the viewport pipelines
+    pipeline_layout: wgpu::PipelineLayout,
     /// Render pipeline
     pipeline: wgpu::RenderPipeline,
+    /// Pipeline for the ground grid: blended, fading, no depth writes
+    grid_pipeline: wgpu::RenderPipeline,
+    /// Pipeline for shaded entity faces
+    mesh_pipeline: wgpu::RenderPipeline,
+    /// Entity ID texture for picking
+    pick_texture: wgpu::Texture,
+    /// View for the entity ID texture
+    pick_view: wgpu::TextureView,
+    /// Pipeline drawing entity proxies into the ID texture
+    pick_pipeline: wgpu::RenderPipeline,
+    /// Buffer the picked pixel is copied into for readback
+    pick_buffer: wgpu::Buffer,
     /// Grid vertices
     grid_vertex_buffer: wgpu::Buffer,
     /// Grid vertex count
     grid_vertex_count: u32,
+    /// Layout the grid was built for
+    grid_layout: GridLayout,
     /// Axis vertices (XYZ gizmo at origin)
     axis_vertex_buffer: wgpu::Buffer,
     /// Axis vertex count
@@ -61,6 +468,18 @@ pub struct ViewportRenderer {
     camera_bind_group: wgpu::BindGroup,
     /// egui texture ID for the render result
     egui_texture_id: Option<egui::TextureId>,
+    /// Scene ambient and fog settings
+    environment: EnvironmentSettings,
+    /// Camera of the last frame
+    camera_view: Option<CameraView>,
+    /// Whether the last frame drew the grid
+    show_grid: bool,
+    /// Shaded entity triangles of the last frame
+    mesh_vertex_buffer: Option<(wgpu::Buffer, u32)>,
+    /// Entity edge lines of the last frame
+    edge_vertex_buffer: Option<(wgpu::Buffer, u32)>,
+    /// Physics debug lines of the last frame
+    debug_vertex_buffer: Option<(wgpu::Buffer, u32)>,
 }
 
 impl ViewportRenderer {
@@ -71,16 +490,21 @@ impl ViewportRenderer {
         // Create render target texture
         let (render_texture, render_view) = Self::create_render_texture(device, size);
         let (depth_texture, depth_view) = Self::create_depth_texture(device, size);
+        let (pick_texture, pick_view) = Self::create_pick_texture(device, size);
 
         // Create shader
         let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
             label: Some("Viewport Shader"),
-            source: wgpu::ShaderSource::Wgsl(include_str!("viewport.wgsl").into()),
+            source: wgpu::ShaderSource::Wgsl(SHADER_SOURCE.into()),
         });
 
         // Camera uniform buffer
         let camera_uniform = CameraUniform {
             view_proj: Self::identity_matrix(),
+            camera_position: [0.0; 4],
+            fog_color: [0.0; 4],
+            fog_params: [0.0; 4],
+            grid_fade: [0.0; 4],
         };
         let camera_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: Some("Camera Buffer"),
@@ -94,7 +518,7 @@ impl ViewportRenderer {
                 label: Some("Camera Bind Group Layout"),
                 entries: &[wgpu::BindGroupLayoutEntry {
                     binding: 0,
-                    visibility: wgpu::ShaderStages::VERTEX,
+                    visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
                     ty: wgpu::BindingType::Buffer {
                         ty: wgpu::BufferBindingType::Uniform,
                         has_dynamic_offset: false,
@@ -120,21 +544,85 @@ impl ViewportRenderer {
             push_constant_ranges: &[],
         });
 
-        // Render pipeline
-        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        let ViewportPipelines {
+            lines: pipeline,
+            grid: grid_pipeline,
+            mesh: mesh_pipeline,
+            pick: pick_pipeline,
+        } = Self::create_pipelines(device, &pipeline_layout, &shader);
+
+        // One row of the ID texture, padded to the copy alignment
+        let pick_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Viewport Pick Buffer"),
+            size: u64::from(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT),
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        // Create grid vertices
+        let grid_layout = GridLayout::new(UpAxis::default(), 1.0, [0.0; 3], 10.0, 1000.0);
+        let (grid_vertex_buffer, grid_vertex_count) = Self::create_grid_buffer(device, &grid_layout);
+
+        // Create axis vertices
+        let (axis_vertices, axis_vertex_count) = Self::create_axis_vertices();
+        let axis_vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Axis Vertex Buffer"),
+            contents: bytemuck::cast_slice(&axis_vertices),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+
+        Self {
+            render_texture,
+            render_view,
+            depth_texture,
+            depth_view,
+            size,
+            pipeline_layout,
+            pipeline,
+            grid_pipeline,
+            mesh_pipeline,
+            pick_texture,
+            pick_view,
+            pick_pipeline,
+            pick_buffer,
+            grid_vertex_buffer,
+            grid_vertex_count,
+            grid_layout,
+            axis_vertex_buffer,
+            axis_vertex_count,
+            camera_buffer,
+            camera_bind_group,
+            egui_texture_id: None,
+            environment: EnvironmentSettings::default(),
+            camera_view: None,
+            show_grid: true,
+            mesh_vertex_buffer: None,
+            edge_vertex_buffer: None,
+            debug_vertex_buffer: None,
+        }
+    }
+
+    /// Build the viewport pipelines from a compiled viewport shader
+    fn create_pipelines(
+        device: &wgpu::Device,
+        layout: &wgpu::PipelineLayout,
+        shader: &wgpu::ShaderModule,
+    ) -> ViewportPipelines {
+        // Scene lines: gizmo axes, entity edges and debug lines
+        let lines = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
             label: Some("Viewport Pipeline"),
-            layout: Some(&pipeline_layout),
+            layout: Some(layout),
             vertex: wgpu::VertexState {
-                module: &shader,
+                module: shader,
                 entry_point: Some("vs_main"),
                 buffers: &[Vertex::desc()],
                 compilation_options: wgpu::PipelineCompilationOptions::default(),
             },
             fragment: Some(wgpu::FragmentState {
-                module: &shader,
+                module: shader,
                 entry_point: Some("fs_main"),
                 targets: &[Some(wgpu::ColorTargetState {
-                    format: wgpu::TextureFormat::Rgba8UnormSrgb,
+                    format: COLOR_FORMAT,
                     blend: Some(wgpu::BlendState::REPLACE),
                     write_mask: wgpu::ColorWrites::ALL,
                 })],
@@ -161,42 +649,214 @@ impl ViewportRenderer {
             cache: None,
         });
 
-        // Create grid vertices
-        let (grid_vertices, grid_vertex_count) = Self::create_grid_vertices();
-        let grid_vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Grid Vertex Buffer"),
-            contents: bytemuck::cast_slice(&grid_vertices),
-            usage: wgpu::BufferUsages::VERTEX,
+        // Grid pipeline: lines blend in as they fade, and are tested against
+        // the scene's depth without occluding anything themselves
+        let grid = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Viewport Grid Pipeline"),
+            layout: Some(layout),
+            vertex: wgpu::VertexState {
+                module: shader,
+                entry_point: Some("vs_main"),
+                buffers: &[Vertex::desc()],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: shader,
+                entry_point: Some("fs_grid"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: COLOR_FORMAT,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::LineList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: None,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: wgpu::TextureFormat::Depth32Float,
+                depth_write_enabled: false,
+                depth_compare: wgpu::CompareFunction::Less,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
         });
 
-        // Create axis vertices
-        let (axis_vertices, axis_vertex_count) = Self::create_axis_vertices();
-        let axis_vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Axis Vertex Buffer"),
-            contents: bytemuck::cast_slice(&axis_vertices),
-            usage: wgpu::BufferUsages::VERTEX,
+        // Shaded faces are pushed back a little so edges drawn over them
+        // with the line pipeline win the depth test
+        let mesh = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Viewport Mesh Pipeline"),
+            layout: Some(layout),
+            vertex: wgpu::VertexState {
+                module: shader,
+                entry_point: Some("vs_main"),
+                buffers: &[Vertex::desc()],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: COLOR_FORMAT,
+                    blend: Some(wgpu::BlendState::REPLACE),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: None,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: wgpu::TextureFormat::Depth32Float,
+                depth_write_enabled: true,
+                depth_compare: wgpu::CompareFunction::Less,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState {
+                    constant: 2,
+                    slope_scale: 1.0,
+                    clamp: 0.0,
+                },
+            }),
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
         });
 
-        Self {
-            render_texture,
-            render_view,
-            depth_texture,
-            depth_view,
-            size,
-            pipeline,
-            grid_vertex_buffer,
-            grid_vertex_count,
-            axis_vertex_buffer,
-            axis_vertex_count,
-            camera_buffer,
-            camera_bind_group,
-            egui_texture_id: None,
+        // Entity ID pipeline: solid proxies, no fog
+        let pick = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Viewport Pick Pipeline"),
+            layout: Some(layout),
+            vertex: wgpu::VertexState {
+                module: shader,
+                entry_point: Some("vs_main"),
+                buffers: &[Vertex::desc()],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: shader,
+                entry_point: Some("fs_pick"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: PICK_FORMAT,
+                    blend: Some(wgpu::BlendState::REPLACE),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: wgpu::PipelineCompilationOptions::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: None,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: wgpu::TextureFormat::Depth32Float,
+                depth_write_enabled: true,
+                depth_compare: wgpu::CompareFunction::Less,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+
+        ViewportPipelines { lines, grid, mesh, pick }
+    }
+
+    /// On-disk source of the viewport shader, looked up at runtime: in
+    /// debug builds the project's override under its asset root first,
+    /// then the one shipped next to the editor executable. `None` means
+    /// the built-in shader is used.
+    pub fn shader_source_path(project_dir: Option<&Path>) -> Option<PathBuf> {
+        let project = cfg!(debug_assertions)
+            .then(|| crate::asset_paths::asset_root(project_dir).join(PROJECT_SHADER_PATH));
+        let shipped = std::env::current_exe()
+            .ok()
+            .and_then(|exe| Some(exe.parent()?.join(SHIPPED_SHADER_PATH)));
+        [project, shipped].into_iter().flatten().find(|path| path.is_file())
+    }
+
+    /// Check if the viewport pipelines are built from the shader at `path`
+    pub fn uses_shader(path: &Path, project_dir: Option<&Path>) -> bool {
+        let Some(source) = Self::shader_source_path(project_dir) else {
+            return false;
+        };
+        // Watchers may report paths through symlinks or with `..` in them
+        match (path.canonicalize(), source.canonicalize()) {
+            (Ok(path), Ok(source)) => path == source,
+            _ => path == source,
         }
     }
 
+    /// Recompile the viewport shader from [`Self::shader_source_path`], or
+    /// the built-in one when there is none, and rebuild its pipelines.
+    ///
+    /// On a compile or validation error the current pipelines are kept, so
+    /// the viewport keeps rendering with the last good shader.
+    pub fn reload_shaders(&mut self, device: &wgpu::Device, project_dir: Option<&Path>) -> Result<(), String> {
+        let (name, source) = match Self::shader_source_path(project_dir) {
+            Some(path) => {
+                let source =
+                    std::fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+                (path.display().to_string(), source)
+            }
+            None => ("built-in viewport shader".to_string(), SHADER_SOURCE.to_string()),
+        };
+
+        // Capture validation errors instead of letting the device's
+        // uncaptured error handler panic
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Viewport Shader"),
+            source: wgpu::ShaderSource::Wgsl(source.into()),
+        });
+        let pipelines = Self::create_pipelines(device, &self.pipeline_layout, &shader);
+        if let Some(error) = pollster::block_on(device.pop_error_scope()) {
+            return Err(format!("{}: {}", name, error));
+        }
+
+        self.pipeline = pipelines.lines;
+        self.grid_pipeline = pipelines.grid;
+        self.mesh_pipeline = pipelines.mesh;
+        self.pick_pipeline = pipelines.pick;
+        Ok(())
+    }
+
     fn create_render_texture(device: &wgpu::Device, size: [u32; 2]) -> (wgpu::Texture, wgpu::TextureView) {
+        Self::create_color_texture(
+            device,
+            size,
+            "Viewport Render Texture",
+            wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+        )
+    }
+
+    fn create_color_texture(
+        device: &wgpu::Device,
+        size: [u32; 2],
+        label: &str,
+        usage: wgpu::TextureUsages,
+    ) -> (wgpu::Texture, wgpu::TextureView) {
         let texture = device.create_texture(&wgpu::TextureDescriptor {
-            label: Some("Viewport Render Texture"),
+            label: Some(label),
             size: wgpu::Extent3d {
                 width: size[0],
                 height: size[1],
@@ -205,8 +865,8 @@ impl ViewportRenderer {
             mip_level_count: 1,
             sample_count: 1,
             dimension: wgpu::TextureDimension::D2,
-            format: wgpu::TextureFormat::Rgba8UnormSrgb,
-            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+            format: COLOR_FORMAT,
+            usage,
             view_formats: &[],
         });
         let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
@@ -232,6 +892,25 @@ impl ViewportRenderer {
         (texture, view)
     }
 
+    fn create_pick_texture(device: &wgpu::Device, size: [u32; 2]) -> (wgpu::Texture, wgpu::TextureView) {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Viewport Pick Texture"),
+            size: wgpu::Extent3d {
+                width: size[0],
+                height: size[1],
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: PICK_FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        (texture, view)
+    }
+
     fn identity_matrix() -> [[f32; 4]; 4] {
         [
             [1.0, 0.0, 0.0, 0.0],
@@ -241,53 +920,50 @@ impl ViewportRenderer {
         ]
     }
 
-    fn create_grid_vertices() -> (Vec<Vertex>, u32) {
-        let mut vertices = Vec::new();
-        let grid_size = 10;
-        let grid_spacing = 1.0;
-        let grid_color = [0.3, 0.3, 0.3];
-
-        // Create grid lines on XZ plane
-        for i in -grid_size..=grid_size {
-            let pos = i as f32 * grid_spacing;
-
-            // Lines parallel to X axis
-            vertices.push(Vertex {
-                position: [-grid_size as f32 * grid_spacing, 0.0, pos],
-                color: grid_color,
-            });
-            vertices.push(Vertex {
-                position: [grid_size as f32 * grid_spacing, 0.0, pos],
-                color: grid_color,
-            });
+    fn create_grid_buffer(device: &wgpu::Device, layout: &GridLayout) -> (wgpu::Buffer, u32) {
+        let grid_vertices = grid_vertices(layout);
+        let buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Grid Vertex Buffer"),
+            contents: bytemuck::cast_slice(&grid_vertices),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+        (buffer, grid_vertices.len() as u32)
+    }
 
-            // Lines parallel to Z axis
-            vertices.push(Vertex {
-                position: [pos, 0.0, -grid_size as f32 * grid_spacing],
-                color: grid_color,
-            });
-            vertices.push(Vertex {
-                position: [pos, 0.0, grid_size as f32 * grid_spacing],
-                color: grid_color,
-            });
+    /// Rebuild the ground grid if the up axis or spacing changed, the
+    /// camera `focus` moved to another major grid cell, or the camera
+    /// zoomed far enough to need more or fewer lines
+    pub fn update_grid(
+        &mut self,
+        device: &wgpu::Device,
+        up_axis: UpAxis,
+        spacing: f32,
+        focus: [f32; 3],
+        camera_distance: f32,
+        far: f32,
+    ) {
+        let layout = GridLayout::new(up_axis, spacing, focus, camera_distance, far);
+        if self.grid_layout == layout {
+            return;
         }
-
-        let count = vertices.len() as u32;
-        (vertices, count)
+        let (buffer, count) = Self::create_grid_buffer(device, &layout);
+        self.grid_vertex_buffer = buffer;
+        self.grid_vertex_count = count;
+        self.grid_layout = layout;
     }
 
     fn create_axis_vertices() -> (Vec<Vertex>, u32) {
         let axis_length = 2.0;
         let vertices = vec![
             // X axis (red)
-            Vertex { position: [0.0, 0.0, 0.0], color: [1.0, 0.2, 0.2] },
-            Vertex { position: [axis_length, 0.0, 0.0], color: [1.0, 0.2, 0.2] },
+            Vertex { position: [0.0, 0.0, 0.0], color: AXIS_COLORS[0] },
+            Vertex { position: [axis_length, 0.0, 0.0], color: AXIS_COLORS[0] },
             // Y axis (green)
-            Vertex { position: [0.0, 0.0, 0.0], color: [0.2, 1.0, 0.2] },
-            Vertex { position: [0.0, axis_length, 0.0], color: [0.2, 1.0, 0.2] },
+            Vertex { position: [0.0, 0.0, 0.0], color: AXIS_COLORS[1] },
+            Vertex { position: [0.0, axis_length, 0.0], color: AXIS_COLORS[1] },
             // Z axis (blue)
-            Vertex { position: [0.0, 0.0, 0.0], color: [0.2, 0.2, 1.0] },
-            Vertex { position: [0.0, 0.0, axis_length], color: [0.2, 0.2, 1.0] },
+            Vertex { position: [0.0, 0.0, 0.0], color: AXIS_COLORS[2] },
+            Vertex { position: [0.0, 0.0, axis_length], color: AXIS_COLORS[2] },
         ];
 
         let count = vertices.len() as u32;
@@ -301,18 +977,39 @@ impl ViewportRenderer {
             self.size = new_size;
             let (render_texture, render_view) = Self::create_render_texture(device, new_size);
             let (depth_texture, depth_view) = Self::create_depth_texture(device, new_size);
+            let (pick_texture, pick_view) = Self::create_pick_texture(device, new_size);
             self.render_texture = render_texture;
             self.render_view = render_view;
             self.depth_texture = depth_texture;
             self.depth_view = depth_view;
+            self.pick_texture = pick_texture;
+            self.pick_view = pick_view;
             // Clear the egui texture ID so it gets re-registered
             self.egui_texture_id = None;
         }
     }
 
-    /// Update camera matrices
+    /// Use the scene's ambient and fog settings for subsequent frames
+    pub fn set_environment(&mut self, environment: &EnvironmentSettings) {
+        self.environment = environment.clone();
+    }
+
+    /// Background color: the fog color when fog is on, otherwise the ambient term
+    fn clear_color(&self) -> wgpu::Color {
+        let [r, g, b] = if self.environment.fog_enabled {
+            self.environment.fog_color
+        } else {
+            self.environment.ambient()
+        };
+        wgpu::Color { r: r as f64, g: g as f64, b: b as f64, a: 1.0 }
+    }
+
+    /// Update camera matrices.
+    ///
+    /// An orthographic camera shows what the perspective one would show at
+    /// the target distance, and doesn't clip what lies behind its position.
     pub fn update_camera(
-        &self,
+        &mut self,
         queue: &wgpu::Queue,
         position: [f32; 3],
         target: [f32; 3],
@@ -321,12 +1018,37 @@ impl ViewportRenderer {
         fov: f32,
         near: f32,
         far: f32,
+        projection: CameraProjection,
     ) {
+        let camera = CameraView { position, target, up, aspect, fov, near, far, projection };
+        self.camera_view = Some(camera);
+        self.write_camera(queue, &camera);
+    }
+
+    fn write_camera(&self, queue: &wgpu::Queue, camera: &CameraView) {
+        let CameraView { position, target, up, aspect, fov, near, far, projection } = *camera;
         let view = Self::look_at(position, target, up);
-        let proj = Self::perspective(fov, aspect, near, far);
+        let proj = match projection {
+            CameraProjection::Perspective => Self::perspective(fov, aspect, near, far),
+            CameraProjection::Orthographic => {
+                let offset = [target[0] - position[0], target[1] - position[1], target[2] - position[2]];
+                let half_height = Self::dot(offset, offset).sqrt() * (fov / 2.0).tan();
+                Self::orthographic(half_height * aspect, half_height, -far, far)
+            }
+        };
         let view_proj = Self::mat4_mul(&proj, &view);
 
-        let uniform = CameraUniform { view_proj };
+        let env = &self.environment;
+        // Fade out with distance from the camera, well before the grid edge
+        let offset = [target[0] - position[0], target[1] - position[1], target[2] - position[2]];
+        let grid_extent = grid_reach(Self::dot(offset, offset).sqrt(), far).min(self.grid_layout.extent());
+        let uniform = CameraUniform {
+            view_proj,
+            camera_position: [position[0], position[1], position[2], 0.0],
+            fog_color: [env.fog_color[0], env.fog_color[1], env.fog_color[2], 0.0],
+            fog_params: [env.fog_start, env.fog_end, if env.fog_enabled { 1.0 } else { 0.0 }, 0.0],
+            grid_fade: [grid_extent * 0.25, grid_extent * 0.9, 0.0, 0.0],
+        };
         queue.write_buffer(&self.camera_buffer, 0, bytemuck::cast_slice(&[uniform]));
     }
 
@@ -362,6 +1084,16 @@ impl ViewportRenderer {
         ]
     }
 
+    /// Orthographic projection mapping depth to wgpu's 0..1 range
+    fn orthographic(half_width: f32, half_height: f32, near: f32, far: f32) -> [[f32; 4]; 4] {
+        [
+            [1.0 / half_width, 0.0, 0.0, 0.0],
+            [0.0, 1.0 / half_height, 0.0, 0.0],
+            [0.0, 0.0, -1.0 / (far - near), 0.0],
+            [0.0, 0.0, -near / (far - near), 1.0],
+        ]
+    }
+
     fn normalize(v: [f32; 3]) -> [f32; 3] {
         let len = (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt();
         if len > 0.0 {
@@ -397,7 +1129,22 @@ impl ViewportRenderer {
 
     /// Render the viewport scene
     #[allow(unsafe_code)] // Workaround for wgpu 23 lifetime issue with RenderPass
-    pub fn render(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, show_grid: bool) {
+    pub fn render(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        scene: &SceneData,
+        show_grid: bool,
+        mode: RenderMode,
+        debug_lines: &[DebugLine],
+    ) {
+        self.show_grid = show_grid;
+        let (triangles, lines) = scene_geometry(scene, mode);
+        self.mesh_vertex_buffer = Self::create_geometry_buffer(device, "Mesh Vertex Buffer", &triangles);
+        self.edge_vertex_buffer = Self::create_geometry_buffer(device, "Edge Vertex Buffer", &lines);
+        self.debug_vertex_buffer =
+            Self::create_geometry_buffer(device, "Physics Debug Vertex Buffer", &debug_line_vertices(debug_lines));
+
         let encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
             label: Some("Viewport Encoder"),
         });
@@ -408,19 +1155,206 @@ impl ViewportRenderer {
         {
             // SAFETY: encoder_ptr is valid and properly reclaimed after render_pass is dropped
             let encoder_ref: &'static mut wgpu::CommandEncoder = unsafe { &mut *encoder_ptr };
+            self.draw_scene(encoder_ref, &self.render_view, &self.depth_view, show_grid);
+        }
 
-            let mut render_pass = encoder_ref.begin_render_pass(&wgpu::RenderPassDescriptor {
-                label: Some("Viewport Render Pass"),
+        // SAFETY: Reclaim the Box after render_pass is dropped
+        let encoder = unsafe { Box::from_raw(encoder_ptr) };
+        queue.submit(std::iter::once(encoder.finish()));
+    }
+
+    fn create_geometry_buffer(device: &wgpu::Device, label: &str, vertices: &[Vertex]) -> Option<(wgpu::Buffer, u32)> {
+        if vertices.is_empty() {
+            return None;
+        }
+        let buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some(label),
+            contents: bytemuck::cast_slice(vertices),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+        Some((buffer, vertices.len() as u32))
+    }
+
+    /// Record the scene pass into `color` and `depth`
+    fn draw_scene(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        color: &wgpu::TextureView,
+        depth: &wgpu::TextureView,
+        show_grid: bool,
+    ) {
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Viewport Render Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: color,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(self.clear_color()),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                view: depth,
+                depth_ops: Some(wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(1.0),
+                    store: wgpu::StoreOp::Store,
+                }),
+                stencil_ops: None,
+            }),
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, &self.camera_bind_group, &[]);
+
+        // Draw axis
+        render_pass.set_vertex_buffer(0, self.axis_vertex_buffer.slice(..));
+        render_pass.draw(0..self.axis_vertex_count, 0..1);
+
+        // Draw entity edges and physics debug lines, then faces
+        if let Some((buffer, count)) = &self.edge_vertex_buffer {
+            render_pass.set_vertex_buffer(0, buffer.slice(..));
+            render_pass.draw(0..*count, 0..1);
+        }
+        if let Some((buffer, count)) = &self.debug_vertex_buffer {
+            render_pass.set_vertex_buffer(0, buffer.slice(..));
+            render_pass.draw(0..*count, 0..1);
+        }
+        if let Some((buffer, count)) = &self.mesh_vertex_buffer {
+            render_pass.set_pipeline(&self.mesh_pipeline);
+            render_pass.set_vertex_buffer(0, buffer.slice(..));
+            render_pass.draw(0..*count, 0..1);
+        }
+
+        // Draw grid last so it blends over the background, and loses
+        // the depth test where it overlaps the axis lines
+        if show_grid {
+            render_pass.set_pipeline(&self.grid_pipeline);
+            render_pass.set_vertex_buffer(0, self.grid_vertex_buffer.slice(..));
+            render_pass.draw(0..self.grid_vertex_count, 0..1);
+        }
+    }
+
+    /// Render the current view at `size` pixels and read it back.
+    ///
+    /// Uses the camera of the last frame at the capture's aspect ratio, and
+    /// doesn't touch the on-screen target. The size is clamped to the
+    /// device's texture and buffer limits, see [`capture_size`].
+    pub fn capture(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        size: [u32; 2],
+    ) -> Result<image::RgbaImage, wgpu::BufferAsyncError> {
+        let limits = device.limits();
+        let [width, height] = capture_size(size, limits.max_texture_dimension_2d, limits.max_buffer_size);
+
+        let (texture, view) = Self::create_color_texture(
+            device,
+            [width, height],
+            "Viewport Capture Texture",
+            wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+        );
+        let (_depth_texture, depth_view) = Self::create_depth_texture(device, [width, height]);
+
+        let bytes_per_row = padded_bytes_per_row(width);
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Viewport Capture Buffer"),
+            size: u64::from(bytes_per_row) * u64::from(height),
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        // Re-project for the capture's aspect ratio, then restore the view
+        if let Some(camera) = self.camera_view {
+            self.write_camera(queue, &CameraView { aspect: width as f32 / height as f32, ..camera });
+        }
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Viewport Capture Encoder"),
+        });
+        self.draw_scene(&mut encoder, &view, &depth_view, self.show_grid);
+        encoder.copy_texture_to_buffer(
+            wgpu::ImageCopyTexture {
+                texture: &texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::ImageCopyBuffer {
+                buffer: &buffer,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(bytes_per_row),
+                    rows_per_image: Some(height),
+                },
+            },
+            wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+        );
+        queue.submit(std::iter::once(encoder.finish()));
+
+        if let Some(camera) = self.camera_view {
+            self.write_camera(queue, &camera);
+        }
+
+        let slice = buffer.slice(..);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+        device.poll(wgpu::Maintain::Wait);
+        receiver.recv().map_err(|_| wgpu::BufferAsyncError)??;
+
+        let pixels = unpad_rows(&slice.get_mapped_range(), width, height);
+        buffer.unmap();
+
+        // The buffer holds exactly `width * height` pixels
+        Ok(image::RgbaImage::from_raw(width, height, pixels).expect("capture size matches pixel data"))
+    }
+
+    /// Find the entity drawn at pixel (`x`, `y`) of the viewport, using the
+    /// camera from the last [`ViewportRenderer::update_camera`].
+    ///
+    /// Renders the entity ID pass for that pixel only and waits for the
+    /// readback. Returns `None` for background and inactive entities.
+    pub fn pick(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        scene: &SceneData,
+        x: u32,
+        y: u32,
+    ) -> Option<EntityId> {
+        if x >= self.size[0] || y >= self.size[1] {
+            return None;
+        }
+
+        let (vertices, entities) = pick_proxies(scene);
+        if entities.is_empty() {
+            return None;
+        }
+        let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Pick Vertex Buffer"),
+            contents: bytemuck::cast_slice(&vertices),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Viewport Pick Encoder"),
+        });
+        {
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Viewport Pick Pass"),
                 color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: &self.render_view,
+                    view: &self.pick_view,
                     resolve_target: None,
                     ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(wgpu::Color {
-                            r: 0.12,
-                            g: 0.12,
-                            b: 0.12,
-                            a: 1.0,
-                        }),
+                        load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
                         store: wgpu::StoreOp::Store,
                     },
                 })],
@@ -428,7 +1362,7 @@ impl ViewportRenderer {
                     view: &self.depth_view,
                     depth_ops: Some(wgpu::Operations {
                         load: wgpu::LoadOp::Clear(1.0),
-                        store: wgpu::StoreOp::Store,
+                        store: wgpu::StoreOp::Discard,
                     }),
                     stencil_ops: None,
                 }),
@@ -436,23 +1370,59 @@ impl ViewportRenderer {
                 occlusion_query_set: None,
             });
 
-            render_pass.set_pipeline(&self.pipeline);
+            // Only the pixel under the cursor is needed
+            render_pass.set_scissor_rect(x, y, 1, 1);
+            render_pass.set_pipeline(&self.pick_pipeline);
             render_pass.set_bind_group(0, &self.camera_bind_group, &[]);
+            render_pass.set_vertex_buffer(0, vertex_buffer.slice(..));
+            render_pass.draw(0..vertices.len() as u32, 0..1);
+        }
 
-            // Draw grid
-            if show_grid {
-                render_pass.set_vertex_buffer(0, self.grid_vertex_buffer.slice(..));
-                render_pass.draw(0..self.grid_vertex_count, 0..1);
-            }
+        encoder.copy_texture_to_buffer(
+            wgpu::ImageCopyTexture {
+                texture: &self.pick_texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d { x, y, z: 0 },
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::ImageCopyBuffer {
+                buffer: &self.pick_buffer,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT),
+                    rows_per_image: Some(1),
+                },
+            },
+            wgpu::Extent3d {
+                width: 1,
+                height: 1,
+                depth_or_array_layers: 1,
+            },
+        );
+        queue.submit(std::iter::once(encoder.finish()));
 
-            // Draw axis
-            render_pass.set_vertex_buffer(0, self.axis_vertex_buffer.slice(..));
-            render_pass.draw(0..self.axis_vertex_count, 0..1);
+        let slice = self.pick_buffer.slice(..);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+        device.poll(wgpu::Maintain::Wait);
+        match receiver.recv() {
+            Ok(Ok(())) => {}
+            Ok(Err(err)) => {
+                tracing::warn!("Pick readback failed: {}", err);
+                return None;
+            }
+            Err(_) => return None,
         }
 
-        // SAFETY: Reclaim the Box after render_pass is dropped
-        let encoder = unsafe { Box::from_raw(encoder_ptr) };
-        queue.submit(std::iter::once(encoder.finish()));
+        let pixel = {
+            let data = slice.get_mapped_range();
+            [data[0], data[1], data[2], data[3]]
+        };
+        self.pick_buffer.unmap();
+
+        decode_pick_id(pixel).and_then(|index| entities.get(index).copied())
     }
 
     /// Get the render texture view for egui integration
@@ -489,3 +1459,150 @@ impl ViewportRenderer {
 
 // Re-export for use
 use wgpu::util::DeviceExt as _;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::EntityData;
+
+    #[test]
+    fn test_pick_id_round_trip() {
+        for index in [0, 1, 254, 255, 256, 70_000] {
+            let color = encode_pick_id(index);
+            let pixel = [color[0], color[1], color[2], 1.0].map(|c| (c * 255.0).round() as u8);
+            assert_eq!(decode_pick_id(pixel), Some(index));
+        }
+        // Cleared background
+        assert_eq!(decode_pick_id([0, 0, 0, 0]), None);
+    }
+
+    #[test]
+    fn test_pick_proxies_skip_inactive() {
+        let mut scene = SceneData::new();
+        let shown = scene.add_entity(EntityData::default());
+        let hidden = scene.add_entity(EntityData {
+            active: false,
+            ..Default::default()
+        });
+        let child = scene.add_entity(EntityData {
+            parent: Some(hidden),
+            ..Default::default()
+        });
+        scene.get_mut(&hidden).unwrap().children.push(child);
+
+        let (vertices, entities) = pick_proxies(&scene);
+        assert_eq!(entities, vec![shown]);
+        assert_eq!(vertices.len(), 36);
+        assert!(vertices.iter().all(|v| v.color == encode_pick_id(0)));
+    }
+
+    #[test]
+    fn test_capture_size_fits_buffer_limit() {
+        let fits = |[width, height]: [u32; 2], limit: u64| u64::from(padded_bytes_per_row(width)) * u64::from(height) <= limit;
+
+        // Within limits: unchanged, apart from the texture dimension clamp
+        assert_eq!(capture_size([1920, 1080], 8192, 1 << 28), [1920, 1080]);
+        assert_eq!(capture_size([16384, 100], 8192, 1 << 28), [8192, 100]);
+        assert_eq!(capture_size([0, 0], 8192, 1 << 28), [1, 1]);
+
+        // 8192 x 8192 needs 256 MiB; a 64 MiB buffer halves both sides
+        let size = capture_size([8192, 8192], 8192, 1 << 26);
+        assert_eq!(size, [4096, 4096]);
+        assert!(fits(size, 1 << 26));
+
+        // Odd sizes keep roughly their aspect and still fit
+        let size = capture_size([7000, 3001], 8192, 10_000_000);
+        assert!(fits(size, 10_000_000));
+        assert!((size[0] as f32 / size[1] as f32 - 7000.0 / 3001.0).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_scene_geometry_per_render_mode() {
+        let mut scene = SceneData::new();
+        let mesh = || {
+            vec![Component::MeshRenderer(crate::components::MeshRendererComponent {
+                mesh: "meshes/crate.glb".to_string(),
+                ..Default::default()
+            })]
+        };
+        scene.add_entity(EntityData {
+            components: mesh(),
+            ..Default::default()
+        });
+        scene.add_entity(EntityData {
+            active: false,
+            components: mesh(),
+            ..Default::default()
+        });
+        scene.add_entity(EntityData::default());
+
+        let (triangles, lines) = scene_geometry(&scene, RenderMode::Shaded);
+        assert_eq!((triangles.len(), lines.len()), (36, 0));
+        let (triangles, lines) = scene_geometry(&scene, RenderMode::Wireframe);
+        assert_eq!((triangles.len(), lines.len()), (0, 48));
+        assert!(lines.iter().all(|v| v.color == RenderMode::Wireframe.edge_color()));
+        let (triangles, lines) = scene_geometry(&scene, RenderMode::ShadedWireframe);
+        assert_eq!((triangles.len(), lines.len()), (36, 48));
+
+        // The top face (third in `CUBE_FACES`) faces the light
+        let top = triangles[12].color;
+        let bottom = triangles[18].color;
+        assert!(top[0] > bottom[0]);
+    }
+
+    #[test]
+    fn test_unpad_capture_rows() {
+        assert_eq!(padded_bytes_per_row(1), wgpu::COPY_BYTES_PER_ROW_ALIGNMENT);
+        assert_eq!(padded_bytes_per_row(64), 256);
+        assert_eq!(padded_bytes_per_row(65), 512);
+
+        // Two rows of three pixels, each padded to 256 bytes
+        let mut data = vec![0u8; 512];
+        data[..12].copy_from_slice(&[1; 12]);
+        data[256..268].copy_from_slice(&[2; 12]);
+        let pixels = unpad_rows(&data, 3, 2);
+        assert_eq!(pixels.len(), 24);
+        assert_eq!(&pixels[..12], &[1; 12]);
+        assert_eq!(&pixels[12..], &[2; 12]);
+    }
+
+    #[test]
+    fn test_grid_follows_focus_and_snap_size() {
+        let layout = GridLayout::new(UpAxis::Y, 0.5, [12.0, 3.0, -7.0], 10.0, 1000.0);
+        // Major cells are 5 units wide
+        assert_eq!(layout.center, [2, -1]);
+        assert_eq!(GridLayout::new(UpAxis::Y, 0.5, [11.0, 100.0, -6.0], 10.0, 1000.0), layout);
+
+        let vertices = grid_vertices(&layout);
+        assert_eq!(vertices.len() as i64, 2 * 2 * (2 * layout.half_lines + 1));
+        // Lines stay on the ground plane, on multiples of the spacing
+        for vertex in &vertices {
+            assert_eq!(vertex.position[1], 0.0);
+            let steps = vertex.position[0] / 0.5;
+            assert!((steps - steps.round()).abs() < 1e-3);
+        }
+
+        // The lines through the origin are colored by the axis they follow
+        let x_axis: Vec<_> = vertices.iter().filter(|v| v.color == AXIS_COLORS[0]).collect();
+        let z_axis: Vec<_> = vertices.iter().filter(|v| v.color == AXIS_COLORS[2]).collect();
+        assert_eq!(x_axis.len(), 2);
+        assert_eq!(z_axis.len(), 2);
+        assert!(x_axis.iter().all(|v| v.position[2] == 0.0));
+        assert!(z_axis.iter().all(|v| v.position[0] == 0.0));
+    }
+
+    #[test]
+    fn test_grid_reach_follows_camera_distance() {
+        let at = |distance: f32, far: f32| GridLayout::new(UpAxis::Y, 1.0, [0.0; 3], distance, far);
+        // Zooming out grows the grid past its reach
+        assert!(at(50.0, 5000.0).extent() > at(5.0, 5000.0).extent());
+        assert!(at(50.0, 5000.0).extent() >= grid_reach(50.0, 5000.0));
+        // Small zoom steps keep the same grid
+        assert_eq!(at(5.0, 5000.0), at(5.5, 5000.0));
+
+        // The far plane caps the reach, and so the line count
+        assert_eq!(grid_reach(500.0, 1000.0), 1000.0);
+        assert_eq!(at(500.0, 1000.0), at(5000.0, 1000.0));
+        assert!(at(1e6, 1e9).half_lines <= MAX_GRID_HALF_LINES);
+    }
+}
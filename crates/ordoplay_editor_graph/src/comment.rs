@@ -0,0 +1,96 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//! Comment frames drawn behind groups of nodes.
+//!
+//! Comments only annotate the graph: they are saved with it but never take
+//! part in evaluation.
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Unique identifier for a comment
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct CommentId(pub Uuid);
+
+impl CommentId {
+    /// Create a new random comment ID
+    pub fn new() -> Self {
+        Self(Uuid::new_v4())
+    }
+}
+
+impl Default for CommentId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A titled, colored frame in graph space
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Comment {
+    /// Unique comment ID
+    pub id: CommentId,
+    /// Title shown in the header
+    pub title: String,
+    /// Frame color
+    pub color: [u8; 3],
+    /// Top-left corner (graph space)
+    pub position: [f32; 2],
+    /// Width and height (graph space)
+    pub size: [f32; 2],
+}
+
+impl Comment {
+    /// Smallest width or height a comment can be resized to
+    pub const MIN_SIZE: f32 = 80.0;
+
+    /// Create a comment covering a rectangle
+    pub fn new(title: impl Into<String>, position: [f32; 2], size: [f32; 2]) -> Self {
+        Self {
+            id: CommentId::new(),
+            title: title.into(),
+            color: [90, 110, 140],
+            position,
+            size: [size[0].max(Self::MIN_SIZE), size[1].max(Self::MIN_SIZE)],
+        }
+    }
+
+    /// Bottom-right corner (graph space)
+    pub fn max(&self) -> [f32; 2] {
+        [self.position[0] + self.size[0], self.position[1] + self.size[1]]
+    }
+
+    /// Whether a point lies inside the frame
+    pub fn contains_point(&self, point: [f32; 2]) -> bool {
+        let max = self.max();
+        (self.position[0]..=max[0]).contains(&point[0]) && (self.position[1]..=max[1]).contains(&point[1])
+    }
+
+    /// Whether a rectangle lies entirely inside the frame
+    pub fn contains_rect(&self, min: [f32; 2], max: [f32; 2]) -> bool {
+        self.contains_point(min) && self.contains_point(max)
+    }
+
+    /// Resize by moving the bottom-right corner, keeping the minimum size
+    pub fn resize_to(&mut self, corner: [f32; 2]) {
+        self.size = [
+            (corner[0] - self.position[0]).max(Self::MIN_SIZE),
+            (corner[1] - self.position[1]).max(Self::MIN_SIZE),
+        ];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_containment_and_resize() {
+        let mut comment = Comment::new("Lighting", [0.0, 0.0], [200.0, 100.0]);
+        assert!(comment.contains_rect([10.0, 10.0], [190.0, 90.0]));
+        assert!(!comment.contains_rect([10.0, 10.0], [210.0, 90.0]));
+
+        comment.resize_to([20.0, 500.0]);
+        assert_eq!(comment.size, [Comment::MIN_SIZE, 500.0]);
+        assert!(comment.contains_point([40.0, 450.0]));
+    }
+}
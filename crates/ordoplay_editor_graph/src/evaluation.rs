@@ -46,6 +46,8 @@ pub struct EvaluationContext<'a> {
     outputs: HashMap<NodeId, NodeOutput>,
     /// Evaluation order
     order: Vec<NodeId>,
+    /// Given port values that take precedence over connections and results
+    inputs: HashMap<PortId, PortValue>,
 }
 
 impl<'a> EvaluationContext<'a> {
@@ -58,11 +60,22 @@ impl<'a> EvaluationContext<'a> {
             graph,
             outputs: HashMap::new(),
             order,
+            inputs: HashMap::new(),
         })
     }
 
+    /// Give a port a fixed value. An input port reads it instead of its
+    /// connection; an output port reports it instead of the computed value.
+    pub fn set_input(&mut self, port_id: PortId, value: PortValue) {
+        self.inputs.insert(port_id, value);
+    }
+
     /// Get the input value for a port
     pub fn get_input(&self, _node_id: NodeId, port_id: PortId) -> Option<&PortValue> {
+        if let Some(value) = self.inputs.get(&port_id) {
+            return Some(value);
+        }
+
         // Find the connection to this port
         let connection = self.graph.connections_to(port_id).next()?;
 
@@ -98,6 +111,116 @@ impl<'a> EvaluationContext<'a> {
     pub fn outputs(&self) -> &HashMap<NodeId, NodeOutput> {
         &self.outputs
     }
+
+    /// Evaluate every node in order and return all outputs.
+    ///
+    /// Reroute nodes are handled here and never reach the evaluator.
+    pub fn run(mut self, evaluator: &dyn NodeEvaluator) -> Result<HashMap<NodeId, NodeOutput>, EvaluationError> {
+        for node_id in self.order.clone() {
+            self.evaluate_node(node_id, evaluator)?;
+        }
+        Ok(self.outputs)
+    }
+
+    /// Evaluate a single node, assuming its dependencies already ran
+    pub fn evaluate_node(&mut self, node_id: NodeId, evaluator: &dyn NodeEvaluator) -> Result<(), EvaluationError> {
+        let mut output = match self.forward_reroute(node_id) {
+            Some(output) => output,
+            None => evaluator.evaluate(node_id, self)?,
+        };
+        if let Some(node) = self.graph.node(node_id) {
+            for port in &node.outputs {
+                if let Some(value) = self.inputs.get(&port.id) {
+                    output.set(port.id, value.clone());
+                }
+            }
+        }
+        self.set_output(node_id, output);
+        Ok(())
+    }
+
+    /// Output of a reroute node: its input value, unchanged
+    fn forward_reroute(&self, node_id: NodeId) -> Option<NodeOutput> {
+        let node = self.graph.node(node_id).filter(|node| node.is_reroute())?;
+        let mut output = NodeOutput::new();
+        if let (Some(input), Some(port)) = (node.inputs.first(), node.outputs.first()) {
+            if let Some(value) = self.get_input(node_id, input.id) {
+                output.set(port.id, value.clone());
+            }
+        }
+        Some(output)
+    }
+}
+
+/// Runs a graph one node at a time in schedule order, recording the value
+/// seen on every port for previews.
+///
+/// Scheduling goes through [`Graph::topological_order`], so a graph with a
+/// cycle is rejected up front instead of looping.
+pub struct EvaluationStepper<'a> {
+    ctx: EvaluationContext<'a>,
+    /// Index into the schedule of the next node to run
+    next: usize,
+    /// Last value on each input and output port
+    port_values: HashMap<PortId, PortValue>,
+}
+
+impl<'a> EvaluationStepper<'a> {
+    /// Schedule a graph, with fixed values for some ports (see
+    /// [`EvaluationContext::set_input`])
+    pub fn new(graph: &'a Graph, inputs: HashMap<PortId, PortValue>) -> Result<Self, EvaluationError> {
+        let mut ctx = EvaluationContext::new(graph)?;
+        ctx.inputs = inputs;
+        Ok(Self {
+            ctx,
+            next: 0,
+            port_values: HashMap::new(),
+        })
+    }
+
+    /// Run the next scheduled node and return it, or `None` once all have run
+    pub fn step(&mut self, evaluator: &dyn NodeEvaluator) -> Result<Option<NodeId>, EvaluationError> {
+        let Some(&node_id) = self.ctx.order.get(self.next) else {
+            return Ok(None);
+        };
+        self.next += 1;
+        self.ctx.evaluate_node(node_id, evaluator)?;
+
+        let graph = self.ctx.graph;
+        let Some(node) = graph.node(node_id) else {
+            return Ok(Some(node_id));
+        };
+        for port in &node.inputs {
+            if let Some(value) = self.ctx.get_input_or_default(node_id, port.id) {
+                self.port_values.insert(port.id, value.clone());
+            }
+        }
+        if let Some(output) = self.ctx.outputs.get(&node_id) {
+            self.port_values.extend(output.values.iter().map(|(id, value)| (*id, value.clone())));
+        }
+        Ok(Some(node_id))
+    }
+
+    /// Run every remaining node
+    pub fn run(&mut self, evaluator: &dyn NodeEvaluator) -> Result<(), EvaluationError> {
+        while self.step(evaluator)?.is_some() {}
+        Ok(())
+    }
+
+    /// Whether every scheduled node has run
+    pub fn is_finished(&self) -> bool {
+        self.next >= self.ctx.order.len()
+    }
+
+    /// Last recorded value of each port
+    pub fn port_values(&self) -> &HashMap<PortId, PortValue> {
+        &self.port_values
+    }
+
+    /// Take the recorded port values
+    pub fn into_port_values(self) -> HashMap<PortId, PortValue> {
+        self.port_values
+    }
 }
 
 /// Trait for evaluating nodes
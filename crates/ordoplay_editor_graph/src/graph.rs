@@ -1,9 +1,10 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 //! Graph data structure containing nodes and connections.
 
+use crate::comment::{Comment, CommentId};
 use crate::connection::{Connection, ConnectionId};
-use crate::node::{Node, NodeId};
-use crate::port::PortId;
+use crate::node::{Node, NodeId, NodeType};
+use crate::port::{Port, PortId, PortType};
 use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 
@@ -16,6 +17,16 @@ pub struct Graph {
     nodes: IndexMap<NodeId, Node>,
     /// Connections between nodes
     connections: IndexMap<ConnectionId, Connection>,
+    /// Whether connections may form cycles, as in state machines. Dataflow
+    /// graphs leave this off so they can always be scheduled.
+    #[serde(default)]
+    pub allow_cycles: bool,
+    /// Comment frames, back to front
+    #[serde(default)]
+    comments: IndexMap<CommentId, Comment>,
+    /// Bumped on every modification, for change detection
+    #[serde(skip)]
+    revision: u64,
 }
 
 impl Graph {
@@ -25,22 +36,36 @@ impl Graph {
             name: name.into(),
             nodes: IndexMap::new(),
             connections: IndexMap::new(),
+            allow_cycles: false,
+            comments: IndexMap::new(),
+            revision: 0,
         }
     }
 
+    /// Modification counter. Any change to nodes or connections (including
+    /// handing out a mutable node) increases it; moving a node with
+    /// [`Graph::set_node_position`] only counts when it actually moves.
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
     /// Add a node to the graph
     pub fn add_node(&mut self, node: Node) -> NodeId {
         let id = node.id;
         self.nodes.insert(id, node);
+        self.revision += 1;
         id
     }
 
     /// Remove a node and its connections
     pub fn remove_node(&mut self, node_id: NodeId) -> Option<Node> {
+        self.revision += 1;
         // Remove connections involving this node
         self.connections.retain(|_, c| !c.involves_node(node_id));
         // Remove the node
-        self.nodes.swap_remove(&node_id)
+        let node = self.nodes.swap_remove(&node_id);
+        self.resolve_reroute_types();
+        node
     }
 
     /// Get a node by ID
@@ -50,9 +75,24 @@ impl Graph {
 
     /// Get a mutable node by ID
     pub fn node_mut(&mut self, node_id: NodeId) -> Option<&mut Node> {
+        self.revision += 1;
         self.nodes.get_mut(&node_id)
     }
 
+    /// Move a node. Returns true, and counts as a modification, only if
+    /// the position changed.
+    pub fn set_node_position(&mut self, node_id: NodeId, position: [f32; 2]) -> bool {
+        let Some(node) = self.nodes.get_mut(&node_id) else {
+            return false;
+        };
+        if node.position == position {
+            return false;
+        }
+        node.position = position;
+        self.revision += 1;
+        true
+    }
+
     /// Get all nodes
     pub fn nodes(&self) -> impl Iterator<Item = &Node> {
         self.nodes.values()
@@ -93,28 +133,126 @@ impl Graph {
             return Err(ConnectionError::IncompatiblePorts);
         }
 
-        // Check for existing connection to this input (if not multi-connect)
-        if !target_port.multi_connect
-            && self.connections.values().any(|c| c.to_port == to_port) {
-                return Err(ConnectionError::PortAlreadyConnected(to_port));
-            }
+        // A reroute passes the value on, so it must also suit everything
+        // already wired downstream of it
+        if target_node.is_reroute()
+            && !self
+                .reroute_consumers(to_node)
+                .iter()
+                .all(|consumer| source_port.port_type.can_connect_to(&consumer.port_type))
+        {
+            return Err(ConnectionError::IncompatiblePorts);
+        }
 
         // Prevent self-loops
         if from_node == to_node {
             return Err(ConnectionError::SelfLoop);
         }
 
-        // TODO: Check for cycles (if required by graph type)
+        // Reject edges closing a loop before the port check, so callers that
+        // replace an existing input connection don't drop it first
+        if !self.allow_cycles {
+            if let Some(path) = self.path_between(to_node, from_node) {
+                return Err(ConnectionError::WouldCreateCycle(path));
+            }
+        }
+
+        // Check for existing connection to this input (if not multi-connect)
+        if !target_port.multi_connect
+            && self.connections.values().any(|c| c.to_port == to_port) {
+                return Err(ConnectionError::PortAlreadyConnected(to_port));
+            }
 
         let connection = Connection::new(from_node, from_port, to_node, to_port);
         let id = connection.id;
         self.connections.insert(id, connection);
+        self.revision += 1;
+        self.resolve_reroute_types();
         Ok(id)
     }
 
     /// Remove a connection
     pub fn disconnect(&mut self, connection_id: ConnectionId) -> Option<Connection> {
-        self.connections.swap_remove(&connection_id)
+        self.revision += 1;
+        let connection = self.connections.swap_remove(&connection_id);
+        self.resolve_reroute_types();
+        connection
+    }
+
+    /// Split a connection with a reroute node placed at `position`.
+    ///
+    /// Returns the new node, or `None` if the connection doesn't exist.
+    pub fn insert_reroute(&mut self, connection_id: ConnectionId, position: [f32; 2]) -> Option<NodeId> {
+        let connection = self.connections.get(&connection_id)?.clone();
+        let reroute = Node::new(&NodeType::reroute()).with_position(position[0], position[1]);
+        let (input, output) = (reroute.inputs[0].id, reroute.outputs[0].id);
+        let reroute_id = self.add_node(reroute);
+
+        self.disconnect(connection_id);
+        let rewired = self
+            .connect(connection.from_node, connection.from_port, reroute_id, input)
+            .and_then(|_| self.connect(reroute_id, output, connection.to_node, connection.to_port));
+        if rewired.is_err() {
+            // Only possible for an already invalid connection; put it back
+            self.remove_node(reroute_id);
+            self.connections.insert(connection.id, connection);
+            self.resolve_reroute_types();
+            return None;
+        }
+        Some(reroute_id)
+    }
+
+    /// Input ports of non-reroute nodes fed by a reroute, following chains
+    /// of reroutes
+    fn reroute_consumers(&self, reroute: NodeId) -> Vec<&Port> {
+        let mut consumers = Vec::new();
+        let mut pending = vec![reroute];
+        let mut seen = std::collections::HashSet::new();
+        while let Some(node_id) = pending.pop() {
+            if !seen.insert(node_id) {
+                continue;
+            }
+            for connection in self.connections.values().filter(|c| c.from_node == node_id) {
+                let Some(target) = self.nodes.get(&connection.to_node) else { continue };
+                if target.is_reroute() {
+                    pending.push(target.id);
+                } else if let Some(port) = target.port(&connection.to_port) {
+                    consumers.push(port);
+                }
+            }
+        }
+        consumers
+    }
+
+    /// Give each reroute output the type of the port feeding its input, or
+    /// `Any` while unconnected
+    fn resolve_reroute_types(&mut self) {
+        // Each pass settles at least one more link of a reroute chain
+        for _ in 0..self.nodes.len() {
+            let updates: Vec<(NodeId, PortType)> = self
+                .nodes
+                .values()
+                .filter(|node| node.is_reroute())
+                .filter_map(|node| {
+                    let port_type = node
+                        .inputs
+                        .first()
+                        .and_then(|input| self.connections_to(input.id).next())
+                        .and_then(|c| self.nodes.get(&c.from_node)?.port(&c.from_port))
+                        .map_or(PortType::Any, |port| port.port_type.clone());
+                    let output = node.outputs.first()?;
+                    (output.port_type != port_type).then_some((node.id, port_type))
+                })
+                .collect();
+            if updates.is_empty() {
+                break;
+            }
+            for (node_id, port_type) in updates {
+                if let Some(output) = self.nodes.get_mut(&node_id).and_then(|node| node.outputs.first_mut()) {
+                    output.port_type = port_type;
+                }
+            }
+        }
     }
 
     /// Get a connection by ID
@@ -147,19 +285,85 @@ impl Graph {
         self.connections.len()
     }
 
+    /// Add a comment frame on top of the existing ones
+    pub fn add_comment(&mut self, comment: Comment) -> CommentId {
+        let id = comment.id;
+        self.comments.insert(id, comment);
+        self.revision += 1;
+        id
+    }
+
+    /// Remove a comment frame, leaving the nodes inside it untouched
+    pub fn remove_comment(&mut self, comment_id: CommentId) -> Option<Comment> {
+        self.revision += 1;
+        self.comments.shift_remove(&comment_id)
+    }
+
+    /// Get a comment by ID
+    pub fn comment(&self, comment_id: CommentId) -> Option<&Comment> {
+        self.comments.get(&comment_id)
+    }
+
+    /// Get a mutable comment by ID
+    pub fn comment_mut(&mut self, comment_id: CommentId) -> Option<&mut Comment> {
+        self.revision += 1;
+        self.comments.get_mut(&comment_id)
+    }
+
+    /// Get all comments, back to front
+    pub fn comments(&self) -> impl DoubleEndedIterator<Item = &Comment> {
+        self.comments.values()
+    }
+
+    /// Shortest chain of connections leading from one node to another.
+    ///
+    /// Returns the nodes along the way, both ends included, or `None` when
+    /// `to` is not downstream of `from`.
+    pub fn path_between(&self, from: NodeId, to: NodeId) -> Option<Vec<NodeId>> {
+        let mut previous = std::collections::HashMap::new();
+        let mut queue = std::collections::VecDeque::from([from]);
+        previous.insert(from, from);
+
+        while let Some(node_id) = queue.pop_front() {
+            if node_id == to {
+                let mut path = vec![to];
+                let mut current = to;
+                while current != from {
+                    current = previous[&current];
+                    path.push(current);
+                }
+                path.reverse();
+                return Some(path);
+            }
+
+            for connection in self.connections.values().filter(|c| c.from_node == node_id) {
+                if let std::collections::hash_map::Entry::Vacant(entry) = previous.entry(connection.to_node) {
+                    entry.insert(node_id);
+                    queue.push_back(connection.to_node);
+                }
+            }
+        }
+        None
+    }
+
+    /// Nodes forming a cycle, if the graph contains one
+    pub fn find_cycle(&self) -> Option<Vec<NodeId>> {
+        self.topological_order().err().map(|err| err.nodes)
+    }
+
     /// Get nodes in topological order (for evaluation)
     pub fn topological_order(&self) -> Result<Vec<NodeId>, CycleError> {
         let mut visited = std::collections::HashSet::new();
-        let mut temp_mark = std::collections::HashSet::new();
+        let mut stack = Vec::new();
         let mut order = Vec::new();
 
         for node_id in self.nodes.keys() {
             if !visited.contains(node_id) {
-                self.visit(*node_id, &mut visited, &mut temp_mark, &mut order)?;
+                self.visit(*node_id, &mut visited, &mut stack, &mut order)?;
             }
         }
 
-        order.reverse();
+        // Dependencies are pushed before their dependents
         Ok(order)
     }
 
@@ -167,26 +371,27 @@ impl Graph {
         &self,
         node_id: NodeId,
         visited: &mut std::collections::HashSet<NodeId>,
-        temp_mark: &mut std::collections::HashSet<NodeId>,
+        stack: &mut Vec<NodeId>,
         order: &mut Vec<NodeId>,
     ) -> Result<(), CycleError> {
-        if temp_mark.contains(&node_id) {
-            return Err(CycleError);
+        // The nodes still on the stack from here on depend on each other
+        if let Some(start) = stack.iter().position(|&id| id == node_id) {
+            return Err(CycleError { nodes: stack[start..].to_vec() });
         }
         if visited.contains(&node_id) {
             return Ok(());
         }
 
-        temp_mark.insert(node_id);
+        stack.push(node_id);
 
         // Visit all nodes that this node depends on
         for connection in self.connections_for_node(node_id) {
             if connection.to_node == node_id {
-                self.visit(connection.from_node, visited, temp_mark, order)?;
+                self.visit(connection.from_node, visited, stack, order)?;
             }
         }
 
-        temp_mark.remove(&node_id);
+        stack.pop();
         visited.insert(node_id);
         order.push(node_id);
 
@@ -222,9 +427,150 @@ pub enum ConnectionError {
     /// Self-loop not allowed
     #[error("Self-loop not allowed")]
     SelfLoop,
+
+    /// The connection would close a loop; holds the existing path from the
+    /// target node back to the source node
+    #[error("Connection would create a cycle through {} nodes", .0.len())]
+    WouldCreateCycle(Vec<NodeId>),
 }
 
 /// Error when graph contains a cycle
 #[derive(Debug, thiserror::Error)]
-#[error("Graph contains a cycle")]
-pub struct CycleError;
+#[error("Graph contains a cycle through {} nodes", .nodes.len())]
+pub struct CycleError {
+    /// Nodes on the cycle
+    pub nodes: Vec<NodeId>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node::{NodeCategory, NodeType};
+    use crate::port::{Port, PortType};
+
+    fn pass_node(id: &str) -> Node {
+        Node::new(&NodeType {
+            id: id.to_string(),
+            name: id.to_string(),
+            category: NodeCategory::Utility,
+            description: String::new(),
+            inputs: vec![Port::input("In", PortType::Float)],
+            outputs: vec![Port::output("Out", PortType::Float)],
+        })
+    }
+
+    fn link(graph: &mut Graph, from: NodeId, to: NodeId) -> Result<ConnectionId, ConnectionError> {
+        let out = graph.node(from).unwrap().outputs[0].id;
+        let input = graph.node(to).unwrap().inputs[0].id;
+        graph.connect(from, out, to, input)
+    }
+
+    fn typed(port_type: PortType) -> Node {
+        Node::new(&NodeType {
+            id: "typed".to_string(),
+            name: "Typed".to_string(),
+            category: NodeCategory::Utility,
+            description: String::new(),
+            inputs: vec![Port::input("In", port_type.clone())],
+            outputs: vec![Port::output("Out", port_type)],
+        })
+    }
+
+    #[test]
+    fn test_reroute_mirrors_input_type() {
+        let mut graph = Graph::new("Reroute");
+        let float = graph.add_node(typed(PortType::Float));
+        let sink = graph.add_node(typed(PortType::Float));
+        let connection = link(&mut graph, float, sink).unwrap();
+
+        let first = graph.insert_reroute(connection, [50.0, 0.0]).unwrap();
+        let sink_input = graph.node(sink).unwrap().inputs[0].id;
+        let last_leg = graph.connections_to(sink_input).next().unwrap().id;
+        let second = graph.insert_reroute(last_leg, [80.0, 0.0]).unwrap();
+        assert_eq!(graph.connection_count(), 3);
+        assert_eq!(graph.path_between(float, sink), Some(vec![float, first, second, sink]));
+        for reroute in [first, second] {
+            assert_eq!(graph.node(reroute).unwrap().outputs[0].port_type, PortType::Float);
+        }
+
+        // The chain still only accepts values its consumer can take
+        let texture = graph.add_node(typed(PortType::Texture));
+        let first_input = graph.node(first).unwrap().inputs[0].id;
+        let existing = graph.connections_to(first_input).next().unwrap().id;
+        graph.disconnect(existing);
+        assert_eq!(graph.node(second).unwrap().outputs[0].port_type, PortType::Any);
+        assert!(matches!(link(&mut graph, texture, first), Err(ConnectionError::IncompatiblePorts)));
+        link(&mut graph, float, first).unwrap();
+
+        // Typed reroute outputs are validated like any other port
+        let other = graph.add_node(typed(PortType::Texture));
+        assert!(matches!(link(&mut graph, second, other), Err(ConnectionError::IncompatiblePorts)));
+    }
+
+    #[test]
+    fn test_topological_order_puts_dependencies_first() {
+        let mut graph = Graph::new("Test");
+        // Added sink first so insertion order disagrees with evaluation order
+        let [sink, middle, source] = ["sink", "middle", "source"].map(|id| graph.add_node(pass_node(id)));
+        let link = |graph: &mut Graph, from: NodeId, to: NodeId| {
+            let from_port = graph.node(from).unwrap().outputs[0].id;
+            let to_port = graph.node(to).unwrap().inputs[0].id;
+            graph.connect(from, from_port, to, to_port).unwrap();
+        };
+        link(&mut graph, source, middle);
+        link(&mut graph, middle, sink);
+
+        assert_eq!(graph.topological_order().unwrap(), vec![source, middle, sink]);
+    }
+
+    #[test]
+    fn test_only_real_moves_change_revision() {
+        let mut graph = Graph::new("Test");
+        let node = graph.add_node(pass_node("node"));
+        let revision = graph.revision();
+
+        // Moving a node onto its own position is not a change
+        let position = graph.node(node).unwrap().position;
+        assert!(!graph.set_node_position(node, position));
+        assert_eq!(graph.revision(), revision);
+        assert!(graph.set_node_position(node, [position[0] + 10.0, position[1]]));
+        assert_ne!(graph.revision(), revision);
+    }
+
+    #[test]
+    fn test_connect_rejects_cycle() {
+        let mut graph = Graph::new("Cycle");
+        let [a, b, c] = ["a", "b", "c"].map(|id| graph.add_node(pass_node(id)));
+        link(&mut graph, a, b).unwrap();
+        link(&mut graph, b, c).unwrap();
+
+        let revision = graph.revision();
+        match link(&mut graph, c, a) {
+            Err(ConnectionError::WouldCreateCycle(path)) => assert_eq!(path, vec![a, b, c]),
+            other => panic!("expected a cycle error, got {other:?}"),
+        }
+        assert_eq!(graph.connection_count(), 2);
+        assert_eq!(graph.revision(), revision);
+        assert!(graph.find_cycle().is_none());
+
+        graph.allow_cycles = true;
+        link(&mut graph, c, a).unwrap();
+        let mut cycle = graph.find_cycle().unwrap();
+        cycle.sort_by_key(|id| id.0);
+        let mut expected = vec![a, b, c];
+        expected.sort_by_key(|id| id.0);
+        assert_eq!(cycle, expected);
+    }
+
+    #[test]
+    fn test_cycle_is_rejected_before_an_occupied_input() {
+        let mut graph = Graph::new("Cycle");
+        let [a, b, c] = ["a", "b", "c"].map(|id| graph.add_node(pass_node(id)));
+        link(&mut graph, a, b).unwrap();
+        link(&mut graph, c, a).unwrap();
+
+        // a's input is taken, but the loop is what the caller needs to
+        // hear about: replacing the input would not help
+        assert!(matches!(link(&mut graph, b, a), Err(ConnectionError::WouldCreateCycle(_))));
+    }
+}
@@ -3,8 +3,15 @@
 //!
 //! Supports execution flow and data flow.
 
-use crate::node::{NodeCategory, NodeRegistry, NodeType};
-use crate::port::{Port, PortDirection, PortId, PortType};
+use crate::evaluation::{EvaluationContext, EvaluationError, EvaluationStepper, NodeEvaluator, NodeOutput};
+use crate::graph::Graph;
+use crate::node::{NodeCategory, NodeId, NodeRegistry, NodeType};
+use crate::port::{Port, PortDirection, PortId, PortType, PortValue};
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+
+/// Frame time reported by `event_tick` in previews
+const PREVIEW_DELTA_TIME: f32 = 1.0 / 60.0;
 
 /// Create the gameplay graph node registry
 pub fn create_gameplay_registry() -> NodeRegistry {
@@ -34,6 +41,58 @@ pub fn create_gameplay_registry() -> NodeRegistry {
         ],
     });
 
+    registry.register(NodeType {
+        id: "event_input_action".to_string(),
+        name: "Event Input Action".to_string(),
+        category: NodeCategory::Input,
+        description: "Triggered when a named input action is pressed or released".to_string(),
+        inputs: vec![
+            Port::new(PortId::new(), "Action", PortType::String, PortDirection::Input),
+        ],
+        outputs: vec![
+            Port::new(PortId::new(), "Pressed", PortType::Exec, PortDirection::Output),
+            Port::new(PortId::new(), "Released", PortType::Exec, PortDirection::Output),
+            Port::new(PortId::new(), "Action", PortType::String, PortDirection::Output),
+        ],
+    });
+
+    registry.register(NodeType {
+        id: "is_action_pressed".to_string(),
+        name: "Is Action Pressed".to_string(),
+        category: NodeCategory::Input,
+        description: "Check if a named input action is held down".to_string(),
+        inputs: vec![
+            Port::new(PortId::new(), "Action", PortType::String, PortDirection::Input),
+        ],
+        outputs: vec![
+            Port::new(PortId::new(), "Pressed", PortType::Bool, PortDirection::Output),
+        ],
+    });
+
+    // Physics
+    registry.register(NodeType {
+        id: "raycast".to_string(),
+        name: "Raycast".to_string(),
+        category: NodeCategory::Utility,
+        description: "Cast a ray against the scene's colliders and return the closest hit".to_string(),
+        inputs: vec![
+            Port::new(PortId::new(), "Origin", PortType::Vector3, PortDirection::Input),
+            Port::new(PortId::new(), "Direction", PortType::Vector3, PortDirection::Input)
+                .with_default(PortValue::Vector3([0.0, -1.0, 0.0])),
+            Port::new(PortId::new(), "Max Distance", PortType::Float, PortDirection::Input)
+                .with_default(PortValue::Float(100.0)),
+            Port::new(PortId::new(), "Hit Triggers", PortType::Bool, PortDirection::Input)
+                .with_default(PortValue::Bool(false)),
+        ],
+        outputs: vec![
+            Port::new(PortId::new(), "Hit", PortType::Bool, PortDirection::Output),
+            Port::new(PortId::new(), "Entity", PortType::Entity, PortDirection::Output),
+            Port::new(PortId::new(), "Point", PortType::Vector3, PortDirection::Output),
+            Port::new(PortId::new(), "Normal", PortType::Vector3, PortDirection::Output),
+            Port::new(PortId::new(), "Distance", PortType::Float, PortDirection::Output),
+        ],
+    });
+
     // Flow control
     registry.register(NodeType {
         id: "branch".to_string(),
@@ -67,3 +126,358 @@ pub fn create_gameplay_registry() -> NodeRegistry {
 
     registry
 }
+
+/// Input actions seen by a preview run, by action name
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ActionInput {
+    /// Actions held down this frame
+    pub held: HashSet<String>,
+    /// Actions that went down this frame
+    pub pressed: HashSet<String>,
+    /// Actions released this frame
+    pub released: HashSet<String>,
+}
+
+/// Closest collider hit reported to a `raycast` node
+#[derive(Debug, Clone, PartialEq)]
+pub struct RayHit {
+    /// Id of the entity that was hit
+    pub entity: String,
+    /// World-space hit point
+    pub point: [f32; 3],
+    /// Surface normal at the hit point
+    pub normal: [f32; 3],
+    /// Distance from the ray origin
+    pub distance: f32,
+}
+
+/// Scene queries available to gameplay previews
+pub trait GameplayWorld {
+    /// Changes whenever query results may have changed
+    fn revision(&self) -> u64;
+
+    /// Cast a ray against the scene's colliders and return the closest hit
+    fn raycast(&self, origin: [f32; 3], direction: [f32; 3], max_distance: f32, hit_triggers: bool) -> Option<RayHit>;
+}
+
+/// Evaluates gameplay nodes for the editor preview, as if gameplay had just
+/// started with the given input actions. Raycasts query the scene when
+/// one is attached and hit nothing otherwise.
+///
+/// Exec outputs that fire carry `Bool(true)`; nodes downstream of an exec
+/// input only act when it fired.
+#[derive(Default)]
+pub struct GameplayPreviewEvaluator<'w> {
+    /// Input actions this run
+    input: ActionInput,
+    /// Scene raycasts are cast against
+    world: Option<&'w dyn GameplayWorld>,
+    /// Strings printed by `print_string` nodes, in execution order
+    printed: RefCell<Vec<String>>,
+}
+
+impl<'w> GameplayPreviewEvaluator<'w> {
+    /// Create a new evaluator with no input held and nothing printed
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an evaluator that sees the given input actions
+    pub fn with_input(input: ActionInput) -> Self {
+        Self {
+            input,
+            ..Self::default()
+        }
+    }
+
+    /// Cast `raycast` nodes against `world`
+    pub fn with_world(mut self, world: &'w dyn GameplayWorld) -> Self {
+        self.world = Some(world);
+        self
+    }
+
+    /// Take the strings printed so far
+    pub fn take_printed(&self) -> Vec<String> {
+        self.printed.take()
+    }
+}
+
+impl NodeEvaluator for GameplayPreviewEvaluator<'_> {
+    fn evaluate(&self, node_id: NodeId, ctx: &mut EvaluationContext) -> Result<NodeOutput, EvaluationError> {
+        let node = ctx.graph.node(node_id).ok_or(EvaluationError::NodeNotFound(node_id))?;
+        let input = |name: &str| {
+            let port = node.inputs.iter().find(|port| port.name == name)?;
+            ctx.get_input_or_default(node_id, port.id)
+        };
+        let fired = matches!(input("Exec"), Some(PortValue::Bool(true)));
+        let action = match input("Action") {
+            Some(PortValue::String(action)) => action.as_str(),
+            _ => "",
+        };
+
+        let mut output = NodeOutput::new();
+        let mut set = |name: &str, value: PortValue| {
+            if let Some(port) = node.outputs.iter().find(|port| port.name == name) {
+                output.set(port.id, value);
+            }
+        };
+        let pulse = || PortValue::Bool(true);
+
+        match node.node_type.as_str() {
+            "event_begin_play" => set("Exec", pulse()),
+            "event_tick" => {
+                set("Exec", pulse());
+                set("Delta Time", PortValue::Float(PREVIEW_DELTA_TIME));
+            }
+            "event_input_action" => {
+                if let Some(name) = input("Action") {
+                    set("Action", name.clone());
+                }
+                if self.input.pressed.contains(action) {
+                    set("Pressed", pulse());
+                }
+                if self.input.released.contains(action) {
+                    set("Released", pulse());
+                }
+            }
+            "is_action_pressed" => set("Pressed", PortValue::Bool(self.input.held.contains(action))),
+            "raycast" => {
+                let vector = |name: &str, default: [f32; 3]| match input(name) {
+                    Some(PortValue::Vector3(v)) => *v,
+                    _ => default,
+                };
+                let max_distance = match input("Max Distance") {
+                    Some(PortValue::Float(distance)) => *distance,
+                    _ => 100.0,
+                };
+                let hit_triggers = matches!(input("Hit Triggers"), Some(PortValue::Bool(true)));
+                let hit = self.world.and_then(|world| {
+                    world.raycast(vector("Origin", [0.0; 3]), vector("Direction", [0.0, -1.0, 0.0]), max_distance, hit_triggers)
+                });
+
+                set("Hit", PortValue::Bool(hit.is_some()));
+                if let Some(hit) = hit {
+                    set("Entity", PortValue::String(hit.entity));
+                    set("Point", PortValue::Vector3(hit.point));
+                    set("Normal", PortValue::Vector3(hit.normal));
+                    set("Distance", PortValue::Float(hit.distance));
+                }
+            }
+            "branch" if fired => {
+                let condition = matches!(input("Condition"), Some(PortValue::Bool(true)));
+                set(if condition { "True" } else { "False" }, pulse());
+            }
+            "print_string" if fired => {
+                let text = input("String").map(ToString::to_string).unwrap_or_default();
+                self.printed.borrow_mut().push(text);
+                set("Exec", pulse());
+            }
+            _ => {}
+        }
+        Ok(output)
+    }
+}
+
+/// Live value preview of a gameplay graph, re-run whenever the graph changes
+#[derive(Debug, Default)]
+pub struct GameplayPreview {
+    /// Graph revision of the last run
+    revision: Option<u64>,
+    /// Input actions of the last run
+    input: ActionInput,
+    /// World revision of the last run
+    world_revision: Option<u64>,
+    /// Strings printed by the last run
+    printed: Vec<String>,
+    /// Why the last run failed, e.g. a cycle
+    pub error: Option<String>,
+}
+
+impl GameplayPreview {
+    /// Create a preview that runs on the first update
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Re-run the graph if it, the input actions or the world changed since
+    /// the last update, replacing `values` with the value recorded on each port.
+    ///
+    /// Returns the printed strings when they differ from the previous run,
+    /// so moving nodes around doesn't repeat them in the console.
+    pub fn update(
+        &mut self,
+        graph: &Graph,
+        input: &ActionInput,
+        world: Option<&dyn GameplayWorld>,
+        values: &mut HashMap<PortId, PortValue>,
+    ) -> Option<Vec<String>> {
+        let world_revision = world.map(GameplayWorld::revision);
+        if self.revision == Some(graph.revision()) && self.input == *input && self.world_revision == world_revision {
+            return None;
+        }
+        self.revision = Some(graph.revision());
+        self.input.clone_from(input);
+        self.world_revision = world_revision;
+
+        let mut evaluator = GameplayPreviewEvaluator::with_input(input.clone());
+        if let Some(world) = world {
+            evaluator = evaluator.with_world(world);
+        }
+        let result = EvaluationStepper::new(graph, HashMap::new()).and_then(|mut stepper| {
+            stepper.run(&evaluator)?;
+            Ok(stepper.into_port_values())
+        });
+        match result {
+            Ok(port_values) => {
+                *values = port_values;
+                self.error = None;
+            }
+            Err(err) => {
+                values.clear();
+                self.error = Some(err.to_string());
+                return None;
+            }
+        }
+
+        let printed = evaluator.take_printed();
+        if printed == self.printed {
+            return None;
+        }
+        self.printed.clone_from(&printed);
+        Some(printed)
+    }
+
+    /// Forget the last run, so the next update runs and reports again
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preview_prints_on_begin_play() {
+        let registry = create_gameplay_registry();
+        let mut graph = Graph::new("Preview");
+
+        let begin = registry.create_node("event_begin_play").unwrap();
+        let begin_exec = begin.outputs[0].id;
+        let begin = graph.add_node(begin);
+
+        let mut print = registry.create_node("print_string").unwrap();
+        print.inputs[1].default_value = Some(PortValue::String("Hello".to_string()));
+        let (print_exec, print_string) = (print.inputs[0].id, print.inputs[1].id);
+        let print = graph.add_node(print);
+
+        // Not reached by any exec flow yet
+        let mut preview = GameplayPreview::new();
+        let mut values = HashMap::new();
+        assert_eq!(preview.update(&graph, &ActionInput::default(), None, &mut values), None);
+        assert!(!values.contains_key(&print_exec));
+
+        graph.connect(begin, begin_exec, print, print_exec).unwrap();
+        assert_eq!(preview.update(&graph, &ActionInput::default(), None, &mut values), Some(vec!["Hello".to_string()]));
+        assert!(matches!(values.get(&print_exec), Some(PortValue::Bool(true))));
+        assert!(matches!(values.get(&print_string), Some(PortValue::String(text)) if text == "Hello"));
+
+        // Unchanged output isn't reported again
+        assert!(graph.set_node_position(print, [50.0, 0.0]));
+        assert_eq!(preview.update(&graph, &ActionInput::default(), None, &mut values), None);
+        assert!(preview.error.is_none());
+    }
+
+    #[test]
+    fn test_preview_follows_input_actions() {
+        let registry = create_gameplay_registry();
+        let mut graph = Graph::new("Input");
+
+        let mut event = registry.create_node("event_input_action").unwrap();
+        event.inputs[0].default_value = Some(PortValue::String("Jump".to_string()));
+        let (event_pressed, event_released) = (event.outputs[0].id, event.outputs[1].id);
+        let event = graph.add_node(event);
+
+        let mut held = registry.create_node("is_action_pressed").unwrap();
+        held.inputs[0].default_value = Some(PortValue::String("Jump".to_string()));
+        let held_out = held.outputs[0].id;
+        graph.add_node(held);
+
+        let mut print = registry.create_node("print_string").unwrap();
+        print.inputs[1].default_value = Some(PortValue::String("Jumped".to_string()));
+        let print_exec = print.inputs[0].id;
+        let print = graph.add_node(print);
+        graph.connect(event, event_pressed, print, print_exec).unwrap();
+
+        let mut preview = GameplayPreview::new();
+        let mut values = HashMap::new();
+        assert_eq!(preview.update(&graph, &ActionInput::default(), None, &mut values), None);
+        assert!(matches!(values.get(&held_out), Some(PortValue::Bool(false))));
+
+        // Same graph, new input: the preview re-runs
+        let jump = ActionInput {
+            held: HashSet::from(["Jump".to_string()]),
+            pressed: HashSet::from(["Jump".to_string()]),
+            released: HashSet::new(),
+        };
+        assert_eq!(preview.update(&graph, &jump, None, &mut values), Some(vec!["Jumped".to_string()]));
+        assert!(matches!(values.get(&held_out), Some(PortValue::Bool(true))));
+        assert!(!values.contains_key(&event_released));
+
+        let released = ActionInput {
+            released: HashSet::from(["Jump".to_string()]),
+            ..Default::default()
+        };
+        preview.update(&graph, &released, None, &mut values);
+        assert!(matches!(values.get(&event_released), Some(PortValue::Bool(true))));
+        assert!(matches!(values.get(&held_out), Some(PortValue::Bool(false))));
+    }
+
+    /// A floor plane at y = 0 owned by entity "floor"
+    struct Floor {
+        revision: u64,
+    }
+
+    impl GameplayWorld for Floor {
+        fn revision(&self) -> u64 {
+            self.revision
+        }
+
+        fn raycast(&self, origin: [f32; 3], direction: [f32; 3], max_distance: f32, _: bool) -> Option<RayHit> {
+            let distance = -origin[1] / direction[1];
+            (distance >= 0.0 && distance <= max_distance).then(|| RayHit {
+                entity: "floor".to_string(),
+                point: [origin[0] + direction[0] * distance, 0.0, origin[2] + direction[2] * distance],
+                normal: [0.0, 1.0, 0.0],
+                distance,
+            })
+        }
+    }
+
+    #[test]
+    fn test_raycast_node_queries_the_world() {
+        let registry = create_gameplay_registry();
+        let mut graph = Graph::new("Raycast");
+        let mut raycast = registry.create_node("raycast").unwrap();
+        raycast.inputs[0].default_value = Some(PortValue::Vector3([2.0, 5.0, 0.0]));
+        let [hit, entity, point, _, distance] = [0, 1, 2, 3, 4].map(|i| raycast.outputs[i].id);
+        let raycast = graph.add_node(raycast);
+
+        let mut preview = GameplayPreview::new();
+        let mut values = HashMap::new();
+        preview.update(&graph, &ActionInput::default(), None, &mut values);
+        assert!(matches!(values.get(&hit), Some(PortValue::Bool(false))));
+
+        let floor = Floor { revision: 1 };
+        preview.update(&graph, &ActionInput::default(), Some(&floor), &mut values);
+        assert!(matches!(values.get(&hit), Some(PortValue::Bool(true))));
+        assert!(matches!(values.get(&entity), Some(PortValue::String(name)) if name == "floor"));
+        assert!(matches!(values.get(&point), Some(PortValue::Vector3(p)) if *p == [2.0, 0.0, 0.0]));
+        assert!(matches!(values.get(&distance), Some(PortValue::Float(d)) if (*d - 5.0).abs() < 1e-5));
+
+        // Out of reach once the ray is shortened
+        graph.node_mut(raycast).unwrap().inputs[2].default_value = Some(PortValue::Float(1.0));
+        preview.update(&graph, &ActionInput::default(), Some(&floor), &mut values);
+        assert!(matches!(values.get(&hit), Some(PortValue::Bool(false))));
+    }
+}
@@ -4,8 +4,11 @@
 //! Provides a comprehensive set of nodes for creating PBR materials
 //! compatible with `ordoplay_materialx` for runtime compilation to WGSL.
 
-use crate::node::{NodeCategory, NodeRegistry, NodeType};
-use crate::port::{Port, PortType, PortValue};
+use crate::evaluation::{EvaluationContext, EvaluationError, NodeEvaluator, NodeOutput};
+use crate::graph::Graph;
+use crate::node::{Node, NodeCategory, NodeId, NodeRegistry, NodeType, REROUTE_NODE_TYPE};
+use crate::port::{Port, PortId, PortType, PortValue};
+use std::collections::{HashMap, HashSet};
 
 /// Create the material graph node registry with all available node types
 pub fn create_material_registry() -> NodeRegistry {
@@ -836,10 +839,710 @@ pub fn create_material_registry() -> NodeRegistry {
     registry
 }
 
+/// CPU approximation of a material graph used for editor previews.
+///
+/// Outputs with a default value produce it, the basic arithmetic nodes
+/// combine their inputs componentwise, and every other node passes its
+/// first input that matches the output type through unchanged.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MaterialPreviewEvaluator;
+
+impl NodeEvaluator for MaterialPreviewEvaluator {
+    fn evaluate(&self, node_id: NodeId, ctx: &mut EvaluationContext) -> Result<NodeOutput, EvaluationError> {
+        let node = ctx.graph.node(node_id).ok_or(EvaluationError::NodeNotFound(node_id))?;
+        let inputs: Vec<Option<&PortValue>> = node
+            .inputs
+            .iter()
+            .map(|port| ctx.get_input_or_default(node_id, port.id))
+            .collect();
+
+        let mut output = NodeOutput::new();
+        for port in &node.outputs {
+            let value = port.default_value.clone().or_else(|| {
+                let binary = |op: fn(f32, f32) -> f32| match inputs.as_slice() {
+                    [Some(a), Some(b), ..] => combine(a, b, op),
+                    _ => None,
+                };
+                match node.node_type.as_str() {
+                    "add" => binary(|a, b| a + b),
+                    "subtract" => binary(|a, b| a - b),
+                    "multiply" => binary(|a, b| a * b),
+                    "divide" => binary(|a, b| if b == 0.0 { 0.0 } else { a / b }),
+                    _ => inputs
+                        .iter()
+                        .flatten()
+                        .find(|value| port.port_type == PortType::Any || value.port_type() == port.port_type)
+                        .map(|value| (*value).clone()),
+                }
+            });
+
+            if let Some(value) = value {
+                output.set(port.id, value);
+            }
+        }
+        Ok(output)
+    }
+}
+
+fn lanes(value: &PortValue) -> Option<&[f32]> {
+    match value {
+        PortValue::Float(v) => Some(std::slice::from_ref(v)),
+        PortValue::Vector2(v) => Some(v),
+        PortValue::Vector3(v) => Some(v),
+        PortValue::Vector4(v) | PortValue::Color(v) => Some(v),
+        _ => None,
+    }
+}
+
+/// Apply `op` componentwise, broadcasting scalars over vectors
+fn combine(a: &PortValue, b: &PortValue, op: fn(f32, f32) -> f32) -> Option<PortValue> {
+    let (lhs, rhs) = (lanes(a)?, lanes(b)?);
+    if lhs.len() != rhs.len() && lhs.len() != 1 && rhs.len() != 1 {
+        return None;
+    }
+
+    let mut result = if lhs.len() >= rhs.len() { a.clone() } else { b.clone() };
+    let count = lhs.len().max(rhs.len());
+    let lane = |values: &[f32], i: usize| values[i.min(values.len() - 1)];
+    let out: Vec<f32> = (0..count).map(|i| op(lane(lhs, i), lane(rhs, i))).collect();
+
+    match &mut result {
+        PortValue::Float(v) => *v = out[0],
+        PortValue::Vector2(v) => v.copy_from_slice(&out),
+        PortValue::Vector3(v) => v.copy_from_slice(&out),
+        PortValue::Vector4(v) | PortValue::Color(v) => v.copy_from_slice(&out),
+        _ => return None,
+    }
+    Some(result)
+}
+
+/// Cached preview evaluation of a material graph, re-run only when the
+/// graph changes
+#[derive(Debug, Default)]
+pub struct MaterialPreview {
+    /// Graph revision of the last run
+    revision: Option<u64>,
+    /// Outputs of every node in the last run, empty if it failed
+    outputs: HashMap<NodeId, NodeOutput>,
+}
+
+impl MaterialPreview {
+    /// Create a preview that runs on the first update
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Re-evaluate the graph if it changed since the last update and
+    /// return the output of every node
+    pub fn update(&mut self, graph: &Graph) -> &HashMap<NodeId, NodeOutput> {
+        if self.revision != Some(graph.revision()) {
+            self.revision = Some(graph.revision());
+            self.outputs = EvaluationContext::new(graph)
+                .and_then(|ctx| ctx.run(&MaterialPreviewEvaluator))
+                .unwrap_or_default();
+        }
+        &self.outputs
+    }
+
+    /// Forget the last run, e.g. after replacing the graph
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// Bind group the textures of a compiled material are declared in
+pub const MATERIAL_BIND_GROUP: u32 = 2;
+
+/// Structs shared by every compiled material
+const MATERIAL_STRUCTS: &str = "struct MaterialInput {
+    uv: vec2<f32>,
+    world_position: vec3<f32>,
+    world_normal: vec3<f32>,
+    view_direction: vec3<f32>,
+    vertex_color: vec4<f32>,
+    time: f32,
+    delta_time: f32,
+}
+
+struct MaterialOutput {
+    base_color: vec4<f32>,
+    metallic: f32,
+    roughness: f32,
+    normal: vec3<f32>,
+    emission: vec3<f32>,
+    opacity: f32,
+    occlusion: f32,
+}
+";
+
+/// `material_output` inputs and the `MaterialOutput` fields they fill.
+/// Emission Strength is folded into `emission`.
+const OUTPUT_FIELDS: [(&str, &str, WgslType); 7] = [
+    ("Base Color", "base_color", WgslType::Vec4),
+    ("Metallic", "metallic", WgslType::F32),
+    ("Roughness", "roughness", WgslType::F32),
+    ("Normal", "normal", WgslType::Vec3),
+    ("Emission", "emission", WgslType::Vec3),
+    ("Opacity", "opacity", WgslType::F32),
+    ("Ambient Occlusion", "occlusion", WgslType::F32),
+];
+
+/// Error compiling a material graph to WGSL
+#[derive(Debug, thiserror::Error)]
+pub enum CompileError {
+    /// The graph has no material output node
+    #[error("Material graph has no Material Output node")]
+    MissingOutput,
+
+    /// A node feeds back into itself
+    #[error("Material graph contains a cycle through node {0:?}")]
+    CycleDetected(NodeId),
+
+    /// A connection references a node that is not in the graph
+    #[error("Node not found: {0:?}")]
+    NodeNotFound(NodeId),
+
+    /// The node type has no WGSL translation
+    #[error("Node '{name}' ({node_type}) cannot be compiled to WGSL")]
+    UnsupportedNode {
+        /// Offending node
+        node: NodeId,
+        /// Display name of the node
+        name: String,
+        /// Node type ID
+        node_type: String,
+    },
+}
+
+/// Compile a material graph into a WGSL fragment-shader snippet.
+///
+/// The nodes feeding the `material_output` node are emitted in dependency
+/// order into `fn evaluate_material(input: MaterialInput) -> MaterialOutput`.
+/// The snippet also declares both structs and a texture/sampler pair in
+/// [`MATERIAL_BIND_GROUP`] for every sampled texture.
+///
+/// Unconnected inputs use their default value. Inputs without one read the
+/// matching mesh attribute (UV, normal, position) or fall back to zero.
+pub fn compile_to_wgsl(graph: &Graph) -> Result<String, CompileError> {
+    let output = graph
+        .nodes()
+        .find(|node| node.node_type == "material_output")
+        .ok_or(CompileError::MissingOutput)?;
+
+    let mut compiler = WgslCompiler::new(graph);
+    let mut fields = Vec::with_capacity(OUTPUT_FIELDS.len());
+    for (port_name, field, ty) in OUTPUT_FIELDS {
+        let mut value = compiler.input_named(output, port_name, ty)?;
+        if field == "emission" {
+            let strength = compiler.input_named(output, "Emission Strength", WgslType::F32)?;
+            value = Expr::new(format!("({} * {})", value.code, strength.code), ty);
+        }
+        fields.push((field, value));
+    }
+
+    let mut wgsl = String::from(MATERIAL_STRUCTS);
+    if !compiler.bindings.is_empty() {
+        wgsl.push('\n');
+        wgsl.push_str(&compiler.bindings);
+    }
+    wgsl.push_str("\nfn evaluate_material(input: MaterialInput) -> MaterialOutput {\n");
+    wgsl.push_str(&compiler.body);
+    wgsl.push_str("    var result: MaterialOutput;\n");
+    for (field, value) in fields {
+        wgsl.push_str(&format!("    result.{field} = {};\n", value.code));
+    }
+    wgsl.push_str("    return result;\n}\n");
+    Ok(wgsl)
+}
+
+/// WGSL value type of a compiled expression
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum WgslType {
+    F32,
+    Vec2,
+    Vec3,
+    Vec4,
+}
+
+impl WgslType {
+    fn of_port(port_type: &PortType) -> Option<Self> {
+        match port_type {
+            PortType::Bool | PortType::Int | PortType::Float => Some(Self::F32),
+            PortType::Vector2 => Some(Self::Vec2),
+            PortType::Vector3 => Some(Self::Vec3),
+            PortType::Vector4 | PortType::Color => Some(Self::Vec4),
+            _ => None,
+        }
+    }
+
+    fn from_lanes(lanes: usize) -> Self {
+        match lanes {
+            0 | 1 => Self::F32,
+            2 => Self::Vec2,
+            3 => Self::Vec3,
+            _ => Self::Vec4,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::F32 => "f32",
+            Self::Vec2 => "vec2<f32>",
+            Self::Vec3 => "vec3<f32>",
+            Self::Vec4 => "vec4<f32>",
+        }
+    }
+}
+
+/// A WGSL expression and its type
+#[derive(Debug, Clone)]
+struct Expr {
+    code: String,
+    ty: WgslType,
+}
+
+impl Expr {
+    fn new(code: impl Into<String>, ty: WgslType) -> Self {
+        Self { code: code.into(), ty }
+    }
+
+    fn float(value: f32) -> Self {
+        Self::new(literal(value), WgslType::F32)
+    }
+
+    fn vector(values: &[f32]) -> Self {
+        let ty = WgslType::from_lanes(values.len());
+        let args: Vec<String> = values.iter().map(|&v| literal(v)).collect();
+        Self::new(format!("{}({})", ty.name(), args.join(", ")), ty)
+    }
+
+    fn zero(ty: WgslType) -> Self {
+        match ty {
+            WgslType::F32 => Self::float(0.0),
+            _ => Self::new(format!("{}(0.0)", ty.name()), ty),
+        }
+    }
+
+    /// Literal for a port value; strings have none
+    fn value(value: &PortValue) -> Option<Self> {
+        match value {
+            PortValue::Bool(b) => Some(Self::float(if *b { 1.0 } else { 0.0 })),
+            PortValue::Int(i) => Some(Self::float(*i as f32)),
+            PortValue::Float(f) => Some(Self::float(*f)),
+            PortValue::Vector2(v) => Some(Self::vector(v)),
+            PortValue::Vector3(v) => Some(Self::vector(v)),
+            PortValue::Vector4(v) | PortValue::Color(v) => Some(Self::vector(v)),
+            PortValue::String(_) => None,
+        }
+    }
+
+    fn swizzle(&self, components: &str) -> Self {
+        Self::new(format!("{}.{components}", self.code), WgslType::from_lanes(components.len()))
+    }
+
+    /// Convert to `ty`: scalars splat, vectors are truncated or padded with
+    /// zero (and one for w)
+    fn cast(&self, ty: WgslType) -> Self {
+        let code = &self.code;
+        let code = match (self.ty, ty) {
+            (from, to) if from == to => return self.clone(),
+            (WgslType::F32, to) => format!("{}({code})", to.name()),
+            (_, WgslType::F32) => format!("{code}.x"),
+            (WgslType::Vec2, WgslType::Vec3) => format!("vec3<f32>({code}, 0.0)"),
+            (WgslType::Vec2, WgslType::Vec4) => format!("vec4<f32>({code}, 0.0, 1.0)"),
+            (WgslType::Vec3, WgslType::Vec4) => format!("vec4<f32>({code}, 1.0)"),
+            (_, WgslType::Vec2) => format!("{code}.xy"),
+            (_, _) => format!("{code}.xyz"),
+        };
+        Self::new(code, ty)
+    }
+
+    /// Whether the expression is a plain name that needs no temporary
+    fn is_name(&self) -> bool {
+        self.code.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+    }
+}
+
+fn literal(value: f32) -> String {
+    if !value.is_finite() {
+        "0.0".to_string()
+    } else if value < 0.0 {
+        format!("({value:?})")
+    } else {
+        format!("{value:?}")
+    }
+}
+
+/// Widen two expressions to the larger of their types
+fn widen(a: Expr, b: Expr) -> (Expr, Expr) {
+    let ty = a.ty.max(b.ty);
+    (a.cast(ty), b.cast(ty))
+}
+
+/// Emits the nodes a material output depends on as WGSL statements
+struct WgslCompiler<'a> {
+    graph: &'a Graph,
+    /// Statements of `evaluate_material`
+    body: String,
+    /// Texture and sampler declarations
+    bindings: String,
+    /// Texture/sampler pairs declared so far
+    textures: u32,
+    /// Temporaries declared so far
+    temps: usize,
+    /// Expressions of compiled output ports
+    outputs: HashMap<PortId, Expr>,
+    /// Nodes already emitted
+    compiled: HashSet<NodeId>,
+    /// Nodes on the current dependency path
+    visiting: HashSet<NodeId>,
+}
+
+impl<'a> WgslCompiler<'a> {
+    fn new(graph: &'a Graph) -> Self {
+        Self {
+            graph,
+            body: String::new(),
+            bindings: String::new(),
+            textures: 0,
+            temps: 0,
+            outputs: HashMap::new(),
+            compiled: HashSet::new(),
+            visiting: HashSet::new(),
+        }
+    }
+
+    /// Emit a node after everything it depends on
+    fn compile_node(&mut self, node_id: NodeId) -> Result<(), CompileError> {
+        if self.compiled.contains(&node_id) {
+            return Ok(());
+        }
+        if !self.visiting.insert(node_id) {
+            return Err(CompileError::CycleDetected(node_id));
+        }
+
+        let graph = self.graph;
+        let node = graph.node(node_id).ok_or(CompileError::NodeNotFound(node_id))?;
+        let values = self.emit(node)?;
+        for (port, value) in node.outputs.iter().zip(values) {
+            let value = if value.is_name() { value } else { self.temp(value) };
+            self.outputs.insert(port.id, value);
+        }
+
+        self.visiting.remove(&node_id);
+        self.compiled.insert(node_id);
+        Ok(())
+    }
+
+    /// Bind `value` to a new `let` and refer to it by name
+    fn temp(&mut self, value: Expr) -> Expr {
+        let name = format!("v{}", self.temps);
+        self.temps += 1;
+        self.body.push_str(&format!("    let {name} = {};\n", value.code));
+        Expr::new(name, value.ty)
+    }
+
+    /// Declare a texture and sampler, returning their names
+    fn texture(&mut self) -> (String, String) {
+        let index = self.textures;
+        self.textures += 1;
+        let (texture, sampler) = (format!("material_texture_{index}"), format!("material_sampler_{index}"));
+        self.bindings.push_str(&format!(
+            "@group({MATERIAL_BIND_GROUP}) @binding({}) var {texture}: texture_2d<f32>;\n",
+            index * 2
+        ));
+        self.bindings.push_str(&format!(
+            "@group({MATERIAL_BIND_GROUP}) @binding({}) var {sampler}: sampler;\n",
+            index * 2 + 1
+        ));
+        (texture, sampler)
+    }
+
+    /// Value flowing into an input port, converted to the port's type
+    fn input(&mut self, node: &Node, index: usize) -> Result<Expr, CompileError> {
+        let Some(port) = node.inputs.get(index) else {
+            return Ok(Expr::float(0.0));
+        };
+
+        let source = self.graph.connections_to(port.id).next().map(|c| (c.from_node, c.from_port));
+        let value = match source {
+            Some((from_node, from_port)) => {
+                self.compile_node(from_node)?;
+                self.outputs.get(&from_port).cloned()
+            }
+            None => port.default_value.as_ref().and_then(Expr::value),
+        };
+        let ty = WgslType::of_port(&port.port_type);
+        let value = value.unwrap_or_else(|| implicit_input(&port.name, ty.unwrap_or(WgslType::F32)));
+        Ok(match ty {
+            Some(ty) => value.cast(ty),
+            None => value,
+        })
+    }
+
+    /// Value of the named input port as `ty`, zero if the node lacks it
+    fn input_named(&mut self, node: &Node, name: &str, ty: WgslType) -> Result<Expr, CompileError> {
+        match node.inputs.iter().position(|port| port.name == name) {
+            Some(index) => Ok(self.input(node, index)?.cast(ty)),
+            None => Ok(Expr::zero(ty)),
+        }
+    }
+
+    /// WGSL expressions for each of the node's output ports
+    fn emit(&mut self, node: &Node) -> Result<Vec<Expr>, CompileError> {
+        use WgslType::{Vec2, Vec3, Vec4, F32};
+
+        let unary = |this: &mut Self, function: &str| -> Result<Vec<Expr>, CompileError> {
+            let x = this.input(node, 0)?;
+            Ok(vec![Expr::new(format!("{function}({})", x.code), x.ty)])
+        };
+        let binary = |this: &mut Self, op: &str| -> Result<Vec<Expr>, CompileError> {
+            let (a, b) = widen(this.input(node, 0)?, this.input(node, 1)?);
+            Ok(vec![Expr::new(format!("({} {op} {})", a.code, b.code), a.ty)])
+        };
+        let call2 = |this: &mut Self, function: &str| -> Result<Vec<Expr>, CompileError> {
+            let (a, b) = widen(this.input(node, 0)?, this.input(node, 1)?);
+            Ok(vec![Expr::new(format!("{function}({}, {})", a.code, b.code), a.ty)])
+        };
+        let components = |value: Expr, names: &str| -> Vec<Expr> {
+            std::iter::once(value.clone())
+                .chain(names.chars().map(|c| value.swizzle(&c.to_string())))
+                .collect()
+        };
+
+        let values = match node.node_type.as_str() {
+            REROUTE_NODE_TYPE => vec![self.input(node, 0)?],
+
+            "color_constant" | "float_constant" | "vector2_constant" | "vector3_constant" | "vector4_constant" => node
+                .outputs
+                .iter()
+                .map(|port| {
+                    let ty = WgslType::of_port(&port.port_type).unwrap_or(F32);
+                    port.default_value.as_ref().and_then(Expr::value).map_or_else(|| Expr::zero(ty), |v| v.cast(ty))
+                })
+                .collect(),
+
+            "uv_coord" => components(Expr::new("input.uv", Vec2), "xy"),
+            "vertex_color" => components(Expr::new("input.vertex_color", Vec4), "rgba"),
+            "world_position" => components(Expr::new("input.world_position", Vec3), "xyz"),
+            "world_normal" => vec![Expr::new("input.world_normal", Vec3)],
+            "view_direction" => vec![Expr::new("input.view_direction", Vec3)],
+            "time" => vec![
+                Expr::new("input.time", F32),
+                Expr::new("sin(input.time)", F32),
+                Expr::new("cos(input.time)", F32),
+                Expr::new("input.delta_time", F32),
+            ],
+
+            "texture_sample" => {
+                let uv = self.input(node, 1)?;
+                let (texture, sampler) = self.texture();
+                let color = self.temp(Expr::new(format!("textureSample({texture}, {sampler}, {})", uv.code), Vec4));
+                components(color, "rgba")
+            }
+            "normal_map" => {
+                let uv = self.input(node, 1)?;
+                let strength = self.input(node, 2)?;
+                let (texture, sampler) = self.texture();
+                let sample = self.temp(Expr::new(format!("textureSample({texture}, {sampler}, {})", uv.code), Vec4));
+                vec![Expr::new(
+                    format!(
+                        "normalize(mix(vec3<f32>(0.0, 0.0, 1.0), {}.xyz * 2.0 - 1.0, {}))",
+                        sample.code, strength.code
+                    ),
+                    Vec3,
+                )]
+            }
+
+            "add" => binary(self, "+")?,
+            "subtract" => binary(self, "-")?,
+            "multiply" => binary(self, "*")?,
+            "divide" => binary(self, "/")?,
+            "power" => call2(self, "pow")?,
+            "atan2" => call2(self, "atan2")?,
+            "min" => call2(self, "min")?,
+            "max" => call2(self, "max")?,
+            "sqrt" => unary(self, "sqrt")?,
+            "abs" => unary(self, "abs")?,
+            "negate" => unary(self, "-")?,
+            "sin" => unary(self, "sin")?,
+            "cos" => unary(self, "cos")?,
+            "tan" => unary(self, "tan")?,
+            "saturate" => unary(self, "saturate")?,
+            "floor" => unary(self, "floor")?,
+            "ceil" => unary(self, "ceil")?,
+            "fract" => unary(self, "fract")?,
+            "one_minus" => {
+                let x = self.input(node, 0)?;
+                vec![Expr::new(format!("(1.0 - {})", x.code), x.ty)]
+            }
+            "lerp" => {
+                let (a, b) = widen(self.input(node, 0)?, self.input(node, 1)?);
+                let t = self.input(node, 2)?;
+                vec![Expr::new(format!("mix({}, {}, {})", a.code, b.code, t.code), a.ty)]
+            }
+            "smoothstep" => {
+                let (edge0, edge1, x) = (self.input(node, 0)?, self.input(node, 1)?, self.input(node, 2)?);
+                vec![Expr::new(format!("smoothstep({}, {}, {})", edge0.code, edge1.code, x.code), F32)]
+            }
+            "clamp" => {
+                let value = self.input(node, 0)?;
+                let (low, high) = (self.input(node, 1)?, self.input(node, 2)?);
+                let ty = value.ty.max(low.ty).max(high.ty);
+                let (value, low, high) = (value.cast(ty), low.cast(ty), high.cast(ty));
+                vec![Expr::new(format!("clamp({}, {}, {})", value.code, low.code, high.code), ty)]
+            }
+            "remap" => {
+                let inputs = (0..5).map(|i| self.input(node, i)).collect::<Result<Vec<_>, _>>()?;
+                let [value, in_min, in_max, out_min, out_max] = [0, 1, 2, 3, 4].map(|i| &inputs[i].code);
+                vec![Expr::new(
+                    format!("({out_min} + ({value} - {in_min}) * ({out_max} - {out_min}) / ({in_max} - {in_min}))"),
+                    F32,
+                )]
+            }
+
+            "dot" => {
+                let (a, b) = (self.input(node, 0)?, self.input(node, 1)?);
+                vec![Expr::new(format!("dot({}, {})", a.code, b.code), F32)]
+            }
+            "cross" => call2(self, "cross")?,
+            "reflect" => call2(self, "reflect")?,
+            "normalize" => {
+                let x = self.input(node, 0)?;
+                // normalize() is only defined for vectors
+                let function = if x.ty == F32 { "sign" } else { "normalize" };
+                vec![Expr::new(format!("{function}({})", x.code), x.ty)]
+            }
+            "length" => {
+                let x = self.input(node, 0)?;
+                vec![Expr::new(format!("length({})", x.code), F32)]
+            }
+            "distance" => {
+                let (a, b) = widen(self.input(node, 0)?, self.input(node, 1)?);
+                vec![Expr::new(format!("distance({}, {})", a.code, b.code), F32)]
+            }
+
+            "split_vector2" => components(self.input(node, 0)?, "xy").split_off(1),
+            "split_vector3" => components(self.input(node, 0)?, "xyz").split_off(1),
+            "split_vector4" => components(self.input(node, 0)?, "xyzw").split_off(1),
+            "combine_vector2" | "combine_vector3" | "combine_vector4" => {
+                let parts = (0..node.inputs.len()).map(|i| self.input(node, i)).collect::<Result<Vec<_>, _>>()?;
+                let ty = WgslType::from_lanes(parts.len());
+                let args: Vec<&str> = parts.iter().map(|p| p.code.as_str()).collect();
+                vec![Expr::new(format!("{}({})", ty.name(), args.join(", ")), ty)]
+            }
+
+            "hsv_to_rgb" => {
+                let (h, s, v) = (self.input(node, 0)?, self.input(node, 1)?, self.input(node, 2)?);
+                let rgb = format!(
+                    "{v} * mix(vec3<f32>(1.0), saturate(abs(fract({h} + vec3<f32>(1.0, 2.0 / 3.0, 1.0 / 3.0)) * 6.0 - 3.0) - 1.0), {s})",
+                    h = h.code,
+                    s = s.code,
+                    v = v.code
+                );
+                vec![Expr::new(rgb, Vec3).cast(Vec4)]
+            }
+            "contrast" => {
+                let color = self.input(node, 0)?;
+                let color = self.temp(color);
+                let contrast = self.input(node, 1)?;
+                vec![Expr::new(
+                    format!("vec4<f32>(({c}.rgb - 0.5) * {k} + 0.5, {c}.a)", c = color.code, k = contrast.code),
+                    Vec4,
+                )]
+            }
+            "saturation" => {
+                let color = self.input(node, 0)?;
+                let color = self.temp(color);
+                let amount = self.input(node, 1)?;
+                vec![Expr::new(
+                    format!(
+                        "vec4<f32>(mix(vec3<f32>(dot({c}.rgb, vec3<f32>(0.2126, 0.7152, 0.0722))), {c}.rgb, {s}), {c}.a)",
+                        c = color.code,
+                        s = amount.code
+                    ),
+                    Vec4,
+                )]
+            }
+            "blend" => {
+                let (base, blend, opacity) = (self.input(node, 0)?, self.input(node, 1)?, self.input(node, 2)?);
+                vec![Expr::new(format!("mix({}, {}, {})", base.code, blend.code, opacity.code), Vec4)]
+            }
+
+            "uv_tiling" => {
+                let (uv, tiling, offset) = (self.input(node, 0)?, self.input(node, 1)?, self.input(node, 2)?);
+                vec![Expr::new(format!("({} * {} + {})", uv.code, tiling.code, offset.code), Vec2)]
+            }
+            "uv_rotate" => {
+                let uv = self.input(node, 0)?;
+                let (center, angle) = (self.input(node, 1)?, self.input(node, 2)?);
+                let (center, angle) = (self.temp(center), self.temp(angle));
+                let d = self.temp(Expr::new(format!("({} - {})", uv.code, center.code), Vec2));
+                vec![Expr::new(
+                    format!(
+                        "(vec2<f32>(cos({a}) * {d}.x - sin({a}) * {d}.y, sin({a}) * {d}.x + cos({a}) * {d}.y) + {c})",
+                        a = angle.code,
+                        d = d.code,
+                        c = center.code
+                    ),
+                    Vec2,
+                )]
+            }
+            "checkerboard" => {
+                let uv = self.input(node, 0)?;
+                let scale = self.input(node, 1)?;
+                let (a, b) = (self.input(node, 2)?, self.input(node, 3)?);
+                let cell = self.temp(Expr::new(format!("floor({} * {})", uv.code, scale.code), Vec2));
+                vec![Expr::new(
+                    format!("select({}, {}, fract(({c}.x + {c}.y) * 0.5) >= 0.5)", a.code, b.code, c = cell.code),
+                    Vec4,
+                )]
+            }
+            "gradient" => {
+                let uv = self.input(node, 0)?;
+                let (a, b) = (self.input(node, 1)?, self.input(node, 2)?);
+                vec![Expr::new(format!("mix({}, {}, saturate({}.x))", a.code, b.code, uv.code), Vec4)]
+            }
+            "fresnel" => {
+                let (normal, power) = (self.input(node, 0)?, self.input(node, 1)?);
+                vec![Expr::new(
+                    format!(
+                        "pow(1.0 - saturate(dot(normalize({}), input.view_direction)), {})",
+                        normal.code, power.code
+                    ),
+                    F32,
+                )]
+            }
+
+            _ => {
+                return Err(CompileError::UnsupportedNode {
+                    node: node.id,
+                    name: node.name.clone(),
+                    node_type: node.node_type.clone(),
+                })
+            }
+        };
+        Ok(values)
+    }
+}
+
+/// Fallback for an unconnected input without a default: mesh attributes for
+/// coordinate-like ports, one for an upper bound and zero otherwise
+fn implicit_input(port_name: &str, ty: WgslType) -> Expr {
+    let attribute = match port_name {
+        "UV" => Expr::new("input.uv", WgslType::Vec2),
+        "Normal" => Expr::new("input.world_normal", WgslType::Vec3),
+        "Position" => Expr::new("input.world_position", WgslType::Vec3),
+        "Max" => Expr::float(1.0),
+        _ => return Expr::zero(ty),
+    };
+    attribute.cast(ty)
+}
+
 /// Material graph panel state for the editor
 pub struct MaterialGraphPanel {
     /// The material graph being edited
-    pub graph: crate::Graph,
+    pub graph: Graph,
     /// Graph editor UI state
     pub editor_state: crate::ui::GraphEditorState,
     /// Node registry
@@ -848,13 +1551,17 @@ pub struct MaterialGraphPanel {
     pub name: String,
     /// Whether the material has been modified
     pub dirty: bool,
+    /// WGSL from the last successful compile
+    pub shader: Option<String>,
+    /// Error from the last compile, if it failed
+    pub compile_error: Option<String>,
 }
 
 impl MaterialGraphPanel {
     /// Create a new material graph panel
     pub fn new() -> Self {
         let registry = create_material_registry();
-        let mut graph = crate::Graph::new("New Material");
+        let mut graph = Graph::new("New Material");
 
         // Add default output node
         if let Some(output_node) = registry.create_node("material_output") {
@@ -867,6 +1574,8 @@ impl MaterialGraphPanel {
             registry,
             name: "New Material".to_string(),
             dirty: false,
+            shader: None,
+            compile_error: None,
         }
     }
 
@@ -891,7 +1600,12 @@ impl MaterialGraphPanel {
             ui.separator();
 
             if ui.button("Compile").clicked() {
-                // TODO: Compile to WGSL
+                self.compile();
+            }
+            if let Some(error) = &self.compile_error {
+                ui.colored_label(egui::Color32::from_rgb(230, 80, 80), "Compile failed").on_hover_text(error);
+            } else if let Some(shader) = &self.shader {
+                ui.weak("Compiled").on_hover_text(format!("{} lines of WGSL", shader.lines().count()));
             }
 
             ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
@@ -908,6 +1622,17 @@ impl MaterialGraphPanel {
         self.editor_state.ui_with_registry(ui, &mut self.graph, Some(&self.registry));
     }
 
+    /// Compile the graph to WGSL, keeping the previous shader on failure
+    pub fn compile(&mut self) {
+        match compile_to_wgsl(&self.graph) {
+            Ok(shader) => {
+                self.shader = Some(shader);
+                self.compile_error = None;
+            }
+            Err(err) => self.compile_error = Some(err.to_string()),
+        }
+    }
+
     fn add_node_menu(&mut self, ui: &mut egui::Ui) {
         use crate::node::NodeCategory;
 
@@ -954,3 +1679,165 @@ impl Default for MaterialGraphPanel {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn validate_wgsl(source: &str) {
+        let module = naga::front::wgsl::parse_str(source).unwrap_or_else(|err| panic!("{}\n{source}", err.emit_to_string(source)));
+        naga::valid::Validator::new(naga::valid::ValidationFlags::all(), naga::valid::Capabilities::default())
+            .validate(&module)
+            .unwrap_or_else(|err| panic!("{err:?}\n{source}"));
+    }
+
+    #[test]
+    fn test_compile_color_to_base_color() {
+        let registry = create_material_registry();
+        let mut graph = Graph::new("Compile");
+
+        let mut color = registry.create_node("color_constant").unwrap();
+        color.outputs[0].default_value = Some(PortValue::Color([1.0, 0.5, 0.25, 1.0]));
+        let color_port = color.outputs[0].id;
+        let color = graph.add_node(color);
+
+        let output = registry.create_node("material_output").unwrap();
+        let base_color = output.inputs[0].id;
+        let output = graph.add_node(output);
+        let connection = graph.connect(color, color_port, output, base_color).unwrap();
+        // Reroutes pass the value through without emitting anything
+        graph.insert_reroute(connection, [100.0, 0.0]).unwrap();
+
+        let wgsl = compile_to_wgsl(&graph).unwrap();
+        assert!(wgsl.contains("let v0 = vec4<f32>(1.0, 0.5, 0.25, 1.0);"));
+        assert!(wgsl.contains("result.base_color = v0;"));
+        // Unconnected inputs keep their defaults
+        assert!(wgsl.contains("result.roughness = 0.5;"));
+        validate_wgsl(&wgsl);
+    }
+
+    #[test]
+    fn test_compile_texture_math_chain() {
+        let registry = create_material_registry();
+        let mut graph = Graph::new("Compile");
+
+        let tiling = registry.create_node("uv_tiling").unwrap();
+        let tiled_uv = tiling.outputs[0].id;
+        let tiling = graph.add_node(tiling);
+
+        let sample = registry.create_node("texture_sample").unwrap();
+        let (uv_in, color_out, alpha_out) = (sample.inputs[1].id, sample.outputs[0].id, sample.outputs[4].id);
+        let sample = graph.add_node(sample);
+
+        let mut multiply = registry.create_node("multiply").unwrap();
+        multiply.inputs[1].default_value = Some(PortValue::Float(2.0));
+        let (a_in, product) = (multiply.inputs[0].id, multiply.outputs[0].id);
+        let multiply = graph.add_node(multiply);
+
+        let output = registry.create_node("material_output").unwrap();
+        let (base_color, opacity) = (output.inputs[0].id, output.inputs[6].id);
+        let output = graph.add_node(output);
+
+        graph.connect(tiling, tiled_uv, sample, uv_in).unwrap();
+        graph.connect(sample, color_out, multiply, a_in).unwrap();
+        graph.connect(multiply, product, output, base_color).unwrap();
+        graph.connect(sample, alpha_out, output, opacity).unwrap();
+
+        let wgsl = compile_to_wgsl(&graph).unwrap();
+        // The shared sample is declared and bound once
+        assert_eq!(wgsl.matches("textureSample(").count(), 1);
+        assert!(wgsl.contains("@group(2) @binding(1) var material_sampler_0: sampler;"));
+        validate_wgsl(&wgsl);
+    }
+
+    #[test]
+    fn test_compile_errors() {
+        let registry = create_material_registry();
+        assert!(matches!(compile_to_wgsl(&Graph::new("Empty")), Err(CompileError::MissingOutput)));
+
+        // Cycles can't be connected normally, only loaded from older files
+        let mut graph = Graph::new("Cycle");
+        graph.allow_cycles = true;
+        let add = registry.create_node("add").unwrap();
+        let (add_in, add_out) = (add.inputs[0].id, add.outputs[0].id);
+        let add = graph.add_node(add);
+        let multiply = registry.create_node("multiply").unwrap();
+        let (multiply_in, multiply_out) = (multiply.inputs[0].id, multiply.outputs[0].id);
+        let multiply = graph.add_node(multiply);
+        let output = registry.create_node("material_output").unwrap();
+        let base_color = output.inputs[0].id;
+        let output = graph.add_node(output);
+        graph.connect(add, add_out, multiply, multiply_in).unwrap();
+        graph.connect(multiply, multiply_out, add, add_in).unwrap();
+        graph.connect(multiply, multiply_out, output, base_color).unwrap();
+        assert!(matches!(compile_to_wgsl(&graph), Err(CompileError::CycleDetected(_))));
+
+        let mut graph = Graph::new("Unsupported");
+        let noise = registry.create_node("noise_voronoi").unwrap();
+        let distance = noise.outputs[0].id;
+        let noise = graph.add_node(noise);
+        let output = registry.create_node("material_output").unwrap();
+        let roughness = output.inputs[2].id;
+        let output = graph.add_node(output);
+        graph.connect(noise, distance, output, roughness).unwrap();
+        match compile_to_wgsl(&graph) {
+            Err(CompileError::UnsupportedNode { node, node_type, .. }) => {
+                assert_eq!(node, noise);
+                assert_eq!(node_type, "noise_voronoi");
+            }
+            other => panic!("expected unsupported node, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_preview_evaluator_combines_inputs() {
+        let registry = create_material_registry();
+        let mut graph = Graph::new("Preview");
+
+        let mut multiply = registry.create_node("multiply").unwrap();
+        multiply.inputs[0].default_value = Some(PortValue::Color([1.0, 0.5, 0.2, 1.0]));
+        multiply.inputs[1].default_value = Some(PortValue::Float(0.5));
+        let result_port = multiply.outputs[0].id;
+        let multiply = graph.add_node(multiply);
+
+        let output = registry.create_node("material_output").unwrap();
+        let base_color = output.inputs[0].id;
+        let output = graph.add_node(output);
+        graph.connect(multiply, result_port, output, base_color).unwrap();
+
+        let outputs = EvaluationContext::new(&graph)
+            .unwrap()
+            .run(&MaterialPreviewEvaluator)
+            .unwrap();
+        let value = outputs[&multiply].get(&result_port).unwrap();
+        assert_eq!(value.preview_color(), Some([0.5, 0.25, 0.1, 0.5]));
+    }
+
+    #[test]
+    fn test_material_preview_reruns_only_when_the_graph_changes() {
+        let registry = create_material_registry();
+        let mut graph = Graph::new("Cached");
+
+        let mut color = registry.create_node("color_constant").unwrap();
+        color.outputs[0].default_value = Some(PortValue::Color([1.0, 0.0, 0.0, 1.0]));
+        let color_port = color.outputs[0].id;
+        let color = graph.add_node(color);
+
+        let mut preview = MaterialPreview::new();
+        let value = |preview: &mut MaterialPreview, graph: &Graph| {
+            preview.update(graph)[&color].get(&color_port).and_then(PortValue::preview_color)
+        };
+        assert_eq!(value(&mut preview, &graph), Some([1.0, 0.0, 0.0, 1.0]));
+
+        // An edit is picked up on the next update
+        graph.node_mut(color).unwrap().outputs[0].default_value = Some(PortValue::Color([0.0, 1.0, 0.0, 1.0]));
+        assert_eq!(value(&mut preview, &graph), Some([0.0, 1.0, 0.0, 1.0]));
+
+        // An unchanged graph isn't evaluated again
+        preview.outputs.clear();
+        assert!(preview.update(&graph).is_empty());
+
+        preview.reset();
+        assert_eq!(value(&mut preview, &graph), Some([0.0, 1.0, 0.0, 1.0]));
+    }
+}
@@ -18,6 +18,7 @@
 pub mod node;
 pub mod port;
 pub mod connection;
+pub mod comment;
 pub mod graph;
 pub mod evaluation;
 pub mod ui;
@@ -26,4 +27,5 @@ pub mod graphs;
 pub use node::{Node, NodeId, NodeType};
 pub use port::{Port, PortId, PortType, PortDirection};
 pub use connection::{Connection, ConnectionId};
+pub use comment::{Comment, CommentId};
 pub use graph::Graph;
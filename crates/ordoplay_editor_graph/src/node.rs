@@ -1,7 +1,7 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 //! Node definitions for the graph framework.
 
-use crate::port::Port;
+use crate::port::{Port, PortId, PortType};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -22,6 +22,9 @@ impl Default for NodeId {
     }
 }
 
+/// Type ID of the built-in reroute node
+pub const REROUTE_NODE_TYPE: &str = "reroute";
+
 /// Node type category
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum NodeCategory {
@@ -58,6 +61,21 @@ pub struct NodeType {
     pub outputs: Vec<Port>,
 }
 
+impl NodeType {
+    /// Built-in pass-through node for tidying long wires. Its output takes
+    /// the type of whatever is connected to its input.
+    pub fn reroute() -> Self {
+        Self {
+            id: REROUTE_NODE_TYPE.to_string(),
+            name: "Reroute".to_string(),
+            category: NodeCategory::Utility,
+            description: "Forwards its input unchanged".to_string(),
+            inputs: vec![Port::input("In", PortType::Any)],
+            outputs: vec![Port::output("Out", PortType::Any)],
+        }
+    }
+}
+
 /// A node instance in the graph
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Node {
@@ -82,13 +100,17 @@ pub struct Node {
 impl Node {
     /// Create a new node from a type definition
     pub fn new(node_type: &NodeType) -> Self {
+        // Connections are keyed by port ID, so every instance needs its own
+        let instantiate = |ports: &[Port]| -> Vec<Port> {
+            ports.iter().map(|port| Port { id: PortId::new(), ..port.clone() }).collect()
+        };
         Self {
             id: NodeId::new(),
             node_type: node_type.id.clone(),
             name: node_type.name.clone(),
             position: [0.0, 0.0],
-            inputs: node_type.inputs.clone(),
-            outputs: node_type.outputs.clone(),
+            inputs: instantiate(&node_type.inputs),
+            outputs: instantiate(&node_type.outputs),
             collapsed: false,
             color: None,
         }
@@ -100,6 +122,11 @@ impl Node {
         self
     }
 
+    /// Whether this is a built-in reroute node
+    pub fn is_reroute(&self) -> bool {
+        self.node_type == REROUTE_NODE_TYPE
+    }
+
     /// Get an input port by index
     pub fn input(&self, index: usize) -> Option<&Port> {
         self.inputs.get(index)
@@ -111,7 +138,7 @@ impl Node {
     }
 
     /// Get a port by ID
-    pub fn port(&self, port_id: &crate::port::PortId) -> Option<&Port> {
+    pub fn port(&self, port_id: &PortId) -> Option<&Port> {
         self.inputs.iter().find(|p| p.id == *port_id)
             .or_else(|| self.outputs.iter().find(|p| p.id == *port_id))
     }
@@ -129,11 +156,13 @@ pub struct NodeRegistry {
 }
 
 impl NodeRegistry {
-    /// Create a new empty registry
+    /// Create a registry holding only the built-in reroute node
     pub fn new() -> Self {
-        Self {
+        let mut registry = Self {
             types: indexmap::IndexMap::new(),
-        }
+        };
+        registry.register(NodeType::reroute());
+        registry
     }
 
     /// Register a node type
@@ -167,3 +196,46 @@ impl Default for NodeRegistry {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::Graph;
+    use crate::port::PortType;
+
+    fn pass_type() -> NodeType {
+        NodeType {
+            id: "pass".to_string(),
+            name: "Pass".to_string(),
+            category: NodeCategory::Utility,
+            description: String::new(),
+            inputs: vec![Port::input("In", PortType::Float)],
+            outputs: vec![Port::output("Out", PortType::Float)],
+        }
+    }
+
+    #[test]
+    fn test_instances_get_their_own_port_ids() {
+        let node_type = pass_type();
+        let a = Node::new(&node_type);
+        let b = Node::new(&node_type);
+        assert_ne!(a.inputs[0].id, b.inputs[0].id);
+        assert_ne!(a.outputs[0].id, b.outputs[0].id);
+        assert_ne!(a.inputs[0].id, node_type.inputs[0].id);
+        assert_eq!(a.inputs[0].name, node_type.inputs[0].name);
+    }
+
+    #[test]
+    fn test_chain_of_same_type_connects() {
+        // Shared port IDs made the second input look already connected
+        let node_type = pass_type();
+        let mut graph = Graph::new("Test");
+        let [a, b, c] = [(); 3].map(|_| graph.add_node(Node::new(&node_type)));
+        for (from, to) in [(a, b), (b, c)] {
+            let from_port = graph.node(from).unwrap().outputs[0].id;
+            let to_port = graph.node(to).unwrap().inputs[0].id;
+            graph.connect(from, from_port, to, to_port).unwrap();
+        }
+        assert_eq!(graph.connection_count(), 2);
+    }
+}
@@ -237,4 +237,63 @@ impl PortValue {
             Self::String(_) => PortType::String,
         }
     }
+
+    /// Color used to preview this value: scalars as greyscale, colors as-is
+    /// and vectors as RGB. Strings have no preview.
+    pub fn preview_color(&self) -> Option<[f32; 4]> {
+        let grey = |v: f32| [v, v, v, 1.0];
+        match self {
+            Self::Bool(b) => Some(grey(if *b { 1.0 } else { 0.0 })),
+            Self::Int(i) => Some(grey(*i as f32)),
+            Self::Float(f) => Some(grey(*f)),
+            Self::Vector2([x, y]) => Some([*x, *y, 0.0, 1.0]),
+            Self::Vector3([x, y, z]) | Self::Vector4([x, y, z, _]) => Some([*x, *y, *z, 1.0]),
+            Self::Color(color) => Some(*color),
+            Self::String(_) => None,
+        }
+    }
+}
+
+impl std::fmt::Display for PortValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let list = |f: &mut std::fmt::Formatter<'_>, values: &[f32]| {
+            let parts: Vec<String> = values.iter().map(|v| format!("{v:.3}")).collect();
+            write!(f, "({})", parts.join(", "))
+        };
+        match self {
+            Self::Bool(b) => write!(f, "{b}"),
+            Self::Int(i) => write!(f, "{i}"),
+            Self::Float(v) => write!(f, "{v:.3}"),
+            Self::Vector2(v) => list(f, v),
+            Self::Vector3(v) => list(f, v),
+            Self::Vector4(v) | Self::Color(v) => list(f, v),
+            Self::String(text) => f.write_str(text),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preview_color_mapping() {
+        assert_eq!(PortValue::Float(0.25).preview_color(), Some([0.25, 0.25, 0.25, 1.0]));
+        assert_eq!(
+            PortValue::Color([0.9, 0.1, 0.2, 0.5]).preview_color(),
+            Some([0.9, 0.1, 0.2, 0.5])
+        );
+        assert_eq!(
+            PortValue::Vector3([0.0, 0.5, 1.0]).preview_color(),
+            Some([0.0, 0.5, 1.0, 1.0])
+        );
+        assert_eq!(PortValue::String("albedo".to_string()).preview_color(), None);
+    }
+
+    #[test]
+    fn test_value_display() {
+        assert_eq!(PortValue::Float(0.5).to_string(), "0.500");
+        assert_eq!(PortValue::Vector2([1.0, -2.0]).to_string(), "(1.000, -2.000)");
+        assert_eq!(PortValue::String("Hello".to_string()).to_string(), "Hello");
+    }
 }
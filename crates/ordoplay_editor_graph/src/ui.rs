@@ -6,17 +6,23 @@
 //! - Connection rendering (bezier curves)
 //! - Pan/zoom navigation
 //! - Node selection and multi-selection
-//! - Connection drag-to-create
+//! - Connection drag-to-create with compatibility highlighting and snapping
 //! - Node dragging
 //! - Context menus
 //! - Minimap
-
-use crate::connection::ConnectionId;
-use crate::graph::Graph;
-use crate::node::{Node, NodeId, NodeRegistry};
-use crate::port::{Port, PortDirection, PortId};
+//! - Copy/paste and duplication of node selections
+//! - Comment frames that move the nodes they contain
+//! - Reroute nodes, inserted by double-clicking a connection
+//! - Overlay of evaluated port values for live previews
+
+use crate::comment::{Comment, CommentId};
+use crate::connection::{Connection, ConnectionId};
+use crate::graph::{ConnectionError, Graph};
+use crate::node::{Node, NodeId, NodeRegistry, NodeType};
+use crate::port::{Port, PortDirection, PortId, PortType, PortValue};
 use egui::{Color32, Pos2, Rect, Stroke, Vec2};
-use std::collections::HashSet;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 
 /// Node visual dimensions
 const NODE_WIDTH: f32 = 180.0;
@@ -26,14 +32,28 @@ const PORT_RADIUS: f32 = 6.0;
 const PORT_PADDING: f32 = 12.0;
 const NODE_ROUNDING: f32 = 6.0;
 const NODE_SHADOW_OFFSET: f32 = 3.0;
+/// Side length of a reroute node (graph space)
+const REROUTE_SIZE: f32 = 16.0;
 
 /// Connection visual parameters
 const BEZIER_CURVATURE: f32 = 50.0;
 const CONNECTION_THICKNESS: f32 = 2.5;
+/// Distance (screen space) within which a dragged connection snaps to a port
+const CONNECTION_SNAP_RADIUS: f32 = 20.0;
+/// Distance (screen space) within which the pointer is over a connection
+const CONNECTION_HOVER_DISTANCE: f32 = 6.0;
 
 /// Grid parameters
 const GRID_SPACING: f32 = 20.0;
 
+/// Offset (graph space) between pasted nodes and the nodes they were copied from
+const PASTE_OFFSET: f32 = GRID_SPACING * 2.0;
+
+/// Comment frame parameters (graph space)
+const COMMENT_HEADER_HEIGHT: f32 = 24.0;
+const COMMENT_PADDING: f32 = 20.0;
+const COMMENT_HANDLE_SIZE: f32 = 14.0;
+
 /// Dragging state for creating connections
 #[derive(Debug, Clone)]
 pub struct ConnectionDrag {
@@ -45,6 +65,168 @@ pub struct ConnectionDrag {
     pub direction: PortDirection,
     /// Current mouse position (screen space)
     pub current_pos: Pos2,
+    /// Compatibility of every port in the graph with the source port
+    pub candidates: HashMap<(NodeId, PortId), PortCompatibility>,
+    /// Compatible port the preview is currently snapped to
+    pub snap_target: Option<(NodeId, PortId)>,
+}
+
+/// How a port relates to the port a connection is being dragged from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PortCompatibility {
+    /// The port the drag started from
+    Source,
+    /// The connection can be completed on this port
+    Compatible,
+    /// The connection cannot be completed on this port
+    Incompatible,
+}
+
+/// Classify every candidate port against the port a connection is dragged from.
+///
+/// A candidate is compatible when it has the opposite direction, lives on a
+/// different node, and the data flowing from the output side can be accepted
+/// by the input side according to [`PortType::can_connect_to`].
+pub fn classify_connection_candidates<'a>(
+    source_node: NodeId,
+    source: &Port,
+    ports: impl IntoIterator<Item = (NodeId, &'a Port)>,
+) -> HashMap<(NodeId, PortId), PortCompatibility> {
+    ports
+        .into_iter()
+        .map(|(node_id, port)| {
+            let compatibility = if node_id == source_node && port.id == source.id {
+                PortCompatibility::Source
+            } else if node_id == source_node || port.direction == source.direction {
+                PortCompatibility::Incompatible
+            } else {
+                let (output, input) = if source.direction == PortDirection::Output {
+                    (source, port)
+                } else {
+                    (port, source)
+                };
+                if output.port_type.can_connect_to(&input.port_type) {
+                    PortCompatibility::Compatible
+                } else {
+                    PortCompatibility::Incompatible
+                }
+            };
+            ((node_id, port.id), compatibility)
+        })
+        .collect()
+}
+
+/// Index of the first port on a node type that could connect to a dragged port
+fn first_compatible_port(
+    node_type: &NodeType,
+    port_type: &PortType,
+    direction: PortDirection,
+) -> Option<usize> {
+    match direction {
+        PortDirection::Output => node_type
+            .inputs
+            .iter()
+            .position(|p| port_type.can_connect_to(&p.port_type)),
+        PortDirection::Input => node_type
+            .outputs
+            .iter()
+            .position(|p| p.port_type.can_connect_to(port_type)),
+    }
+}
+
+/// Nodes copied from a graph together with the connections between them
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GraphClipboard {
+    /// Copied nodes
+    pub nodes: Vec<Node>,
+    /// Connections whose both endpoints are copied nodes
+    pub connections: Vec<Connection>,
+}
+
+impl GraphClipboard {
+    /// Copy `node_ids` from `graph`. Connections to nodes outside the set are
+    /// dropped.
+    pub fn copy(graph: &Graph, node_ids: &HashSet<NodeId>) -> Self {
+        Self {
+            nodes: graph.nodes().filter(|n| node_ids.contains(&n.id)).cloned().collect(),
+            connections: graph
+                .connections()
+                .filter(|c| node_ids.contains(&c.from_node) && node_ids.contains(&c.to_node))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Whether nothing was copied
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Serialize for the system clipboard
+    pub fn to_ron(&self) -> Option<String> {
+        ron::to_string(self).ok()
+    }
+
+    /// Parse clipboard text produced by [`Self::to_ron`]
+    pub fn from_ron(text: &str) -> Option<Self> {
+        ron::from_str(text).ok()
+    }
+
+    /// Add copies of the nodes to `graph`, moved by `offset`.
+    ///
+    /// Nodes and ports get fresh IDs and the copied connections are remapped
+    /// onto them. Returns the new node IDs in clipboard order.
+    pub fn paste_into(&self, graph: &mut Graph, offset: [f32; 2]) -> Vec<NodeId> {
+        let mut node_ids = HashMap::new();
+        let mut port_ids = HashMap::new();
+        let mut pasted = Vec::with_capacity(self.nodes.len());
+
+        for node in &self.nodes {
+            let mut copy = node.clone();
+            copy.id = NodeId::new();
+            copy.position = [node.position[0] + offset[0], node.position[1] + offset[1]];
+            for port in copy.inputs.iter_mut().chain(copy.outputs.iter_mut()) {
+                let id = PortId::new();
+                port_ids.insert(port.id, id);
+                port.id = id;
+            }
+            node_ids.insert(node.id, copy.id);
+            pasted.push(graph.add_node(copy));
+        }
+
+        for connection in &self.connections {
+            let endpoints = (
+                node_ids.get(&connection.from_node),
+                port_ids.get(&connection.from_port),
+                node_ids.get(&connection.to_node),
+                port_ids.get(&connection.to_port),
+            );
+            if let (Some(&from_node), Some(&from_port), Some(&to_node), Some(&to_port)) = endpoints {
+                // The copies were valid in the source graph, so this only
+                // fails if the clipboard was edited by hand
+                let _ = graph.connect(from_node, from_port, to_node, to_port);
+            }
+        }
+
+        pasted
+    }
+}
+
+/// Add-node menu opened by releasing a connection drag over empty canvas
+#[derive(Debug, Clone)]
+pub struct AddNodeMenu {
+    /// Menu position (screen space)
+    pub screen_pos: Pos2,
+    /// Position for the new node (graph space)
+    pub graph_pos: Pos2,
+    /// Node the connection was dragged from
+    pub from_node: NodeId,
+    /// Port the connection was dragged from
+    pub from_port: PortId,
+    /// Direction of the port the connection was dragged from
+    pub direction: PortDirection,
+    /// Type of the port the connection was dragged from
+    pub port_type: PortType,
 }
 
 /// Box selection state
@@ -69,6 +251,18 @@ pub enum InteractionMode {
         /// Starting positions of nodes being dragged (node ID, position)
         start_positions: Vec<(NodeId, [f32; 2])>,
     },
+    /// Dragging a comment by its header
+    DraggingComment {
+        /// Comment being dragged
+        comment_id: CommentId,
+        /// Nodes inside the comment when the drag started, moved along
+        nodes: Vec<NodeId>,
+    },
+    /// Resizing a comment by its bottom-right corner
+    ResizingComment {
+        /// Comment being resized
+        comment_id: CommentId,
+    },
     /// Creating a connection
     CreatingConnection(ConnectionDrag),
     /// Box selection
@@ -85,6 +279,8 @@ pub struct GraphEditorState {
     pub selected_nodes: HashSet<NodeId>,
     /// Selected connections
     pub selected_connections: HashSet<ConnectionId>,
+    /// Most recently selected node
+    last_selected: Option<NodeId>,
     /// Current interaction mode
     pub mode: InteractionMode,
     /// Show minimap
@@ -103,6 +299,28 @@ pub struct GraphEditorState {
     hovered_port: Option<(NodeId, PortId)>,
     /// Connection being hovered
     hovered_connection: Option<ConnectionId>,
+    /// Pending add-node menu from a dropped connection
+    add_node_menu: Option<AddNodeMenu>,
+    /// Nodes highlighted as part of a cycle
+    pub cycle_nodes: Vec<NodeId>,
+    /// Why the last connection attempt failed, or why the graph is invalid
+    connection_error: Option<String>,
+    /// Graph revision the cycle check last ran at
+    checked_revision: Option<u64>,
+    /// Nodes copied with Ctrl+C
+    pub clipboard: Option<GraphClipboard>,
+    /// Text placed on the system clipboard for `clipboard`
+    clipboard_text: Option<String>,
+    /// Pastes since the last copy, so repeated pastes fan out
+    paste_count: u32,
+    /// Selected comment frame
+    pub selected_comment: Option<CommentId>,
+    /// Comment whose title and color are being edited
+    editing_comment: Option<CommentId>,
+    /// Show `port_values` next to each port
+    pub show_port_values: bool,
+    /// Last evaluated value of each port, filled in by the host
+    pub port_values: HashMap<PortId, PortValue>,
 }
 
 impl GraphEditorState {
@@ -113,6 +331,7 @@ impl GraphEditorState {
             zoom: 1.0,
             selected_nodes: HashSet::new(),
             selected_connections: HashSet::new(),
+            last_selected: None,
             mode: InteractionMode::Normal,
             show_minimap: true,
             show_grid: true,
@@ -122,6 +341,17 @@ impl GraphEditorState {
             hovered_node: None,
             hovered_port: None,
             hovered_connection: None,
+            add_node_menu: None,
+            cycle_nodes: Vec::new(),
+            connection_error: None,
+            checked_revision: None,
+            clipboard: None,
+            clipboard_text: None,
+            paste_count: 0,
+            selected_comment: None,
+            editing_comment: None,
+            show_port_values: false,
+            port_values: HashMap::new(),
         }
     }
 
@@ -159,6 +389,16 @@ impl GraphEditorState {
     pub fn clear_selection(&mut self) {
         self.selected_nodes.clear();
         self.selected_connections.clear();
+        self.selected_comment = None;
+        self.last_selected = None;
+    }
+
+    /// Node to preview: the most recently selected node still in the
+    /// selection, or `None` when nothing is selected
+    pub fn preview_node(&self) -> Option<NodeId> {
+        self.last_selected
+            .filter(|id| self.selected_nodes.contains(id))
+            .or_else(|| self.selected_nodes.iter().next().copied())
     }
 
     /// Select a node (optionally add to selection)
@@ -166,8 +406,10 @@ impl GraphEditorState {
         if !add_to_selection {
             self.selected_nodes.clear();
             self.selected_connections.clear();
+            self.selected_comment = None;
         }
         self.selected_nodes.insert(node_id);
+        self.last_selected = Some(node_id);
     }
 
     /// Toggle node selection
@@ -176,6 +418,7 @@ impl GraphEditorState {
             self.selected_nodes.remove(&node_id);
         } else {
             self.selected_nodes.insert(node_id);
+            self.last_selected = Some(node_id);
         }
     }
 
@@ -190,6 +433,100 @@ impl GraphEditorState {
         for node_id in self.selected_nodes.drain() {
             graph.remove_node(node_id);
         }
+        self.last_selected = None;
+
+        // Deleting a comment keeps the nodes inside it
+        if let Some(comment_id) = self.selected_comment.take() {
+            graph.remove_comment(comment_id);
+        }
+    }
+
+    /// Frame the selected nodes in a new comment and start editing its title
+    pub fn comment_selection(&mut self, graph: &mut Graph) -> Option<CommentId> {
+        let bounds = self
+            .selected_nodes
+            .iter()
+            .filter_map(|id| graph.node(*id))
+            .map(|node| self.get_node_rect(node))
+            .reduce(Rect::union)?;
+
+        let min = [bounds.min.x - COMMENT_PADDING, bounds.min.y - COMMENT_PADDING - COMMENT_HEADER_HEIGHT];
+        let size = [
+            bounds.width() + COMMENT_PADDING * 2.0,
+            bounds.height() + COMMENT_PADDING * 2.0 + COMMENT_HEADER_HEIGHT,
+        ];
+        let comment_id = graph.add_comment(Comment::new("Comment", min, size));
+        self.selected_comment = Some(comment_id);
+        self.editing_comment = Some(comment_id);
+        Some(comment_id)
+    }
+
+    /// Nodes lying entirely inside a comment
+    pub fn nodes_in_comment(&self, graph: &Graph, comment: &Comment) -> Vec<NodeId> {
+        graph
+            .nodes()
+            .filter(|node| {
+                let node_rect = self.get_node_rect(node);
+                comment.contains_rect([node_rect.min.x, node_rect.min.y], [node_rect.max.x, node_rect.max.y])
+            })
+            .map(|node| node.id)
+            .collect()
+    }
+
+    /// Copy the selected nodes and the connections between them.
+    ///
+    /// Returns the clipboard text for the system clipboard, or `None` when
+    /// nothing is selected.
+    pub fn copy_selected(&mut self, graph: &Graph) -> Option<String> {
+        let clipboard = GraphClipboard::copy(graph, &self.selected_nodes);
+        if clipboard.is_empty() {
+            return None;
+        }
+
+        self.clipboard_text = clipboard.to_ron();
+        self.clipboard = Some(clipboard);
+        self.paste_count = 0;
+        self.clipboard_text.clone()
+    }
+
+    /// Paste the clipboard, offset from the copied nodes, and select the
+    /// pasted nodes. Returns how many nodes were added.
+    pub fn paste(&mut self, graph: &mut Graph) -> usize {
+        let Some(clipboard) = &self.clipboard else {
+            return 0;
+        };
+
+        self.paste_count += 1;
+        let offset = PASTE_OFFSET * self.paste_count as f32;
+        let pasted = clipboard.paste_into(graph, [offset, offset]);
+
+        self.clear_selection();
+        for &node_id in &pasted {
+            self.select_node(node_id, true);
+        }
+        pasted.len()
+    }
+
+    /// Paste nodes from system clipboard text. Text copied from this editor
+    /// keeps fanning out; other graph text replaces the clipboard first.
+    pub fn paste_text(&mut self, graph: &mut Graph, text: &str) -> usize {
+        if self.clipboard_text.as_deref() != Some(text) {
+            let Some(clipboard) = GraphClipboard::from_ron(text) else {
+                return 0;
+            };
+            self.clipboard = Some(clipboard);
+            self.clipboard_text = Some(text.to_string());
+            self.paste_count = 0;
+        }
+        self.paste(graph)
+    }
+
+    /// Duplicate the selected nodes next to themselves
+    pub fn duplicate_selected(&mut self, graph: &mut Graph) -> usize {
+        if self.copy_selected(graph).is_none() {
+            return 0;
+        }
+        self.paste(graph)
     }
 
     /// Render the graph editor
@@ -213,6 +550,8 @@ impl GraphEditorState {
         self.hovered_port = None;
         self.hovered_connection = None;
 
+        self.check_cycles(graph);
+
         // Draw grid
         if self.show_grid {
             self.draw_grid(&painter, rect);
@@ -221,6 +560,9 @@ impl GraphEditorState {
         // Handle input
         self.handle_input(ui, &response, rect, graph, registry);
 
+        // Comments sit behind everything else
+        self.draw_comments(&painter, rect, graph);
+
         // Draw connections first (below nodes)
         self.draw_connections(&painter, rect, graph);
 
@@ -242,10 +584,47 @@ impl GraphEditorState {
             self.draw_minimap(&painter, rect, graph);
         }
 
+        // Draw add-node menu for a connection dropped on empty canvas
+        if let Some(registry) = registry {
+            self.show_add_node_menu(ui, graph, registry);
+        }
+
+        self.show_comment_editor(ui, rect, graph);
+
         // Draw status bar
         self.draw_status_bar(ui, rect, graph);
     }
 
+    /// Re-check the graph for cycles after it changes. Graphs that forbid
+    /// cycles can still contain one when loaded from an older file.
+    fn check_cycles(&mut self, graph: &Graph) {
+        if self.checked_revision == Some(graph.revision()) {
+            return;
+        }
+        self.checked_revision = Some(graph.revision());
+
+        match graph.find_cycle().filter(|_| !graph.allow_cycles) {
+            Some(nodes) => {
+                self.connection_error = Some(format!("Graph contains a cycle through {} nodes", nodes.len()));
+                self.cycle_nodes = nodes;
+            }
+            None => {
+                self.connection_error = None;
+                self.cycle_nodes.clear();
+            }
+        }
+    }
+
+    /// Connect two ports, highlighting the offending nodes if it fails
+    fn connect(&mut self, graph: &mut Graph, from_node: NodeId, from_port: PortId, to_node: NodeId, to_port: PortId) {
+        if let Err(err) = connect_replacing(graph, from_node, from_port, to_node, to_port) {
+            self.connection_error = Some(err.to_string());
+            if let ConnectionError::WouldCreateCycle(path) = err {
+                self.cycle_nodes = path;
+            }
+        }
+    }
+
     fn draw_grid(&self, painter: &egui::Painter, rect: Rect) {
         let spacing = GRID_SPACING * self.zoom;
         let major_spacing = spacing * 5.0;
@@ -316,7 +695,7 @@ impl GraphEditorState {
         response: &egui::Response,
         rect: Rect,
         graph: &mut Graph,
-        _registry: Option<&NodeRegistry>,
+        registry: Option<&NodeRegistry>,
     ) {
         let mouse_pos = ui.input(|i| i.pointer.hover_pos().unwrap_or(self.last_mouse_pos));
         let delta = mouse_pos - self.last_mouse_pos;
@@ -360,15 +739,57 @@ impl GraphEditorState {
 
                     if let Some(node_id) = clicked_node {
                         self.select_node(node_id, shift_held);
+                    } else if let Some(comment_id) = self.comment_header_at(graph_pos, graph) {
+                        self.clear_selection();
+                        self.selected_comment = Some(comment_id);
                     } else if !shift_held {
                         self.clear_selection();
                     }
                 }
 
-                // Start box selection with left click on empty space
+                let graph_pos = self.screen_to_graph(mouse_pos, rect);
+                if rect.contains(mouse_pos) && self.find_node_at(graph_pos, graph).is_none() {
+                    self.hovered_connection = self.find_connection_at(mouse_pos, rect, graph);
+                }
+
+                // Double-click a comment header to edit its title and color,
+                // or a connection to split it with a reroute
+                if response.double_clicked() && self.find_node_at(graph_pos, graph).is_none() {
+                    if let Some(comment_id) = self.comment_header_at(graph_pos, graph) {
+                        self.editing_comment = Some(comment_id);
+                    } else if let Some(connection_id) = self.hovered_connection {
+                        let half = REROUTE_SIZE / 2.0;
+                        let position = [graph_pos.x - half, graph_pos.y - half];
+                        if let Some(reroute) = graph.insert_reroute(connection_id, position) {
+                            self.hovered_connection = None;
+                            self.select_node(reroute, false);
+                        }
+                    }
+                }
+
+                // Start a connection from a port, or box selection on empty space
                 if response.drag_started_by(egui::PointerButton::Primary) {
-                    let graph_pos = self.screen_to_graph(mouse_pos, rect);
-                    if self.find_node_at(graph_pos, graph).is_none() {
+                    let press_pos = ui.input(|i| i.pointer.press_origin()).unwrap_or(mouse_pos);
+                    let graph_pos = self.screen_to_graph(press_pos, rect);
+                    if let Some((node_id, port_id)) = self.find_port_at(press_pos, rect, graph) {
+                        self.begin_connection_drag(graph, node_id, port_id, mouse_pos);
+                    } else if let Some(comment_id) = self
+                        .comment_handle_at(graph_pos, graph)
+                        .filter(|_| self.find_node_at(graph_pos, graph).is_none())
+                    {
+                        self.selected_comment = Some(comment_id);
+                        self.mode = InteractionMode::ResizingComment { comment_id };
+                    } else if let Some(comment) = self
+                        .comment_header_at(graph_pos, graph)
+                        .filter(|_| self.find_node_at(graph_pos, graph).is_none())
+                        .and_then(|id| graph.comment(id))
+                    {
+                        let comment_id = comment.id;
+                        let nodes = self.nodes_in_comment(graph, comment);
+                        self.clear_selection();
+                        self.selected_comment = Some(comment_id);
+                        self.mode = InteractionMode::DraggingComment { comment_id, nodes };
+                    } else if self.find_node_at(graph_pos, graph).is_none() {
                         self.mode = InteractionMode::BoxSelect(BoxSelection {
                             start: mouse_pos,
                             current: mouse_pos,
@@ -397,9 +818,8 @@ impl GraphEditorState {
                 if response.dragged() {
                     let graph_delta = delta / self.zoom;
                     for node_id in &self.selected_nodes {
-                        if let Some(node) = graph.node_mut(*node_id) {
-                            node.position[0] += graph_delta.x;
-                            node.position[1] += graph_delta.y;
+                        if let Some(position) = graph.node(*node_id).map(|node| node.position) {
+                            graph.set_node_position(*node_id, [position[0] + graph_delta.x, position[1] + graph_delta.y]);
                         }
                     }
                 }
@@ -407,8 +827,8 @@ impl GraphEditorState {
                     // Snap to grid on release
                     if self.snap_to_grid {
                         for node_id in &self.selected_nodes {
-                            if let Some(node) = graph.node_mut(*node_id) {
-                                node.position = self.snap_position(node.position);
+                            if let Some(position) = graph.node(*node_id).map(|node| node.position) {
+                                graph.set_node_position(*node_id, self.snap_position(position));
                             }
                         }
                     }
@@ -416,25 +836,42 @@ impl GraphEditorState {
                 }
             }
 
-            InteractionMode::CreatingConnection(drag) => {
-                drag.current_pos = mouse_pos;
-
+            InteractionMode::DraggingComment { comment_id, nodes } => {
+                if response.dragged() {
+                    let graph_delta = delta / self.zoom;
+                    if let Some(comment) = graph.comment_mut(*comment_id) {
+                        comment.position[0] += graph_delta.x;
+                        comment.position[1] += graph_delta.y;
+                    }
+                    for node_id in nodes.iter() {
+                        if let Some(node) = graph.node_mut(*node_id) {
+                            node.position[0] += graph_delta.x;
+                            node.position[1] += graph_delta.y;
+                        }
+                    }
+                }
                 if response.drag_stopped() {
-                    // Try to complete the connection
-                    if let Some((target_node, target_port)) = self.hovered_port {
-                        let (from_node, from_port, to_node, to_port) = if drag.direction == PortDirection::Output {
-                            (drag.from_node, drag.from_port, target_node, target_port)
-                        } else {
-                            (target_node, target_port, drag.from_node, drag.from_port)
-                        };
+                    self.mode = InteractionMode::Normal;
+                }
+            }
 
-                        // Ignore connection errors (e.g., incompatible types)
-                        let _ = graph.connect(from_node, from_port, to_node, to_port);
+            InteractionMode::ResizingComment { comment_id } => {
+                let comment_id = *comment_id;
+                if response.dragged() {
+                    let corner = self.screen_to_graph(mouse_pos, rect);
+                    if let Some(comment) = graph.comment_mut(comment_id) {
+                        comment.resize_to([corner.x, corner.y]);
                     }
+                }
+                if response.drag_stopped() {
                     self.mode = InteractionMode::Normal;
                 }
             }
 
+            InteractionMode::CreatingConnection(_) => {
+                self.update_connection_drag(ui, response, rect, graph, registry.is_some(), mouse_pos);
+            }
+
             InteractionMode::BoxSelect(selection) => {
                 selection.current = mouse_pos;
 
@@ -460,8 +897,8 @@ impl GraphEditorState {
                             Pos2::new(node.position[0], node.position[1]),
                             rect,
                         );
-                        if selection_rect.contains(node_screen) {
-                            self.selected_nodes.insert(node.id);
+                        if selection_rect.contains(node_screen) && self.selected_nodes.insert(node.id) {
+                            self.last_selected = Some(node.id);
                         }
                     }
 
@@ -470,12 +907,336 @@ impl GraphEditorState {
             }
         }
 
+        // Keys would otherwise act while a comment title is being typed
+        if ui.ctx().wants_keyboard_input() {
+            return;
+        }
+
         // Delete key
         ui.input(|i| {
             if i.key_pressed(egui::Key::Delete) || i.key_pressed(egui::Key::Backspace) {
                 self.delete_selected(graph);
             }
         });
+
+        // C frames the selection in a comment
+        if ui.rect_contains_pointer(rect) && ui.input(|i| i.key_pressed(egui::Key::C) && i.modifiers.is_none()) {
+            self.comment_selection(graph);
+        }
+
+        // Clipboard shortcuts arrive as events rather than key presses
+        if ui.rect_contains_pointer(rect) {
+            let (copy, paste, duplicate) = ui.input_mut(|i| {
+                let copy = i.events.iter().any(|e| matches!(e, egui::Event::Copy));
+                let paste = i.events.iter().find_map(|e| match e {
+                    egui::Event::Paste(text) => Some(text.clone()),
+                    _ => None,
+                });
+                let duplicate = i.consume_key(egui::Modifiers::COMMAND, egui::Key::D);
+                (copy, paste, duplicate)
+            });
+
+            if copy {
+                if let Some(text) = self.copy_selected(graph) {
+                    ui.ctx().copy_text(text);
+                }
+            }
+            if let Some(text) = paste {
+                self.paste_text(graph, &text);
+            }
+            if duplicate {
+                self.duplicate_selected(graph);
+            }
+        }
+    }
+
+    fn begin_connection_drag(&mut self, graph: &Graph, node_id: NodeId, port_id: PortId, mouse_pos: Pos2) {
+        let Some(source) = graph.node(node_id).and_then(|n| n.port(&port_id)) else {
+            return;
+        };
+
+        let candidates = classify_connection_candidates(
+            node_id,
+            source,
+            graph.nodes().flat_map(|n| n.ports().map(move |p| (n.id, p))),
+        );
+
+        self.add_node_menu = None;
+        self.mode = InteractionMode::CreatingConnection(ConnectionDrag {
+            from_node: node_id,
+            from_port: port_id,
+            direction: source.direction,
+            current_pos: mouse_pos,
+            candidates,
+            snap_target: None,
+        });
+    }
+
+    fn update_connection_drag(
+        &mut self,
+        ui: &egui::Ui,
+        response: &egui::Response,
+        rect: Rect,
+        graph: &mut Graph,
+        has_registry: bool,
+        mouse_pos: Pos2,
+    ) {
+        let snap_target = self.find_snap_target(mouse_pos, rect, graph);
+        let cancelled = ui.input(|i| i.key_pressed(egui::Key::Escape));
+        let InteractionMode::CreatingConnection(drag) = &mut self.mode else {
+            return;
+        };
+        drag.current_pos = mouse_pos;
+        drag.snap_target = snap_target;
+
+        if !cancelled && !response.drag_stopped() {
+            return;
+        }
+
+        let drag = drag.clone();
+        self.mode = InteractionMode::Normal;
+        if cancelled {
+            return;
+        }
+
+        if let Some((target_node, target_port)) = drag.snap_target {
+            let (from_node, from_port, to_node, to_port) = if drag.direction == PortDirection::Output {
+                (drag.from_node, drag.from_port, target_node, target_port)
+            } else {
+                (target_node, target_port, drag.from_node, drag.from_port)
+            };
+            self.connect(graph, from_node, from_port, to_node, to_port);
+            return;
+        }
+
+        // Released over empty canvas: offer nodes that accept the dragged type
+        let graph_pos = self.screen_to_graph(mouse_pos, rect);
+        if has_registry && self.find_node_at(graph_pos, graph).is_none() {
+            let port_type = graph
+                .node(drag.from_node)
+                .and_then(|n| n.port(&drag.from_port))
+                .map(|p| p.port_type.clone());
+            if let Some(port_type) = port_type {
+                self.add_node_menu = Some(AddNodeMenu {
+                    screen_pos: mouse_pos,
+                    graph_pos,
+                    from_node: drag.from_node,
+                    from_port: drag.from_port,
+                    direction: drag.direction,
+                    port_type,
+                });
+            }
+        }
+    }
+
+    /// Find the nearest compatible port within snapping distance of the cursor
+    fn find_snap_target(&self, mouse_pos: Pos2, rect: Rect, graph: &Graph) -> Option<(NodeId, PortId)> {
+        let InteractionMode::CreatingConnection(drag) = &self.mode else {
+            return None;
+        };
+
+        let snap_radius = CONNECTION_SNAP_RADIUS * self.zoom.max(1.0);
+        let mut best: Option<((NodeId, PortId), f32)> = None;
+
+        for (&(node_id, port_id), compatibility) in &drag.candidates {
+            if *compatibility != PortCompatibility::Compatible {
+                continue;
+            }
+            let Some(node) = graph.node(node_id) else { continue };
+            let Some(port_pos) = self.get_port_position(node, &port_id, rect) else { continue };
+
+            let distance = port_pos.distance(mouse_pos);
+            if distance <= snap_radius && !best.is_some_and(|(_, d)| d <= distance) {
+                best = Some(((node_id, port_id), distance));
+            }
+        }
+
+        best.map(|(target, _)| target)
+    }
+
+    /// Find the port under a screen position
+    fn find_port_at(&self, screen_pos: Pos2, rect: Rect, graph: &Graph) -> Option<(NodeId, PortId)> {
+        let hit_radius = PORT_RADIUS * self.zoom * 1.5;
+        graph.nodes().find_map(|node| {
+            node.ports().find_map(|port| {
+                let port_pos = self.get_port_position(node, &port.id, rect)?;
+                (port_pos.distance(screen_pos) < hit_radius).then_some((node.id, port.id))
+            })
+        })
+    }
+
+    fn show_add_node_menu(&mut self, ui: &egui::Ui, graph: &mut Graph, registry: &NodeRegistry) {
+        let Some(menu) = self.add_node_menu.clone() else {
+            return;
+        };
+
+        let mut chosen: Option<(String, usize)> = None;
+        let area = egui::Area::new(egui::Id::new("graph_add_node_menu"))
+            .fixed_pos(menu.screen_pos)
+            .order(egui::Order::Foreground)
+            .show(ui.ctx(), |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.label(egui::RichText::new("Add Node").strong());
+                    ui.separator();
+
+                    let mut any = false;
+                    for node_type in registry.types() {
+                        let Some(port_index) = first_compatible_port(node_type, &menu.port_type, menu.direction) else {
+                            continue;
+                        };
+                        any = true;
+                        if ui.button(&node_type.name).on_hover_text(&node_type.description).clicked() {
+                            chosen = Some((node_type.id.clone(), port_index));
+                        }
+                    }
+
+                    if !any {
+                        ui.weak("No compatible nodes");
+                    }
+                });
+            });
+
+        if let Some((type_id, port_index)) = chosen {
+            if let Some(node) = registry.create_node(&type_id) {
+                let node = node.with_position(menu.graph_pos.x, menu.graph_pos.y);
+                let new_port = match menu.direction {
+                    PortDirection::Output => node.input(port_index),
+                    PortDirection::Input => node.output(port_index),
+                }
+                .map(|p| p.id);
+                let new_node = graph.add_node(node);
+
+                if let Some(new_port) = new_port {
+                    if menu.direction == PortDirection::Output {
+                        self.connect(graph, menu.from_node, menu.from_port, new_node, new_port);
+                    } else {
+                        self.connect(graph, new_node, new_port, menu.from_node, menu.from_port);
+                    }
+                }
+                self.select_node(new_node, false);
+            }
+            self.add_node_menu = None;
+        } else if area.response.clicked_elsewhere() || ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+            self.add_node_menu = None;
+        }
+    }
+
+    /// Topmost comment whose header contains a graph position
+    fn comment_header_at(&self, graph_pos: Pos2, graph: &Graph) -> Option<CommentId> {
+        graph
+            .comments()
+            .rev()
+            .find(|comment| {
+                let header = Rect::from_min_size(
+                    Pos2::new(comment.position[0], comment.position[1]),
+                    Vec2::new(comment.size[0], COMMENT_HEADER_HEIGHT),
+                );
+                header.contains(graph_pos)
+            })
+            .map(|comment| comment.id)
+    }
+
+    /// Topmost comment whose resize handle contains a graph position
+    fn comment_handle_at(&self, graph_pos: Pos2, graph: &Graph) -> Option<CommentId> {
+        graph
+            .comments()
+            .rev()
+            .find(|comment| {
+                let [x, y] = comment.max();
+                let handle = Rect::from_min_max(
+                    Pos2::new(x - COMMENT_HANDLE_SIZE, y - COMMENT_HANDLE_SIZE),
+                    Pos2::new(x, y),
+                );
+                handle.contains(graph_pos)
+            })
+            .map(|comment| comment.id)
+    }
+
+    fn draw_comments(&self, painter: &egui::Painter, rect: Rect, graph: &Graph) {
+        for comment in graph.comments() {
+            let [r, g, b] = comment.color;
+            let screen_rect = Rect::from_min_max(
+                self.graph_to_screen(Pos2::new(comment.position[0], comment.position[1]), rect),
+                self.graph_to_screen(Pos2::new(comment.max()[0], comment.max()[1]), rect),
+            );
+            if !screen_rect.intersects(rect) {
+                continue;
+            }
+
+            let rounding = NODE_ROUNDING * self.zoom;
+            painter.rect_filled(screen_rect, rounding, Color32::from_rgba_unmultiplied(r, g, b, 40));
+
+            let header_rect = Rect::from_min_size(
+                screen_rect.min,
+                Vec2::new(screen_rect.width(), COMMENT_HEADER_HEIGHT * self.zoom),
+            );
+            painter.rect_filled(
+                header_rect,
+                egui::Rounding { nw: rounding, ne: rounding, sw: 0.0, se: 0.0 },
+                Color32::from_rgba_unmultiplied(r, g, b, 170),
+            );
+            painter.text(
+                header_rect.left_center() + Vec2::new(8.0 * self.zoom, 0.0),
+                egui::Align2::LEFT_CENTER,
+                &comment.title,
+                egui::FontId::proportional(13.0 * self.zoom),
+                Color32::WHITE,
+            );
+
+            let border = if self.selected_comment == Some(comment.id) {
+                Stroke::new(2.0, Color32::from_rgb(100, 150, 255))
+            } else {
+                Stroke::new(1.0, Color32::from_rgba_unmultiplied(r, g, b, 200))
+            };
+            painter.rect_stroke(screen_rect, rounding, border);
+
+            // Resize handle in the bottom-right corner
+            let corner = screen_rect.max;
+            let handle = COMMENT_HANDLE_SIZE * self.zoom;
+            painter.add(egui::Shape::convex_polygon(
+                vec![corner, corner - Vec2::new(handle, 0.0), corner - Vec2::new(0.0, handle)],
+                Color32::from_rgba_unmultiplied(r, g, b, 200),
+                Stroke::NONE,
+            ));
+        }
+    }
+
+    /// Title and color editor for the comment being edited
+    fn show_comment_editor(&mut self, ui: &egui::Ui, rect: Rect, graph: &mut Graph) {
+        let Some(comment_id) = self.editing_comment else {
+            return;
+        };
+        let Some(comment) = graph.comment(comment_id) else {
+            self.editing_comment = None;
+            return;
+        };
+
+        let (mut title, mut color) = (comment.title.clone(), comment.color);
+        let anchor = self.graph_to_screen(Pos2::new(comment.position[0], comment.position[1]), rect);
+        let mut done = false;
+        let area = egui::Area::new(egui::Id::new("graph_comment_editor"))
+            .fixed_pos(anchor)
+            .order(egui::Order::Foreground)
+            .show(ui.ctx(), |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        let edit = ui.text_edit_singleline(&mut title);
+                        edit.request_focus();
+                        egui::color_picker::color_edit_button_srgb(ui, &mut color);
+                        done = edit.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                    });
+                });
+            });
+
+        if title != comment.title || color != comment.color {
+            if let Some(comment) = graph.comment_mut(comment_id) {
+                comment.title = title;
+                comment.color = color;
+            }
+        }
+        if done || area.response.clicked_elsewhere() || ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+            self.editing_comment = None;
+        }
     }
 
     fn find_node_at(&self, graph_pos: Pos2, graph: &Graph) -> Option<NodeId> {
@@ -490,6 +1251,12 @@ impl GraphEditorState {
     }
 
     fn get_node_rect(&self, node: &Node) -> Rect {
+        if node.is_reroute() {
+            return Rect::from_min_size(
+                Pos2::new(node.position[0], node.position[1]),
+                Vec2::splat(REROUTE_SIZE),
+            );
+        }
         let port_count = node.inputs.len().max(node.outputs.len());
         let height = NODE_HEADER_HEIGHT + (port_count as f32 * PORT_HEIGHT) + 8.0;
         Rect::from_min_size(
@@ -536,6 +1303,19 @@ impl GraphEditorState {
     }
 
     fn get_port_position(&self, node: &Node, port_id: &PortId, rect: Rect) -> Option<Pos2> {
+        // Reroutes take their input on the left edge and output on the right
+        if node.is_reroute() {
+            let node_rect = self.get_node_rect(node);
+            let pos = if node.inputs.iter().any(|port| port.id == *port_id) {
+                node_rect.left_center()
+            } else if node.outputs.iter().any(|port| port.id == *port_id) {
+                node_rect.right_center()
+            } else {
+                return None;
+            };
+            return Some(self.graph_to_screen(pos, rect));
+        }
+
         // Find port in inputs
         for (i, port) in node.inputs.iter().enumerate() {
             if port.id == *port_id {
@@ -557,14 +1337,45 @@ impl GraphEditorState {
         None
     }
 
-    fn draw_bezier_connection(&self, painter: &egui::Painter, from: Pos2, to: Pos2, color: Color32) {
+    /// Screen-space polyline of the curve drawn between two ports
+    fn connection_curve(&self, from: Pos2, to: Pos2) -> Vec<Pos2> {
         let distance = (to.x - from.x).abs();
         let curvature = (BEZIER_CURVATURE * self.zoom).min(distance * 0.5);
 
         let ctrl1 = Pos2::new(from.x + curvature, from.y);
         let ctrl2 = Pos2::new(to.x - curvature, to.y);
 
-        let points = bezier_points(from, ctrl1, ctrl2, to, 32);
+        bezier_points(from, ctrl1, ctrl2, to, 32)
+    }
+
+    /// Connection whose curve passes closest to a screen position, if near enough
+    fn find_connection_at(&self, screen_pos: Pos2, rect: Rect, graph: &Graph) -> Option<ConnectionId> {
+        let mut best: Option<(ConnectionId, f32)> = None;
+        for connection in graph.connections() {
+            let (Some(from), Some(to)) = (graph.node(connection.from_node), graph.node(connection.to_node)) else {
+                continue;
+            };
+            let (Some(from_pos), Some(to_pos)) = (
+                self.get_port_position(from, &connection.from_port, rect),
+                self.get_port_position(to, &connection.to_port, rect),
+            ) else {
+                continue;
+            };
+
+            let distance = self
+                .connection_curve(from_pos, to_pos)
+                .windows(2)
+                .map(|segment| distance_to_segment(screen_pos, segment[0], segment[1]))
+                .fold(f32::INFINITY, f32::min);
+            if distance <= CONNECTION_HOVER_DISTANCE && !best.is_some_and(|(_, d)| d <= distance) {
+                best = Some((connection.id, distance));
+            }
+        }
+        best.map(|(connection_id, _)| connection_id)
+    }
+
+    fn draw_bezier_connection(&self, painter: &egui::Painter, from: Pos2, to: Pos2, color: Color32) {
+        let points = self.connection_curve(from, to);
         for i in 0..points.len() - 1 {
             painter.line_segment(
                 [points[i], points[i + 1]],
@@ -583,10 +1394,18 @@ impl GraphEditorState {
                     Color32::GRAY
                 };
 
+                // Snap the preview end onto the target port when close enough
+                let end_pos = drag
+                    .snap_target
+                    .and_then(|(node_id, port_id)| {
+                        graph.node(node_id).and_then(|n| self.get_port_position(n, &port_id, rect))
+                    })
+                    .unwrap_or(drag.current_pos);
+
                 if drag.direction == PortDirection::Output {
-                    self.draw_bezier_connection(painter, from_pos, drag.current_pos, color);
+                    self.draw_bezier_connection(painter, from_pos, end_pos, color);
                 } else {
-                    self.draw_bezier_connection(painter, drag.current_pos, from_pos, color);
+                    self.draw_bezier_connection(painter, end_pos, from_pos, color);
                 }
             }
         }
@@ -613,6 +1432,11 @@ impl GraphEditorState {
                 continue;
             }
 
+            if node.is_reroute() {
+                self.draw_reroute(ui, painter, node, screen_rect, is_selected, mouse_pos);
+                continue;
+            }
+
             // Draw shadow
             let shadow_rect = screen_rect.translate(Vec2::new(NODE_SHADOW_OFFSET, NODE_SHADOW_OFFSET));
             painter.rect_filled(
@@ -659,8 +1483,14 @@ impl GraphEditorState {
                 Color32::WHITE,
             );
 
-            // Draw selection outline
-            if is_selected {
+            // Draw selection outline, or flag nodes on a cycle
+            if self.cycle_nodes.contains(&node.id) {
+                painter.rect_stroke(
+                    screen_rect,
+                    NODE_ROUNDING * self.zoom,
+                    Stroke::new(2.0, Color32::from_rgb(230, 80, 80)),
+                );
+            } else if is_selected {
                 painter.rect_stroke(
                     screen_rect,
                     NODE_ROUNDING * self.zoom,
@@ -673,6 +1503,35 @@ impl GraphEditorState {
         }
     }
 
+    /// Reroutes are drawn as a small pill in the color of the value they carry
+    fn draw_reroute(
+        &mut self,
+        ui: &egui::Ui,
+        painter: &egui::Painter,
+        node: &Node,
+        screen_rect: Rect,
+        is_selected: bool,
+        mouse_pos: Pos2,
+    ) {
+        let [r, g, b] = node.outputs.first().map_or([150, 150, 150], |port| port.port_type.color());
+        let rounding = screen_rect.height() / 2.0;
+        painter.rect_filled(screen_rect, rounding, Color32::from_rgb(r, g, b).gamma_multiply(0.6));
+        if is_selected {
+            painter.rect_stroke(screen_rect, rounding, Stroke::new(2.0, Color32::from_rgb(100, 150, 255)));
+        }
+
+        let ports = node
+            .inputs
+            .iter()
+            .map(|port| (port, screen_rect.left_center()))
+            .chain(node.outputs.iter().map(|port| (port, screen_rect.right_center())));
+        for (port, port_pos) in ports {
+            if self.draw_port(ui, painter, node.id, port, port_pos, mouse_pos) {
+                self.hovered_port = Some((node.id, port.id));
+            }
+        }
+    }
+
     fn draw_ports(
         &mut self,
         ui: &egui::Ui,
@@ -690,7 +1549,7 @@ impl GraphEditorState {
                 screen_rect.top() + y_offset * self.zoom,
             );
 
-            let is_hovered = self.draw_port(ui, painter, port, port_pos, mouse_pos);
+            let is_hovered = self.draw_port(ui, painter, node.id, port, port_pos, mouse_pos);
             if is_hovered {
                 self.hovered_port = Some((node.id, port.id));
             }
@@ -703,6 +1562,9 @@ impl GraphEditorState {
                 egui::FontId::proportional(10.0 * self.zoom),
                 Color32::from_gray(200),
             );
+
+            let value_pos = Pos2::new(port_pos.x - PORT_PADDING * self.zoom, port_pos.y);
+            self.draw_port_value(painter, port, value_pos, egui::Align2::RIGHT_CENTER);
         }
 
         // Draw output ports
@@ -713,7 +1575,7 @@ impl GraphEditorState {
                 screen_rect.top() + y_offset * self.zoom,
             );
 
-            let is_hovered = self.draw_port(ui, painter, port, port_pos, mouse_pos);
+            let is_hovered = self.draw_port(ui, painter, node.id, port, port_pos, mouse_pos);
             if is_hovered {
                 self.hovered_port = Some((node.id, port.id));
             }
@@ -726,16 +1588,69 @@ impl GraphEditorState {
                 egui::FontId::proportional(10.0 * self.zoom),
                 Color32::from_gray(200),
             );
+
+            let value_pos = Pos2::new(port_pos.x + PORT_PADDING * self.zoom, port_pos.y);
+            self.draw_port_value(painter, port, value_pos, egui::Align2::LEFT_CENTER);
         }
     }
 
-    fn draw_port(&self, _ui: &egui::Ui, painter: &egui::Painter, port: &Port, pos: Pos2, mouse_pos: Pos2) -> bool {
+    /// Label with the port's last evaluated value, outside the node. Exec
+    /// ports only carry a pulse, so they are left out.
+    fn draw_port_value(&self, painter: &egui::Painter, port: &Port, pos: Pos2, align: egui::Align2) {
+        if !self.show_port_values || port.port_type == PortType::Exec {
+            return;
+        }
+        let Some(value) = self.port_values.get(&port.id) else {
+            return;
+        };
+
+        let galley = painter.layout_no_wrap(
+            value.to_string(),
+            egui::FontId::monospace(10.0 * self.zoom),
+            Color32::from_gray(230),
+        );
+        let text_rect = align.anchor_size(pos, galley.size());
+        painter.rect_filled(
+            text_rect.expand(3.0 * self.zoom),
+            3.0 * self.zoom,
+            Color32::from_rgba_unmultiplied(20, 20, 24, 220),
+        );
+        painter.galley(text_rect.min, galley, Color32::from_gray(230));
+    }
+
+    fn draw_port(
+        &self,
+        _ui: &egui::Ui,
+        painter: &egui::Painter,
+        node_id: NodeId,
+        port: &Port,
+        pos: Pos2,
+        mouse_pos: Pos2,
+    ) -> bool {
         let radius = PORT_RADIUS * self.zoom;
         let [r, g, b] = port.port_type.color();
-        let color = Color32::from_rgb(r, g, b);
+        let mut color = Color32::from_rgb(r, g, b);
 
         let is_hovered = pos.distance(mouse_pos) < radius * 1.5;
 
+        // Highlight compatible ports and dim incompatible ones while dragging a connection
+        if let InteractionMode::CreatingConnection(ref drag) = self.mode {
+            match drag.candidates.get(&(node_id, port.id)) {
+                Some(PortCompatibility::Compatible) => {
+                    let ring = if drag.snap_target == Some((node_id, port.id)) {
+                        radius * 2.0
+                    } else {
+                        radius * 1.6
+                    };
+                    painter.circle_stroke(pos, ring, Stroke::new(2.0, Color32::from_rgb(120, 220, 120)));
+                }
+                Some(PortCompatibility::Incompatible) => {
+                    color = color.gamma_multiply(0.25);
+                }
+                Some(PortCompatibility::Source) | None => {}
+            }
+        }
+
         // Draw port circle
         if is_hovered {
             painter.circle_filled(pos, radius * 1.3, color);
@@ -875,6 +1790,16 @@ impl GraphEditorState {
             egui::FontId::proportional(11.0),
             Color32::from_gray(150),
         );
+
+        if let Some(error) = &self.connection_error {
+            ui.painter().text(
+                status_rect.right_center(),
+                egui::Align2::RIGHT_CENTER,
+                error,
+                egui::FontId::proportional(11.0),
+                Color32::from_rgb(230, 80, 80),
+            );
+        }
     }
 }
 
@@ -884,6 +1809,41 @@ impl Default for GraphEditorState {
     }
 }
 
+/// Connect two ports, replacing an existing link on a single-connection input
+fn connect_replacing(
+    graph: &mut Graph,
+    from_node: NodeId,
+    from_port: PortId,
+    to_node: NodeId,
+    to_port: PortId,
+) -> Result<(), ConnectionError> {
+    match graph.connect(from_node, from_port, to_node, to_port) {
+        Err(ConnectionError::PortAlreadyConnected(_)) => {
+            let existing: Vec<_> = graph
+                .connections_to(to_port)
+                .filter(|c| c.to_node == to_node)
+                .map(|c| c.id)
+                .collect();
+            for connection_id in existing {
+                graph.disconnect(connection_id);
+            }
+            graph.connect(from_node, from_port, to_node, to_port).map(|_| ())
+        }
+        result => result.map(|_| ()),
+    }
+}
+
+/// Distance from a point to the segment between `a` and `b`
+fn distance_to_segment(point: Pos2, a: Pos2, b: Pos2) -> f32 {
+    let ab = b - a;
+    let length_sq = ab.length_sq();
+    if length_sq <= f32::EPSILON {
+        return point.distance(a);
+    }
+    let t = ((point - a).dot(ab) / length_sq).clamp(0.0, 1.0);
+    point.distance(a + ab * t)
+}
+
 /// Generate points along a cubic bezier curve
 fn bezier_points(p0: Pos2, p1: Pos2, p2: Pos2, p3: Pos2, segments: usize) -> Vec<Pos2> {
     let mut points = Vec::with_capacity(segments + 1);
@@ -902,3 +1862,197 @@ fn bezier_points(p0: Pos2, p1: Pos2, p2: Pos2, p3: Pos2, segments: usize) -> Vec
     }
     points
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preview_node_follows_last_selection() {
+        let mut state = GraphEditorState::new();
+        let (first, second) = (NodeId::new(), NodeId::new());
+        assert_eq!(state.preview_node(), None);
+
+        state.select_node(first, false);
+        state.select_node(second, true);
+        assert_eq!(state.preview_node(), Some(second));
+
+        state.toggle_node_selection(second);
+        assert_eq!(state.preview_node(), Some(first));
+
+        state.clear_selection();
+        assert_eq!(state.preview_node(), None);
+    }
+
+    #[test]
+    fn test_classify_candidates_from_output() {
+        let source_node = NodeId::new();
+        let other_node = NodeId::new();
+        let source = Port::output("Value", PortType::Float);
+        let same_node_input = Port::input("In", PortType::Float);
+        let float_input = Port::input("Float", PortType::Float);
+        let vector_input = Port::input("Vector", PortType::Vector3);
+        let bool_input = Port::input("Bool", PortType::Bool);
+        let other_output = Port::output("Out", PortType::Float);
+
+        let candidates = classify_connection_candidates(
+            source_node,
+            &source,
+            [
+                (source_node, &source),
+                (source_node, &same_node_input),
+                (other_node, &float_input),
+                (other_node, &vector_input),
+                (other_node, &bool_input),
+                (other_node, &other_output),
+            ],
+        );
+
+        assert_eq!(candidates[&(source_node, source.id)], PortCompatibility::Source);
+        assert_eq!(candidates[&(source_node, same_node_input.id)], PortCompatibility::Incompatible);
+        assert_eq!(candidates[&(other_node, float_input.id)], PortCompatibility::Compatible);
+        assert_eq!(candidates[&(other_node, vector_input.id)], PortCompatibility::Compatible);
+        assert_eq!(candidates[&(other_node, bool_input.id)], PortCompatibility::Incompatible);
+        assert_eq!(candidates[&(other_node, other_output.id)], PortCompatibility::Incompatible);
+    }
+
+    #[test]
+    fn test_classify_candidates_from_input() {
+        let source_node = NodeId::new();
+        let other_node = NodeId::new();
+        let source = Port::input("Vector", PortType::Vector3);
+        let float_output = Port::output("Float", PortType::Float);
+        let vector4_output = Port::output("Vector4", PortType::Vector4);
+        let any_output = Port::output("Any", PortType::Any);
+
+        let candidates = classify_connection_candidates(
+            source_node,
+            &source,
+            [
+                (other_node, &float_output),
+                (other_node, &vector4_output),
+                (other_node, &any_output),
+            ],
+        );
+
+        // Conversions are directional: Float widens into Vector3, Vector4 does not narrow
+        assert_eq!(candidates[&(other_node, float_output.id)], PortCompatibility::Compatible);
+        assert_eq!(candidates[&(other_node, vector4_output.id)], PortCompatibility::Incompatible);
+        assert_eq!(candidates[&(other_node, any_output.id)], PortCompatibility::Compatible);
+    }
+
+    #[test]
+    fn test_first_compatible_port() {
+        let node_type = NodeType {
+            id: "test".to_string(),
+            name: "Test".to_string(),
+            category: crate::node::NodeCategory::Utility,
+            description: String::new(),
+            inputs: vec![Port::input("Flag", PortType::Bool), Port::input("Color", PortType::Color)],
+            outputs: vec![Port::output("Out", PortType::Float)],
+        };
+
+        assert_eq!(first_compatible_port(&node_type, &PortType::Vector4, PortDirection::Output), Some(1));
+        assert_eq!(first_compatible_port(&node_type, &PortType::Texture, PortDirection::Output), None);
+        assert_eq!(first_compatible_port(&node_type, &PortType::Int, PortDirection::Input), Some(0));
+    }
+
+    fn pass_through() -> NodeType {
+        NodeType {
+            id: "pass".to_string(),
+            name: "Pass".to_string(),
+            category: crate::node::NodeCategory::Utility,
+            description: String::new(),
+            inputs: vec![Port::input("In", PortType::Float)],
+            outputs: vec![Port::output("Out", PortType::Float)],
+        }
+    }
+
+    /// Input and output port of a pass-through node
+    fn ports(graph: &Graph, id: NodeId) -> (PortId, PortId) {
+        let node = graph.node(id).unwrap();
+        (node.inputs[0].id, node.outputs[0].id)
+    }
+
+    #[test]
+    fn test_rejected_cycle_keeps_existing_input() {
+        let node_type = pass_through();
+        let mut graph = Graph::new("Test");
+        let [source, a, b] = [(); 3].map(|_| graph.add_node(Node::new(&node_type)));
+        let ((_, source_out), (a_in, a_out), (b_in, b_out)) = (ports(&graph, source), ports(&graph, a), ports(&graph, b));
+        connect_replacing(&mut graph, source, source_out, a, a_in).unwrap();
+        connect_replacing(&mut graph, a, a_out, b, b_in).unwrap();
+
+        let result = connect_replacing(&mut graph, b, b_out, a, a_in);
+        assert!(matches!(result, Err(ConnectionError::WouldCreateCycle(_))));
+        assert_eq!(graph.connections_to(a_in).next().map(|c| c.from_node), Some(source));
+    }
+
+    #[test]
+    fn test_paste_remaps_internal_connections() {
+        let node_type = pass_through();
+        let mut graph = Graph::new("Test");
+        let [outside, a, b] = [(); 3].map(|_| graph.add_node(Node::new(&node_type).with_position(10.0, 20.0)));
+        let ((_, outside_out), (a_in, a_out), (b_in, _)) = (ports(&graph, outside), ports(&graph, a), ports(&graph, b));
+        graph.connect(outside, outside_out, a, a_in).unwrap();
+        graph.connect(a, a_out, b, b_in).unwrap();
+
+        let mut state = GraphEditorState::new();
+        state.select_node(a, false);
+        state.select_node(b, true);
+        let text = state.copy_selected(&graph).unwrap();
+        assert_eq!(state.clipboard.as_ref().unwrap().connections.len(), 1);
+
+        assert_eq!(state.paste(&mut graph), 2);
+        assert_eq!(graph.node_count(), 5);
+        assert_eq!(graph.connection_count(), 3);
+        assert!(!state.selected_nodes.contains(&a) && !state.selected_nodes.contains(&b));
+
+        let pasted: Vec<&Node> = state.selected_nodes.iter().map(|id| graph.node(*id).unwrap()).collect();
+        for node in &pasted {
+            assert_eq!(node.position, [10.0 + PASTE_OFFSET, 20.0 + PASTE_OFFSET]);
+            // The outside connection was dropped
+            assert!(graph.connections_for_node(node.id).all(|c| c.from_node != outside));
+        }
+        let (new_a_in, new_a_out) = (pasted[0].inputs[0].id, pasted[0].outputs[0].id);
+        assert_ne!(new_a_in, a_in);
+        assert_ne!(new_a_out, a_out);
+        let internal = graph.connections().filter(|c| state.selected_nodes.contains(&c.from_node)).count();
+        assert_eq!(internal, 1);
+
+        // Pasting the same text again fans out instead of overlapping
+        assert_eq!(state.paste_text(&mut graph, &text), 2);
+        let position = graph.node(*state.selected_nodes.iter().next().unwrap()).unwrap().position;
+        assert_eq!(position, [10.0 + PASTE_OFFSET * 2.0, 20.0 + PASTE_OFFSET * 2.0]);
+        assert_eq!(state.paste_text(&mut graph, "not a graph"), 0);
+    }
+
+    #[test]
+    fn test_comment_selection_frames_nodes() {
+        let node_type = pass_through();
+        let mut graph = Graph::new("Test");
+        let a = graph.add_node(Node::new(&node_type).with_position(0.0, 0.0));
+        let b = graph.add_node(Node::new(&node_type).with_position(300.0, 200.0));
+        let outside = graph.add_node(Node::new(&node_type).with_position(2000.0, 0.0));
+
+        let mut state = GraphEditorState::new();
+        assert!(state.comment_selection(&mut graph).is_none());
+        state.select_node(a, false);
+        state.select_node(b, true);
+        let comment_id = state.comment_selection(&mut graph).unwrap();
+        assert_eq!(state.selected_comment, Some(comment_id));
+
+        let comment = graph.comment(comment_id).unwrap();
+        let mut framed = state.nodes_in_comment(&graph, comment);
+        framed.sort_by_key(|id| graph.nodes().position(|n| n.id == *id));
+        assert_eq!(framed, vec![a, b]);
+        assert!(!framed.contains(&outside));
+
+        // Deleting the comment keeps its nodes
+        state.clear_selection();
+        state.selected_comment = Some(comment_id);
+        state.delete_selected(&mut graph);
+        assert!(graph.comment(comment_id).is_none());
+        assert_eq!(graph.node_count(), 3);
+    }
+}
@@ -50,4 +50,61 @@ impl EntityBinding {
             property_path: Some(property_path.into()),
         }
     }
+
+    /// Create a binding to one transform channel of an entity
+    pub fn transform(entity_id: EntityId, channel: TransformChannel) -> Self {
+        Self::property(entity_id, "Transform", channel.property_path())
+    }
+
+    /// Transform channel this binding animates, if any.
+    ///
+    /// A Transform binding without a property path animates position.
+    pub fn transform_channel(&self) -> Option<TransformChannel> {
+        if let Some(component) = &self.component {
+            if !component.eq_ignore_ascii_case("transform") {
+                return None;
+            }
+        }
+
+        match self.property_path.as_deref() {
+            None | Some("position") | Some("transform.position") => Some(TransformChannel::Position),
+            Some("rotation") | Some("transform.rotation") => Some(TransformChannel::Rotation),
+            Some("scale") | Some("transform.scale") => Some(TransformChannel::Scale),
+            Some(_) => None,
+        }
+    }
+}
+
+/// Transform channel animated by a bound `Vec3` track
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TransformChannel {
+    /// Position relative to the parent
+    Position,
+    /// Euler rotation in degrees
+    Rotation,
+    /// Scale
+    Scale,
+}
+
+impl TransformChannel {
+    /// All transform channels
+    pub const ALL: [Self; 3] = [Self::Position, Self::Rotation, Self::Scale];
+
+    /// Property path tracks use to bind this channel
+    pub fn property_path(self) -> &'static str {
+        match self {
+            Self::Position => "position",
+            Self::Rotation => "rotation",
+            Self::Scale => "scale",
+        }
+    }
+
+    /// Display name
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Position => "Position",
+            Self::Rotation => "Rotation",
+            Self::Scale => "Scale",
+        }
+    }
 }
@@ -34,8 +34,41 @@ pub enum InterpolationMode {
     Bezier,
     /// Auto-smooth
     Auto,
+    /// Cubic ease-in (starts slow)
+    EaseIn,
+    /// Cubic ease-out (ends slow)
+    EaseOut,
+    /// Cubic ease-in-out (starts and ends slow)
+    EaseInOut,
 }
 
+impl InterpolationMode {
+    /// Display name
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Constant => "Step",
+            Self::Linear => "Linear",
+            Self::Bezier => "Bezier",
+            Self::Auto => "Auto",
+            Self::EaseIn => "Ease In",
+            Self::EaseOut => "Ease Out",
+            Self::EaseInOut => "Ease In/Out",
+        }
+    }
+
+    /// Remap linear progress `t` (0 to 1) through a segment for this mode.
+    /// Step holds the start value; bezier and auto shapes come from
+    /// tangents rather than `t`, so they pass it through.
+    pub fn ease(&self, t: f32) -> f32 {
+        match self {
+            Self::Constant => 0.0,
+            Self::Linear | Self::Bezier | Self::Auto => t,
+            Self::EaseIn => Interpolation::ease_in(t),
+            Self::EaseOut => Interpolation::ease_out(t),
+            Self::EaseInOut => Interpolation::ease_in_out(t),
+        }
+    }
+}
 
 /// Value stored in a keyframe
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -67,9 +100,9 @@ pub struct Keyframe {
     pub value: KeyframeValue,
     /// Interpolation mode to next keyframe
     pub interpolation: InterpolationMode,
-    /// In-tangent for bezier curves
+    /// In-tangent for bezier curves, as a (time, value) offset from the key
     pub in_tangent: Option<[f32; 2]>,
-    /// Out-tangent for bezier curves
+    /// Out-tangent for bezier curves, as a (time, value) offset from the key
     pub out_tangent: Option<[f32; 2]>,
 }
 
@@ -98,6 +131,29 @@ impl Keyframe {
         self.out_tangent = Some(out_tangent);
         self
     }
+
+    /// Copy of this keyframe at `time` with a new ID
+    pub fn duplicate_at(&self, time: f32) -> Self {
+        Self {
+            id: KeyframeId::new(),
+            time,
+            ..self.clone()
+        }
+    }
+
+    /// Out-tangent offset for a following segment of `span` seconds.
+    /// Defaults to a flat handle a third of the way along; the time offset
+    /// is kept inside the segment so the curve can't double back in time.
+    pub fn out_handle(&self, span: f32) -> [f32; 2] {
+        let [dt, dv] = self.out_tangent.unwrap_or([span / 3.0, 0.0]);
+        [dt.clamp(0.0, span), dv]
+    }
+
+    /// In-tangent offset for a preceding segment of `span` seconds
+    pub fn in_handle(&self, span: f32) -> [f32; 2] {
+        let [dt, dv] = self.in_tangent.unwrap_or([-span / 3.0, 0.0]);
+        [dt.clamp(-span, 0.0), dv]
+    }
 }
 
 /// Interpolation utilities
@@ -109,6 +165,25 @@ impl Interpolation {
         a + (b - a) * t
     }
 
+    /// Cubic ease-in: slow start, fast finish
+    pub fn ease_in(t: f32) -> f32 {
+        t * t * t
+    }
+
+    /// Cubic ease-out: fast start, slow finish
+    pub fn ease_out(t: f32) -> f32 {
+        1.0 - Self::ease_in(1.0 - t)
+    }
+
+    /// Cubic ease-in-out: slow at both ends
+    pub fn ease_in_out(t: f32) -> f32 {
+        if t < 0.5 {
+            4.0 * t * t * t
+        } else {
+            1.0 - (2.0 - 2.0 * t).powi(3) / 2.0
+        }
+    }
+
     /// Cubic bezier interpolation
     pub fn bezier(p0: f32, p1: f32, p2: f32, p3: f32, t: f32) -> f32 {
         let t2 = t * t;
@@ -120,6 +195,22 @@ impl Interpolation {
         p0 * mt3 + 3.0 * p1 * mt2 * t + 3.0 * p2 * mt * t2 + p3 * t3
     }
 
+    /// Value at `time` on a cubic bezier curve through (time, value)
+    /// points. The control point times must lie between `p0` and `p3`.
+    pub fn bezier_at_time(p0: [f32; 2], p1: [f32; 2], p2: [f32; 2], p3: [f32; 2], time: f32) -> f32 {
+        // Time is monotonic along the curve, so bisect for the parameter
+        let (mut lo, mut hi) = (0.0, 1.0);
+        for _ in 0..24 {
+            let mid = (lo + hi) * 0.5;
+            if Self::bezier(p0[0], p1[0], p2[0], p3[0], mid) < time {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+        }
+        Self::bezier(p0[1], p1[1], p2[1], p3[1], (lo + hi) * 0.5)
+    }
+
     /// Hermite spline interpolation (for auto-smooth)
     pub fn hermite(p0: f32, m0: f32, p1: f32, m1: f32, t: f32) -> f32 {
         let t2 = t * t;
@@ -182,7 +273,7 @@ impl Interpolation {
         let sin_theta = theta.sin();
         let sin_theta_0 = theta_0.sin();
 
-        let s0 = (theta_0 - theta).cos() - dot * sin_theta / sin_theta_0;
+        let s0 = (theta_0 - theta).sin() / sin_theta_0;
         let s1 = sin_theta / sin_theta_0;
 
         [
@@ -229,9 +320,17 @@ impl KeyframeValue {
                 // Bezier uses same logic for now (tangents handled at track level)
                 self.interpolate(other, t, InterpolationMode::Linear)
             }
+            InterpolationMode::EaseIn | InterpolationMode::EaseOut | InterpolationMode::EaseInOut => {
+                self.interpolate(other, mode.ease(t), InterpolationMode::Linear)
+            }
         }
     }
 
+    /// Whether both values are the same kind, e.g. both `Float`
+    pub fn same_kind(&self, other: &KeyframeValue) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+
     /// Get as float if possible
     pub fn as_float(&self) -> Option<f32> {
         match self {
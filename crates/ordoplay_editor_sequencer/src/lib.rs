@@ -19,8 +19,10 @@
 pub mod track;
 pub mod keyframe;
 pub mod binding;
+pub mod math;
 pub mod sequence;
 pub mod ui;
+pub mod waveform;
 
 pub use track::{
     Track, TrackId, TrackType,
@@ -28,6 +30,7 @@ pub use track::{
     EventTrack, EventMarker,
 };
 pub use keyframe::{Keyframe, KeyframeId, InterpolationMode, KeyframeValue, Interpolation};
-pub use binding::{EntityBinding, EntityId};
-pub use sequence::{Sequence, SequenceId, PlaybackState, PlaybackController, LoopMarker, TimeMarker};
-pub use ui::{SequencerState, SequencerPanel, ViewMode, Selection, DragOperation};
+pub use binding::{EntityBinding, EntityId, TransformChannel};
+pub use sequence::{Sequence, SequenceId, BindingSample, PlaybackState, PlaybackController, LoopMarker, LoopRegionError, TimeMarker};
+pub use ui::{SequencerState, SequencerPanel, ViewMode, Selection, DragOperation, CurveHandle, KeyframeClipboard};
+pub use waveform::{Waveform, WaveformCache, WaveformError, WaveformState};
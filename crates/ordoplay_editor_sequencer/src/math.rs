@@ -0,0 +1,71 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//! Rotation math shared by the sequencer and the editor.
+//!
+//! Euler angles are in degrees as (roll X, pitch Y, yaw Z), composed in
+//! yaw-pitch-roll order; quaternions are `[x, y, z, w]`.
+
+/// Convert euler angles (degrees) to quaternion [x, y, z, w]
+pub fn euler_to_quaternion(euler_deg: [f32; 3]) -> [f32; 4] {
+    let half_x = (euler_deg[0] * std::f32::consts::PI / 180.0) * 0.5;
+    let half_y = (euler_deg[1] * std::f32::consts::PI / 180.0) * 0.5;
+    let half_z = (euler_deg[2] * std::f32::consts::PI / 180.0) * 0.5;
+
+    let (sx, cx) = half_x.sin_cos();
+    let (sy, cy) = half_y.sin_cos();
+    let (sz, cz) = half_z.sin_cos();
+
+    [
+        sx * cy * cz - cx * sy * sz, // x
+        cx * sy * cz + sx * cy * sz, // y
+        cx * cy * sz - sx * sy * cz, // z
+        cx * cy * cz + sx * sy * sz, // w
+    ]
+}
+
+/// Convert quaternion [x, y, z, w] to euler angles (degrees)
+pub fn quaternion_to_euler(q: [f32; 4]) -> [f32; 3] {
+    let (x, y, z, w) = (q[0], q[1], q[2], q[3]);
+
+    // Roll (X)
+    let sinr_cosp = 2.0 * (w * x + y * z);
+    let cosr_cosp = 1.0 - 2.0 * (x * x + y * y);
+    let roll = sinr_cosp.atan2(cosr_cosp);
+
+    // Pitch (Y)
+    let sinp = 2.0 * (w * y - z * x);
+    let pitch = if sinp.abs() >= 1.0 {
+        std::f32::consts::FRAC_PI_2.copysign(sinp)
+    } else {
+        sinp.asin()
+    };
+
+    // Yaw (Z)
+    let siny_cosp = 2.0 * (w * z + x * y);
+    let cosy_cosp = 1.0 - 2.0 * (y * y + z * z);
+    let yaw = siny_cosp.atan2(cosy_cosp);
+
+    [
+        roll * 180.0 / std::f32::consts::PI,
+        pitch * 180.0 / std::f32::consts::PI,
+        yaw * 180.0 / std::f32::consts::PI,
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_euler_quaternion_round_trip() {
+        for euler in [[0.0, 0.0, 0.0], [30.0, -45.0, 120.0], [-170.0, 10.0, 90.0]] {
+            let back = quaternion_to_euler(euler_to_quaternion(euler));
+            for (a, b) in euler.iter().zip(back) {
+                assert!((a - b).abs() < 1e-3, "{euler:?} came back as {back:?}");
+            }
+        }
+
+        // A half turn about Y
+        let q = euler_to_quaternion([0.0, 180.0, 0.0]);
+        assert!(q[1].abs() > 0.999 && q[3].abs() < 1e-3);
+    }
+}
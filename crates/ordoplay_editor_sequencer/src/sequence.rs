@@ -1,7 +1,9 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 //! Sequence containing multiple tracks.
 
-use crate::track::{Track, TrackId};
+use crate::binding::{EntityBinding, EntityId, TransformChannel};
+use crate::keyframe::KeyframeValue;
+use crate::track::{Track, TrackId, TrackType};
 use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
@@ -37,6 +39,17 @@ pub enum PlaybackState {
     Reverse,
 }
 
+/// Value of one bound track sampled at a point in time
+#[derive(Debug, Clone)]
+pub struct BindingSample {
+    /// Track the value was sampled from
+    pub track_id: TrackId,
+    /// Entity/property the track drives
+    pub binding: EntityBinding,
+    /// Evaluated value
+    pub value: KeyframeValue,
+}
+
 /// A sequence of tracks
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Sequence {
@@ -106,6 +119,63 @@ impl Sequence {
             .fold(0.0, f32::max)
     }
 
+    /// Sample every bound, non-muted track at `time`.
+    ///
+    /// Tracks without a binding or without keyframes are skipped.
+    pub fn sample_at(&self, time: f32) -> Vec<BindingSample> {
+        self.tracks
+            .values()
+            .filter(|track| !track.muted)
+            .filter_map(|track| {
+                let binding = track.binding.clone()?;
+                let value = track.evaluate(time)?;
+                Some(BindingSample { track_id: track.id, binding, value })
+            })
+            .collect()
+    }
+
+    /// Find the track animating `channel` of `entity`
+    pub fn transform_track(&self, entity: EntityId, channel: TransformChannel) -> Option<TrackId> {
+        self.tracks
+            .values()
+            .find(|track| {
+                track.binding.as_ref().is_some_and(|binding| {
+                    binding.entity_id == entity && binding.transform_channel() == Some(channel)
+                })
+            })
+            .map(|track| track.id)
+    }
+
+    /// Key `value` on the track animating `channel` of `entity` at `time`.
+    ///
+    /// A key already at `time` is overwritten. When the entity has no track
+    /// for the channel one is added, named after `entity_name`. Returns the
+    /// keyed track, or `None` when that track is locked.
+    pub fn record_transform(
+        &mut self,
+        entity: EntityId,
+        entity_name: &str,
+        channel: TransformChannel,
+        value: [f32; 3],
+        time: f32,
+    ) -> Option<TrackId> {
+        let track_id = match self.transform_track(entity, channel) {
+            Some(track_id) => track_id,
+            None => {
+                let mut track = Track::new(format!("{} {}", entity_name, channel.name()), TrackType::Transform);
+                track.binding = Some(EntityBinding::transform(entity, channel));
+                self.add_track(track)
+            }
+        };
+
+        let track = self.tracks.get_mut(&track_id)?;
+        if track.locked {
+            return None;
+        }
+        track.set_keyframe_at(time, KeyframeValue::Vec3(value));
+        Some(track_id)
+    }
+
     /// Convert time to frame number
     pub fn time_to_frame(&self, time: f32) -> u32 {
         (time * self.frame_rate) as u32
@@ -131,10 +201,10 @@ pub struct PlaybackController {
     pub state: PlaybackState,
     /// Playback speed multiplier
     pub speed: f32,
-    /// Loop start point (for loop range)
-    pub loop_start: Option<f32>,
-    /// Loop end point (for loop range)
-    pub loop_end: Option<f32>,
+    /// Loop in/out points, set with `set_loop_region`
+    loop_region: Option<LoopMarker>,
+    /// Whether playback wraps inside the loop region
+    pub loop_enabled: bool,
     /// Events triggered this frame
     pending_events: Vec<(TrackId, String)>,
 }
@@ -146,8 +216,8 @@ impl PlaybackController {
             time: 0.0,
             state: PlaybackState::Stopped,
             speed: 1.0,
-            loop_start: None,
-            loop_end: None,
+            loop_region: None,
+            loop_enabled: false,
             pending_events: Vec::new(),
         }
     }
@@ -172,12 +242,19 @@ impl PlaybackController {
 
     /// Check and handle end of sequence
     fn check_bounds(&mut self, sequence: &Sequence) {
-        let end_time = self.loop_end.unwrap_or(sequence.duration);
+        if let Some(region) = self.active_loop().and_then(|region| region.within(sequence)) {
+            // Wrap to the in point, keeping any overshoot
+            if self.time >= region.end {
+                self.time = region.start + (self.time - region.end) % region.length();
+            }
+            return;
+        }
+
+        let end_time = sequence.duration;
 
         if self.time >= end_time {
-            if sequence.looping || self.loop_end.is_some() {
-                let start = self.loop_start.unwrap_or(0.0);
-                self.time = start + (self.time - end_time);
+            if sequence.looping {
+                self.time -= end_time;
             } else {
                 self.time = end_time;
                 self.state = PlaybackState::Stopped;
@@ -187,14 +264,18 @@ impl PlaybackController {
 
     /// Check and handle reverse playback bounds
     fn check_bounds_reverse(&mut self, sequence: &Sequence) {
-        let start_time = self.loop_start.unwrap_or(0.0);
+        if let Some(region) = self.active_loop().and_then(|region| region.within(sequence)) {
+            if self.time <= region.start {
+                self.time = region.end - (region.start - self.time) % region.length();
+            }
+            return;
+        }
 
-        if self.time <= start_time {
-            if sequence.looping || self.loop_start.is_some() {
-                let end = self.loop_end.unwrap_or(sequence.duration);
-                self.time = end - (start_time - self.time);
+        if self.time <= 0.0 {
+            if sequence.looping {
+                self.time += sequence.duration;
             } else {
-                self.time = start_time;
+                self.time = 0.0;
                 self.state = PlaybackState::Stopped;
             }
         }
@@ -205,13 +286,13 @@ impl PlaybackController {
         self.pending_events.clear();
 
         for track in sequence.tracks() {
-            if track.muted || track.track_type != crate::track::TrackType::Event {
+            if track.muted || track.track_type != TrackType::Event {
                 continue;
             }
 
             // Check for events at current time (within a small window)
             for keyframe in track.keyframes_in_range(self.time - 0.016, self.time) {
-                if let crate::keyframe::KeyframeValue::Event(event_name) = &keyframe.value {
+                if let KeyframeValue::Event(event_name) = &keyframe.value {
                     self.pending_events.push((track.id, event_name.clone()));
                 }
             }
@@ -238,7 +319,7 @@ impl PlaybackController {
     /// Stop and reset to beginning
     pub fn stop(&mut self) {
         self.state = PlaybackState::Stopped;
-        self.time = self.loop_start.unwrap_or(0.0);
+        self.time = self.active_loop().map_or(0.0, |region| region.start);
     }
 
     /// Toggle play/pause
@@ -259,16 +340,38 @@ impl PlaybackController {
         self.time = time.max(0.0);
     }
 
-    /// Set loop range
-    pub fn set_loop_range(&mut self, start: f32, end: f32) {
-        self.loop_start = Some(start);
-        self.loop_end = Some(end);
+    /// Set the loop in and out points and enable looping. Both points are
+    /// clamped to `sequence`'s duration. Regions whose out point is before
+    /// the in point, or that are shorter than one frame, are rejected and
+    /// leave the current region.
+    pub fn set_loop_region(&mut self, start: f32, end: f32, sequence: &Sequence) -> Result<(), LoopRegionError> {
+        let region = LoopMarker::new(start, end, sequence.frame_rate)?
+            .within(sequence)
+            .ok_or(LoopRegionError::TooShort)?;
+        self.loop_region = Some(region);
+        self.loop_enabled = true;
+        Ok(())
+    }
+
+    /// Clear the loop region
+    pub fn clear_loop_region(&mut self) {
+        self.loop_region = None;
+        self.loop_enabled = false;
+    }
+
+    /// The loop region, whether or not looping is enabled
+    pub fn loop_region(&self) -> Option<LoopMarker> {
+        self.loop_region
+    }
+
+    /// Turn looping inside the loop region on or off
+    pub fn toggle_loop(&mut self) {
+        self.loop_enabled = !self.loop_enabled;
     }
 
-    /// Clear loop range
-    pub fn clear_loop_range(&mut self) {
-        self.loop_start = None;
-        self.loop_end = None;
+    /// The loop region if looping is enabled
+    fn active_loop(&self) -> Option<LoopMarker> {
+        self.loop_region.filter(|_| self.loop_enabled)
     }
 
     /// Is currently playing (forward or reverse)
@@ -282,7 +385,7 @@ impl PlaybackController {
     }
 
     /// Evaluate all tracks at current time
-    pub fn evaluate_all(&self, sequence: &Sequence) -> Vec<(TrackId, crate::keyframe::KeyframeValue)> {
+    pub fn evaluate_all(&self, sequence: &Sequence) -> Vec<(TrackId, KeyframeValue)> {
         let mut results = Vec::new();
 
         for track in sequence.tracks() {
@@ -306,7 +409,7 @@ impl Default for PlaybackController {
 }
 
 /// Marker for loop region
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct LoopMarker {
     /// Start time
     pub start: f32,
@@ -314,6 +417,47 @@ pub struct LoopMarker {
     pub end: f32,
 }
 
+impl LoopMarker {
+    /// Create a loop region, rejecting inverted regions and regions shorter
+    /// than one frame at `frame_rate`
+    pub fn new(start: f32, end: f32, frame_rate: f32) -> Result<Self, LoopRegionError> {
+        if end < start {
+            return Err(LoopRegionError::Inverted);
+        }
+        if end - start < 1.0 / frame_rate.max(1.0) {
+            return Err(LoopRegionError::TooShort);
+        }
+        Ok(Self {
+            start: start.max(0.0),
+            end,
+        })
+    }
+
+    /// Length of the region in seconds
+    pub fn length(&self) -> f32 {
+        self.end - self.start
+    }
+
+    /// The region cut to `sequence`'s duration, or `None` when less than a
+    /// frame of it is left, such as after the sequence was shortened
+    pub fn within(self, sequence: &Sequence) -> Option<Self> {
+        let duration = sequence.duration.max(0.0);
+        Self::new(self.start.min(duration), self.end.min(duration), sequence.frame_rate).ok()
+    }
+}
+
+/// Error when setting a loop region
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum LoopRegionError {
+    /// The out point is before the in point
+    #[error("Loop out point is before the in point")]
+    Inverted,
+
+    /// The region is shorter than one frame
+    #[error("Loop region is shorter than one frame")]
+    TooShort,
+}
+
 /// Time marker in the sequence
 #[derive(Debug, Clone)]
 pub struct TimeMarker {
@@ -324,3 +468,141 @@ pub struct TimeMarker {
     /// Marker color
     pub color: [u8; 3],
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keyframe::Keyframe;
+
+    fn bound_track(entity: EntityId, keys: &[(f32, f32)]) -> Track {
+        let mut track = Track::new("Position", TrackType::Transform);
+        track.binding = Some(EntityBinding::property(entity, "Transform", "position"));
+        for &(time, x) in keys {
+            track.add_keyframe(Keyframe::new(time, KeyframeValue::Vec3([x, 0.0, 0.0])));
+        }
+        track
+    }
+
+    fn sampled_x(samples: &[BindingSample]) -> f32 {
+        match samples[0].value {
+            KeyframeValue::Vec3(v) => v[0],
+            ref other => panic!("unexpected value {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_sample_at_skips_unbound_and_muted_tracks() {
+        let entity = EntityId(Uuid::new_v4());
+        let mut sequence = Sequence::new("Test");
+        sequence.add_track(bound_track(entity, &[(0.0, 1.0)]));
+
+        let mut unbound = Track::new("Unbound", TrackType::Property);
+        unbound.add_keyframe(Keyframe::new(0.0, KeyframeValue::Float(1.0)));
+        sequence.add_track(unbound);
+
+        let mut muted = bound_track(entity, &[(0.0, 2.0)]);
+        muted.muted = true;
+        sequence.add_track(muted);
+
+        let samples = sequence.sample_at(0.0);
+        assert_eq!(samples.len(), 1);
+        assert_eq!(samples[0].binding.entity_id, entity);
+        assert_eq!(sampled_x(&samples), 1.0);
+    }
+
+    #[test]
+    fn test_record_transform_overwrites_and_adds_tracks() {
+        let entity = EntityId(Uuid::new_v4());
+        let mut sequence = Sequence::new("Test");
+        let position = sequence.add_track(bound_track(entity, &[(0.0, 1.0), (1.0, 2.0)]));
+
+        let keyed = sequence.record_transform(entity, "Hero", TransformChannel::Position, [5.0, 0.0, 0.0], 1.0);
+        assert_eq!(keyed, Some(position));
+        let track = sequence.track(position).unwrap();
+        assert_eq!(track.keyframes.len(), 2);
+        assert_eq!(track.evaluate(1.0).and_then(|v| v.as_vec3()), Some([5.0, 0.0, 0.0]));
+
+        let rotation = sequence
+            .record_transform(entity, "Hero", TransformChannel::Rotation, [0.0, 90.0, 0.0], 0.5)
+            .unwrap();
+        assert_ne!(rotation, position);
+        assert_eq!(sequence.track(rotation).unwrap().name, "Hero Rotation");
+        assert!(sequence.track(rotation).unwrap().is_rotation());
+        assert_eq!(sequence.transform_track(entity, TransformChannel::Rotation), Some(rotation));
+
+        sequence.track_mut(position).unwrap().locked = true;
+        let keyed = sequence.record_transform(entity, "Hero", TransformChannel::Position, [9.0, 0.0, 0.0], 2.0);
+        assert_eq!(keyed, None);
+        assert_eq!(sequence.track(position).unwrap().keyframes.len(), 2);
+    }
+
+    #[test]
+    fn test_playback_wraps_inside_loop_region() {
+        let sequence = Sequence::new("Test");
+        let mut playback = PlaybackController::new();
+        playback.set_loop_region(2.0, 4.0, &sequence).unwrap();
+        playback.seek(3.5);
+        playback.play();
+
+        // Passing the out point wraps to the in point, keeping the overshoot
+        playback.update(1.0, &sequence);
+        assert!((playback.time - 2.5).abs() < 1e-4);
+        assert!(playback.is_playing());
+
+        // Reverse playback wraps the other way
+        playback.play_reverse();
+        playback.update(1.0, &sequence);
+        assert!((playback.time - 3.5).abs() < 1e-4);
+
+        // With looping off, playback runs on to the sequence end
+        playback.toggle_loop();
+        playback.play();
+        playback.update(1.0, &sequence);
+        assert!((playback.time - 4.5).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_invalid_loop_regions_are_rejected() {
+        let mut sequence = Sequence::new("Test");
+        sequence.frame_rate = 30.0;
+        let mut playback = PlaybackController::new();
+        playback.set_loop_region(1.0, 2.0, &sequence).unwrap();
+
+        assert_eq!(playback.set_loop_region(3.0, 2.0, &sequence), Err(LoopRegionError::Inverted));
+        assert_eq!(playback.set_loop_region(1.0, 1.01, &sequence), Err(LoopRegionError::TooShort));
+        assert_eq!(playback.loop_region(), Some(LoopMarker { start: 1.0, end: 2.0 }));
+    }
+
+    #[test]
+    fn test_loop_region_is_clamped_to_the_sequence() {
+        let mut sequence = Sequence::new("Test");
+        sequence.duration = 5.0;
+        let mut playback = PlaybackController::new();
+
+        playback.set_loop_region(3.0, 8.0, &sequence).unwrap();
+        assert_eq!(playback.loop_region(), Some(LoopMarker { start: 3.0, end: 5.0 }));
+        assert_eq!(playback.set_loop_region(6.0, 8.0, &sequence), Err(LoopRegionError::TooShort));
+
+        // Shortening the sequence pulls the loop's out point in with it
+        sequence.duration = 4.0;
+        playback.seek(3.5);
+        playback.play();
+        playback.update(1.0, &sequence);
+        assert!((playback.time - 3.5).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_two_sequences_sample_independently() {
+        let entity = EntityId(Uuid::new_v4());
+        let mut active = Sequence::new("Active");
+        active.add_track(bound_track(entity, &[(0.0, 0.0), (2.0, 10.0)]));
+        let mut reference = Sequence::new("Reference");
+        reference.add_track(bound_track(entity, &[(0.0, 0.0), (2.0, 4.0)]));
+
+        let active_x = sampled_x(&active.sample_at(1.0));
+        let reference_x = sampled_x(&reference.sample_at(1.0));
+
+        assert!((active_x - 5.0).abs() < 1e-4);
+        assert!((reference_x - 2.0).abs() < 1e-4);
+    }
+}
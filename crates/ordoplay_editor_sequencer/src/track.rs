@@ -2,7 +2,7 @@
 //! Track definitions for the sequencer.
 
 use crate::keyframe::Keyframe;
-use crate::binding::EntityBinding;
+use crate::binding::{EntityBinding, TransformChannel};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -85,6 +85,9 @@ pub struct Track {
     pub locked: bool,
     /// Track color override
     pub color: Option<[u8; 3]>,
+    /// Audio clip regions (audio tracks only)
+    #[serde(default)]
+    pub clips: Vec<AudioClip>,
 }
 
 impl Track {
@@ -99,6 +102,7 @@ impl Track {
             muted: false,
             locked: false,
             color: None,
+            clips: Vec::new(),
         }
     }
 
@@ -168,12 +172,44 @@ impl Track {
                 if (b.time - a.time).abs() < 0.0001 {
                     return Some(b.value.clone());
                 }
+                // Float bezier segments follow the keys' tangent handles
+                if a.interpolation == crate::keyframe::InterpolationMode::Bezier {
+                    if let (Some(v0), Some(v1)) = (a.value.as_float(), b.value.as_float()) {
+                        let span = b.time - a.time;
+                        let [out_dt, out_dv] = a.out_handle(span);
+                        let [in_dt, in_dv] = b.in_handle(span);
+                        let value = crate::keyframe::Interpolation::bezier_at_time(
+                            [a.time, v0],
+                            [a.time + out_dt, v0 + out_dv],
+                            [b.time + in_dt, v1 + in_dv],
+                            [b.time, v1],
+                            time,
+                        );
+                        return Some(crate::keyframe::KeyframeValue::Float(value));
+                    }
+                }
                 let t = (time - a.time) / (b.time - a.time);
+                // Euler rotation keys take the shortest path via quaternions
+                if self.is_rotation() {
+                    if let (Some(from), Some(to)) = (a.value.as_vec3(), b.value.as_vec3()) {
+                        let euler = slerp_euler(from, to, a.interpolation.ease(t));
+                        return Some(crate::keyframe::KeyframeValue::Vec3(euler));
+                    }
+                }
                 a.value.interpolate(&b.value, t, a.interpolation)
             }
         }
     }
 
+    /// Whether the track animates a transform rotation, which is keyed as
+    /// euler angles in degrees
+    pub fn is_rotation(&self) -> bool {
+        self.binding
+            .as_ref()
+            .and_then(EntityBinding::transform_channel)
+            == Some(TransformChannel::Rotation)
+    }
+
     /// Get keyframes in a time range
     pub fn keyframes_in_range(&self, start: f32, end: f32) -> Vec<&Keyframe> {
         self.keyframes
@@ -249,12 +285,38 @@ impl Track {
         }
     }
 
+    /// Insert a keyframe at `time` holding the value the track currently
+    /// evaluates to there, so the curve keeps its shape.
+    ///
+    /// The new keyframe inherits the interpolation of the segment it splits,
+    /// so inserting between stepped keyframes captures the held value and
+    /// stays stepped. A keyframe already at `time` is updated instead.
+    /// Returns `None` if the track has no keyframes to sample.
+    pub fn insert_keyframe_at_current_value(&mut self, time: f32) -> Option<crate::keyframe::KeyframeId> {
+        let value = self.evaluate(time)?;
+
+        let threshold = 0.001;
+        if let Some(existing) = self.keyframes.iter_mut().find(|k| (k.time - time).abs() < threshold) {
+            existing.value = value;
+            return Some(existing.id);
+        }
+
+        let interpolation = match self.find_keyframes(time) {
+            (Some(prev), _) => prev.interpolation,
+            (None, Some(next)) => next.interpolation,
+            (None, None) => crate::keyframe::InterpolationMode::Linear,
+        };
+
+        let keyframe = Keyframe::new(time, value).with_interpolation(interpolation);
+        let id = keyframe.id;
+        self.add_keyframe(keyframe);
+        Some(id)
+    }
+
     /// Duplicate keyframe to new time
     pub fn duplicate_keyframe(&mut self, keyframe_id: crate::keyframe::KeyframeId, new_time: f32) -> Option<crate::keyframe::KeyframeId> {
-        if let Some(source) = self.keyframe(keyframe_id).cloned() {
-            let mut new_kf = source;
-            new_kf.id = crate::keyframe::KeyframeId::new();
-            new_kf.time = new_time;
+        if let Some(source) = self.keyframe(keyframe_id) {
+            let new_kf = source.duplicate_at(new_time);
             let new_id = new_kf.id;
             self.add_keyframe(new_kf);
             Some(new_id)
@@ -263,6 +325,41 @@ impl Track {
         }
     }
 
+    /// Whether a keyframe holding `value` fits this track, i.e. matches the
+    /// kind of its existing values. Empty tracks accept any kind.
+    pub fn accepts_value(&self, value: &crate::keyframe::KeyframeValue) -> bool {
+        self.keyframes.first().map_or(true, |k| k.value.same_kind(value))
+    }
+
+    /// Paste copies of `keyframes` with new IDs, shifted by `time_offset`.
+    ///
+    /// Keyframes already at a pasted time are replaced. Returns `None`
+    /// without touching the track if the pasted values don't all match
+    /// the track's value kind.
+    pub fn paste_keyframes(
+        &mut self,
+        keyframes: &[Keyframe],
+        time_offset: f32,
+    ) -> Option<Vec<crate::keyframe::KeyframeId>> {
+        let first = keyframes.first()?;
+        let compatible = self.accepts_value(&first.value)
+            && keyframes.iter().all(|k| k.value.same_kind(&first.value));
+        if !compatible {
+            return None;
+        }
+
+        let threshold = 0.001;
+        let mut ids = Vec::with_capacity(keyframes.len());
+        for source in keyframes {
+            let pasted = source.duplicate_at((source.time + time_offset).max(0.0));
+            self.keyframes.retain(|k| (k.time - pasted.time).abs() >= threshold);
+            ids.push(pasted.id);
+            self.keyframes.push(pasted);
+        }
+        self.sort_keyframes();
+        Some(ids)
+    }
+
     /// Scale all keyframes by a time factor
     pub fn scale_time(&mut self, factor: f32) {
         for kf in &mut self.keyframes {
@@ -294,6 +391,21 @@ impl Track {
     pub fn keyframes(&self) -> &[Keyframe] {
         &self.keyframes
     }
+
+    /// Get the keyframe times (in order), without values
+    pub fn timing(&self) -> Vec<f32> {
+        self.keyframes.iter().map(|k| k.time).collect()
+    }
+
+    /// Reposition keyframes onto the given times, matching by index.
+    ///
+    /// Extra times are ignored; keyframes beyond the provided times stay where they are.
+    pub fn retime_to(&mut self, times: &[f32]) {
+        for (kf, &time) in self.keyframes.iter_mut().zip(times) {
+            kf.time = time.max(0.0);
+        }
+        self.sort_keyframes();
+    }
 }
 
 /// Transform track with position, rotation, scale channels
@@ -303,7 +415,7 @@ pub struct TransformTrack {
     pub base: Track,
     /// Position channel keyframes
     pub position: Vec<Keyframe>,
-    /// Rotation channel keyframes (quaternion)
+    /// Rotation channel keyframes: quaternions, or euler angles in degrees
     pub rotation: Vec<Keyframe>,
     /// Scale channel keyframes
     pub scale: Vec<Keyframe>,
@@ -346,9 +458,19 @@ impl TransformTrack {
         evaluate_channel_vec3(&self.position, time)
     }
 
-    /// Evaluate rotation at time
+    /// Add a rotation keyframe as euler angles in degrees
+    pub fn add_rotation_euler(&mut self, time: f32, euler: [f32; 3]) {
+        self.add_rotation(time, crate::math::euler_to_quaternion(euler));
+    }
+
+    /// Evaluate rotation at time as a quaternion, slerping between keys
     pub fn evaluate_rotation(&self, time: f32) -> Option<[f32; 4]> {
-        evaluate_channel_vec4(&self.rotation, time)
+        evaluate_channel_quat(&self.rotation, time)
+    }
+
+    /// Evaluate rotation at time as euler angles in degrees, for display
+    pub fn evaluate_rotation_euler(&self, time: f32) -> Option<[f32; 3]> {
+        self.evaluate_rotation(time).map(crate::math::quaternion_to_euler)
     }
 
     /// Evaluate scale at time
@@ -575,7 +697,7 @@ fn evaluate_channel_float(keyframes: &[Keyframe], time: f32) -> Option<f32> {
             if (b.time - a.time).abs() < 0.0001 {
                 return b.value.as_float();
             }
-            let t = (time - a.time) / (b.time - a.time);
+            let t = a.interpolation.ease((time - a.time) / (b.time - a.time));
             let va = a.value.as_float()?;
             let vb = b.value.as_float()?;
             Some(crate::keyframe::Interpolation::lerp(va, vb, t))
@@ -599,7 +721,7 @@ fn evaluate_channel_vec3(keyframes: &[Keyframe], time: f32) -> Option<[f32; 3]>
             if (b.time - a.time).abs() < 0.0001 {
                 return b.value.as_vec3();
             }
-            let t = (time - a.time) / (b.time - a.time);
+            let t = a.interpolation.ease((time - a.time) / (b.time - a.time));
             let va = a.value.as_vec3()?;
             let vb = b.value.as_vec3()?;
             Some(crate::keyframe::Interpolation::lerp_vec3(va, vb, t))
@@ -607,7 +729,22 @@ fn evaluate_channel_vec3(keyframes: &[Keyframe], time: f32) -> Option<[f32; 3]>
     }
 }
 
-fn evaluate_channel_vec4(keyframes: &[Keyframe], time: f32) -> Option<[f32; 4]> {
+/// Rotation key as a quaternion; euler keys (degrees) are converted
+fn rotation_quat(value: &crate::keyframe::KeyframeValue) -> Option<[f32; 4]> {
+    value.as_vec4().or_else(|| value.as_vec3().map(crate::math::euler_to_quaternion))
+}
+
+/// Interpolate euler angles (degrees) along the shortest rotation
+fn slerp_euler(from: [f32; 3], to: [f32; 3], t: f32) -> [f32; 3] {
+    let q = crate::keyframe::Interpolation::slerp(
+        crate::math::euler_to_quaternion(from),
+        crate::math::euler_to_quaternion(to),
+        t,
+    );
+    crate::math::quaternion_to_euler(q)
+}
+
+fn evaluate_channel_quat(keyframes: &[Keyframe], time: f32) -> Option<[f32; 4]> {
     if keyframes.is_empty() {
         return None;
     }
@@ -615,19 +752,235 @@ fn evaluate_channel_vec4(keyframes: &[Keyframe], time: f32) -> Option<[f32; 4]>
     let next_idx = keyframes.iter().position(|k| k.time >= time);
 
     match next_idx {
-        None => keyframes.last()?.value.as_vec4(),
-        Some(0) => keyframes.first()?.value.as_vec4(),
+        None => rotation_quat(&keyframes.last()?.value),
+        Some(0) => rotation_quat(&keyframes.first()?.value),
         Some(idx) => {
             let a = &keyframes[idx - 1];
             let b = &keyframes[idx];
             if (b.time - a.time).abs() < 0.0001 {
-                return b.value.as_vec4();
+                return rotation_quat(&b.value);
             }
-            let t = (time - a.time) / (b.time - a.time);
-            let va = a.value.as_vec4()?;
-            let vb = b.value.as_vec4()?;
-            // Use slerp for quaternion interpolation
-            Some(crate::keyframe::Interpolation::slerp(va, vb, t))
+            let t = a.interpolation.ease((time - a.time) / (b.time - a.time));
+            let qa = rotation_quat(&a.value)?;
+            let qb = rotation_quat(&b.value)?;
+            Some(crate::keyframe::Interpolation::slerp(qa, qb, t))
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keyframe::{InterpolationMode, KeyframeValue};
+
+    fn float_track(keys: &[(f32, f32)]) -> Track {
+        let mut track = Track::new("Test", TrackType::Property);
+        for &(time, value) in keys {
+            track.add_keyframe(Keyframe::new(time, KeyframeValue::Float(value)));
+        }
+        track
+    }
+
+    fn values(track: &Track) -> Vec<f32> {
+        track.keyframes.iter().filter_map(|k| k.value.as_float()).collect()
+    }
+
+    #[test]
+    fn test_timing() {
+        let track = float_track(&[(0.0, 1.0), (0.5, 2.0), (2.0, 3.0)]);
+        assert_eq!(track.timing(), vec![0.0, 0.5, 2.0]);
+    }
+
+    #[test]
+    fn test_retime_maps_by_index() {
+        let source = float_track(&[(0.0, 0.0), (0.25, 0.0), (1.0, 0.0)]);
+        let mut target = float_track(&[(0.0, 10.0), (1.0, 20.0), (3.0, 30.0)]);
+
+        target.retime_to(&source.timing());
+
+        assert_eq!(target.timing(), vec![0.0, 0.25, 1.0]);
+        assert_eq!(values(&target), vec![10.0, 20.0, 30.0]);
+    }
+
+    #[test]
+    fn test_retime_with_fewer_times() {
+        let mut target = float_track(&[(0.0, 10.0), (1.0, 20.0), (3.0, 30.0)]);
+
+        target.retime_to(&[0.5, 2.0]);
+
+        // Extra keyframe stays in place
+        assert_eq!(target.timing(), vec![0.5, 2.0, 3.0]);
+        assert_eq!(values(&target), vec![10.0, 20.0, 30.0]);
+    }
+
+    #[test]
+    fn test_insert_at_current_value_on_linear_ramp() {
+        let mut track = float_track(&[(0.0, 0.0), (2.0, 10.0)]);
+
+        let id = track.insert_keyframe_at_current_value(1.0).unwrap();
+
+        assert_eq!(track.timing(), vec![0.0, 1.0, 2.0]);
+        assert_eq!(values(&track), vec![0.0, 5.0, 10.0]);
+        assert_eq!(track.keyframe(id).unwrap().interpolation, InterpolationMode::Linear);
+        // Curve shape is unchanged
+        assert_eq!(track.evaluate(0.5).and_then(|v| v.as_float()), Some(2.5));
+    }
+
+    #[test]
+    fn test_paste_keyframes_keeps_spacing_with_new_ids() {
+        let source = float_track(&[(1.0, 2.0), (1.5, 4.0)]);
+        let mut track = float_track(&[(0.0, 0.0), (3.5, 9.0)]);
+
+        let ids = track.paste_keyframes(&source.keyframes, 2.0).unwrap();
+
+        // The key at 3.5 is replaced by the pasted one
+        assert_eq!(track.timing(), vec![0.0, 3.0, 3.5]);
+        assert_eq!(values(&track), vec![0.0, 2.0, 4.0]);
+        assert_eq!(ids.len(), 2);
+        assert!(ids.iter().all(|id| source.keyframe(*id).is_none() && track.keyframe(*id).is_some()));
+    }
+
+    #[test]
+    fn test_paste_keyframes_rejects_mismatched_values() {
+        let source = float_track(&[(0.0, 1.0)]);
+        let mut track = Track::new("Events", TrackType::Event);
+        track.add_keyframe(Keyframe::new(0.0, KeyframeValue::Event("boom".to_string())));
+
+        assert!(track.paste_keyframes(&source.keyframes, 1.0).is_none());
+        assert_eq!(track.keyframe_count(), 1);
+
+        // Empty tracks take any kind
+        let mut empty = Track::new("Empty", TrackType::Property);
+        assert!(empty.paste_keyframes(&source.keyframes, 1.0).is_some());
+        assert_eq!(empty.timing(), vec![1.0]);
+    }
+
+    fn sample_two_keys(mode: InterpolationMode) -> [f32; 3] {
+        let mut track = Track::new("Test", TrackType::Property);
+        track.add_keyframe(Keyframe::new(0.0, KeyframeValue::Float(0.0)).with_interpolation(mode));
+        track.add_keyframe(Keyframe::new(1.0, KeyframeValue::Float(1.0)));
+        [0.25, 0.5, 0.75].map(|time| track.evaluate(time).and_then(|v| v.as_float()).unwrap())
+    }
+
+    #[test]
+    fn test_evaluate_per_interpolation_mode() {
+        let cases = [
+            (InterpolationMode::Constant, [0.0, 0.0, 0.0]),
+            (InterpolationMode::Linear, [0.25, 0.5, 0.75]),
+            (InterpolationMode::EaseIn, [0.015625, 0.125, 0.421875]),
+            (InterpolationMode::EaseOut, [0.578125, 0.875, 0.984375]),
+            (InterpolationMode::EaseInOut, [0.0625, 0.5, 0.9375]),
+            // Default flat tangents give a smoothstep
+            (InterpolationMode::Bezier, [0.15625, 0.5, 0.84375]),
+        ];
+        for (mode, expected) in cases {
+            let samples = sample_two_keys(mode);
+            for (sample, want) in samples.iter().zip(expected) {
+                assert!((sample - want).abs() < 1e-4, "{mode:?}: {samples:?} != {expected:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_channel_evaluation_uses_interpolation_mode() {
+        let keys = [
+            Keyframe::new(0.0, KeyframeValue::Float(0.0)).with_interpolation(InterpolationMode::EaseIn),
+            Keyframe::new(1.0, KeyframeValue::Float(1.0)),
+        ];
+        assert_eq!(evaluate_channel_float(&keys, 0.5), Some(0.125));
+    }
+
+    /// Rotation angle about Y (degrees) of a quaternion that only turns about Y
+    fn yaw_of(q: [f32; 4]) -> f32 {
+        (2.0 * q[1].atan2(q[3])).to_degrees()
+    }
+
+    #[test]
+    fn test_transform_rotation_takes_shortest_path() {
+        let mut track = TransformTrack::new("Spin");
+        track.add_rotation_euler(0.0, [0.0, 170.0, 0.0]);
+        track.add_rotation_euler(1.0, [0.0, -170.0, 0.0]);
+
+        let start = track.evaluate_rotation(0.0).unwrap();
+        assert!((yaw_of(start) - 170.0).abs() < 1e-2);
+
+        // Halfway is the 20 degree arc through 180, not 340 degrees through 0
+        let mid = track.evaluate_rotation(0.5).unwrap();
+        assert!((yaw_of(mid).abs() - 180.0).abs() < 1e-2, "midpoint at {}", yaw_of(mid));
+        let length = mid.iter().map(|c| c * c).sum::<f32>().sqrt();
+        assert!((length - 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_euler_rotation_track_slerps() {
+        let mut track = float_track(&[]);
+        track.binding = Some(EntityBinding {
+            entity_id: crate::binding::EntityId(Uuid::new_v4()),
+            component: Some("Transform".to_string()),
+            property_path: Some("rotation".to_string()),
+        });
+        track.add_keyframe(Keyframe::new(0.0, KeyframeValue::Vec3([0.0, 170.0, 0.0])));
+        track.add_keyframe(Keyframe::new(1.0, KeyframeValue::Vec3([0.0, -170.0, 0.0])));
+        assert!(track.is_rotation());
+
+        let mid = track.evaluate(0.5).and_then(|v| v.as_vec3()).unwrap();
+        let q = crate::math::euler_to_quaternion(mid);
+        assert!((yaw_of(q).abs() - 180.0).abs() < 1e-2, "midpoint at {mid:?}");
+    }
+
+    #[test]
+    fn test_evaluate_bezier_follows_tangents() {
+        let mut track = Track::new("Test", TrackType::Property);
+        track.add_keyframe(Keyframe::new(0.0, KeyframeValue::Float(0.0)).with_interpolation(InterpolationMode::Bezier));
+        track.add_keyframe(Keyframe::new(1.0, KeyframeValue::Float(1.0)));
+        let value_at = |track: &Track, time| track.evaluate(time).and_then(|v| v.as_float()).unwrap();
+
+        // Default flat handles ease in and out symmetrically
+        assert!((value_at(&track, 0.5) - 0.5).abs() < 1e-3);
+        assert!(value_at(&track, 0.25) < 0.25);
+        assert!(value_at(&track, 0.75) > 0.75);
+
+        // A steep out-tangent overshoots early on
+        let first = track.keyframes[0].id;
+        track.keyframe_mut(first).unwrap().out_tangent = Some([0.1, 2.0]);
+        assert!(value_at(&track, 0.25) > 0.25);
+        // Handles are kept inside the segment
+        track.keyframe_mut(first).unwrap().out_tangent = Some([5.0, 0.0]);
+        assert!((0.0..=1.0).contains(&value_at(&track, 0.9)));
+    }
+
+    #[test]
+    fn test_insert_at_current_value_between_stepped_keys() {
+        let mut track = Track::new("Test", TrackType::Property);
+        track.add_keyframe(Keyframe::new(0.0, KeyframeValue::Float(3.0)).with_interpolation(InterpolationMode::Constant));
+        track.add_keyframe(Keyframe::new(2.0, KeyframeValue::Float(8.0)).with_interpolation(InterpolationMode::Constant));
+
+        let id = track.insert_keyframe_at_current_value(1.5).unwrap();
+
+        assert_eq!(track.keyframe(id).unwrap().value.as_float(), Some(3.0));
+        assert_eq!(track.keyframe(id).unwrap().interpolation, InterpolationMode::Constant);
+    }
+
+    #[test]
+    fn test_insert_at_current_value_updates_existing_key() {
+        let mut track = float_track(&[(0.0, 0.0), (1.0, 4.0), (2.0, 10.0)]);
+        let existing = track.keyframes[1].id;
+
+        assert_eq!(track.insert_keyframe_at_current_value(1.0), Some(existing));
+        assert_eq!(track.keyframe_count(), 3);
+        assert_eq!(values(&track), vec![0.0, 4.0, 10.0]);
+
+        assert_eq!(Track::new("Empty", TrackType::Property).insert_keyframe_at_current_value(1.0), None);
+    }
+
+    #[test]
+    fn test_retime_with_more_times() {
+        let mut target = float_track(&[(0.0, 10.0), (1.0, 20.0)]);
+
+        target.retime_to(&[0.2, 0.4, 0.6, 0.8]);
+
+        assert_eq!(target.keyframe_count(), 2);
+        assert_eq!(target.timing(), vec![0.2, 0.4]);
+        assert_eq!(values(&target), vec![10.0, 20.0]);
+    }
+}
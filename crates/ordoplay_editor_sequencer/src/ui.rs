@@ -5,13 +5,16 @@
 //! - Timeline header with time ruler
 //! - Track list panel
 //! - Keyframe rendering and editing
+//! - Audio clips with waveforms
 //! - Curve editor
 //! - Playback controls
 //! - Zoom/pan navigation
 
+use crate::binding::{EntityId, TransformChannel};
 use crate::keyframe::{KeyframeId, KeyframeValue, InterpolationMode};
 use crate::sequence::{Sequence, PlaybackController};
-use crate::track::{Track, TrackId, TrackType};
+use crate::track::{AudioClip, Track, TrackId, TrackType};
+use crate::waveform::{Waveform, WaveformCache, WaveformState};
 use egui::{Color32, Pos2, Rect, Sense, Stroke, Vec2};
 use std::collections::HashSet;
 
@@ -22,6 +25,11 @@ const KEYFRAME_SIZE: f32 = 10.0;
 const PLAYHEAD_WIDTH: f32 = 2.0;
 const MIN_ZOOM: f32 = 20.0;
 const MAX_ZOOM: f32 = 500.0;
+const CURVE_KEY_RADIUS: f32 = 4.0;
+const CURVE_HANDLE_SIZE: f32 = 6.0;
+const CURVE_HIT_RADIUS: f32 = 8.0;
+const CURVE_SAMPLE_SPACING: f32 = 2.0;
+const CURVE_GRID_SPACING: f32 = 30.0;
 
 /// View mode for the sequencer
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -44,6 +52,44 @@ pub struct Selection {
 }
 
 
+/// Keyframes copied in the sequencer, grouped by source track
+#[derive(Debug, Clone, Default)]
+pub struct KeyframeClipboard {
+    /// Copied keyframes per source track, in track order
+    pub tracks: Vec<(TrackId, Vec<crate::keyframe::Keyframe>)>,
+}
+
+impl KeyframeClipboard {
+    /// Time of the earliest copied keyframe
+    pub fn start_time(&self) -> Option<f32> {
+        self.keyframes().map(|k| k.time).reduce(f32::min)
+    }
+
+    /// Time between the earliest and latest copied keyframes
+    pub fn span(&self) -> f32 {
+        let end = self.keyframes().map(|k| k.time).reduce(f32::max);
+        end.zip(self.start_time()).map_or(0.0, |(end, start)| end - start)
+    }
+
+    /// Whether nothing was copied
+    pub fn is_empty(&self) -> bool {
+        self.tracks.is_empty()
+    }
+
+    fn keyframes(&self) -> impl Iterator<Item = &crate::keyframe::Keyframe> {
+        self.tracks.iter().flat_map(|(_, keyframes)| keyframes)
+    }
+
+    /// Tracks the clipboard pastes onto: a single copied track goes to
+    /// `target` when given, otherwise keys return to their source tracks
+    fn destinations(&self, target: Option<TrackId>) -> Vec<(TrackId, &[crate::keyframe::Keyframe])> {
+        match (self.tracks.as_slice(), target) {
+            ([(_, keyframes)], Some(target)) => vec![(target, keyframes.as_slice())],
+            (tracks, _) => tracks.iter().map(|(id, keyframes)| (*id, keyframes.as_slice())).collect(),
+        }
+    }
+}
+
 /// Drag operation state
 #[derive(Debug, Clone)]
 pub enum DragOperation {
@@ -68,8 +114,45 @@ pub enum DragOperation {
         /// Scroll offset when pan started
         start_scroll: f32,
     },
+    /// Dragging a keyframe or tangent handle in the curve editor
+    CurveHandle {
+        /// Track of the dragged keyframe
+        track_id: TrackId,
+        /// Dragged keyframe
+        keyframe_id: KeyframeId,
+        /// Part of the keyframe being dragged
+        handle: CurveHandle,
+    },
+}
+
+/// Draggable part of a keyframe in the curve editor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CurveHandle {
+    /// The keyframe itself (time and value)
+    Key,
+    /// The in-tangent handle
+    InTangent,
+    /// The out-tangent handle
+    OutTangent,
 }
 
+/// Screen positions of a keyframe and its visible tangent handles
+struct CurvePoints {
+    key: Pos2,
+    in_handle: Option<Pos2>,
+    out_handle: Option<Pos2>,
+}
+
+/// Interpolation presets offered in keyframe context menus
+const INTERPOLATION_PRESETS: [InterpolationMode; 6] = [
+    InterpolationMode::Linear,
+    InterpolationMode::Constant,
+    InterpolationMode::EaseIn,
+    InterpolationMode::EaseOut,
+    InterpolationMode::EaseInOut,
+    InterpolationMode::Bezier,
+];
+
 /// Sequencer editor state
 pub struct SequencerState {
     /// Playback controller
@@ -92,12 +175,28 @@ pub struct SequencerState {
     pub snap_interval: f32,
     /// Show waveforms for audio tracks
     pub show_waveforms: bool,
+    /// Decoded waveforms of audio clips
+    pub waveforms: WaveformCache,
     /// Auto-scroll to follow playhead
     pub auto_scroll: bool,
-    /// Curve editor Y scale
+    /// Key committed transform edits at the playhead
+    pub recording: bool,
+    /// Curve editor Y scale (pixels per value unit)
     pub curve_scale: f32,
-    /// Curve editor Y offset
+    /// Curve editor value at the vertical center of the view
     pub curve_offset: f32,
+    /// Track shown in the curve editor
+    pub curve_track: Option<TrackId>,
+    /// Fit the curve's values in view on the next frame
+    frame_curve: bool,
+    /// Keyframe the curve editor's context menu was opened on
+    curve_context: Option<(TrackId, KeyframeId)>,
+    /// Keyframe times copied with "Copy Timing"
+    pub copied_timing: Option<Vec<f32>>,
+    /// Keyframes copied with "Copy Keys"
+    pub keyframe_clipboard: KeyframeClipboard,
+    /// Track the context menu was opened on
+    context_track: Option<TrackId>,
 }
 
 impl SequencerState {
@@ -114,9 +213,17 @@ impl SequencerState {
             snap_enabled: true,
             snap_interval: 0.1, // 100ms
             show_waveforms: true,
+            waveforms: WaveformCache::new(),
             auto_scroll: true,
+            recording: false,
             curve_scale: 100.0,
             curve_offset: 0.0,
+            curve_track: None,
+            frame_curve: true,
+            curve_context: None,
+            copied_timing: None,
+            keyframe_clipboard: KeyframeClipboard::default(),
+            context_track: None,
         }
     }
 
@@ -130,6 +237,16 @@ impl SequencerState {
         (x - TRACK_HEADER_WIDTH) / self.zoom + self.scroll_offset
     }
 
+    /// Find the track under a y position in the tracks area
+    fn track_at_y(&self, y: f32, tracks_top: f32, sequence: &Sequence) -> Option<TrackId> {
+        let offset = y - tracks_top + self.vertical_scroll;
+        if offset < 0.0 {
+            return None;
+        }
+        let index = (offset / TRACK_HEIGHT) as usize;
+        sequence.tracks().nth(index).map(|t| t.id)
+    }
+
     /// Snap time to grid if enabled
     fn snap_time(&self, time: f32) -> f32 {
         if self.snap_enabled {
@@ -139,6 +256,142 @@ impl SequencerState {
         }
     }
 
+    /// Snap a time to the nearest frame if snapping is enabled
+    fn snap_to_frame(&self, time: f32, frame_rate: f32) -> f32 {
+        if self.snap_enabled && frame_rate > 0.0 {
+            (time * frame_rate).round() / frame_rate
+        } else {
+            time
+        }
+    }
+
+    /// Convert a curve value to y position in the curve editor
+    fn value_to_y(&self, value: f32, rect: Rect) -> f32 {
+        rect.center().y - (value - self.curve_offset) * self.curve_scale
+    }
+
+    /// Convert y position in the curve editor to a curve value
+    fn y_to_value(&self, y: f32, rect: Rect) -> f32 {
+        (rect.center().y - y) / self.curve_scale + self.curve_offset
+    }
+
+    /// Track shown in the curve editor: the chosen one if it has float
+    /// keys, otherwise the first track that does
+    fn curve_track_id(&self, sequence: &Sequence) -> Option<TrackId> {
+        let has_floats = |track: &&Track| track.keyframes.iter().any(|k| k.value.as_float().is_some());
+        self.curve_track
+            .and_then(|id| sequence.track(id))
+            .filter(has_floats)
+            .or_else(|| sequence.tracks().find(has_floats))
+            .map(|t| t.id)
+    }
+
+    /// Selected keyframes grouped by track, in track and time order
+    fn selected_keyframes(&self, sequence: &Sequence) -> KeyframeClipboard {
+        let tracks = sequence
+            .tracks()
+            .map(|track| {
+                let keyframes: Vec<crate::keyframe::Keyframe> = track
+                    .keyframes
+                    .iter()
+                    .filter(|k| self.selection.keyframes.contains(&(track.id, k.id)))
+                    .cloned()
+                    .collect();
+                (track.id, keyframes)
+            })
+            .filter(|(_, keyframes)| !keyframes.is_empty())
+            .collect();
+        KeyframeClipboard { tracks }
+    }
+
+    /// Copy the selected keyframes to the clipboard. Returns how many were
+    /// copied; an empty selection leaves the clipboard as it was.
+    pub fn copy_keyframes(&mut self, sequence: &Sequence) -> usize {
+        let copied = self.selected_keyframes(sequence);
+        let count = copied.keyframes().count();
+        if count > 0 {
+            self.keyframe_clipboard = copied;
+        }
+        count
+    }
+
+    /// Paste `clipboard` with its earliest keyframe at `time`, keeping the
+    /// relative spacing. Locked tracks and tracks whose values don't match
+    /// the copied kind are skipped. The pasted keyframes become the
+    /// selection; returns how many were pasted.
+    fn paste_clipboard(
+        &mut self,
+        clipboard: &KeyframeClipboard,
+        sequence: &mut Sequence,
+        time: f32,
+        target: Option<TrackId>,
+    ) -> usize {
+        let Some(start) = clipboard.start_time() else {
+            return 0;
+        };
+
+        let mut pasted = Vec::new();
+        for (track_id, keyframes) in clipboard.destinations(target) {
+            let Some(track) = sequence.track_mut(track_id).filter(|t| !t.locked) else {
+                continue;
+            };
+            if let Some(ids) = track.paste_keyframes(keyframes, time - start) {
+                pasted.extend(ids.into_iter().map(|id| (track_id, id)));
+            }
+        }
+
+        if !pasted.is_empty() {
+            self.selection.keyframes = pasted.iter().copied().collect();
+        }
+        pasted.len()
+    }
+
+    /// Paste the clipboard at the playhead, onto `target` when a single
+    /// track was copied. Returns how many keyframes were pasted.
+    pub fn paste_keyframes(&mut self, sequence: &mut Sequence, target: Option<TrackId>) -> usize {
+        let clipboard = std::mem::take(&mut self.keyframe_clipboard);
+        let count = self.paste_clipboard(&clipboard, sequence, self.playback.time, target);
+        self.keyframe_clipboard = clipboard;
+        count
+    }
+
+    /// Duplicate the selected keyframes one frame after the end of the
+    /// selection, without touching the clipboard. Returns how many
+    /// keyframes were created.
+    pub fn duplicate_keyframes(&mut self, sequence: &mut Sequence) -> usize {
+        let selected = self.selected_keyframes(sequence);
+        let Some(start) = selected.start_time() else {
+            return 0;
+        };
+        let time = start + selected.span() + 1.0 / sequence.frame_rate.max(1.0);
+        self.paste_clipboard(&selected, sequence, time, None)
+    }
+
+    /// Set the interpolation of every selected keyframe on unlocked tracks
+    pub fn set_selected_interpolation(&self, sequence: &mut Sequence, mode: InterpolationMode) {
+        for (track_id, keyframe_id) in &self.selection.keyframes {
+            let Some(track) = sequence.track_mut(*track_id).filter(|t| !t.locked) else {
+                continue;
+            };
+            if let Some(keyframe) = track.keyframe_mut(*keyframe_id) {
+                keyframe.interpolation = mode;
+            }
+        }
+    }
+
+    /// Whether the clipboard can be pasted onto `track_id`
+    fn can_paste_onto(&self, sequence: &Sequence, track_id: TrackId) -> bool {
+        let Some(track) = sequence.track(track_id).filter(|t| !t.locked) else {
+            return false;
+        };
+        self.keyframe_clipboard
+            .destinations(Some(track_id))
+            .iter()
+            .any(|(id, keyframes)| {
+                *id == track_id && keyframes.iter().all(|k| track.accepts_value(&k.value))
+            })
+    }
+
     /// Render the full sequencer UI
     pub fn ui(&mut self, ui: &mut egui::Ui, sequence: &mut Sequence) {
         let available_rect = ui.available_rect_before_wrap();
@@ -167,8 +420,21 @@ impl SequencerState {
         // Render tracks area
         self.render_tracks_area(ui, content_rect, sequence);
 
+        // The curve editor replaces the track rows' content area and
+        // handles its own input
+        let input_rect = if self.view_mode == ViewMode::CurveEditor {
+            let curve_rect = Rect::from_min_max(
+                Pos2::new(TRACK_HEADER_WIDTH, content_rect.min.y),
+                content_rect.max,
+            );
+            self.render_curve_editor(ui, curve_rect, sequence);
+            Rect::from_min_max(remaining_rect.min, Pos2::new(TRACK_HEADER_WIDTH, remaining_rect.max.y))
+        } else {
+            remaining_rect
+        };
+
         // Handle global input
-        self.handle_input(ui, remaining_rect, sequence);
+        self.handle_input(ui, input_rect, sequence);
 
         // Auto-scroll to follow playhead
         if self.auto_scroll && self.playback.is_playing() {
@@ -201,6 +467,45 @@ impl SequencerState {
                 self.playback.seek(sequence.duration);
             }
 
+            let record_icon = if self.recording {
+                egui::RichText::new("⏺").color(Color32::from_rgb(230, 60, 60))
+            } else {
+                egui::RichText::new("⏺")
+            };
+            if ui
+                .selectable_label(self.recording, record_icon)
+                .on_hover_text("Record: key transform edits at the playhead")
+                .clicked()
+            {
+                self.recording = !self.recording;
+            }
+
+            // Loop region
+            let has_region = self.playback.loop_region().is_some();
+            if ui
+                .add_enabled(has_region, egui::SelectableLabel::new(self.playback.loop_enabled, "🔁"))
+                .on_hover_text("Loop between the in and out points")
+                .on_disabled_hover_text("Set loop in and out points first")
+                .clicked()
+            {
+                self.playback.toggle_loop();
+            }
+
+            let time = self.playback.time;
+            let region = self.playback.loop_region();
+            if ui.button("In").on_hover_text("Set the loop in point at the playhead").clicked() {
+                let end = region.map_or(sequence.duration, |r| r.end);
+                // Rejected regions (past the out point, or under a frame) are ignored
+                let _ = self.playback.set_loop_region(time, end, sequence);
+            }
+            if ui.button("Out").on_hover_text("Set the loop out point at the playhead").clicked() {
+                let start = region.map_or(0.0, |r| r.start);
+                let _ = self.playback.set_loop_region(start, time, sequence);
+            }
+            if has_region && ui.small_button("✕").on_hover_text("Clear loop region").clicked() {
+                self.playback.clear_loop_region();
+            }
+
             ui.separator();
 
             // Time display
@@ -224,6 +529,11 @@ impl SequencerState {
             // View mode toggle
             ui.selectable_value(&mut self.view_mode, ViewMode::Dopesheet, "Dopesheet");
             ui.selectable_value(&mut self.view_mode, ViewMode::CurveEditor, "Curves");
+            if self.view_mode == ViewMode::CurveEditor
+                && ui.button("Frame").on_hover_text("Fit the curve's values in view").clicked()
+            {
+                self.frame_curve = true;
+            }
 
             ui.separator();
 
@@ -236,6 +546,9 @@ impl SequencerState {
                     .suffix("s"));
             }
 
+            ui.checkbox(&mut self.show_waveforms, "Waveforms")
+                .on_hover_text("Draw audio waveforms inside clips");
+
             ui.separator();
 
             // Zoom controls
@@ -394,7 +707,9 @@ impl SequencerState {
 
                 // Get track data
                 if let Some(track) = sequence.track(*track_id) {
-                    let is_selected = self.selection.tracks.contains(track_id);
+                    let is_curve_track = self.view_mode == ViewMode::CurveEditor
+                        && self.curve_track == Some(*track_id);
+                    let is_selected = self.selection.tracks.contains(track_id) || is_curve_track;
                     self.render_track(ui, painter.clone(), track_rect, track, is_selected, idx);
                 }
             }
@@ -402,6 +717,11 @@ impl SequencerState {
             y += TRACK_HEIGHT;
         }
 
+        // Keep repainting until background waveform decodes finish
+        if self.waveforms.is_loading() {
+            ui.ctx().request_repaint_after(std::time::Duration::from_millis(100));
+        }
+
         // Draw playhead line
         let playhead_x = self.time_to_x(self.playback.time);
         if playhead_x >= TRACK_HEADER_WIDTH && playhead_x <= rect.max.x {
@@ -411,17 +731,18 @@ impl SequencerState {
             );
         }
 
-        // Draw loop region if set
-        if let (Some(start), Some(end)) = (self.playback.loop_start, self.playback.loop_end) {
-            let start_x = self.time_to_x(start);
-            let end_x = self.time_to_x(end);
+        // Draw loop region if set, fainter while looping is off
+        if let Some(region) = self.playback.loop_region().and_then(|region| region.within(sequence)) {
+            let start_x = self.time_to_x(region.start);
+            let end_x = self.time_to_x(region.end);
 
             let loop_rect = Rect::from_min_max(
                 Pos2::new(start_x.max(TRACK_HEADER_WIDTH), rect.min.y),
                 Pos2::new(end_x.min(rect.max.x), rect.max.y),
             );
 
-            painter.rect_filled(loop_rect, 0.0, Color32::from_rgba_unmultiplied(100, 150, 255, 30));
+            let alpha = if self.playback.loop_enabled { 30 } else { 12 };
+            painter.rect_filled(loop_rect, 0.0, Color32::from_rgba_unmultiplied(100, 150, 255, alpha));
         }
     }
 
@@ -519,11 +840,11 @@ impl SequencerState {
 
         match self.view_mode {
             ViewMode::Dopesheet => {
+                self.render_audio_clips(&painter, content_rect, track);
                 self.render_keyframes_dopesheet(&painter, content_rect, track);
             }
-            ViewMode::CurveEditor => {
-                self.render_keyframes_curves(&painter, content_rect, track);
-            }
+            // The curve editor draws over the whole content area instead
+            ViewMode::CurveEditor => {}
         }
 
         // Separator line
@@ -533,6 +854,79 @@ impl SequencerState {
         );
     }
 
+    /// Render audio clip regions, with their waveforms when enabled
+    fn render_audio_clips(&mut self, painter: &egui::Painter, rect: Rect, track: &Track) {
+        let color = track.effective_color();
+        let fill = Color32::from_rgba_unmultiplied(color[0], color[1], color[2], 50);
+        let outline = Color32::from_rgb(color[0], color[1], color[2]);
+
+        for clip in &track.clips {
+            let clip_rect = Rect::from_min_max(
+                Pos2::new(self.time_to_x(clip.start_time), rect.min.y + 2.0),
+                Pos2::new(self.time_to_x(clip.end_time), rect.max.y - 2.0),
+            );
+            let visible = clip_rect.intersect(rect);
+            if !visible.is_positive() {
+                continue;
+            }
+
+            painter.rect_filled(visible, 3.0, fill);
+
+            if self.show_waveforms {
+                match self.waveforms.get(&clip.asset_path) {
+                    WaveformState::Ready(waveform) => {
+                        self.render_waveform(painter, visible, clip, &waveform, outline);
+                    }
+                    WaveformState::Loading => {
+                        painter.text(
+                            Pos2::new(visible.max.x - 4.0, visible.center().y),
+                            egui::Align2::RIGHT_CENTER,
+                            "Loading…",
+                            egui::FontId::proportional(9.0),
+                            Color32::from_gray(140),
+                        );
+                    }
+                    WaveformState::Failed(_) => {}
+                }
+            }
+
+            painter.rect_stroke(visible, 3.0, Stroke::new(1.0, outline));
+
+            let name = clip.asset_path.rsplit(['/', '\\']).next().unwrap_or_default();
+            painter.text(
+                Pos2::new(visible.min.x + 4.0, visible.min.y + 1.0),
+                egui::Align2::LEFT_TOP,
+                name,
+                egui::FontId::proportional(9.0),
+                Color32::from_gray(220),
+            );
+        }
+    }
+
+    /// Draw one min/max column per pixel of a clip's waveform
+    fn render_waveform(&self, painter: &egui::Painter, rect: Rect, clip: &AudioClip, waveform: &Waveform, color: Color32) {
+        let center_y = rect.center().y;
+        let half_height = (rect.height() * 0.5 - 1.0).max(0.0) * clip.volume.clamp(0.0, 1.0);
+        let stroke = Stroke::new(1.0, color.gamma_multiply(0.8));
+        let pixel_time = 1.0 / self.zoom;
+
+        let mut x = rect.min.x.floor();
+        while x < rect.max.x {
+            // Time within the audio file at this column
+            let start = self.x_to_time(x) - clip.start_time + clip.clip_start;
+            if let Some((min, max)) = waveform.range(start, start + pixel_time) {
+                painter.line_segment(
+                    [
+                        Pos2::new(x + 0.5, center_y - max.clamp(-1.0, 1.0) * half_height),
+                        Pos2::new(x + 0.5, center_y - min.clamp(-1.0, 1.0) * half_height),
+                    ],
+                    stroke,
+                );
+            }
+            x += 1.0;
+        }
+    }
+
     /// Render keyframes in dopesheet mode
     fn render_keyframes_dopesheet(&self, painter: &egui::Painter, rect: Rect, track: &Track) {
         let center_y = rect.center().y;
@@ -586,58 +980,306 @@ impl SequencerState {
                         Stroke::new(1.0, Color32::from_rgb(100, 200, 255)),
                     );
                 }
+                InterpolationMode::EaseIn | InterpolationMode::EaseOut | InterpolationMode::EaseInOut => {
+                    painter.circle_stroke(
+                        Pos2::new(x, center_y),
+                        2.0,
+                        Stroke::new(1.0, Color32::from_rgb(150, 255, 150)),
+                    );
+                }
                 _ => {}
             }
         }
     }
 
-    /// Render keyframes in curve editor mode
-    fn render_keyframes_curves(&self, painter: &egui::Painter, rect: Rect, track: &Track) {
-        // Only render float-valued keyframes in curve mode
-        let float_keyframes: Vec<_> = track.keyframes.iter()
-            .filter_map(|k| k.value.as_float().map(|v| (k, v)))
-            .collect();
+    /// Render the curve editor for the curve track
+    fn render_curve_editor(&mut self, ui: &mut egui::Ui, rect: Rect, sequence: &mut Sequence) {
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 0.0, Color32::from_gray(26));
 
-        if float_keyframes.is_empty() {
+        let response = ui.interact(rect, ui.id().with("curve_editor"), Sense::click_and_drag());
+
+        let Some(track_id) = self.curve_track_id(sequence) else {
+            painter.text(
+                rect.center(),
+                egui::Align2::CENTER_CENTER,
+                "No track with float keyframes",
+                egui::FontId::proportional(12.0),
+                Color32::from_gray(120),
+            );
             return;
+        };
+        if self.curve_track != Some(track_id) {
+            self.curve_track = Some(track_id);
+            self.frame_curve = true;
+        }
+        if std::mem::take(&mut self.frame_curve) {
+            if let Some(track) = sequence.track(track_id) {
+                self.frame_curve_values(track, rect);
+            }
         }
 
-        let center_y = rect.center().y;
+        self.handle_curve_input(&response, rect, track_id, sequence);
 
-        // Draw curve
-        let mut points = Vec::new();
-        for (keyframe, value) in &float_keyframes {
-            let x = self.time_to_x(keyframe.time);
-            let y = center_y - (value - self.curve_offset) * self.curve_scale / 100.0;
-            points.push(Pos2::new(x, y.clamp(rect.min.y, rect.max.y)));
+        self.render_value_grid(&painter, rect);
+        if let Some(track) = sequence.track(track_id) {
+            self.render_curve(&painter, rect, track);
         }
 
-        if points.len() >= 2 {
-            let color = track.effective_color();
-            painter.add(egui::Shape::line(
-                points.clone(),
-                Stroke::new(2.0, Color32::from_rgb(color[0], color[1], color[2])),
-            ));
+        // Playhead
+        let playhead_x = self.time_to_x(self.playback.time);
+        if playhead_x >= rect.min.x && playhead_x <= rect.max.x {
+            painter.line_segment(
+                [Pos2::new(playhead_x, rect.min.y), Pos2::new(playhead_x, rect.max.y)],
+                Stroke::new(PLAYHEAD_WIDTH, Color32::from_rgb(255, 100, 100)),
+            );
         }
+    }
 
-        // Draw keyframe points
-        for (idx, (keyframe, _value)) in float_keyframes.iter().enumerate() {
-            if idx >= points.len() {
+    /// Fit the track's keyframe values, tangents included, in the view
+    fn frame_curve_values(&mut self, track: &Track, rect: Rect) {
+        let mut min = f32::INFINITY;
+        let mut max = f32::NEG_INFINITY;
+        for (index, keyframe) in track.keyframes.iter().enumerate() {
+            let Some(value) = keyframe.value.as_float() else {
                 continue;
+            };
+            min = min.min(value);
+            max = max.max(value);
+            if let Some(points) = self.curve_points(track, index, rect) {
+                for handle in points.in_handle.into_iter().chain(points.out_handle) {
+                    let handle_value = self.y_to_value(handle.y, rect);
+                    min = min.min(handle_value);
+                    max = max.max(handle_value);
+                }
+            }
+        }
+        if min > max {
+            return;
+        }
+
+        let range = (max - min).max(0.1);
+        self.curve_offset = (min + max) * 0.5;
+        self.curve_scale = rect.height() * 0.8 / range;
+    }
+
+    /// Draw horizontal value lines with labels
+    fn render_value_grid(&self, painter: &egui::Painter, rect: Rect) {
+        // Power-of-ten step that keeps lines at least CURVE_GRID_SPACING apart
+        let step = 10f32.powf((CURVE_GRID_SPACING / self.curve_scale).log10().ceil());
+        let bottom = self.y_to_value(rect.max.y, rect);
+        let top = self.y_to_value(rect.min.y, rect);
+
+        let mut value = (bottom / step).ceil() * step;
+        while value <= top {
+            let y = self.value_to_y(value, rect);
+            let color = if value.abs() < step * 0.5 {
+                Color32::from_gray(70)
+            } else {
+                Color32::from_gray(40)
+            };
+            painter.line_segment([Pos2::new(rect.min.x, y), Pos2::new(rect.max.x, y)], Stroke::new(1.0, color));
+            painter.text(
+                Pos2::new(rect.min.x + 4.0, y - 2.0),
+                egui::Align2::LEFT_BOTTOM,
+                format!("{:.*}", (-step.log10()).round().max(0.0) as usize, value),
+                egui::FontId::monospace(10.0),
+                Color32::from_gray(110),
+            );
+            value += step;
+        }
+    }
+
+    /// Screen positions of a float keyframe and its tangent handles. A
+    /// handle is shown when the segment on its side is a bezier.
+    fn curve_points(&self, track: &Track, index: usize, rect: Rect) -> Option<CurvePoints> {
+        let keyframe = track.keyframes.get(index)?;
+        let value = keyframe.value.as_float()?;
+        let to_screen = |[dt, dv]: [f32; 2]| {
+            Pos2::new(self.time_to_x(keyframe.time + dt), self.value_to_y(value + dv, rect))
+        };
+
+        let in_handle = index
+            .checked_sub(1)
+            .and_then(|i| track.keyframes.get(i))
+            .filter(|prev| prev.interpolation == InterpolationMode::Bezier)
+            .map(|prev| to_screen(keyframe.in_handle(keyframe.time - prev.time)));
+        let out_handle = track
+            .keyframes
+            .get(index + 1)
+            .filter(|_| keyframe.interpolation == InterpolationMode::Bezier)
+            .map(|next| to_screen(keyframe.out_handle(next.time - keyframe.time)));
+
+        Some(CurvePoints {
+            key: to_screen([0.0, 0.0]),
+            in_handle,
+            out_handle,
+        })
+    }
+
+    /// Keyframe or tangent handle under `pos`; handles take priority
+    fn curve_hit(&self, track: &Track, rect: Rect, pos: Pos2) -> Option<(KeyframeId, CurveHandle)> {
+        let points: Vec<(KeyframeId, CurvePoints)> = (0..track.keyframes.len())
+            .filter_map(|i| self.curve_points(track, i, rect).map(|p| (track.keyframes[i].id, p)))
+            .collect();
+        let near = |p: Pos2| p.distance(pos) <= CURVE_HIT_RADIUS;
+
+        points
+            .iter()
+            .find_map(|(id, p)| {
+                if p.in_handle.is_some_and(near) {
+                    Some((*id, CurveHandle::InTangent))
+                } else if p.out_handle.is_some_and(near) {
+                    Some((*id, CurveHandle::OutTangent))
+                } else {
+                    None
+                }
+            })
+            .or_else(|| points.iter().find(|(_, p)| near(p.key)).map(|(id, _)| (*id, CurveHandle::Key)))
+    }
+
+    /// Draw the sampled curve, keyframes and tangent handles
+    fn render_curve(&self, painter: &egui::Painter, rect: Rect, track: &Track) {
+        let color = track.effective_color();
+        let curve_color = Color32::from_rgb(color[0], color[1], color[2]);
+
+        // Sample the evaluated curve so step and bezier segments show as played
+        let mut points = Vec::new();
+        let mut x = rect.min.x;
+        while x <= rect.max.x {
+            if let Some(value) = track.evaluate(self.x_to_time(x)).and_then(|v| v.as_float()) {
+                points.push(Pos2::new(x, self.value_to_y(value, rect)));
             }
+            x += CURVE_SAMPLE_SPACING;
+        }
+        if points.len() >= 2 {
+            painter.add(egui::Shape::line(points, Stroke::new(2.0, curve_color)));
+        }
 
-            let pos = points[idx];
+        let handle_stroke = Stroke::new(1.0, Color32::from_gray(150));
+        for (index, keyframe) in track.keyframes.iter().enumerate() {
+            let Some(points) = self.curve_points(track, index, rect) else {
+                continue;
+            };
             let is_selected = self.selection.keyframes.contains(&(track.id, keyframe.id));
 
-            let color = if is_selected {
+            for handle in points.in_handle.into_iter().chain(points.out_handle) {
+                painter.line_segment([points.key, handle], handle_stroke);
+                painter.rect_filled(
+                    Rect::from_center_size(handle, Vec2::splat(CURVE_HANDLE_SIZE)),
+                    0.0,
+                    Color32::from_rgb(100, 200, 255),
+                );
+            }
+
+            let fill = if is_selected {
                 Color32::from_rgb(255, 200, 100)
             } else {
                 Color32::WHITE
             };
+            painter.circle_filled(points.key, CURVE_KEY_RADIUS, fill);
+            painter.circle_stroke(points.key, CURVE_KEY_RADIUS, Stroke::new(1.0, Color32::from_gray(80)));
+        }
+    }
+
+    /// Handle selection, dragging, panning and the context menu in the
+    /// curve editor
+    fn handle_curve_input(
+        &mut self,
+        response: &egui::Response,
+        rect: Rect,
+        track_id: TrackId,
+        sequence: &mut Sequence,
+    ) {
+        let frame_rate = sequence.frame_rate;
+        let Some(track) = sequence.track_mut(track_id) else {
+            return;
+        };
+        let pointer = response.interact_pointer_pos();
+
+        // Pick on press so a click, drag or right-click all act on the key
+        let primary_pick = response.clicked() || response.drag_started_by(egui::PointerButton::Primary);
+        if primary_pick || response.secondary_clicked() {
+            let hit = pointer.and_then(|pos| self.curve_hit(track, rect, pos));
+            match hit {
+                Some((keyframe_id, _)) => {
+                    if !self.selection.keyframes.contains(&(track_id, keyframe_id)) {
+                        self.selection.keyframes.clear();
+                        self.selection.keyframes.insert((track_id, keyframe_id));
+                    }
+                }
+                None if primary_pick => self.selection.keyframes.clear(),
+                None => {}
+            }
+
+            if response.secondary_clicked() {
+                self.curve_context = hit.map(|(keyframe_id, _)| (track_id, keyframe_id));
+            }
+            if response.drag_started_by(egui::PointerButton::Primary) && !track.locked {
+                if let Some((keyframe_id, handle)) = hit {
+                    self.drag_op = DragOperation::CurveHandle { track_id, keyframe_id, handle };
+                }
+            }
+        }
 
-            painter.circle_filled(pos, 4.0, color);
-            painter.circle_stroke(pos, 4.0, Stroke::new(1.0, Color32::from_gray(80)));
+        if response.dragged_by(egui::PointerButton::Primary) {
+            if let (DragOperation::CurveHandle { keyframe_id, handle, .. }, Some(pos)) = (&self.drag_op, pointer) {
+                let time = self.x_to_time(pos.x).max(0.0);
+                let value = self.y_to_value(pos.y, rect);
+                match handle {
+                    CurveHandle::Key => {
+                        track.move_keyframe(*keyframe_id, self.snap_to_frame(time, frame_rate));
+                        if let Some(keyframe) = track.keyframe_mut(*keyframe_id) {
+                            keyframe.value = KeyframeValue::Float(value);
+                        }
+                    }
+                    CurveHandle::InTangent | CurveHandle::OutTangent => {
+                        if let Some(keyframe) = track.keyframe_mut(*keyframe_id) {
+                            let key_value = keyframe.value.as_float().unwrap_or(value);
+                            let (dt, dv) = (time - keyframe.time, value - key_value);
+                            if *handle == CurveHandle::InTangent {
+                                keyframe.in_tangent = Some([dt.min(0.0), dv]);
+                            } else {
+                                keyframe.out_tangent = Some([dt.max(0.0), dv]);
+                            }
+                        }
+                    }
+                }
+            }
         }
+
+        if response.drag_stopped() && matches!(self.drag_op, DragOperation::CurveHandle { .. }) {
+            self.drag_op = DragOperation::None;
+        }
+
+        // Middle mouse pans both time and value
+        if response.dragged_by(egui::PointerButton::Middle) {
+            let delta = response.drag_delta();
+            self.scroll_offset = (self.scroll_offset - delta.x / self.zoom).max(0.0);
+            self.curve_offset += delta.y / self.curve_scale;
+        }
+
+        response.context_menu(|ui| {
+            let Some((_, keyframe_id)) = self.curve_context else {
+                ui.label("No keyframe");
+                return;
+            };
+            let Some(current) = track.keyframe(keyframe_id).map(|k| k.interpolation) else {
+                ui.label("No keyframe");
+                return;
+            };
+
+            ui.label("Interpolation");
+            for mode in INTERPOLATION_PRESETS {
+                let button = egui::SelectableLabel::new(current == mode, mode.name());
+                if ui.add_enabled(!track.locked, button).clicked() {
+                    if let Some(keyframe) = track.keyframe_mut(keyframe_id) {
+                        keyframe.interpolation = mode;
+                    }
+                    ui.close_menu();
+                }
+            }
+        });
     }
 
     /// Handle input events
@@ -671,6 +1313,119 @@ impl SequencerState {
             });
         }
 
+        // Clicking a track header picks the curve editor's track
+        if response.clicked() {
+            let header_pos = response.interact_pointer_pos().filter(|pos| pos.x < TRACK_HEADER_WIDTH);
+            if let Some(track_id) = header_pos
+                .and_then(|pos| self.track_at_y(pos.y, rect.min.y + TIMELINE_HEADER_HEIGHT, sequence))
+            {
+                self.curve_track = Some(track_id);
+                self.selection.tracks.clear();
+                self.selection.tracks.insert(track_id);
+            }
+        }
+
+        // Clipboard shortcuts arrive as events rather than key presses and
+        // apply while the pointer is over the sequencer
+        if ui.ui_contains_pointer() {
+            let (copy, paste) = ui.input(|input| {
+                (
+                    input.events.iter().any(|e| matches!(e, egui::Event::Copy)),
+                    input.events.iter().any(|e| matches!(e, egui::Event::Paste(_))),
+                )
+            });
+            if copy {
+                self.copy_keyframes(sequence);
+            }
+            if paste && !self.keyframe_clipboard.is_empty() {
+                let target = single_track(&self.selection.tracks);
+                self.paste_keyframes(sequence, target);
+            }
+        }
+
+        // Track context menu
+        if response.secondary_clicked() {
+            self.context_track = response.interact_pointer_pos().and_then(|pos| {
+                self.track_at_y(pos.y, rect.min.y + TIMELINE_HEADER_HEIGHT, sequence)
+            });
+        }
+
+        response.context_menu(|ui| {
+            let Some(track_id) = self.context_track else {
+                ui.label("No track");
+                return;
+            };
+
+            let can_insert = sequence.track(track_id).is_some_and(|t| !t.locked && t.keyframe_count() > 0);
+            if ui
+                .add_enabled(can_insert, egui::Button::new("Insert Key at Playhead"))
+                .on_hover_text("Key the current curve value at the playhead")
+                .clicked()
+            {
+                let time = self.playback.time;
+                if let Some(keyframe_id) = sequence
+                    .track_mut(track_id)
+                    .and_then(|track| track.insert_keyframe_at_current_value(time))
+                {
+                    self.selection.keyframes.clear();
+                    self.selection.keyframes.insert((track_id, keyframe_id));
+                }
+                ui.close_menu();
+            }
+
+            ui.separator();
+
+            let has_selection = !self.selection.keyframes.is_empty();
+            if ui.add_enabled(has_selection, egui::Button::new("Copy Keys")).clicked() {
+                self.copy_keyframes(sequence);
+                ui.close_menu();
+            }
+
+            if ui
+                .add_enabled(self.can_paste_onto(sequence, track_id), egui::Button::new("Paste Keys"))
+                .on_hover_text("Paste copied keyframes at the playhead")
+                .on_disabled_hover_text("Nothing copied, or the copied values don't match this track")
+                .clicked()
+            {
+                self.paste_keyframes(sequence, Some(track_id));
+                ui.close_menu();
+            }
+
+            if ui
+                .add_enabled(has_selection, egui::Button::new("Duplicate Keys"))
+                .on_hover_text("Copy the selected keyframes to just after the selection")
+                .clicked()
+            {
+                self.duplicate_keyframes(sequence);
+                ui.close_menu();
+            }
+
+            ui.menu_button("Interpolation", |ui| {
+                for mode in INTERPOLATION_PRESETS {
+                    if ui.add_enabled(has_selection, egui::Button::new(mode.name())).clicked() {
+                        self.set_selected_interpolation(sequence, mode);
+                        ui.close_menu();
+                    }
+                }
+            });
+
+            ui.separator();
+
+            if ui.button("Copy Timing").clicked() {
+                self.copied_timing = sequence.track(track_id).map(Track::timing);
+                ui.close_menu();
+            }
+
+            let can_paste = self.copied_timing.is_some()
+                && sequence.track(track_id).is_some_and(|t| !t.locked);
+            if ui.add_enabled(can_paste, egui::Button::new("Paste Timing")).clicked() {
+                if let (Some(times), Some(track)) = (&self.copied_timing, sequence.track_mut(track_id)) {
+                    track.retime_to(times);
+                }
+                ui.close_menu();
+            }
+        });
+
         // Scroll wheel for zoom and pan
         ui.input(|input| {
             let scroll = input.smooth_scroll_delta;
@@ -707,6 +1462,15 @@ impl SequencerState {
     }
 }
 
+/// The only track in `tracks`, if there is exactly one
+fn single_track(tracks: &HashSet<TrackId>) -> Option<TrackId> {
+    let mut iter = tracks.iter();
+    match (iter.next(), iter.next()) {
+        (Some(id), None) => Some(*id),
+        _ => None,
+    }
+}
+
 impl Default for SequencerState {
     fn default() -> Self {
         Self::new()
@@ -723,6 +1487,8 @@ pub struct SequencerPanel {
     pub state: SequencerState,
     /// Whether changes are unsaved
     pub dirty: bool,
+    /// Read-only sequence shown as a ghost for comparison
+    reference: Option<Sequence>,
 }
 
 impl SequencerPanel {
@@ -748,7 +1514,17 @@ impl SequencerPanel {
         event_track.add_keyframe(crate::keyframe::Keyframe::new(4.0, KeyframeValue::Event("door_open".to_string())));
         sequence.add_track(event_track);
 
-        let audio_track = Track::new("Background Music", TrackType::Audio);
+        let mut audio_track = Track::new("Background Music", TrackType::Audio);
+        audio_track.clips.push(AudioClip {
+            id: uuid::Uuid::new_v4(),
+            start_time: 0.5,
+            end_time: 6.0,
+            asset_path: "audio/music.ogg".to_string(),
+            clip_start: 0.0,
+            volume: 1.0,
+            fade_in: 0.0,
+            fade_out: 0.0,
+        });
         sequence.add_track(audio_track);
 
         let camera_track = Track::new("Camera Settings", TrackType::Camera);
@@ -759,9 +1535,63 @@ impl SequencerPanel {
             sequence,
             state: SequencerState::new(),
             dirty: false,
+            reference: None,
         }
     }
 
+    /// Set the reference sequence compared against the active one
+    pub fn set_reference(&mut self, reference: Sequence) {
+        self.reference = Some(reference);
+    }
+
+    /// Remove the reference sequence
+    pub fn clear_reference(&mut self) {
+        self.reference = None;
+    }
+
+    /// Get the reference sequence, if one is loaded
+    pub fn reference(&self) -> Option<&Sequence> {
+        self.reference.as_ref()
+    }
+
+    /// Sample the reference sequence at the active playhead
+    pub fn reference_samples(&self) -> Vec<crate::sequence::BindingSample> {
+        self.reference
+            .as_ref()
+            .map(|reference| reference.sample_at(self.state.playback.time))
+            .unwrap_or_default()
+    }
+
+    /// Set the directory audio clip paths are resolved against
+    pub fn set_asset_root(&mut self, root: impl Into<std::path::PathBuf>) {
+        self.state.waveforms.set_asset_root(root);
+    }
+
+    /// Key a committed transform edit at the playhead while recording.
+    ///
+    /// `channels` holds the transform channels the edit changed. Returns the
+    /// number of keys written; nothing is keyed when recording is off.
+    pub fn record_transform(
+        &mut self,
+        entity: EntityId,
+        entity_name: &str,
+        channels: &[(TransformChannel, [f32; 3])],
+    ) -> usize {
+        if !self.state.recording {
+            return 0;
+        }
+
+        let time = self.state.snap_to_frame(self.state.playback.time, self.sequence.frame_rate);
+        let keyed = channels
+            .iter()
+            .filter_map(|&(channel, value)| self.sequence.record_transform(entity, entity_name, channel, value, time))
+            .count();
+        if keyed > 0 {
+            self.dirty = true;
+        }
+        keyed
+    }
+
     /// Update playback (call each frame)
     pub fn update(&mut self, delta_time: f32) {
         self.state.playback.update(delta_time, &self.sequence);
@@ -769,6 +1599,21 @@ impl SequencerPanel {
 
     /// Render the panel UI
     pub fn ui(&mut self, ui: &mut egui::Ui) {
+        if let Some(reference) = &self.reference {
+            let mut clear = false;
+            ui.horizontal(|ui| {
+                ui.label(
+                    egui::RichText::new(format!("Reference: {}", reference.name))
+                        .color(Color32::from_rgb(120, 180, 255)),
+                );
+                if ui.small_button("✕").on_hover_text("Clear reference").clicked() {
+                    clear = true;
+                }
+            });
+            if clear {
+                self.clear_reference();
+            }
+        }
         self.state.ui(ui, &mut self.sequence);
     }
 }
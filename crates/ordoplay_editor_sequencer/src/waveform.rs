@@ -0,0 +1,401 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//! Audio waveform envelopes for drawing clips in the sequencer.
+//!
+//! Audio files (WAV or Ogg Vorbis) are decoded on a background thread into
+//! a min/max envelope fine enough for the highest timeline zoom; drawing
+//! then folds envelope buckets into one column per pixel. Envelopes are
+//! cached by file path and decoded again when the file's modified time
+//! changes, so long clips never stall the UI thread.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+
+/// Envelope buckets per second of audio (one per pixel at maximum zoom)
+pub const BUCKETS_PER_SECOND: u32 = 500;
+
+/// How often a cached file's modified time is checked again
+const MODIFIED_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Error when decoding an audio file into a waveform
+#[derive(Debug, thiserror::Error)]
+pub enum WaveformError {
+    /// The file couldn't be read
+    #[error("Failed to read audio file: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// The file type or sample format isn't supported
+    #[error("Unsupported audio format: {0}")]
+    Unsupported(String),
+
+    /// The WAV file is missing required chunks or is truncated
+    #[error("Malformed WAV file: {0}")]
+    MalformedWav(&'static str),
+
+    /// The Ogg Vorbis stream couldn't be decoded
+    #[error("Failed to decode Ogg Vorbis: {0}")]
+    Vorbis(#[from] lewton::VorbisError),
+}
+
+/// Min/max envelope of an audio file, with all channels mixed together
+#[derive(Debug, Clone, PartialEq)]
+pub struct Waveform {
+    /// Length of the audio in seconds
+    pub duration: f32,
+    /// Lowest and highest sample in each bucket of `1 / BUCKETS_PER_SECOND`
+    /// seconds
+    pub buckets: Vec<(f32, f32)>,
+}
+
+impl Waveform {
+    /// Lowest and highest sample between `start` and `end` seconds, or
+    /// `None` if the range lies outside the audio
+    pub fn range(&self, start: f32, end: f32) -> Option<(f32, f32)> {
+        if end <= 0.0 || start >= self.duration {
+            return None;
+        }
+
+        let rate = BUCKETS_PER_SECOND as f32;
+        let first = (start.max(0.0) * rate) as usize;
+        let last = ((end * rate).ceil() as usize).max(first + 1).min(self.buckets.len());
+        self.buckets
+            .get(first..last)?
+            .iter()
+            .copied()
+            .reduce(|(low, high), (min, max)| (low.min(min), high.max(max)))
+    }
+}
+
+/// Folds interleaved samples into envelope buckets
+struct EnvelopeBuilder {
+    channels: u64,
+    sample_rate: u32,
+    samples: u64,
+    buckets: Vec<(f32, f32)>,
+}
+
+impl EnvelopeBuilder {
+    fn new(channels: u16, sample_rate: u32) -> Self {
+        Self {
+            channels: u64::from(channels.max(1)),
+            sample_rate: sample_rate.max(1),
+            samples: 0,
+            buckets: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, samples: impl IntoIterator<Item = f32>) {
+        for sample in samples {
+            let frame = self.samples / self.channels;
+            let bucket = (frame * u64::from(BUCKETS_PER_SECOND) / u64::from(self.sample_rate)) as usize;
+            while self.buckets.len() <= bucket {
+                self.buckets.push((sample, sample));
+            }
+            let (min, max) = &mut self.buckets[bucket];
+            *min = min.min(sample);
+            *max = max.max(sample);
+            self.samples += 1;
+        }
+    }
+
+    fn finish(self) -> Waveform {
+        let frames = self.samples / self.channels;
+        Waveform {
+            duration: frames as f32 / self.sample_rate as f32,
+            buckets: self.buckets,
+        }
+    }
+}
+
+/// Decode the audio file at `path`, choosing the decoder by extension
+pub fn decode(path: &Path) -> Result<Waveform, WaveformError> {
+    let extension = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default();
+
+    match extension.as_str() {
+        "wav" | "wave" => decode_wav(&std::fs::read(path)?),
+        "ogg" | "oga" => decode_ogg(path),
+        other => Err(WaveformError::Unsupported(format!("\".{other}\" files"))),
+    }
+}
+
+/// Sample layout read from a WAV `fmt ` chunk
+struct WavFormat {
+    tag: u16,
+    channels: u16,
+    sample_rate: u32,
+    bits: u16,
+}
+
+/// `WAVE_FORMAT_PCM`
+const WAV_PCM: u16 = 1;
+/// `WAVE_FORMAT_IEEE_FLOAT`
+const WAV_FLOAT: u16 = 3;
+/// `WAVE_FORMAT_EXTENSIBLE`, with the real format tag in the sub-format GUID
+const WAV_EXTENSIBLE: u16 = 0xFFFE;
+
+impl WavFormat {
+    fn parse(body: &[u8]) -> Result<Self, WaveformError> {
+        if body.len() < 16 {
+            return Err(WaveformError::MalformedWav("fmt chunk is too short"));
+        }
+        let mut tag = u16_le(body, 0);
+        if tag == WAV_EXTENSIBLE && body.len() >= 26 {
+            tag = u16_le(body, 24);
+        }
+        Ok(Self {
+            tag,
+            channels: u16_le(body, 2),
+            sample_rate: u32_le(body, 4),
+            bits: u16_le(body, 14),
+        })
+    }
+
+    fn envelope(&self, data: &[u8]) -> Result<Waveform, WaveformError> {
+        let convert: fn(&[u8]) -> f32 = match (self.tag, self.bits) {
+            (WAV_PCM, 8) => |b: &[u8]| (f32::from(b[0]) - 128.0) / 128.0,
+            (WAV_PCM, 16) => |b: &[u8]| f32::from(i16::from_le_bytes([b[0], b[1]])) / 32768.0,
+            (WAV_PCM, 24) => |b: &[u8]| (i32::from_le_bytes([0, b[0], b[1], b[2]]) >> 8) as f32 / 8_388_608.0,
+            (WAV_PCM, 32) => |b: &[u8]| i32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f32 / 2_147_483_648.0,
+            (WAV_FLOAT, 32) => |b: &[u8]| f32::from_le_bytes([b[0], b[1], b[2], b[3]]),
+            (WAV_FLOAT, 64) => |b: &[u8]| f64::from_le_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]) as f32,
+            (tag, bits) => {
+                return Err(WaveformError::Unsupported(format!("WAV format {tag} with {bits}-bit samples")));
+            }
+        };
+
+        let mut builder = EnvelopeBuilder::new(self.channels, self.sample_rate);
+        builder.push(data.chunks_exact(usize::from(self.bits / 8)).map(convert));
+        Ok(builder.finish())
+    }
+}
+
+fn u16_le(bytes: &[u8], at: usize) -> u16 {
+    u16::from_le_bytes([bytes[at], bytes[at + 1]])
+}
+
+fn u32_le(bytes: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]])
+}
+
+/// Decode a RIFF/WAVE file held in memory
+pub fn decode_wav(bytes: &[u8]) -> Result<Waveform, WaveformError> {
+    if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+        return Err(WaveformError::MalformedWav("missing RIFF/WAVE header"));
+    }
+
+    let mut format = None;
+    let mut pos = 12;
+    while pos + 8 <= bytes.len() {
+        let size = u32_le(bytes, pos + 4) as usize;
+        let body_start = pos + 8;
+        // Truncated files keep whatever data made it to disk
+        let body = &bytes[body_start..body_start.saturating_add(size).min(bytes.len())];
+
+        match &bytes[pos..pos + 4] {
+            b"fmt " => format = Some(WavFormat::parse(body)?),
+            b"data" => {
+                let format = format.ok_or(WaveformError::MalformedWav("data chunk before fmt chunk"))?;
+                return format.envelope(body);
+            }
+            _ => {}
+        }
+
+        // Chunks are padded to an even size
+        pos = body_start.saturating_add(size).saturating_add(size & 1);
+    }
+
+    Err(WaveformError::MalformedWav("no data chunk"))
+}
+
+/// Decode an Ogg Vorbis file packet by packet
+fn decode_ogg(path: &Path) -> Result<Waveform, WaveformError> {
+    let file = std::io::BufReader::new(std::fs::File::open(path)?);
+    let mut reader = lewton::inside_ogg::OggStreamReader::new(file)?;
+    let mut builder = EnvelopeBuilder::new(
+        u16::from(reader.ident_hdr.audio_channels),
+        reader.ident_hdr.audio_sample_rate,
+    );
+    while let Some(packet) = reader.read_dec_packet_itl()? {
+        builder.push(packet.into_iter().map(|s| f32::from(s) / 32768.0));
+    }
+    Ok(builder.finish())
+}
+
+/// Progress of a clip's waveform
+#[derive(Debug, Clone)]
+pub enum WaveformState {
+    /// Still decoding in the background
+    Loading,
+    /// Decoded and ready to draw
+    Ready(Arc<Waveform>),
+    /// Decoding failed with the given message
+    Failed(Arc<str>),
+}
+
+struct CacheEntry {
+    /// Modified time of the file when decoding started
+    modified: Option<SystemTime>,
+    /// When `modified` was last compared against the file
+    checked_at: Instant,
+    state: WaveformState,
+    pending: Option<Receiver<Result<Waveform, WaveformError>>>,
+}
+
+impl CacheEntry {
+    /// Start decoding `path` on a background thread
+    fn load(path: PathBuf, now: Instant) -> Self {
+        let modified = modified_time(&path);
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            // The cache may have dropped the receiver if the file changed
+            let _ = sender.send(decode(&path));
+        });
+
+        Self {
+            modified,
+            checked_at: now,
+            state: WaveformState::Loading,
+            pending: Some(receiver),
+        }
+    }
+
+    fn poll(&mut self) {
+        let Some(receiver) = &self.pending else {
+            return;
+        };
+        self.state = match receiver.try_recv() {
+            Ok(Ok(waveform)) => WaveformState::Ready(Arc::new(waveform)),
+            Ok(Err(err)) => WaveformState::Failed(err.to_string().into()),
+            Err(TryRecvError::Empty) => return,
+            Err(TryRecvError::Disconnected) => WaveformState::Failed("Decoder stopped unexpectedly".into()),
+        };
+        self.pending = None;
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Waveforms of audio clips by file, decoded off the UI thread
+#[derive(Default)]
+pub struct WaveformCache {
+    /// Directory clip asset paths are resolved against
+    asset_root: PathBuf,
+    entries: HashMap<PathBuf, CacheEntry>,
+}
+
+impl WaveformCache {
+    /// Create an empty cache resolving paths against the working directory
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the directory clip asset paths are resolved against. Changing
+    /// it drops every cached waveform.
+    pub fn set_asset_root(&mut self, root: impl Into<PathBuf>) {
+        let root = root.into();
+        if root != self.asset_root {
+            self.asset_root = root;
+            self.entries.clear();
+        }
+    }
+
+    /// Waveform of the clip asset at `asset_path`. The first request, and
+    /// the first one after the file's modified time changes, starts a
+    /// background decode and reports `Loading` until it finishes.
+    pub fn get(&mut self, asset_path: &str) -> WaveformState {
+        let path = self.asset_root.join(asset_path);
+        let now = Instant::now();
+
+        let stale = match self.entries.get_mut(&path) {
+            Some(entry) if now.duration_since(entry.checked_at) < MODIFIED_CHECK_INTERVAL => false,
+            Some(entry) => {
+                entry.checked_at = now;
+                modified_time(&path) != entry.modified
+            }
+            None => true,
+        };
+        if stale {
+            self.entries.insert(path.clone(), CacheEntry::load(path.clone(), now));
+        }
+
+        match self.entries.get_mut(&path) {
+            Some(entry) => {
+                entry.poll();
+                entry.state.clone()
+            }
+            None => WaveformState::Loading,
+        }
+    }
+
+    /// Whether any waveform is still decoding
+    pub fn is_loading(&self) -> bool {
+        self.entries.values().any(|e| e.pending.is_some())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wav_bytes(tag: u16, channels: u16, sample_rate: u32, bits: u16, data: &[u8]) -> Vec<u8> {
+        let block_align = channels * bits / 8;
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(b"RIFF");
+        bytes.extend_from_slice(&(36 + data.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(b"WAVE");
+        bytes.extend_from_slice(b"fmt ");
+        bytes.extend_from_slice(&16u32.to_le_bytes());
+        bytes.extend_from_slice(&tag.to_le_bytes());
+        bytes.extend_from_slice(&channels.to_le_bytes());
+        bytes.extend_from_slice(&sample_rate.to_le_bytes());
+        bytes.extend_from_slice(&(sample_rate * u32::from(block_align)).to_le_bytes());
+        bytes.extend_from_slice(&block_align.to_le_bytes());
+        bytes.extend_from_slice(&bits.to_le_bytes());
+        bytes.extend_from_slice(b"data");
+        bytes.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(data);
+        bytes
+    }
+
+    #[test]
+    fn test_decode_wav_envelope() {
+        // One second of 16-bit stereo at 1 kHz: silence, then the left
+        // channel swings to full scale in the second half
+        let mut data = Vec::new();
+        for frame in 0..1000 {
+            let left: i16 = if frame < 500 { 0 } else if frame % 2 == 0 { i16::MAX } else { i16::MIN };
+            data.extend_from_slice(&left.to_le_bytes());
+            data.extend_from_slice(&0i16.to_le_bytes());
+        }
+
+        let waveform = decode_wav(&wav_bytes(WAV_PCM, 2, 1000, 16, &data)).unwrap();
+        assert_eq!(waveform.duration, 1.0);
+        assert_eq!(waveform.buckets.len(), BUCKETS_PER_SECOND as usize);
+        assert_eq!(waveform.range(0.0, 0.5), Some((0.0, 0.0)));
+        let (min, max) = waveform.range(0.5, 1.0).unwrap();
+        assert_eq!(min, -1.0);
+        assert!(max > 0.99);
+        assert_eq!(waveform.range(1.0, 2.0), None);
+        assert_eq!(waveform.range(-1.0, 0.0), None);
+    }
+
+    #[test]
+    fn test_decode_wav_rejects_unsupported_formats() {
+        assert!(matches!(decode_wav(b"not a wav file"), Err(WaveformError::MalformedWav(_))));
+        assert!(matches!(
+            decode_wav(&wav_bytes(2, 1, 8000, 4, &[0; 16])),
+            Err(WaveformError::Unsupported(_))
+        ));
+
+        // 8-bit PCM is unsigned around 128
+        let waveform = decode_wav(&wav_bytes(WAV_PCM, 1, 1000, 8, &[128, 255, 0])).unwrap();
+        assert_eq!(waveform.range(0.0, 0.01), Some((-1.0, 127.0 / 128.0)));
+    }
+}